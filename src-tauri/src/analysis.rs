@@ -2,11 +2,11 @@
 
 use std::collections::BTreeMap;
 
-use chrono::{Datelike as _, NaiveDate, Weekday};
+use chrono::{Datelike as _, NaiveDate, Timelike as _};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::app_data::{DateRange, HistoryDay};
+use crate::app_data::{root_tag_path, DateRange, DayBoundary, HistoryDay, Phase};
 use crate::errors::{AppError, AppResult};
 
 /// 专注分析结果（用于前端图表渲染）。
@@ -18,14 +18,26 @@ pub struct FocusAnalysis {
     pub hourly_counts: Vec<u32>,
     /// 时段分布：`[0-6, 6-12, 12-18, 18-24]`。
     pub period_counts: Vec<u32>,
-    /// 星期分布：`[周一..周日]`。
+    /// 星期分布：从设置的每周首日起的 7 天（默认 `[周一..周日]`）。
     pub weekday_counts: Vec<u32>,
-    /// 交叉热力：`weekday_hour_counts[weekday][hour]`（7x24）。
+    /// 交叉热力：`weekday_hour_counts[weekday][hour]`（7x24，星期索引同 `weekday_counts`）。
     pub weekday_hour_counts: Vec<Vec<u32>>,
     /// 标签效率：各标签平均专注时长（分钟）。
     pub tag_efficiency: Vec<TagEfficiency>,
     /// 文字总结（示例：「你在上午 9-11 点专注效率最高」）。
     pub summary: String,
+    /// 实际专注秒数（工作阶段计时，不含暂停）。
+    pub focus_seconds: u64,
+    /// 工作阶段的墙钟跨度秒数（实际专注 + 暂停）。
+    pub focus_span_seconds: u64,
+    /// 实际休息秒数（含被跳过休息的已休时长）。
+    pub break_seconds: u64,
+    /// 休息占比：`休息 / (专注 + 休息)`（无数据时为 0）。
+    pub rest_ratio: f64,
+    /// 开始过的休息次数（含跳过）。
+    pub break_count: u32,
+    /// 被提前跳过的休息次数。
+    pub skipped_break_count: u32,
 }
 
 /// 标签效率条目。
@@ -42,7 +54,15 @@ pub struct TagEfficiency {
 }
 
 /// 生成指定日期范围的专注分析（输入为按日分组的历史数据切片）。
-pub fn get_focus_analysis(days: &[HistoryDay], range: &DateRange) -> AppResult<FocusAnalysis> {
+///
+/// 记录按 `boundary` 归属到逻辑日期后再做范围过滤与星期分桶；小时分布仍按实际开始时刻。
+/// `roll_up_tags` 为真时，标签效率按顶层标签汇总子标签。
+pub fn get_focus_analysis(
+    days: &[HistoryDay],
+    range: &DateRange,
+    boundary: DayBoundary,
+    roll_up_tags: bool,
+) -> AppResult<FocusAnalysis> {
     let (from, to) = parse_range(range)?;
 
     let mut hourly = vec![0u32; 24];
@@ -53,25 +73,53 @@ pub fn get_focus_analysis(days: &[HistoryDay], range: &DateRange) -> AppResult<F
     let mut tag_total: BTreeMap<String, u32> = BTreeMap::new();
     let mut tag_count: BTreeMap<String, u32> = BTreeMap::new();
 
-    for day in days {
-        let day_date = match NaiveDate::parse_from_str(&day.date, "%Y-%m-%d") {
-            Ok(d) => d,
-            Err(_) => continue,
-        };
-        if day_date < from || day_date > to {
-            continue;
-        }
+    let mut focus_seconds = 0u64;
+    let mut focus_span_seconds = 0u64;
+    let mut break_seconds = 0u64;
+    let mut break_count = 0u32;
+    let mut skipped_break_count = 0u32;
 
-        let weekday_index = weekday_to_index(day_date.weekday());
+    for day in days {
         for r in &day.records {
-            let hour = parse_hour(&r.start_time).unwrap_or(0);
+            let date = boundary.record_date(&day.date, r);
+            let date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            if date < from || date > to {
+                continue;
+            }
+
+            if r.phase != Phase::Work {
+                // 休息阶段只参与休息占比统计，不计入番茄分布与标签效率。
+                break_seconds += r.active_seconds();
+                break_count += 1;
+                if r.skipped {
+                    skipped_break_count += 1;
+                }
+                continue;
+            }
+            focus_seconds += r.active_seconds();
+            focus_span_seconds += r.active_seconds() + r.paused_seconds();
+
+            let hour = r
+                .started_at_local()
+                .map(|at| at.hour() as usize)
+                .or_else(|| parse_hour(&r.start_time))
+                .unwrap_or(0);
+            let weekday_index = boundary.weekday_index(date.weekday());
             hourly[hour] += 1;
             periods[period_index(hour)] += 1;
             weekday_counts[weekday_index] += 1;
             matrix[weekday_index][hour] += 1;
 
-            *tag_total.entry(r.tag.clone()).or_insert(0) += r.duration;
-            *tag_count.entry(r.tag.clone()).or_insert(0) += 1;
+            let tag = if roll_up_tags {
+                root_tag_path(&r.tag)
+            } else {
+                r.tag.as_str()
+            };
+            *tag_total.entry(tag.to_string()).or_insert(0) += r.duration;
+            *tag_count.entry(tag.to_string()).or_insert(0) += 1;
         }
     }
 
@@ -99,6 +147,11 @@ pub fn get_focus_analysis(days: &[HistoryDay], range: &DateRange) -> AppResult<F
     });
 
     let summary = build_summary(&hourly);
+    let rest_ratio = if focus_seconds + break_seconds > 0 {
+        break_seconds as f64 / (focus_seconds + break_seconds) as f64
+    } else {
+        0.0
+    };
 
     Ok(FocusAnalysis {
         hourly_counts: hourly,
@@ -107,6 +160,12 @@ pub fn get_focus_analysis(days: &[HistoryDay], range: &DateRange) -> AppResult<F
         weekday_hour_counts: matrix,
         tag_efficiency,
         summary,
+        focus_seconds,
+        focus_span_seconds,
+        break_seconds,
+        rest_ratio,
+        break_count,
+        skipped_break_count,
     })
 }
 
@@ -145,19 +204,6 @@ fn period_index(hour: usize) -> usize {
     }
 }
 
-/// 将 chrono `Weekday` 转为 `[周一..周日]` 索引。
-fn weekday_to_index(weekday: Weekday) -> usize {
-    match weekday {
-        Weekday::Mon => 0,
-        Weekday::Tue => 1,
-        Weekday::Wed => 2,
-        Weekday::Thu => 3,
-        Weekday::Fri => 4,
-        Weekday::Sat => 5,
-        Weekday::Sun => 6,
-    }
-}
-
 /// 生成摘要：取番茄数量最多的连续 2 小时窗口。
 fn build_summary(hourly: &[u32]) -> String {
     if hourly.len() != 24 {
@@ -212,18 +258,11 @@ fn time_range_label(from_hour: usize, to_hour: usize) -> String {
 mod tests {
     use super::*;
 
-    use crate::app_data::{HistoryRecord, Phase};
+    use crate::app_data::HistoryRecord;
 
     /// 构造一条最小的历史记录（用于专注分析测试）。
     fn record(tag: &str, start_time: &str, duration: u32) -> HistoryRecord {
-        HistoryRecord {
-            tag: tag.to_string(),
-            start_time: start_time.to_string(),
-            end_time: None,
-            duration,
-            phase: Phase::Work,
-            remark: String::new(),
-        }
+        HistoryRecord::for_test(tag, start_time).with_duration(duration)
     }
 
     /// `parse_range`：合法范围应通过，并返回正确的 NaiveDate。
@@ -290,18 +329,6 @@ mod tests {
         assert_eq!(period_index(23), 3);
     }
 
-    /// `weekday_to_index`：应将周一到周日映射为 0..=6。
-    #[test]
-    fn weekday_to_index_maps_mon_to_sun() {
-        assert_eq!(weekday_to_index(Weekday::Mon), 0);
-        assert_eq!(weekday_to_index(Weekday::Tue), 1);
-        assert_eq!(weekday_to_index(Weekday::Wed), 2);
-        assert_eq!(weekday_to_index(Weekday::Thu), 3);
-        assert_eq!(weekday_to_index(Weekday::Fri), 4);
-        assert_eq!(weekday_to_index(Weekday::Sat), 5);
-        assert_eq!(weekday_to_index(Weekday::Sun), 6);
-    }
-
     /// `build_summary`：空数据应返回“暂无分析数据”。
     #[test]
     fn build_summary_returns_no_data_when_empty() {
@@ -347,6 +374,8 @@ mod tests {
                 from: "2025-01-01".to_string(),
                 to: "2025-01-07".to_string(),
             },
+            DayBoundary::default(),
+            false,
         )
         .unwrap();
         assert_eq!(out.hourly_counts, vec![0u32; 24]);
@@ -356,6 +385,7 @@ mod tests {
         assert_eq!(out.weekday_hour_counts[0].len(), 24);
         assert_eq!(out.tag_efficiency.len(), 0);
         assert_eq!(out.summary, "暂无分析数据");
+        assert_eq!(out.rest_ratio, 0.0);
     }
 
     /// `get_focus_analysis`：单日数据应正确累计小时/时段/星期与标签效率。
@@ -376,6 +406,8 @@ mod tests {
                 from: "2025-01-01".to_string(),
                 to: "2025-01-01".to_string(),
             },
+            DayBoundary::default(),
+            false,
         )
         .unwrap();
 
@@ -397,6 +429,48 @@ mod tests {
         assert!((out.tag_efficiency[1].avg_duration - 15.0).abs() < 1e-9);
     }
 
+    /// `get_focus_analysis`：休息记录只计入休息占比；暂停计入墙钟跨度但不计入实际专注。
+    #[test]
+    fn get_focus_analysis_separates_breaks_and_pauses() {
+        let mut paused = record("学习", "09:00", 25);
+        paused.elapsed_seconds = Some(25 * 60);
+        paused.pauses = vec![crate::app_data::PauseSegment {
+            start_time: "09:10".to_string(),
+            end_time: "09:15".to_string(),
+            seconds: 5 * 60,
+        }];
+        let mut rest = record("学习", "09:30", 5);
+        rest.phase = Phase::ShortBreak;
+        let mut skipped = record("学习", "10:00", 0);
+        skipped.phase = Phase::LongBreak;
+        skipped.elapsed_seconds = Some(5 * 60);
+        skipped.skipped = true;
+
+        let days = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![paused, rest, record("学习", "09:35", 25), skipped],
+        }];
+        let out = get_focus_analysis(
+            &days,
+            &DateRange {
+                from: "2025-01-01".to_string(),
+                to: "2025-01-01".to_string(),
+            },
+            DayBoundary::default(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(out.hourly_counts.iter().sum::<u32>(), 2);
+        assert_eq!(out.tag_efficiency[0].count, 2);
+        assert_eq!(out.focus_seconds, 50 * 60);
+        assert_eq!(out.focus_span_seconds, 55 * 60);
+        assert_eq!(out.break_seconds, 10 * 60);
+        assert_eq!(out.break_count, 2);
+        assert_eq!(out.skipped_break_count, 1);
+        assert!((out.rest_ratio - 10.0 / 60.0).abs() < 1e-9);
+    }
+
     /// `get_focus_analysis`：日期范围应为闭区间，并忽略范围外数据与非法日期。
     #[test]
     fn get_focus_analysis_respects_inclusive_range_and_skips_invalid_dates() {
@@ -425,6 +499,8 @@ mod tests {
                 from: "2025-01-02".to_string(),
                 to: "2025-01-03".to_string(),
             },
+            DayBoundary::default(),
+            false,
         )
         .unwrap();
 
@@ -435,4 +511,71 @@ mod tests {
         assert_eq!(out.tag_efficiency[0].tag, "B");
         assert_eq!(out.tag_efficiency[1].tag, "C");
     }
+
+    /// `get_focus_analysis`：记录按日界线归属逻辑日期，星期索引从每周首日起算。
+    #[test]
+    fn get_focus_analysis_uses_day_boundary() {
+        // 2025-01-05 是周日；凌晨 01:00 的记录在日界线 4 点下属于周六（2025-01-04）。
+        let days = vec![HistoryDay {
+            date: "2025-01-05".to_string(),
+            records: vec![record("夜读", "01:00", 25), record("学习", "09:00", 25)],
+        }];
+        let boundary = DayBoundary {
+            rollover_hour: 4,
+            first_weekday: chrono::Weekday::Sun,
+        };
+
+        let out = get_focus_analysis(
+            &days,
+            &DateRange {
+                from: "2025-01-05".to_string(),
+                to: "2025-01-05".to_string(),
+            },
+            boundary,
+            false,
+        )
+        .unwrap();
+        assert_eq!(out.weekday_counts[0], 1);
+        assert_eq!(out.hourly_counts[1], 0);
+
+        let out = get_focus_analysis(
+            &days,
+            &DateRange {
+                from: "2025-01-04".to_string(),
+                to: "2025-01-05".to_string(),
+            },
+            boundary,
+            false,
+        )
+        .unwrap();
+        assert_eq!(out.weekday_counts[6], 1);
+        assert_eq!(out.weekday_hour_counts[6][1], 1);
+        assert_eq!(out.hourly_counts[1], 1);
+    }
+
+    /// `get_focus_analysis`：开启标签汇总时，子标签的标签效率应计入顶层标签。
+    #[test]
+    fn get_focus_analysis_rolls_up_child_tags() {
+        let days = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![
+                record("工作/项目X", "09:00", 20),
+                record("工作", "10:00", 30),
+                record("学习", "11:00", 25),
+            ],
+        }];
+        let range = DateRange {
+            from: "2025-01-01".to_string(),
+            to: "2025-01-01".to_string(),
+        };
+
+        let out = get_focus_analysis(&days, &range, DayBoundary::default(), false).unwrap();
+        assert_eq!(out.tag_efficiency.len(), 3);
+
+        let out = get_focus_analysis(&days, &range, DayBoundary::default(), true).unwrap();
+        assert_eq!(out.tag_efficiency.len(), 2);
+        let work = out.tag_efficiency.iter().find(|t| t.tag == "工作").unwrap();
+        assert_eq!(work.count, 2);
+        assert!((work.avg_duration - 25.0).abs() < 1e-9);
+    }
 }
@@ -1,14 +1,23 @@
 //! PRD 约定的数据结构（settings / blacklist / tags / history）。
 
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 use ts_rs::TS;
 
 /// Store 文件名（最终路径由后端根据平台解析到统一的数据根目录下）。
 pub const STORE_FILE_NAME: &str = "pomodoro-data.json";
 
+/// 历史数据库文件名（历史与中断记录保存在该 SQLite 文件中，与 store 文件同目录）。
+pub const HISTORY_DB_FILE_NAME: &str = "pomodoro-history.db";
+
 /// Store 内部主键（保存整棵 `AppData`）。
 pub const STORE_KEY: &str = "appData";
 
+/// Store 内部键：计时器运行态检查点（用于重启后恢复进行中的番茄）。
+pub const TIMER_STORE_KEY: &str = "timerRuntime";
+
 /// 计时器/历史记录阶段（与前端 `Phase` 类型对齐）。
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 #[serde(rename_all = "camelCase")]
@@ -66,8 +75,44 @@ pub struct Settings {
     /// 中断设置（PRD v4）。
     #[serde(default)]
     pub interruption: InterruptionSettings,
+    /// 系统挂起期间阶段到期时的处理策略。
+    #[serde(default)]
+    pub suspend_expiry_policy: SuspendExpiryPolicy,
+    /// 逻辑日界线（0-23 点）：该时刻之前完成的番茄仍计入前一天（0 表示按自然日）。
+    #[serde(default)]
+    pub day_rollover_hour: u8,
+    /// 每周首日（影响本周统计、周目标与星期分布）。
+    #[serde(default)]
+    pub first_weekday: FirstWeekday,
+    /// 每月目标番茄数量（0 表示不设目标）。
+    #[serde(default)]
+    pub monthly_goal: u32,
+    /// 每年目标番茄数量（0 表示不设目标）。
+    #[serde(default)]
+    pub yearly_goal: u32,
+    /// 按标签设置的目标。
+    #[serde(default)]
+    pub tag_goals: Vec<TagGoal>,
+    /// 连续达标（每日目标）设置。
+    #[serde(default)]
+    pub streak: StreakSettings,
+    /// 按标签覆盖的时长/自动继续/黑名单模板设置（切换到该标签时生效）。
+    #[serde(default)]
+    pub tag_overrides: Vec<TagOverride>,
+    /// 统计与分析时是否将子标签汇总到顶层标签（如 `工作/项目X` 计入 `工作`）。
+    #[serde(default)]
+    pub roll_up_tags: bool,
+    /// 自动备份设置。
+    #[serde(default)]
+    pub backup: BackupSettings,
+    /// 历史保留月数：早于该月数的历史移入按年压缩的归档文件（0 表示不归档，最多 `MAX_ARCHIVE_AFTER_MONTHS`）。
+    #[serde(default)]
+    pub archive_after_months: u32,
 }
 
+/// 历史保留月数上限。
+pub const MAX_ARCHIVE_AFTER_MONTHS: u32 = 120;
+
 /// 音效设置（白噪音/专注音乐）。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -170,6 +215,358 @@ impl Default for InterruptionSettings {
     }
 }
 
+/// 挂起期间到期阶段的处理策略（系统睡眠/休眠恢复时当前阶段已越过截止时刻）。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum SuspendExpiryPolicy {
+    /// 记为完成：按截止时刻写入历史并进入下一阶段。
+    Completed,
+    /// 记为中断：工作阶段写入一条挂起中断记录，并回到工作阶段初始状态。
+    Interrupted,
+    /// 暂停：恢复为挂起前的剩余时间并暂停，等待手动继续。
+    Paused,
+}
+
+impl Default for SuspendExpiryPolicy {
+    /// 默认策略：暂停（不凭空生成完成记录）。
+    fn default() -> Self {
+        Self::Paused
+    }
+}
+
+/// 标签级设置覆盖：为空的字段沿用全局设置。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TagOverride {
+    /// 标签名（保存时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+    pub tag: String,
+    /// 标签 id（保存设置时按路径绑定；旧数据由 `migrate_v6` 回填）。
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// 工作时长（分钟）。
+    #[serde(default)]
+    pub pomodoro: Option<u32>,
+    /// 短休息时长（分钟）。
+    #[serde(default)]
+    pub short_break: Option<u32>,
+    /// 长休息时长（分钟）。
+    #[serde(default)]
+    pub long_break: Option<u32>,
+    /// 是否启用连续番茄模式。
+    #[serde(default)]
+    pub auto_continue_enabled: Option<bool>,
+    /// 连续番茄数量。
+    #[serde(default)]
+    pub auto_continue_pomodoros: Option<u32>,
+    /// 切换到该标签时启用的黑名单模板 id 集合。
+    #[serde(default)]
+    pub blacklist_template_ids: Option<Vec<String>>,
+}
+
+/// 标签层级路径的分隔符（如 `工作/项目X`）。
+pub const TAG_PATH_SEPARATOR: char = '/';
+
+/// 标签定义：稳定 id 与显示属性，可通过 `parent_id` 组成层级。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct Tag {
+    /// 稳定 id（历史/中断记录通过它引用标签，重命名不会改变）。
+    pub id: String,
+    /// 标签名（不含父级路径）。
+    pub name: String,
+    /// 颜色（如 `#f97316`；为空表示使用默认配色）。
+    #[serde(default)]
+    pub color: String,
+    /// 图标（emoji；为空表示不显示）。
+    #[serde(default)]
+    pub icon: String,
+    /// 是否已归档（归档后不出现在标签选择器中，历史记录保留）。
+    #[serde(default)]
+    pub archived: bool,
+    /// 父标签 id（为空表示顶层标签）。
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+/// 规范化标签路径：逐级 trim 并丢弃空段（如 ` 工作 / 项目X ` -> `工作/项目X`）。
+pub fn normalize_tag_path(path: &str) -> String {
+    path.split(TAG_PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 标签路径的顶层标签（用于统计时将子标签汇总到父标签）。
+pub fn root_tag_path(path: &str) -> &str {
+    path.split(TAG_PATH_SEPARATOR).next().unwrap_or(path)
+}
+
+/// 判断 `path` 是否为 `ancestor` 本身或其子孙标签。
+pub fn tag_path_within(path: &str, ancestor: &str) -> bool {
+    path == ancestor
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with(TAG_PATH_SEPARATOR))
+}
+
+/// 记录的当前标签路径：按 `tag_id` 从 `paths`（标签 id → 完整路径）解析；标签已删除或缺少 id 时沿用记录中的路径。
+pub fn current_tag_path<'a>(
+    paths: &'a BTreeMap<String, String>,
+    tag: &'a str,
+    tag_id: Option<&str>,
+) -> &'a str {
+    tag_id
+        .and_then(|id| paths.get(id))
+        .map_or(tag, String::as_str)
+}
+
+/// 将历史副本中的标签刷新为当前路径（仅用于读取结果；存储中的记录不随标签改名/移动而改写）。
+pub fn resolve_history_tags(days: &mut [HistoryDay], paths: &BTreeMap<String, String>) {
+    for record in days.iter_mut().flat_map(|d| d.records.iter_mut()) {
+        let path = current_tag_path(paths, &record.tag, record.tag_id.as_deref());
+        if path != record.tag {
+            record.tag = path.to_string();
+        }
+    }
+}
+
+/// 目标统计周期。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum GoalPeriod {
+    /// 每日。
+    Daily,
+    /// 每周。
+    Weekly,
+    /// 每月。
+    Monthly,
+    /// 每年。
+    Yearly,
+}
+
+/// 标签目标：某个标签在指定周期内需完成的番茄数量。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TagGoal {
+    /// 标签名（保存时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+    pub tag: String,
+    /// 标签 id（保存设置时按路径绑定；旧数据由 `migrate_v6` 回填）。
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// 统计周期。
+    pub period: GoalPeriod,
+    /// 目标番茄数量。
+    pub target: u32,
+}
+
+/// 自动备份设置：定时快照与保留策略（破坏性操作前的备份始终开启）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// 是否启用定时备份。
+    #[serde(default = "default_backup_enabled")]
+    pub enabled: bool,
+    /// 定时备份间隔（小时，1-168）。
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    /// 每种备份保留的份数（1-100，超出后删除最旧的）。
+    #[serde(default = "default_backup_keep_count")]
+    pub keep_count: u32,
+    /// 是否加密备份（仅在启用数据加密后生效）。
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+/// 默认启用定时备份。
+fn default_backup_enabled() -> bool {
+    true
+}
+
+/// 默认每 24 小时备份一次。
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+/// 默认每种备份保留 10 份。
+fn default_backup_keep_count() -> u32 {
+    10
+}
+
+impl Default for BackupSettings {
+    /// 默认备份设置：每天一次、每种保留 10 份、不加密。
+    fn default() -> Self {
+        Self {
+            enabled: default_backup_enabled(),
+            interval_hours: default_backup_interval_hours(),
+            keep_count: default_backup_keep_count(),
+            encrypt: false,
+        }
+    }
+}
+
+/// 连续达标设置：按“每日目标”判定某天是否达标。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct StreakSettings {
+    /// 每周允许的休息日数量（0-6）：未达标但不中断连续记录的天数。
+    #[serde(default)]
+    pub rest_days_per_week: u8,
+    /// 是否在当天较晚时提醒“连续记录即将中断”。
+    #[serde(default = "default_streak_reminder_enabled")]
+    pub reminder_enabled: bool,
+    /// 提醒时刻（0-23 点，按逻辑日计算）。
+    #[serde(default = "default_streak_reminder_hour")]
+    pub reminder_hour: u8,
+}
+
+/// 默认开启连续记录提醒。
+fn default_streak_reminder_enabled() -> bool {
+    true
+}
+
+/// 默认提醒时刻：21 点。
+fn default_streak_reminder_hour() -> u8 {
+    21
+}
+
+impl Default for StreakSettings {
+    /// 默认连续达标设置：无休息日、21 点提醒。
+    fn default() -> Self {
+        Self {
+            rest_days_per_week: 0,
+            reminder_enabled: default_streak_reminder_enabled(),
+            reminder_hour: default_streak_reminder_hour(),
+        }
+    }
+}
+
+/// 每周首日。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum FirstWeekday {
+    /// 周一。
+    Monday,
+    /// 周二。
+    Tuesday,
+    /// 周三。
+    Wednesday,
+    /// 周四。
+    Thursday,
+    /// 周五。
+    Friday,
+    /// 周六。
+    Saturday,
+    /// 周日。
+    Sunday,
+}
+
+impl Default for FirstWeekday {
+    /// 默认每周首日：周一（与旧版本统计口径一致）。
+    fn default() -> Self {
+        Self::Monday
+    }
+}
+
+impl FirstWeekday {
+    /// 转换为 chrono 的 `Weekday`。
+    pub fn to_chrono(self) -> chrono::Weekday {
+        match self {
+            Self::Monday => chrono::Weekday::Mon,
+            Self::Tuesday => chrono::Weekday::Tue,
+            Self::Wednesday => chrono::Weekday::Wed,
+            Self::Thursday => chrono::Weekday::Thu,
+            Self::Friday => chrono::Weekday::Fri,
+            Self::Saturday => chrono::Weekday::Sat,
+            Self::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+/// 统计口径的日/周边界（由设置推导）：决定记录归属的“逻辑日期”与周的起止。
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DayBoundary {
+    /// 逻辑日界线（0-23 点）。
+    pub rollover_hour: u32,
+    /// 每周首日。
+    pub first_weekday: chrono::Weekday,
+}
+
+impl Default for DayBoundary {
+    /// 默认边界：自然日 + 周一为首日。
+    fn default() -> Self {
+        Self {
+            rollover_hour: 0,
+            first_weekday: chrono::Weekday::Mon,
+        }
+    }
+}
+
+impl DayBoundary {
+    /// 计算本地时刻 `at` 所属的逻辑日期（日界线之前的时刻归入前一天）。
+    pub fn logical_date(&self, at: chrono::NaiveDateTime) -> chrono::NaiveDate {
+        (at - chrono::Duration::hours(i64::from(self.rollover_hour))).date()
+    }
+
+    /// 计算历史记录所属的逻辑日期（YYYY-MM-DD）：按开始时刻换算，`day_date` 为其所在的自然日分组。
+    pub fn record_date(&self, day_date: &str, record: &HistoryRecord) -> String {
+        self.date_of(day_date, self.before_rollover(record.start_hour()))
+    }
+
+    /// 判断 `hour` 点开始的记录是否早于日界线（小时未知时视为不早于）。
+    pub fn before_rollover(&self, hour: Option<u32>) -> bool {
+        hour.is_some_and(|hour| hour < self.rollover_hour)
+    }
+
+    /// 计算自然日 `day_date` 内开始的记录所属的逻辑日期：早于日界线时归入前一天。
+    pub fn date_of(&self, day_date: &str, before_rollover: bool) -> String {
+        if !before_rollover {
+            return day_date.to_string();
+        }
+        match chrono::NaiveDate::parse_from_str(day_date, "%Y-%m-%d") {
+            Ok(d) => d
+                .pred_opt()
+                .map(|p| p.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| day_date.to_string()),
+            Err(_) => day_date.to_string(),
+        }
+    }
+
+    /// 将逻辑日期范围换算为需要读取的自然日分组范围（日界线只会把记录归入前一天，故结束日期顺延一天）。
+    pub fn natural_range(&self, range: &DateRange) -> DateRange {
+        let to = match chrono::NaiveDate::parse_from_str(&range.to, "%Y-%m-%d") {
+            Ok(d) if self.rollover_hour > 0 => d
+                .succ_opt()
+                .map(|n| n.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| range.to.clone()),
+            _ => range.to.clone(),
+        };
+        DateRange {
+            from: range.from.clone(),
+            to,
+        }
+    }
+
+    /// 计算 `date` 所在周的首日。
+    pub fn week_start_of(&self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        date - chrono::Duration::days(self.weekday_index(chrono::Datelike::weekday(&date)) as i64)
+    }
+
+    /// 将星期映射为从每周首日起算的索引（0..=6）。
+    pub fn weekday_index(&self, weekday: chrono::Weekday) -> usize {
+        let offset = 7 + weekday.num_days_from_monday() - self.first_weekday.num_days_from_monday();
+        (offset % 7) as usize
+    }
+}
+
 /// 默认连续番茄数量（用于旧版本数据缺失字段时的兼容回填）。
 fn default_auto_continue_pomodoros() -> u32 {
     4
@@ -201,6 +598,53 @@ impl Default for Settings {
             audio: AudioSettings::default(),
             animation: AnimationSettings::default(),
             interruption: InterruptionSettings::default(),
+            suspend_expiry_policy: SuspendExpiryPolicy::default(),
+            day_rollover_hour: 0,
+            first_weekday: FirstWeekday::default(),
+            monthly_goal: 0,
+            yearly_goal: 0,
+            tag_goals: Vec::new(),
+            streak: StreakSettings::default(),
+            tag_overrides: Vec::new(),
+            roll_up_tags: false,
+            backup: BackupSettings::default(),
+            archive_after_months: 0,
+        }
+    }
+}
+
+impl Settings {
+    /// 查找指定标签的覆盖设置：双方都带标签 id 时按 id 匹配（标签改名/移动后仍然有效），否则按路径匹配。
+    pub fn tag_override(&self, tag: &str, tag_id: Option<&str>) -> Option<&TagOverride> {
+        self.tag_overrides
+            .iter()
+            .find(|o| match (o.tag_id.as_deref(), tag_id) {
+                (Some(a), Some(b)) => a == b,
+                _ => o.tag == tag,
+            })
+    }
+
+    /// 合并指定标签的覆盖项后得到的有效设置（无覆盖时与全局设置一致）。
+    pub fn for_tag(&self, tag: &str, tag_id: Option<&str>) -> Settings {
+        let mut out = self.clone();
+        if let Some(o) = self.tag_override(tag, tag_id) {
+            out.pomodoro = o.pomodoro.unwrap_or(out.pomodoro);
+            out.short_break = o.short_break.unwrap_or(out.short_break);
+            out.long_break = o.long_break.unwrap_or(out.long_break);
+            out.auto_continue_enabled =
+                o.auto_continue_enabled.unwrap_or(out.auto_continue_enabled);
+            out.auto_continue_pomodoros = o
+                .auto_continue_pomodoros
+                .unwrap_or(out.auto_continue_pomodoros);
+        }
+        out
+    }
+
+    /// 由设置推导统计口径的日/周边界。
+    pub fn day_boundary(&self) -> DayBoundary {
+        DayBoundary {
+            rollover_hour: u32::from(self.day_rollover_hour.min(23)),
+            first_weekday: self.first_weekday.to_chrono(),
         }
     }
 }
@@ -220,7 +664,7 @@ pub struct CustomAudio {
     pub builtin: bool,
 }
 
-/// 中断类型（PRD v4：reset/skip/quit）。
+/// 中断类型（PRD v4：reset/skip/quit；suspend 为挂起期间到期）。
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(rename_all = "camelCase")]
@@ -231,6 +675,8 @@ pub enum InterruptionType {
     Skip,
     /// 退出中断。
     Quit,
+    /// 挂起中断（系统挂起期间工作阶段到期）。
+    Suspend,
 }
 
 /// 中断记录（PRD v4）。
@@ -248,8 +694,17 @@ pub struct InterruptionRecord {
     pub reason: String,
     /// 中断类型。
     pub r#type: InterruptionType,
-    /// 当时的任务标签。
+    /// 当时的任务标签（写入时的完整路径；读取时按 `tag_id` 解析为当前路径）。
     pub tag: String,
+    /// 当时的任务标签 id（旧数据由 `migrate_v6` 回填）。
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// 本次工作阶段的开始时刻（RFC 3339，带 UTC 偏移；旧数据由 `migrate_v5` 按已专注秒数回推）。
+    #[serde(default)]
+    pub started_at: Option<String>,
+    /// 中断发生时刻（RFC 3339，带本地 UTC 偏移）。
+    #[serde(default)]
+    pub ended_at: Option<String>,
 }
 
 /// 某一天的中断集合。
@@ -289,26 +744,141 @@ pub struct BlacklistTemplate {
     pub processes: Vec<BlacklistItem>,
 }
 
-/// 单条历史记录（仅记录完成的“工作”阶段）。
+/// 阶段内的一段暂停（开始于暂停、结束于继续/跳过）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct PauseSegment {
+    /// 暂停开始时间（HH:mm）。
+    pub start_time: String,
+    /// 暂停结束时间（HH:mm）。
+    pub end_time: String,
+    /// 暂停时长（秒）。
+    pub seconds: u64,
+}
+
+/// 单条历史记录（完成的工作阶段，以及开始过的休息阶段）。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(rename_all = "camelCase")]
 pub struct HistoryRecord {
-    /// 任务标签。
+    /// 记录 id（创建时生成，编辑、移动日期后保持不变；旧数据由 `migrate_v7` 补齐）。
+    #[serde(default)]
+    pub id: String,
+    /// 任务标签（写入时的完整路径；读取时按 `tag_id` 解析为当前路径）。
     pub tag: String,
+    /// 任务标签 id（为空表示无标签；旧数据由 `migrate_v6` 回填）。
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// 关联的任务 id（仅工作记录；为空表示未关联任务）。
+    #[serde(default)]
+    pub task_id: Option<String>,
     /// 开始时间（HH:mm）。
     pub start_time: String,
     /// 结束时间（HH:mm；旧数据可能缺失，前端可按 `start_time + duration` 推导展示）。
     #[serde(default)]
     pub end_time: Option<String>,
+    /// 开始时刻（RFC 3339，带 UTC 偏移；旧数据由 `migrate_v5` 回填）。
+    #[serde(default)]
+    pub started_at: Option<String>,
+    /// 结束时刻（RFC 3339，带 UTC 偏移；跨午夜/时区变化/夏令时下不再有歧义）。
+    #[serde(default)]
+    pub ended_at: Option<String>,
     /// 本次番茄时长（分钟）。
     pub duration: u32,
-    /// 阶段类型（用于导出/分析；当前仅记录工作阶段）。
+    /// 阶段类型（用于导出/分析；休息阶段不计入番茄统计）。
     #[serde(default)]
     pub phase: Phase,
     /// 备注（完成后可填写，也可在历史中编辑）。
     #[serde(default)]
     pub remark: String,
+    /// 实际计时秒数（不含暂停；旧数据缺失时按 `duration` 推算）。
+    #[serde(default)]
+    pub elapsed_seconds: Option<u64>,
+    /// 阶段内的暂停片段（按发生顺序）。
+    #[serde(default)]
+    pub pauses: Vec<PauseSegment>,
+    /// 是否被提前跳过（仅休息阶段会写入跳过记录）。
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+impl HistoryRecord {
+    /// 实际计时秒数（不含暂停）：旧数据回退为 `duration` 分钟。
+    pub fn active_seconds(&self) -> u64 {
+        self.elapsed_seconds
+            .unwrap_or(u64::from(self.duration) * 60)
+    }
+
+    /// 暂停总秒数。
+    pub fn paused_seconds(&self) -> u64 {
+        self.pauses.iter().map(|p| p.seconds).sum()
+    }
+
+    /// 开始时刻（换算到本地时区；缺失或无法解析时为 `None`）。
+    pub fn started_at_local(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        let at = chrono::DateTime::parse_from_rfc3339(self.started_at.as_deref()?).ok()?;
+        Some(at.with_timezone(&chrono::Local))
+    }
+
+    /// 开始时刻所在的小时：优先取 `started_at`，旧记录按 `start_time`（HH:mm）解析。
+    pub fn start_hour(&self) -> Option<u32> {
+        match self.started_at_local() {
+            Some(at) => Some(chrono::Timelike::hour(&at)),
+            None => self
+                .start_time
+                .split(':')
+                .next()
+                .and_then(|h| h.trim().parse::<u32>().ok()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl HistoryRecord {
+    /// 测试用：构造一条 25 分钟的工作记录（无标签 id、任务与起止时刻），其余字段按需用 `with_*` 或结构体更新语法覆盖。
+    pub(crate) fn for_test(tag: &str, start: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            tag: tag.to_string(),
+            tag_id: None,
+            task_id: None,
+            start_time: start.to_string(),
+            end_time: None,
+            started_at: None,
+            ended_at: None,
+            duration: 25,
+            phase: Phase::Work,
+            remark: String::new(),
+            elapsed_seconds: None,
+            pauses: Vec::new(),
+            skipped: false,
+        }
+    }
+
+    /// 测试用：指定阶段。
+    pub(crate) fn with_phase(mut self, phase: Phase) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// 测试用：指定时长（分钟）。
+    pub(crate) fn with_duration(mut self, duration: u32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// 测试用：指定标签 id。
+    pub(crate) fn with_tag_id(mut self, tag_id: Option<String>) -> Self {
+        self.tag_id = tag_id;
+        self
+    }
+
+    /// 测试用：指定结束时间（HH:mm）。
+    pub(crate) fn with_end(mut self, end: &str) -> Self {
+        self.end_time = Some(end.to_string());
+        self
+    }
 }
 
 /// 某一天的历史集合。
@@ -322,6 +892,95 @@ pub struct HistoryDay {
     pub records: Vec<HistoryRecord>,
 }
 
+/// 历史归档概况：早于归档线的历史已移入按年压缩的归档文件，这里只保留按日/按标签的汇总。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct HistoryArchive {
+    /// 归档线（YYYY-MM-DD）：自然日期早于该日的历史已归档（从未归档时为 `null`）。
+    pub before: Option<String>,
+    /// 已归档历史的逐日汇总（按逻辑日期升序）。
+    pub days: Vec<ArchivedDay>,
+}
+
+/// 已归档的一天：工作记录按标签汇总（日期按归档时的日界线归属）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ArchivedDay {
+    /// 逻辑日期（YYYY-MM-DD）。
+    pub date: String,
+    /// 按标签汇总的工作记录。
+    pub by_tag: Vec<ArchivedTagTotal>,
+}
+
+/// 已归档的一天中某个标签的工作记录汇总。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ArchivedTagTotal {
+    /// 标签完整路径（归档时的路径）。
+    pub tag: String,
+    /// 标签 id（读取时据此解析当前路径）。
+    pub tag_id: Option<String>,
+    /// 完成的番茄数量。
+    pub count: u32,
+    /// 专注分钟数。
+    pub minutes: u32,
+}
+
+/// 会话计划中的一个步骤（按 `kind` 决定其作为工作还是休息参与计时、历史与统计）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct PlanStep {
+    /// 步骤名称（例如“热身”“深度工作”）。
+    pub name: String,
+    /// 步骤类型（工作/短休息/长休息）。
+    pub kind: Phase,
+    /// 步骤时长（分钟）。
+    pub minutes: u32,
+}
+
+/// 任务：用于规划与复盘的最小意图单元（完成的工作记录通过 `task_id` 关联）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct Task {
+    /// 任务 id（保存时若为空则自动生成）。
+    pub id: String,
+    /// 任务标题。
+    pub title: String,
+    /// 所属标签（保存时的完整路径，读取时按 `tag_id` 解析为当前路径；为空表示沿用当前标签）。
+    #[serde(default)]
+    pub tag: String,
+    /// 所属标签 id（保存任务时按路径绑定；旧数据由 `migrate_v6` 回填）。
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// 预估番茄数（0 表示未预估）。
+    #[serde(default)]
+    pub estimated_pomodoros: u32,
+    /// 截止日期（YYYY-MM-DD）。
+    #[serde(default)]
+    pub due_date: Option<String>,
+    /// 是否已完成。
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// 自定义会话计划：按顺序循环执行的步骤列表（替代经典的“工作/休息 + 长休息间隔”循环）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SessionPlan {
+    /// 计划 id。
+    pub id: String,
+    /// 计划名称。
+    pub name: String,
+    /// 有序步骤列表。
+    pub steps: Vec<PlanStep>,
+}
+
 /// 日期范围（闭区间）：`from <= date <= to`。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -338,6 +997,9 @@ pub struct DateRange {
 #[serde(rename_all = "camelCase")]
 #[ts(rename_all = "camelCase")]
 pub struct AppData {
+    /// 数据结构版本（缺失时为 0，表示引入版本号之前写入的数据；见 `migrations`）。
+    #[serde(default)]
+    pub schema_version: u32,
     /// 用户设置。
     pub settings: Settings,
     /// 进程黑名单。
@@ -351,8 +1013,11 @@ pub struct AppData {
     /// 兼容字段：旧/示例数据中的单一激活模板（用于自动迁移到 `active_template_ids`）。
     #[serde(default)]
     pub active_template_id: Option<String>,
-    /// 历史标签。
+    /// 兼容字段：未归档标签的完整路径列表（由 `tag_defs` 同步，供标签选择器使用）。
     pub tags: Vec<String>,
+    /// 标签定义（含 id/颜色/图标/归档/层级）。
+    #[serde(default)]
+    pub tag_defs: Vec<Tag>,
     /// 历史记录（按日分组）。
     pub history: Vec<HistoryDay>,
     /// 调试历史记录（仅开发环境使用，与正式数据隔离）。
@@ -370,37 +1035,157 @@ pub struct AppData {
     /// 累计完成番茄总数（用于里程碑）。
     #[serde(default)]
     pub total_pomodoros: u64,
+    /// 自定义会话计划列表。
+    #[serde(default)]
+    pub session_plans: Vec<SessionPlan>,
+    /// 当前启用的会话计划 id（为空时使用经典循环）。
+    #[serde(default)]
+    pub active_plan_id: Option<String>,
+    /// 任务列表。
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    /// 历史归档概况（已归档历史的汇总，见 `archive`）。
+    #[serde(default)]
+    pub history_archive: HistoryArchive,
+    /// 自上次持久化以来登记的记录变更（仅内存；持久化时据此只重写有变化的日期）。
+    #[serde(skip)]
+    pub pending: PendingChanges,
+    /// 可撤销操作进行中时采集的撤销片段（仅内存，见 `begin_undo_capture`）。
+    #[serde(skip)]
+    pub undo_capture: Option<crate::undo::UndoCapture>,
 }
 
-impl Default for AppData {
-    /// PRD 初始数据：默认 settings + 默认 tags + 空 blacklist/history。
-    fn default() -> Self {
-        let templates = builtin_templates();
-        let active = vec!["work".to_string()];
-        let blacklist = templates
-            .iter()
+/// 记录集合（持久化时按集合 + 日期定位需要重写的记录行）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RecordSet {
+    /// 正式历史（`history`）。
+    History,
+    /// 开发环境测试历史（`history_dev`）。
+    HistoryDev,
+    /// 中断记录（`interruptions`）。
+    Interruptions,
+}
+
+/// 除记录外可单独登记变更的字段分组（审计时按分组生成事件）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    /// 设置。
+    Settings,
+    /// 当前黑名单。
+    Blacklist,
+    /// 黑名单模板与启用的模板。
+    Templates,
+    /// 标签列表与标签定义。
+    Tags,
+    /// 自定义音频。
+    CustomAudios,
+    /// Combo 与累计番茄数。
+    Counters,
+    /// 会话计划与启用的计划。
+    Plans,
+    /// 任务。
+    Tasks,
+    /// 历史归档汇总。
+    Archive,
+}
+
+impl Section {
+    /// 全部分组。
+    pub const ALL: [Section; 9] = [
+        Self::Settings,
+        Self::Blacklist,
+        Self::Templates,
+        Self::Tags,
+        Self::CustomAudios,
+        Self::Counters,
+        Self::Plans,
+        Self::Tasks,
+        Self::Archive,
+    ];
+
+    /// 字段（camelCase）所属的分组。
+    pub fn of_field(field: &str) -> Option<Section> {
+        Self::ALL.into_iter().find(|s| s.fields().contains(&field))
+    }
+
+    /// 分组包含的 `AppData` 字段（camelCase）。
+    pub fn fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Settings => &["settings"],
+            Self::Blacklist => &["blacklist"],
+            Self::Templates => &[
+                "blacklistTemplates",
+                "activeTemplateIds",
+                "activeTemplateId",
+            ],
+            Self::Tags => &["tags", "tagDefs"],
+            Self::CustomAudios => &["customAudios"],
+            Self::Counters => &["currentCombo", "totalPomodoros"],
+            Self::Plans => &["sessionPlans", "activePlanId"],
+            Self::Tasks => &["tasks"],
+            Self::Archive => &["historyArchive"],
+        }
+    }
+}
+
+/// 待持久化的变更：修改数据前经 `AppData::touch_day`、`touch_section` 等登记，持久化时由历史数据库取出。
+#[derive(Debug, Clone, Default)]
+pub struct PendingChanges {
+    /// 记录有变化的 `(集合, 日期)`。
+    pub days: BTreeSet<(RecordSet, String)>,
+    /// 记录被整体替换（恢复备份、同步合并、迁移等），需全量重写（审计时写入新的基线）。
+    pub all_records: bool,
+    /// 有变化的字段分组。
+    pub sections: BTreeSet<Section>,
+    /// 命令登记的领域事件（按发生顺序）。
+    pub events: Vec<crate::audit::DomainEvent>,
+}
+
+impl Default for AppData {
+    /// PRD 初始数据：默认 settings + 默认 tags + 空 blacklist/history。
+    fn default() -> Self {
+        let templates = builtin_templates();
+        let active = vec!["work".to_string()];
+        let blacklist = templates
+            .iter()
             .find(|t| t.id == "work")
             .map(|t| t.processes.clone())
             .unwrap_or_default();
 
+        let tag_defs: Vec<Tag> = ["工作", "学习", "阅读", "写作"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Tag {
+                id: format!("tag-{}", i + 1),
+                name: name.to_string(),
+                color: String::new(),
+                icon: String::new(),
+                archived: false,
+                parent_id: None,
+            })
+            .collect();
+
         Self {
+            schema_version: crate::migrations::CURRENT_SCHEMA_VERSION,
             settings: Settings::default(),
             blacklist,
             blacklist_templates: templates,
             active_template_ids: active.clone(),
             active_template_id: active.first().cloned(),
-            tags: vec![
-                "工作".to_string(),
-                "学习".to_string(),
-                "阅读".to_string(),
-                "写作".to_string(),
-            ],
+            tags: tag_defs.iter().map(|t| t.name.clone()).collect(),
+            tag_defs,
             history: Vec::new(),
             history_dev: Vec::new(),
             custom_audios: Vec::new(),
             interruptions: Vec::new(),
             current_combo: 0,
             total_pomodoros: 0,
+            session_plans: Vec::new(),
+            active_plan_id: None,
+            tasks: Vec::new(),
+            history_archive: HistoryArchive::default(),
+            pending: PendingChanges::default(),
+            undo_capture: None,
         }
     }
 }
@@ -451,7 +1236,7 @@ impl AppData {
             let total_from_history = self
                 .history
                 .iter()
-                .map(|d| d.records.len() as u64)
+                .map(|d| d.records.iter().filter(|r| r.phase == Phase::Work).count() as u64)
                 .sum::<u64>();
             if total_from_history > 0 {
                 self.total_pomodoros = total_from_history;
@@ -461,6 +1246,605 @@ impl AppData {
 
         changed
     }
+
+    /// 将旧版本数据迁移到 v5 结构：由“日期 + HH:mm”回填历史与中断记录的 RFC 3339 起止时刻。
+    pub fn migrate_v5(&mut self) -> bool {
+        let mut changed = false;
+
+        for day in self.history.iter_mut().chain(self.history_dev.iter_mut()) {
+            for record in &mut day.records {
+                if record.started_at.is_some() {
+                    continue;
+                }
+                let Some(started) = local_instant(&day.date, &record.start_time) else {
+                    continue;
+                };
+                let ended = record
+                    .end_time
+                    .as_deref()
+                    .and_then(|end| local_instant(&day.date, end))
+                    .map(|end| {
+                        // 结束时间早于开始时间：记录跨越了午夜。
+                        if end < started {
+                            end + chrono::Duration::days(1)
+                        } else {
+                            end
+                        }
+                    })
+                    .unwrap_or_else(|| {
+                        let seconds = record.active_seconds() + record.paused_seconds();
+                        started + chrono::Duration::seconds(seconds as i64)
+                    });
+                record.started_at = Some(started.to_rfc3339());
+                record.ended_at = Some(ended.to_rfc3339());
+                changed = true;
+            }
+        }
+
+        for day in &mut self.interruptions {
+            for record in &mut day.records {
+                if record.ended_at.is_some() {
+                    continue;
+                }
+                let Ok(at) = chrono::DateTime::parse_from_rfc3339(&record.timestamp) else {
+                    continue;
+                };
+                let ended = at.with_timezone(&chrono::Local);
+                let started = ended - chrono::Duration::seconds(record.focused_seconds as i64);
+                record.started_at = Some(started.to_rfc3339());
+                record.ended_at = Some(ended.to_rfc3339());
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// 将旧版本数据迁移到 v6 结构：由字符串标签生成标签定义，并为历史/中断记录、标签覆盖项/目标与任务回填 `tag_id`。
+    ///
+    /// 含 `/` 的旧标签按层级拆分；仅出现在历史中的标签（已不在标签列表里）迁移为归档标签。
+    pub fn migrate_v6(&mut self) -> bool {
+        let mut changed = false;
+
+        let listed: Vec<String> = self.tags.iter().map(|t| normalize_tag_path(t)).collect();
+        let recorded: Vec<String> = self
+            .history
+            .iter()
+            .chain(self.history_dev.iter())
+            .flat_map(|d| d.records.iter().map(|r| r.tag.as_str()))
+            .chain(
+                self.interruptions
+                    .iter()
+                    .flat_map(|d| d.records.iter().map(|r| r.tag.as_str())),
+            )
+            .map(normalize_tag_path)
+            .collect();
+        for (path, archived) in listed
+            .iter()
+            .map(|p| (p, false))
+            .chain(recorded.iter().map(|p| (p, !listed.contains(p))))
+        {
+            if path.is_empty() || self.tag_id_by_path(path).is_some() {
+                continue;
+            }
+            let id = self.ensure_tag_path(path);
+            if let Some(tag) = self.tag_defs.iter_mut().find(|t| t.id == id) {
+                tag.archived = archived;
+            }
+            changed = true;
+        }
+
+        let ids: BTreeMap<String, String> = self
+            .tag_defs
+            .iter()
+            .filter_map(|t| Some((self.tag_path(&t.id)?, t.id.clone())))
+            .collect();
+        let lookup = |tag: &str| ids.get(&normalize_tag_path(tag)).cloned();
+        for day in self.history.iter_mut().chain(self.history_dev.iter_mut()) {
+            for record in &mut day.records {
+                if record.tag_id.is_none() && !record.tag.trim().is_empty() {
+                    record.tag_id = lookup(&record.tag);
+                    changed |= record.tag_id.is_some();
+                }
+            }
+        }
+        for day in &mut self.interruptions {
+            for record in &mut day.records {
+                if record.tag_id.is_none() && !record.tag.trim().is_empty() {
+                    record.tag_id = lookup(&record.tag);
+                    changed |= record.tag_id.is_some();
+                }
+            }
+        }
+        let refs = self
+            .settings
+            .tag_overrides
+            .iter_mut()
+            .map(|o| (&o.tag, &mut o.tag_id))
+            .chain(
+                self.settings
+                    .tag_goals
+                    .iter_mut()
+                    .map(|g| (&g.tag, &mut g.tag_id)),
+            )
+            .chain(self.tasks.iter_mut().map(|t| (&t.tag, &mut t.tag_id)));
+        for (tag, tag_id) in refs {
+            if tag_id.is_none() && !tag.trim().is_empty() {
+                *tag_id = lookup(tag);
+                changed |= tag_id.is_some();
+            }
+        }
+
+        if changed {
+            self.sync_tags();
+        }
+        changed
+    }
+
+    /// 将旧版本数据迁移到 v7 结构：为缺少 id 的历史记录补齐 id。
+    ///
+    /// id 由日期、开始时刻、阶段与标签确定性生成，多台设备各自迁移同一份旧记录会得到相同的 id。
+    pub fn migrate_v7(&mut self) -> bool {
+        self.ensure_record_ids()
+    }
+
+    /// 将旧版本数据迁移到 v8 结构：新增历史归档汇总与历史保留月数。
+    ///
+    /// 旧数据中二者取默认值（无归档、不归档），无需改动。
+    pub fn migrate_v8(&mut self) -> bool {
+        false
+    }
+
+    /// 为缺少 id 的历史记录补齐确定性 id（内容相同的记录依次加序号去重），返回是否有改动。
+    ///
+    /// 只修改内存中的记录，调用方负责登记变更（迁移随后会整体重写）。
+    pub fn ensure_record_ids(&mut self) -> bool {
+        let mut seen: HashSet<String> = self
+            .history
+            .iter()
+            .chain(&self.history_dev)
+            .flat_map(|d| &d.records)
+            .filter(|r| !r.id.is_empty())
+            .map(|r| r.id.clone())
+            .collect();
+        let mut changed = false;
+        for day in self.history.iter_mut().chain(self.history_dev.iter_mut()) {
+            for record in day.records.iter_mut().filter(|r| r.id.is_empty()) {
+                let mut n = 0u32;
+                record.id = loop {
+                    let id = legacy_record_id(&day.date, record, n);
+                    if seen.insert(id.clone()) {
+                        break id;
+                    }
+                    n += 1;
+                };
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// 标签的完整路径（父级在前，以 `/` 连接）；id 不存在时返回 `None`。
+    pub fn tag_path(&self, id: &str) -> Option<String> {
+        let mut tag = self.tag_defs.iter().find(|t| t.id == id)?;
+        let mut parts = vec![tag.name.as_str()];
+        // 以标签总数为上限，防止损坏数据中的循环引用导致死循环。
+        while parts.len() <= self.tag_defs.len() {
+            let Some(parent) = tag
+                .parent_id
+                .as_deref()
+                .and_then(|pid| self.tag_defs.iter().find(|t| t.id == pid))
+            else {
+                break;
+            };
+            parts.push(parent.name.as_str());
+            tag = parent;
+        }
+        parts.reverse();
+        Some(parts.join("/"))
+    }
+
+    /// 按完整路径查找标签 id。
+    pub fn tag_id_by_path(&self, path: &str) -> Option<String> {
+        self.tag_defs
+            .iter()
+            .find(|t| self.tag_path(&t.id).as_deref() == Some(path))
+            .map(|t| t.id.clone())
+    }
+
+    /// 确保路径上的各级标签都存在（缺失的逐级创建），返回末级标签 id；路径为空时返回空字符串。
+    pub fn ensure_tag_path(&mut self, path: &str) -> String {
+        let path = normalize_tag_path(path);
+        if path.is_empty() {
+            return String::new();
+        }
+        let mut parent: Option<String> = None;
+        for name in path.split(TAG_PATH_SEPARATOR) {
+            let existing = self
+                .tag_defs
+                .iter()
+                .find(|t| t.name == name && t.parent_id == parent)
+                .map(|t| t.id.clone());
+            let id = existing.unwrap_or_else(|| {
+                let id = self.next_tag_id();
+                self.tag_defs.push(Tag {
+                    id: id.clone(),
+                    name: name.to_string(),
+                    color: String::new(),
+                    icon: String::new(),
+                    archived: false,
+                    parent_id: parent.clone(),
+                });
+                id
+            });
+            parent = Some(id);
+        }
+        parent.unwrap_or_default()
+    }
+
+    /// 生成下一个标签 id（`tag-<序号>`，序号取现有最大值 + 1）。
+    fn next_tag_id(&self) -> String {
+        let max = self
+            .tag_defs
+            .iter()
+            .filter_map(|t| t.id.strip_prefix("tag-")?.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        format!("tag-{}", max + 1)
+    }
+
+    /// 全部标签的完整路径（按标签 id）。
+    pub fn tag_paths(&self) -> BTreeMap<String, String> {
+        self.tag_defs
+            .iter()
+            .filter_map(|t| Some((t.id.clone(), self.tag_path(&t.id)?)))
+            .collect()
+    }
+
+    /// 合并标签（完整路径）的覆盖项后得到的有效设置（覆盖项按标签 id 匹配）。
+    pub fn settings_for_tag(&self, tag: &str) -> Settings {
+        self.settings
+            .for_tag(tag, self.tag_id_by_path(tag).as_deref())
+    }
+
+    /// 按当前路径为设置中的标签覆盖项与标签目标绑定标签 id（保存前端提交的设置时调用）。
+    pub fn bind_tag_ids(&self, settings: &mut Settings) {
+        for o in &mut settings.tag_overrides {
+            o.tag_id = self.tag_id_by_path(&o.tag);
+        }
+        for g in &mut settings.tag_goals {
+            g.tag_id = self.tag_id_by_path(&g.tag);
+        }
+    }
+
+    /// 由标签定义重建兼容字段 `tags`（历史记录只保存 `tag_id`，展示路径在读取时解析）。
+    pub fn sync_tags(&mut self) {
+        let paths = self.tag_paths();
+        self.tags = self
+            .tag_defs
+            .iter()
+            .filter(|t| !t.archived)
+            .filter_map(|t| paths.get(&t.id).cloned())
+            .collect();
+    }
+
+    /// 读取用的数据副本：历史、中断记录、归档汇总、标签覆盖项/目标与任务的标签路径按 `tag_id` 解析为当前路径。
+    pub fn resolved(&self) -> AppData {
+        let paths = self.tag_paths();
+        let mut out = self.clone();
+        resolve_history_tags(&mut out.history, &paths);
+        resolve_history_tags(&mut out.history_dev, &paths);
+        for record in out
+            .interruptions
+            .iter_mut()
+            .flat_map(|d| d.records.iter_mut())
+        {
+            record.tag =
+                current_tag_path(&paths, &record.tag, record.tag_id.as_deref()).to_string();
+        }
+        for total in out
+            .history_archive
+            .days
+            .iter_mut()
+            .flat_map(|d| d.by_tag.iter_mut())
+        {
+            total.tag = current_tag_path(&paths, &total.tag, total.tag_id.as_deref()).to_string();
+        }
+        for o in &mut out.settings.tag_overrides {
+            o.tag = current_tag_path(&paths, &o.tag, o.tag_id.as_deref()).to_string();
+        }
+        for g in &mut out.settings.tag_goals {
+            g.tag = current_tag_path(&paths, &g.tag, g.tag_id.as_deref()).to_string();
+        }
+        for t in &mut out.tasks {
+            t.tag = current_tag_path(&paths, &t.tag, t.tag_id.as_deref()).to_string();
+        }
+        out
+    }
+
+    /// 登记某日的记录有变化（直接修改 `history`/`history_dev`/`interruptions` 中的某日前调用）。
+    pub fn touch_day(&mut self, set: RecordSet, date: &str) {
+        self.capture_undo(|capture, data| capture.capture_day(data, set, date));
+        if !self.pending.all_records {
+            self.pending.days.insert((set, date.to_string()));
+        }
+    }
+
+    /// 登记全部记录需要重写（批量修复、迁移等逐日登记不便时调用；已登记的事件由新的基线取代）。
+    pub fn touch_all_records(&mut self) {
+        self.capture_undo(|capture, data| capture.capture_all(data));
+        self.pending = PendingChanges {
+            all_records: true,
+            ..PendingChanges::default()
+        };
+    }
+
+    /// 登记某个字段分组有变化（修改分组内字段前调用）。
+    pub fn touch_section(&mut self, section: Section) {
+        self.capture_undo(|capture, data| capture.capture_section(data, section));
+        if !self.pending.all_records {
+            self.pending.sections.insert(section);
+        }
+    }
+
+    /// 开始采集撤销片段：之后经 `touch_*` 登记的日期与字段分组在修改前保存原值。
+    pub fn begin_undo_capture(&mut self) {
+        self.undo_capture = Some(crate::undo::UndoCapture::default());
+    }
+
+    /// 结束采集并取出结果（未开始采集时为空结果）。
+    pub fn take_undo_capture(&mut self) -> crate::undo::UndoCapture {
+        self.undo_capture.take().unwrap_or_default()
+    }
+
+    /// 正在采集撤销片段时，把登记交给采集器（采集器需读取修改前的数据）。
+    fn capture_undo(&mut self, f: impl FnOnce(&mut crate::undo::UndoCapture, &AppData)) {
+        if let Some(mut capture) = self.undo_capture.take() {
+            f(&mut capture, self);
+            self.undo_capture = Some(capture);
+        }
+    }
+
+    /// 登记一个领域事件（持久化时与记录一起写入审计日志）。
+    pub fn record_event(&mut self, event: crate::audit::DomainEvent) {
+        if !self.pending.all_records {
+            self.pending.events.push(event);
+        }
+    }
+
+    /// 为批量修改过的日期登记整日替换事件（修改完成后调用，事件携带当日的最新记录）。
+    pub fn record_days_replaced(&mut self, days: impl IntoIterator<Item = (RecordSet, String)>) {
+        for (set, date) in days {
+            let event = match set {
+                RecordSet::History | RecordSet::HistoryDev => {
+                    let dev = set == RecordSet::HistoryDev;
+                    let list = if dev {
+                        &self.history_dev
+                    } else {
+                        &self.history
+                    };
+                    crate::audit::DomainEvent::HistoryDayReplaced {
+                        dev,
+                        records: list
+                            .iter()
+                            .filter(|d| d.date == date)
+                            .flat_map(|d| d.records.iter().cloned())
+                            .collect(),
+                        date,
+                    }
+                }
+                RecordSet::Interruptions => crate::audit::DomainEvent::InterruptionDayReplaced {
+                    records: self
+                        .interruptions
+                        .iter()
+                        .filter(|d| d.date == date)
+                        .flat_map(|d| d.records.iter().cloned())
+                        .collect(),
+                    date,
+                },
+            };
+            self.record_event(event);
+        }
+    }
+
+    /// 分组内某个字段的 JSON 值（字段名为 camelCase，见 `Section::fields`）。
+    pub fn section_value(&self, field: &str) -> serde_json::Result<serde_json::Value> {
+        match field {
+            "settings" => serde_json::to_value(&self.settings),
+            "blacklist" => serde_json::to_value(&self.blacklist),
+            "blacklistTemplates" => serde_json::to_value(&self.blacklist_templates),
+            "activeTemplateIds" => serde_json::to_value(&self.active_template_ids),
+            "activeTemplateId" => serde_json::to_value(&self.active_template_id),
+            "tags" => serde_json::to_value(&self.tags),
+            "tagDefs" => serde_json::to_value(&self.tag_defs),
+            "customAudios" => serde_json::to_value(&self.custom_audios),
+            "currentCombo" => serde_json::to_value(self.current_combo),
+            "totalPomodoros" => serde_json::to_value(self.total_pomodoros),
+            "sessionPlans" => serde_json::to_value(&self.session_plans),
+            "activePlanId" => serde_json::to_value(&self.active_plan_id),
+            "tasks" => serde_json::to_value(&self.tasks),
+            "historyArchive" => serde_json::to_value(&self.history_archive),
+            _ => Ok(serde_json::Value::Null),
+        }
+    }
+
+    /// 以 JSON 值替换分组内某个字段（未知字段忽略）。
+    pub fn set_section_value(
+        &mut self,
+        field: &str,
+        value: serde_json::Value,
+    ) -> serde_json::Result<()> {
+        match field {
+            "settings" => self.settings = serde_json::from_value(value)?,
+            "blacklist" => self.blacklist = serde_json::from_value(value)?,
+            "blacklistTemplates" => self.blacklist_templates = serde_json::from_value(value)?,
+            "activeTemplateIds" => self.active_template_ids = serde_json::from_value(value)?,
+            "activeTemplateId" => self.active_template_id = serde_json::from_value(value)?,
+            "tags" => self.tags = serde_json::from_value(value)?,
+            "tagDefs" => self.tag_defs = serde_json::from_value(value)?,
+            "customAudios" => self.custom_audios = serde_json::from_value(value)?,
+            "currentCombo" => self.current_combo = serde_json::from_value(value)?,
+            "totalPomodoros" => self.total_pomodoros = serde_json::from_value(value)?,
+            "sessionPlans" => self.session_plans = serde_json::from_value(value)?,
+            "activePlanId" => self.active_plan_id = serde_json::from_value(value)?,
+            "tasks" => self.tasks = serde_json::from_value(value)?,
+            "historyArchive" => self.history_archive = serde_json::from_value(value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 以 `other` 整体替换数据（恢复备份、按事件日志重建等），下次持久化全量重写记录。
+    pub fn replace_with(&mut self, other: AppData) {
+        self.capture_undo(|capture, data| capture.capture_all(data));
+        let capture = self.undo_capture.take();
+        *self = other;
+        self.undo_capture = capture;
+        self.pending = PendingChanges {
+            all_records: true,
+            ..PendingChanges::default()
+        };
+    }
+
+    /// 以 `other` 替换数据，但只登记与当前内容不同的日期与字段分组（同步合并等通常只改动少量内容时使用），返回是否有变化。
+    pub fn replace_changed(&mut self, other: AppData) -> serde_json::Result<bool> {
+        let mut sections = Vec::new();
+        for section in Section::ALL {
+            for field in section.fields() {
+                if self.section_value(field)? != other.section_value(field)? {
+                    sections.push(section);
+                    break;
+                }
+            }
+        }
+        let mut days = Vec::new();
+        for set in [
+            RecordSet::History,
+            RecordSet::HistoryDev,
+            RecordSet::Interruptions,
+        ] {
+            let (old, new) = (self.dated_records(set)?, other.dated_records(set)?);
+            for date in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+                if old.get(date) != new.get(date) {
+                    days.push((set, date.clone()));
+                }
+            }
+        }
+        if sections.is_empty() && days.is_empty() {
+            return Ok(false);
+        }
+
+        for section in sections {
+            self.touch_section(section);
+        }
+        for (set, date) in &days {
+            self.touch_day(*set, date);
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let capture = self.undo_capture.take();
+        *self = other;
+        self.pending = pending;
+        self.undo_capture = capture;
+        self.record_days_replaced(days);
+        Ok(true)
+    }
+
+    /// 某个记录集合按日期分组的记录 JSON（同一日期出现多次时依次拼接）。
+    fn dated_records(
+        &self,
+        set: RecordSet,
+    ) -> serde_json::Result<BTreeMap<String, Vec<serde_json::Value>>> {
+        let days = match set {
+            RecordSet::History => serde_json::to_value(&self.history)?,
+            RecordSet::HistoryDev => serde_json::to_value(&self.history_dev)?,
+            RecordSet::Interruptions => serde_json::to_value(&self.interruptions)?,
+        };
+        let mut out: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        for day in days.as_array().into_iter().flatten() {
+            let date = day["date"].as_str().unwrap_or_default().to_string();
+            let records = day["records"].as_array().cloned().unwrap_or_default();
+            out.entry(date).or_default().extend(records);
+        }
+        Ok(out)
+    }
+
+    /// 某日的历史（`dev` 为 true 时为开发环境测试历史；不存在时按日期顺序插入空的一天），并登记该日有变化。
+    pub fn history_day_mut(&mut self, dev: bool, date: &str) -> &mut HistoryDay {
+        let set = if dev {
+            RecordSet::HistoryDev
+        } else {
+            RecordSet::History
+        };
+        self.touch_day(set, date);
+        let days = if dev {
+            &mut self.history_dev
+        } else {
+            &mut self.history
+        };
+        let index = match days.iter().position(|d| d.date == date) {
+            Some(index) => index,
+            None => {
+                let index = days
+                    .iter()
+                    .position(|d| d.date.as_str() > date)
+                    .unwrap_or(days.len());
+                days.insert(
+                    index,
+                    HistoryDay {
+                        date: date.to_string(),
+                        records: Vec::new(),
+                    },
+                );
+                index
+            }
+        };
+        &mut days[index]
+    }
+
+    /// 某日的中断记录（不存在时创建），并登记该日有变化。
+    pub fn interruption_day_mut(&mut self, date: &str) -> &mut InterruptionDay {
+        self.touch_day(RecordSet::Interruptions, date);
+        crate::interruptions::ensure_interruption_day(&mut self.interruptions, date)
+    }
+
+    /// 追加一条中断记录到当日，并登记 `InterruptionRecorded` 事件。
+    pub fn push_interruption(&mut self, date: &str, record: InterruptionRecord) {
+        self.interruption_day_mut(date).records.push(record.clone());
+        self.record_event(crate::audit::DomainEvent::InterruptionRecorded {
+            date: date.to_string(),
+            record,
+        });
+    }
+
+    /// 当前启用的会话计划（未启用、计划不存在或没有步骤时返回 `None`，计时器回退为经典循环）。
+    pub fn active_session_plan(&self) -> Option<&SessionPlan> {
+        let id = self.active_plan_id.as_deref()?;
+        self.session_plans
+            .iter()
+            .find(|p| p.id == id && !p.steps.is_empty())
+    }
+}
+
+/// 旧记录的确定性 id：对日期、开始时刻、阶段、标签与去重序号做 SHA-256，取前 16 字节的十六进制。
+fn legacy_record_id(date: &str, record: &HistoryRecord, n: u32) -> String {
+    let start = record.started_at.as_deref().unwrap_or(&record.start_time);
+    let key = format!("{date}|{start}|{:?}|{}|{n}", record.phase, record.tag);
+    Sha256::digest(key.as_bytes())[..16]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 将本地日期（YYYY-MM-DD）与时间（HH:mm）解析为本地时刻。
+///
+/// 夏令时回拨造成的重复时刻取较早者；落在跳变空档内的时刻无法表示，返回 `None`。
+pub fn local_instant(date: &str, hhmm: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    use chrono::TimeZone as _;
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+    let time = chrono::NaiveTime::parse_from_str(hhmm.trim(), "%H:%M").ok()?;
+    chrono::Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
 }
 
 /// 构建 PRD v2 内置黑名单模板列表。
@@ -554,6 +1938,165 @@ fn builtin_templates() -> Vec<BlacklistTemplate> {
 mod tests {
     use super::*;
 
+    /// `migrate_v6`：应由字符串标签生成层级标签定义，仅存在于历史的标签归档，并回填记录的 `tag_id`。
+    #[test]
+    fn migrate_v6_builds_tag_defs_and_backfills_ids() {
+        let record = |tag: &str| HistoryRecord::for_test(tag, "09:00");
+        let mut data = AppData {
+            tags: vec!["工作".to_string(), "工作 / 项目X".to_string()],
+            tag_defs: Vec::new(),
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![record("工作 / 项目X"), record("旧标签"), record("")],
+            }],
+            ..AppData::default()
+        };
+
+        assert!(data.migrate_v6());
+        assert_eq!(
+            data.tags,
+            vec!["工作".to_string(), "工作/项目X".to_string()]
+        );
+        let child = data.tag_id_by_path("工作/项目X").unwrap();
+        let parent = data.tag_id_by_path("工作").unwrap();
+        let child_def = data.tag_defs.iter().find(|t| t.id == child).unwrap();
+        assert_eq!(child_def.parent_id.as_deref(), Some(parent.as_str()));
+        let legacy = data.tag_id_by_path("旧标签").unwrap();
+        assert!(data.tag_defs.iter().any(|t| t.id == legacy && t.archived));
+
+        let records = &data.history[0].records;
+        assert_eq!(data.resolved().history[0].records[0].tag, "工作/项目X");
+        assert_eq!(records[0].tag_id.as_deref(), Some(child.as_str()));
+        assert_eq!(records[1].tag_id.as_deref(), Some(legacy.as_str()));
+        assert!(records[2].tag_id.is_none());
+
+        assert!(!data.migrate_v6());
+    }
+
+    /// `migrate_v6`：标签覆盖项、标签目标与任务按路径回填 `tag_id`，之后随标签改名解析为新路径。
+    #[test]
+    fn migrate_v6_backfills_tag_refs_in_settings_and_tasks() {
+        let mut data = AppData {
+            tags: vec!["学习".to_string()],
+            tag_defs: Vec::new(),
+            tasks: vec![Task {
+                id: "task-1".to_string(),
+                title: "背单词".to_string(),
+                tag: "学习".to_string(),
+                tag_id: None,
+                estimated_pomodoros: 1,
+                due_date: None,
+                done: false,
+            }],
+            ..AppData::default()
+        };
+        data.settings.tag_overrides.push(TagOverride {
+            tag: "学习".to_string(),
+            pomodoro: Some(40),
+            ..TagOverride::default()
+        });
+        data.settings.tag_goals.push(TagGoal {
+            tag: "学习".to_string(),
+            tag_id: None,
+            period: GoalPeriod::Weekly,
+            target: 5,
+        });
+
+        assert!(data.migrate_v6());
+        let id = data.tag_id_by_path("学习").unwrap();
+        assert_eq!(
+            data.settings.tag_overrides[0].tag_id.as_deref(),
+            Some(id.as_str())
+        );
+        assert_eq!(
+            data.settings.tag_goals[0].tag_id.as_deref(),
+            Some(id.as_str())
+        );
+        assert_eq!(data.tasks[0].tag_id.as_deref(), Some(id.as_str()));
+
+        data.tag_defs[0].name = "读书".to_string();
+        assert_eq!(data.settings_for_tag("读书").pomodoro, 40);
+        let resolved = data.resolved();
+        assert_eq!(resolved.settings.tag_overrides[0].tag, "读书");
+        assert_eq!(resolved.settings.tag_goals[0].tag, "读书");
+        assert_eq!(resolved.tasks[0].tag, "读书");
+    }
+
+    /// `migrate_v7`：缺少 id 的记录补齐确定性 id，内容相同的记录 id 不重复；已有 id 保持不变。
+    #[test]
+    fn migrate_v7_fills_stable_record_ids() {
+        let legacy = |start: &str| HistoryRecord {
+            id: String::new(),
+            ..HistoryRecord::for_test("工作", start)
+        };
+        let kept = HistoryRecord::for_test("学习", "11:00");
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![legacy("09:00"), legacy("09:00"), kept.clone()],
+            }],
+            ..AppData::default()
+        };
+        let mut copy = data.clone();
+
+        assert!(data.migrate_v7());
+        let ids: Vec<&str> = data.history[0]
+            .records
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        assert!(!ids[0].is_empty() && ids[0] != ids[1]);
+        assert_eq!(ids[2], kept.id);
+        assert!(copy.migrate_v7());
+        assert!(copy.history[0]
+            .records
+            .iter()
+            .zip(&ids)
+            .all(|(r, id)| r.id == *id));
+        assert!(!data.migrate_v7());
+    }
+
+    /// `migrate_v8`：v7 数据缺少归档字段时取默认值，无需改动。
+    #[test]
+    fn migrate_v8_defaults_archive_fields() {
+        let mut value = serde_json::to_value(AppData::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.insert("schemaVersion".to_string(), serde_json::json!(7));
+        object.remove("historyArchive");
+        object["settings"]
+            .as_object_mut()
+            .unwrap()
+            .remove("archiveAfterMonths");
+
+        let mut data: AppData = serde_json::from_value(value).unwrap();
+        assert!(!data.migrate_v8());
+        assert!(data.history_archive.before.is_none());
+        assert!(data.history_archive.days.is_empty());
+        assert_eq!(data.settings.archive_after_months, 0);
+    }
+
+    /// `Settings::for_tag`：仅覆盖已设置的字段，无覆盖的标签沿用全局设置。
+    #[test]
+    fn settings_for_tag_merges_override_fields() {
+        let settings = Settings {
+            tag_overrides: vec![TagOverride {
+                tag: "学习".to_string(),
+                pomodoro: Some(45),
+                auto_continue_enabled: Some(true),
+                ..TagOverride::default()
+            }],
+            ..Settings::default()
+        };
+
+        let merged = settings.for_tag("学习", None);
+        assert_eq!(merged.pomodoro, 45);
+        assert!(merged.auto_continue_enabled);
+        assert_eq!(merged.short_break, settings.short_break);
+        assert_eq!(merged.long_break, settings.long_break);
+        assert_eq!(settings.for_tag("工作", None).pomodoro, settings.pomodoro);
+        assert!(!settings.for_tag("工作", None).auto_continue_enabled);
+    }
+
     /// v2 迁移：当模板列表缺失时应补齐内置模板，并修复激活模板字段。
     #[test]
     fn migrate_v2_fills_templates_and_active_ids() {
@@ -586,6 +2129,172 @@ mod tests {
         assert_eq!(data.active_template_id.as_deref(), Some("deep"));
     }
 
+    /// v4 迁移：旧预设音效应回退为默认音效，累计番茄数为 0 时由历史工作记录回填。
+    #[test]
+    fn migrate_v4_resets_builtin_audio_and_backfills_total() {
+        let work = |phase: Phase| HistoryRecord::for_test("工作", "09:00").with_phase(phase);
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![
+                    work(Phase::Work),
+                    work(Phase::ShortBreak),
+                    work(Phase::Work),
+                ],
+            }],
+            ..AppData::default()
+        };
+        data.settings.audio.current_audio_id = "builtin-rain".to_string();
+
+        assert!(data.migrate_v4());
+        assert_eq!(data.settings.audio.current_audio_id, default_audio_id());
+        assert_eq!(data.total_pomodoros, 2);
+        assert!(!data.migrate_v4());
+    }
+
+    /// `DayBoundary`：日界线之前开始的记录归入前一天；日界线为 0 时按自然日。
+    #[test]
+    fn day_boundary_assigns_early_records_to_previous_day() {
+        let record = |start: &str| HistoryRecord::for_test("学习", start);
+        let boundary = Settings {
+            day_rollover_hour: 4,
+            ..Settings::default()
+        }
+        .day_boundary();
+
+        assert_eq!(
+            boundary.record_date("2025-01-02", &record("00:30")),
+            "2025-01-01"
+        );
+        assert_eq!(
+            boundary.record_date("2025-01-02", &record("04:00")),
+            "2025-01-02"
+        );
+        assert_eq!(
+            DayBoundary::default().record_date("2025-01-02", &record("00:30")),
+            "2025-01-02"
+        );
+
+        let late = chrono::NaiveDate::from_ymd_opt(2025, 1, 2)
+            .unwrap()
+            .and_hms_opt(3, 59, 0)
+            .unwrap();
+        assert_eq!(
+            boundary.logical_date(late),
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+        );
+
+        let range = DateRange {
+            from: "2025-01-01".to_string(),
+            to: "2025-01-31".to_string(),
+        };
+        assert_eq!(boundary.natural_range(&range).to, "2025-02-01");
+        assert_eq!(
+            DayBoundary::default().natural_range(&range).to,
+            "2025-01-31"
+        );
+    }
+
+    /// `DayBoundary`：周首日决定星期索引与周起点。
+    #[test]
+    fn day_boundary_respects_first_weekday() {
+        let boundary = Settings {
+            first_weekday: FirstWeekday::Sunday,
+            ..Settings::default()
+        }
+        .day_boundary();
+
+        assert_eq!(boundary.weekday_index(chrono::Weekday::Sun), 0);
+        assert_eq!(boundary.weekday_index(chrono::Weekday::Mon), 1);
+        assert_eq!(boundary.weekday_index(chrono::Weekday::Sat), 6);
+
+        // 2025-01-01 是周三，以周日为首日时所在周从 2024-12-29 开始。
+        let wed = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(
+            boundary.week_start_of(wed),
+            chrono::NaiveDate::from_ymd_opt(2024, 12, 29).unwrap()
+        );
+        assert_eq!(
+            DayBoundary::default().week_start_of(wed),
+            chrono::NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()
+        );
+    }
+
+    /// v5 迁移：历史记录按“日期 + HH:mm”回填起止时刻，跨午夜的结束时刻应落在次日。
+    #[test]
+    fn migrate_v5_backfills_history_instants_across_midnight() {
+        let record = |start: &str, end: Option<&str>| HistoryRecord {
+            end_time: end.map(str::to_string),
+            ..HistoryRecord::for_test("学习", start)
+        };
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![record("23:50", Some("00:15")), record("09:00", None)],
+            }],
+            ..AppData::default()
+        };
+
+        assert!(data.migrate_v5());
+        let records = &data.history[0].records;
+        let instant = |s: &Option<String>| {
+            chrono::DateTime::parse_from_rfc3339(s.as_deref().unwrap())
+                .unwrap()
+                .with_timezone(&chrono::Local)
+        };
+        assert_eq!(
+            instant(&records[0].started_at),
+            local_instant("2025-01-01", "23:50").unwrap()
+        );
+        assert_eq!(
+            instant(&records[0].ended_at),
+            local_instant("2025-01-02", "00:15").unwrap()
+        );
+        assert_eq!(
+            instant(&records[1].ended_at),
+            local_instant("2025-01-01", "09:25").unwrap()
+        );
+
+        // 已回填的数据再次迁移应无变化。
+        assert!(!data.migrate_v5());
+    }
+
+    /// v5 迁移：中断记录以 UTC 时间戳为结束时刻，并按已专注秒数回推开始时刻。
+    #[test]
+    fn migrate_v5_backfills_interruption_instants() {
+        let mut data = AppData {
+            interruptions: vec![InterruptionDay {
+                date: "2025-01-01".to_string(),
+                records: vec![InterruptionRecord {
+                    timestamp: "2025-01-01T01:10:00+00:00".to_string(),
+                    remaining_seconds: 900,
+                    focused_seconds: 600,
+                    reason: String::new(),
+                    r#type: InterruptionType::Reset,
+                    tag: "学习".to_string(),
+                    tag_id: None,
+                    started_at: None,
+                    ended_at: None,
+                }],
+            }],
+            ..AppData::default()
+        };
+
+        assert!(data.migrate_v5());
+        let record = &data.interruptions[0].records[0];
+        let parse = |s: &Option<String>| {
+            chrono::DateTime::parse_from_rfc3339(s.as_deref().unwrap()).unwrap()
+        };
+        assert_eq!(
+            parse(&record.ended_at),
+            parse(&Some(record.timestamp.clone()))
+        );
+        assert_eq!(
+            parse(&record.ended_at) - parse(&record.started_at),
+            chrono::Duration::seconds(600)
+        );
+    }
+
     /// `Settings::default`：默认值应符合 PRD 约定（25/5/15/4 + 目标值）。
     #[test]
     fn settings_default_matches_prd() {
@@ -5,31 +5,45 @@ use std::path::PathBuf;
 #[cfg(not(windows))]
 use tauri::Manager as _;
 
-use crate::app_data::STORE_FILE_NAME;
+use crate::app_data::{HISTORY_DB_FILE_NAME, STORE_FILE_NAME};
+use crate::data_root::RootLocator;
 use crate::errors::{AppError, AppResult};
 
-/// 获取统一入口的根目录。
+/// 获取默认模式下的根目录。
 ///
 /// - Windows：`%APPDATA%/pomodoro-technique/`
 /// - 其他平台：`app_data_dir/`（Tauri 标准应用数据目录）
 #[cfg(windows)]
-pub fn app_root_dir<R: tauri::Runtime>(_app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
+pub fn default_root_dir<R: tauri::Runtime>(_app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
     let appdata = std::env::var("APPDATA")
         .map_err(|_| AppError::Invariant("无法读取环境变量 APPDATA".to_string()))?;
     Ok(PathBuf::from(appdata).join("pomodoro-technique"))
 }
 
-/// 获取统一入口的根目录。
+/// 获取默认模式下的根目录。
 ///
 /// - Windows：`%APPDATA%/pomodoro-technique/`
 /// - 其他平台：`app_data_dir/`（Tauri 标准应用数据目录）
 #[cfg(not(windows))]
-pub fn app_root_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
+pub fn default_root_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
     app.path()
         .app_data_dir()
         .map_err(|_| AppError::Invariant("无法解析应用数据目录（app_data_dir）".to_string()))
 }
 
+/// 获取根目录定位器（默认目录 + 可执行文件目录，用于便携模式与自定义目录）。
+pub fn root_locator<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<RootLocator> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(std::path::Path::to_path_buf));
+    Ok(RootLocator::new(default_root_dir(app)?, exe_dir))
+}
+
+/// 获取统一入口的根目录（便携模式、自定义目录或默认目录，见 `data_root`）。
+pub fn app_root_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
+    Ok(root_locator(app)?.root())
+}
+
 /// 获取应用数据目录（位于统一入口根目录下的 `data/`）。
 pub fn app_data_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
     Ok(app_root_dir(app)?.join("data"))
@@ -40,11 +54,59 @@ pub fn store_file_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResul
     Ok(app_data_dir(app)?.join(STORE_FILE_NAME))
 }
 
+/// 获取某个配置的数据目录（store、历史数据库与加密配置文件所在目录）。
+pub fn profile_data_dir<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profile_id: &str,
+) -> AppResult<PathBuf> {
+    Ok(crate::profiles::profile_dir(
+        &app_data_dir(app)?,
+        profile_id,
+    ))
+}
+
+/// 获取某个配置的 store 文件路径（默认配置即 `store_file_path`）。
+pub fn profile_store_file_path<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profile_id: &str,
+) -> AppResult<PathBuf> {
+    Ok(profile_data_dir(app, profile_id)?.join(STORE_FILE_NAME))
+}
+
+/// 获取某个配置的历史数据库路径（与该配置的 store 文件位于同一目录）。
+pub fn profile_history_db_path<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profile_id: &str,
+) -> AppResult<PathBuf> {
+    Ok(profile_data_dir(app, profile_id)?.join(HISTORY_DB_FILE_NAME))
+}
+
 /// 获取应用日志目录（位于统一入口根目录下的 `logs/`）。
 pub fn app_log_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
     Ok(app_root_dir(app)?.join("logs"))
 }
 
+/// 获取备份目录（位于统一入口根目录下的 `backups/`）。
+pub fn app_backup_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
+    Ok(app_root_dir(app)?.join("backups"))
+}
+
+/// 获取某个配置的备份目录（默认配置即 `app_backup_dir`，其余位于 `backups/profiles/<id>/`）。
+pub fn profile_backup_dir<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    profile_id: &str,
+) -> AppResult<PathBuf> {
+    Ok(crate::profiles::profile_backup_dir(
+        &app_backup_dir(app)?,
+        profile_id,
+    ))
+}
+
+/// 获取隔离目录（位于统一入口根目录下的 `quarantine/`，存放无法完整解析的 store 副本）。
+pub fn app_quarantine_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<PathBuf> {
+    Ok(app_root_dir(app)?.join("quarantine"))
+}
+
 /// 获取音频目录（位于统一入口根目录下的 `audio/`）。
 ///
 /// PRD v4：自定义音频导入后会被复制到该目录下。
@@ -68,7 +130,7 @@ mod tests {
         assert_eq!(out, expected);
     }
 
-    /// `app_data_dir/store_file_path/app_log_dir`：应在统一入口根目录下拼接子路径。
+    /// `app_data_dir/store_file_path/app_log_dir/profile_*`：应在统一入口根目录下拼接子路径。
     #[test]
     fn derived_paths_join_under_root() {
         let app = tauri::test::mock_app();
@@ -79,7 +141,31 @@ mod tests {
             store_file_path(app.handle()).unwrap(),
             root.join("data").join(STORE_FILE_NAME)
         );
+        assert_eq!(
+            profile_history_db_path(app.handle(), crate::profiles::DEFAULT_PROFILE_ID).unwrap(),
+            root.join("data").join(HISTORY_DB_FILE_NAME)
+        );
         assert_eq!(app_log_dir(app.handle()).unwrap(), root.join("logs"));
         assert_eq!(app_audio_dir(app.handle()).unwrap(), root.join("audio"));
+        assert_eq!(app_backup_dir(app.handle()).unwrap(), root.join("backups"));
+        assert_eq!(
+            app_quarantine_dir(app.handle()).unwrap(),
+            root.join("quarantine")
+        );
+        assert_eq!(
+            profile_store_file_path(app.handle(), crate::profiles::DEFAULT_PROFILE_ID).unwrap(),
+            store_file_path(app.handle()).unwrap()
+        );
+        assert_eq!(
+            profile_history_db_path(app.handle(), "p1").unwrap(),
+            root.join("data")
+                .join("profiles")
+                .join("p1")
+                .join(HISTORY_DB_FILE_NAME)
+        );
+        assert_eq!(
+            profile_backup_dir(app.handle(), "p1").unwrap(),
+            root.join("backups").join("profiles").join("p1")
+        );
     }
 }
@@ -0,0 +1,752 @@
+//! 历史归档：按保留策略把早于 N 个月的历史移入按年压缩的归档文件 `archive/history-YYYY.json.gz`。
+//!
+//! 主存储（store 与历史数据库）只保留归档线与按日/按标签的汇总（`AppData.history_archive`），
+//! 统计、目标与连续达标直接使用汇总；历史查询、专注分析与导出按需读取范围内的归档文件并与数据库结果合并。
+//! 归档后补录到归档线之前的记录先留在数据库中，下次归档时并入文件（同一条记录不会重复）。
+//! 启用数据加密时文件内容为压缩数据的 `{"sealed": ...}` 密文（文件名不变）。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read as _, Write as _};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::app_data::{
+    current_tag_path, AppData, ArchivedDay, ArchivedTagTotal, DayBoundary, HistoryDay,
+    HistoryRecord, Phase, RecordSet, Section,
+};
+use crate::crypto::Cipher;
+use crate::errors::{AppError, AppResult};
+
+/// 归档目录名（位于配置数据目录）。
+pub const ARCHIVE_DIR_NAME: &str = "archive";
+
+/// 归档文件格式版本（用于后续演进时识别旧格式）。
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// 归档文件名前缀。
+const FILE_PREFIX: &str = "history-";
+
+/// 归档文件名后缀。
+const FILE_SUFFIX: &str = ".json.gz";
+
+/// gzip 数据的文件头（未加密的归档文件以此开头）。
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 归档文件内容（解压后）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveFile {
+    /// 归档文件格式版本。
+    version: u32,
+    /// 该年已归档的历史（按日期升序）。
+    days: Vec<HistoryDay>,
+}
+
+/// 一次归档的结果。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ArchiveReport {
+    /// 归档后的归档线（YYYY-MM-DD）。
+    pub before: Option<String>,
+    /// 本次移入归档文件的天数。
+    pub archived_days: u32,
+    /// 本次移入归档文件的记录数。
+    pub archived_records: u32,
+}
+
+/// 一个归档文件的概况。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ArchiveFileInfo {
+    /// 年份（YYYY）。
+    pub year: String,
+    /// 文件大小（字节）。
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+/// 归档状态（用于设置页展示）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ArchiveStatus {
+    /// 历史保留月数（0 表示不归档）。
+    pub after_months: u32,
+    /// 当前归档线（从未归档时为 `null`）。
+    pub before: Option<String>,
+    /// 已归档的天数（按汇总）。
+    pub archived_days: u32,
+    /// 已归档的番茄数（按汇总）。
+    pub archived_pomodoros: u32,
+    /// 归档文件（按年份升序）。
+    pub files: Vec<ArchiveFileInfo>,
+}
+
+/// 配置数据目录下的归档目录。
+pub fn archive_dir(profile_dir: &Path) -> PathBuf {
+    profile_dir.join(ARCHIVE_DIR_NAME)
+}
+
+/// 按保留月数计算归档线：`today` 往前 `months` 个月（0 表示不归档）。
+pub fn cutoff(today: NaiveDate, months: u32) -> Option<String> {
+    if months == 0 {
+        return None;
+    }
+    today
+        .checked_sub_months(chrono::Months::new(months))
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// 是否有需要移入归档文件的历史（自然日期早于 `before` 的非空天）。
+pub fn has_due(data: &AppData, before: &str) -> bool {
+    data.history
+        .iter()
+        .any(|d| is_due(&d.date, before) && !d.records.is_empty())
+}
+
+/// 把自然日期早于 `before` 的历史移入归档文件，并由全部归档文件重建汇总（归档线只前移不后退）。
+///
+/// 先写文件再修改数据：写入失败时数据保持不变。
+pub fn archive(
+    dir: &Path,
+    data: &mut AppData,
+    before: &str,
+    cipher: Option<&Cipher>,
+) -> AppResult<ArchiveReport> {
+    let before = match data.history_archive.before.as_deref() {
+        Some(current) if current > before => current.to_string(),
+        _ => before.to_string(),
+    };
+    data.touch_section(Section::Archive);
+
+    let mut by_year: BTreeMap<&str, Vec<HistoryDay>> = BTreeMap::new();
+    let mut archived_days = 0u32;
+    let mut archived_records = 0u32;
+    for day in data
+        .history
+        .iter()
+        .filter(|d| is_due(&d.date, &before) && !d.records.is_empty())
+    {
+        archived_days += 1;
+        archived_records += day.records.len() as u32;
+        by_year.entry(&day.date[..4]).or_default().push(day.clone());
+    }
+
+    if !by_year.is_empty() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::Invariant(format!("创建归档目录失败：{e}")))?;
+        for (year, days) in by_year {
+            let mut merged = read_year(dir, year, cipher)?;
+            merge_days(&mut merged, days)?;
+            write_year(dir, year, merged, cipher)?;
+        }
+        let due: Vec<String> = data
+            .history
+            .iter()
+            .filter(|d| is_due(&d.date, &before))
+            .map(|d| d.date.clone())
+            .collect();
+        for date in &due {
+            data.touch_day(RecordSet::History, date);
+        }
+        data.history.retain(|d| !is_due(&d.date, &before));
+        data.record_days_replaced(due.into_iter().map(|date| (RecordSet::History, date)));
+        data.history_archive.days = summarize(
+            &read_all(dir, cipher)?,
+            data.settings.day_boundary(),
+            &data.tag_paths(),
+        );
+    }
+    data.history_archive.before = Some(before.clone());
+    tracing::info!(
+        target: "storage",
+        "历史归档完成：before={} days={} records={}",
+        before,
+        archived_days,
+        archived_records
+    );
+    Ok(ArchiveReport {
+        before: Some(before),
+        archived_days,
+        archived_records,
+    })
+}
+
+/// 将范围 `[from, to]`（自然日期）内的归档历史并入数据库查询结果（按日期升序返回）。
+///
+/// 与数据库中相同的记录只保留一份（标签路径由调用方按 `tag_id` 解析）。
+pub fn include_archived(
+    dir: &Path,
+    cipher: Option<&Cipher>,
+    before: Option<&str>,
+    from: &str,
+    to: &str,
+    days: Vec<HistoryDay>,
+) -> AppResult<Vec<HistoryDay>> {
+    let Some(before) = before.filter(|before| from < *before) else {
+        return Ok(days);
+    };
+    let (first_year, last_year) = (from.get(..4).unwrap_or(from), to.get(..4).unwrap_or(to));
+    let mut out: Vec<HistoryDay> = Vec::new();
+    for (year, _) in list_files(dir)?
+        .into_iter()
+        .filter(|(year, _)| year.as_str() >= first_year && year.as_str() <= last_year)
+    {
+        out.extend(read_year(dir, &year, cipher)?.into_iter().filter(|d| {
+            d.date.as_str() >= from && d.date.as_str() <= to && d.date.as_str() < before
+        }));
+    }
+    merge_days(&mut out, days)?;
+    Ok(out)
+}
+
+/// 查询归档状态。
+pub fn status(dir: &Path, data: &AppData) -> AppResult<ArchiveStatus> {
+    let files = list_files(dir)?
+        .into_iter()
+        .map(|(year, path)| {
+            let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            ArchiveFileInfo { year, size_bytes }
+        })
+        .collect();
+    let archive = &data.history_archive;
+    Ok(ArchiveStatus {
+        after_months: data.settings.archive_after_months,
+        before: archive.before.clone(),
+        archived_days: archive.days.len() as u32,
+        archived_pomodoros: archive
+            .days
+            .iter()
+            .flat_map(|d| d.by_tag.iter())
+            .map(|t| t.count)
+            .sum(),
+        files,
+    })
+}
+
+/// 以 `days` 整体替换归档文件（恢复备份时使用）：按年写入，删除 `days` 中没有的年份的文件。
+pub fn replace_all(dir: &Path, days: Vec<HistoryDay>, cipher: Option<&Cipher>) -> AppResult<()> {
+    let mut by_year: BTreeMap<String, Vec<HistoryDay>> = BTreeMap::new();
+    for day in days {
+        if let Some(year) = day.date.get(..4) {
+            by_year.entry(year.to_string()).or_default().push(day);
+        }
+    }
+    if !by_year.is_empty() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::Invariant(format!("创建归档目录失败：{e}")))?;
+    }
+    let stale: Vec<PathBuf> = list_files(dir)?
+        .into_iter()
+        .filter(|(year, _)| !by_year.contains_key(year))
+        .map(|(_, path)| path)
+        .collect();
+    let written = by_year.len();
+    for (year, days) in by_year {
+        write_year(dir, &year, days, cipher)?;
+    }
+    for path in stale {
+        std::fs::remove_file(&path)
+            .map_err(|e| AppError::Invariant(format!("删除归档文件失败：{e}")))?;
+    }
+    tracing::info!(target: "storage", "已恢复归档文件：files={}", written);
+    Ok(())
+}
+
+/// 归档汇总引用、但归档目录中没有对应文件的年份（按升序）。
+///
+/// 汇总按逻辑日期记录，日界线晚于零点时元旦凌晨的记录归属上一年，因此次年的文件也算存在。
+pub fn missing_years(dir: &Path, data: &AppData) -> AppResult<Vec<String>> {
+    let files: BTreeSet<String> = list_files(dir)?.into_iter().map(|(year, _)| year).collect();
+    let mut missing = BTreeSet::new();
+    for year in data
+        .history_archive
+        .days
+        .iter()
+        .filter_map(|d| d.date.get(..4))
+    {
+        let next = year.parse::<u32>().map(|y| (y + 1).to_string());
+        if !files.contains(year) && !next.is_ok_and(|next| files.contains(&next)) {
+            missing.insert(year.to_string());
+        }
+    }
+    Ok(missing.into_iter().collect())
+}
+
+/// 以新密钥（或明文）重写全部归档文件，返回重写的文件数（无法解密的文件保持原样）。
+pub fn reseal(dir: &Path, old: Option<&Cipher>, new: Option<&Cipher>) -> AppResult<usize> {
+    let mut rewritten = 0usize;
+    for (year, path) in list_files(dir)? {
+        let bytes = std::fs::read(&path)
+            .map_err(|e| AppError::Invariant(format!("读取归档文件失败：{e}")))?;
+        if bytes.starts_with(&GZIP_MAGIC) && new.is_none() {
+            continue;
+        }
+        let file = match decode(&bytes, old) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!(target: "storage", "归档文件无法解密，保持原样：year={} err={}", year, e);
+                continue;
+            }
+        };
+        write_atomically(&path, &encode(&file, new)?)?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
+
+/// 后台归档任务：启动后稍候执行一次，之后每 6 小时按保留策略检查一次。
+#[cfg(not(test))]
+pub fn spawn_archive_task(app: tauri::AppHandle) {
+    use tauri::Manager as _;
+
+    tauri::async_runtime::spawn(async move {
+        let mut delay = std::time::Duration::from_secs(60);
+        loop {
+            tokio::time::sleep(delay).await;
+            delay = std::time::Duration::from_secs(6 * 3600);
+            let state = app.state::<crate::state::AppState>();
+            if state.settings_snapshot().archive_after_months == 0 {
+                continue;
+            }
+            let result = crate::app_paths::profile_data_dir(&app, &state.active_profile().id)
+                .and_then(|dir| crate::commands::archive::archive_history_impl(&*state, &dir));
+            if let Err(e) = result {
+                tracing::warn!(target: "storage", "定时归档失败：{e}");
+            }
+        }
+    });
+}
+
+/// 自然日期是否早于归档线（日期不合法的天不归档，留给完整性检查处理）。
+fn is_due(date: &str, before: &str) -> bool {
+    date < before && NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+/// 某天按（标签路径, 标签 id）分组的（番茄数, 分钟数）。
+type TagTotals = BTreeMap<(String, Option<String>), (u32, u32)>;
+
+/// 由归档历史生成逐日汇总（工作记录按当前日界线归属逻辑日期，按当前标签路径 + id 分组）。
+fn summarize(
+    days: &[HistoryDay],
+    boundary: DayBoundary,
+    paths: &BTreeMap<String, String>,
+) -> Vec<ArchivedDay> {
+    let mut totals: BTreeMap<String, TagTotals> = BTreeMap::new();
+    for day in days {
+        for r in day.records.iter().filter(|r| r.phase == Phase::Work) {
+            let entry = totals
+                .entry(boundary.record_date(&day.date, r))
+                .or_default()
+                .entry((
+                    current_tag_path(paths, &r.tag, r.tag_id.as_deref()).to_string(),
+                    r.tag_id.clone(),
+                ))
+                .or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 += r.duration;
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(date, by_tag)| ArchivedDay {
+            date,
+            by_tag: by_tag
+                .into_iter()
+                .map(|((tag, tag_id), (count, minutes))| ArchivedTagTotal {
+                    tag,
+                    tag_id,
+                    count,
+                    minutes,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// 将 `incoming` 并入 `target`：同日期的记录按 `record_key` 取并集（已有的记录优先），结果按日期升序。
+fn merge_days(target: &mut Vec<HistoryDay>, incoming: Vec<HistoryDay>) -> AppResult<()> {
+    for day in incoming {
+        let Some(existing) = target.iter_mut().find(|d| d.date == day.date) else {
+            target.push(day);
+            continue;
+        };
+        let mut seen = existing
+            .records
+            .iter()
+            .map(record_key)
+            .collect::<AppResult<BTreeSet<String>>>()?;
+        for record in day.records {
+            if seen.insert(record_key(&record)?) {
+                existing.records.push(record);
+            }
+        }
+    }
+    target.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(())
+}
+
+/// 记录的去重键：稳定的记录 id（缺少 id 的旧记录退回为完整内容）。
+fn record_key(record: &HistoryRecord) -> AppResult<String> {
+    if record.id.is_empty() {
+        Ok(serde_json::to_string(record)?)
+    } else {
+        Ok(record.id.clone())
+    }
+}
+
+/// 列出归档目录下的归档文件：`(年份, 路径)`，按年份升序（目录不存在时为空）。
+fn list_files(dir: &Path) -> AppResult<Vec<(String, PathBuf)>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::Invariant(format!("读取归档目录失败：{e}"))),
+    };
+    let mut out: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let year = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+            (year.len() == 4 && year.chars().all(|c| c.is_ascii_digit()))
+                .then(|| (year.to_string(), path.clone()))
+        })
+        .collect();
+    out.sort();
+    Ok(out)
+}
+
+/// 某年归档文件的路径。
+fn year_path(dir: &Path, year: &str) -> PathBuf {
+    dir.join(format!("{FILE_PREFIX}{year}{FILE_SUFFIX}"))
+}
+
+/// 读取某年的归档历史（文件不存在时为空）。
+fn read_year(dir: &Path, year: &str, cipher: Option<&Cipher>) -> AppResult<Vec<HistoryDay>> {
+    let bytes = match std::fs::read(year_path(dir, year)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::Invariant(format!("读取归档文件失败：{e}"))),
+    };
+    Ok(decode(&bytes, cipher)?.days)
+}
+
+/// 读取全部归档历史（按日期升序；目录不存在时为空）。
+pub fn read_all(dir: &Path, cipher: Option<&Cipher>) -> AppResult<Vec<HistoryDay>> {
+    let mut out = Vec::new();
+    for (year, _) in list_files(dir)? {
+        out.extend(read_year(dir, &year, cipher)?);
+    }
+    Ok(out)
+}
+
+/// 写入某年的归档历史（先写临时文件再重命名）。
+fn write_year(
+    dir: &Path,
+    year: &str,
+    days: Vec<HistoryDay>,
+    cipher: Option<&Cipher>,
+) -> AppResult<()> {
+    let file = ArchiveFile {
+        version: ARCHIVE_FORMAT_VERSION,
+        days,
+    };
+    write_atomically(&year_path(dir, year), &encode(&file, cipher)?)
+}
+
+/// 序列化并压缩归档文件；传入密钥时再加密为 `{"sealed": ...}`。
+fn encode(file: &ArchiveFile, cipher: Option<&Cipher>) -> AppResult<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&serde_json::to_vec(file)?)
+        .map_err(|e| AppError::Invariant(format!("压缩归档文件失败：{e}")))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| AppError::Invariant(format!("压缩归档文件失败：{e}")))?;
+    match cipher {
+        Some(cipher) => Ok(serde_json::to_vec(&cipher.seal_bytes(&compressed))?),
+        None => Ok(compressed),
+    }
+}
+
+/// 解密（如需）并解压归档文件；已加密但未解锁时返回 `AppError::Locked`。
+fn decode(bytes: &[u8], cipher: Option<&Cipher>) -> AppResult<ArchiveFile> {
+    let compressed = if bytes.starts_with(&GZIP_MAGIC) {
+        bytes.to_vec()
+    } else {
+        let value: Value = serde_json::from_slice(bytes)?;
+        let blob = crate::crypto::sealed_blob(&value)
+            .ok_or_else(|| AppError::Invariant("归档文件格式不正确".to_string()))?;
+        cipher.ok_or(AppError::Locked)?.open(&blob)?
+    };
+    let mut json = Vec::new();
+    flate2::read::GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| AppError::Invariant(format!("解压归档文件失败：{e}")))?;
+    let file: ArchiveFile = serde_json::from_slice(&json)?;
+    if file.version > ARCHIVE_FORMAT_VERSION {
+        return Err(AppError::Validation(
+            "归档文件来自更新版本的应用，无法读取".to_string(),
+        ));
+    }
+    Ok(file)
+}
+
+/// 先写临时文件再重命名，避免留下半个文件。
+fn write_atomically(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, bytes)
+        .map_err(|e| AppError::Invariant(format!("写入归档文件失败：{e}")))?;
+    std::fs::rename(&tmp, path).map_err(|e| AppError::Invariant(format!("保存归档文件失败：{e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_data::HistoryRecord;
+
+    /// 构造一条记录（`tag` 为默认标签之一，`tag_id` 与之对应）。
+    fn record(tag: &str, start: &str, phase: Phase) -> HistoryRecord {
+        HistoryRecord::for_test(tag, start)
+            .with_phase(phase)
+            .with_tag_id(AppData::default().tag_id_by_path(tag))
+    }
+
+    /// 构造跨两年的历史数据。
+    fn sample() -> AppData {
+        AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2023-12-31".to_string(),
+                    records: vec![record("工作", "09:00", Phase::Work)],
+                },
+                HistoryDay {
+                    date: "2024-01-02".to_string(),
+                    records: vec![
+                        record("学习", "09:00", Phase::Work),
+                        record("学习", "09:25", Phase::ShortBreak),
+                        record("工作", "10:00", Phase::Work),
+                    ],
+                },
+                HistoryDay {
+                    date: "2024-06-01".to_string(),
+                    records: vec![record("工作", "09:00", Phase::Work)],
+                },
+            ],
+            ..AppData::default()
+        }
+    }
+
+    /// 归档：早于归档线的天按年写入压缩文件并生成汇总，查询时透明合并且不重复。
+    #[test]
+    fn archive_moves_old_days_and_queries_merge_them_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = sample();
+        let mut data = original.clone();
+        let report = archive(dir.path(), &mut data, "2024-03-01", None).unwrap();
+        assert_eq!(report.archived_days, 2);
+        assert_eq!(report.archived_records, 4);
+        assert_eq!(data.history.len(), 1);
+        assert_eq!(data.history_archive.before.as_deref(), Some("2024-03-01"));
+        assert!(year_path(dir.path(), "2023").exists());
+        assert!(year_path(dir.path(), "2024").exists());
+        assert_eq!(
+            data.history_archive.days,
+            vec![
+                ArchivedDay {
+                    date: "2023-12-31".to_string(),
+                    by_tag: vec![ArchivedTagTotal {
+                        tag: "工作".to_string(),
+                        tag_id: Some("tag-1".to_string()),
+                        count: 1,
+                        minutes: 25,
+                    }],
+                },
+                ArchivedDay {
+                    date: "2024-01-02".to_string(),
+                    by_tag: vec![
+                        ArchivedTagTotal {
+                            tag: "学习".to_string(),
+                            tag_id: Some("tag-2".to_string()),
+                            count: 1,
+                            minutes: 25,
+                        },
+                        ArchivedTagTotal {
+                            tag: "工作".to_string(),
+                            tag_id: Some("tag-1".to_string()),
+                            count: 1,
+                            minutes: 25,
+                        },
+                    ],
+                },
+            ]
+        );
+
+        // 查询跨越归档线：归档部分与数据库部分合并，数据库中重复的记录只保留一份。
+        let db_days = vec![original.history[1].clone(), data.history[0].clone()];
+        let out = include_archived(
+            dir.path(),
+            None,
+            data.history_archive.before.as_deref(),
+            "2023-01-01",
+            "2024-12-31",
+            db_days,
+        )
+        .unwrap();
+        let dates: Vec<&str> = out.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, vec!["2023-12-31", "2024-01-02", "2024-06-01"]);
+        assert_eq!(out[1].records.len(), 3);
+
+        // 范围不涉及归档时不读取文件。
+        let out = include_archived(
+            dir.path(),
+            None,
+            Some("2024-03-01"),
+            "2024-03-01",
+            "2024-12-31",
+            Vec::new(),
+        )
+        .unwrap();
+        assert!(out.is_empty());
+
+        // 再次归档（归档线不后退）：补录的旧记录并入已有文件。
+        data.history.push(HistoryDay {
+            date: "2024-01-02".to_string(),
+            records: vec![record("阅读", "20:00", Phase::Work)],
+        });
+        let report = archive(dir.path(), &mut data, "2024-02-01", None).unwrap();
+        assert_eq!(report.before.as_deref(), Some("2024-03-01"));
+        assert_eq!(report.archived_records, 1);
+        assert_eq!(
+            read_year(dir.path(), "2024", None).unwrap()[0]
+                .records
+                .len(),
+            4
+        );
+        assert_eq!(data.history_archive.days[1].by_tag.len(), 3);
+    }
+
+    /// 归档文件与汇总保留归档时的标签路径，读取时按当前标签定义解析。
+    #[test]
+    fn archived_tags_follow_renames() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = sample();
+        archive(dir.path(), &mut data, "2024-01-01", None).unwrap();
+        data.tag_defs[0].name = "上班".to_string();
+        data.sync_tags();
+        assert_eq!(data.history_archive.days[0].by_tag[0].tag, "工作");
+        assert_eq!(
+            data.resolved().history_archive.days[0].by_tag[0].tag,
+            "上班"
+        );
+
+        let mut out = include_archived(
+            dir.path(),
+            None,
+            data.history_archive.before.as_deref(),
+            "2023-12-31",
+            "2023-12-31",
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(out[0].records[0].tag, "工作");
+        crate::app_data::resolve_history_tags(&mut out, &data.tag_paths());
+        assert_eq!(out[0].records[0].tag, "上班");
+    }
+
+    /// 加密：归档文件为密文，未解锁不可读；更换/关闭加密后重写为新状态。
+    #[test]
+    fn encrypted_archives_reseal() {
+        let dir = tempfile::tempdir().unwrap();
+        let cipher = Cipher::generate("first-pass").unwrap();
+        let mut data = sample();
+        archive(dir.path(), &mut data, "2024-01-01", Some(&cipher)).unwrap();
+        let bytes = std::fs::read(year_path(dir.path(), "2023")).unwrap();
+        assert!(!bytes.starts_with(&GZIP_MAGIC));
+        assert!(matches!(
+            read_year(dir.path(), "2023", None),
+            Err(AppError::Locked)
+        ));
+        assert_eq!(
+            read_year(dir.path(), "2023", Some(&cipher)).unwrap().len(),
+            1
+        );
+
+        assert_eq!(reseal(dir.path(), Some(&cipher), None).unwrap(), 1);
+        assert_eq!(read_year(dir.path(), "2023", None).unwrap().len(), 1);
+        assert_eq!(reseal(dir.path(), None, None).unwrap(), 0);
+    }
+
+    /// 合并按记录 id 去重：内容相同但 id 不同的两条都保留，同一 id 的记录即使内容改过也只保留已有的一份。
+    #[test]
+    fn merge_days_dedupes_by_record_id() {
+        let first = record("工作", "09:00", Phase::Work);
+        let twin = HistoryRecord {
+            id: "twin".to_string(),
+            ..first.clone()
+        };
+        let edited = HistoryRecord {
+            remark: "补充".to_string(),
+            ..first.clone()
+        };
+        let mut days = vec![HistoryDay {
+            date: "2024-01-02".to_string(),
+            records: vec![first.clone()],
+        }];
+        merge_days(
+            &mut days,
+            vec![HistoryDay {
+                date: "2024-01-02".to_string(),
+                records: vec![twin, edited],
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            days[0]
+                .records
+                .iter()
+                .map(|r| (r.id.as_str(), r.remark.as_str()))
+                .collect::<Vec<_>>(),
+            [(first.id.as_str(), ""), ("twin", "")]
+        );
+    }
+
+    /// 恢复归档：按年写回并删除多余年份；汇总引用的年份及次年都没有文件时列出。
+    #[test]
+    fn replace_all_and_missing_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = sample();
+        archive(dir.path(), &mut data, "2024-01-01", None).unwrap();
+        assert!(missing_years(dir.path(), &data).unwrap().is_empty());
+
+        replace_all(dir.path(), sample().history.split_off(1), None).unwrap();
+        assert_eq!(
+            list_files(dir.path())
+                .unwrap()
+                .into_iter()
+                .map(|(year, _)| year)
+                .collect::<Vec<_>>(),
+            ["2024"]
+        );
+        assert!(missing_years(dir.path(), &data).unwrap().is_empty());
+
+        replace_all(dir.path(), Vec::new(), None).unwrap();
+        assert!(list_files(dir.path()).unwrap().is_empty());
+        assert_eq!(missing_years(dir.path(), &data).unwrap(), ["2023"]);
+    }
+
+    /// 归档线：按保留月数向前推算，0 表示不归档。
+    #[test]
+    fn cutoff_subtracts_months() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        assert_eq!(cutoff(today, 0), None);
+        assert_eq!(cutoff(today, 1).as_deref(), Some("2025-02-28"));
+        assert_eq!(cutoff(today, 12).as_deref(), Some("2024-03-31"));
+    }
+}
@@ -0,0 +1,657 @@
+//! 审计日志：命令修改数据时登记带类型的领域事件，持久化时随记录一起追加到事件日志。
+//!
+//! 日志以基线开始：基线引用一份完整数据快照（首次审计，以及恢复备份、迁移等整体替换数据时写入），
+//! 只保留最近 `BASELINE_LIMIT` 个基线及其之后的事件。
+//! 历史记录事件按记录 id 定位；字段分组（设置、黑名单、标签等）以修改后的值记录，
+//! 因此从最近一次基线按顺序重放其后的事件即可重建出与实际一致的 `AppData`。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::app_data::{
+    AppData, BlacklistItem, HistoryDay, HistoryRecord, InterruptionDay, InterruptionRecord,
+    PendingChanges, Phase, Section, Settings,
+};
+use crate::errors::{AppError, AppResult};
+
+/// 审计日志保留的基线数：追加新基线时删除更早的基线、快照及其之前的事件。
+pub const BASELINE_LIMIT: usize = 5;
+
+/// 记录字段（重建校验时按日期归并后比较）。
+pub(crate) const RECORD_SECTIONS: [&str; 3] = ["history", "historyDev", "interruptions"];
+
+/// 领域事件（按发生顺序追加到事件日志）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum DomainEvent {
+    /// 基线：引用一份完整数据快照（`snapshot` 为快照 id），重放从最近一次基线开始。
+    Baseline { snapshot: i64 },
+    /// 计时器完成一个阶段，在当日末尾追加一条历史记录。
+    PomodoroCompleted { date: String, record: HistoryRecord },
+    /// 手动补录一条历史记录。
+    RecordAdded {
+        dev: bool,
+        date: String,
+        record: HistoryRecord,
+    },
+    /// 编辑一条历史记录（`from` 为编辑前的日期；记录 id 不变）。
+    RecordUpdated {
+        dev: bool,
+        from: String,
+        date: String,
+        record: HistoryRecord,
+    },
+    /// 删除一条历史记录。
+    RecordDeleted {
+        dev: bool,
+        date: String,
+        record_id: String,
+    },
+    /// 编辑历史记录备注。
+    RemarkEdited {
+        dev: bool,
+        date: String,
+        record_id: String,
+        from: String,
+        to: String,
+    },
+    /// 重命名标签（仅用于变更历史展示；标签定义的变化由随后的字段分组事件记录，设置与任务按标签 id 引用无需改写）。
+    TagRenamed {
+        tag_id: String,
+        from: String,
+        to: String,
+    },
+    /// 黑名单变更（记录变更后的完整列表）。
+    BlacklistChanged { blacklist: Vec<BlacklistItem> },
+    /// 设置更新（记录变更后的完整设置）。
+    SettingsUpdated { settings: Box<Settings> },
+    /// 记录一次中断。
+    InterruptionRecorded {
+        date: String,
+        record: InterruptionRecord,
+    },
+    /// 某日历史整体变更（导入、删除标签、归档等批量修改；空列表表示删除该日）。
+    HistoryDayReplaced {
+        dev: bool,
+        date: String,
+        records: Vec<HistoryRecord>,
+    },
+    /// 某日中断记录整体变更（空列表表示删除该日）。
+    InterruptionDayReplaced {
+        date: String,
+        records: Vec<InterruptionRecord>,
+    },
+    /// 其余字段整段替换（任务、模板、计划、统计计数等）。
+    SectionReplaced { section: String, value: Value },
+}
+
+/// 变更历史中的一条事件（供前端展示）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// 事件序号（日志内单调递增）。
+    pub seq: u32,
+    /// 记录时刻（RFC 3339）。
+    pub at: String,
+    /// 事件类型（与 `DomainEvent` 的 `type` 一致）。
+    pub kind: String,
+    /// 事件主体（如 `history:<记录 id>`、`tag:<id>`、`settings`）。
+    pub subject: String,
+    /// 中文摘要。
+    pub summary: String,
+}
+
+/// 按事件日志重放的校验结果。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ReplayReport {
+    /// 重放的事件数量（从最近一次基线起，含基线）。
+    pub events: u32,
+    /// 重放结果是否与当前数据一致。
+    pub matches: bool,
+    /// 不一致的字段（`AppData` 的 camelCase 字段名）。
+    pub differing_sections: Vec<String>,
+    /// 是否已用重放结果替换当前数据。
+    pub committed: bool,
+}
+
+impl DomainEvent {
+    /// 事件类型名（与序列化的 `type` 一致）。
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Baseline { .. } => "baseline",
+            Self::PomodoroCompleted { .. } => "pomodoroCompleted",
+            Self::RecordAdded { .. } => "recordAdded",
+            Self::RecordUpdated { .. } => "recordUpdated",
+            Self::RecordDeleted { .. } => "recordDeleted",
+            Self::RemarkEdited { .. } => "remarkEdited",
+            Self::TagRenamed { .. } => "tagRenamed",
+            Self::BlacklistChanged { .. } => "blacklistChanged",
+            Self::SettingsUpdated { .. } => "settingsUpdated",
+            Self::InterruptionRecorded { .. } => "interruptionRecorded",
+            Self::HistoryDayReplaced { .. } => "historyDayReplaced",
+            Self::InterruptionDayReplaced { .. } => "interruptionDayReplaced",
+            Self::SectionReplaced { .. } => "sectionReplaced",
+        }
+    }
+
+    /// 事件主体：单条历史记录为 `history:<记录 id>`，整日为 `history:<日期>`，其余见各分支。
+    pub fn subject(&self) -> String {
+        match self {
+            Self::Baseline { .. } => "baseline".to_string(),
+            Self::PomodoroCompleted { record, .. }
+            | Self::RecordAdded { record, .. }
+            | Self::RecordUpdated { record, .. } => format!("history:{}", record.id),
+            Self::RecordDeleted { record_id, .. } | Self::RemarkEdited { record_id, .. } => {
+                format!("history:{record_id}")
+            }
+            Self::TagRenamed { tag_id, .. } => format!("tag:{tag_id}"),
+            Self::BlacklistChanged { .. } => "blacklist".to_string(),
+            Self::SettingsUpdated { .. } => "settings".to_string(),
+            Self::InterruptionRecorded { date, .. }
+            | Self::InterruptionDayReplaced { date, .. } => format!("interruption:{date}"),
+            Self::HistoryDayReplaced { dev, date, .. } => {
+                let source = if *dev { "historyDev" } else { "history" };
+                format!("{source}:{date}")
+            }
+            Self::SectionReplaced { section, .. } => section.clone(),
+        }
+    }
+
+    /// 中文摘要（用于变更历史展示）。
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Baseline { snapshot } => format!("基线快照 #{snapshot}"),
+            Self::PomodoroCompleted { record, .. } => {
+                let phase = match record.phase {
+                    Phase::Work => "番茄",
+                    Phase::ShortBreak => "短休息",
+                    Phase::LongBreak => "长休息",
+                };
+                format!(
+                    "完成{phase}：{} {} {} 分钟",
+                    record.start_time, record.tag, record.duration
+                )
+            }
+            Self::RecordAdded { date, record, .. } => format!(
+                "补录：{date} {} {} {} 分钟",
+                record.start_time, record.tag, record.duration
+            ),
+            Self::RecordUpdated { date, record, .. } => format!(
+                "编辑：{date} {} {} {} 分钟",
+                record.start_time, record.tag, record.duration
+            ),
+            Self::RecordDeleted { date, .. } => format!("删除 {date} 的记录"),
+            Self::RemarkEdited { from, to, .. } => format!("备注：「{from}」→「{to}」"),
+            Self::TagRenamed { from, to, .. } => format!("标签重命名：「{from}」→「{to}」"),
+            Self::BlacklistChanged { blacklist } => {
+                format!("黑名单更新：共 {} 项", blacklist.len())
+            }
+            Self::SettingsUpdated { .. } => "设置更新".to_string(),
+            Self::InterruptionRecorded { record, .. } => {
+                if record.reason.is_empty() {
+                    format!("记录中断：{}", record.tag)
+                } else {
+                    format!("记录中断：{}（{}）", record.tag, record.reason)
+                }
+            }
+            Self::HistoryDayReplaced { date, records, .. } => {
+                format!("{date} 的历史整体更新：{} 条", records.len())
+            }
+            Self::InterruptionDayReplaced { date, records } => {
+                format!("{date} 的中断记录整体更新：{} 条", records.len())
+            }
+            Self::SectionReplaced { section, .. } => format!("{section} 更新"),
+        }
+    }
+
+    /// 将事件应用到 `data`（重放用；基线由 `replay` 处理，事件与数据对不上时返回 `Invariant`）。
+    pub fn apply(&self, data: &mut AppData) -> AppResult<()> {
+        match self {
+            Self::Baseline { .. } => {}
+            Self::PomodoroCompleted { date, record } => {
+                day_records(&mut data.history, date).push(record.clone());
+            }
+            Self::RecordAdded { dev, date, record } => {
+                insert_sorted(history_mut(data, *dev), date, record.clone());
+            }
+            Self::RecordUpdated {
+                dev,
+                from,
+                date,
+                record,
+            } => {
+                let days = history_mut(data, *dev);
+                remove_by_id(days, from, &record.id)?;
+                insert_sorted(days, date, record.clone());
+            }
+            Self::RecordDeleted {
+                dev,
+                date,
+                record_id,
+            } => {
+                remove_by_id(history_mut(data, *dev), date, record_id)?;
+            }
+            Self::RemarkEdited {
+                dev,
+                date,
+                record_id,
+                to,
+                ..
+            } => {
+                let record = history_mut(data, *dev)
+                    .iter_mut()
+                    .filter(|d| d.date == *date)
+                    .flat_map(|d| d.records.iter_mut())
+                    .find(|r| r.id == *record_id)
+                    .ok_or_else(|| missing_record(date, record_id))?;
+                record.remark = to.clone();
+            }
+            Self::TagRenamed { .. } => {}
+            Self::BlacklistChanged { blacklist } => data.blacklist = blacklist.clone(),
+            Self::SettingsUpdated { settings } => data.settings = (**settings).clone(),
+            Self::InterruptionRecorded { date, record } => {
+                day_records(&mut data.interruptions, date).push(record.clone());
+            }
+            Self::HistoryDayReplaced { dev, date, records } => {
+                replace_day(history_mut(data, *dev), date, records.clone());
+            }
+            Self::InterruptionDayReplaced { date, records } => {
+                replace_day(&mut data.interruptions, date, records.clone());
+            }
+            Self::SectionReplaced { section, value } => {
+                data.set_section_value(section, value.clone())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 本次登记的审计事件：命令登记的事件按发生顺序，其后为各字段分组修改后的值。
+pub fn pending_events(pending: &PendingChanges, data: &AppData) -> AppResult<Vec<DomainEvent>> {
+    let mut events = pending.events.clone();
+    for section in &pending.sections {
+        match section {
+            Section::Settings => events.push(DomainEvent::SettingsUpdated {
+                settings: Box::new(data.settings.clone()),
+            }),
+            Section::Blacklist => events.push(DomainEvent::BlacklistChanged {
+                blacklist: data.blacklist.clone(),
+            }),
+            other => {
+                for field in other.fields() {
+                    events.push(DomainEvent::SectionReplaced {
+                        section: field.to_string(),
+                        value: data.section_value(field)?,
+                    });
+                }
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// 从最近一次基线开始重放事件（`snapshot` 按 id 读取基线快照）；返回重放的事件数与结果。
+pub fn replay(
+    events: &[DomainEvent],
+    snapshot: impl FnOnce(i64) -> AppResult<AppData>,
+) -> AppResult<(usize, AppData)> {
+    let Some((start, id)) = events.iter().enumerate().rev().find_map(|(i, e)| match e {
+        DomainEvent::Baseline { snapshot } => Some((i, *snapshot)),
+        _ => None,
+    }) else {
+        return Err(AppError::Invariant(
+            "事件日志缺少基线，无法重放".to_string(),
+        ));
+    };
+    let mut data = snapshot(id)?;
+    for event in &events[start + 1..] {
+        event.apply(&mut data)?;
+    }
+    Ok((events.len() - start, data))
+}
+
+/// 列出两份数据中不一致的字段（记录按日期归并后比较）。
+pub fn differing_sections(a: &AppData, b: &AppData) -> AppResult<Vec<String>> {
+    let a = normalized(a)?;
+    let b = normalized(b)?;
+    let (Some(a), Some(b)) = (a.as_object(), b.as_object()) else {
+        return Ok(Vec::new());
+    };
+    let mut names: Vec<&String> = a.keys().chain(b.keys()).collect();
+    names.sort();
+    names.dedup();
+    Ok(names
+        .into_iter()
+        .filter(|name| a.get(*name) != b.get(*name))
+        .cloned()
+        .collect())
+}
+
+/// 序列化为 JSON 并把记录字段归并为按日期排序的形式（去除空日期、合并重复日期）。
+fn normalized(data: &AppData) -> AppResult<Value> {
+    let mut value = serde_json::to_value(data)?;
+    if let Some(object) = value.as_object_mut() {
+        for name in RECORD_SECTIONS {
+            let Some(Value::Array(days)) = object.get(name) else {
+                continue;
+            };
+            let mut merged: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+            for day in days {
+                let date = day["date"].as_str().unwrap_or_default().to_string();
+                if let Some(records) = day["records"].as_array() {
+                    merged
+                        .entry(date)
+                        .or_default()
+                        .extend(records.iter().cloned());
+                }
+            }
+            let days: Vec<Value> = merged
+                .into_iter()
+                .filter(|(_, records)| !records.is_empty())
+                .map(|(date, records)| serde_json::json!({ "date": date, "records": records }))
+                .collect();
+            object.insert(name.to_string(), Value::Array(days));
+        }
+    }
+    Ok(value)
+}
+
+/// 正式历史或开发环境测试历史。
+fn history_mut(data: &mut AppData, dev: bool) -> &mut Vec<HistoryDay> {
+    if dev {
+        &mut data.history_dev
+    } else {
+        &mut data.history
+    }
+}
+
+/// 事件引用的记录不存在。
+fn missing_record(date: &str, record_id: &str) -> AppError {
+    AppError::Invariant(format!(
+        "事件日志与数据不一致：找不到历史记录 {date}/{record_id}"
+    ))
+}
+
+/// 按 id 移除某日的一条记录（当日为空时删除该日）。
+fn remove_by_id(days: &mut Vec<HistoryDay>, date: &str, record_id: &str) -> AppResult<()> {
+    let day = days
+        .iter_mut()
+        .find(|d| d.date == date && d.records.iter().any(|r| r.id == record_id))
+        .ok_or_else(|| missing_record(date, record_id))?;
+    day.records.retain(|r| r.id != record_id);
+    days.retain(|d| d.date != date || !d.records.is_empty());
+    Ok(())
+}
+
+/// 追加一条记录并按开始时间保持当日有序（与手动补录/编辑的写入方式一致）。
+fn insert_sorted(days: &mut Vec<HistoryDay>, date: &str, record: HistoryRecord) {
+    let records = day_records(days, date);
+    records.push(record);
+    records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+}
+
+/// 按日期分组的记录集合（历史日与中断日共用重放逻辑）。
+trait Day {
+    /// 记录类型。
+    type Record;
+
+    /// 创建空的某日集合。
+    fn empty(date: &str) -> Self;
+
+    /// 日期（YYYY-MM-DD）。
+    fn date(&self) -> &str;
+
+    /// 当日记录。
+    fn records_mut(&mut self) -> &mut Vec<Self::Record>;
+}
+
+impl Day for HistoryDay {
+    type Record = HistoryRecord;
+
+    /// 创建空的历史日。
+    fn empty(date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            records: Vec::new(),
+        }
+    }
+
+    /// 日期。
+    fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// 当日历史记录。
+    fn records_mut(&mut self) -> &mut Vec<HistoryRecord> {
+        &mut self.records
+    }
+}
+
+impl Day for InterruptionDay {
+    type Record = InterruptionRecord;
+
+    /// 创建空的中断日。
+    fn empty(date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            records: Vec::new(),
+        }
+    }
+
+    /// 日期。
+    fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// 当日中断记录。
+    fn records_mut(&mut self) -> &mut Vec<InterruptionRecord> {
+        &mut self.records
+    }
+}
+
+/// 取某日的记录列表（不存在时按日期顺序插入空的一天）。
+fn day_records<'a, D: Day>(days: &'a mut Vec<D>, date: &str) -> &'a mut Vec<D::Record> {
+    let index = match days.iter().position(|d| d.date() == date) {
+        Some(index) => index,
+        None => {
+            let index = days
+                .iter()
+                .position(|d| d.date() > date)
+                .unwrap_or(days.len());
+            days.insert(index, D::empty(date));
+            index
+        }
+    };
+    days[index].records_mut()
+}
+
+/// 以 `records` 替换某日全部记录（为空时删除该日）。
+fn replace_day<D: Day>(days: &mut Vec<D>, date: &str, records: Vec<D::Record>) {
+    days.retain(|d| d.date() != date);
+    if !records.is_empty() {
+        *day_records(days, date) = records;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::Tag;
+
+    /// 构造带一天历史与一个标签的数据。
+    fn sample_data() -> AppData {
+        AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![
+                    HistoryRecord::for_test("工作", "09:00"),
+                    HistoryRecord::for_test("工作", "10:00"),
+                ],
+            }],
+            tag_defs: vec![Tag {
+                id: "t1".to_string(),
+                name: "工作".to_string(),
+                color: String::new(),
+                icon: String::new(),
+                archived: false,
+                parent_id: None,
+            }],
+            ..AppData::default()
+        }
+    }
+
+    /// `pending_events`：命令登记的事件在前，字段分组按修改后的值生成事件。
+    #[test]
+    fn pending_events_follow_registered_changes() {
+        let mut data = sample_data();
+        let record_id = data.history[0].records[1].id.clone();
+        data.record_event(DomainEvent::RemarkEdited {
+            dev: false,
+            date: "2025-01-01".to_string(),
+            record_id: record_id.clone(),
+            from: String::new(),
+            to: "复盘".to_string(),
+        });
+        data.touch_section(Section::Counters);
+        data.total_pomodoros = 3;
+        data.touch_section(Section::Settings);
+
+        let events = pending_events(&data.pending, &data).unwrap();
+        let kinds: Vec<&str> = events.iter().map(DomainEvent::kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                "remarkEdited",
+                "settingsUpdated",
+                "sectionReplaced",
+                "sectionReplaced"
+            ]
+        );
+        assert_eq!(events[0].subject(), format!("history:{record_id}"));
+        assert!(matches!(
+            &events[3],
+            DomainEvent::SectionReplaced { section, value }
+                if section == "totalPomodoros" && value == 3
+        ));
+
+        data.touch_all_records();
+        assert!(pending_events(&data.pending, &data).unwrap().is_empty());
+    }
+
+    /// `replay`：从最近一次基线开始应用记录事件；事件经 JSON 往返后保持不变，记录 id 不随编辑变化。
+    #[test]
+    fn replay_rebuilds_from_latest_baseline() {
+        let base = sample_data();
+        let first = base.history[0].records[0].clone();
+        let second = base.history[0].records[1].clone();
+        let added = HistoryRecord::for_test("学习", "08:00");
+        let moved = HistoryRecord {
+            start_time: "07:00".to_string(),
+            ..first.clone()
+        };
+        let events = [
+            DomainEvent::Baseline { snapshot: 1 },
+            DomainEvent::PomodoroCompleted {
+                date: "2024-12-31".to_string(),
+                record: HistoryRecord::for_test("工作", "20:00"),
+            },
+            DomainEvent::Baseline { snapshot: 2 },
+            DomainEvent::RecordAdded {
+                dev: false,
+                date: "2025-01-01".to_string(),
+                record: added.clone(),
+            },
+            DomainEvent::RemarkEdited {
+                dev: false,
+                date: "2025-01-01".to_string(),
+                record_id: second.id.clone(),
+                from: String::new(),
+                to: "复盘".to_string(),
+            },
+            DomainEvent::RecordUpdated {
+                dev: false,
+                from: "2025-01-01".to_string(),
+                date: "2025-01-02".to_string(),
+                record: moved.clone(),
+            },
+            DomainEvent::RecordDeleted {
+                dev: false,
+                date: "2025-01-01".to_string(),
+                record_id: added.id.clone(),
+            },
+            DomainEvent::SectionReplaced {
+                section: "totalPomodoros".to_string(),
+                value: serde_json::json!(5),
+            },
+        ];
+        let events: Vec<DomainEvent> = events
+            .iter()
+            .map(|e| serde_json::from_str(&serde_json::to_string(e).unwrap()).unwrap())
+            .collect();
+
+        let (count, rebuilt) = replay(&events, |id| {
+            assert_eq!(id, 2);
+            Ok(base.clone())
+        })
+        .unwrap();
+        assert_eq!(count, 6);
+        assert_eq!(rebuilt.total_pomodoros, 5);
+        let dates: Vec<&str> = rebuilt.history.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2025-01-01", "2025-01-02"]);
+        assert_eq!(rebuilt.history[0].records.len(), 1);
+        assert_eq!(rebuilt.history[0].records[0].id, second.id);
+        assert_eq!(rebuilt.history[0].records[0].remark, "复盘");
+        assert_eq!(rebuilt.history[1].records[0].id, moved.id);
+        assert_eq!(events[5].subject(), format!("history:{}", first.id));
+    }
+
+    /// `replay`：缺少基线或事件引用的记录不存在时返回 `Invariant`。
+    #[test]
+    fn replay_rejects_inconsistent_logs() {
+        let deleted = DomainEvent::RecordDeleted {
+            dev: false,
+            date: "2025-01-01".to_string(),
+            record_id: "missing".to_string(),
+        };
+        assert!(matches!(
+            replay(std::slice::from_ref(&deleted), |_| Ok(sample_data())),
+            Err(AppError::Invariant(_))
+        ));
+        assert!(matches!(
+            replay(&[DomainEvent::Baseline { snapshot: 1 }, deleted], |_| Ok(
+                sample_data()
+            )),
+            Err(AppError::Invariant(_))
+        ));
+    }
+
+    /// `differing_sections`：记录按日期归并后比较，与日期顺序、空日期无关。
+    #[test]
+    fn differing_sections_ignore_day_order() {
+        let mut a = sample_data();
+        a.history.push(HistoryDay {
+            date: "2024-12-31".to_string(),
+            records: vec![HistoryRecord::for_test("学习", "20:00")],
+        });
+        let mut b = a.clone();
+        b.history.reverse();
+        b.history.push(HistoryDay {
+            date: "2025-02-01".to_string(),
+            records: Vec::new(),
+        });
+        assert!(differing_sections(&a, &b).unwrap().is_empty());
+        b.total_pomodoros = 1;
+        assert_eq!(differing_sections(&a, &b).unwrap(), ["totalPomodoros"]);
+    }
+}
@@ -0,0 +1,597 @@
+//! 数据备份：定时/操作前将完整 `AppData`（含历史与中断记录）及归档文件中的历史快照到 `backups/` 目录，支持列表、预览与恢复。
+//!
+//! 备份文件名携带时间与类型：`backup-YYYYMMDD-HHMMSSmmm-scheduled.json` / `backup-…-pre-<操作>.json`。
+//! 启用数据加密且开启“加密备份”时，文件内容为 `{"schemaVersion": N, "sealed": ...}` 密文（文件名不变）。
+
+use std::path::{Path, PathBuf};
+
+use chrono::TimeZone as _;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::app_data::{AppData, BackupSettings, HistoryDay};
+use crate::crypto::Cipher;
+use crate::errors::{AppError, AppResult};
+use crate::migrations::MigrationReport;
+
+/// 备份文件格式版本（用于后续演进时识别旧格式）。
+///
+/// 2：包含归档文件中的历史（`archive`），旧版本应用读到时拒绝恢复，避免只恢复汇总而丢失归档。
+pub const BACKUP_FORMAT_VERSION: u32 = 2;
+
+/// 备份文件名前缀。
+const FILE_PREFIX: &str = "backup-";
+
+/// 备份文件名后缀。
+const FILE_SUFFIX: &str = ".json";
+
+/// 文件名中的时间格式（本地时间，精确到毫秒以避免同一秒内重名）。
+const FILE_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+/// 文件名中的时间部分长度（`YYYYMMDD-HHMMSSmmm`）。
+const FILE_TIME_LEN: usize = 18;
+
+/// 操作前备份在文件名中的类型标记。
+const PRE_OPERATION_MARK: &str = "pre-";
+
+/// 定时备份在文件名中的类型标记。
+const SCHEDULED_MARK: &str = "scheduled";
+
+/// 备份类型。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum BackupKind {
+    /// 定时备份。
+    Scheduled,
+    /// 破坏性操作（删除标签、恢复备份等）之前的自动备份。
+    BeforeOperation,
+}
+
+/// 备份文件条目（用于列表展示）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// 文件名（恢复/预览时作为标识）。
+    pub file_name: String,
+    /// 创建时刻（RFC 3339，本地时区）。
+    pub created_at: String,
+    /// 备份类型。
+    pub kind: BackupKind,
+    /// 触发备份的操作（仅操作前备份，如 `delete-tag`）。
+    pub reason: String,
+    /// 文件大小（字节）。
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+/// 备份内容预览（恢复前确认用）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct BackupPreview {
+    /// 备份条目信息。
+    pub info: BackupInfo,
+    /// 累计完成番茄数。
+    #[ts(type = "number")]
+    pub total_pomodoros: u64,
+    /// 标签数量（不含归档标签）。
+    pub tag_count: u32,
+    /// 任务数量。
+    pub task_count: u32,
+    /// 有历史记录的天数。
+    pub history_days: u32,
+    /// 历史记录总数。
+    pub history_records: u32,
+    /// 中断记录总数。
+    pub interruption_records: u32,
+    /// 最早的历史日期（无历史时为空）。
+    pub first_date: Option<String>,
+    /// 最晚的历史日期（无历史时为空）。
+    pub last_date: Option<String>,
+    /// 恢复时将执行的数据迁移（预演结果）。
+    pub migration: MigrationReport,
+}
+
+/// 备份文件内容。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    /// 备份文件格式版本。
+    pub version: u32,
+    /// 创建时刻（RFC 3339）。
+    pub created_at: String,
+    /// 备份类型。
+    pub kind: BackupKind,
+    /// 触发备份的操作（定时备份为空）。
+    #[serde(default)]
+    pub reason: String,
+    /// 完整数据（含历史与中断记录）。
+    pub data: AppData,
+    /// 归档文件中的历史（按日期升序；格式 1 的备份没有此字段）。
+    #[serde(default)]
+    pub archive: Option<Vec<HistoryDay>>,
+}
+
+/// 写入一份备份（先写临时文件再重命名，避免留下半个文件），并按保留策略清理同类型的旧备份。
+///
+/// 一并写入 `archive_dir` 下归档文件中的历史；传入密钥且设置中开启了加密备份时写入密文。
+#[allow(clippy::too_many_arguments)]
+pub fn create_backup(
+    dir: &Path,
+    archive_dir: &Path,
+    data: &AppData,
+    kind: BackupKind,
+    reason: &str,
+    settings: &BackupSettings,
+    now: chrono::DateTime<chrono::Local>,
+    cipher: Option<&Cipher>,
+) -> AppResult<BackupInfo> {
+    if kind == BackupKind::BeforeOperation && !is_valid_reason(reason) {
+        return Err(AppError::Invariant(format!("备份原因不合法：{reason}")));
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| AppError::Invariant(format!("创建备份目录失败：{e}")))?;
+
+    let archive = crate::archive::read_all(archive_dir, cipher)?;
+    let file_name = backup_file_name(kind, reason, now);
+    let file = BackupFile {
+        version: BACKUP_FORMAT_VERSION,
+        created_at: now.to_rfc3339(),
+        kind,
+        reason: reason.to_string(),
+        data: data.clone(),
+        archive: Some(archive),
+    };
+    let bytes = match cipher.filter(|_| settings.encrypt) {
+        Some(cipher) => {
+            serde_json::to_vec(&cipher.seal_json_with_schema(&file, data.schema_version)?)?
+        }
+        None => serde_json::to_vec(&file)?,
+    };
+    let path = dir.join(&file_name);
+    write_atomically(&path, &bytes)?;
+
+    let removed = prune_backups(dir, kind, settings.keep_count)?;
+    tracing::info!(
+        target: "storage",
+        "已创建备份：file={} kind={:?} pruned={}",
+        file_name,
+        kind,
+        removed
+    );
+    parse_backup_entry(&path)
+        .ok_or_else(|| AppError::Invariant("写入后读取备份信息失败".to_string()))
+}
+
+/// 列出备份目录下的全部备份（按创建时间倒序；目录不存在时为空）。
+pub fn list_backups(dir: &Path) -> AppResult<Vec<BackupInfo>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::Invariant(format!("读取备份目录失败：{e}"))),
+    };
+    let mut out: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_backup_entry(&entry.path()))
+        .collect();
+    // 文件名中的时间部分可按字典序比较。
+    out.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(out)
+}
+
+/// 读取一份备份（文件名必须是备份目录下的合法备份文件名；加密备份需传入当前密钥）。
+pub fn read_backup(
+    dir: &Path,
+    file_name: &str,
+    cipher: Option<&Cipher>,
+) -> AppResult<(BackupInfo, BackupFile)> {
+    let path = backup_path(dir, file_name)?;
+    let info = parse_backup_entry(&path)
+        .ok_or_else(|| AppError::Validation("备份文件不存在".to_string()))?;
+    let bytes =
+        std::fs::read(&path).map_err(|e| AppError::Invariant(format!("读取备份失败：{e}")))?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes)?;
+    crate::migrations::ensure_supported_value(&value)?;
+    let value = crate::crypto::unseal_json(cipher, value)?;
+    let file: BackupFile = serde_json::from_value(value)?;
+    if file.version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::Validation(
+            "备份文件来自更新版本的应用，无法恢复".to_string(),
+        ));
+    }
+    Ok((info, file))
+}
+
+/// 生成备份内容预览（含迁移预演；备份由更新版本写入时拒绝）。
+pub fn preview_backup(info: BackupInfo, data: &AppData) -> AppResult<BackupPreview> {
+    let migration = crate::migrations::dry_run(data)?;
+    let days = data.history.iter().filter(|d| !d.records.is_empty());
+    Ok(BackupPreview {
+        info,
+        total_pomodoros: data.total_pomodoros,
+        tag_count: data.tags.len() as u32,
+        task_count: data.tasks.len() as u32,
+        history_days: days.clone().count() as u32,
+        history_records: data.history.iter().map(|d| d.records.len() as u32).sum(),
+        interruption_records: data
+            .interruptions
+            .iter()
+            .map(|d| d.records.len() as u32)
+            .sum(),
+        first_date: days.clone().map(|d| d.date.clone()).min(),
+        last_date: days.map(|d| d.date.clone()).max(),
+        migration,
+    })
+}
+
+/// 启用、更换或关闭加密后重写已有备份：以旧密钥解密，再按新密钥与加密备份设置写回，返回重写的文件数。
+///
+/// 无法用旧密钥解密的备份（更早的密码）保持原样并记录日志。
+pub fn reseal_backups(
+    dir: &Path,
+    old: Option<&Cipher>,
+    new: Option<&Cipher>,
+    settings: &BackupSettings,
+) -> AppResult<usize> {
+    let target = new.filter(|_| settings.encrypt);
+    let mut rewritten = 0usize;
+    for info in list_backups(dir)? {
+        let path = dir.join(&info.file_name);
+        let bytes =
+            std::fs::read(&path).map_err(|e| AppError::Invariant(format!("读取备份失败：{e}")))?;
+        let value: serde_json::Value = serde_json::from_slice(&bytes)?;
+        let sealed = crate::crypto::sealed_blob(&value).is_some();
+        if !sealed && target.is_none() {
+            continue;
+        }
+        let plain = match crate::crypto::unseal_json(old, value) {
+            Ok(plain) => plain,
+            Err(e) => {
+                tracing::warn!(target: "storage", "备份无法解密，保持原样：file={} err={}", info.file_name, e);
+                continue;
+            }
+        };
+        let bytes = match target {
+            Some(cipher) => {
+                let schema_version = plain["data"]["schemaVersion"].as_u64().unwrap_or(0);
+                let schema_version = u32::try_from(schema_version).unwrap_or(u32::MAX);
+                serde_json::to_vec(&cipher.seal_json_with_schema(&plain, schema_version)?)?
+            }
+            None => serde_json::to_vec(&plain)?,
+        };
+        write_atomically(&path, &bytes)?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
+
+/// 判断定时备份是否到期：尚无定时备份，或距最近一次定时备份已超过间隔。
+pub fn scheduled_backup_due(
+    dir: &Path,
+    settings: &BackupSettings,
+    now: chrono::DateTime<chrono::Local>,
+) -> AppResult<bool> {
+    if !settings.enabled {
+        return Ok(false);
+    }
+    let latest = list_backups(dir)?
+        .into_iter()
+        .find(|b| b.kind == BackupKind::Scheduled)
+        .and_then(|b| chrono::DateTime::parse_from_rfc3339(&b.created_at).ok());
+    Ok(match latest {
+        Some(at) => {
+            now.signed_duration_since(at)
+                >= chrono::Duration::hours(i64::from(settings.interval_hours))
+        }
+        None => true,
+    })
+}
+
+/// 启动后台定时备份任务：启动时检查一次，此后每 10 分钟检查是否到期。
+#[cfg(not(test))]
+pub fn spawn_backup_task(app: tauri::AppHandle) {
+    use tauri::Manager as _;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<crate::state::AppState>();
+            if let Err(e) = state.run_scheduled_backup_if_due() {
+                tracing::warn!(target: "storage", "定时备份失败：{e}");
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(600)).await;
+        }
+    });
+}
+
+/// 先写临时文件再重命名，避免留下半个备份文件。
+fn write_atomically(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, bytes).map_err(|e| AppError::Invariant(format!("写入备份失败：{e}")))?;
+    std::fs::rename(&tmp, path).map_err(|e| AppError::Invariant(format!("保存备份失败：{e}")))
+}
+
+/// 按保留数量清理指定类型的旧备份，返回删除的文件数。
+fn prune_backups(dir: &Path, kind: BackupKind, keep: u32) -> AppResult<usize> {
+    let mut removed = 0usize;
+    for info in list_backups(dir)?
+        .into_iter()
+        .filter(|b| b.kind == kind)
+        .skip(keep as usize)
+    {
+        if std::fs::remove_file(dir.join(&info.file_name)).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// 生成备份文件名。
+fn backup_file_name(
+    kind: BackupKind,
+    reason: &str,
+    now: chrono::DateTime<chrono::Local>,
+) -> String {
+    let mark = match kind {
+        BackupKind::Scheduled => SCHEDULED_MARK.to_string(),
+        BackupKind::BeforeOperation => format!("{PRE_OPERATION_MARK}{reason}"),
+    };
+    format!(
+        "{FILE_PREFIX}{}-{mark}{FILE_SUFFIX}",
+        now.format(FILE_TIME_FORMAT)
+    )
+}
+
+/// 操作原因只允许小写字母、数字与 `-`（会拼进文件名）。
+fn is_valid_reason(reason: &str) -> bool {
+    !reason.is_empty()
+        && reason
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// 校验文件名并拼出完整路径（拒绝路径分隔符等，避免越出备份目录）。
+fn backup_path(dir: &Path, file_name: &str) -> AppResult<PathBuf> {
+    let valid = file_name.starts_with(FILE_PREFIX)
+        && file_name.ends_with(FILE_SUFFIX)
+        && !file_name.contains(['/', '\\'])
+        && !file_name.contains("..");
+    if !valid {
+        return Err(AppError::Validation("备份文件名不合法".to_string()));
+    }
+    Ok(dir.join(file_name))
+}
+
+/// 从备份文件路径解析条目信息（非备份文件返回 `None`）。
+fn parse_backup_entry(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let rest = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(FILE_SUFFIX)?;
+    let time = rest.get(..FILE_TIME_LEN)?;
+    let mark = rest.get(FILE_TIME_LEN..)?.strip_prefix('-')?;
+    let (kind, reason) = if mark == SCHEDULED_MARK {
+        (BackupKind::Scheduled, String::new())
+    } else {
+        let reason = mark.strip_prefix(PRE_OPERATION_MARK)?;
+        (BackupKind::BeforeOperation, reason.to_string())
+    };
+    let naive = chrono::NaiveDateTime::parse_from_str(time, FILE_TIME_FORMAT).ok()?;
+    let created_at = chrono::Local
+        .from_local_datetime(&naive)
+        .earliest()?
+        .to_rfc3339();
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    Some(BackupInfo {
+        file_name,
+        created_at,
+        kind,
+        reason,
+        size_bytes: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{HistoryDay, HistoryRecord};
+
+    /// 构造本地时刻。
+    fn at(day: u32, hour: u32) -> chrono::DateTime<chrono::Local> {
+        chrono::Local
+            .with_ymd_and_hms(2025, 1, day, hour, 0, 0)
+            .earliest()
+            .unwrap()
+    }
+
+    /// 构造带一天历史的数据。
+    fn sample_data() -> AppData {
+        AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![HistoryRecord::for_test("工作", "09:00")],
+            }],
+            total_pomodoros: 1,
+            ..AppData::default()
+        }
+    }
+
+    /// `create_backup`/`read_backup`：写入后应能列出并完整读回，预览统计正确。
+    #[test]
+    fn create_list_and_read_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = BackupSettings::default();
+        let info = create_backup(
+            dir.path(),
+            &dir.path().join("archive"),
+            &sample_data(),
+            BackupKind::BeforeOperation,
+            "delete-tag",
+            &settings,
+            at(2, 9),
+            None,
+        )
+        .unwrap();
+        assert_eq!(info.kind, BackupKind::BeforeOperation);
+        assert_eq!(info.reason, "delete-tag");
+
+        let list = list_backups(dir.path()).unwrap();
+        assert_eq!(list, vec![info.clone()]);
+
+        let (read_info, file) = read_backup(dir.path(), &info.file_name, None).unwrap();
+        assert_eq!(read_info, info);
+        let preview = preview_backup(read_info, &file.data).unwrap();
+        assert_eq!(preview.history_records, 1);
+        assert_eq!(preview.first_date.as_deref(), Some("2025-01-01"));
+        assert_eq!(preview.total_pomodoros, 1);
+    }
+
+    /// `create_backup`：每种类型只保留最近 `keep_count` 份，互不挤占。
+    #[test]
+    fn create_backup_prunes_per_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = BackupSettings {
+            keep_count: 2,
+            ..BackupSettings::default()
+        };
+        let data = AppData::default();
+        for day in 1..=4 {
+            create_backup(
+                dir.path(),
+                &dir.path().join("archive"),
+                &data,
+                BackupKind::Scheduled,
+                "",
+                &settings,
+                at(day, 8),
+                None,
+            )
+            .unwrap();
+        }
+        create_backup(
+            dir.path(),
+            &dir.path().join("archive"),
+            &data,
+            BackupKind::BeforeOperation,
+            "restore",
+            &settings,
+            at(5, 8),
+            None,
+        )
+        .unwrap();
+
+        let list = list_backups(dir.path()).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].kind, BackupKind::BeforeOperation);
+        assert!(list[1].file_name.starts_with("backup-20250104-"));
+        assert!(list[2].file_name.starts_with("backup-20250103-"));
+    }
+
+    /// `scheduled_backup_due`：无定时备份时到期；间隔内不到期；关闭时永不到期。
+    #[test]
+    fn scheduled_backup_due_respects_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = BackupSettings {
+            interval_hours: 24,
+            ..BackupSettings::default()
+        };
+        assert!(scheduled_backup_due(dir.path(), &settings, at(1, 8)).unwrap());
+
+        create_backup(
+            dir.path(),
+            &dir.path().join("archive"),
+            &AppData::default(),
+            BackupKind::Scheduled,
+            "",
+            &settings,
+            at(1, 8),
+            None,
+        )
+        .unwrap();
+        assert!(!scheduled_backup_due(dir.path(), &settings, at(1, 20)).unwrap());
+        assert!(scheduled_backup_due(dir.path(), &settings, at(2, 8)).unwrap());
+
+        let disabled = BackupSettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!scheduled_backup_due(dir.path(), &disabled, at(9, 8)).unwrap());
+    }
+
+    /// 加密备份：开启加密备份时写入密文，读取需密钥；关闭加密后 `reseal_backups` 还原为明文。
+    #[test]
+    fn encrypted_backups_round_trip_and_reseal() {
+        let dir = tempfile::tempdir().unwrap();
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let settings = BackupSettings {
+            encrypt: true,
+            ..BackupSettings::default()
+        };
+        let info = create_backup(
+            dir.path(),
+            &dir.path().join("archive"),
+            &sample_data(),
+            BackupKind::Scheduled,
+            "",
+            &settings,
+            at(1, 8),
+            Some(&cipher),
+        )
+        .unwrap();
+        let raw = std::fs::read_to_string(dir.path().join(&info.file_name)).unwrap();
+        assert!(!raw.contains("工作"));
+        let envelope: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(
+            envelope["schemaVersion"],
+            crate::migrations::CURRENT_SCHEMA_VERSION
+        );
+        assert!(matches!(
+            read_backup(dir.path(), &info.file_name, None),
+            Err(AppError::Locked)
+        ));
+        let (_, file) = read_backup(dir.path(), &info.file_name, Some(&cipher)).unwrap();
+        assert_eq!(file.data.total_pomodoros, 1);
+
+        assert_eq!(
+            reseal_backups(dir.path(), Some(&cipher), None, &settings).unwrap(),
+            1
+        );
+        let (_, file) = read_backup(dir.path(), &info.file_name, None).unwrap();
+        assert_eq!(file.data.total_pomodoros, 1);
+        assert_eq!(
+            reseal_backups(dir.path(), None, None, &settings).unwrap(),
+            0
+        );
+    }
+
+    /// `read_backup`/`create_backup`：应拒绝越界文件名与非法原因。
+    #[test]
+    fn rejects_invalid_names_and_reasons() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            read_backup(dir.path(), "../pomodoro-data.json", None),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            read_backup(dir.path(), "backup-20250101-000000000-scheduled.json", None),
+            Err(AppError::Validation(_))
+        ));
+        assert!(create_backup(
+            dir.path(),
+            &dir.path().join("archive"),
+            &AppData::default(),
+            BackupKind::BeforeOperation,
+            "删除/标签",
+            &BackupSettings::default(),
+            at(1, 8),
+            None,
+        )
+        .is_err());
+    }
+}
@@ -5,13 +5,22 @@ use std::path::PathBuf;
 use ts_rs::TS as _;
 
 use tauri_app_lib::typegen::{
-    AnimationIntensity, AnimationSettings, AppData, AppSnapshot, AudioSettings, BlacklistItem,
-    BlacklistTemplate, CustomAudio, DateRange, ExportField, ExportFormat, ExportRequest,
-    FocusAnalysis, GoalProgress, HistoryDay, HistoryRecord, InterruptionDay,
-    InterruptionReasonCount, InterruptionRecord, InterruptionSettings, InterruptionStats,
-    InterruptionType, KillItem, KillSummary, MilestoneReachedPayload, Phase,
-    PomodoroCompletedPayload, ProcessInfo, Settings, StorePaths, TagCount, TagEfficiency,
-    TimerSnapshot, TodayStats, WeekStats, WorkCompletedEvent,
+    AnimationIntensity, AnimationSettings, AppData, AppSnapshot, ArchiveFileInfo, ArchiveReport,
+    ArchiveStatus, ArchivedDay, ArchivedTagTotal, AudioSettings, AuditEntry, BackupInfo,
+    BackupKind, BackupPreview, BackupSettings, BlacklistItem, BlacklistTemplate, CsvColumnMapping,
+    CustomAudio, DataRootInfo, DataRootMode, DateRange, ExportField, ExportFormat, ExportRequest,
+    ExternalImportRequest, ExternalImportSource, FirstWeekday, FocusAnalysis, GoalPeriod,
+    GoalProgress, HistoryArchive, HistoryDay, HistoryRecord, HistoryRecordInput, ImportMode,
+    ImportReport, ImportRequest, ImportRowError, IntegrityIssue, IntegrityIssueKind,
+    IntegrityReport, InterruptionDay, InterruptionReasonCount, InterruptionRecord,
+    InterruptionSettings, InterruptionStats, InterruptionType, KillItem, KillSummary,
+    MigrationReport, MigrationStepReport, MilestoneReachedPayload, PauseSegment, Phase, PlanStep,
+    PomodoroCompletedPayload, ProcessInfo, ProfileInfo, ProfileRegistry, ProjectTagMapping,
+    RelocateRequest, ReplayReport, SessionPlan, Settings, StorePaths, StoreRecoveryReport,
+    StreakProgress, StreakSettings, SuspendExpiryPolicy, SyncConflict, SyncDeviceStatus,
+    SyncReport, SyncStatus, Tag, TagCount, TagEfficiency, TagEstimate, TagGoal, TagGoalProgress,
+    TagOverride, Task, TaskEstimate, TaskEstimateReport, TimerSnapshot, TodayStats, UndoOutcome,
+    UndoState, WeekStats, WorkCompletedEvent,
 };
 
 /// 解析输出路径参数：支持 `--out <path>`，否则写入默认位置。
@@ -53,14 +62,42 @@ fn build_types_file() -> String {
     out.push('\n');
     out.push_str(&exported_decl(&InterruptionSettings::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&SuspendExpiryPolicy::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&FirstWeekday::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&GoalPeriod::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&Tag::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&TagGoal::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&TagOverride::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&Task::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&TaskEstimate::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&TagEstimate::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&TaskEstimateReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&StreakSettings::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&BackupSettings::decl()));
+    out.push('\n');
     out.push_str(&exported_decl(&BlacklistItem::decl()));
     out.push('\n');
     out.push_str(&exported_decl(&BlacklistTemplate::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&PauseSegment::decl()));
+    out.push('\n');
     out.push_str(&exported_decl(&HistoryRecord::decl()));
     out.push('\n');
     out.push_str(&exported_decl(&HistoryDay::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&HistoryRecordInput::decl()));
+    out.push('\n');
     out.push_str(&exported_decl(&CustomAudio::decl()));
     out.push('\n');
     out.push_str(&exported_decl(&InterruptionType::decl()));
@@ -69,6 +106,16 @@ fn build_types_file() -> String {
     out.push('\n');
     out.push_str(&exported_decl(&InterruptionDay::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&PlanStep::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&SessionPlan::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ArchivedTagTotal::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ArchivedDay::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&HistoryArchive::decl()));
+    out.push('\n');
     out.push_str(&exported_decl(&AppData::decl()));
     out.push('\n');
 
@@ -78,6 +125,10 @@ fn build_types_file() -> String {
     out.push('\n');
     out.push_str(&exported_decl(&WeekStats::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&TagGoalProgress::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&StreakProgress::decl()));
+    out.push('\n');
     out.push_str(&exported_decl(&GoalProgress::decl()));
     out.push('\n');
     out.push_str(&exported_decl(&TimerSnapshot::decl()));
@@ -89,6 +140,56 @@ fn build_types_file() -> String {
     out.push('\n');
     out.push_str(&exported_decl(&StorePaths::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&DataRootMode::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&DataRootInfo::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&RelocateRequest::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&BackupKind::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&MigrationStepReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&MigrationReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&BackupInfo::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&BackupPreview::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&StoreRecoveryReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ProfileInfo::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ProfileRegistry::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&AuditEntry::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ReplayReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&IntegrityIssueKind::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&IntegrityIssue::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&IntegrityReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&SyncDeviceStatus::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&SyncStatus::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&SyncConflict::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&SyncReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ArchiveReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ArchiveFileInfo::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ArchiveStatus::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&UndoState::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&UndoOutcome::decl()));
+    out.push('\n');
 
     out.push_str(&exported_decl(&ProcessInfo::decl()));
     out.push('\n');
@@ -116,6 +217,22 @@ fn build_types_file() -> String {
     out.push('\n');
     out.push_str(&exported_decl(&ExportRequest::decl()));
     out.push('\n');
+    out.push_str(&exported_decl(&ImportMode::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ImportRequest::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ImportRowError::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ImportReport::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ExternalImportSource::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&CsvColumnMapping::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ProjectTagMapping::decl()));
+    out.push('\n');
+    out.push_str(&exported_decl(&ExternalImportRequest::decl()));
+    out.push('\n');
 
     out.push_str(&exported_decl(&PomodoroCompletedPayload::decl()));
     out.push('\n');
@@ -2,7 +2,7 @@
 
 use chrono::{Duration as ChronoDuration, NaiveDate, NaiveDateTime, NaiveTime};
 
-use crate::app_data::{AppData, Phase, Settings};
+use crate::app_data::{AppData, Phase, Section, Settings};
 use crate::errors::{AppError, AppResult};
 use crate::timer::TimerClock;
 
@@ -68,6 +68,7 @@ impl ComboRuntime {
             1
         };
 
+        data.touch_section(Section::Counters);
         data.current_combo = next_combo;
         self.current_work_is_continuation = false;
         self.last_completed_at = Some(clock_naive_now(clock)?);
@@ -82,7 +83,10 @@ impl ComboRuntime {
 
     /// 在“工作阶段中断”时清空 Combo（PRD v4：中断后 Combo 重置为 0）。
     pub fn on_interrupted(&mut self, data: &mut AppData) {
-        data.current_combo = 0;
+        if data.current_combo != 0 {
+            data.touch_section(Section::Counters);
+            data.current_combo = 0;
+        }
         self.last_completed_at = None;
         self.last_expected_break_minutes = 0;
         self.current_work_is_continuation = false;
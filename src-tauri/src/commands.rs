@@ -2,18 +2,31 @@
 
 pub mod analysis;
 pub mod app;
+pub mod archive;
+pub mod audit;
+pub mod backup;
 pub mod blacklist;
 pub(crate) mod common;
+pub mod data_root;
 pub mod debug;
+pub mod encryption;
 pub mod export;
 pub mod history;
+pub mod import;
+pub mod import_external;
+pub mod integrity;
 pub mod logging;
+pub mod plans;
 pub mod processes;
+pub mod profiles;
 pub mod settings;
 mod state_like;
+pub mod sync;
 pub mod tags;
+pub mod tasks;
 pub mod templates;
 pub mod timer;
+pub mod undo;
 pub(crate) mod validation;
 
 pub mod types;
@@ -5,23 +5,26 @@ use crate::app_data::DateRange;
 use crate::errors::AppResult;
 
 use super::state_like::CommandState;
-use super::validation::{history_for_ui, validate_date_range};
+use super::validation::validate_date_range;
 
-/// 获取专注分析的内部实现。
+/// 获取专注分析的内部实现：只查询范围内（按日界线顺延）的历史。
 pub(crate) fn get_focus_analysis_impl<S: CommandState>(
     state: &S,
     range: &DateRange,
 ) -> AppResult<FocusAnalysis> {
     validate_date_range(range)?;
-    let data = state.data_snapshot();
-    crate::analysis::get_focus_analysis(history_for_ui(&data), range)
+    let settings = state.settings_snapshot();
+    let boundary = settings.day_boundary();
+    let natural = boundary.natural_range(range);
+    let days = state.history_in_range(&natural.from, &natural.to)?;
+    crate::analysis::get_focus_analysis(&days, range, boundary, settings.roll_up_tags)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::app_data::{AppData, HistoryDay, HistoryRecord, Phase};
+    use crate::app_data::{AppData, HistoryDay, HistoryRecord};
     use crate::commands::state_like::TestState;
 
     /// `get_focus_analysis_impl`：应校验日期范围，并在开发环境优先使用 `history_dev`。
@@ -30,25 +33,11 @@ mod tests {
         let data = AppData {
             history: vec![HistoryDay {
                 date: "2025-01-01".to_string(),
-                records: vec![HistoryRecord {
-                    tag: "A".to_string(),
-                    start_time: "09:00".to_string(),
-                    end_time: None,
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                }],
+                records: vec![HistoryRecord::for_test("A", "09:00")],
             }],
             history_dev: vec![HistoryDay {
                 date: "2025-01-02".to_string(),
-                records: vec![HistoryRecord {
-                    tag: "B".to_string(),
-                    start_time: "10:00".to_string(),
-                    end_time: None,
-                    duration: 30,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                }],
+                records: vec![HistoryRecord::for_test("B", "10:00").with_duration(30)],
             }],
             ..AppData::default()
         };
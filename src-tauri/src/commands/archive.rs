@@ -0,0 +1,113 @@
+//! 历史归档相关命令：查询归档状态与按保留策略立即归档（归档文件保存在配置数据目录）。
+
+use std::path::Path;
+
+use crate::archive::{ArchiveReport, ArchiveStatus};
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+
+/// 查询当前配置的归档状态。
+pub(crate) fn get_archive_status_impl<S: CommandState>(
+    state: &S,
+    profile_dir: &Path,
+) -> AppResult<ArchiveStatus> {
+    crate::archive::status(
+        &crate::archive::archive_dir(profile_dir),
+        &state.data_snapshot(),
+    )
+}
+
+/// 按设置的保留月数立即归档：有待归档的历史时先备份，再移入按年压缩的归档文件。
+pub(crate) fn archive_history_impl<S: CommandState>(
+    state: &S,
+    profile_dir: &Path,
+) -> AppResult<ArchiveReport> {
+    let months = state.settings_snapshot().archive_after_months;
+    let today = chrono::Local::now().date_naive();
+    let Some(before) = crate::archive::cutoff(today, months) else {
+        return Err(AppError::Validation("尚未设置历史保留月数".to_string()));
+    };
+    if crate::archive::has_due(&state.data_snapshot(), &before) {
+        state.backup_before("archive")?;
+    }
+
+    let dir = crate::archive::archive_dir(profile_dir);
+    let cipher = state.cipher();
+    state.update_data_and_timer(
+        |data, _| crate::archive::archive(&dir, data, &before, cipher.as_ref()),
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, DateRange, HistoryDay, HistoryRecord};
+    use crate::commands::analysis::get_focus_analysis_impl;
+    use crate::commands::history::get_history_impl;
+    use crate::commands::state_like::TestState;
+
+    /// 构造某天的一条工作记录。
+    fn day(date: &str) -> HistoryDay {
+        HistoryDay {
+            date: date.to_string(),
+            records: vec![
+                HistoryRecord::for_test("工作", "09:00").with_tag_id(Some("tag-1".to_string()))
+            ],
+        }
+    }
+
+    /// 归档后主存储只保留近期历史；历史查询与专注分析仍透明包含归档范围。
+    #[test]
+    fn archive_history_keeps_queries_transparent() {
+        let profile = tempfile::tempdir().unwrap();
+        let today = chrono::Local::now().date_naive();
+        let old = (today - chrono::Duration::days(800))
+            .format("%Y-%m-%d")
+            .to_string();
+        let recent = today.format("%Y-%m-%d").to_string();
+        let state = TestState::new(AppData {
+            history: vec![day(&old), day(&recent)],
+            ..AppData::default()
+        })
+        .with_profile_dir(profile.path());
+
+        assert!(matches!(
+            archive_history_impl(&state, profile.path()),
+            Err(AppError::Validation(_))
+        ));
+
+        state
+            .update_data(|data| {
+                data.settings.archive_after_months = 12;
+                Ok(())
+            })
+            .unwrap();
+        let report = archive_history_impl(&state, profile.path()).unwrap();
+        assert_eq!(report.archived_days, 1);
+        assert_eq!(state.take_backups(), vec!["archive".to_string()]);
+        assert_eq!(state.data_snapshot().history.len(), 1);
+
+        let status = get_archive_status_impl(&state, profile.path()).unwrap();
+        assert_eq!(status.after_months, 12);
+        assert_eq!(status.archived_pomodoros, 1);
+        assert_eq!(status.files.len(), 1);
+
+        let range = DateRange {
+            from: old.clone(),
+            to: recent.clone(),
+        };
+        let days = get_history_impl(&state, &range).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[1].date, old);
+        let analysis = get_focus_analysis_impl(&state, &range).unwrap();
+        assert_eq!(analysis.weekday_counts.iter().sum::<u32>(), 2);
+
+        // 没有待归档的历史时不再备份。
+        let report = archive_history_impl(&state, profile.path()).unwrap();
+        assert_eq!(report.archived_days, 0);
+        assert!(state.take_backups().is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! 审计日志相关命令：查询记录的变更历史、按事件日志重放校验并可选重建数据。
+
+use crate::audit::{AuditEntry, ReplayReport};
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+use super::tags::DEFAULT_TAG;
+
+/// 查询变更历史：`subject` 为事件主体（如 `history:<记录 id>`、`tag:<id>`、`settings`）。
+pub(crate) fn get_change_history_impl<S: CommandState>(
+    state: &S,
+    subject: &str,
+) -> AppResult<Vec<AuditEntry>> {
+    let subject = subject.trim();
+    if subject.is_empty() {
+        return Err(AppError::Validation("查询主体不能为空".to_string()));
+    }
+    state.change_history(subject)
+}
+
+/// 从最近一次基线按事件日志重放并与当前数据比较；`commit` 为 true 且不一致时先备份，再以重放结果替换当前数据。
+pub(crate) fn rebuild_from_event_log_impl<S: CommandState>(
+    state: &S,
+    commit: bool,
+) -> AppResult<ReplayReport> {
+    let events = state.audit_events()?;
+    if events.is_empty() {
+        return Err(AppError::Validation("事件日志为空".to_string()));
+    }
+    let (replayed, rebuilt) = crate::audit::replay(&events, |id| state.audit_snapshot(id))?;
+    let differing_sections = crate::audit::differing_sections(&rebuilt, &state.data_snapshot())?;
+    let matches = differing_sections.is_empty();
+    let committed = commit && !matches;
+
+    if committed {
+        if state.timer_snapshot().is_running {
+            return Err(AppError::Validation(
+                "计时进行中，无法从事件日志重建".to_string(),
+            ));
+        }
+        state.backup_before("rebuild-events")?;
+        let clock = crate::timer::SystemClock;
+        state.update_data_and_timer(
+            |data, timer_runtime| {
+                data.replace_with(rebuilt);
+                if !data.tags.contains(&timer_runtime.current_tag) {
+                    timer_runtime.set_current_tag(DEFAULT_TAG.to_string(), &clock);
+                }
+                Ok(())
+            },
+            true,
+        )?;
+        let _ = state.emit_timer_snapshot();
+    }
+
+    tracing::info!(
+        target: "storage",
+        "已按事件日志重放：events={} matches={} committed={} differing={:?}",
+        replayed,
+        matches,
+        committed,
+        differing_sections
+    );
+    Ok(ReplayReport {
+        events: replayed as u32,
+        matches,
+        differing_sections,
+        committed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, HistoryDay, HistoryRecord};
+    use crate::commands::history::set_history_remark_impl;
+    use crate::commands::state_like::TestState;
+
+    /// 构造带一条当日记录的数据。
+    fn sample_data() -> AppData {
+        AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![HistoryRecord::for_test("工作", "09:00")],
+            }],
+            ..AppData::default()
+        }
+    }
+
+    /// 编辑备注后应能按记录查询到对应的变更事件；空主体被拒绝。
+    #[test]
+    fn change_history_lists_remark_edits() {
+        let state = TestState::new(sample_data());
+        let record_id = state.data_snapshot().history[0].records[0].id.clone();
+        set_history_remark_impl(&state, "2025-01-01".to_string(), 0, "复盘".to_string()).unwrap();
+
+        let entries = get_change_history_impl(&state, &format!(" history:{record_id} ")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, "remarkEdited");
+        assert!(entries[0].summary.contains("复盘"));
+        assert!(get_change_history_impl(&state, "settings")
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            get_change_history_impl(&state, "  "),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// 重放一致时不替换数据；不一致且 `commit` 时先备份再以重放结果替换。
+    #[test]
+    fn rebuild_replaces_diverged_data_after_backup() {
+        let state = TestState::new(sample_data());
+        set_history_remark_impl(&state, "2025-01-01".to_string(), 0, "复盘".to_string()).unwrap();
+
+        let report = rebuild_from_event_log_impl(&state, true).unwrap();
+        assert!(report.matches && !report.committed);
+        assert_eq!(report.events, 2);
+
+        // 模拟日志之外的改动（如直接修改文件）：重放结果与当前数据不一致。
+        state
+            .update_data_and_timer(
+                |data, _| {
+                    data.total_pomodoros = 99;
+                    Ok(())
+                },
+                false,
+            )
+            .unwrap();
+        let report = rebuild_from_event_log_impl(&state, false).unwrap();
+        assert_eq!(report.differing_sections, ["totalPomodoros"]);
+        assert!(state.take_backups().is_empty());
+
+        let report = rebuild_from_event_log_impl(&state, true).unwrap();
+        assert!(report.committed);
+        assert_eq!(state.take_backups(), ["rebuild-events"]);
+        assert_eq!(state.data_snapshot().total_pomodoros, 0);
+        assert_eq!(state.data_snapshot().history[0].records[0].remark, "复盘");
+    }
+}
@@ -0,0 +1,264 @@
+//! 备份相关命令：列出备份、预览备份内容与恢复备份。
+
+use std::path::Path;
+
+use crate::backup::{BackupInfo, BackupPreview};
+use crate::crypto::Cipher;
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+use super::tags::DEFAULT_TAG;
+use super::types::AppSnapshot;
+
+/// 列出备份的内部实现（按创建时间倒序）。
+pub(crate) fn list_backups_impl(dir: &Path) -> AppResult<Vec<BackupInfo>> {
+    crate::backup::list_backups(dir)
+}
+
+/// 预览备份内容的内部实现（不修改当前数据；加密备份需传入当前密钥）。
+pub(crate) fn preview_backup_impl(
+    dir: &Path,
+    file_name: String,
+    cipher: Option<&Cipher>,
+) -> AppResult<BackupPreview> {
+    let (info, file) = crate::backup::read_backup(dir, file_name.trim(), cipher)?;
+    crate::backup::preview_backup(info, &file.data)
+}
+
+/// 恢复备份的内部实现：先备份当前数据，再整体替换为备份内容（历史数据库在同一事务内重写）。
+///
+/// 备份来自旧版本时先执行数据迁移（来自更新版本时拒绝恢复）并修复完整性问题；计时器的当前标签/任务在恢复后不存在时回退为默认值。
+/// 备份中的归档历史写回 `archive_dir`；不含归档历史的旧格式备份沿用现有归档文件，其汇总引用的文件缺失时拒绝恢复。
+pub(crate) fn restore_backup_impl<S: CommandState>(
+    state: &S,
+    dir: &Path,
+    archive_dir: &Path,
+    file_name: String,
+) -> AppResult<AppSnapshot> {
+    let cipher = state.cipher();
+    let (info, file) = crate::backup::read_backup(dir, file_name.trim(), cipher.as_ref())?;
+    let mut restored = file.data;
+    let migration = crate::migrations::migrate(&mut restored)?;
+    let repaired = crate::integrity::repair(&mut restored);
+    if file.archive.is_none() {
+        let missing = crate::archive::missing_years(archive_dir, &restored)?;
+        if !missing.is_empty() {
+            return Err(AppError::Validation(format!(
+                "备份引用的归档文件（{} 年）不存在，无法恢复",
+                missing.join("、")
+            )));
+        }
+    }
+
+    state.backup_before("restore")?;
+    if let Some(archive) = file.archive {
+        crate::archive::replace_all(archive_dir, archive, cipher.as_ref())?;
+    }
+    let clock = crate::timer::SystemClock;
+    state.update_data_and_timer(
+        |data, timer_runtime| {
+            data.replace_with(restored);
+            if !data.tags.contains(&timer_runtime.current_tag) {
+                timer_runtime.set_current_tag(DEFAULT_TAG.to_string(), &clock);
+            }
+            if timer_runtime
+                .current_task_id
+                .as_ref()
+                .is_some_and(|id| !data.tasks.iter().any(|t| &t.id == id))
+            {
+                timer_runtime.current_task_id = None;
+            }
+            Ok(())
+        },
+        true,
+    )?;
+
+    tracing::info!(
+        target: "storage",
+        "已恢复备份：file={} migratedFrom=v{} integrityIssues={}",
+        info.file_name,
+        migration.from_version,
+        repaired.len()
+    );
+    let _ = state.emit_timer_snapshot();
+    Ok(AppSnapshot {
+        data: state.data_snapshot(),
+        timer: state.timer_snapshot(),
+        profile: state.active_profile(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, BackupSettings, HistoryDay, HistoryRecord};
+    use crate::backup::BackupKind;
+    use crate::commands::state_like::TestState;
+    use crate::commands::tags::add_tag_impl;
+    use crate::commands::tags::set_current_tag_impl;
+
+    /// 在临时目录中写入一份定时备份（归档目录为 `dir/archive`）。
+    fn write_backup(dir: &Path, data: &AppData) -> BackupInfo {
+        crate::backup::create_backup(
+            dir,
+            &dir.join("archive"),
+            data,
+            BackupKind::Scheduled,
+            "",
+            &BackupSettings::default(),
+            chrono::Local::now(),
+            None,
+        )
+        .unwrap()
+    }
+
+    /// `restore_backup_impl`：应先写入操作前备份，再整体替换数据，并回退不存在的当前标签。
+    #[test]
+    fn restore_backup_replaces_data_and_resets_missing_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let backed_up = AppData {
+            total_pomodoros: 42,
+            ..AppData::default()
+        };
+        let info = write_backup(dir.path(), &backed_up);
+
+        let state = TestState::new(AppData::default());
+        add_tag_impl(&state, "新标签".to_string()).unwrap();
+        set_current_tag_impl(&state, "新标签".to_string()).unwrap();
+
+        let snapshot = restore_backup_impl(
+            &state,
+            dir.path(),
+            &dir.path().join("archive"),
+            info.file_name,
+        )
+        .unwrap();
+        assert_eq!(snapshot.data.total_pomodoros, 42);
+        assert!(!snapshot.data.tags.iter().any(|t| t == "新标签"));
+        assert_eq!(snapshot.timer.current_tag, DEFAULT_TAG);
+        assert_eq!(state.take_backups(), vec!["restore".to_string()]);
+    }
+
+    /// `preview_backup_impl`/`list_backups_impl`：应返回备份统计且不修改当前数据。
+    #[test]
+    fn preview_and_list_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_backups_impl(dir.path()).unwrap().is_empty());
+
+        let info = write_backup(dir.path(), &AppData::default());
+        assert_eq!(list_backups_impl(dir.path()).unwrap(), vec![info.clone()]);
+
+        let preview = preview_backup_impl(dir.path(), info.file_name.clone(), None).unwrap();
+        assert_eq!(preview.info, info);
+        assert_eq!(preview.tag_count, 4);
+        assert_eq!(preview.history_records, 0);
+        assert!(!preview.migration.is_needed());
+    }
+
+    /// `restore_backup_impl`：备份由更新版本写入时应拒绝恢复，且不写入操作前备份。
+    #[test]
+    fn restore_backup_rejects_newer_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = write_backup(
+            dir.path(),
+            &AppData {
+                schema_version: crate::migrations::CURRENT_SCHEMA_VERSION + 1,
+                ..AppData::default()
+            },
+        );
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            restore_backup_impl(
+                &state,
+                dir.path(),
+                &dir.path().join("archive"),
+                info.file_name.clone()
+            ),
+            Err(AppError::SchemaTooNew { .. })
+        ));
+        assert!(preview_backup_impl(dir.path(), info.file_name, None).is_err());
+        assert!(state.take_backups().is_empty());
+    }
+
+    /// `restore_backup_impl`：文件不存在时应拒绝，且不写入操作前备份。
+    #[test]
+    fn restore_backup_rejects_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            restore_backup_impl(
+                &state,
+                dir.path(),
+                &dir.path().join("archive"),
+                "backup-20250101-000000000-scheduled.json".to_string()
+            ),
+            Err(AppError::Validation(_))
+        ));
+        assert!(state.take_backups().is_empty());
+    }
+
+    /// 带一天已归档历史的数据（归档文件写入 `archive_dir`）。
+    fn archived_data(archive_dir: &Path) -> AppData {
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2024-06-01".to_string(),
+                records: vec![HistoryRecord::for_test("工作", "09:00")],
+            }],
+            ..AppData::default()
+        };
+        crate::archive::archive(archive_dir, &mut data, "2025-01-01", None).unwrap();
+        assert!(data.history.is_empty());
+        data
+    }
+
+    /// `restore_backup_impl`：备份包含归档历史，恢复时写回归档文件并删除备份中没有的年份。
+    #[test]
+    fn restore_backup_restores_archive_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = dir.path().join("archive");
+        let info = write_backup(dir.path(), &archived_data(&archive_dir));
+        std::fs::remove_dir_all(&archive_dir).unwrap();
+        std::fs::create_dir_all(&archive_dir).unwrap();
+        std::fs::write(archive_dir.join("history-2020.json.gz"), b"stale").unwrap();
+
+        let state = TestState::new(AppData::default());
+        let snapshot =
+            restore_backup_impl(&state, dir.path(), &archive_dir, info.file_name).unwrap();
+        let status = crate::archive::status(&archive_dir, &snapshot.data).unwrap();
+        assert_eq!(
+            status
+                .files
+                .iter()
+                .map(|f| f.year.as_str())
+                .collect::<Vec<_>>(),
+            ["2024"]
+        );
+        assert_eq!(status.archived_pomodoros, 1);
+    }
+
+    /// `restore_backup_impl`：不含归档历史的旧格式备份在所引用的归档文件缺失时拒绝恢复。
+    #[test]
+    fn restore_backup_rejects_legacy_backup_with_missing_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = dir.path().join("archive");
+        let info = write_backup(dir.path(), &archived_data(&archive_dir));
+        let path = dir.path().join(&info.file_name);
+        let mut value: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        value["version"] = 1.into();
+        value.as_object_mut().unwrap().remove("archive");
+        std::fs::write(&path, serde_json::to_vec(&value).unwrap()).unwrap();
+
+        let state = TestState::new(AppData::default());
+        restore_backup_impl(&state, dir.path(), &archive_dir, info.file_name.clone()).unwrap();
+        assert_eq!(state.take_backups(), vec!["restore".to_string()]);
+
+        std::fs::remove_dir_all(&archive_dir).unwrap();
+        assert!(matches!(
+            restore_backup_impl(&state, dir.path(), &archive_dir, info.file_name),
+            Err(AppError::Validation(message)) if message.contains("2024")
+        ));
+        assert!(state.take_backups().is_empty());
+    }
+}
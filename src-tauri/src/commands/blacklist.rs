@@ -1,6 +1,6 @@
 //! 黑名单相关命令：设置黑名单、专注期锁定校验等。
 
-use crate::app_data::BlacklistItem;
+use crate::app_data::{BlacklistItem, Section};
 use crate::errors::{AppError, AppResult};
 
 use super::state_like::CommandState;
@@ -24,8 +24,8 @@ fn set_blacklist_impl_with_killer<S: CommandState>(
 ) -> AppResult<Vec<BlacklistItem>> {
     validate_blacklist_items(&blacklist)?;
 
-    let (added_names, should_kill_added) = state.update_data_and_timer(
-        |data, timer_runtime| {
+    let (added_names, should_kill_added) =
+        state.update_undoable("修改黑名单", |data, timer_runtime| {
             let locked = timer_runtime.blacklist_locked();
 
             if locked {
@@ -54,15 +54,14 @@ fn set_blacklist_impl_with_killer<S: CommandState>(
                 .map(|b| b.name.clone())
                 .collect();
 
+            data.touch_section(Section::Blacklist);
             data.blacklist = blacklist.clone();
 
             // PRD：番茄周期内可动态添加并立即终止。
             let should_kill = locked && !added.is_empty();
 
             Ok((added, should_kill))
-        },
-        true,
-    )?;
+        })?;
 
     if should_kill_added {
         tracing::info!(target: "blacklist", "专注期新增黑名单条目，立即尝试终止：{:?}", added_names);
@@ -154,7 +153,7 @@ mod tests {
             .update_data_and_timer(
                 |data, timer_runtime| {
                     let clock = crate::timer::SystemClock;
-                    timer_runtime.start(&data.settings, &clock);
+                    timer_runtime.start(data, &clock);
                     Ok(())
                 },
                 false,
@@ -0,0 +1,115 @@
+//! 数据根目录命令：查询当前位置，登记迁移到默认目录、自定义目录或便携目录（迁移在重启后执行）。
+
+use crate::data_root::{self, DataRootInfo, PendingRelocation, RootLocator};
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+use super::types::RelocateRequest;
+
+/// 获取数据根目录信息（附带本次启动迁移失败的原因）。
+pub(crate) fn get_data_root_impl(
+    locator: &RootLocator,
+    last_error: Option<String>,
+) -> DataRootInfo {
+    DataRootInfo {
+        last_error,
+        ..locator.info()
+    }
+}
+
+/// 登记迁移：计时进行中拒绝；校验目标目录后写入待迁移请求，由调用方重启应用执行。
+pub(crate) fn request_relocation_impl<S: CommandState>(
+    state: &S,
+    locator: &RootLocator,
+    request: &RelocateRequest,
+) -> AppResult<PendingRelocation> {
+    if state.timer_snapshot().is_running {
+        return Err(AppError::Validation(
+            "计时进行中，无法迁移数据目录".to_string(),
+        ));
+    }
+    let from = locator.root();
+    let to = locator.target_root(request.mode, request.path.as_deref())?;
+    data_root::check_target(&from, &to)?;
+    let pending = PendingRelocation {
+        mode: request.mode,
+        from,
+        to,
+    };
+    data_root::save_pending(&pending)?;
+    tracing::info!(
+        target: "storage",
+        "已登记数据目录迁移：{} -> {}",
+        pending.from.to_string_lossy(),
+        pending.to.to_string_lossy()
+    );
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::AppData;
+    use crate::commands::state_like::TestState;
+    use crate::data_root::DataRootMode;
+    use crate::timer::SystemClock;
+
+    /// `request_relocation_impl`：登记自定义目录迁移，并在信息中显示待迁移目标。
+    #[test]
+    fn request_relocation_records_pending_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let locator = RootLocator::new(dir.path().join("default"), None);
+        let state = TestState::new(AppData::default());
+        let target = dir.path().join("usb");
+        let request = RelocateRequest {
+            mode: DataRootMode::Custom,
+            path: Some(target.to_string_lossy().to_string()),
+        };
+
+        let pending = request_relocation_impl(&state, &locator, &request).unwrap();
+        assert_eq!(pending.to, target);
+        let info = get_data_root_impl(&locator, None);
+        assert_eq!(info.mode, DataRootMode::Default);
+        assert_eq!(
+            info.pending_target,
+            Some(target.to_string_lossy().to_string())
+        );
+    }
+
+    /// `request_relocation_impl`：计时中、相对路径、无法便携与目标相同时均拒绝。
+    #[test]
+    fn request_relocation_rejects_invalid_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let locator = RootLocator::new(dir.path().join("default"), None);
+        let state = TestState::new(AppData::default());
+        for (mode, path) in [
+            (DataRootMode::Custom, Some("relative/dir".to_string())),
+            (DataRootMode::Custom, None),
+            (DataRootMode::Portable, None),
+            (DataRootMode::Default, None),
+        ] {
+            let request = RelocateRequest { mode, path };
+            assert!(matches!(
+                request_relocation_impl(&state, &locator, &request),
+                Err(AppError::Validation(_))
+            ));
+        }
+
+        state
+            .update_timer(|t, d| {
+                t.start(d, &SystemClock);
+                Ok(())
+            })
+            .unwrap();
+        let request = RelocateRequest {
+            mode: DataRootMode::Custom,
+            path: Some(dir.path().join("usb").to_string_lossy().to_string()),
+        };
+        assert!(matches!(
+            request_relocation_impl(&state, &locator, &request),
+            Err(AppError::Validation(_))
+        ));
+        assert!(locator.info().pending_target.is_none());
+    }
+}
@@ -24,8 +24,14 @@ pub(crate) fn debug_generate_history_impl<S: CommandState>(state: &S, days: u32)
             data.tags.clone()
         };
 
-        let (history, count) = generate_history_dev(days, &settings, &tags);
+        let (mut history, count) = generate_history_dev(days, &settings, &tags);
+        for day in &mut history {
+            for r in &mut day.records {
+                r.tag_id = data.tag_id_by_path(&r.tag);
+            }
+        }
         data.history_dev = history;
+        data.touch_all_records();
         generated = count;
         Ok(())
     })?;
@@ -49,6 +55,7 @@ pub(crate) fn debug_generate_history_impl<S: CommandState>(
 pub(crate) fn debug_clear_history_impl<S: CommandState>(state: &S) -> AppResult<bool> {
     state.update_data(|data| {
         data.history_dev = Vec::new();
+        data.touch_all_records();
         Ok(())
     })?;
 
@@ -110,12 +117,20 @@ fn generate_history_dev(days: u32, settings: &Settings, tags: &[String]) -> (Vec
             let tag = pick_random_tag(&mut rng, tags);
 
             records.push(HistoryRecord {
+                id: uuid::Uuid::new_v4().to_string(),
                 tag,
+                tag_id: None,
+                task_id: None,
                 start_time,
                 end_time: Some(end_time),
+                started_at: None,
+                ended_at: None,
                 duration,
                 phase,
                 remark: String::new(),
+                elapsed_seconds: None,
+                pauses: Vec::new(),
+                skipped: false,
             });
         }
 
@@ -0,0 +1,113 @@
+//! 数据加密相关命令：查询状态、启用加密、更换口令与关闭加密（口令错误时返回 `AppError::WrongPassphrase`）。
+
+use crate::crypto::Cipher;
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+
+/// 当前配置是否已启用加密。
+pub(crate) fn encryption_status_impl<S: CommandState>(state: &S) -> bool {
+    state.cipher().is_some()
+}
+
+/// 启用加密：以新口令派生密钥，并将 store、历史数据库与备份重写为密文。
+pub(crate) fn enable_encryption_impl<S: CommandState>(
+    state: &S,
+    passphrase: &str,
+) -> AppResult<()> {
+    if state.cipher().is_some() {
+        return Err(AppError::Validation("数据已加密".to_string()));
+    }
+    state.set_cipher(Some(Cipher::generate(passphrase)?))?;
+    tracing::info!(target: "storage", "已启用数据加密：profile={}", state.active_profile().id);
+    Ok(())
+}
+
+/// 更换口令：校验旧口令后以新口令（新盐）重写全部数据。
+pub(crate) fn change_passphrase_impl<S: CommandState>(
+    state: &S,
+    old_passphrase: &str,
+    new_passphrase: &str,
+) -> AppResult<()> {
+    verify_current(state, old_passphrase)?;
+    state.set_cipher(Some(Cipher::generate(new_passphrase)?))?;
+    tracing::info!(target: "storage", "已更换数据加密口令：profile={}", state.active_profile().id);
+    Ok(())
+}
+
+/// 关闭加密：校验口令后将全部数据重写为明文。
+pub(crate) fn disable_encryption_impl<S: CommandState>(
+    state: &S,
+    passphrase: &str,
+) -> AppResult<()> {
+    verify_current(state, passphrase)?;
+    state.set_cipher(None)?;
+    tracing::info!(target: "storage", "已关闭数据加密：profile={}", state.active_profile().id);
+    Ok(())
+}
+
+/// 校验口令与当前密钥一致（未启用加密时拒绝）。
+fn verify_current<S: CommandState>(state: &S, passphrase: &str) -> AppResult<()> {
+    let cipher = state
+        .cipher()
+        .ok_or_else(|| AppError::Validation("数据尚未加密".to_string()))?;
+    if !cipher.verify(passphrase) {
+        return Err(AppError::WrongPassphrase);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::AppData;
+    use crate::commands::state_like::TestState;
+
+    /// 启用 → 更换 → 关闭：旧口令错误时拒绝且密钥保持不变。
+    #[test]
+    fn enable_change_and_disable_flow() {
+        let state = TestState::new(AppData::default());
+        assert!(!encryption_status_impl(&state));
+        assert!(matches!(
+            enable_encryption_impl(&state, "short"),
+            Err(AppError::Validation(_))
+        ));
+
+        enable_encryption_impl(&state, "first-pass").unwrap();
+        assert!(encryption_status_impl(&state));
+        assert!(matches!(
+            enable_encryption_impl(&state, "first-pass"),
+            Err(AppError::Validation(_))
+        ));
+
+        assert!(matches!(
+            change_passphrase_impl(&state, "wrong-pass", "second-pass"),
+            Err(AppError::WrongPassphrase)
+        ));
+        assert!(state.cipher().unwrap().verify("first-pass"));
+        change_passphrase_impl(&state, "first-pass", "second-pass").unwrap();
+        assert!(state.cipher().unwrap().verify("second-pass"));
+
+        assert!(matches!(
+            disable_encryption_impl(&state, "first-pass"),
+            Err(AppError::WrongPassphrase)
+        ));
+        disable_encryption_impl(&state, "second-pass").unwrap();
+        assert!(!encryption_status_impl(&state));
+    }
+
+    /// 未启用加密时更换口令与关闭加密应被拒绝。
+    #[test]
+    fn change_and_disable_require_encryption() {
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            change_passphrase_impl(&state, "first-pass", "second-pass"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            disable_encryption_impl(&state, "first-pass"),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -2,7 +2,7 @@
 
 use serde::Serialize;
 
-use crate::app_data::{DateRange, HistoryDay, HistoryRecord, Phase};
+use crate::app_data::{DateRange, HistoryDay, HistoryRecord, PauseSegment, Phase};
 use crate::errors::{AppError, AppResult};
 
 use super::history::get_history_impl;
@@ -11,6 +11,8 @@ use super::types::{ExportField, ExportFormat, ExportRequest};
 use super::validation::validate_date_range;
 
 /// 将导出请求写入指定路径（用于测试与复用：不依赖系统文件对话框）。
+///
+/// 请求携带口令时写入 `{"sealed": ...}` 密文，明文不落盘。
 pub(crate) fn export_history_to_path<S: CommandState>(
     state: &S,
     request: &ExportRequest,
@@ -22,10 +24,16 @@ pub(crate) fn export_history_to_path<S: CommandState>(
     let days = get_history_impl(state, &request.range)?;
     let export_rows = flatten_days_to_rows(&days);
 
-    match request.format {
-        ExportFormat::Csv => export_csv(path, &fields, &export_rows)?,
-        ExportFormat::Json => export_json(path, &request.range, &export_rows)?,
-    }
+    let content = match request.format {
+        ExportFormat::Csv => export_csv(&fields, &export_rows)?,
+        ExportFormat::Json => export_json(&request.range, &export_rows)?,
+    };
+    let content = match request.passphrase.as_deref().filter(|p| !p.is_empty()) {
+        Some(passphrase) => crate::crypto::seal_with_passphrase(passphrase, &content)?,
+        None => content,
+    };
+    std::fs::write(path, content)
+        .map_err(|e| AppError::Invariant(format!("写入导出文件失败：{e}")))?;
     Ok(())
 }
 
@@ -91,11 +99,9 @@ fn derive_end_time_hhmm(start_time: &str, duration_minutes: u32) -> Option<Strin
     Some(format!("{:02}:{:02}", hh, mm))
 }
 
-/// 导出 CSV 文件（字段可配置）。
-fn export_csv(path: &std::path::Path, fields: &[ExportField], rows: &[ExportRow]) -> AppResult<()> {
-    let file = std::fs::File::create(path)
-        .map_err(|e| AppError::Invariant(format!("创建导出文件失败：{e}")))?;
-    let mut wtr = csv::Writer::from_writer(file);
+/// 生成 CSV 导出内容（字段可配置）。
+fn export_csv(fields: &[ExportField], rows: &[ExportRow]) -> AppResult<Vec<u8>> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
 
     let header: Vec<&str> = fields
         .iter()
@@ -107,6 +113,11 @@ fn export_csv(path: &std::path::Path, fields: &[ExportField], rows: &[ExportRow]
             ExportField::Tag => "tag",
             ExportField::Phase => "phase",
             ExportField::Remark => "remark",
+            ExportField::ElapsedSeconds => "elapsed_seconds",
+            ExportField::PausedSeconds => "paused_seconds",
+            ExportField::Skipped => "skipped",
+            ExportField::StartedAt => "started_at",
+            ExportField::EndedAt => "ended_at",
         })
         .collect();
     wtr.write_record(&header)
@@ -132,15 +143,19 @@ fn export_csv(path: &std::path::Path, fields: &[ExportField], rows: &[ExportRow]
                     Phase::LongBreak => "longBreak".to_string(),
                 },
                 ExportField::Remark => row.record.remark.clone(),
+                ExportField::ElapsedSeconds => row.record.active_seconds().to_string(),
+                ExportField::PausedSeconds => row.record.paused_seconds().to_string(),
+                ExportField::Skipped => row.record.skipped.to_string(),
+                ExportField::StartedAt => row.record.started_at.clone().unwrap_or_default(),
+                ExportField::EndedAt => row.record.ended_at.clone().unwrap_or_default(),
             };
             record.push(v);
         }
         wtr.write_record(&record)
             .map_err(|e| AppError::Invariant(format!("写入 CSV 行失败：{e}")))?;
     }
-    wtr.flush()
-        .map_err(|e| AppError::Invariant(format!("写入 CSV 失败：{e}")))?;
-    Ok(())
+    wtr.into_inner()
+        .map_err(|e| AppError::Invariant(format!("写入 CSV 失败：{e}")))
 }
 
 /// JSON 导出文件顶层结构。
@@ -159,14 +174,20 @@ struct JsonExportRecord {
     date: String,
     start_time: String,
     end_time: String,
+    started_at: Option<String>,
+    ended_at: Option<String>,
     duration: u32,
     tag: String,
     phase: String,
     remark: String,
+    elapsed_seconds: u64,
+    paused_seconds: u64,
+    skipped: bool,
+    pauses: Vec<PauseSegment>,
 }
 
-/// 导出 JSON 文件（字段固定为 PRD v2 示例的 superset）。
-fn export_json(path: &std::path::Path, range: &DateRange, rows: &[ExportRow]) -> AppResult<()> {
+/// 生成 JSON 导出内容（字段固定为 PRD v2 示例的 superset）。
+fn export_json(range: &DateRange, rows: &[ExportRow]) -> AppResult<Vec<u8>> {
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
     let mut records: Vec<JsonExportRecord> = Vec::new();
     for row in rows {
@@ -186,10 +207,16 @@ fn export_json(path: &std::path::Path, range: &DateRange, rows: &[ExportRow]) ->
             date: row.date.clone(),
             start_time: row.record.start_time.clone(),
             end_time,
+            started_at: row.record.started_at.clone(),
+            ended_at: row.record.ended_at.clone(),
             duration: row.record.duration,
             tag: row.record.tag.clone(),
             phase,
             remark: row.record.remark.clone(),
+            elapsed_seconds: row.record.active_seconds(),
+            paused_seconds: row.record.paused_seconds(),
+            skipped: row.record.skipped,
+            pauses: row.record.pauses.clone(),
         });
     }
 
@@ -199,9 +226,7 @@ fn export_json(path: &std::path::Path, range: &DateRange, rows: &[ExportRow]) ->
         records,
     };
 
-    let json = serde_json::to_string_pretty(&out)?;
-    std::fs::write(path, json).map_err(|e| AppError::Invariant(format!("写入 JSON 失败：{e}")))?;
-    Ok(())
+    Ok(serde_json::to_vec_pretty(&out)?)
 }
 
 #[cfg(test)]
@@ -232,22 +257,10 @@ mod tests {
         let days = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![
-                HistoryRecord {
-                    tag: "A".to_string(),
-                    start_time: "09:00".to_string(),
-                    end_time: None,
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "B".to_string(),
-                    start_time: "10:00".to_string(),
-                    end_time: None,
-                    duration: 5,
-                    phase: Phase::ShortBreak,
-                    remark: String::new(),
-                },
+                HistoryRecord::for_test("A", "09:00"),
+                HistoryRecord::for_test("B", "10:00")
+                    .with_duration(5)
+                    .with_phase(Phase::ShortBreak),
             ],
         }];
 
@@ -261,24 +274,16 @@ mod tests {
     /// `export_csv`：应按字段顺序写入表头与行，并在缺失 end_time 时推导。
     #[test]
     fn export_csv_writes_expected_content() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("out.csv");
-
         let days = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: Phase::Work,
                 remark: "hi".to_string(),
+                ..HistoryRecord::for_test("A", "09:00")
             }],
         }];
         let rows = flatten_days_to_rows(&days);
 
-        export_csv(
-            &path,
+        let content = export_csv(
             &[
                 ExportField::Date,
                 ExportField::StartTime,
@@ -292,7 +297,7 @@ mod tests {
         )
         .unwrap();
 
-        let content = std::fs::read_to_string(&path).unwrap();
+        let content = String::from_utf8(content).unwrap();
         let lines: Vec<&str> = content.lines().collect();
         assert_eq!(
             lines[0],
@@ -301,12 +306,53 @@ mod tests {
         assert_eq!(lines[1], "2025-01-01,09:00,09:25,25,A,work,hi");
     }
 
+    /// `export_csv`：实际计时/暂停/跳过列应取自记录（旧数据按 duration 回填实际计时）。
+    #[test]
+    fn export_csv_writes_elapsed_pause_and_skip_columns() {
+        let days = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![
+                HistoryRecord {
+                    pauses: vec![PauseSegment {
+                        start_time: "09:10".to_string(),
+                        end_time: "09:12".to_string(),
+                        seconds: 120,
+                    }],
+                    ..HistoryRecord::for_test("A", "09:00")
+                },
+                HistoryRecord {
+                    end_time: Some("09:29".to_string()),
+                    elapsed_seconds: Some(150),
+                    skipped: true,
+                    ..HistoryRecord::for_test("A", "09:27")
+                        .with_duration(2)
+                        .with_phase(Phase::ShortBreak)
+                },
+            ],
+        }];
+        let rows = flatten_days_to_rows(&days);
+
+        let content = export_csv(
+            &[
+                ExportField::Phase,
+                ExportField::ElapsedSeconds,
+                ExportField::PausedSeconds,
+                ExportField::Skipped,
+            ],
+            &rows,
+        )
+        .unwrap();
+
+        let content = String::from_utf8(content).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], "phase,elapsed_seconds,paused_seconds,skipped");
+        assert_eq!(lines[1], "work,1500,120,false");
+        assert_eq!(lines[2], "shortBreak,150,0,true");
+    }
+
     /// `export_json`：应写入可解析 JSON，且包含 range 与 records。
     #[test]
     fn export_json_writes_parseable_json() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("out.json");
-
         let range = DateRange {
             from: "2025-01-01".to_string(),
             to: "2025-01-07".to_string(),
@@ -314,24 +360,22 @@ mod tests {
         let days = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: Phase::Work,
-                remark: String::new(),
+                started_at: Some("2025-01-01T09:00:00+08:00".to_string()),
+                ended_at: Some("2025-01-01T09:25:00+08:00".to_string()),
+                ..HistoryRecord::for_test("A", "09:00")
             }],
         }];
         let rows = flatten_days_to_rows(&days);
 
-        export_json(&path, &range, &rows).unwrap();
-        let content = std::fs::read_to_string(&path).unwrap();
+        let content = String::from_utf8(export_json(&range, &rows).unwrap()).unwrap();
         let v: serde_json::Value = serde_json::from_str(&content).unwrap();
         assert_eq!(v["range"]["from"], "2025-01-01");
         assert_eq!(v["range"]["to"], "2025-01-07");
         assert_eq!(v["records"].as_array().unwrap().len(), 1);
         assert_eq!(v["records"][0]["date"], "2025-01-01");
         assert_eq!(v["records"][0]["endTime"], "09:25");
+        assert_eq!(v["records"][0]["startedAt"], "2025-01-01T09:00:00+08:00");
+        assert_eq!(v["records"][0]["endedAt"], "2025-01-01T09:25:00+08:00");
     }
 
     /// `normalize_export_fields`：当入参为空时应回退到默认字段集合。
@@ -382,14 +426,7 @@ mod tests {
         let mut data = AppData::default();
         data.history_dev = vec![HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: Phase::Work,
-                remark: String::new(),
-            }],
+            records: vec![HistoryRecord::for_test("A", "09:00")],
         }];
         let state = TestState::new(data);
 
@@ -402,6 +439,7 @@ mod tests {
                     to: "2025-01-01".to_string(),
                 },
                 fields: Vec::new(),
+                passphrase: None,
             },
             &path,
         )
@@ -422,14 +460,7 @@ mod tests {
         let mut data = AppData::default();
         data.history_dev = vec![HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: Phase::Work,
-                remark: String::new(),
-            }],
+            records: vec![HistoryRecord::for_test("A", "09:00")],
         }];
         let state = TestState::new(data);
 
@@ -442,6 +473,7 @@ mod tests {
                     to: "2025-01-01".to_string(),
                 },
                 fields: Vec::new(),
+                passphrase: None,
             },
             &path,
         )
@@ -460,30 +492,15 @@ mod tests {
         data.history = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![
-                HistoryRecord {
-                    tag: "A".to_string(),
-                    start_time: "09:00".to_string(),
-                    end_time: Some("09:25".to_string()),
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "B".to_string(),
-                    start_time: "09:30".to_string(),
-                    end_time: Some("09:35".to_string()),
-                    duration: 5,
-                    phase: Phase::ShortBreak,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "C".to_string(),
-                    start_time: "10:00".to_string(),
-                    end_time: Some("10:15".to_string()),
-                    duration: 15,
-                    phase: Phase::LongBreak,
-                    remark: String::new(),
-                },
+                HistoryRecord::for_test("A", "09:00").with_end("09:25"),
+                HistoryRecord::for_test("B", "09:30")
+                    .with_duration(5)
+                    .with_phase(Phase::ShortBreak)
+                    .with_end("09:35"),
+                HistoryRecord::for_test("C", "10:00")
+                    .with_duration(15)
+                    .with_phase(Phase::LongBreak)
+                    .with_end("10:15"),
             ],
         }];
         let state = crate::commands::state_like::TestState::new(data);
@@ -497,6 +514,7 @@ mod tests {
             },
             format: ExportFormat::Csv,
             fields: Vec::new(),
+            passphrase: None,
         };
 
         export_history_to_path(&state, &request, &path).unwrap();
@@ -513,30 +531,15 @@ mod tests {
         data.history = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![
-                HistoryRecord {
-                    tag: "A".to_string(),
-                    start_time: "09:00".to_string(),
-                    end_time: Some("09:25".to_string()),
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "B".to_string(),
-                    start_time: "09:30".to_string(),
-                    end_time: Some("09:35".to_string()),
-                    duration: 5,
-                    phase: Phase::ShortBreak,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "C".to_string(),
-                    start_time: "10:00".to_string(),
-                    end_time: Some("10:15".to_string()),
-                    duration: 15,
-                    phase: Phase::LongBreak,
-                    remark: String::new(),
-                },
+                HistoryRecord::for_test("A", "09:00").with_end("09:25"),
+                HistoryRecord::for_test("B", "09:30")
+                    .with_duration(5)
+                    .with_phase(Phase::ShortBreak)
+                    .with_end("09:35"),
+                HistoryRecord::for_test("C", "10:00")
+                    .with_duration(15)
+                    .with_phase(Phase::LongBreak)
+                    .with_end("10:15"),
             ],
         }];
         let state = crate::commands::state_like::TestState::new(data);
@@ -550,6 +553,7 @@ mod tests {
             },
             format: ExportFormat::Json,
             fields: Vec::new(),
+            passphrase: None,
         };
 
         export_history_to_path(&state, &request, &path).unwrap();
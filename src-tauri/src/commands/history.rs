@@ -1,24 +1,31 @@
-//! 历史相关命令：查询历史、编辑备注等。
+//! 历史相关命令：查询历史、编辑备注，以及手动补录/编辑/删除/拆分/合并记录。
 
-use crate::app_data::{DateRange, HistoryDay, HistoryRecord};
+use chrono::TimeZone as _;
+
+use crate::app_data::{
+    normalize_tag_path, AppData, DateRange, HistoryDay, HistoryRecord, Phase, Section,
+};
+use crate::audit::DomainEvent;
 use crate::errors::{AppError, AppResult};
 
 use super::state_like::CommandState;
-use super::validation::{history_for_ui, history_for_ui_mut, validate_date_range, validate_ymd};
+use super::types::{AppSnapshot, HistoryRecordInput};
+use super::validation::{
+    history_for_ui, history_for_ui_is_dev, history_for_ui_mut, validate_date_range, validate_hhmm,
+    validate_ymd,
+};
+
+/// 手动记录的最长时长（分钟）。
+const MAX_RECORD_MINUTES: u32 = 24 * 60;
 
-/// 获取历史的内部实现：校验日期范围后按 `YYYY-MM-DD` 字符串过滤（闭区间）。
+/// 获取历史的内部实现：校验日期范围后按 `YYYY-MM-DD` 查询历史数据库（闭区间）。
 pub(crate) fn get_history_impl<S: CommandState>(
     state: &S,
     range: &DateRange,
 ) -> AppResult<Vec<HistoryDay>> {
     validate_date_range(range)?;
 
-    let data = state.data_snapshot();
-    let mut out: Vec<HistoryDay> = history_for_ui(&data)
-        .iter()
-        .filter(|d| d.date >= range.from && d.date <= range.to)
-        .cloned()
-        .collect();
+    let mut out = state.history_in_range(&range.from, &range.to)?;
 
     // 让 UI 的“默认本周”更自然：按日期倒序展示。
     out.sort_by(|a, b| b.date.cmp(&a.date));
@@ -36,15 +43,19 @@ pub(crate) fn set_history_remark_impl<S: CommandState>(
     validate_ymd(&date)?;
 
     let remark = remark.trim().to_string();
-    state.update_data(|data| {
-        let list = history_for_ui_mut(data);
-        let Some(day) = list.iter_mut().find(|d| d.date == date) else {
-            return Err(AppError::Validation("找不到指定日期的历史记录".to_string()));
+    let description = format!("编辑 {date} 第 {} 条记录的备注", record_index + 1);
+    state.update_undoable(&description, |data, _| {
+        locate(history_for_ui(data), &date, record_index)?;
+        let dev = history_for_ui_is_dev(data);
+        let record = &mut data.history_day_mut(dev, &date).records[record_index];
+        let event = DomainEvent::RemarkEdited {
+            dev,
+            date: date.clone(),
+            record_id: record.id.clone(),
+            from: std::mem::replace(&mut record.remark, remark.clone()),
+            to: remark.clone(),
         };
-        if record_index >= day.records.len() {
-            return Err(AppError::Validation("历史记录索引超出范围".to_string()));
-        }
-        day.records[record_index].remark = remark.clone();
+        data.record_event(event);
         Ok(())
     })?;
 
@@ -57,14 +68,493 @@ pub(crate) fn set_history_remark_impl<S: CommandState>(
     Ok(day.records[record_index].clone())
 }
 
+/// 手动补录一条历史记录（用于应用关闭或离线期间的番茄）。
+pub(crate) fn add_history_record_impl<S: CommandState>(
+    state: &S,
+    input: &HistoryRecordInput,
+) -> AppResult<AppSnapshot> {
+    let (date, record) = build_record(input, None)?;
+    let description = format!("补录 {date} {} 的记录", record.start_time);
+    state.update_undoable(&description, |data, _| {
+        check_overlap(history_for_ui(data), &date, &record, None)?;
+        let record = insert_record(data, date.clone(), record);
+        let event = DomainEvent::RecordAdded {
+            dev: history_for_ui_is_dev(data),
+            date: date.clone(),
+            record,
+        };
+        data.record_event(event);
+        Ok(())
+    })?;
+    tracing::info!(target: "storage", "补录历史记录：date={}", date);
+    snapshot_after_edit(state)
+}
+
+/// 编辑一条历史记录（标签、日期、开始时间、时长、阶段与备注）。
+pub(crate) fn update_history_record_impl<S: CommandState>(
+    state: &S,
+    date: String,
+    record_index: usize,
+    input: &HistoryRecordInput,
+) -> AppResult<AppSnapshot> {
+    let date = date.trim().to_string();
+    validate_ymd(&date)?;
+    let description = format!("编辑 {date} 第 {} 条记录", record_index + 1);
+    state.update_undoable(&description, |data, _| {
+        let previous = locate(history_for_ui(data), &date, record_index)?.clone();
+        let (new_date, record) = build_record(input, Some((&date, &previous)))?;
+        check_overlap(
+            history_for_ui(data),
+            &new_date,
+            &record,
+            Some((&date, record_index)),
+        )?;
+        remove_record(data, &date, record_index)?;
+        let record = insert_record(data, new_date.clone(), record);
+        record_updated(data, &date, new_date, record);
+        Ok(())
+    })?;
+    tracing::info!(target: "storage", "编辑历史记录：date={} index={}", date, record_index);
+    snapshot_after_edit(state)
+}
+
+/// 删除一条历史记录（工作记录会同步扣减累计番茄数）。
+pub(crate) fn delete_history_record_impl<S: CommandState>(
+    state: &S,
+    date: String,
+    record_index: usize,
+) -> AppResult<AppSnapshot> {
+    let date = date.trim().to_string();
+    validate_ymd(&date)?;
+    let description = format!("删除 {date} 第 {} 条记录", record_index + 1);
+    state.update_undoable(&description, |data, _| {
+        let removed = remove_record(data, &date, record_index)?;
+        let event = DomainEvent::RecordDeleted {
+            dev: history_for_ui_is_dev(data),
+            date: date.clone(),
+            record_id: removed.id,
+        };
+        data.record_event(event);
+        Ok(())
+    })?;
+    tracing::info!(target: "storage", "删除历史记录：date={} index={}", date, record_index);
+    snapshot_after_edit(state)
+}
+
+/// 将一条记录在专注第 `minutes` 分钟处拆分为前后两条（标签、阶段与备注沿用原记录）。
+///
+/// 拆分点之前的暂停归入前一条并使拆分时刻相应后移；暂停按以开始时刻为基准的真实时刻划分（可跨午夜）。
+pub(crate) fn split_history_record_impl<S: CommandState>(
+    state: &S,
+    date: String,
+    record_index: usize,
+    minutes: u32,
+) -> AppResult<AppSnapshot> {
+    let date = date.trim().to_string();
+    validate_ymd(&date)?;
+    let description = format!("拆分 {date} 第 {} 条记录", record_index + 1);
+    state.update_undoable(&description, |data, _| {
+        let record = with_instants(&date, locate(history_for_ui(data), &date, record_index)?);
+        if minutes == 0 || minutes >= record.duration {
+            return Err(AppError::Validation(format!(
+                "拆分点需在 1-{} 分钟之间",
+                record.duration.saturating_sub(1)
+            )));
+        }
+        let start = record_start(&date, &record)?;
+        let mut pauses = record
+            .pauses
+            .iter()
+            .map(|p| Ok((pause_start(start, &p.start_time)?, p.clone())))
+            .collect::<AppResult<Vec<_>>>()?;
+        pauses.sort_by_key(|(at, _)| *at);
+        let mut middle = start + chrono::Duration::minutes(i64::from(minutes));
+        let (mut first_pauses, mut second_pauses) = (Vec::new(), Vec::new());
+        for (at, pause) in pauses {
+            if at < middle {
+                middle += chrono::Duration::seconds(pause.seconds as i64);
+                first_pauses.push(pause);
+            } else {
+                second_pauses.push(pause);
+            }
+        }
+        let middle_time = middle.format("%H:%M").to_string();
+        let first = HistoryRecord {
+            end_time: Some(middle_time.clone()),
+            ended_at: Some(middle.to_rfc3339()),
+            duration: minutes,
+            elapsed_seconds: Some(u64::from(minutes) * 60),
+            pauses: first_pauses,
+            ..record.clone()
+        };
+        let second = HistoryRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            start_time: middle_time,
+            started_at: Some(middle.to_rfc3339()),
+            duration: record.duration - minutes,
+            elapsed_seconds: Some(
+                record
+                    .active_seconds()
+                    .saturating_sub(u64::from(minutes) * 60),
+            ),
+            pauses: second_pauses,
+            ..record
+        };
+
+        remove_record(data, &date, record_index)?;
+        let first = insert_record(data, date.clone(), first);
+        let second = insert_record(data, date.clone(), second);
+        record_updated(data, &date, date.clone(), first);
+        let event = DomainEvent::RecordAdded {
+            dev: history_for_ui_is_dev(data),
+            date: date.clone(),
+            record: second,
+        };
+        data.record_event(event);
+        Ok(())
+    })?;
+    tracing::info!(target: "storage", "拆分历史记录：date={} index={}", date, record_index);
+    snapshot_after_edit(state)
+}
+
+/// 将一条记录与其后一条相邻记录合并（仅限相同阶段；标签沿用前一条，备注拼接）。
+pub(crate) fn merge_history_records_impl<S: CommandState>(
+    state: &S,
+    date: String,
+    record_index: usize,
+) -> AppResult<AppSnapshot> {
+    let date = date.trim().to_string();
+    validate_ymd(&date)?;
+    let description = format!(
+        "合并 {date} 第 {}、{} 条记录",
+        record_index + 1,
+        record_index + 2
+    );
+    state.update_undoable(&description, |data, _| {
+        let list = history_for_ui(data);
+        let first = with_instants(&date, locate(list, &date, record_index)?);
+        let second = locate(list, &date, record_index + 1)
+            .map_err(|_| AppError::Validation("没有可合并的下一条记录".to_string()))?;
+        let second = with_instants(&date, second);
+        if first.phase != second.phase {
+            return Err(AppError::Validation(
+                "只能合并相同阶段的相邻记录".to_string(),
+            ));
+        }
+
+        let remark = [first.remark.as_str(), second.remark.as_str()]
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect::<Vec<_>>()
+            .join("；");
+        let merged = HistoryRecord {
+            task_id: first.task_id.clone().or(second.task_id.clone()),
+            end_time: second.end_time.clone(),
+            ended_at: second.ended_at.clone(),
+            duration: first.duration + second.duration,
+            remark,
+            elapsed_seconds: Some(first.active_seconds() + second.active_seconds()),
+            pauses: first.pauses.iter().chain(&second.pauses).cloned().collect(),
+            skipped: first.skipped && second.skipped,
+            ..first
+        };
+
+        let removed = remove_record(data, &date, record_index + 1)?;
+        let event = DomainEvent::RecordDeleted {
+            dev: history_for_ui_is_dev(data),
+            date: date.clone(),
+            record_id: removed.id,
+        };
+        data.record_event(event);
+        remove_record(data, &date, record_index)?;
+        let merged = insert_record(data, date.clone(), merged);
+        record_updated(data, &date, date.clone(), merged);
+        Ok(())
+    })?;
+    tracing::info!(target: "storage", "合并历史记录：date={} index={}", date, record_index);
+    snapshot_after_edit(state)
+}
+
+/// 由入参构造历史记录：校验日期/时间/时长，并按本地时区回填起止时刻。
+///
+/// 编辑时保留原记录的 id 与关联任务；若时间段未变，同时保留原起止时刻、暂停与实际计时。
+fn build_record(
+    input: &HistoryRecordInput,
+    previous: Option<(&str, &HistoryRecord)>,
+) -> AppResult<(String, HistoryRecord)> {
+    let date = input.date.trim().to_string();
+    validate_ymd(&date)?;
+    let start_time = validate_hhmm(input.start_time.trim())?;
+    if !(1..=MAX_RECORD_MINUTES).contains(&input.duration) {
+        return Err(AppError::Validation(format!(
+            "时长需在 1-{MAX_RECORD_MINUTES} 分钟"
+        )));
+    }
+
+    let start = local_instant(&date, &start_time)?;
+    let end = start + chrono::Duration::minutes(i64::from(input.duration));
+    let mut record = HistoryRecord {
+        id: previous.map_or_else(
+            || uuid::Uuid::new_v4().to_string(),
+            |(_, prev)| prev.id.clone(),
+        ),
+        tag: normalize_tag_path(&input.tag),
+        tag_id: None,
+        task_id: None,
+        start_time,
+        end_time: Some(end.format("%H:%M").to_string()),
+        started_at: Some(start.to_rfc3339()),
+        ended_at: Some(end.to_rfc3339()),
+        duration: input.duration,
+        phase: input.phase,
+        remark: input.remark.trim().to_string(),
+        elapsed_seconds: Some(u64::from(input.duration) * 60),
+        pauses: Vec::new(),
+        skipped: false,
+    };
+
+    let unchanged = previous.filter(|(prev_date, prev)| {
+        *prev_date == date
+            && prev.start_time == record.start_time
+            && prev.duration == record.duration
+    });
+    if let Some((_, prev)) = unchanged {
+        record.end_time = prev.end_time.clone();
+        record.started_at = prev.started_at.clone().or(record.started_at);
+        record.ended_at = prev.ended_at.clone().or(record.ended_at);
+        record.elapsed_seconds = prev.elapsed_seconds;
+        record.pauses = prev.pauses.clone();
+        record.skipped = prev.skipped && prev.phase == record.phase;
+    } else if end > chrono::Local::now() {
+        return Err(AppError::Validation(
+            "记录结束时间不能晚于当前时间".to_string(),
+        ));
+    }
+    if record.phase == Phase::Work {
+        record.task_id = previous.and_then(|(_, prev)| prev.task_id.clone());
+    }
+    Ok((date, record))
+}
+
+/// 将 `YYYY-MM-DD` + `HH:mm` 解释为本地时刻（夏令时跳过的时刻无法表示）。
+fn local_instant(date: &str, time: &str) -> AppResult<chrono::DateTime<chrono::Local>> {
+    let naive = chrono::NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M")
+        .map_err(|_| AppError::Validation(format!("无法解析时刻：{date} {time}")))?;
+    chrono::Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| AppError::Validation(format!("时刻在本地时区不存在：{date} {time}")))
+}
+
+/// 暂停片段的开始时刻：取记录开始之后首个与 `time`（HH:mm）对应的时刻（跨午夜时落在次日）。
+fn pause_start(
+    start: chrono::DateTime<chrono::Local>,
+    time: &str,
+) -> AppResult<chrono::DateTime<chrono::Local>> {
+    let day = start.date_naive();
+    let at = local_instant(&day.format("%Y-%m-%d").to_string(), time)?;
+    if at + chrono::Duration::minutes(1) > start {
+        return Ok(at);
+    }
+    let next = day + chrono::Duration::days(1);
+    local_instant(&next.format("%Y-%m-%d").to_string(), time)
+}
+
+/// 记录的开始时刻：优先使用 RFC 3339 起始时刻，旧数据回退为“日期 + 开始时间”。
+fn record_start(date: &str, record: &HistoryRecord) -> AppResult<chrono::DateTime<chrono::Local>> {
+    match record.started_at_local() {
+        Some(at) => Ok(at),
+        None => local_instant(date, &record.start_time),
+    }
+}
+
+/// 记录占用的时间段（左闭右开；结束优先取 RFC 3339 结束时刻，否则按时长 + 暂停推算）。
+fn record_span(
+    date: &str,
+    record: &HistoryRecord,
+) -> Option<(
+    chrono::DateTime<chrono::Local>,
+    chrono::DateTime<chrono::Local>,
+)> {
+    let start = record_start(date, record).ok()?;
+    let end = record
+        .ended_at
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|at| at.with_timezone(&chrono::Local))
+        .unwrap_or_else(|| {
+            let seconds = u64::from(record.duration) * 60 + record.paused_seconds();
+            start + chrono::Duration::seconds(seconds as i64)
+        });
+    Some((start, end))
+}
+
+/// 复制记录并补齐缺失的起止时刻与结束时间（旧数据拆分/合并前按时间段推算）。
+fn with_instants(date: &str, record: &HistoryRecord) -> HistoryRecord {
+    let mut record = record.clone();
+    if let Some((start, end)) = record_span(date, &record) {
+        record.started_at.get_or_insert_with(|| start.to_rfc3339());
+        record.ended_at.get_or_insert_with(|| end.to_rfc3339());
+        record
+            .end_time
+            .get_or_insert_with(|| end.format("%H:%M").to_string());
+    }
+    record
+}
+
+/// 检查记录与目标日期已有记录是否时间重叠（`skip` 为编辑中的原记录位置）。
+fn check_overlap(
+    list: &[HistoryDay],
+    date: &str,
+    record: &HistoryRecord,
+    skip: Option<(&str, usize)>,
+) -> AppResult<()> {
+    let Some(day) = list.iter().find(|d| d.date == date) else {
+        return Ok(());
+    };
+    let Some((start, end)) = record_span(date, record) else {
+        return Ok(());
+    };
+    for (index, other) in day.records.iter().enumerate() {
+        if skip == Some((date, index)) {
+            continue;
+        }
+        if let Some((other_start, other_end)) = record_span(date, other) {
+            if start < other_end && other_start < end {
+                return Err(AppError::Validation(format!(
+                    "与 {} 开始的记录时间重叠",
+                    other.start_time
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按日期 + 索引定位记录。
+fn locate<'a>(
+    list: &'a [HistoryDay],
+    date: &str,
+    record_index: usize,
+) -> AppResult<&'a HistoryRecord> {
+    let Some(day) = list.iter().find(|d| d.date == date) else {
+        return Err(AppError::Validation("找不到指定日期的历史记录".to_string()));
+    };
+    day.records
+        .get(record_index)
+        .ok_or_else(|| AppError::Validation("历史记录索引超出范围".to_string()))
+}
+
+/// 移除一条记录：空日期一并移除，工作记录扣减累计番茄数。
+fn remove_record(data: &mut AppData, date: &str, record_index: usize) -> AppResult<HistoryRecord> {
+    if locate(history_for_ui(data), date, record_index)?.phase == Phase::Work {
+        data.touch_section(Section::Counters);
+    }
+    let dev = history_for_ui_is_dev(data);
+    let day = data.history_day_mut(dev, date);
+    let removed = day.records.remove(record_index);
+    if day.records.is_empty() {
+        history_for_ui_mut(data).retain(|d| d.date != date);
+    }
+    if removed.phase == Phase::Work {
+        data.total_pomodoros = data.total_pomodoros.saturating_sub(1);
+    }
+    Ok(removed)
+}
+
+/// 写入一条记录：补建标签并回填 `tag_id`、维护累计番茄数，并保持当日记录有序；返回写入的记录。
+fn insert_record(data: &mut AppData, date: String, mut record: HistoryRecord) -> HistoryRecord {
+    if !record.tag.is_empty() {
+        let is_new = data.tag_id_by_path(&record.tag).is_none();
+        if is_new {
+            data.touch_section(Section::Tags);
+        }
+        record.tag_id = Some(data.ensure_tag_path(&record.tag));
+        if is_new {
+            data.sync_tags();
+        }
+    }
+    if record.phase == Phase::Work {
+        data.touch_section(Section::Counters);
+        data.total_pomodoros = data.total_pomodoros.saturating_add(1);
+    }
+
+    let dev = history_for_ui_is_dev(data);
+    let day = data.history_day_mut(dev, &date);
+    day.records.push(record.clone());
+    day.records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    record
+}
+
+/// 登记一条记录被编辑（`from` 为编辑前的日期）。
+fn record_updated(data: &mut AppData, from: &str, date: String, record: HistoryRecord) {
+    let event = DomainEvent::RecordUpdated {
+        dev: history_for_ui_is_dev(data),
+        from: from.to_string(),
+        date,
+        record,
+    };
+    data.record_event(event);
+}
+
+/// 编辑后通知前端并返回最新快照（今日统计/累计番茄数可能变化）。
+fn snapshot_after_edit<S: CommandState>(state: &S) -> AppResult<AppSnapshot> {
+    let _ = state.emit_timer_snapshot();
+    Ok(AppSnapshot {
+        data: state.data_snapshot(),
+        timer: state.timer_snapshot(),
+        profile: state.active_profile(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::app_data::{AppData, Phase};
+    use crate::app_data::{AppData, PauseSegment, Phase};
     use crate::commands::state_like::CommandState;
     use crate::commands::state_like::TestState;
 
+    /// 构造一条无 RFC 3339 时刻的旧格式记录。
+    fn record(start: &str, duration: u32, phase: Phase) -> HistoryRecord {
+        HistoryRecord::for_test("", start)
+            .with_duration(duration)
+            .with_phase(phase)
+    }
+
+    /// 构造补录/编辑入参。
+    fn input(
+        date: &str,
+        start: &str,
+        duration: u32,
+        tag: &str,
+        phase: Phase,
+    ) -> HistoryRecordInput {
+        HistoryRecordInput {
+            date: date.to_string(),
+            start_time: start.to_string(),
+            duration,
+            tag: tag.to_string(),
+            phase,
+            remark: String::new(),
+        }
+    }
+
+    /// 含一天两条工作记录（09:00、10:00 各 25 分钟）的测试状态。
+    fn state_with_day() -> TestState {
+        TestState::new(AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-02".to_string(),
+                records: vec![
+                    record("09:00", 25, Phase::Work),
+                    record("10:00", 25, Phase::Work),
+                ],
+            }],
+            total_pomodoros: 2,
+            ..AppData::default()
+        })
+    }
+
     /// `get_history_impl`：应按闭区间筛选，并按日期倒序返回。
     #[test]
     fn get_history_filters_and_sorts_desc() {
@@ -127,14 +617,7 @@ mod tests {
         let data = AppData {
             history_dev: vec![HistoryDay {
                 date: "2025-01-01".to_string(),
-                records: vec![HistoryRecord {
-                    tag: "学习".to_string(),
-                    start_time: "09:00".to_string(),
-                    end_time: Some("09:25".to_string()),
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                }],
+                records: vec![HistoryRecord::for_test("学习", "09:00").with_end("09:25")],
             }],
             ..AppData::default()
         };
@@ -184,4 +667,206 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, AppError::Validation(_)));
     }
+
+    /// 补录/删除：应维护累计番茄数、标签列表与记录顺序，并拒绝时间重叠或尚未结束的记录。
+    #[test]
+    fn add_and_delete_history_record_keep_data_consistent() {
+        let state = state_with_day();
+
+        let snapshot = add_history_record_impl(
+            &state,
+            &input("2025-01-02", "9:30", 20, "学习/英语", Phase::Work),
+        )
+        .unwrap();
+        let day = &snapshot.data.history[0];
+        let starts: Vec<_> = day.records.iter().map(|r| r.start_time.as_str()).collect();
+        assert_eq!(starts, ["09:00", "09:30", "10:00"]);
+        assert_eq!(day.records[1].end_time.as_deref(), Some("09:50"));
+        assert!(day.records[1].started_at.is_some());
+        assert_eq!(
+            day.records[1].tag_id,
+            snapshot.data.tag_id_by_path("学习/英语")
+        );
+        assert!(snapshot.data.tags.iter().any(|t| t == "学习/英语"));
+        assert_eq!(snapshot.data.total_pomodoros, 3);
+
+        let err =
+            add_history_record_impl(&state, &input("2025-01-02", "09:10", 10, "", Phase::Work))
+                .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let tomorrow = (chrono::Local::now() + chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let err = add_history_record_impl(&state, &input(&tomorrow, "09:00", 25, "", Phase::Work))
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let err =
+            add_history_record_impl(&state, &input("2025-01-03", "09:00", 0, "", Phase::Work))
+                .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        add_history_record_impl(
+            &state,
+            &input("2025-01-01", "20:00", 5, "", Phase::ShortBreak),
+        )
+        .unwrap();
+        let data = state.data_snapshot();
+        assert_eq!(data.history[0].date, "2025-01-01");
+        assert_eq!(data.total_pomodoros, 3);
+
+        let snapshot = delete_history_record_impl(&state, "2025-01-01".to_string(), 0).unwrap();
+        assert_eq!(snapshot.data.history.len(), 1);
+        assert_eq!(snapshot.data.total_pomodoros, 3);
+        let snapshot = delete_history_record_impl(&state, "2025-01-02".to_string(), 1).unwrap();
+        assert_eq!(snapshot.data.history[0].records.len(), 2);
+        assert_eq!(snapshot.data.total_pomodoros, 2);
+        assert_eq!(
+            state.undo_state().unwrap().undo.as_deref(),
+            Some("删除 2025-01-02 第 2 条记录")
+        );
+    }
+
+    /// 编辑：时间段不变时保留暂停信息；修改阶段/日期时同步累计番茄数与日期分组。
+    #[test]
+    fn update_history_record_moves_and_recounts() {
+        let state = state_with_day();
+        state
+            .update_data(|data| {
+                data.history[0].records[0].pauses = vec![PauseSegment {
+                    start_time: "09:10".to_string(),
+                    end_time: "09:12".to_string(),
+                    seconds: 120,
+                }];
+                Ok(())
+            })
+            .unwrap();
+
+        let snapshot = update_history_record_impl(
+            &state,
+            "2025-01-02".to_string(),
+            0,
+            &input("2025-01-02", "09:00", 25, "阅读", Phase::Work),
+        )
+        .unwrap();
+        let first = &snapshot.data.history[0].records[0];
+        assert_eq!(first.tag, "阅读");
+        assert_eq!(first.pauses.len(), 1);
+
+        let err = update_history_record_impl(
+            &state,
+            "2025-01-02".to_string(),
+            0,
+            &input("2025-01-02", "09:50", 25, "", Phase::Work),
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        let snapshot = update_history_record_impl(
+            &state,
+            "2025-01-02".to_string(),
+            1,
+            &input("2024-12-31", "22:00", 15, "", Phase::LongBreak),
+        )
+        .unwrap();
+        let dates: Vec<_> = snapshot
+            .data
+            .history
+            .iter()
+            .map(|d| d.date.as_str())
+            .collect();
+        assert_eq!(dates, ["2024-12-31", "2025-01-02"]);
+        assert_eq!(snapshot.data.history[1].records.len(), 1);
+        assert_eq!(snapshot.data.total_pomodoros, 1);
+
+        let err = update_history_record_impl(
+            &state,
+            "2025-01-02".to_string(),
+            5,
+            &input("2025-01-02", "12:00", 25, "", Phase::Work),
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    /// 拆分：拆分点之前的暂停计入前一条并后移拆分时刻；跨午夜的暂停按真实时刻归入后一条。
+    #[test]
+    fn split_history_record_accounts_for_pauses() {
+        let pause = |start: &str, end: &str, seconds: u64| PauseSegment {
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            seconds,
+        };
+        let mut paused = record("09:00", 25, Phase::Work);
+        paused.pauses = vec![pause("09:05", "09:10", 300)];
+        let mut late = record("23:50", 25, Phase::Work);
+        late.pauses = vec![pause("00:05", "00:07", 120)];
+        let state = TestState::new(AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-02".to_string(),
+                records: vec![paused, late],
+            }],
+            ..AppData::default()
+        });
+
+        let snapshot = split_history_record_impl(&state, "2025-01-02".to_string(), 0, 10).unwrap();
+        let records = &snapshot.data.history[0].records;
+        assert_eq!(records[0].end_time.as_deref(), Some("09:15"));
+        assert_eq!(records[0].duration, 10);
+        assert_eq!(records[0].elapsed_seconds, Some(600));
+        assert_eq!(records[0].pauses.len(), 1);
+        assert_eq!(records[1].start_time, "09:15");
+        assert_eq!(records[1].duration, 15);
+        assert_eq!(records[1].elapsed_seconds, Some(900));
+        assert!(records[1].pauses.is_empty());
+
+        let snapshot = split_history_record_impl(&state, "2025-01-02".to_string(), 2, 5).unwrap();
+        let records = &snapshot.data.history[0].records;
+        assert_eq!(records[2].end_time.as_deref(), Some("23:55"));
+        assert!(records[2].pauses.is_empty());
+        assert_eq!(records[3].start_time, "23:55");
+        assert_eq!(records[3].pauses.len(), 1);
+    }
+
+    /// 拆分与合并：应互为逆操作，并拒绝越界拆分点与不同阶段的合并。
+    #[test]
+    fn split_and_merge_history_records() {
+        let state = state_with_day();
+
+        let snapshot = split_history_record_impl(&state, "2025-01-02".to_string(), 0, 10).unwrap();
+        let records = &snapshot.data.history[0].records;
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            (records[0].start_time.as_str(), records[0].duration),
+            ("09:00", 10)
+        );
+        assert_eq!(
+            (records[1].start_time.as_str(), records[1].duration),
+            ("09:10", 15)
+        );
+        assert_eq!(records[0].end_time.as_deref(), Some("09:10"));
+        assert_eq!(snapshot.data.total_pomodoros, 3);
+
+        for minutes in [0, 15] {
+            let err = split_history_record_impl(&state, "2025-01-02".to_string(), 1, minutes)
+                .unwrap_err();
+            assert!(matches!(err, AppError::Validation(_)));
+        }
+
+        let snapshot = merge_history_records_impl(&state, "2025-01-02".to_string(), 0).unwrap();
+        let records = &snapshot.data.history[0].records;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].duration, 25);
+        assert_eq!(records[0].end_time.as_deref(), Some("09:25"));
+        assert_eq!(snapshot.data.total_pomodoros, 2);
+
+        let err = merge_history_records_impl(&state, "2025-01-02".to_string(), 1).unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        add_history_record_impl(
+            &state,
+            &input("2025-01-02", "10:25", 5, "", Phase::ShortBreak),
+        )
+        .unwrap();
+        let err = merge_history_records_impl(&state, "2025-01-02".to_string(), 1).unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
 }
@@ -0,0 +1,656 @@
+//! 导入相关命令：读取本应用导出的 CSV/JSON，校验、去重后合并或按日期范围替换历史记录。
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::app_data::{
+    normalize_tag_path, AppData, DateRange, HistoryRecord, PauseSegment, Phase, RecordSet, Section,
+};
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+use super::types::{ExportFormat, ImportMode, ImportReport, ImportRequest, ImportRowError};
+use super::validation::{validate_date_range, validate_ymd};
+
+/// 单条导入记录的原始字段（JSON 记录与 CSV 行共用；缺失列为空）。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct RawRecord {
+    date: Option<String>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+    duration: Option<u32>,
+    tag: Option<String>,
+    phase: Option<String>,
+    remark: Option<String>,
+    elapsed_seconds: Option<u64>,
+    skipped: Option<bool>,
+    pauses: Vec<PauseSegment>,
+}
+
+/// 校验通过的导入记录：`date + record`。
+#[derive(Debug, Clone)]
+pub(super) struct ImportRow {
+    pub(super) date: String,
+    pub(super) record: HistoryRecord,
+}
+
+/// 导入文件解析结果。
+#[derive(Debug, Clone)]
+pub(super) struct ParsedImport {
+    pub(super) format: ExportFormat,
+    /// JSON 导出自带的日期范围（替换模式优先使用）。
+    pub(super) range: Option<DateRange>,
+    pub(super) total_rows: u32,
+    pub(super) rows: Vec<ImportRow>,
+    pub(super) invalid: Vec<ImportRowError>,
+}
+
+/// 导入历史的内部实现：`commit=false` 时在数据副本上演练并返回预览报告；否则先备份再写入。
+pub(crate) fn import_history_impl<S: CommandState>(
+    state: &S,
+    request: &ImportRequest,
+) -> AppResult<ImportReport> {
+    let parsed = parse_import_file(
+        Path::new(request.path.trim()),
+        request.passphrase.as_deref(),
+    )?;
+    run_import(state, parsed, request.mode, request.commit)
+}
+
+/// 预览或写入解析结果（导出文件与外部工具导入共用）。
+pub(super) fn run_import<S: CommandState>(
+    state: &S,
+    parsed: ParsedImport,
+    mode: ImportMode,
+    commit: bool,
+) -> AppResult<ImportReport> {
+    if parsed.total_rows == 0 {
+        return Err(AppError::Validation("导入文件中没有记录".to_string()));
+    }
+
+    if !commit {
+        let mut preview = state.data_snapshot();
+        return Ok(apply_import(&mut preview, parsed, mode));
+    }
+
+    state.backup_before("import")?;
+    let mut report = None;
+    state.update_undoable("导入历史记录", |data, _| {
+        report = Some(apply_import(data, parsed, mode));
+        Ok(())
+    })?;
+    let mut report = report.ok_or_else(|| AppError::Invariant("导入后读取报告失败".to_string()))?;
+    report.committed = true;
+
+    tracing::info!(
+        target: "storage",
+        "已导入历史：mode={:?} imported={} duplicates={} replaced={} invalid={} newTags={}",
+        mode,
+        report.imported,
+        report.duplicates,
+        report.replaced,
+        report.invalid.len(),
+        report.new_tags.len()
+    );
+    let _ = state.emit_timer_snapshot();
+    Ok(report)
+}
+
+/// 读取并解析导入文件：加密文件先以口令解密，再按扩展名识别格式（无法识别时按内容判断）。
+fn parse_import_file(path: &Path, passphrase: Option<&str>) -> AppResult<ParsedImport> {
+    let bytes =
+        std::fs::read(path).map_err(|e| AppError::Validation(format!("读取导入文件失败：{e}")))?;
+    let bytes = crate::crypto::unseal_with_passphrase(passphrase.filter(|p| !p.is_empty()), bytes)?;
+    let content = String::from_utf8(bytes)
+        .map_err(|e| AppError::Validation(format!("导入文件不是有效的 UTF-8 文本：{e}")))?;
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let is_json = match ext.as_str() {
+        "json" => true,
+        "csv" => false,
+        _ => content.trim_start().starts_with('{'),
+    };
+    if is_json {
+        parse_json(&content)
+    } else {
+        parse_csv(&content)
+    }
+}
+
+/// 解析 JSON 导出：`{ exportDate, range, records: [...] }`。
+fn parse_json(content: &str) -> AppResult<ParsedImport> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| AppError::Validation(format!("JSON 导入文件无法解析：{e}")))?;
+    let Some(records) = value.get("records").and_then(serde_json::Value::as_array) else {
+        return Err(AppError::Validation(
+            "JSON 导入文件缺少 records 数组".to_string(),
+        ));
+    };
+    let range = value
+        .get("range")
+        .and_then(|r| serde_json::from_value::<DateRange>(r.clone()).ok())
+        .filter(|r| validate_date_range(r).is_ok());
+
+    let raws = records.iter().map(|item| {
+        serde_json::from_value::<RawRecord>(item.clone()).map_err(|e| format!("字段类型错误：{e}"))
+    });
+    Ok(collect_rows(ExportFormat::Json, range, raws))
+}
+
+/// 解析 CSV 导出：表头为导出字段名的任意子集（顺序不限）。
+fn parse_csv(content: &str) -> AppResult<ParsedImport> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::Validation(format!("CSV 表头无法解析：{e}")))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if let Some(unknown) = headers.iter().find(|h| !CSV_COLUMNS.contains(&h.as_str())) {
+        return Err(AppError::Validation(format!(
+            "无法识别的 CSV 列：{unknown}"
+        )));
+    }
+
+    let raws: Vec<Result<RawRecord, String>> = reader
+        .records()
+        .map(|row| {
+            let row = row.map_err(|e| format!("CSV 行无法解析：{e}"))?;
+            csv_row_to_raw(&headers, &row)
+        })
+        .collect();
+    Ok(collect_rows(ExportFormat::Csv, None, raws))
+}
+
+/// CSV 导出使用的列名（与 `export_csv` 表头一致）。
+const CSV_COLUMNS: &[&str] = &[
+    "date",
+    "start_time",
+    "end_time",
+    "duration",
+    "tag",
+    "phase",
+    "remark",
+    "elapsed_seconds",
+    "paused_seconds",
+    "skipped",
+    "started_at",
+    "ended_at",
+];
+
+/// 将一行 CSV 按表头映射为原始字段（空单元格视为缺失）。
+fn csv_row_to_raw(headers: &[String], row: &csv::StringRecord) -> Result<RawRecord, String> {
+    let mut raw = RawRecord::default();
+    for (header, value) in headers.iter().zip(row.iter()) {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let text = Some(value.to_string());
+        match header.as_str() {
+            "date" => raw.date = text,
+            "start_time" => raw.start_time = text,
+            "end_time" => raw.end_time = text,
+            "duration" => raw.duration = Some(parse_number(header, value)?),
+            "tag" => raw.tag = text,
+            "phase" => raw.phase = text,
+            "remark" => raw.remark = text,
+            "elapsed_seconds" => raw.elapsed_seconds = Some(parse_number(header, value)?),
+            "skipped" => {
+                raw.skipped = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("skipped 需为 true/false：{value}"))?,
+                )
+            }
+            "started_at" => raw.started_at = text,
+            "ended_at" => raw.ended_at = text,
+            // 暂停总秒数由暂停明细推导，导入时忽略。
+            _ => {}
+        }
+    }
+    Ok(raw)
+}
+
+/// 解析数值单元格。
+fn parse_number<T: std::str::FromStr>(header: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{header} 需为非负整数：{value}"))
+}
+
+/// 逐行校验原始记录，收集有效记录与无效行。
+fn collect_rows(
+    format: ExportFormat,
+    range: Option<DateRange>,
+    raws: impl IntoIterator<Item = Result<RawRecord, String>>,
+) -> ParsedImport {
+    let mut parsed = ParsedImport {
+        format,
+        range,
+        total_rows: 0,
+        rows: Vec::new(),
+        invalid: Vec::new(),
+    };
+    for (i, raw) in raws.into_iter().enumerate() {
+        parsed.total_rows += 1;
+        match raw.and_then(build_row) {
+            Ok(row) => parsed.rows.push(row),
+            Err(message) => parsed.invalid.push(ImportRowError {
+                row: i as u32 + 1,
+                message,
+            }),
+        }
+    }
+    parsed
+}
+
+/// 由原始字段构造历史记录：缺失的日期/开始时间/时长可由 RFC 3339 起止时刻或实际计时推导。
+fn build_row(raw: RawRecord) -> Result<ImportRow, String> {
+    let non_empty = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let parse_instant = |v: Option<String>, name: &str| -> Result<_, String> {
+        non_empty(v)
+            .map(|s| {
+                chrono::DateTime::parse_from_rfc3339(&s)
+                    .map(|at| (s.clone(), at.with_timezone(&chrono::Local)))
+                    .map_err(|_| format!("{name} 需为 RFC 3339 时刻：{s}"))
+            })
+            .transpose()
+    };
+    let started = parse_instant(raw.started_at, "started_at")?;
+    let ended = parse_instant(raw.ended_at, "ended_at")?;
+
+    let date = match non_empty(raw.date) {
+        Some(date) => {
+            validate_ymd(&date).map_err(|_| format!("日期格式必须为 YYYY-MM-DD：{date}"))?;
+            date
+        }
+        None => started
+            .as_ref()
+            .map(|(_, at)| at.format("%Y-%m-%d").to_string())
+            .ok_or_else(|| "缺少日期".to_string())?,
+    };
+    let start_time = match non_empty(raw.start_time) {
+        Some(time) => parse_hhmm(&time)?,
+        None => started
+            .as_ref()
+            .map(|(_, at)| at.format("%H:%M").to_string())
+            .ok_or_else(|| "缺少开始时间".to_string())?,
+    };
+    let end_time = match non_empty(raw.end_time) {
+        Some(time) => Some(parse_hhmm(&time)?),
+        None => ended.as_ref().map(|(_, at)| at.format("%H:%M").to_string()),
+    };
+
+    let phase = match non_empty(raw.phase).as_deref() {
+        None | Some("work") => Phase::Work,
+        Some("shortBreak") => Phase::ShortBreak,
+        Some("longBreak") => Phase::LongBreak,
+        Some(other) => return Err(format!("未知阶段：{other}")),
+    };
+
+    let duration = match raw.duration {
+        Some(minutes) => minutes,
+        None => match (raw.elapsed_seconds, &started, &ended) {
+            (Some(seconds), _, _) => ((seconds + 30) / 60) as u32,
+            (None, Some((_, from)), Some((_, to))) => {
+                ((*to - *from).num_seconds().max(0) / 60) as u32
+            }
+            _ => return Err("缺少时长".to_string()),
+        },
+    };
+    if duration > 24 * 60 {
+        return Err(format!("时长需在 0-1440 分钟：{duration}"));
+    }
+
+    Ok(ImportRow {
+        date,
+        record: HistoryRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            tag: normalize_tag_path(&raw.tag.unwrap_or_default()),
+            tag_id: None,
+            task_id: None,
+            start_time,
+            end_time,
+            started_at: started.map(|(s, _)| s),
+            ended_at: ended.map(|(s, _)| s),
+            duration,
+            phase,
+            remark: raw.remark.unwrap_or_default().trim().to_string(),
+            elapsed_seconds: raw.elapsed_seconds,
+            pauses: raw.pauses,
+            skipped: raw.skipped.unwrap_or(false),
+        },
+    })
+}
+
+/// 校验并规范化 `HH:mm`。
+fn parse_hhmm(time: &str) -> Result<String, String> {
+    chrono::NaiveTime::parse_from_str(time, "%H:%M")
+        .map(|t| t.format("%H:%M").to_string())
+        .map_err(|_| format!("时间格式必须为 HH:mm：{time}"))
+}
+
+/// 记录去重键：同一日期、开始时间与阶段视为同一条记录。
+fn dedupe_key(date: &str, record: &HistoryRecord) -> String {
+    format!("{date} {} {:?}", record.start_time, record.phase)
+}
+
+/// 将解析结果写入数据：替换模式先清空范围内记录；随后去重追加、补建标签并维护累计番茄数（改动的日期登记为整日替换）。
+fn apply_import(data: &mut AppData, parsed: ParsedImport, mode: ImportMode) -> ImportReport {
+    let range = parsed.range.clone().or_else(|| {
+        let from = parsed.rows.iter().map(|r| r.date.clone()).min()?;
+        let to = parsed.rows.iter().map(|r| r.date.clone()).max()?;
+        Some(DateRange { from, to })
+    });
+    let mut report = ImportReport {
+        format: parsed.format,
+        total_rows: parsed.total_rows,
+        imported: 0,
+        duplicates: 0,
+        replaced: 0,
+        invalid: parsed.invalid,
+        new_tags: Vec::new(),
+        range: range.clone(),
+        committed: false,
+    };
+    let is_work = |r: &HistoryRecord| r.phase == Phase::Work;
+    let mut touched = BTreeSet::new();
+
+    if let (ImportMode::Replace, Some(range)) = (mode, &range) {
+        let dates: Vec<String> = data
+            .history
+            .iter()
+            .filter(|d| d.date >= range.from && d.date <= range.to)
+            .map(|d| d.date.clone())
+            .collect();
+        if !dates.is_empty() {
+            data.touch_section(Section::Counters);
+        }
+        for date in &dates {
+            touched.insert(date.clone());
+            let day = data.history_day_mut(false, date);
+            report.replaced += day.records.len() as u32;
+            let work = day.records.iter().filter(|r| is_work(r)).count() as u64;
+            day.records.clear();
+            data.total_pomodoros = data.total_pomodoros.saturating_sub(work);
+        }
+        data.history.retain(|d| !d.records.is_empty());
+    }
+
+    let mut seen: HashSet<String> = data
+        .history
+        .iter()
+        .flat_map(|d| d.records.iter().map(|r| dedupe_key(&d.date, r)))
+        .collect();
+    for ImportRow { date, mut record } in parsed.rows {
+        if !seen.insert(dedupe_key(&date, &record)) {
+            report.duplicates += 1;
+            continue;
+        }
+        if !record.tag.is_empty() {
+            if data.tag_id_by_path(&record.tag).is_none() {
+                data.touch_section(Section::Tags);
+                report.new_tags.push(record.tag.clone());
+            }
+            record.tag_id = Some(data.ensure_tag_path(&record.tag));
+        }
+        if is_work(&record) {
+            data.touch_section(Section::Counters);
+            data.total_pomodoros = data.total_pomodoros.saturating_add(1);
+        }
+        touched.insert(date.clone());
+        let day = data.history_day_mut(false, &date);
+        day.records.push(record);
+        day.records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        report.imported += 1;
+    }
+
+    if !report.new_tags.is_empty() {
+        data.sync_tags();
+    }
+    data.record_days_replaced(touched.into_iter().map(|date| (RecordSet::History, date)));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::HistoryDay;
+    use crate::commands::export::export_history_to_path;
+    use crate::commands::state_like::TestState;
+    use crate::commands::types::{ExportRequest, ImportMode};
+
+    /// 构造导入请求。
+    fn request(path: &Path, mode: ImportMode, commit: bool) -> ImportRequest {
+        ImportRequest {
+            path: path.to_string_lossy().to_string(),
+            mode,
+            commit,
+            passphrase: None,
+        }
+    }
+
+    /// JSON 导出应可完整导回：记录字段一致、缺失标签自动创建、累计番茄数同步。
+    #[test]
+    fn import_round_trips_json_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let mut source = AppData::default();
+        let mut work = HistoryRecord::for_test("阅读/小说", "09:00");
+        work.started_at = Some("2025-01-01T09:00:00+08:00".to_string());
+        work.ended_at = Some("2025-01-01T09:25:00+08:00".to_string());
+        work.remark = "第一章".to_string();
+        work.pauses = vec![PauseSegment {
+            start_time: "09:10".to_string(),
+            end_time: "09:11".to_string(),
+            seconds: 60,
+        }];
+        source.history = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![
+                work,
+                HistoryRecord::for_test("工作", "09:30").with_phase(Phase::ShortBreak),
+            ],
+        }];
+        export_history_to_path(
+            &TestState::new(source),
+            &ExportRequest {
+                range: DateRange {
+                    from: "2025-01-01".to_string(),
+                    to: "2025-01-01".to_string(),
+                },
+                format: ExportFormat::Json,
+                fields: Vec::new(),
+                passphrase: None,
+            },
+            &path,
+        )
+        .unwrap();
+
+        let state = TestState::new(AppData::default());
+        let report = import_history_impl(&state, &request(&path, ImportMode::Merge, true)).unwrap();
+        assert!(report.committed);
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.new_tags, vec!["阅读/小说".to_string()]);
+        assert_eq!(state.take_backups(), vec!["import".to_string()]);
+
+        let data = state.data_snapshot();
+        assert_eq!(data.total_pomodoros, 1);
+        assert!(data.tags.contains(&"阅读/小说".to_string()));
+        let imported = &data.history[0].records[0];
+        assert_eq!(imported.tag, "阅读/小说");
+        assert!(imported.tag_id.is_some());
+        assert_eq!(imported.remark, "第一章");
+        assert_eq!(imported.end_time.as_deref(), Some("09:25"));
+        assert_eq!(
+            imported.started_at.as_deref(),
+            Some("2025-01-01T09:00:00+08:00")
+        );
+        assert_eq!(imported.pauses.len(), 1);
+        assert_eq!(data.history[0].records[1].phase, Phase::ShortBreak);
+
+        // 再次导入：全部视为重复。
+        let again = import_history_impl(&state, &request(&path, ImportMode::Merge, true)).unwrap();
+        assert_eq!(again.imported, 0);
+        assert_eq!(again.duplicates, 2);
+    }
+
+    /// 加密导出：文件不含明文，需同一口令才能导入（缺少口令报 `Locked`，口令错误报 `WrongPassphrase`）。
+    #[test]
+    fn import_requires_passphrase_for_encrypted_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let mut work = HistoryRecord::for_test("工作", "09:00");
+        work.remark = "客户甲评审".to_string();
+        let source = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![work],
+            }],
+            ..AppData::default()
+        };
+        export_history_to_path(
+            &TestState::new(source),
+            &ExportRequest {
+                range: DateRange {
+                    from: "2025-01-01".to_string(),
+                    to: "2025-01-01".to_string(),
+                },
+                format: ExportFormat::Json,
+                fields: Vec::new(),
+                passphrase: Some("export-pass".to_string()),
+            },
+            &path,
+        )
+        .unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("客户甲"));
+
+        let state = TestState::new(AppData::default());
+        let mut req = request(&path, ImportMode::Merge, true);
+        assert!(matches!(
+            import_history_impl(&state, &req),
+            Err(AppError::Locked)
+        ));
+        req.passphrase = Some("wrong-pass".to_string());
+        assert!(matches!(
+            import_history_impl(&state, &req),
+            Err(AppError::WrongPassphrase)
+        ));
+        req.passphrase = Some("export-pass".to_string());
+        assert_eq!(import_history_impl(&state, &req).unwrap().imported, 1);
+        assert_eq!(
+            state.data_snapshot().history[0].records[0].remark,
+            "客户甲评审"
+        );
+    }
+
+    /// CSV 字段子集：应去重、报告无效行，且预览不修改数据。
+    #[test]
+    fn import_csv_subset_previews_dedupes_and_reports_invalid_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.csv");
+        std::fs::write(
+            &path,
+            "tag,date,start_time,duration\n\
+             工作,2025-01-01,09:00,25\n\
+             工作,2025-01-01,10:00,25\n\
+             工作,2025-13-01,10:00,25\n\
+             工作,2025-01-02,9点,25\n\
+             新标签,2025-01-02,11:00,\n",
+        )
+        .unwrap();
+        let data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![HistoryRecord::for_test("工作", "09:00")],
+            }],
+            ..AppData::default()
+        };
+        let state = TestState::new(data);
+
+        let preview =
+            import_history_impl(&state, &request(&path, ImportMode::Merge, false)).unwrap();
+        assert!(!preview.committed);
+        assert_eq!(preview.total_rows, 5);
+        assert_eq!(preview.imported, 1);
+        assert_eq!(preview.duplicates, 1);
+        let rows: Vec<u32> = preview.invalid.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![3, 4, 5]);
+        assert!(preview.new_tags.is_empty());
+        assert_eq!(state.data_snapshot().history[0].records.len(), 1);
+        assert!(state.take_backups().is_empty());
+    }
+
+    /// 替换模式：应先移除导入范围内的已有记录，范围外记录保持不变。
+    #[test]
+    fn import_replace_clears_records_in_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.csv");
+        std::fs::write(
+            &path,
+            "date,start_time,duration,tag,phase\n2025-01-02,14:00,50,学习,work\n",
+        )
+        .unwrap();
+        let data = AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2025-01-01".to_string(),
+                    records: vec![HistoryRecord::for_test("工作", "09:00")],
+                },
+                HistoryDay {
+                    date: "2025-01-02".to_string(),
+                    records: vec![
+                        HistoryRecord::for_test("工作", "09:00"),
+                        HistoryRecord::for_test("工作", "10:00"),
+                    ],
+                },
+            ],
+            total_pomodoros: 3,
+            ..AppData::default()
+        };
+        let state = TestState::new(data);
+
+        let report =
+            import_history_impl(&state, &request(&path, ImportMode::Replace, true)).unwrap();
+        assert_eq!(report.replaced, 2);
+        assert_eq!(report.imported, 1);
+        let data = state.data_snapshot();
+        assert_eq!(data.total_pomodoros, 2);
+        assert_eq!(data.history.len(), 2);
+        assert_eq!(data.history[0].records.len(), 1);
+        assert_eq!(data.history[1].records.len(), 1);
+        assert_eq!(data.history[1].records[0].duration, 50);
+    }
+
+    /// 非导出格式：未知 CSV 列与缺少 records 的 JSON 应被拒绝。
+    #[test]
+    fn import_rejects_foreign_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = TestState::new(AppData::default());
+
+        let csv_path = dir.path().join("other.csv");
+        std::fs::write(&csv_path, "date,project\n2025-01-01,x\n").unwrap();
+        assert!(matches!(
+            import_history_impl(&state, &request(&csv_path, ImportMode::Merge, false)),
+            Err(AppError::Validation(_))
+        ));
+
+        let json_path = dir.path().join("other.json");
+        std::fs::write(&json_path, "{\"items\": []}").unwrap();
+        assert!(matches!(
+            import_history_impl(&state, &request(&json_path, ImportMode::Merge, false)),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,418 @@
+//! 外部时间记录工具导入：按 Toggl 预设或通用列映射读取 CSV，长条目按番茄时长拆分，并将项目映射为标签。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::TimeZone as _;
+
+use crate::app_data::{normalize_tag_path, HistoryRecord, Phase};
+use crate::errors::{AppError, AppResult};
+
+use super::import::{run_import, ImportRow, ParsedImport};
+use super::state_like::CommandState;
+use super::types::{
+    CsvColumnMapping, ExportFormat, ExternalImportRequest, ExternalImportSource, ImportReport,
+    ImportRowError,
+};
+
+/// Toggl 时间条目导出的列映射预设。
+pub(crate) fn toggl_mapping() -> CsvColumnMapping {
+    CsvColumnMapping {
+        start: "Start time".to_string(),
+        start_date: Some("Start date".to_string()),
+        end: Some("End time".to_string()),
+        end_date: Some("End date".to_string()),
+        duration: Some("Duration".to_string()),
+        tag: Some("Project".to_string()),
+        remark: Some("Description".to_string()),
+        date_format: "%Y-%m-%d".to_string(),
+        time_format: "%H:%M:%S".to_string(),
+    }
+}
+
+/// 外部工具导入的内部实现：解析 CSV 后复用导出文件导入的预览/写入流程。
+pub(crate) fn import_external_impl<S: CommandState>(
+    state: &S,
+    request: &ExternalImportRequest,
+) -> AppResult<ImportReport> {
+    let mapping = match request.source {
+        ExternalImportSource::Toggl => toggl_mapping(),
+        ExternalImportSource::Generic => request
+            .mapping
+            .clone()
+            .ok_or_else(|| AppError::Validation("通用 CSV 导入需要配置列映射".to_string()))?,
+    };
+    let project_tags: HashMap<String, String> = request
+        .project_tags
+        .iter()
+        .map(|m| (m.project.trim().to_string(), normalize_tag_path(&m.tag)))
+        .collect();
+    let split_minutes = state.settings_snapshot().pomodoro.max(1);
+
+    let content = std::fs::read_to_string(Path::new(request.path.trim()))
+        .map_err(|e| AppError::Validation(format!("读取导入文件失败：{e}")))?;
+    let parsed = parse_external_csv(&content, &mapping, &project_tags, split_minutes)?;
+    run_import(state, parsed, request.mode, request.commit)
+}
+
+/// 已解析到表头下标的列映射。
+struct ColumnIndex {
+    start: usize,
+    start_date: Option<usize>,
+    end: Option<usize>,
+    end_date: Option<usize>,
+    duration: Option<usize>,
+    tag: Option<usize>,
+    remark: Option<usize>,
+}
+
+/// 解析外部 CSV：逐行转换为（可能被拆分的）工作记录，无效行记录原因。
+fn parse_external_csv(
+    content: &str,
+    mapping: &CsvColumnMapping,
+    project_tags: &HashMap<String, String>,
+    split_minutes: u32,
+) -> AppResult<ParsedImport> {
+    if mapping.end.is_none() && mapping.duration.is_none() {
+        return Err(AppError::Validation(
+            "列映射需至少包含结束时间列或时长列".to_string(),
+        ));
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::Validation(format!("CSV 表头无法解析：{e}")))?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').trim().to_string())
+        .collect();
+    let find = |name: &str| -> AppResult<usize> {
+        headers
+            .iter()
+            .position(|h| h == name.trim())
+            .ok_or_else(|| AppError::Validation(format!("CSV 缺少列：{name}")))
+    };
+    let find_opt = |name: &Option<String>| name.as_deref().map(find).transpose();
+    let columns = ColumnIndex {
+        start: find(&mapping.start)?,
+        start_date: find_opt(&mapping.start_date)?,
+        end: find_opt(&mapping.end)?,
+        end_date: find_opt(&mapping.end_date)?,
+        duration: find_opt(&mapping.duration)?,
+        tag: find_opt(&mapping.tag)?,
+        remark: find_opt(&mapping.remark)?,
+    };
+
+    let mut parsed = ParsedImport {
+        format: ExportFormat::Csv,
+        range: None,
+        total_rows: 0,
+        rows: Vec::new(),
+        invalid: Vec::new(),
+    };
+    for (i, row) in reader.records().enumerate() {
+        parsed.total_rows += 1;
+        let converted = row
+            .map_err(|e| format!("CSV 行无法解析：{e}"))
+            .and_then(|row| convert_row(&row, &columns, mapping, project_tags, split_minutes));
+        match converted {
+            Ok(rows) => parsed.rows.extend(rows),
+            Err(message) => parsed.invalid.push(ImportRowError {
+                row: i as u32 + 1,
+                message,
+            }),
+        }
+    }
+    Ok(parsed)
+}
+
+/// 将一行外部条目转换为工作记录：超过番茄时长的条目按 `split_minutes` 连续拆分。
+fn convert_row(
+    row: &csv::StringRecord,
+    columns: &ColumnIndex,
+    mapping: &CsvColumnMapping,
+    project_tags: &HashMap<String, String>,
+    split_minutes: u32,
+) -> Result<Vec<ImportRow>, String> {
+    let cell = |index: Option<usize>| {
+        index
+            .and_then(|i| row.get(i))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let start_date = cell(columns.start_date);
+    let start = parse_datetime(
+        cell(Some(columns.start)).ok_or_else(|| "缺少开始时间".to_string())?,
+        start_date,
+        mapping,
+    )?;
+    let end = match cell(columns.end) {
+        Some(end) => {
+            let end_date = cell(columns.end_date).or(start_date);
+            let mut end = parse_datetime(end, end_date, mapping)?;
+            // 未提供结束日期且结束早于开始：视为跨过午夜。
+            if end < start && cell(columns.end_date).is_none() && start_date.is_some() {
+                end += chrono::Duration::days(1);
+            }
+            end
+        }
+        None => {
+            let seconds = cell(columns.duration)
+                .map(parse_duration_seconds)
+                .transpose()?
+                .ok_or_else(|| "缺少结束时间或时长".to_string())?;
+            start + chrono::Duration::seconds(seconds)
+        }
+    };
+    let total_seconds = (end - start).num_seconds();
+    if total_seconds < 60 {
+        return Err("时长不足 1 分钟".to_string());
+    }
+
+    let tag = cell(columns.tag)
+        .map(|project| {
+            project_tags
+                .get(project)
+                .cloned()
+                .unwrap_or_else(|| normalize_tag_path(project))
+        })
+        .unwrap_or_default();
+    let remark = cell(columns.remark).unwrap_or_default().to_string();
+
+    let chunk_seconds = i64::from(split_minutes) * 60;
+    let mut rows = Vec::new();
+    let mut offset = 0;
+    while offset < total_seconds {
+        let len = chunk_seconds.min(total_seconds - offset);
+        // 拆分后不足 1 分钟的尾段不单独成条。
+        if len < 60 {
+            break;
+        }
+        let chunk_start = start + chrono::Duration::seconds(offset);
+        let chunk_end = chunk_start + chrono::Duration::seconds(len);
+        rows.push(ImportRow {
+            date: chunk_start.format("%Y-%m-%d").to_string(),
+            record: HistoryRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                tag: tag.clone(),
+                tag_id: None,
+                task_id: None,
+                start_time: chunk_start.format("%H:%M").to_string(),
+                end_time: Some(chunk_end.format("%H:%M").to_string()),
+                started_at: Some(to_local_rfc3339(chunk_start)?),
+                ended_at: Some(to_local_rfc3339(chunk_end)?),
+                duration: ((len + 30) / 60) as u32,
+                phase: Phase::Work,
+                remark: remark.clone(),
+                elapsed_seconds: Some(len as u64),
+                pauses: Vec::new(),
+                skipped: false,
+            },
+        });
+        offset += len;
+    }
+    Ok(rows)
+}
+
+/// 按映射中的格式解析时刻：提供日期列时分别解析日期与时间，否则整体按“日期 时间”解析。
+fn parse_datetime(
+    value: &str,
+    date: Option<&str>,
+    mapping: &CsvColumnMapping,
+) -> Result<chrono::NaiveDateTime, String> {
+    match date {
+        Some(date) => {
+            let day = chrono::NaiveDate::parse_from_str(date, &mapping.date_format)
+                .map_err(|_| format!("日期不符合格式 {}：{date}", mapping.date_format))?;
+            let time = chrono::NaiveTime::parse_from_str(value, &mapping.time_format)
+                .map_err(|_| format!("时间不符合格式 {}：{value}", mapping.time_format))?;
+            Ok(day.and_time(time))
+        }
+        None => {
+            let format = format!("{} {}", mapping.date_format, mapping.time_format);
+            chrono::NaiveDateTime::parse_from_str(value, &format)
+                .map_err(|_| format!("时刻不符合格式 {format}：{value}"))
+        }
+    }
+}
+
+/// 解析时长：`HH:MM:SS`、`HH:MM` 或分钟数。
+fn parse_duration_seconds(value: &str) -> Result<i64, String> {
+    let invalid = || format!("时长格式无法识别：{value}");
+    let parts: Vec<i64> = value
+        .split(':')
+        .map(|p| p.trim().parse::<i64>().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let seconds = match parts.as_slice() {
+        [minutes] => minutes * 60,
+        [h, m] => h * 3600 + m * 60,
+        [h, m, s] => h * 3600 + m * 60 + s,
+        _ => return Err(invalid()),
+    };
+    if seconds < 0 {
+        return Err(invalid());
+    }
+    Ok(seconds)
+}
+
+/// 将本地无时区时刻转换为 RFC 3339（夏令时跳过的时刻无法表示）。
+fn to_local_rfc3339(at: chrono::NaiveDateTime) -> Result<String, String> {
+    chrono::Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|t| t.to_rfc3339())
+        .ok_or_else(|| format!("时刻在本地时区不存在：{at}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::AppData;
+    use crate::commands::state_like::TestState;
+    use crate::commands::types::{ImportMode, ProjectTagMapping};
+
+    /// 构造外部导入请求。
+    fn request(
+        path: &Path,
+        source: ExternalImportSource,
+        mapping: Option<CsvColumnMapping>,
+    ) -> ExternalImportRequest {
+        ExternalImportRequest {
+            path: path.to_string_lossy().to_string(),
+            source,
+            mapping,
+            project_tags: Vec::new(),
+            mode: ImportMode::Merge,
+            commit: true,
+        }
+    }
+
+    /// Toggl 导出：长条目按番茄时长拆分，项目按映射转为标签，跨午夜条目归入开始日期。
+    #[test]
+    fn import_toggl_splits_long_entries_and_maps_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toggl.csv");
+        std::fs::write(
+            &path,
+            "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\n\
+             a,a@x.com,,Website,,写文档,No,2025-01-01,09:00:00,2025-01-01,10:00:00,01:00:00,\n\
+             a,a@x.com,,Reading,,,No,2025-01-01,23:50:00,2025-01-02,00:10:00,00:20:00,\n\
+             a,a@x.com,,Website,,,No,2025-01-01,bad,2025-01-01,10:00:00,01:00:00,\n",
+        )
+        .unwrap();
+        let state = TestState::new(AppData::default());
+        let mut req = request(&path, ExternalImportSource::Toggl, None);
+        req.project_tags = vec![ProjectTagMapping {
+            project: "Website".to_string(),
+            tag: "工作/官网".to_string(),
+        }];
+
+        let report = import_external_impl(&state, &req).unwrap();
+        assert!(report.committed);
+        assert_eq!(report.total_rows, 3);
+        assert_eq!(report.imported, 4);
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].row, 3);
+        assert_eq!(
+            report.new_tags,
+            vec!["工作/官网".to_string(), "Reading".to_string()]
+        );
+
+        let data = state.data_snapshot();
+        let day1 = &data.history[0];
+        assert_eq!(day1.date, "2025-01-01");
+        let starts: Vec<&str> = day1.records.iter().map(|r| r.start_time.as_str()).collect();
+        assert_eq!(starts, vec!["09:00", "09:25", "09:50", "23:50"]);
+        let durations: Vec<u32> = day1.records.iter().map(|r| r.duration).collect();
+        assert_eq!(durations, vec![25, 25, 10, 20]);
+        assert_eq!(day1.records[0].tag, "工作/官网");
+        assert_eq!(day1.records[0].remark, "写文档");
+        assert_eq!(day1.records[3].end_time.as_deref(), Some("00:10"));
+        assert_eq!(data.total_pomodoros, 4);
+    }
+
+    /// 通用 CSV：按列映射与自定义格式解析，仅有时长列时由开始时刻推算结束。
+    #[test]
+    fn import_generic_csv_uses_mapping_and_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("generic.csv");
+        std::fs::write(
+            &path,
+            "when,minutes,category\n\
+             01/02/2025 14:00,30,学习\n\
+             2025-01-02 15:00,30,学习\n",
+        )
+        .unwrap();
+        let mapping = CsvColumnMapping {
+            start: "when".to_string(),
+            start_date: None,
+            end: None,
+            end_date: None,
+            duration: Some("minutes".to_string()),
+            tag: Some("category".to_string()),
+            remark: None,
+            date_format: "%d/%m/%Y".to_string(),
+            time_format: "%H:%M".to_string(),
+        };
+        let state = TestState::new(AppData::default());
+        let mut req = request(&path, ExternalImportSource::Generic, Some(mapping));
+        req.commit = false;
+
+        let report = import_external_impl(&state, &req).unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.invalid.len(), 1);
+        assert!(state.data_snapshot().history.is_empty());
+    }
+
+    /// 通用 CSV：缺少映射、映射列不存在或既无结束列也无时长列时应拒绝。
+    #[test]
+    fn import_generic_rejects_bad_mapping() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("generic.csv");
+        std::fs::write(&path, "start,end\n2025-01-01 09:00,2025-01-01 09:30\n").unwrap();
+        let state = TestState::new(AppData::default());
+        let mut mapping = CsvColumnMapping {
+            start: "start".to_string(),
+            start_date: None,
+            end: Some("finish".to_string()),
+            end_date: None,
+            duration: None,
+            tag: None,
+            remark: None,
+            date_format: "%Y-%m-%d".to_string(),
+            time_format: "%H:%M".to_string(),
+        };
+
+        for mapping in [None, Some(mapping.clone())] {
+            let req = request(&path, ExternalImportSource::Generic, mapping);
+            assert!(matches!(
+                import_external_impl(&state, &req),
+                Err(AppError::Validation(_))
+            ));
+        }
+        mapping.end = None;
+        let req = request(&path, ExternalImportSource::Generic, Some(mapping.clone()));
+        assert!(matches!(
+            import_external_impl(&state, &req),
+            Err(AppError::Validation(_))
+        ));
+
+        mapping.end = Some("end".to_string());
+        let req = request(&path, ExternalImportSource::Generic, Some(mapping));
+        assert_eq!(import_external_impl(&state, &req).unwrap().imported, 2);
+    }
+
+    /// `parse_duration_seconds`：支持时分秒、时分与分钟数。
+    #[test]
+    fn parse_duration_accepts_common_formats() {
+        assert_eq!(parse_duration_seconds("01:02:03"), Ok(3723));
+        assert_eq!(parse_duration_seconds("1:30"), Ok(5400));
+        assert_eq!(parse_duration_seconds("45"), Ok(2700));
+        assert!(parse_duration_seconds("1h").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! 数据完整性相关命令：扫描 `AppData` 报告问题，并可选自动修复。
+
+use crate::errors::AppResult;
+use crate::integrity::IntegrityReport;
+
+use super::state_like::CommandState;
+
+/// 检查数据完整性；`repair` 为 true 且存在可修复问题时先备份，再修复（可撤销），报告修复前发现的问题。
+pub(crate) fn check_data_integrity_impl<S: CommandState>(
+    state: &S,
+    repair: bool,
+) -> AppResult<IntegrityReport> {
+    let issues = crate::integrity::check(&state.data_snapshot());
+    let repaired = repair && issues.iter().any(|i| i.repairable);
+    if !repaired {
+        tracing::info!(target: "storage", "数据完整性检查：issues={}", issues.len());
+        return Ok(IntegrityReport {
+            issues,
+            repaired: false,
+        });
+    }
+
+    state.backup_before("repair")?;
+    let issues = state.update_undoable("修复数据完整性问题", |data, _| {
+        Ok(crate::integrity::repair(data))
+    })?;
+    tracing::info!(
+        target: "storage",
+        "已修复数据完整性问题：issues={} unrepairable={}",
+        issues.len(),
+        issues.iter().filter(|i| !i.repairable).count()
+    );
+    let _ = state.emit_timer_snapshot();
+    Ok(IntegrityReport {
+        issues,
+        repaired: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, HistoryDay, HistoryRecord};
+    use crate::commands::state_like::TestState;
+    use crate::integrity::IntegrityIssueKind;
+
+    /// 含重复日期与失效模板引用的测试状态。
+    fn broken_state() -> TestState {
+        let record = HistoryRecord::for_test("", "09:00").with_end("09:25");
+        let day = HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![record],
+        };
+        let mut data = AppData {
+            history: vec![day.clone(), day],
+            ..AppData::default()
+        };
+        data.active_template_ids.push("gone".to_string());
+        TestState::new(data)
+    }
+
+    /// 仅检查时不修改数据；修复时先备份、写入修复结果并可撤销。
+    #[test]
+    fn check_then_repair_data_integrity() {
+        let state = broken_state();
+
+        let report = check_data_integrity_impl(&state, false).unwrap();
+        assert!(!report.repaired);
+        let kinds: Vec<_> = report.issues.iter().map(|i| i.kind).collect();
+        assert!(kinds.contains(&IntegrityIssueKind::DuplicateDay));
+        assert!(kinds.contains(&IntegrityIssueKind::MissingTemplate));
+        assert_eq!(state.data_snapshot().history.len(), 2);
+        assert!(state.take_backups().is_empty());
+
+        let report = check_data_integrity_impl(&state, true).unwrap();
+        assert!(report.repaired);
+        assert_eq!(state.take_backups(), vec!["repair".to_string()]);
+        let data = state.data_snapshot();
+        assert_eq!(data.history.len(), 1);
+        assert_eq!(data.history[0].records.len(), 2);
+        assert!(crate::integrity::check(&data).is_empty());
+        assert_eq!(
+            state.undo_state().unwrap().undo.as_deref(),
+            Some("修复数据完整性问题")
+        );
+
+        let report = check_data_integrity_impl(&state, true).unwrap();
+        assert!(report.issues.is_empty());
+        assert!(!report.repaired);
+        assert!(state.take_backups().is_empty());
+    }
+}
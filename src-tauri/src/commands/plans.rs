@@ -0,0 +1,232 @@
+//! 会话计划相关命令：查询/保存/删除/启用自定义阶段序列。
+
+use crate::app_data::{Section, SessionPlan};
+use crate::errors::{AppError, AppResult};
+
+use super::state_like::CommandState;
+use super::types::AppSnapshot;
+use super::validation::validate_session_plan;
+
+/// 获取会话计划列表的内部实现。
+pub(crate) fn get_session_plans_impl<S: CommandState>(state: &S) -> AppResult<Vec<SessionPlan>> {
+    Ok(state.data_snapshot().session_plans)
+}
+
+/// 保存会话计划的内部实现：规范化并校验字段，按 id 新增或覆盖。
+pub(crate) fn save_session_plan_impl<S: CommandState>(
+    state: &S,
+    mut plan: SessionPlan,
+) -> AppResult<SessionPlan> {
+    plan.name = plan.name.trim().to_string();
+    for step in &mut plan.steps {
+        step.name = step.name.trim().to_string();
+    }
+    validate_session_plan(&plan)?;
+
+    if plan.id.trim().is_empty() {
+        let ts = chrono::Utc::now().timestamp_millis();
+        plan.id = format!("plan-{ts}");
+    }
+
+    state.update_data_and_timer(
+        |data, timer_runtime| {
+            data.touch_section(Section::Plans);
+            match data.session_plans.iter_mut().find(|p| p.id == plan.id) {
+                Some(existing) => *existing = plan.clone(),
+                None => data.session_plans.push(plan.clone()),
+            }
+            // 编辑的是当前启用的计划：未运行时同步当前步骤时长。
+            if data.active_plan_id.as_deref() == Some(plan.id.as_str()) {
+                timer_runtime.sync_idle_duration(data);
+            }
+            Ok(())
+        },
+        true,
+    )?;
+
+    tracing::info!(
+        target: "storage",
+        "保存会话计划：id={} name={} steps={}",
+        plan.id,
+        plan.name,
+        plan.steps.len()
+    );
+    let _ = state.emit_timer_snapshot();
+    Ok(plan)
+}
+
+/// 删除会话计划的内部实现：若删除的是当前启用的计划，则回退为经典循环。
+pub(crate) fn delete_session_plan_impl<S: CommandState>(state: &S, id: String) -> AppResult<bool> {
+    let id = id.trim().to_string();
+    if id.is_empty() {
+        return Err(AppError::Validation("会话计划 id 不能为空".to_string()));
+    }
+
+    let deleted = state.update_data_and_timer(
+        |data, timer_runtime| {
+            if !data.session_plans.iter().any(|p| p.id == id) {
+                return Ok(false);
+            }
+            data.touch_section(Section::Plans);
+            data.session_plans.retain(|p| p.id != id);
+            if data.active_plan_id.as_deref() == Some(id.as_str()) {
+                data.active_plan_id = None;
+                timer_runtime.sync_idle_duration(data);
+            }
+            Ok(true)
+        },
+        true,
+    )?;
+
+    if deleted {
+        tracing::info!(target: "storage", "删除会话计划：id={}", id);
+        let _ = state.emit_timer_snapshot();
+    }
+    Ok(deleted)
+}
+
+/// 启用/取消会话计划的内部实现：仅允许在未开始专注时切换，切换后计时器回到计划第一步。
+pub(crate) fn set_active_session_plan_impl<S: CommandState>(
+    state: &S,
+    id: Option<String>,
+) -> AppResult<AppSnapshot> {
+    let id = id.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+
+    let timer = state.timer_snapshot();
+    if timer.is_running || timer.blacklist_locked {
+        return Err(AppError::Validation(
+            "计时进行中，请先重置计时器再切换会话计划".to_string(),
+        ));
+    }
+
+    state.update_data_and_timer(
+        |data, timer_runtime| {
+            if let Some(id) = id.as_deref() {
+                if !data.session_plans.iter().any(|p| p.id == id) {
+                    return Err(AppError::Validation("会话计划不存在".to_string()));
+                }
+            }
+            data.touch_section(Section::Plans);
+            data.active_plan_id = id.clone();
+            timer_runtime.reset(data);
+            Ok(())
+        },
+        true,
+    )?;
+
+    tracing::info!(target: "timer", "切换会话计划：active={:?}", id);
+    let _ = state.emit_timer_snapshot();
+
+    Ok(AppSnapshot {
+        data: state.data_snapshot(),
+        timer: state.timer_snapshot(),
+        profile: state.active_profile(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, Phase, PlanStep};
+    use crate::commands::state_like::TestState;
+
+    /// 构造一个“热身 15 + 专注 50 + 休息 10”的会话计划（id 为空，由保存逻辑生成）。
+    fn sample_plan() -> SessionPlan {
+        SessionPlan {
+            id: String::new(),
+            name: " 热身计划 ".to_string(),
+            steps: vec![
+                PlanStep {
+                    name: " 热身 ".to_string(),
+                    kind: Phase::Work,
+                    minutes: 15,
+                },
+                PlanStep {
+                    name: "专注".to_string(),
+                    kind: Phase::Work,
+                    minutes: 50,
+                },
+                PlanStep {
+                    name: "休息".to_string(),
+                    kind: Phase::ShortBreak,
+                    minutes: 10,
+                },
+            ],
+        }
+    }
+
+    /// `save_session_plan_impl`：应生成 id、规范化名称，并支持按 id 覆盖。
+    #[test]
+    fn save_session_plan_generates_id_and_upserts() {
+        let state = TestState::new(AppData::default());
+
+        let saved = save_session_plan_impl(&state, sample_plan()).unwrap();
+        assert!(saved.id.starts_with("plan-"));
+        assert_eq!(saved.name, "热身计划");
+        assert_eq!(saved.steps[0].name, "热身");
+
+        let mut edited = saved.clone();
+        edited.steps[1].minutes = 45;
+        save_session_plan_impl(&state, edited).unwrap();
+
+        let plans = get_session_plans_impl(&state).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].steps[1].minutes, 45);
+    }
+
+    /// `set_active_session_plan_impl`：启用后计时器回到计划第一步；运行中禁止切换。
+    #[test]
+    fn set_active_session_plan_resets_timer_to_first_step() {
+        let state = TestState::new(AppData::default());
+        let saved = save_session_plan_impl(&state, sample_plan()).unwrap();
+
+        let snapshot = set_active_session_plan_impl(&state, Some(saved.id.clone())).unwrap();
+        assert_eq!(
+            snapshot.data.active_plan_id.as_deref(),
+            Some(saved.id.as_str())
+        );
+        assert_eq!(snapshot.timer.phase, Phase::Work);
+        assert_eq!(snapshot.timer.remaining_seconds, 15 * 60);
+
+        state
+            .update_timer(|t, _d| {
+                t.is_running = true;
+                Ok(())
+            })
+            .unwrap();
+        assert!(matches!(
+            set_active_session_plan_impl(&state, None),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// `set_active_session_plan_impl`：不存在的计划 id 应被拒绝。
+    #[test]
+    fn set_active_session_plan_rejects_unknown_id() {
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            set_active_session_plan_impl(&state, Some("missing".to_string())),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// `delete_session_plan_impl`：删除当前启用的计划应回退为经典循环并同步时长。
+    #[test]
+    fn delete_active_session_plan_falls_back_to_classic_cycle() {
+        let state = TestState::new(AppData::default());
+        let saved = save_session_plan_impl(&state, sample_plan()).unwrap();
+        set_active_session_plan_impl(&state, Some(saved.id.clone())).unwrap();
+
+        assert!(delete_session_plan_impl(&state, saved.id.clone()).unwrap());
+        assert!(!delete_session_plan_impl(&state, saved.id).unwrap());
+
+        let data = state.data_snapshot();
+        assert!(data.session_plans.is_empty());
+        assert!(data.active_plan_id.is_none());
+        assert_eq!(
+            state.timer_snapshot().remaining_seconds,
+            data.settings.pomodoro as u64 * 60
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! 配置相关命令：列出、新建与切换配置（各配置的标签、目标、黑名单模板与历史完全独立）。
+
+use std::path::Path;
+
+use crate::errors::{AppError, AppResult};
+use crate::profiles::ProfileRegistry;
+
+use super::state_like::CommandState;
+use super::types::AppSnapshot;
+
+/// 列出全部配置与当前配置。
+pub(crate) fn list_profiles_impl(data_dir: &Path) -> AppResult<ProfileRegistry> {
+    ProfileRegistry::load(data_dir)
+}
+
+/// 新建配置（不切换），返回更新后的注册表。
+pub(crate) fn create_profile_impl(
+    data_dir: &Path,
+    name: &str,
+    now: chrono::DateTime<chrono::Local>,
+) -> AppResult<ProfileRegistry> {
+    let mut registry = ProfileRegistry::load(data_dir)?;
+    let profile = registry.create(name, now)?;
+    registry.save(data_dir)?;
+    tracing::info!(target: "storage", "已新建配置：id={} name={}", profile.id, profile.name);
+    Ok(registry)
+}
+
+/// 切换到指定配置并记录为当前配置，返回新配置的快照（已是当前配置时直接返回；目标已加密时需口令）。
+pub(crate) fn switch_profile_impl<S: CommandState>(
+    state: &S,
+    data_dir: &Path,
+    profile_id: &str,
+    passphrase: Option<&str>,
+) -> AppResult<AppSnapshot> {
+    let mut registry = ProfileRegistry::load(data_dir)?;
+    let profile = registry
+        .find(profile_id)
+        .cloned()
+        .ok_or_else(|| AppError::Validation("配置不存在".to_string()))?;
+
+    if profile.id != state.active_profile().id {
+        state.switch_profile(&profile, passphrase)?;
+        registry.active = profile.id.clone();
+        registry.save(data_dir)?;
+        let _ = state.emit_timer_snapshot();
+    }
+
+    Ok(AppSnapshot {
+        data: state.data_snapshot(),
+        timer: state.timer_snapshot(),
+        profile: state.active_profile(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, Phase};
+    use crate::commands::state_like::TestState;
+    use crate::profiles::DEFAULT_PROFILE_ID;
+
+    /// 切换配置：数据互不影响，切回后恢复原数据，注册表记录当前配置。
+    #[test]
+    fn switch_profile_swaps_data_and_persists_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = AppData {
+            tags: vec!["私人阅读".to_string()],
+            total_pomodoros: 5,
+            ..AppData::default()
+        };
+        let state = TestState::new(data);
+        let registry = create_profile_impl(dir.path(), "工作", chrono::Local::now()).unwrap();
+        let work_id = registry.profiles[1].id.clone();
+
+        let snapshot = switch_profile_impl(&state, dir.path(), &work_id, None).unwrap();
+        assert_eq!(snapshot.profile.name, "工作");
+        assert_eq!(snapshot.data.total_pomodoros, 0);
+        assert!(!snapshot.data.tags.contains(&"私人阅读".to_string()));
+        assert_eq!(list_profiles_impl(dir.path()).unwrap().active, work_id);
+        assert_eq!(state.emitted_timer_snapshot_count(), 1);
+
+        let back = switch_profile_impl(&state, dir.path(), DEFAULT_PROFILE_ID, None).unwrap();
+        assert_eq!(back.profile.id, DEFAULT_PROFILE_ID);
+        assert_eq!(back.data.total_pomodoros, 5);
+
+        // 已是当前配置：不重复切换。
+        switch_profile_impl(&state, dir.path(), DEFAULT_PROFILE_ID, None).unwrap();
+        assert_eq!(state.emitted_timer_snapshot_count(), 2);
+    }
+
+    /// 切换配置：运行中的休息倒计时先暂停再换出，切回后保持暂停（不按离开的时长补算阶段）。
+    #[test]
+    fn switch_profile_pauses_running_break() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = TestState::new(AppData::default());
+        let registry = create_profile_impl(dir.path(), "工作", chrono::Local::now()).unwrap();
+        let work_id = registry.profiles[1].id.clone();
+        state
+            .update_timer(|timer, data| {
+                timer.phase = Phase::ShortBreak;
+                timer.remaining_seconds = 300;
+                timer.start(data, &crate::timer::SystemClock);
+                Ok(())
+            })
+            .unwrap();
+        assert!(!state.timer_snapshot().blacklist_locked);
+
+        switch_profile_impl(&state, dir.path(), &work_id, None).unwrap();
+        let back = switch_profile_impl(&state, dir.path(), DEFAULT_PROFILE_ID, None).unwrap();
+        assert!(!back.timer.is_running);
+        assert_eq!(back.timer.phase, Phase::ShortBreak);
+        assert!(back.timer.remaining_seconds > 0 && back.timer.remaining_seconds <= 300);
+    }
+
+    /// 切换配置：目标不存在或专注锁定中应拒绝，且当前配置保持不变。
+    #[test]
+    fn switch_profile_rejects_unknown_and_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            switch_profile_impl(&state, dir.path(), "missing", None),
+            Err(AppError::Validation(_))
+        ));
+
+        let registry = create_profile_impl(dir.path(), "工作", chrono::Local::now()).unwrap();
+        let work_id = registry.profiles[1].id.clone();
+        state
+            .update_timer(|timer, data| {
+                timer.start(data, &crate::timer::SystemClock);
+                Ok(())
+            })
+            .unwrap();
+        assert!(state.timer_snapshot().blacklist_locked);
+        assert!(matches!(
+            switch_profile_impl(&state, dir.path(), &work_id, None),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(state.active_profile().id, DEFAULT_PROFILE_ID);
+        assert_eq!(
+            list_profiles_impl(dir.path()).unwrap().active,
+            DEFAULT_PROFILE_ID
+        );
+    }
+}
@@ -1,8 +1,9 @@
 //! 设置相关命令：更新 settings、设置目标等。
 
-use crate::app_data::{Phase, Settings};
+use crate::app_data::{Section, Settings};
 use crate::errors::AppResult;
 use crate::timer;
+use crate::timer::stats::GoalProgress;
 
 use super::state_like::CommandState;
 use super::types::AppSnapshot;
@@ -28,22 +29,13 @@ pub(crate) fn update_settings_impl<S: CommandState>(
 
     state.update_data_and_timer(
         |data, timer_runtime| {
-            data.settings = settings.clone();
+            data.touch_section(Section::Settings);
+            let mut settings = settings.clone();
+            data.bind_tag_ids(&mut settings);
+            data.settings = settings;
 
             // 若当前未运行，则根据阶段同步剩余时间，以保证 UI 与设置一致。
-            if !timer_runtime.is_running {
-                match timer_runtime.phase {
-                    Phase::Work => {
-                        timer_runtime.remaining_seconds = settings.pomodoro as u64 * 60;
-                    }
-                    Phase::ShortBreak => {
-                        timer_runtime.remaining_seconds = settings.short_break as u64 * 60;
-                    }
-                    Phase::LongBreak => {
-                        timer_runtime.remaining_seconds = settings.long_break as u64 * 60;
-                    }
-                }
-            }
+            timer_runtime.sync_idle_duration(data);
             Ok(())
         },
         true,
@@ -54,6 +46,7 @@ pub(crate) fn update_settings_impl<S: CommandState>(
     Ok(AppSnapshot {
         data: state.data_snapshot(),
         timer: state.timer_snapshot(),
+        profile: state.active_profile(),
     })
 }
 
@@ -69,6 +62,7 @@ pub(crate) fn set_goals_impl<S: CommandState>(
     timer::validate_settings(&next)?;
 
     state.update_data(|data| {
+        data.touch_section(Section::Settings);
         data.settings.daily_goal = daily;
         data.settings.weekly_goal = weekly;
         Ok(())
@@ -80,13 +74,18 @@ pub(crate) fn set_goals_impl<S: CommandState>(
     Ok(state.data_snapshot().settings)
 }
 
+/// 获取目标进度（每日/每周/每月/每年、标签目标与连续达标）的内部实现。
+pub(crate) fn get_goal_progress_impl<S: CommandState>(state: &S) -> AppResult<GoalProgress> {
+    Ok(state.timer_snapshot().goal_progress)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::sync::Once;
 
-    use crate::app_data::AppData;
+    use crate::app_data::{AppData, Phase};
     use crate::commands::state_like::CommandState;
     use crate::commands::state_like::TestState;
 
@@ -190,4 +189,24 @@ mod tests {
         let err = set_goals_impl(&state, 1001, 0).unwrap_err();
         assert!(matches!(err, crate::errors::AppError::Validation(_)));
     }
+
+    /// `get_goal_progress_impl`：应返回包含月/年目标与标签目标的进度。
+    #[test]
+    fn get_goal_progress_reports_configured_goals() {
+        use crate::app_data::{GoalPeriod, TagGoal};
+        let mut data = AppData::default();
+        data.settings.monthly_goal = 30;
+        data.settings.tag_goals = vec![TagGoal {
+            tag: "学习".to_string(),
+            tag_id: None,
+            period: GoalPeriod::Weekly,
+            target: 10,
+        }];
+        let state = TestState::new(data);
+
+        let out = get_goal_progress_impl(&state).unwrap();
+        assert_eq!(out.monthly_goal, 30);
+        assert_eq!(out.tag_goals.len(), 1);
+        assert_eq!(out.tag_goals[0].completed, 0);
+    }
 }
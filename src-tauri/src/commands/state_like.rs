@@ -1,13 +1,19 @@
 //! 命令层可测试状态抽象：用 trait 解耦 `AppState`，便于单元测试 commands/\*.rs。
 
-use crate::app_data::AppData;
+use crate::app_data::{AppData, HistoryDay, Settings};
+use crate::audit::{AuditEntry, DomainEvent};
+use crate::crypto::Cipher;
 use crate::errors::AppResult;
 use crate::processes::KillSummary;
+use crate::profiles::ProfileInfo;
 use crate::timer::{TimerRuntime, TimerSnapshot};
+use crate::undo::{UndoEntry, UndoStack, UndoState};
 
 #[cfg(not(test))]
 use crate::state::AppState;
 
+#[cfg(test)]
+use std::collections::HashMap;
 #[cfg(test)]
 use std::sync::{atomic::AtomicUsize, atomic::Ordering, Mutex};
 
@@ -19,6 +25,15 @@ pub(crate) trait CommandState {
     /// 获取计时器快照（只读，包含统计/目标进度等派生字段）。
     fn timer_snapshot(&self) -> TimerSnapshot;
 
+    /// 获取设置快照（只读）。
+    fn settings_snapshot(&self) -> Settings;
+
+    /// 按自然日期闭区间查询历史（开发环境优先 `history_dev`；按日期升序）。
+    fn history_in_range(&self, from: &str, to: &str) -> AppResult<Vec<HistoryDay>>;
+
+    /// 逻辑日期 `date`（YYYY-MM-DD）已完成的工作番茄数（正式实现按历史数据库聚合）。
+    fn completed_on(&self, date: &str) -> u32;
+
     /// 原子更新：修改数据并持久化（测试实现可忽略持久化）。
     fn update_data(&self, f: impl FnOnce(&mut AppData) -> AppResult<()>) -> AppResult<()>;
 
@@ -35,6 +50,25 @@ pub(crate) trait CommandState {
         persist: bool,
     ) -> AppResult<T>;
 
+    /// 可撤销的原子更新：同 `update_data_and_timer(f, true)`，并把本次变更以 `description` 压入撤销栈（清空重做栈）。
+    fn update_undoable<T>(
+        &self,
+        description: &str,
+        f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
+    ) -> AppResult<T>;
+
+    /// 取出撤销栈或重做栈顶的操作。
+    fn pop_undo(&self, stack: UndoStack) -> AppResult<Option<UndoEntry>>;
+
+    /// 向撤销栈或重做栈压入操作（不清空另一个栈）。
+    fn push_undo(&self, stack: UndoStack, entry: UndoEntry) -> AppResult<()>;
+
+    /// 待撤销与待重做操作的描述。
+    fn undo_state(&self) -> AppResult<UndoState>;
+
+    /// 在破坏性操作前备份当前数据（`reason` 为操作标识，如 `delete-tag`；测试实现只记录）。
+    fn backup_before(&self, reason: &str) -> AppResult<()>;
+
     /// 推送当前计时器快照事件给前端（测试实现可记录调用）。
     fn emit_timer_snapshot(&self) -> AppResult<()>;
 
@@ -43,6 +77,27 @@ pub(crate) trait CommandState {
 
     /// 推送一个“无结构负载”的简单事件给前端（测试实现可记录 event）。
     fn emit_simple_event(&self, event: &str) -> AppResult<()>;
+
+    /// 获取当前配置。
+    fn active_profile(&self) -> ProfileInfo;
+
+    /// 切换配置：保存当前配置（运行中的计时器先暂停）后换入目标配置的数据与计时器（专注锁定中拒绝切换；目标已加密时需口令）。
+    fn switch_profile(&self, profile: &ProfileInfo, passphrase: Option<&str>) -> AppResult<()>;
+
+    /// 当前配置的加密密钥（未启用加密时为 `None`）。
+    fn cipher(&self) -> Option<Cipher>;
+
+    /// 启用、更换（`Some`）或关闭（`None`）当前配置的加密，并以新状态重写已保存的数据。
+    fn set_cipher(&self, cipher: Option<Cipher>) -> AppResult<()>;
+
+    /// 查询某个主体（如 `history:<记录 id>`）在审计日志中的变更历史。
+    fn change_history(&self, subject: &str) -> AppResult<Vec<AuditEntry>>;
+
+    /// 读取审计日志中的全部事件（按发生顺序）。
+    fn audit_events(&self) -> AppResult<Vec<DomainEvent>>;
+
+    /// 读取审计基线引用的数据快照。
+    fn audit_snapshot(&self, id: i64) -> AppResult<AppData>;
 }
 
 #[cfg(not(test))]
@@ -57,6 +112,21 @@ impl CommandState for AppState {
         AppState::timer_snapshot(self)
     }
 
+    /// 读取设置快照。
+    fn settings_snapshot(&self) -> Settings {
+        AppState::settings_snapshot(self)
+    }
+
+    /// 从历史数据库按范围查询历史。
+    fn history_in_range(&self, from: &str, to: &str) -> AppResult<Vec<HistoryDay>> {
+        AppState::history_in_range(self, from, to)
+    }
+
+    /// 按历史数据库聚合当日完成数。
+    fn completed_on(&self, date: &str) -> u32 {
+        AppState::completed_on(self, date)
+    }
+
     /// 原子更新：修改数据并持久化到 store。
     fn update_data(&self, f: impl FnOnce(&mut AppData) -> AppResult<()>) -> AppResult<()> {
         AppState::update_data(self, f)
@@ -79,6 +149,35 @@ impl CommandState for AppState {
         AppState::update_data_and_timer(self, f, persist)
     }
 
+    /// 修改并持久化数据，同时记录撤销信息。
+    fn update_undoable<T>(
+        &self,
+        description: &str,
+        f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
+    ) -> AppResult<T> {
+        AppState::update_undoable(self, description, f)
+    }
+
+    /// 从历史数据库取出栈顶操作。
+    fn pop_undo(&self, stack: UndoStack) -> AppResult<Option<UndoEntry>> {
+        AppState::pop_undo(self, stack)
+    }
+
+    /// 向历史数据库压入操作。
+    fn push_undo(&self, stack: UndoStack, entry: UndoEntry) -> AppResult<()> {
+        AppState::push_undo(self, stack, &entry)
+    }
+
+    /// 从历史数据库读取撤销/重做状态。
+    fn undo_state(&self) -> AppResult<UndoState> {
+        AppState::undo_state(self)
+    }
+
+    /// 写入一份操作前备份。
+    fn backup_before(&self, reason: &str) -> AppResult<()> {
+        AppState::backup_before(self, reason)
+    }
+
     /// 向前端广播计时器快照事件。
     fn emit_timer_snapshot(&self) -> AppResult<()> {
         AppState::emit_timer_snapshot(self)
@@ -93,6 +192,41 @@ impl CommandState for AppState {
     fn emit_simple_event(&self, event: &str) -> AppResult<()> {
         AppState::emit_simple_event(self, event)
     }
+
+    /// 读取当前配置。
+    fn active_profile(&self) -> ProfileInfo {
+        AppState::active_profile(self)
+    }
+
+    /// 换入目标配置的 store/历史数据库/数据/计时器。
+    fn switch_profile(&self, profile: &ProfileInfo, passphrase: Option<&str>) -> AppResult<()> {
+        AppState::switch_profile(self, profile, passphrase)
+    }
+
+    /// 读取当前配置的加密密钥。
+    fn cipher(&self) -> Option<Cipher> {
+        AppState::cipher(self)
+    }
+
+    /// 以新密钥（或明文）重写 store、历史数据库与备份。
+    fn set_cipher(&self, cipher: Option<Cipher>) -> AppResult<()> {
+        AppState::set_cipher(self, cipher)
+    }
+
+    /// 从历史数据库的事件表查询变更历史。
+    fn change_history(&self, subject: &str) -> AppResult<Vec<AuditEntry>> {
+        AppState::change_history(self, subject)
+    }
+
+    /// 从历史数据库的事件表读取全部事件。
+    fn audit_events(&self) -> AppResult<Vec<DomainEvent>> {
+        AppState::audit_events(self)
+    }
+
+    /// 从历史数据库读取审计快照。
+    fn audit_snapshot(&self, id: i64) -> AppResult<AppData> {
+        AppState::audit_snapshot(self, id)
+    }
 }
 
 /// 测试用状态：以内存模拟 `AppState`（不依赖 Tauri runtime / store / AppHandle）。
@@ -108,6 +242,22 @@ pub(crate) struct TestState {
     emitted_kill_results: Mutex<Vec<KillSummary>>,
     /// 记录所有简单事件名。
     emitted_events: Mutex<Vec<String>>,
+    /// 记录所有操作前备份的原因。
+    backups: Mutex<Vec<String>>,
+    /// 当前配置。
+    profile: Mutex<ProfileInfo>,
+    /// 未激活配置的数据与计时器运行态（按配置 id）。
+    inactive_profiles: Mutex<HashMap<String, (AppData, TimerRuntime)>>,
+    /// 当前配置的加密密钥。
+    cipher: Mutex<Option<Cipher>>,
+    /// 内存中的审计日志（以初始数据为基线，每次持久化式更新后追加登记的事件）。
+    audit: Mutex<Vec<DomainEvent>>,
+    /// 审计基线引用的快照（快照 id 为下标加 1）。
+    snapshots: Mutex<Vec<AppData>>,
+    /// 内存中的撤销栈与重做栈（栈顶在末尾）。
+    undo_stacks: Mutex<(Vec<UndoEntry>, Vec<UndoEntry>)>,
+    /// 配置数据目录（设置后历史查询会合并其中的归档文件）。
+    profile_dir: Option<std::path::PathBuf>,
 }
 
 #[cfg(test)]
@@ -116,13 +266,44 @@ impl TestState {
     pub(crate) fn new(data: AppData) -> Self {
         let clock = crate::timer::SystemClock;
         let timer = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        let snapshot = data.clone();
         Self {
             data: Mutex::new(data),
             timer: Mutex::new(timer),
             emitted_timer_snapshots: AtomicUsize::new(0),
             emitted_kill_results: Mutex::new(Vec::new()),
             emitted_events: Mutex::new(Vec::new()),
+            backups: Mutex::new(Vec::new()),
+            profile: Mutex::new(ProfileInfo::default_profile()),
+            inactive_profiles: Mutex::new(HashMap::new()),
+            cipher: Mutex::new(None),
+            audit: Mutex::new(vec![DomainEvent::Baseline { snapshot: 1 }]),
+            snapshots: Mutex::new(vec![snapshot]),
+            undo_stacks: Mutex::new((Vec::new(), Vec::new())),
+            profile_dir: None,
+        }
+    }
+
+    /// 指定配置数据目录（用于测试归档历史的透明查询）。
+    pub(crate) fn with_profile_dir(mut self, dir: &std::path::Path) -> Self {
+        self.profile_dir = Some(dir.to_path_buf());
+        self
+    }
+
+    /// 取出 `data` 中登记的变更并追加审计事件（模拟持久化；整体替换时追加新的基线）。
+    fn record_audit(&self, data: &mut AppData) -> AppResult<()> {
+        let pending = std::mem::take(&mut data.pending);
+        let mut audit = self.audit.lock().unwrap();
+        if pending.all_records {
+            let mut snapshots = self.snapshots.lock().unwrap();
+            snapshots.push(data.clone());
+            audit.push(DomainEvent::Baseline {
+                snapshot: snapshots.len() as i64,
+            });
+        } else {
+            audit.extend(crate::audit::pending_events(&pending, data)?);
         }
+        Ok(())
     }
 
     /// 读取已记录的“计时器快照事件”触发次数。
@@ -139,26 +320,66 @@ impl TestState {
     pub(crate) fn take_events(&self) -> Vec<String> {
         std::mem::take(&mut *self.emitted_events.lock().unwrap())
     }
+
+    /// 取出已记录的操作前备份原因（按调用顺序）。
+    pub(crate) fn take_backups(&self) -> Vec<String> {
+        std::mem::take(&mut *self.backups.lock().unwrap())
+    }
 }
 
 #[cfg(test)]
 impl CommandState for TestState {
     /// 读取一份 `AppData` 的快照（用于断言）。
     fn data_snapshot(&self) -> AppData {
-        self.data.lock().unwrap().clone()
+        self.data.lock().unwrap().resolved()
     }
 
     /// 获取计时器快照（用于断言）。
     fn timer_snapshot(&self) -> TimerSnapshot {
         let data = self.data.lock().unwrap();
         let timer = self.timer.lock().unwrap();
-        timer.snapshot(&data)
+        timer.snapshot(&data, None)
+    }
+
+    /// 读取设置快照。
+    fn settings_snapshot(&self) -> Settings {
+        self.data.lock().unwrap().settings.clone()
+    }
+
+    /// 按范围筛选内存中的历史（测试实现不依赖数据库；指定了配置数据目录时合并归档）。
+    fn history_in_range(&self, from: &str, to: &str) -> AppResult<Vec<HistoryDay>> {
+        let data = self.data.lock().unwrap();
+        let mut out: Vec<HistoryDay> = crate::commands::validation::history_for_ui(&data)
+            .iter()
+            .filter(|d| d.date.as_str() >= from && d.date.as_str() <= to)
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| a.date.cmp(&b.date));
+        let mut out = match &self.profile_dir {
+            Some(dir) if data.history_dev.is_empty() => crate::archive::include_archived(
+                &crate::archive::archive_dir(dir),
+                self.cipher.lock().unwrap().as_ref(),
+                data.history_archive.before.as_deref(),
+                from,
+                to,
+                out,
+            )?,
+            _ => out,
+        };
+        crate::app_data::resolve_history_tags(&mut out, &data.tag_paths());
+        Ok(out)
+    }
+
+    /// 统计内存中的记录。
+    fn completed_on(&self, date: &str) -> u32 {
+        crate::timer::compute_today_stats(&*self.data.lock().unwrap(), date).total
     }
 
-    /// 原子更新：修改数据（测试实现不做持久化）。
+    /// 原子更新：修改数据（测试实现不做持久化，只记录审计事件）。
     fn update_data(&self, f: impl FnOnce(&mut AppData) -> AppResult<()>) -> AppResult<()> {
         let mut data = self.data.lock().unwrap();
-        f(&mut data)
+        f(&mut data)?;
+        self.record_audit(&mut data)
     }
 
     /// 修改计时器运行态（测试实现直接更新内存）。
@@ -171,15 +392,74 @@ impl CommandState for TestState {
         f(&mut timer, &data)
     }
 
-    /// 同时修改 `AppData` 与 `TimerRuntime`（测试实现忽略 persist 参数）。
+    /// 同时修改 `AppData` 与 `TimerRuntime`（`persist` 为 true 时记录审计事件）。
     fn update_data_and_timer<T>(
         &self,
         f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
-        _persist: bool,
+        persist: bool,
+    ) -> AppResult<T> {
+        let mut data = self.data.lock().unwrap();
+        let mut timer = self.timer.lock().unwrap();
+        let out = f(&mut data, &mut timer)?;
+        if persist {
+            self.record_audit(&mut data)?;
+        }
+        Ok(out)
+    }
+
+    /// 修改数据并记录审计事件与撤销信息（测试实现不做持久化）。
+    fn update_undoable<T>(
+        &self,
+        description: &str,
+        f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
     ) -> AppResult<T> {
         let mut data = self.data.lock().unwrap();
         let mut timer = self.timer.lock().unwrap();
-        f(&mut data, &mut timer)
+        data.begin_undo_capture();
+        let out = f(&mut data, &mut timer);
+        let capture = data.take_undo_capture();
+        let out = out?;
+        self.record_audit(&mut data)?;
+        if let Some(entry) = UndoEntry::from_capture(description, capture, &data)? {
+            let mut stacks = self.undo_stacks.lock().unwrap();
+            stacks.1.clear();
+            push_bounded(&mut stacks.0, entry);
+        }
+        Ok(out)
+    }
+
+    /// 取出内存栈顶的操作。
+    fn pop_undo(&self, stack: UndoStack) -> AppResult<Option<UndoEntry>> {
+        let mut stacks = self.undo_stacks.lock().unwrap();
+        Ok(match stack {
+            UndoStack::Undo => stacks.0.pop(),
+            UndoStack::Redo => stacks.1.pop(),
+        })
+    }
+
+    /// 向内存栈压入操作。
+    fn push_undo(&self, stack: UndoStack, entry: UndoEntry) -> AppResult<()> {
+        let mut stacks = self.undo_stacks.lock().unwrap();
+        match stack {
+            UndoStack::Undo => push_bounded(&mut stacks.0, entry),
+            UndoStack::Redo => push_bounded(&mut stacks.1, entry),
+        }
+        Ok(())
+    }
+
+    /// 读取内存栈顶的操作描述。
+    fn undo_state(&self) -> AppResult<UndoState> {
+        let stacks = self.undo_stacks.lock().unwrap();
+        Ok(UndoState {
+            undo: stacks.0.last().map(|e| e.description.clone()),
+            redo: stacks.1.last().map(|e| e.description.clone()),
+        })
+    }
+
+    /// 记录一次操作前备份（测试实现不写文件）。
+    fn backup_before(&self, reason: &str) -> AppResult<()> {
+        self.backups.lock().unwrap().push(reason.to_string());
+        Ok(())
     }
 
     /// 记录一次“计时器快照事件”触发。
@@ -199,4 +479,92 @@ impl CommandState for TestState {
         self.emitted_events.lock().unwrap().push(event.to_string());
         Ok(())
     }
+
+    /// 读取当前配置。
+    fn active_profile(&self) -> ProfileInfo {
+        self.profile.lock().unwrap().clone()
+    }
+
+    /// 在内存中换入目标配置的数据与计时器（运行中的计时器先暂停；首次切换到的配置使用默认数据；测试实现不校验口令）。
+    fn switch_profile(&self, profile: &ProfileInfo, _passphrase: Option<&str>) -> AppResult<()> {
+        let mut data = self.data.lock().unwrap();
+        let mut timer = self.timer.lock().unwrap();
+        if timer.blacklist_locked() {
+            return Err(crate::errors::AppError::Validation(
+                "专注进行中，无法切换配置".to_string(),
+            ));
+        }
+        let clock = crate::timer::SystemClock;
+        timer.pause(&clock);
+        let mut current = self.profile.lock().unwrap();
+        let mut inactive = self.inactive_profiles.lock().unwrap();
+        let (next_data, next_timer) = inactive.remove(&profile.id).unwrap_or_else(|| {
+            let data = AppData::default();
+            let timer = TimerRuntime::new(&data.settings, &data.tags, &clock);
+            (data, timer)
+        });
+        inactive.insert(
+            current.id.clone(),
+            (
+                std::mem::replace(&mut *data, next_data),
+                std::mem::replace(&mut *timer, next_timer),
+            ),
+        );
+        *current = profile.clone();
+        Ok(())
+    }
+
+    /// 读取内存中的加密密钥。
+    fn cipher(&self) -> Option<Cipher> {
+        self.cipher.lock().unwrap().clone()
+    }
+
+    /// 仅替换内存中的加密密钥（测试实现不重写数据）。
+    fn set_cipher(&self, cipher: Option<Cipher>) -> AppResult<()> {
+        *self.cipher.lock().unwrap() = cipher;
+        Ok(())
+    }
+
+    /// 按主体筛选内存中的审计事件（序号从 1 开始）。
+    fn change_history(&self, subject: &str) -> AppResult<Vec<AuditEntry>> {
+        Ok(self
+            .audit
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.subject() == subject)
+            .map(|(i, e)| AuditEntry {
+                seq: i as u32 + 1,
+                at: String::new(),
+                kind: e.kind().to_string(),
+                subject: e.subject(),
+                summary: e.summary(),
+            })
+            .collect())
+    }
+
+    /// 读取内存中的审计事件。
+    fn audit_events(&self) -> AppResult<Vec<DomainEvent>> {
+        Ok(self.audit.lock().unwrap().clone())
+    }
+
+    /// 读取内存中的审计快照。
+    fn audit_snapshot(&self, id: i64) -> AppResult<AppData> {
+        usize::try_from(id - 1)
+            .ok()
+            .and_then(|index| self.snapshots.lock().unwrap().get(index).cloned())
+            .ok_or_else(|| {
+                crate::errors::AppError::Invariant(format!("审计日志引用的快照不存在：{id}"))
+            })
+    }
+}
+
+/// 压入操作并丢弃超出 `UNDO_LIMIT` 的最旧记录（与数据库实现一致）。
+#[cfg(test)]
+fn push_bounded(stack: &mut Vec<UndoEntry>, entry: UndoEntry) {
+    stack.push(entry);
+    if stack.len() > crate::undo::UNDO_LIMIT {
+        stack.remove(0);
+    }
 }
@@ -0,0 +1,183 @@
+//! 文件夹同步相关命令：启用/关闭同步、查询状态与立即同步（同步配置保存在配置数据目录）。
+
+use std::path::{Path, PathBuf};
+
+use crate::errors::{AppError, AppResult};
+use crate::sync::{SyncConfig, SyncReport, SyncStatus};
+
+use super::state_like::CommandState;
+
+/// 查询当前配置的同步状态。
+pub(crate) fn get_sync_status_impl(profile_dir: &Path) -> AppResult<SyncStatus> {
+    crate::sync::status(SyncConfig::load(profile_dir)?.as_ref())
+}
+
+/// 启用同步（或更换共享文件夹/设备名称）并立即同步一次；已加密的配置不支持同步。
+pub(crate) fn enable_sync_impl<S: CommandState>(
+    state: &S,
+    profile_dir: &Path,
+    folder: &str,
+    device_name: &str,
+) -> AppResult<SyncStatus> {
+    if state.cipher().is_some() {
+        return Err(AppError::Validation(
+            "已加密的配置暂不支持文件夹同步".to_string(),
+        ));
+    }
+    let folder = PathBuf::from(folder.trim());
+    if !folder.is_absolute() || !folder.is_dir() {
+        return Err(AppError::Validation("请选择已存在的同步文件夹".to_string()));
+    }
+    if device_name.trim().is_empty() {
+        return Err(AppError::Validation("设备名称不能为空".to_string()));
+    }
+
+    let previous = SyncConfig::load(profile_dir)?;
+    let mut config = crate::sync::new_config(folder, device_name, previous.as_ref());
+    if let Some(previous) = previous.filter(|p| p.folder == config.folder) {
+        config.seen = previous.seen;
+        config.last_sync_at = previous.last_sync_at;
+    }
+    config.save(profile_dir)?;
+    tracing::info!(
+        target: "sync",
+        "已启用文件夹同步：folder={} device={}",
+        config.folder.display(),
+        config.device_id
+    );
+    sync_now_impl(state, profile_dir)?;
+    get_sync_status_impl(profile_dir)
+}
+
+/// 关闭同步（共享文件夹中的日志保持不变）。
+pub(crate) fn disable_sync_impl(profile_dir: &Path) -> AppResult<SyncStatus> {
+    SyncConfig::remove(profile_dir)?;
+    tracing::info!(target: "sync", "已关闭文件夹同步");
+    get_sync_status_impl(profile_dir)
+}
+
+/// 立即同步：有其他设备的新变更时先备份，再发布本机变更并合并全部日志。
+pub(crate) fn sync_now_impl<S: CommandState>(
+    state: &S,
+    profile_dir: &Path,
+) -> AppResult<SyncReport> {
+    let mut config = SyncConfig::load(profile_dir)?
+        .ok_or_else(|| AppError::Validation("尚未启用文件夹同步".to_string()))?;
+    if state.cipher().is_some() {
+        return Err(AppError::Validation(
+            "已加密的配置暂不支持文件夹同步".to_string(),
+        ));
+    }
+    if crate::sync::has_pending(&config)? {
+        state.backup_before("sync")?;
+    }
+
+    let report = state.update_data_and_timer(
+        |data, _| crate::sync::run(&mut config, data, chrono::Local::now()),
+        true,
+    )?;
+    config.save(profile_dir)?;
+    tracing::info!(
+        target: "sync",
+        "同步完成：published={} received={} conflicts={} skipped={}",
+        report.published,
+        report.received,
+        report.conflicts.len(),
+        report.skipped.len()
+    );
+    if report.received > 0 {
+        let _ = state.emit_simple_event(crate::sync::EVENT_SYNCED);
+        let _ = state.emit_timer_snapshot();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, HistoryDay, HistoryRecord};
+    use crate::commands::state_like::TestState;
+    use crate::crypto::Cipher;
+
+    /// 构造带一条历史记录的数据。
+    fn data_with_record(start: &str) -> AppData {
+        AppData {
+            history: vec![HistoryDay {
+                date: "2025-03-01".to_string(),
+                records: vec![HistoryRecord {
+                    started_at: Some(format!("2025-03-01T{start}:00+00:00")),
+                    ..HistoryRecord::for_test("工作", start).with_tag_id(Some("tag-1".to_string()))
+                }],
+            }],
+            ..AppData::default()
+        }
+    }
+
+    /// 两台设备启用同步后互相合并记录；合并前备份并通知前端刷新；关闭后不再同步。
+    #[test]
+    fn enable_sync_merges_devices_and_disable_stops() {
+        let folder = tempfile::tempdir().unwrap();
+        let folder_path = folder.path().to_string_lossy().to_string();
+        let desktop_dir = tempfile::tempdir().unwrap();
+        let laptop_dir = tempfile::tempdir().unwrap();
+        let desktop = TestState::new(data_with_record("09:00"));
+        let laptop = TestState::new(data_with_record("10:00"));
+
+        assert!(!get_sync_status_impl(desktop_dir.path()).unwrap().enabled);
+        assert!(matches!(
+            enable_sync_impl(&desktop, desktop_dir.path(), "relative/dir", "台式机"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            sync_now_impl(&desktop, desktop_dir.path()),
+            Err(AppError::Validation(_))
+        ));
+
+        let status =
+            enable_sync_impl(&desktop, desktop_dir.path(), &folder_path, "台式机").unwrap();
+        assert!(status.enabled);
+        assert_eq!(status.devices.len(), 1);
+        assert!(desktop.take_backups().is_empty());
+
+        let status = enable_sync_impl(&laptop, laptop_dir.path(), &folder_path, "笔记本").unwrap();
+        assert_eq!(status.devices.len(), 2);
+        assert_eq!(laptop.take_backups(), vec!["sync".to_string()]);
+        assert_eq!(laptop.data_snapshot().history[0].records.len(), 2);
+        assert!(laptop
+            .take_events()
+            .contains(&crate::sync::EVENT_SYNCED.to_string()));
+
+        let report = sync_now_impl(&desktop, desktop_dir.path()).unwrap();
+        assert_eq!(report.received, 1);
+        assert_eq!(desktop.data_snapshot().history[0].records.len(), 2);
+
+        let status = disable_sync_impl(desktop_dir.path()).unwrap();
+        assert!(!status.enabled);
+        assert!(matches!(
+            sync_now_impl(&desktop, desktop_dir.path()),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// 已加密的配置不能启用同步。
+    #[test]
+    fn enable_sync_rejects_encrypted_profile() {
+        let folder = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = TestState::new(AppData::default());
+        state
+            .set_cipher(Some(Cipher::generate("first-pass").unwrap()))
+            .unwrap();
+        assert!(matches!(
+            enable_sync_impl(
+                &state,
+                dir.path(),
+                &folder.path().to_string_lossy(),
+                "台式机"
+            ),
+            Err(AppError::Validation(_))
+        ));
+        assert!(!get_sync_status_impl(dir.path()).unwrap().enabled);
+    }
+}
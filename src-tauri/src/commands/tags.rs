@@ -1,26 +1,54 @@
-//! 标签相关命令：设置当前标签、管理标签列表。
+//! 标签相关命令：设置当前标签、管理标签定义（层级/颜色/图标/归档）。
 
+use std::collections::BTreeSet;
+
+use crate::app_data::{normalize_tag_path, tag_path_within, AppData, RecordSet, Section, Tag};
+use crate::audit::DomainEvent;
 use crate::errors::{AppError, AppResult};
 
 use super::state_like::CommandState;
+use super::templates::activate_templates;
 use super::types::AppSnapshot;
 
+/// 默认标签（不可删除/归档，计时器当前标签为空时回退到它）。
+pub(crate) const DEFAULT_TAG: &str = "工作";
+
 /// 设置当前标签的内部实现（便于统一错误处理）。
+///
+/// 标签按路径（如 `工作/项目X`）解析，缺失的各级标签会自动创建；已归档的标签不可选用。
+/// 若该标签配置了覆盖项：阶段尚未开始时按其时长同步剩余时间；黑名单未锁定时切换到其指定的模板集合。
 pub(crate) fn set_current_tag_impl<S: CommandState>(
     state: &S,
     tag: String,
 ) -> AppResult<AppSnapshot> {
     let clock = crate::timer::SystemClock;
-    let tag = tag.trim().to_string();
+    let tag = normalize_tag_path(&tag);
     if tag.is_empty() {
         return Err(AppError::Validation("标签不能为空".to_string()));
     }
 
     state.update_data_and_timer(
         |data, timer_runtime| {
+            if find_tag(data, &tag).is_some_and(|t| t.archived) {
+                return Err(AppError::Validation("标签已归档，请先取消归档".to_string()));
+            }
             timer_runtime.set_current_tag(tag.clone(), &clock);
-            if !data.tags.iter().any(|t| t == &tag) {
-                data.tags.push(tag);
+            if timer_runtime.phase_started_at().is_none() {
+                timer_runtime.sync_idle_duration(data);
+            }
+            let template_ids = data
+                .settings
+                .tag_override(&tag, data.tag_id_by_path(&tag).as_deref())
+                .and_then(|o| o.blacklist_template_ids.clone());
+            if let Some(ids) = template_ids {
+                if !timer_runtime.blacklist_locked() {
+                    activate_templates(data, &ids);
+                }
+            }
+            if data.tag_id_by_path(&tag).is_none() {
+                data.touch_section(Section::Tags);
+                data.ensure_tag_path(&tag);
+                data.sync_tags();
             }
             Ok(())
         },
@@ -32,35 +60,42 @@ pub(crate) fn set_current_tag_impl<S: CommandState>(
     Ok(AppSnapshot {
         data: state.data_snapshot(),
         timer: state.timer_snapshot(),
+        profile: state.active_profile(),
     })
 }
 
-/// 新增标签的内部实现（便于统一错误处理）。
+/// 新增标签的内部实现（便于统一错误处理）：按路径逐级创建，已归档的同名标签会被恢复。
 pub(crate) fn add_tag_impl<S: CommandState>(state: &S, tag: String) -> AppResult<Vec<String>> {
-    let tag = tag.trim().to_string();
+    let tag = normalize_tag_path(&tag);
     if tag.is_empty() {
         return Err(AppError::Validation("标签不能为空".to_string()));
     }
 
-    state.update_data(|data| {
-        if !data.tags.iter().any(|t| t == &tag) {
-            data.tags.push(tag);
+    state.update_undoable(&format!("新增标签「{tag}」"), |data, _| {
+        data.touch_section(Section::Tags);
+        let id = data.ensure_tag_path(&tag);
+        if let Some(t) = data.tag_defs.iter_mut().find(|t| t.id == id) {
+            t.archived = false;
         }
+        data.sync_tags();
         Ok(())
     })?;
 
     Ok(state.data_snapshot().tags)
 }
 
-/// 重命名标签的内部实现：同步更新 tags 列表、计时器当前标签与历史记录。
+/// 重命名标签的内部实现：`to` 为新的完整路径（可借此移动到其他父标签下）。
+///
+/// 历史/中断记录、标签覆盖项/目标与任务都按标签 id 关联，读取时解析为新路径（子标签随之更新）；
+/// 只有按路径保存的计时器当前标签需要刷新。
 pub(crate) fn rename_tag_impl<S: CommandState>(
     state: &S,
     from: String,
     to: String,
 ) -> AppResult<AppSnapshot> {
     let clock = crate::timer::SystemClock;
-    let from = from.trim().to_string();
-    let to = to.trim().to_string();
+    let from = normalize_tag_path(&from);
+    let to = normalize_tag_path(&to);
     if from.is_empty() || to.is_empty() {
         return Err(AppError::Validation("标签不能为空".to_string()));
     }
@@ -68,52 +103,53 @@ pub(crate) fn rename_tag_impl<S: CommandState>(
         return Ok(AppSnapshot {
             data: state.data_snapshot(),
             timer: state.timer_snapshot(),
+            profile: state.active_profile(),
         });
     }
+    if tag_path_within(&to, &from) {
+        return Err(AppError::Validation(
+            "不能将标签移动到其子标签下".to_string(),
+        ));
+    }
 
-    state.update_data_and_timer(
+    state.update_undoable(
+        &format!("重命名标签「{from}」为「{to}」"),
         |data, timer_runtime| {
-            if !data.tags.iter().any(|t| t == &from) {
+            let Some(id) = data.tag_id_by_path(&from) else {
                 return Err(AppError::Validation("原标签不存在".to_string()));
-            }
-            if data.tags.iter().any(|t| t == &to) {
+            };
+            if data.tag_id_by_path(&to).is_some() {
                 return Err(AppError::Validation("目标标签已存在".to_string()));
             }
 
-            for t in data.tags.iter_mut() {
-                if *t == from {
-                    *t = to.clone();
-                }
+            data.touch_section(Section::Tags);
+            data.record_event(DomainEvent::TagRenamed {
+                tag_id: id.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            });
+
+            // 计时器当前标签按路径保存：记录其所在标签的 id，改名后刷新为新路径。
+            let current_id = data.tag_id_by_path(&timer_runtime.current_tag);
+
+            let (parent_path, name) = match to.rsplit_once('/') {
+                Some((parent, name)) => (Some(parent), name),
+                None => (None, to.as_str()),
+            };
+            let parent_id = parent_path.map(|p| data.ensure_tag_path(p));
+            if let Some(tag) = data.tag_defs.iter_mut().find(|t| t.id == id) {
+                tag.name = name.to_string();
+                tag.parent_id = parent_id;
             }
+            data.sync_tags();
 
-            for day in data.history.iter_mut() {
-                for r in day.records.iter_mut() {
-                    if r.tag == from {
-                        r.tag = to.clone();
-                    }
+            if let Some(path) = current_id.and_then(|id| data.tag_path(&id)) {
+                if path != timer_runtime.current_tag {
+                    timer_runtime.set_current_tag(path, &clock);
                 }
             }
-            for day in data.history_dev.iter_mut() {
-                for r in day.records.iter_mut() {
-                    if r.tag == from {
-                        r.tag = to.clone();
-                    }
-                }
-            }
-            for d in data.interruptions.iter_mut() {
-                for r in d.records.iter_mut() {
-                    if r.tag == from {
-                        r.tag = to.clone();
-                    }
-                }
-            }
-
-            if timer_runtime.current_tag == from {
-                timer_runtime.set_current_tag(to.clone(), &clock);
-            }
             Ok(())
         },
-        true,
     )?;
 
     let _ = state.emit_timer_snapshot();
@@ -121,55 +157,107 @@ pub(crate) fn rename_tag_impl<S: CommandState>(
     Ok(AppSnapshot {
         data: state.data_snapshot(),
         timer: state.timer_snapshot(),
+        profile: state.active_profile(),
     })
 }
 
-/// 删除标签的内部实现：同步更新 tags 列表、计时器当前标签与历史记录。
+/// 删除标签的内部实现：先写入操作前备份，再移除标签定义并清空历史/中断记录中对它的引用（含子标签时拒绝删除）。
 pub(crate) fn delete_tag_impl<S: CommandState>(state: &S, tag: String) -> AppResult<AppSnapshot> {
     let clock = crate::timer::SystemClock;
-    let tag = tag.trim().to_string();
+    let tag = normalize_tag_path(&tag);
     if tag.is_empty() {
         return Err(AppError::Validation("标签不能为空".to_string()));
     }
-    if tag == "工作" {
+    if tag == DEFAULT_TAG {
         return Err(AppError::Validation("默认标签不可删除".to_string()));
     }
 
-    state.update_data_and_timer(
+    // 删除会清空历史记录中的该标签：先备份，便于误删后恢复。
+    state.backup_before("delete-tag")?;
+    state.update_undoable(
+        &format!("删除标签「{tag}」"),
         |data, timer_runtime| {
-            if !data.tags.iter().any(|t| t == &tag) {
+            let Some(id) = data.tag_id_by_path(&tag) else {
                 return Err(AppError::Validation("标签不存在".to_string()));
+            };
+            if data
+                .tag_defs
+                .iter()
+                .any(|t| t.parent_id.as_deref() == Some(id.as_str()))
+            {
+                return Err(AppError::Validation(
+                    "请先删除或移动该标签的子标签".to_string(),
+                ));
             }
-            data.tags.retain(|t| t != &tag);
-
-            for day in data.history.iter_mut() {
-                for r in day.records.iter_mut() {
-                    if r.tag == tag {
-                        r.tag = "".to_string();
+            data.touch_section(Section::Tags);
+            data.touch_section(Section::Settings);
+            data.touch_section(Section::Tasks);
+            data.tag_defs.retain(|t| t.id != id);
+
+            // 先登记引用该标签的日期，再清空其中的标签引用。
+            let owned = Some(id);
+            let mut touched = BTreeSet::new();
+            for (set, days) in [
+                (RecordSet::History, &data.history),
+                (RecordSet::HistoryDev, &data.history_dev),
+            ] {
+                for day in days {
+                    if day.records.iter().any(|r| r.tag_id == owned) {
+                        touched.insert((set, day.date.clone()));
                     }
                 }
             }
-            for day in data.history_dev.iter_mut() {
-                for r in day.records.iter_mut() {
-                    if r.tag == tag {
-                        r.tag = "".to_string();
-                    }
+            for d in &data.interruptions {
+                if d.records.iter().any(|r| r.tag_id == owned) {
+                    touched.insert((RecordSet::Interruptions, d.date.clone()));
                 }
             }
-            for d in data.interruptions.iter_mut() {
-                for r in d.records.iter_mut() {
-                    if r.tag == tag {
-                        r.tag = "".to_string();
-                    }
+            for (set, date) in &touched {
+                data.touch_day(*set, date);
+            }
+            let history = data.history.iter_mut().chain(data.history_dev.iter_mut());
+            for r in history.flat_map(|d| d.records.iter_mut()) {
+                if r.tag_id == owned {
+                    r.tag = "".to_string();
+                    r.tag_id = None;
+                }
+            }
+            for r in data
+                .interruptions
+                .iter_mut()
+                .flat_map(|d| d.records.iter_mut())
+            {
+                if r.tag_id == owned {
+                    r.tag = "".to_string();
+                    r.tag_id = None;
+                }
+            }
+            data.record_days_replaced(touched);
+            data.sync_tags();
+
+            // 按 id 匹配引用（缺少 id 的旧数据按路径）：改名后保存的路径可能已过期。
+            let refers = |path: &str, tag_id: &Option<String>| match tag_id {
+                Some(_) => *tag_id == owned,
+                None => path == tag,
+            };
+            data.settings
+                .tag_overrides
+                .retain(|o| !refers(&o.tag, &o.tag_id));
+            data.settings
+                .tag_goals
+                .retain(|g| !refers(&g.tag, &g.tag_id));
+            for t in data.tasks.iter_mut() {
+                if refers(&t.tag, &t.tag_id) {
+                    t.tag.clear();
+                    t.tag_id = None;
                 }
             }
 
             if timer_runtime.current_tag == tag {
-                timer_runtime.set_current_tag("工作".to_string(), &clock);
+                timer_runtime.set_current_tag(DEFAULT_TAG.to_string(), &clock);
             }
             Ok(())
         },
-        true,
     )?;
 
     let _ = state.emit_timer_snapshot();
@@ -177,9 +265,60 @@ pub(crate) fn delete_tag_impl<S: CommandState>(state: &S, tag: String) -> AppRes
     Ok(AppSnapshot {
         data: state.data_snapshot(),
         timer: state.timer_snapshot(),
+        profile: state.active_profile(),
     })
 }
 
+/// 更新标签外观与归档状态的内部实现（按 id 匹配；名称与层级请通过重命名修改）。
+pub(crate) fn update_tag_impl<S: CommandState>(state: &S, tag: Tag) -> AppResult<AppSnapshot> {
+    let color = tag.color.trim().to_string();
+    let icon = tag.icon.trim().to_string();
+    if !color.is_empty() && !is_hex_color(&color) {
+        return Err(AppError::Validation(
+            "标签颜色需为 #RRGGBB 格式".to_string(),
+        ));
+    }
+    if icon.chars().count() > 8 {
+        return Err(AppError::Validation(
+            "标签图标不能超过 8 个字符".to_string(),
+        ));
+    }
+
+    state.update_undoable(&format!("修改标签「{}」", tag.name), |data, _| {
+        if tag.archived && data.tag_path(&tag.id).as_deref() == Some(DEFAULT_TAG) {
+            return Err(AppError::Validation("默认标签不可归档".to_string()));
+        }
+        data.touch_section(Section::Tags);
+        let Some(existing) = data.tag_defs.iter_mut().find(|t| t.id == tag.id) else {
+            return Err(AppError::Validation("标签不存在".to_string()));
+        };
+        existing.color = color;
+        existing.icon = icon;
+        existing.archived = tag.archived;
+        data.sync_tags();
+        Ok(())
+    })?;
+
+    Ok(AppSnapshot {
+        data: state.data_snapshot(),
+        timer: state.timer_snapshot(),
+        profile: state.active_profile(),
+    })
+}
+
+/// 按完整路径查找标签定义。
+fn find_tag<'a>(data: &'a AppData, path: &str) -> Option<&'a Tag> {
+    let id = data.tag_id_by_path(path)?;
+    data.tag_defs.iter().find(|t| t.id == id)
+}
+
+/// 判断是否为 `#RRGGBB` 形式的颜色值。
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +344,49 @@ mod tests {
         assert!(state.emitted_timer_snapshot_count() >= 1);
     }
 
+    /// `set_current_tag_impl`：切换到带覆盖项的标签时，应同步空闲时长并启用其黑名单模板。
+    #[test]
+    fn set_current_tag_applies_tag_override() {
+        use crate::app_data::{BlacklistItem, BlacklistTemplate, TagOverride};
+        let mut data = AppData::default();
+        data.blacklist_templates.push(BlacklistTemplate {
+            id: "custom-1".to_string(),
+            name: "学习".to_string(),
+            builtin: false,
+            processes: vec![BlacklistItem {
+                name: "game.exe".to_string(),
+                display_name: "游戏".to_string(),
+            }],
+        });
+        data.settings.tag_overrides.push(TagOverride {
+            tag: "学习".to_string(),
+            pomodoro: Some(45),
+            blacklist_template_ids: Some(vec!["custom-1".to_string(), "missing".to_string()]),
+            ..TagOverride::default()
+        });
+        let state = TestState::new(data);
+
+        let snapshot = set_current_tag_impl(&state, "学习".to_string()).unwrap();
+        assert_eq!(snapshot.timer.remaining_seconds, 45 * 60);
+        assert_eq!(snapshot.timer.settings.pomodoro, 45);
+        assert_eq!(
+            snapshot.data.active_template_ids,
+            vec!["custom-1".to_string()]
+        );
+        assert_eq!(snapshot.data.blacklist.len(), 1);
+        assert_eq!(snapshot.data.blacklist[0].name, "game.exe");
+
+        let snapshot = set_current_tag_impl(&state, "工作".to_string()).unwrap();
+        assert_eq!(
+            snapshot.timer.remaining_seconds,
+            snapshot.data.settings.pomodoro as u64 * 60
+        );
+        assert_eq!(
+            snapshot.data.active_template_ids,
+            vec!["custom-1".to_string()]
+        );
+    }
+
     /// `add_tag_impl`：应 trim 并去重追加到 tags。
     #[test]
     fn add_tag_trims_and_dedupes() {
@@ -225,22 +407,16 @@ mod tests {
         assert!(matches!(err, AppError::Validation(_)));
     }
 
-    /// `rename_tag_impl`：应同步更新 tags、timer.current_tag 与历史记录 tag。
+    /// `rename_tag_impl`：应同步更新 tags 与 timer.current_tag；历史记录按 id 读取为新路径，存储的记录不被改写。
     #[test]
     fn rename_tag_updates_tags_timer_and_history() {
         let mut data = AppData::default();
         data.tags = vec!["工作".to_string(), "旧".to_string()];
         data.history = vec![crate::app_data::HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![crate::app_data::HistoryRecord {
-                tag: "旧".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: crate::app_data::Phase::Work,
-                remark: "".to_string(),
-            }],
+            records: vec![crate::app_data::HistoryRecord::for_test("旧", "09:00")],
         }];
+        data.migrate_v6();
         let state = TestState::new(data);
 
         let snapshot = rename_tag_impl(&state, "旧".to_string(), "新".to_string()).unwrap();
@@ -248,6 +424,12 @@ mod tests {
         assert!(!snapshot.data.tags.iter().any(|t| t == "旧"));
         assert_eq!(snapshot.data.history[0].records[0].tag, "新");
         assert!(state.emitted_timer_snapshot_count() >= 1);
+        state
+            .update_data(|data| {
+                assert_eq!(data.history[0].records[0].tag, "旧");
+                Ok(())
+            })
+            .unwrap();
     }
 
     /// `delete_tag_impl`：应从 tags 移除，并清空历史记录中的该标签。
@@ -257,20 +439,165 @@ mod tests {
         data.tags = vec!["工作".to_string(), "A".to_string()];
         data.history = vec![crate::app_data::HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![crate::app_data::HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "09:00".to_string(),
-                end_time: None,
-                duration: 25,
-                phase: crate::app_data::Phase::Work,
-                remark: "".to_string(),
-            }],
+            records: vec![crate::app_data::HistoryRecord::for_test("A", "09:00")],
         }];
+        data.migrate_v6();
         let state = TestState::new(data);
 
         let snapshot = delete_tag_impl(&state, "A".to_string()).unwrap();
         assert!(!snapshot.data.tags.iter().any(|t| t == "A"));
         assert_eq!(snapshot.data.history[0].records[0].tag, "");
         assert!(state.emitted_timer_snapshot_count() >= 1);
+        assert_eq!(state.take_backups(), vec!["delete-tag".to_string()]);
+    }
+
+    /// `rename_tag_impl`：移动父标签时，子标签、按 id 关联的历史记录、覆盖项与任务应随之解析为新路径。
+    #[test]
+    fn rename_tag_moves_subtree_and_follows_tag_ids() {
+        use crate::app_data::{HistoryDay, HistoryRecord, TagOverride, Task};
+        let mut data = AppData::default();
+        let child = data.ensure_tag_path("工作/项目X");
+        data.sync_tags();
+        data.history = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![
+                HistoryRecord::for_test("工作/项目X", "09:00").with_tag_id(Some(child.clone()))
+            ],
+        }];
+        data.settings.tag_overrides.push(TagOverride {
+            tag: "工作/项目X".to_string(),
+            tag_id: Some(child.clone()),
+            pomodoro: Some(50),
+            ..TagOverride::default()
+        });
+        data.tasks.push(Task {
+            id: "task-1".to_string(),
+            title: "周报".to_string(),
+            tag: "工作/项目X".to_string(),
+            tag_id: Some(child.clone()),
+            estimated_pomodoros: 1,
+            due_date: None,
+            done: false,
+        });
+        let state = TestState::new(data);
+        set_current_tag_impl(&state, "工作/项目X".to_string()).unwrap();
+
+        let snapshot = rename_tag_impl(&state, "工作".to_string(), "公司".to_string()).unwrap();
+        assert!(snapshot.data.tags.iter().any(|t| t == "公司/项目X"));
+        assert!(!snapshot.data.tags.iter().any(|t| t.starts_with("工作")));
+        assert_eq!(snapshot.data.history[0].records[0].tag, "公司/项目X");
+        assert_eq!(snapshot.data.settings.tag_overrides[0].tag, "公司/项目X");
+        assert_eq!(snapshot.data.tasks[0].tag, "公司/项目X");
+        assert_eq!(snapshot.timer.current_tag, "公司/项目X");
+        assert_eq!(snapshot.timer.settings.pomodoro, 50);
+
+        let snapshot =
+            rename_tag_impl(&state, "公司/项目X".to_string(), "项目X".to_string()).unwrap();
+        assert_eq!(snapshot.data.tag_path(&child).as_deref(), Some("项目X"));
+        assert_eq!(snapshot.data.history[0].records[0].tag, "项目X");
+
+        assert!(matches!(
+            rename_tag_impl(&state, "公司".to_string(), "公司/子".to_string()),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// `delete_tag_impl`：改名后按 id 移除覆盖项与目标、清空任务标签（保存的旧路径不影响匹配）。
+    #[test]
+    fn delete_tag_clears_references_by_id_after_rename() {
+        use crate::app_data::{GoalPeriod, TagGoal, TagOverride};
+        let state = TestState::new(AppData::default());
+        add_tag_impl(&state, "学习".to_string()).unwrap();
+        let mut settings = state.data_snapshot().settings;
+        settings.tag_overrides.push(TagOverride {
+            tag: "学习".to_string(),
+            pomodoro: Some(40),
+            ..TagOverride::default()
+        });
+        settings.tag_goals.push(TagGoal {
+            tag: "学习".to_string(),
+            tag_id: None,
+            period: GoalPeriod::Weekly,
+            target: 5,
+        });
+        crate::commands::settings::update_settings_impl(&state, settings).unwrap();
+        crate::commands::tasks::save_task_impl(
+            &state,
+            crate::app_data::Task {
+                id: String::new(),
+                title: "背单词".to_string(),
+                tag: "学习".to_string(),
+                tag_id: None,
+                estimated_pomodoros: 1,
+                due_date: None,
+                done: false,
+            },
+        )
+        .unwrap();
+
+        let snapshot = rename_tag_impl(&state, "学习".to_string(), "读书".to_string()).unwrap();
+        assert_eq!(snapshot.data.settings.tag_goals[0].tag, "读书");
+        let snapshot = delete_tag_impl(&state, "读书".to_string()).unwrap();
+        assert!(snapshot.data.settings.tag_overrides.is_empty());
+        assert!(snapshot.data.settings.tag_goals.is_empty());
+        assert!(snapshot.data.tasks[0].tag.is_empty());
+        assert!(snapshot.data.tasks[0].tag_id.is_none());
+    }
+
+    /// `delete_tag_impl`：仍有子标签的父标签不可删除。
+    #[test]
+    fn delete_tag_rejects_parent_with_children() {
+        let mut data = AppData::default();
+        data.ensure_tag_path("学习/数学");
+        data.sync_tags();
+        let state = TestState::new(data);
+
+        assert!(matches!(
+            delete_tag_impl(&state, "学习".to_string()),
+            Err(AppError::Validation(_))
+        ));
+        delete_tag_impl(&state, "学习/数学".to_string()).unwrap();
+        let snapshot = delete_tag_impl(&state, "学习".to_string()).unwrap();
+        assert!(!snapshot.data.tag_defs.iter().any(|t| t.name == "学习"));
+    }
+
+    /// `update_tag_impl`：应更新颜色/图标/归档；归档标签从选择列表隐藏且不可选用。
+    #[test]
+    fn update_tag_sets_style_and_archives() {
+        let state = TestState::new(AppData::default());
+        let mut tag = state
+            .data_snapshot()
+            .tag_defs
+            .into_iter()
+            .find(|t| t.name == "阅读")
+            .unwrap();
+        tag.color = "#F97316".to_string();
+        tag.icon = " 📚 ".to_string();
+        tag.archived = true;
+
+        let snapshot = update_tag_impl(&state, tag.clone()).unwrap();
+        let saved = snapshot
+            .data
+            .tag_defs
+            .iter()
+            .find(|t| t.id == tag.id)
+            .unwrap();
+        assert_eq!(saved.color, "#F97316");
+        assert_eq!(saved.icon, "📚");
+        assert!(saved.archived);
+        assert!(!snapshot.data.tags.iter().any(|t| t == "阅读"));
+        assert!(matches!(
+            set_current_tag_impl(&state, "阅读".to_string()),
+            Err(AppError::Validation(_))
+        ));
+
+        let tags = add_tag_impl(&state, "阅读".to_string()).unwrap();
+        assert!(tags.iter().any(|t| t == "阅读"));
+
+        tag.color = "orange".to_string();
+        assert!(matches!(
+            update_tag_impl(&state, tag),
+            Err(AppError::Validation(_))
+        ));
     }
 }
@@ -0,0 +1,234 @@
+//! 任务相关命令：查询/保存/删除任务、标记完成、选择当前任务与预估复盘。
+
+use crate::app_data::{normalize_tag_path, Section, Task};
+use crate::errors::{AppError, AppResult};
+use crate::tasks::TaskEstimateReport;
+
+use super::state_like::CommandState;
+use super::tags::set_current_tag_impl;
+use super::types::AppSnapshot;
+use super::validation::{history_for_ui, validate_task};
+
+/// 获取任务列表的内部实现。
+pub(crate) fn get_tasks_impl<S: CommandState>(state: &S) -> AppResult<Vec<Task>> {
+    Ok(state.data_snapshot().tasks)
+}
+
+/// 保存任务的内部实现：规范化并校验字段，按 id 新增或覆盖（按路径绑定标签 id，标签不存在时自动创建）。
+pub(crate) fn save_task_impl<S: CommandState>(state: &S, mut task: Task) -> AppResult<Task> {
+    task.title = task.title.trim().to_string();
+    task.tag = normalize_tag_path(&task.tag);
+    task.due_date = task
+        .due_date
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+    validate_task(&task)?;
+
+    let saved = state.update_data_and_timer(
+        |data, timer_runtime| {
+            if task.id.trim().is_empty() {
+                let mut ts = chrono::Utc::now().timestamp_millis();
+                while data.tasks.iter().any(|t| t.id == format!("task-{ts}")) {
+                    ts += 1;
+                }
+                task.id = format!("task-{ts}");
+            }
+            task.tag_id = data.tag_id_by_path(&task.tag);
+            if !task.tag.is_empty() && task.tag_id.is_none() {
+                data.touch_section(Section::Tags);
+                task.tag_id = Some(data.ensure_tag_path(&task.tag));
+                data.sync_tags();
+            }
+            data.touch_section(Section::Tasks);
+            match data.tasks.iter_mut().find(|t| t.id == task.id) {
+                Some(existing) => *existing = task.clone(),
+                None => data.tasks.push(task.clone()),
+            }
+            // 当前任务被标记为完成：不再关联后续的工作记录。
+            if task.done && timer_runtime.current_task_id.as_deref() == Some(task.id.as_str()) {
+                timer_runtime.current_task_id = None;
+            }
+            Ok(task.clone())
+        },
+        true,
+    )?;
+
+    tracing::info!(target: "storage", "保存任务：id={} title={}", saved.id, saved.title);
+    let _ = state.emit_timer_snapshot();
+    Ok(saved)
+}
+
+/// 删除任务的内部实现：若删除的是当前任务则一并取消选择（历史记录中的关联保留）。
+pub(crate) fn delete_task_impl<S: CommandState>(state: &S, id: String) -> AppResult<bool> {
+    let id = id.trim().to_string();
+    if id.is_empty() {
+        return Err(AppError::Validation("任务 id 不能为空".to_string()));
+    }
+
+    let deleted = state.update_data_and_timer(
+        |data, timer_runtime| {
+            if !data.tasks.iter().any(|t| t.id == id) {
+                return Ok(false);
+            }
+            data.touch_section(Section::Tasks);
+            data.tasks.retain(|t| t.id != id);
+            if timer_runtime.current_task_id.as_deref() == Some(id.as_str()) {
+                timer_runtime.current_task_id = None;
+            }
+            Ok(true)
+        },
+        true,
+    )?;
+
+    if deleted {
+        tracing::info!(target: "storage", "删除任务：id={}", id);
+        let _ = state.emit_timer_snapshot();
+    }
+    Ok(deleted)
+}
+
+/// 标记任务完成/未完成的内部实现。
+pub(crate) fn set_task_done_impl<S: CommandState>(
+    state: &S,
+    id: String,
+    done: bool,
+) -> AppResult<Task> {
+    let Some(mut task) = state
+        .data_snapshot()
+        .tasks
+        .into_iter()
+        .find(|t| t.id == id.trim())
+    else {
+        return Err(AppError::Validation("任务不存在".to_string()));
+    };
+    task.done = done;
+    save_task_impl(state, task)
+}
+
+/// 选择当前任务的内部实现（`id` 为空表示取消选择）：任务带标签时同时切换当前标签。
+pub(crate) fn set_current_task_impl<S: CommandState>(
+    state: &S,
+    id: Option<String>,
+) -> AppResult<AppSnapshot> {
+    let id = id.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+    let task = match id.as_deref() {
+        Some(id) => {
+            let Some(task) = state.data_snapshot().tasks.into_iter().find(|t| t.id == id) else {
+                return Err(AppError::Validation("任务不存在".to_string()));
+            };
+            if task.done {
+                return Err(AppError::Validation("任务已完成".to_string()));
+            }
+            Some(task)
+        }
+        None => None,
+    };
+
+    state.update_timer(|timer_runtime, _data| {
+        timer_runtime.current_task_id = id.clone();
+        Ok(())
+    })?;
+    tracing::info!(target: "timer", "切换当前任务：id={:?}", id);
+
+    match task.filter(|t| !t.tag.is_empty()) {
+        Some(task) => set_current_tag_impl(state, task.tag),
+        None => {
+            let _ = state.emit_timer_snapshot();
+            Ok(AppSnapshot {
+                data: state.data_snapshot(),
+                timer: state.timer_snapshot(),
+                profile: state.active_profile(),
+            })
+        }
+    }
+}
+
+/// 获取任务预估准确度报告的内部实现（按任务与按标签）。
+pub(crate) fn get_task_estimates_impl<S: CommandState>(state: &S) -> AppResult<TaskEstimateReport> {
+    let data = state.data_snapshot();
+    Ok(crate::tasks::compute_estimate_report(
+        &data.tasks,
+        history_for_ui(&data),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::AppData;
+    use crate::commands::state_like::TestState;
+
+    /// 构造一个待保存的任务（id 为空，由保存逻辑生成）。
+    fn sample_task(title: &str, tag: &str) -> Task {
+        Task {
+            id: String::new(),
+            title: title.to_string(),
+            tag: tag.to_string(),
+            tag_id: None,
+            estimated_pomodoros: 3,
+            due_date: Some(" ".to_string()),
+            done: false,
+        }
+    }
+
+    /// `save_task_impl`：应生成 id、规范化字段、自动创建标签，并支持按 id 覆盖。
+    #[test]
+    fn save_task_generates_id_and_upserts() {
+        let state = TestState::new(AppData::default());
+
+        let saved = save_task_impl(&state, sample_task(" 写周报 ", "工作 / 周报")).unwrap();
+        assert!(saved.id.starts_with("task-"));
+        assert_eq!(saved.title, "写周报");
+        assert_eq!(saved.tag, "工作/周报");
+        assert!(saved.due_date.is_none());
+        assert!(state.data_snapshot().tags.iter().any(|t| t == "工作/周报"));
+
+        let other = save_task_impl(&state, sample_task("读论文", "学习")).unwrap();
+        assert_ne!(other.id, saved.id);
+
+        let mut edited = saved.clone();
+        edited.estimated_pomodoros = 5;
+        save_task_impl(&state, edited).unwrap();
+        let tasks = get_tasks_impl(&state).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].estimated_pomodoros, 5);
+    }
+
+    /// `set_current_task_impl`：应设置当前任务并切换到任务标签；完成或删除后取消选择。
+    #[test]
+    fn set_current_task_switches_tag_and_clears_on_done() {
+        let state = TestState::new(AppData::default());
+        let task = save_task_impl(&state, sample_task("读论文", "学习")).unwrap();
+
+        let snapshot = set_current_task_impl(&state, Some(task.id.clone())).unwrap();
+        assert_eq!(
+            snapshot.timer.current_task_id.as_deref(),
+            Some(task.id.as_str())
+        );
+        assert_eq!(snapshot.timer.current_tag, "学习");
+
+        set_task_done_impl(&state, task.id.clone(), true).unwrap();
+        assert!(state.timer_snapshot().current_task_id.is_none());
+        assert!(matches!(
+            set_current_task_impl(&state, Some(task.id.clone())),
+            Err(AppError::Validation(_))
+        ));
+
+        set_task_done_impl(&state, task.id.clone(), false).unwrap();
+        set_current_task_impl(&state, Some(task.id.clone())).unwrap();
+        assert!(delete_task_impl(&state, task.id.clone()).unwrap());
+        assert!(!delete_task_impl(&state, task.id).unwrap());
+        assert!(state.timer_snapshot().current_task_id.is_none());
+    }
+
+    /// `set_current_task_impl`：不存在的任务 id 应被拒绝。
+    #[test]
+    fn set_current_task_rejects_unknown_id() {
+        let state = TestState::new(AppData::default());
+        assert!(matches!(
+            set_current_task_impl(&state, Some("missing".to_string())),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
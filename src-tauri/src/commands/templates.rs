@@ -1,6 +1,6 @@
 //! 黑名单模板相关命令：查询/保存/删除/应用模板。
 
-use crate::app_data::{BlacklistItem, BlacklistTemplate};
+use crate::app_data::{BlacklistItem, BlacklistTemplate, Section};
 use crate::errors::{AppError, AppResult};
 
 use super::state_like::CommandState;
@@ -31,7 +31,8 @@ pub(crate) fn save_template_impl<S: CommandState>(
 
     validate_blacklist_items(&template.processes)?;
 
-    state.update_data(|data| {
+    let description = format!("保存模板「{}」", template.name);
+    state.update_undoable(&description, |data, _| {
         if let Some(existing) = data
             .blacklist_templates
             .iter()
@@ -42,6 +43,7 @@ pub(crate) fn save_template_impl<S: CommandState>(
             }
         }
 
+        data.touch_section(Section::Templates);
         let mut next: Vec<BlacklistTemplate> = Vec::new();
         let mut replaced = false;
         for t in &data.blacklist_templates {
@@ -71,7 +73,7 @@ pub(crate) fn delete_template_impl<S: CommandState>(state: &S, id: String) -> Ap
     }
 
     let mut deleted = false;
-    state.update_data(|data| {
+    state.update_undoable("删除黑名单模板", |data, _| {
         let Some(existing) = data.blacklist_templates.iter().find(|t| t.id == id) else {
             return Ok(());
         };
@@ -79,6 +81,7 @@ pub(crate) fn delete_template_impl<S: CommandState>(state: &S, id: String) -> Ap
             return Err(AppError::Validation("内置模板不可删除".to_string()));
         }
 
+        data.touch_section(Section::Templates);
         data.blacklist_templates = data
             .blacklist_templates
             .iter()
@@ -114,12 +117,14 @@ pub(crate) fn apply_template_impl<S: CommandState>(
         return Err(AppError::BlacklistLocked);
     }
 
-    state.update_data(|data| {
+    state.update_undoable("切换黑名单模板", |data, _| {
         let exists = data.blacklist_templates.iter().any(|t| t.id == id);
         if !exists {
             return Err(AppError::Validation("模板不存在".to_string()));
         }
 
+        data.touch_section(Section::Templates);
+        data.touch_section(Section::Blacklist);
         if data.active_template_ids.iter().any(|x| x == &id) {
             data.active_template_ids.retain(|x| x != &id);
         } else {
@@ -137,6 +142,22 @@ pub(crate) fn apply_template_impl<S: CommandState>(
     Ok(out)
 }
 
+/// 将启用模板集合替换为 `ids`（忽略不存在的模板），并重新计算有效黑名单。
+pub(crate) fn activate_templates(data: &mut crate::app_data::AppData, ids: &[String]) {
+    let mut next: Vec<String> = ids
+        .iter()
+        .filter(|id| data.blacklist_templates.iter().any(|t| &t.id == *id))
+        .cloned()
+        .collect();
+    next.sort();
+    next.dedup();
+    data.touch_section(Section::Templates);
+    data.touch_section(Section::Blacklist);
+    data.active_template_ids = next;
+    data.active_template_id = data.active_template_ids.first().cloned();
+    data.blacklist = compute_blacklist_from_active_templates(data);
+}
+
 /// 根据当前启用模板集合计算“有效黑名单”（按进程名去重，忽略大小写）。
 fn compute_blacklist_from_active_templates(data: &crate::app_data::AppData) -> Vec<BlacklistItem> {
     let active: std::collections::BTreeSet<String> =
@@ -337,7 +358,7 @@ mod tests {
             .update_data_and_timer(
                 |d, t| {
                     let clock = crate::timer::SystemClock;
-                    t.start(&d.settings, &clock);
+                    t.start(d, &clock);
                     Ok(())
                 },
                 false,
@@ -350,10 +371,13 @@ mod tests {
 
         // 解除锁定（跳到休息阶段即可）。
         state
-            .update_timer(|t, d| {
-                t.skip(&d.settings, 1);
-                Ok(())
-            })
+            .update_data_and_timer(
+                |d, t| {
+                    t.skip(d, 1, &crate::timer::SystemClock);
+                    Ok(())
+                },
+                false,
+            )
             .unwrap();
         assert!(!state.timer_snapshot().blacklist_locked);
 
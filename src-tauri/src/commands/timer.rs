@@ -2,7 +2,7 @@
 
 use crate::app_data::Phase;
 use crate::errors::AppResult;
-use crate::timer::{compute_today_stats, TimerClock, TimerSnapshot};
+use crate::timer::{TimerClock, TimerSnapshot};
 
 use super::state_like::CommandState;
 
@@ -26,7 +26,7 @@ fn timer_start_transition_with_deps<S: CommandState>(
                 && !timer_runtime.blacklist_locked()
                 && !timer_runtime.is_running;
             let names: Vec<String> = data.blacklist.iter().map(|b| b.name.clone()).collect();
-            timer_runtime.start(&data.settings, clock);
+            timer_runtime.start(data, clock);
             Ok((names, should_kill))
         },
         false,
@@ -61,7 +61,7 @@ pub(crate) fn timer_pause_impl<S: CommandState>(state: &S) -> AppResult<TimerSna
 /// 暂停计时的可测试实现：不依赖托盘与系统资源。
 fn timer_pause_transition<S: CommandState>(state: &S) -> AppResult<()> {
     state.update_timer(|timer_runtime, _data| {
-        timer_runtime.pause();
+        timer_runtime.pause(&crate::timer::SystemClock);
         Ok(())
     })?;
 
@@ -86,7 +86,7 @@ pub(crate) fn timer_reset_impl<S: CommandState>(state: &S) -> AppResult<TimerSna
 fn timer_reset_transition<S: CommandState>(state: &S) -> AppResult<()> {
     state.update_data_and_timer(
         |data, timer_runtime| {
-            timer_runtime.reset(&data.settings);
+            timer_runtime.reset(data);
             Ok(())
         },
         false,
@@ -108,14 +108,14 @@ fn timer_skip_transition_with_clock<S: CommandState>(
     state: &S,
     clock: &dyn TimerClock,
 ) -> AppResult<()> {
+    let today = clock.logical_today(state.settings_snapshot().day_boundary());
+    let completed_today = state.completed_on(&today);
     state.update_data_and_timer(
         |data, timer_runtime| {
-            let today = clock.today_date();
-            let completed_today = compute_today_stats(data, &today).total;
-            timer_runtime.skip(&data.settings, completed_today);
+            timer_runtime.skip(data, completed_today, clock);
             Ok(())
         },
-        false,
+        true,
     )?;
 
     tracing::info!(target: "timer", "跳过阶段：phase={:?}", state.timer_snapshot().phase);
@@ -129,7 +129,9 @@ mod tests {
 
     use std::sync::Once;
 
-    use crate::app_data::{AppData, BlacklistItem, HistoryDay, HistoryRecord, Phase, Settings};
+    use crate::app_data::{
+        AppData, BlacklistItem, DayBoundary, HistoryDay, HistoryRecord, Phase, Settings,
+    };
     use crate::commands::state_like::CommandState;
     use crate::commands::state_like::TestState;
 
@@ -150,6 +152,7 @@ mod tests {
         now: String,
         week_from: String,
         week_to: String,
+        monotonic: std::time::Instant,
     }
 
     impl FixedClock {
@@ -160,6 +163,7 @@ mod tests {
                 now: now.to_string(),
                 week_from: today.to_string(),
                 week_to: today.to_string(),
+                monotonic: std::time::Instant::now(),
             }
         }
     }
@@ -175,10 +179,34 @@ mod tests {
             self.now.clone()
         }
 
+        /// 返回固定逻辑日期（忽略日界线）。
+        fn logical_today(&self, _boundary: DayBoundary) -> String {
+            self.today.clone()
+        }
+
         /// 返回固定周范围。
-        fn current_week_range(&self) -> (String, String) {
+        fn current_week_range(&self, _boundary: DayBoundary) -> (String, String) {
             (self.week_from.clone(), self.week_to.clone())
         }
+
+        /// 返回由固定日期与时间组成的本地时间点。
+        fn now(&self) -> chrono::DateTime<chrono::Local> {
+            use chrono::TimeZone as _;
+            let naive = chrono::NaiveDateTime::parse_from_str(
+                &format!("{} {}", self.today, self.now),
+                "%Y-%m-%d %H:%M",
+            )
+            .expect("测试时间格式必须合法");
+            chrono::Local
+                .from_local_datetime(&naive)
+                .single()
+                .expect("测试时间必须唯一")
+        }
+
+        /// 返回构造时的单调时钟读数。
+        fn monotonic(&self) -> std::time::Instant {
+            self.monotonic
+        }
     }
 
     /// `timer_start_transition_with_deps`：工作阶段首次开始应触发 kill，并广播快照。
@@ -281,14 +309,7 @@ mod tests {
         data.settings.long_break_interval = 2;
         data.history = vec![HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![HistoryRecord {
-                tag: "学习".to_string(),
-                start_time: "08:00".to_string(),
-                end_time: Some("08:25".to_string()),
-                duration: 25,
-                phase: Phase::Work,
-                remark: String::new(),
-            }],
+            records: vec![HistoryRecord::for_test("学习", "08:00").with_end("08:25")],
         }];
         let state = TestState::new(data);
         let clock = FixedClock::new("2025-01-01", "09:00");
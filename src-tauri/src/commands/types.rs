@@ -15,6 +15,42 @@ pub struct AppSnapshot {
     pub data: crate::app_data::AppData,
     /// 计时器状态快照。
     pub timer: TimerSnapshot,
+    /// 当前配置。
+    pub profile: crate::profiles::ProfileInfo,
+}
+
+/// 撤销/重做的结果：执行的操作、执行后的快照与新的撤销/重做状态。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct UndoOutcome {
+    /// 本次撤销/重做的操作描述。
+    pub applied: String,
+    /// 执行后的完整快照。
+    pub snapshot: AppSnapshot,
+    /// 执行后的撤销/重做状态。
+    pub pending: crate::undo::UndoState,
+}
+
+/// 手动补录/编辑历史记录的入参（结束时间由开始时间 + 时长推导）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct HistoryRecordInput {
+    /// 日期（YYYY-MM-DD）。
+    pub date: String,
+    /// 开始时间（HH:mm）。
+    pub start_time: String,
+    /// 时长（分钟，1-1440）。
+    pub duration: u32,
+    /// 标签路径（为空表示未分类；缺失的标签会自动创建）。
+    #[serde(default)]
+    pub tag: String,
+    /// 阶段类型。
+    pub phase: crate::app_data::Phase,
+    /// 备注。
+    #[serde(default)]
+    pub remark: String,
 }
 
 /// 应用数据根目录路径信息（用于设置页展示与“打开文件夹”入口）。
@@ -26,6 +62,18 @@ pub struct StorePaths {
     pub store_dir_path: String,
 }
 
+/// 数据根目录迁移请求（迁移在应用重启后执行）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct RelocateRequest {
+    /// 目标模式。
+    pub mode: crate::data_root::DataRootMode,
+    /// 自定义目录（仅 `custom` 模式需要，绝对路径）。
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
 /// 导出格式（CSV/JSON）。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -56,6 +104,16 @@ pub enum ExportField {
     Phase,
     /// 备注（PRD v2 新增，可选导出）。
     Remark,
+    /// 实际计时秒数（不含暂停）。
+    ElapsedSeconds,
+    /// 暂停总秒数。
+    PausedSeconds,
+    /// 是否被提前跳过（休息阶段）。
+    Skipped,
+    /// 开始时刻（RFC 3339，带 UTC 偏移）。
+    StartedAt,
+    /// 结束时刻（RFC 3339，带 UTC 偏移）。
+    EndedAt,
 }
 
 /// 导出请求参数。
@@ -70,4 +128,140 @@ pub struct ExportRequest {
     /// 导出字段（为空则导出默认字段集）。
     #[serde(default)]
     pub fields: Vec<ExportField>,
+    /// 加密口令（非空时导出文件整体加密，需同一口令才能导入）。
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// 导入模式。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// 合并：仅追加不重复的记录。
+    Merge,
+    /// 替换：先清空导入日期范围内的已有记录，再写入导入记录。
+    Replace,
+}
+
+/// 导入请求参数。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ImportRequest {
+    /// 导入文件路径（本应用导出的 CSV/JSON）。
+    pub path: String,
+    /// 导入模式。
+    pub mode: ImportMode,
+    /// 是否写入（为 false 时仅返回预览报告，不修改数据）。
+    #[serde(default)]
+    pub commit: bool,
+    /// 解密口令（导入加密导出文件时必填）。
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// 导入时被跳过的无效行。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ImportRowError {
+    /// 行号（CSV 不含表头，从 1 开始；JSON 为 records 下标 + 1）。
+    pub row: u32,
+    /// 跳过原因。
+    pub message: String,
+}
+
+/// 导入报告（预览与写入共用）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// 识别出的文件格式。
+    pub format: ExportFormat,
+    /// 文件中的记录行数。
+    pub total_rows: u32,
+    /// 新增的记录数。
+    pub imported: u32,
+    /// 与已有记录（或文件内）重复而跳过的记录数。
+    pub duplicates: u32,
+    /// 替换模式下被移除的已有记录数。
+    pub replaced: u32,
+    /// 校验失败而跳过的行。
+    pub invalid: Vec<ImportRowError>,
+    /// 自动创建的标签路径。
+    pub new_tags: Vec<String>,
+    /// 导入涉及的日期范围（无有效记录时为空）。
+    pub range: Option<DateRange>,
+    /// 是否已写入。
+    pub committed: bool,
+}
+
+/// 外部时间记录工具的 CSV 布局。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum ExternalImportSource {
+    /// Toggl 风格的时间条目导出（Start date/Start time/End date/End time/Project）。
+    Toggl,
+    /// 通用 CSV：按列映射读取开始/结束时间与标签。
+    Generic,
+}
+
+/// 通用 CSV 的列映射与日期格式（格式串为 chrono `strftime` 语法）。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct CsvColumnMapping {
+    /// 开始列：配置了 `start_date` 时仅含时间，否则为“日期 时间”。
+    pub start: String,
+    /// 开始日期列（可选）。
+    pub start_date: Option<String>,
+    /// 结束列（与 `start` 同样的规则；与 `duration` 至少配置一个）。
+    pub end: Option<String>,
+    /// 结束日期列（可选；缺省时沿用开始日期）。
+    pub end_date: Option<String>,
+    /// 时长列（`HH:MM:SS`、`HH:MM` 或分钟数）。
+    pub duration: Option<String>,
+    /// 项目/标签列。
+    pub tag: Option<String>,
+    /// 备注列。
+    pub remark: Option<String>,
+    /// 日期格式（如 `%Y-%m-%d`）。
+    pub date_format: String,
+    /// 时间格式（如 `%H:%M`）。
+    pub time_format: String,
+}
+
+/// 项目名到标签路径的映射。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ProjectTagMapping {
+    /// 外部工具中的项目名。
+    pub project: String,
+    /// 映射到的标签路径（为空表示不打标签）。
+    pub tag: String,
+}
+
+/// 外部工具导入请求。
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ExternalImportRequest {
+    /// 待导入的 CSV 文件路径。
+    pub path: String,
+    /// CSV 布局。
+    pub source: ExternalImportSource,
+    /// 通用 CSV 的列映射（`source=generic` 时必填）。
+    #[serde(default)]
+    pub mapping: Option<CsvColumnMapping>,
+    /// 项目名到标签的映射（未映射的项目按项目名作为标签）。
+    #[serde(default)]
+    pub project_tags: Vec<ProjectTagMapping>,
+    /// 导入模式。
+    pub mode: ImportMode,
+    /// 是否写入（为 false 时仅返回预览报告）。
+    #[serde(default)]
+    pub commit: bool,
 }
@@ -0,0 +1,182 @@
+//! 撤销/重做命令：恢复或重新执行最近的标签、历史、模板与黑名单修改。
+
+use crate::errors::{AppError, AppResult};
+use crate::undo::{UndoStack, UndoState};
+
+use super::state_like::CommandState;
+use super::tags::DEFAULT_TAG;
+use super::types::{AppSnapshot, UndoOutcome};
+
+/// 查询待撤销与待重做的操作。
+pub(crate) fn get_undo_state_impl<S: CommandState>(state: &S) -> AppResult<UndoState> {
+    state.undo_state()
+}
+
+/// 撤销最近一次可撤销操作，并将其移入重做栈。
+pub(crate) fn undo_impl<S: CommandState>(state: &S) -> AppResult<UndoOutcome> {
+    step(state, UndoStack::Undo)
+}
+
+/// 重做最近一次撤销的操作，并将其移回撤销栈。
+pub(crate) fn redo_impl<S: CommandState>(state: &S) -> AppResult<UndoOutcome> {
+    step(state, UndoStack::Redo)
+}
+
+/// 从 `from` 栈取出操作并执行（撤销或重做）。
+///
+/// 专注期黑名单锁定时，涉及黑名单/模板的操作保留在栈中并拒绝执行；
+/// 被改动的数据之后又有其他修改时，该操作已无法安全执行，从栈中移除并返回错误。
+fn step<S: CommandState>(state: &S, from: UndoStack) -> AppResult<UndoOutcome> {
+    let (to, empty_message) = match from {
+        UndoStack::Undo => (UndoStack::Redo, "没有可撤销的操作"),
+        UndoStack::Redo => (UndoStack::Undo, "没有可重做的操作"),
+    };
+    let Some(entry) = state.pop_undo(from)? else {
+        return Err(AppError::Validation(empty_message.to_string()));
+    };
+    if entry.touches_blacklist() && state.timer_snapshot().blacklist_locked {
+        state.push_undo(from, entry)?;
+        return Err(AppError::BlacklistLocked);
+    }
+
+    let clock = crate::timer::SystemClock;
+    state.update_data_and_timer(
+        |data, timer_runtime| {
+            match from {
+                UndoStack::Undo => entry.revert(data)?,
+                UndoStack::Redo => entry.reapply(data)?,
+            }
+            if !data.tags.contains(&timer_runtime.current_tag) {
+                timer_runtime.set_current_tag(DEFAULT_TAG.to_string(), &clock);
+            }
+            Ok(())
+        },
+        true,
+    )?;
+
+    tracing::info!(
+        target: "storage",
+        "已{}：{}",
+        if from == UndoStack::Undo { "撤销" } else { "重做" },
+        entry.description
+    );
+    let applied = entry.description.clone();
+    state.push_undo(to, entry)?;
+    let _ = state.emit_timer_snapshot();
+    Ok(UndoOutcome {
+        applied,
+        snapshot: AppSnapshot {
+            data: state.data_snapshot(),
+            timer: state.timer_snapshot(),
+            profile: state.active_profile(),
+        },
+        pending: state.undo_state()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{AppData, BlacklistItem, HistoryDay, HistoryRecord};
+    use crate::commands::blacklist::set_blacklist_impl;
+    use crate::commands::history::set_history_remark_impl;
+    use crate::commands::state_like::TestState;
+    use crate::commands::tags::{delete_tag_impl, rename_tag_impl};
+    use crate::timer::SystemClock;
+
+    /// 构造一条带标签 `A` 的历史记录的数据。
+    fn sample_data() -> AppData {
+        let mut data = AppData {
+            tags: vec!["工作".to_string(), "A".to_string()],
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![HistoryRecord::for_test("A", "09:00")],
+            }],
+            ..AppData::default()
+        };
+        data.migrate_v6();
+        data
+    }
+
+    /// 删除标签后撤销应恢复标签与历史引用，重做再次删除；新的操作清空重做栈。
+    #[test]
+    fn undo_and_redo_delete_tag() {
+        let state = TestState::new(sample_data());
+        delete_tag_impl(&state, "A".to_string()).unwrap();
+        assert_eq!(
+            get_undo_state_impl(&state).unwrap().undo.as_deref(),
+            Some("删除标签「A」")
+        );
+
+        let outcome = undo_impl(&state).unwrap();
+        assert_eq!(outcome.applied, "删除标签「A」");
+        assert!(outcome.snapshot.data.tags.iter().any(|t| t == "A"));
+        assert_eq!(outcome.snapshot.data.history[0].records[0].tag, "A");
+        assert_eq!(outcome.pending.redo.as_deref(), Some("删除标签「A」"));
+        assert!(outcome.pending.undo.is_none());
+
+        let outcome = redo_impl(&state).unwrap();
+        assert_eq!(outcome.snapshot.data.history[0].records[0].tag, "");
+        undo_impl(&state).unwrap();
+
+        rename_tag_impl(&state, "A".to_string(), "B".to_string()).unwrap();
+        let pending = get_undo_state_impl(&state).unwrap();
+        assert_eq!(pending.undo.as_deref(), Some("重命名标签「A」为「B」"));
+        assert!(pending.redo.is_none());
+        assert!(matches!(redo_impl(&state), Err(AppError::Validation(_))));
+    }
+
+    /// 改动的记录之后又被修改时，撤销失败并丢弃该操作；空栈时返回提示。
+    #[test]
+    fn undo_rejects_conflicting_changes() {
+        let state = TestState::new(sample_data());
+        set_history_remark_impl(&state, "2025-01-01".to_string(), 0, "一".to_string()).unwrap();
+        state
+            .update_data_and_timer(
+                |data, _| {
+                    data.history[0].records[0].remark = "外部修改".to_string();
+                    Ok(())
+                },
+                true,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            undo_impl(&state),
+            Err(AppError::Validation(message)) if message.contains("无法撤销")
+        ));
+        assert_eq!(
+            state.data_snapshot().history[0].records[0].remark,
+            "外部修改"
+        );
+        assert!(matches!(
+            undo_impl(&state),
+            Err(AppError::Validation(message)) if message == "没有可撤销的操作"
+        ));
+    }
+
+    /// 专注期黑名单锁定时，撤销黑名单修改被拒绝且操作保留在撤销栈中。
+    #[test]
+    fn undo_blacklist_waits_for_unlock() {
+        let state = TestState::new(sample_data());
+        let mut blacklist = state.data_snapshot().blacklist;
+        blacklist.push(BlacklistItem {
+            name: "game.exe".to_string(),
+            display_name: "游戏".to_string(),
+        });
+        set_blacklist_impl(&state, blacklist).unwrap();
+        state
+            .update_timer(|t, d| {
+                t.start(d, &SystemClock);
+                Ok(())
+            })
+            .unwrap();
+
+        assert!(matches!(undo_impl(&state), Err(AppError::BlacklistLocked)));
+        assert_eq!(
+            get_undo_state_impl(&state).unwrap().undo.as_deref(),
+            Some("修改黑名单")
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! 命令层输入校验与通用数据选择逻辑（避免散落在各个模块中）。
 
-use crate::app_data::{BlacklistItem, DateRange, HistoryDay};
+use crate::app_data::{BlacklistItem, DateRange, HistoryDay, Phase, SessionPlan, Task};
 use crate::errors::{AppError, AppResult};
 
 /// 校验黑名单条目：名称不能为空、不得重复（忽略大小写）。
@@ -21,6 +21,51 @@ pub(crate) fn validate_blacklist_items(items: &[BlacklistItem]) -> AppResult<()>
     Ok(())
 }
 
+/// 校验会话计划：名称不能为空、步骤数 1-50、每步名称非空且时长 1-180 分钟、至少包含一个工作步骤。
+pub(crate) fn validate_session_plan(plan: &SessionPlan) -> AppResult<()> {
+    if plan.name.trim().is_empty() {
+        return Err(AppError::Validation("会话计划名称不能为空".to_string()));
+    }
+    if !(1..=50).contains(&plan.steps.len()) {
+        return Err(AppError::Validation("会话计划步骤数需在 1-50".to_string()));
+    }
+    for step in &plan.steps {
+        if step.name.trim().is_empty() {
+            return Err(AppError::Validation("会话计划步骤名称不能为空".to_string()));
+        }
+        if !(1..=180).contains(&step.minutes) {
+            return Err(AppError::Validation(
+                "会话计划步骤时长需在 1-180 分钟".to_string(),
+            ));
+        }
+    }
+    if !plan.steps.iter().any(|s| s.kind == Phase::Work) {
+        return Err(AppError::Validation(
+            "会话计划至少需要一个工作步骤".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验任务：标题 1-100 个字符、预估番茄数不超过 100、截止日期格式为 `YYYY-MM-DD`。
+pub(crate) fn validate_task(task: &Task) -> AppResult<()> {
+    if task.title.trim().is_empty() {
+        return Err(AppError::Validation("任务标题不能为空".to_string()));
+    }
+    if task.title.trim().chars().count() > 100 {
+        return Err(AppError::Validation(
+            "任务标题不能超过 100 个字符".to_string(),
+        ));
+    }
+    if task.estimated_pomodoros > 100 {
+        return Err(AppError::Validation("预估番茄数需在 0-100".to_string()));
+    }
+    if let Some(due) = task.due_date.as_deref() {
+        validate_ymd(due.trim())?;
+    }
+    Ok(())
+}
+
 /// 规范化进程名用于比较（Windows 下大小写不敏感）。
 pub(crate) fn normalize_name(name: &str) -> String {
     name.trim().to_ascii_lowercase()
@@ -33,6 +78,13 @@ pub(crate) fn validate_ymd(date: &str) -> AppResult<()> {
     Ok(())
 }
 
+/// 校验并规范化 `HH:mm` 时间字符串（如 `9:05` -> `09:05`）。
+pub(crate) fn validate_hhmm(time: &str) -> AppResult<String> {
+    chrono::NaiveTime::parse_from_str(time, "%H:%M")
+        .map(|t| t.format("%H:%M").to_string())
+        .map_err(|_| AppError::Validation("时间格式必须为 HH:mm".to_string()))
+}
+
 /// 校验日期范围：格式正确且 `from <= to`。
 pub(crate) fn validate_date_range(range: &DateRange) -> AppResult<()> {
     validate_ymd(range.from.trim())?;
@@ -47,7 +99,7 @@ pub(crate) fn validate_date_range(range: &DateRange) -> AppResult<()> {
 
 /// 选择供“历史页面/导出/分析”使用的历史数据源（开发环境：优先 `history_dev`）。
 pub(crate) fn history_for_ui(data: &crate::app_data::AppData) -> &Vec<HistoryDay> {
-    if cfg!(debug_assertions) && !data.history_dev.is_empty() {
+    if history_for_ui_is_dev(data) {
         &data.history_dev
     } else {
         &data.history
@@ -56,13 +108,18 @@ pub(crate) fn history_for_ui(data: &crate::app_data::AppData) -> &Vec<HistoryDay
 
 /// 选择供“历史备注编辑”使用的可变历史数据源（开发环境：优先 `history_dev`）。
 pub(crate) fn history_for_ui_mut(data: &mut crate::app_data::AppData) -> &mut Vec<HistoryDay> {
-    if cfg!(debug_assertions) && !data.history_dev.is_empty() {
+    if history_for_ui_is_dev(data) {
         &mut data.history_dev
     } else {
         &mut data.history
     }
 }
 
+/// `history_for_ui` 选择的是否为开发环境测试历史（修改记录时据此调用 `AppData::history_day_mut`）。
+pub(crate) fn history_for_ui_is_dev(data: &crate::app_data::AppData) -> bool {
+    cfg!(debug_assertions) && !data.history_dev.is_empty()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +173,71 @@ mod tests {
         assert!(matches!(err, AppError::Validation(_)));
     }
 
+    /// `validate_session_plan`：应拒绝空名称/空步骤/越界时长/不含工作步骤的计划。
+    #[test]
+    fn validate_session_plan_rejects_invalid_plans() {
+        use crate::app_data::PlanStep;
+        let valid = SessionPlan {
+            id: "plan-1".to_string(),
+            name: "50/10".to_string(),
+            steps: vec![
+                PlanStep {
+                    name: "专注".to_string(),
+                    kind: Phase::Work,
+                    minutes: 50,
+                },
+                PlanStep {
+                    name: "休息".to_string(),
+                    kind: Phase::ShortBreak,
+                    minutes: 10,
+                },
+            ],
+        };
+        assert!(validate_session_plan(&valid).is_ok());
+
+        let mut blank_name = valid.clone();
+        blank_name.name = "  ".to_string();
+        let mut no_steps = valid.clone();
+        no_steps.steps.clear();
+        let mut too_long = valid.clone();
+        too_long.steps[0].minutes = 181;
+        let mut breaks_only = valid.clone();
+        breaks_only.steps.remove(0);
+
+        for plan in [blank_name, no_steps, too_long, breaks_only] {
+            assert!(matches!(
+                validate_session_plan(&plan),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+
+    /// `validate_task`：应拒绝空标题、过大的预估与非法截止日期。
+    #[test]
+    fn validate_task_rejects_invalid_tasks() {
+        let valid = Task {
+            id: String::new(),
+            title: "写周报".to_string(),
+            tag: "工作".to_string(),
+            tag_id: None,
+            estimated_pomodoros: 2,
+            due_date: Some("2025-01-03".to_string()),
+            done: false,
+        };
+        assert!(validate_task(&valid).is_ok());
+
+        let mut blank_title = valid.clone();
+        blank_title.title = "  ".to_string();
+        let mut too_many = valid.clone();
+        too_many.estimated_pomodoros = 101;
+        let mut bad_due = valid.clone();
+        bad_due.due_date = Some("2025/01/03".to_string());
+
+        for task in [blank_title, too_many, bad_due] {
+            assert!(matches!(validate_task(&task), Err(AppError::Validation(_))));
+        }
+    }
+
     /// `validate_ymd`：合法日期应通过，非法格式应失败。
     #[test]
     fn validate_ymd_accepts_and_rejects() {
@@ -126,6 +248,20 @@ mod tests {
         ));
     }
 
+    /// `validate_hhmm`：应补齐前导零，并拒绝越界或格式错误的时间。
+    #[test]
+    fn validate_hhmm_normalizes_and_rejects() {
+        assert_eq!(validate_hhmm("9:05").unwrap(), "09:05");
+        assert!(matches!(
+            validate_hhmm("24:00"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_hhmm("0900"),
+            Err(AppError::Validation(_))
+        ));
+    }
+
     /// `validate_date_range`：应校验格式并确保 from <= to。
     #[test]
     fn validate_date_range_validates_order_and_format() {
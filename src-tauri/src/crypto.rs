@@ -0,0 +1,543 @@
+//! 静态数据加密：口令经 PBKDF2-HMAC-SHA256（`pbkdf2` crate）派生密钥，数据以 XChaCha20-Poly1305（`chacha20poly1305` crate）加密并认证。
+//!
+//! 加密后的 JSON 统一表示为 `{"sealed": SealedBlob}`；读取时未加密的数据原样返回，便于启用/关闭加密时新旧数据混合读取。
+//! 加密的 `AppData`（store 与备份）外层另带明文 `schemaVersion`，旧版本应用无需解密即可识别并拒绝打开。
+//! 数据库中需要建索引的标签/任务列改存 HMAC-SHA256 带密钥哈希（密钥由派生密钥导出），可按索引查询而不暴露原值。
+//! 配置目录下的 `encryption.json` 保存加密格式版本与口令校验块（含盐与迭代次数），文件存在即表示该配置已启用加密。
+
+use std::path::Path;
+
+use base64::Engine as _;
+use chacha20poly1305::aead::{AeadInPlace as _, KeyInit as _};
+use chacha20poly1305::{Tag, XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::errors::{AppError, AppResult};
+
+/// 加密配置文件名（位于配置数据目录）。
+pub const ENCRYPTION_FILE_NAME: &str = "encryption.json";
+
+/// 口令最少字符数。
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// PBKDF2 迭代次数（测试中降低以免拖慢用例；实际次数随密文保存，调整后旧数据仍可解密）。
+const KDF_ITERATIONS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+/// 加密 JSON 的外层键名。
+const SEALED_KEY: &str = "sealed";
+
+/// 加密 JSON 外层可附带的明文数据版本键名（与 `AppData::schema_version` 的序列化名一致）。
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// 加密格式版本：1 = PBKDF2-HMAC-SHA256 + XChaCha20-Poly1305。
+const SEALED_VERSION: u32 = 1;
+
+/// 盐长度（字节）。
+const SALT_LEN: usize = 16;
+
+/// 每次加密的随机数长度（字节，XChaCha20 的 192 位随机数）。
+const NONCE_LEN: usize = 24;
+
+/// 密钥长度（字节）。
+const KEY_LEN: usize = 32;
+
+/// 口令校验块的明文。
+const KEY_CHECK: &[u8] = b"pomodoro-key-check";
+
+/// 由派生密钥导出索引哈希密钥时使用的标签。
+const INDEX_KEY_LABEL: &[u8] = b"pomodoro-index-key";
+
+/// 一段密文（字段均为 base64）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SealedBlob {
+    /// 加密格式版本。
+    pub version: u32,
+    /// 派生密钥时的 PBKDF2 迭代次数。
+    pub iterations: u32,
+    /// 派生密钥时的盐。
+    pub salt: String,
+    /// 本次加密的随机数。
+    pub nonce: String,
+    /// 密文。
+    pub data: String,
+    /// Poly1305 认证标签（同时认证版本、盐与迭代次数）。
+    pub mac: String,
+}
+
+/// PBKDF2-HMAC-SHA256 派生密钥。
+fn derive_key(passphrase: &[u8], salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, salt, iterations, &mut key);
+    key
+}
+
+/// HMAC-SHA256。
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; KEY_LEN] {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+/// base64 编码。
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// base64 解码（失败视为数据损坏）。
+fn decode(text: &str) -> AppResult<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| AppError::Invariant(format!("加密数据已损坏：{e}")))
+}
+
+/// 校验加密格式版本（更新版本的格式无法解密）。
+fn ensure_supported_version(version: u32) -> AppResult<()> {
+    if version > SEALED_VERSION {
+        return Err(AppError::Validation(
+            "加密数据来自更新版本的应用，无法解密".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验口令强度（去首尾空白前计数，至少 `MIN_PASSPHRASE_CHARS` 个字符）。
+pub fn validate_passphrase(passphrase: &str) -> AppResult<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(AppError::Validation(format!(
+            "密码至少需要 {MIN_PASSPHRASE_CHARS} 个字符"
+        )));
+    }
+    Ok(())
+}
+
+/// 由口令派生出的加解密密钥。
+#[derive(Clone)]
+pub struct Cipher {
+    /// 派生所用的盐。
+    salt: Vec<u8>,
+    /// 派生所用的迭代次数。
+    iterations: u32,
+    /// 以派生密钥初始化的 AEAD（释放时清零密钥）。
+    aead: XChaCha20Poly1305,
+    /// 索引列哈希所用的密钥（由派生密钥经 HMAC 导出，与加密密钥相互独立）。
+    index_key: [u8; KEY_LEN],
+}
+
+impl std::fmt::Debug for Cipher {
+    /// 不输出任何密钥材料。
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cipher")
+            .field("iterations", &self.iterations)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Cipher {
+    /// 以指定盐与迭代次数从口令派生密钥。
+    fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Self {
+        let mut key = derive_key(passphrase.as_bytes(), salt, iterations);
+        let aead = XChaCha20Poly1305::new(&key.into());
+        let index_key = hmac_sha256(&key, INDEX_KEY_LABEL);
+        key.fill(0);
+        Self {
+            salt: salt.to_vec(),
+            iterations,
+            aead,
+            index_key,
+        }
+    }
+
+    /// 以随机盐从新口令派生密钥（口令需满足强度要求）。
+    pub fn generate(passphrase: &str) -> AppResult<Self> {
+        validate_passphrase(passphrase)?;
+        let mut salt = [0u8; SALT_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        Ok(Self::derive(passphrase, &salt, KDF_ITERATIONS))
+    }
+
+    /// 按密文记录的盐与迭代次数从口令派生密钥（用于解密导出文件等独立密文）。
+    pub fn for_blob(passphrase: &str, blob: &SealedBlob) -> AppResult<Self> {
+        ensure_supported_version(blob.version)?;
+        Ok(Self::derive(
+            passphrase,
+            &decode(&blob.salt)?,
+            blob.iterations,
+        ))
+    }
+
+    /// 判断口令是否与当前密钥一致（以新派生的密钥解密当前密钥加密的校验块）。
+    pub fn verify(&self, passphrase: &str) -> bool {
+        let other = Self::derive(passphrase, &self.salt, self.iterations);
+        other.open(&self.seal(KEY_CHECK)).is_ok()
+    }
+
+    /// 加密一段明文（每次使用新的随机数）。
+    pub fn seal(&self, plaintext: &[u8]) -> SealedBlob {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let mut data = plaintext.to_vec();
+        let tag = self
+            .aead
+            .encrypt_in_place_detached(
+                XNonce::from_slice(&nonce),
+                &self.associated_data(SEALED_VERSION),
+                &mut data,
+            )
+            .expect("明文长度超出 XChaCha20-Poly1305 的上限");
+        SealedBlob {
+            version: SEALED_VERSION,
+            iterations: self.iterations,
+            salt: encode(&self.salt),
+            nonce: encode(&nonce),
+            data: encode(&data),
+            mac: encode(&tag),
+        }
+    }
+
+    /// 解密一段密文：盐/迭代次数不符或认证失败时返回 `AppError::WrongPassphrase`。
+    pub fn open(&self, blob: &SealedBlob) -> AppResult<Vec<u8>> {
+        ensure_supported_version(blob.version)?;
+        if blob.iterations != self.iterations || decode(&blob.salt)? != self.salt {
+            return Err(AppError::WrongPassphrase);
+        }
+        let nonce = decode(&blob.nonce)?;
+        let tag = decode(&blob.mac)?;
+        if nonce.len() != NONCE_LEN || tag.len() != Tag::default().len() {
+            return Err(AppError::Invariant(
+                "加密数据已损坏：随机数或认证标签长度不符".to_string(),
+            ));
+        }
+        let mut data = decode(&blob.data)?;
+        self.aead
+            .decrypt_in_place_detached(
+                XNonce::from_slice(&nonce),
+                &self.associated_data(blob.version),
+                &mut data,
+                Tag::from_slice(&tag),
+            )
+            .map_err(|_| AppError::WrongPassphrase)?;
+        Ok(data)
+    }
+
+    /// 将可序列化值加密为 `{"schemaVersion": N, "sealed": ...}`：外层保留明文数据版本，
+    /// 供 `migrations::ensure_supported_value` 在解密前拒绝更新版本写入的数据。
+    pub fn seal_json_with_schema<T: Serialize>(
+        &self,
+        value: &T,
+        schema_version: u32,
+    ) -> AppResult<serde_json::Value> {
+        let blob = self.seal(&serde_json::to_vec(value)?);
+        Ok(serde_json::json!({ SCHEMA_VERSION_KEY: schema_version, SEALED_KEY: blob }))
+    }
+
+    /// 将任意字节加密为 `{"sealed": ...}`（用于压缩后的归档文件等二进制内容）。
+    pub fn seal_bytes(&self, plaintext: &[u8]) -> serde_json::Value {
+        serde_json::json!({ SEALED_KEY: self.seal(plaintext) })
+    }
+
+    /// 将已序列化的 JSON 文本加密为 `{"sealed": ...}` 文本（用于逐行加密数据库记录）。
+    pub fn seal_json_text(&self, json: &str) -> AppResult<String> {
+        let blob = self.seal(json.as_bytes());
+        Ok(serde_json::to_string(
+            &serde_json::json!({ SEALED_KEY: blob }),
+        )?)
+    }
+
+    /// 计算索引列的带密钥哈希（base64）：同一密钥下相同的值得到相同的哈希，不同密钥之间互不相同。
+    pub fn index_hash(&self, value: &str) -> String {
+        encode(&hmac_sha256(&self.index_key, value.as_bytes()))
+    }
+
+    /// 附加认证数据：版本、迭代次数与盐（避免密文参数被篡改）。
+    fn associated_data(&self, version: u32) -> Vec<u8> {
+        let mut aad = Vec::with_capacity(8 + self.salt.len());
+        aad.extend_from_slice(&version.to_be_bytes());
+        aad.extend_from_slice(&self.iterations.to_be_bytes());
+        aad.extend_from_slice(&self.salt);
+        aad
+    }
+}
+
+/// 若 JSON 为 `{"sealed": ...}` 形式（可带明文 `schemaVersion`）则取出密文。
+pub fn sealed_blob(value: &serde_json::Value) -> Option<SealedBlob> {
+    let object = value.as_object()?;
+    if object
+        .keys()
+        .any(|key| key != SEALED_KEY && key != SCHEMA_VERSION_KEY)
+    {
+        return None;
+    }
+    serde_json::from_value(object.get(SEALED_KEY)?.clone()).ok()
+}
+
+/// 解密 `{"sealed": ...}` 形式的 JSON（未加密时原样返回；已加密但未解锁时返回 `AppError::Locked`）。
+pub fn unseal_json(
+    cipher: Option<&Cipher>,
+    value: serde_json::Value,
+) -> AppResult<serde_json::Value> {
+    let Some(blob) = sealed_blob(&value) else {
+        return Ok(value);
+    };
+    let cipher = cipher.ok_or(AppError::Locked)?;
+    Ok(serde_json::from_slice(&cipher.open(&blob)?)?)
+}
+
+/// 以口令加密一段独立数据（如导出文件），返回 `{"sealed": ...}` 的 JSON 字节。
+pub fn seal_with_passphrase(passphrase: &str, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let blob = Cipher::generate(passphrase)?.seal(plaintext);
+    Ok(serde_json::to_vec(
+        &serde_json::json!({ SEALED_KEY: blob }),
+    )?)
+}
+
+/// 若字节为 `{"sealed": ...}` 形式则以口令解密（未提供口令时返回 `AppError::Locked`），否则原样返回。
+pub fn unseal_with_passphrase(passphrase: Option<&str>, bytes: Vec<u8>) -> AppResult<Vec<u8>> {
+    let blob = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(value) => sealed_blob(&value),
+        Err(_) => None,
+    };
+    let Some(blob) = blob else {
+        return Ok(bytes);
+    };
+    let passphrase = passphrase.ok_or(AppError::Locked)?;
+    Cipher::for_blob(passphrase, &blob)?.open(&blob)
+}
+
+/// 配置的加密文件：保存加密格式版本与口令校验块（其中记录了盐与迭代次数）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionHeader {
+    /// 加密格式版本（解锁前先据此拒绝无法处理的格式）。
+    pub version: u32,
+    /// 以当前密钥加密的固定明文，用于校验口令。
+    pub check: SealedBlob,
+}
+
+impl EncryptionHeader {
+    /// 为密钥生成校验块。
+    pub fn new(cipher: &Cipher) -> Self {
+        Self {
+            version: SEALED_VERSION,
+            check: cipher.seal(KEY_CHECK),
+        }
+    }
+
+    /// 读取配置目录下的加密文件（不存在表示未启用加密）。
+    pub fn load(dir: &Path) -> AppResult<Option<Self>> {
+        let bytes = match std::fs::read(dir.join(ENCRYPTION_FILE_NAME)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AppError::Invariant(format!("读取加密配置失败：{e}"))),
+        };
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    /// 写入加密文件（先写临时文件再重命名）。
+    pub fn save(&self, dir: &Path) -> AppResult<()> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::Invariant(format!("创建数据目录失败：{e}")))?;
+        let tmp = dir.join(format!("{ENCRYPTION_FILE_NAME}.tmp"));
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .map_err(|e| AppError::Invariant(format!("写入加密配置失败：{e}")))?;
+        std::fs::rename(&tmp, dir.join(ENCRYPTION_FILE_NAME))
+            .map_err(|e| AppError::Invariant(format!("保存加密配置失败：{e}")))
+    }
+
+    /// 删除加密文件（关闭加密后调用；文件不存在时忽略）。
+    pub fn remove(dir: &Path) -> AppResult<()> {
+        match std::fs::remove_file(dir.join(ENCRYPTION_FILE_NAME)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Invariant(format!("删除加密配置失败：{e}"))),
+        }
+    }
+
+    /// 以口令解锁：派生密钥并校验，口令错误时返回 `AppError::WrongPassphrase`。
+    pub fn unlock(&self, passphrase: &str) -> AppResult<Cipher> {
+        ensure_supported_version(self.version)?;
+        let cipher = Cipher::for_blob(passphrase, &self.check)?;
+        if cipher.open(&self.check)? != KEY_CHECK {
+            return Err(AppError::WrongPassphrase);
+        }
+        Ok(cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 字节转十六进制字符串。
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// 十六进制字符串转字节。
+    fn unhex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// `derive_key`：与 RFC 7914 第 11 节的 PBKDF2-HMAC-SHA256 测试向量一致（取前 32 字节）。
+    #[test]
+    fn derive_key_matches_rfc7914_vector() {
+        assert_eq!(
+            hex(&derive_key(b"passwd", b"salt", 1)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    /// XChaCha20-Poly1305：与 draft-irtf-cfrg-xchacha 附录 A.3.1 的测试向量一致，解密可还原。
+    #[test]
+    fn aead_matches_xchacha_draft_vector() {
+        let key = unhex("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce = unhex("404142434445464748494a4b4c4d4e4f5051525354555657");
+        let aad = unhex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let aead = XChaCha20Poly1305::new_from_slice(&key).unwrap();
+        let mut data = plaintext.to_vec();
+        let tag = aead
+            .encrypt_in_place_detached(XNonce::from_slice(&nonce), &aad, &mut data)
+            .unwrap();
+        assert_eq!(
+            hex(&data),
+            concat!(
+                "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb",
+                "731c7f1b0b4aa6440bf3a82f4eda7e39ae64c6708c54c216cb96b72e1213b452",
+                "2f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff9",
+                "21f9664c97637da9768812f615c68b13b52e"
+            )
+        );
+        assert_eq!(hex(&tag), "c0875924c1c7987947deafd8780acf49");
+        aead.decrypt_in_place_detached(XNonce::from_slice(&nonce), &aad, &mut data, &tag)
+            .unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    /// `seal/open`：密文不含明文、可还原；篡改密文或换用其他口令时报 `WrongPassphrase`。
+    #[test]
+    fn seal_round_trips_and_detects_wrong_key() {
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let plaintext = "客户：某某公司 — 需求评审".repeat(3);
+        let blob = cipher.seal(plaintext.as_bytes());
+        assert!(!blob.data.contains("客户"));
+        assert_eq!(cipher.open(&blob).unwrap(), plaintext.as_bytes());
+        assert_ne!(cipher.seal(plaintext.as_bytes()).nonce, blob.nonce);
+
+        let mut tampered = blob.clone();
+        tampered.data = encode(&[0u8; 4]);
+        assert!(matches!(
+            cipher.open(&tampered),
+            Err(AppError::WrongPassphrase)
+        ));
+
+        let mut downgraded = blob.clone();
+        downgraded.iterations = 1;
+        assert!(matches!(
+            cipher.open(&downgraded),
+            Err(AppError::WrongPassphrase)
+        ));
+
+        let wrong = Cipher::for_blob("wrong horse", &blob).unwrap();
+        assert!(matches!(wrong.open(&blob), Err(AppError::WrongPassphrase)));
+        assert!(cipher.verify("correct horse"));
+        assert!(!cipher.verify("wrong horse"));
+    }
+
+    /// `index_hash`：同一密钥下结果稳定且不含原值，换用其他口令派生的密钥后结果不同。
+    #[test]
+    fn index_hash_is_keyed_and_stable() {
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let hash = cipher.index_hash("tag-1");
+        assert_eq!(cipher.index_hash("tag-1"), hash);
+        assert_ne!(cipher.index_hash("tag-2"), hash);
+        assert!(!hash.contains("tag-1"));
+        let other = Cipher::generate("correct horse").unwrap();
+        assert_ne!(other.index_hash("tag-1"), hash);
+    }
+
+    /// `unseal_json`：未加密的值原样返回；已加密但未解锁时报 `Locked`。
+    #[test]
+    fn unseal_json_passes_through_plain_values() {
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let value = serde_json::json!({ "remark": "客户会议" });
+        let sealed: serde_json::Value =
+            serde_json::from_str(&cipher.seal_json_text(&value.to_string()).unwrap()).unwrap();
+        assert!(sealed_blob(&sealed).is_some());
+        assert_eq!(unseal_json(Some(&cipher), sealed.clone()).unwrap(), value);
+        assert_eq!(unseal_json(None, value.clone()).unwrap(), value);
+        assert!(matches!(unseal_json(None, sealed), Err(AppError::Locked)));
+    }
+
+    /// `seal_json_with_schema`：外层明文数据版本可在解密前读取，密文仍按普通加密 JSON 解密。
+    #[test]
+    fn sealed_app_data_exposes_schema_version() {
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let value = serde_json::json!({ "schemaVersion": 7, "remark": "客户会议" });
+        let sealed = cipher.seal_json_with_schema(&value, 7).unwrap();
+        assert_eq!(sealed["schemaVersion"], 7);
+        assert!(sealed_blob(&sealed).is_some());
+        assert_eq!(unseal_json(Some(&cipher), sealed).unwrap(), value);
+
+        let newer = cipher
+            .seal_json_with_schema(&value, crate::migrations::CURRENT_SCHEMA_VERSION + 1)
+            .unwrap();
+        assert!(matches!(
+            crate::migrations::ensure_supported_value(&newer),
+            Err(AppError::SchemaTooNew { .. })
+        ));
+    }
+
+    /// `EncryptionHeader`：保存后可读回并以正确口令解锁；错误口令报 `WrongPassphrase`；删除后视为未加密。
+    #[test]
+    fn header_unlocks_with_correct_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(EncryptionHeader::load(dir.path()).unwrap().is_none());
+
+        let cipher = Cipher::generate("correct horse").unwrap();
+        EncryptionHeader::new(&cipher).save(dir.path()).unwrap();
+        let header = EncryptionHeader::load(dir.path()).unwrap().unwrap();
+        let unlocked = header.unlock("correct horse").unwrap();
+        let blob = cipher.seal(b"data");
+        assert_eq!(unlocked.open(&blob).unwrap(), b"data");
+        assert!(matches!(
+            header.unlock("wrong horse"),
+            Err(AppError::WrongPassphrase)
+        ));
+
+        EncryptionHeader::remove(dir.path()).unwrap();
+        assert!(EncryptionHeader::load(dir.path()).unwrap().is_none());
+    }
+
+    /// `seal_with_passphrase`：独立密文需同一口令解密；未加密内容原样返回；过短口令被拒绝。
+    #[test]
+    fn passphrase_sealing_round_trips() {
+        let sealed = seal_with_passphrase("export-pass", b"date,remark").unwrap();
+        assert_eq!(
+            unseal_with_passphrase(Some("export-pass"), sealed.clone()).unwrap(),
+            b"date,remark"
+        );
+        assert!(matches!(
+            unseal_with_passphrase(Some("other-pass"), sealed.clone()),
+            Err(AppError::WrongPassphrase)
+        ));
+        assert!(matches!(
+            unseal_with_passphrase(None, sealed),
+            Err(AppError::Locked)
+        ));
+        assert_eq!(
+            unseal_with_passphrase(None, b"a,b".to_vec()).unwrap(),
+            b"a,b"
+        );
+        assert!(matches!(
+            seal_with_passphrase("short", b""),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,596 @@
+//! 数据根目录：解析默认位置、自定义位置与便携模式，并整体迁移数据（复制 → 校验 → 切换 → 清理旧目录，失败时回滚）。
+//!
+//! 运行中的 store/数据库/日志文件都已打开（退出时 store 插件还会回写旧路径），
+//! 因此迁移只在运行中登记请求，由下次启动在打开任何文件之前执行。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use ts_rs::TS;
+
+use crate::errors::{AppError, AppResult};
+
+/// 自定义根目录指针文件名（位于默认根目录）。
+pub const LOCATION_FILE_NAME: &str = "location.json";
+
+/// 便携模式标记文件名（位于可执行文件同目录，存在即启用便携模式）。
+pub const PORTABLE_MARKER_FILE_NAME: &str = "portable";
+
+/// 便携模式的数据根目录名（位于可执行文件同目录）。
+pub const PORTABLE_DIR_NAME: &str = "pomodoro-data";
+
+/// 待迁移请求文件名（位于当前根目录）。
+const PENDING_FILE_NAME: &str = "relocate-pending.json";
+
+/// 随根目录整体迁移的子目录（store 与历史库、音频库、日志、备份、隔离文件）。
+pub const ROOT_ENTRIES: [&str; 5] = ["data", "audio", "logs", "backups", "quarantine"];
+
+/// 数据根目录模式。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum DataRootMode {
+    /// 系统默认的应用数据目录。
+    Default,
+    /// 用户指定的目录（如同步盘、U 盘）。
+    Custom,
+    /// 可执行文件旁的 `pomodoro-data/`。
+    Portable,
+}
+
+/// 数据根目录信息（用于设置页展示）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct DataRootInfo {
+    /// 当前模式。
+    pub mode: DataRootMode,
+    /// 当前根目录。
+    pub root: String,
+    /// 默认模式的根目录。
+    pub default_root: String,
+    /// 便携模式的根目录（无法定位可执行文件时为 `null`）。
+    pub portable_root: Option<String>,
+    /// 已登记、将在下次启动时迁移到的目录。
+    pub pending_target: Option<String>,
+    /// 本次启动执行迁移失败的原因（数据仍位于原目录）。
+    pub last_error: Option<String>,
+}
+
+/// 自定义根目录指针文件内容。
+#[derive(Debug, Serialize, Deserialize)]
+struct LocationFile {
+    /// 自定义根目录（绝对路径）。
+    root: PathBuf,
+}
+
+/// 切换前的位置指针（自定义指针文件内容与便携标记是否存在），切换失败时据此恢复。
+#[derive(Debug)]
+struct RootPointers {
+    /// 指针文件原内容（不存在时为 `None`）。
+    location: Option<Vec<u8>>,
+    /// 便携标记是否存在（无法定位可执行文件时为 `None`）。
+    portable_marker: Option<bool>,
+}
+
+/// 待迁移请求（运行中登记，下次启动执行）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRelocation {
+    /// 迁移后的模式。
+    pub mode: DataRootMode,
+    /// 迁移前的根目录。
+    pub from: PathBuf,
+    /// 迁移后的根目录。
+    pub to: PathBuf,
+}
+
+/// 一次迁移的结果摘要。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelocateReport {
+    /// 复制并校验通过的文件数。
+    pub files: usize,
+    /// 复制的总字节数。
+    pub bytes: u64,
+    /// 切换后未能删除的旧目录（数据已在新位置，可手动清理）。
+    pub leftovers: Vec<PathBuf>,
+}
+
+/// 根目录定位器：由默认根目录与可执行文件目录决定当前模式与根目录。
+#[derive(Debug, Clone)]
+pub struct RootLocator {
+    /// 系统默认根目录。
+    default_root: PathBuf,
+    /// 可执行文件所在目录（无法获取时为 `None`，此时不支持便携模式）。
+    exe_dir: Option<PathBuf>,
+}
+
+impl RootLocator {
+    /// 创建定位器。
+    pub fn new(default_root: PathBuf, exe_dir: Option<PathBuf>) -> Self {
+        Self {
+            default_root,
+            exe_dir,
+        }
+    }
+
+    /// 便携模式的根目录。
+    pub fn portable_root(&self) -> Option<PathBuf> {
+        self.exe_dir.as_ref().map(|dir| dir.join(PORTABLE_DIR_NAME))
+    }
+
+    /// 解析当前模式与根目录：便携标记优先，其次自定义指针文件，否则为默认目录。
+    ///
+    /// 指针文件无法解析或指向相对路径时记录日志并回退默认目录。
+    pub fn resolve(&self) -> (DataRootMode, PathBuf) {
+        if let Some(dir) = &self.exe_dir {
+            if dir.join(PORTABLE_MARKER_FILE_NAME).is_file() {
+                return (DataRootMode::Portable, dir.join(PORTABLE_DIR_NAME));
+            }
+        }
+        let path = self.default_root.join(LOCATION_FILE_NAME);
+        if let Ok(bytes) = std::fs::read(&path) {
+            match serde_json::from_slice::<LocationFile>(&bytes) {
+                Ok(location) if location.root.is_absolute() => {
+                    return (DataRootMode::Custom, location.root);
+                }
+                _ => {
+                    tracing::warn!(
+                        target: "storage",
+                        "数据根目录指针文件无效，已回退默认目录：{}",
+                        path.to_string_lossy()
+                    );
+                }
+            }
+        }
+        (DataRootMode::Default, self.default_root.clone())
+    }
+
+    /// 当前根目录。
+    pub fn root(&self) -> PathBuf {
+        self.resolve().1
+    }
+
+    /// 生成设置页展示信息（`last_error` 由调用方补充）。
+    pub fn info(&self) -> DataRootInfo {
+        let (mode, root) = self.resolve();
+        DataRootInfo {
+            mode,
+            root: root.to_string_lossy().to_string(),
+            default_root: self.default_root.to_string_lossy().to_string(),
+            portable_root: self
+                .portable_root()
+                .map(|p| p.to_string_lossy().to_string()),
+            pending_target: load_pending(&root).map(|p| p.to.to_string_lossy().to_string()),
+            last_error: None,
+        }
+    }
+
+    /// 计算目标模式对应的根目录（自定义模式需提供绝对路径）。
+    pub fn target_root(&self, mode: DataRootMode, custom: Option<&str>) -> AppResult<PathBuf> {
+        match mode {
+            DataRootMode::Default => Ok(self.default_root.clone()),
+            DataRootMode::Portable => self.portable_root().ok_or_else(|| {
+                AppError::Validation("无法定位程序所在目录，不支持便携模式".to_string())
+            }),
+            DataRootMode::Custom => {
+                let path = PathBuf::from(custom.map(str::trim).unwrap_or_default());
+                if !path.is_absolute() {
+                    return Err(AppError::Validation(
+                        "请选择一个有效的目录（需为绝对路径）".to_string(),
+                    ));
+                }
+                Ok(path)
+            }
+        }
+    }
+
+    /// 切换到目标模式：写入（或清除）便携标记与自定义指针文件。
+    pub fn activate(&self, mode: DataRootMode, root: &Path) -> AppResult<()> {
+        let location = self.default_root.join(LOCATION_FILE_NAME);
+        if mode == DataRootMode::Custom {
+            std::fs::create_dir_all(&self.default_root)
+                .map_err(|e| AppError::Invariant(format!("创建默认数据目录失败：{e}")))?;
+            let bytes = serde_json::to_vec_pretty(&LocationFile {
+                root: root.to_path_buf(),
+            })?;
+            std::fs::write(&location, bytes)
+                .map_err(|e| AppError::Invariant(format!("写入数据目录指针失败：{e}")))?;
+        } else {
+            remove_file_if_exists(&location)?;
+        }
+        if let Some(dir) = &self.exe_dir {
+            let marker = dir.join(PORTABLE_MARKER_FILE_NAME);
+            if mode == DataRootMode::Portable {
+                std::fs::write(&marker, b"")
+                    .map_err(|e| AppError::Invariant(format!("写入便携模式标记失败：{e}")))?;
+            } else {
+                remove_file_if_exists(&marker)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 读取当前的位置指针（指针文件无法读取时返回错误，避免回滚时误删）。
+    fn pointers(&self) -> AppResult<RootPointers> {
+        let path = self.default_root.join(LOCATION_FILE_NAME);
+        let location = match std::fs::read(&path) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(AppError::Invariant(format!(
+                    "读取数据目录指针失败：{}：{e}",
+                    path.to_string_lossy()
+                )))
+            }
+        };
+        let portable_marker = self
+            .exe_dir
+            .as_ref()
+            .map(|dir| dir.join(PORTABLE_MARKER_FILE_NAME).is_file());
+        Ok(RootPointers {
+            location,
+            portable_marker,
+        })
+    }
+
+    /// 恢复 `pointers` 记录的位置指针。
+    fn restore_pointers(&self, pointers: &RootPointers) -> AppResult<()> {
+        let location = self.default_root.join(LOCATION_FILE_NAME);
+        match &pointers.location {
+            Some(bytes) => std::fs::write(&location, bytes)
+                .map_err(|e| AppError::Invariant(format!("恢复数据目录指针失败：{e}")))?,
+            None => remove_file_if_exists(&location)?,
+        }
+        if let (Some(dir), Some(existed)) = (&self.exe_dir, pointers.portable_marker) {
+            let marker = dir.join(PORTABLE_MARKER_FILE_NAME);
+            if existed {
+                std::fs::write(&marker, b"")
+                    .map_err(|e| AppError::Invariant(format!("恢复便携模式标记失败：{e}")))?;
+            } else {
+                remove_file_if_exists(&marker)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 删除文件（不存在时忽略）。
+fn remove_file_if_exists(path: &Path) -> AppResult<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::Invariant(format!(
+            "删除文件失败：{}：{e}",
+            path.to_string_lossy()
+        ))),
+    }
+}
+
+/// 校验迁移目标：不能与当前目录相同或互相嵌套，且目标中不能已有同名数据目录。
+pub fn check_target(from: &Path, to: &Path) -> AppResult<()> {
+    if from == to {
+        return Err(AppError::Validation(
+            "目标目录与当前数据目录相同".to_string(),
+        ));
+    }
+    if to.starts_with(from) || from.starts_with(to) {
+        return Err(AppError::Validation(
+            "目标目录不能位于当前数据目录之内（或包含当前数据目录）".to_string(),
+        ));
+    }
+    if let Some(entry) = ROOT_ENTRIES.iter().find(|e| to.join(e).exists()) {
+        return Err(AppError::Validation(format!(
+            "目标目录中已存在 {entry}/，请选择空目录"
+        )));
+    }
+    Ok(())
+}
+
+/// 读取当前根目录中登记的待迁移请求（无法解析时视为无请求）。
+pub fn load_pending(root: &Path) -> Option<PendingRelocation> {
+    let bytes = std::fs::read(root.join(PENDING_FILE_NAME)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// 在当前根目录登记待迁移请求（覆盖之前的请求）。
+pub fn save_pending(pending: &PendingRelocation) -> AppResult<()> {
+    std::fs::create_dir_all(&pending.from)
+        .map_err(|e| AppError::Invariant(format!("创建数据目录失败：{e}")))?;
+    let bytes = serde_json::to_vec_pretty(pending)?;
+    std::fs::write(pending.from.join(PENDING_FILE_NAME), bytes)
+        .map_err(|e| AppError::Invariant(format!("登记数据迁移失败：{e}")))
+}
+
+/// 启动时执行已登记的迁移（无请求时返回 `None`）。
+///
+/// 请求只尝试一次：执行前即删除请求文件，失败时数据保留在原目录。
+pub fn apply_pending(locator: &RootLocator) -> Option<AppResult<RelocateReport>> {
+    let root = locator.root();
+    let pending = load_pending(&root)?;
+    if let Err(e) = remove_file_if_exists(&root.join(PENDING_FILE_NAME)) {
+        return Some(Err(e));
+    }
+    if pending.from != root {
+        return Some(Err(AppError::Validation(
+            "数据目录在登记迁移后已变化，已取消迁移".to_string(),
+        )));
+    }
+    Some(check_target(&pending.from, &pending.to).and_then(|()| {
+        relocate(&pending.from, &pending.to, locator, || {
+            locator.activate(pending.mode, &pending.to)
+        })
+    }))
+}
+
+/// 迁移根目录：复制并逐文件校验后调用 `activate` 切换位置，最后删除旧目录。
+///
+/// 复制、校验或切换失败时删除已复制的内容并返回错误，原目录保持不变；
+/// 切换中途失败（如已写入指针文件、便携标记写入失败）时把 `locator` 的位置指针恢复为切换前的状态。
+pub fn relocate(
+    from: &Path,
+    to: &Path,
+    locator: &RootLocator,
+    activate: impl FnOnce() -> AppResult<()>,
+) -> AppResult<RelocateReport> {
+    let entries: Vec<&str> = ROOT_ENTRIES
+        .iter()
+        .copied()
+        .filter(|e| from.join(e).exists())
+        .collect();
+    let pointers = locator.pointers()?;
+    let copied = copy_and_verify(from, to, &entries).and_then(|report| {
+        if let Err(e) = activate() {
+            if let Err(restore) = locator.restore_pointers(&pointers) {
+                tracing::error!(target: "storage", "恢复数据目录指针失败：{restore}");
+            }
+            return Err(e);
+        }
+        Ok(report)
+    });
+    let mut report = match copied {
+        Ok(report) => report,
+        Err(e) => {
+            for entry in &entries {
+                let _ = std::fs::remove_dir_all(to.join(entry));
+            }
+            return Err(e);
+        }
+    };
+    for entry in &entries {
+        let path = from.join(entry);
+        if let Err(e) = std::fs::remove_dir_all(&path) {
+            tracing::warn!(
+                target: "storage",
+                "删除旧数据目录失败：{}：{e}",
+                path.to_string_lossy()
+            );
+            report.leftovers.push(path);
+        }
+    }
+    Ok(report)
+}
+
+/// 复制指定子目录并逐文件比对 SHA-256。
+fn copy_and_verify(from: &Path, to: &Path, entries: &[&str]) -> AppResult<RelocateReport> {
+    let mut files = Vec::new();
+    for entry in entries {
+        copy_tree(&from.join(entry), &to.join(entry), &mut files)?;
+    }
+    let mut bytes = 0;
+    for (source, target) in &files {
+        let expected = file_digest(source)?;
+        if file_digest(target)? != expected {
+            return Err(AppError::Invariant(format!(
+                "迁移校验失败：{}",
+                target.to_string_lossy()
+            )));
+        }
+        bytes += std::fs::metadata(target)
+            .map_err(|e| AppError::Invariant(format!("读取文件信息失败：{e}")))?
+            .len();
+    }
+    Ok(RelocateReport {
+        files: files.len(),
+        bytes,
+        leftovers: Vec::new(),
+    })
+}
+
+/// 递归复制目录，记录每个文件的（源，目标）路径。
+fn copy_tree(from: &Path, to: &Path, files: &mut Vec<(PathBuf, PathBuf)>) -> AppResult<()> {
+    std::fs::create_dir_all(to)
+        .map_err(|e| AppError::Invariant(format!("创建目录失败：{}：{e}", to.to_string_lossy())))?;
+    let entries = std::fs::read_dir(from).map_err(|e| {
+        AppError::Invariant(format!("读取目录失败：{}：{e}", from.to_string_lossy()))
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::Invariant(format!("读取目录失败：{e}")))?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_tree(&source, &target, files)?;
+        } else {
+            std::fs::copy(&source, &target).map_err(|e| {
+                AppError::Invariant(format!("复制文件失败：{}：{e}", source.to_string_lossy()))
+            })?;
+            files.push((source, target));
+        }
+    }
+    Ok(())
+}
+
+/// 计算文件内容的 SHA-256。
+fn file_digest(path: &Path) -> AppResult<Vec<u8>> {
+    let bytes = std::fs::read(path).map_err(|e| {
+        AppError::Invariant(format!("读取文件失败：{}：{e}", path.to_string_lossy()))
+    })?;
+    Ok(Sha256::digest(&bytes).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在根目录下写入一套示例数据（store、音频、日志与备份）。
+    fn seed_root(root: &Path) {
+        for (rel, content) in [
+            ("data/store.json", "{}"),
+            ("data/profiles/p1/store.json", "{\"p\":1}"),
+            ("audio/rain.mp3", "audio"),
+            ("logs/pomodoro-2025-01-02.log", "log"),
+            ("backups/a.json", "backup"),
+        ] {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    /// `resolve`：便携标记优先于指针文件；无效指针回退默认目录。
+    #[test]
+    fn resolve_prefers_portable_then_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_root = dir.path().join("default");
+        let exe_dir = dir.path().join("bin");
+        std::fs::create_dir_all(&default_root).unwrap();
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        let locator = RootLocator::new(default_root.clone(), Some(exe_dir.clone()));
+        assert_eq!(
+            locator.resolve(),
+            (DataRootMode::Default, default_root.clone())
+        );
+
+        let custom = dir.path().join("sync");
+        locator.activate(DataRootMode::Custom, &custom).unwrap();
+        assert_eq!(locator.resolve(), (DataRootMode::Custom, custom));
+
+        locator
+            .activate(DataRootMode::Portable, &exe_dir.join(PORTABLE_DIR_NAME))
+            .unwrap();
+        assert_eq!(
+            locator.resolve(),
+            (DataRootMode::Portable, exe_dir.join(PORTABLE_DIR_NAME))
+        );
+        assert!(!default_root.join(LOCATION_FILE_NAME).exists());
+
+        locator
+            .activate(DataRootMode::Default, &default_root)
+            .unwrap();
+        assert_eq!(locator.resolve().0, DataRootMode::Default);
+        std::fs::write(default_root.join(LOCATION_FILE_NAME), r#"{"root":"rel"}"#).unwrap();
+        assert_eq!(locator.resolve(), (DataRootMode::Default, default_root));
+    }
+
+    /// `check_target`：拒绝相同、嵌套与已有数据的目标目录。
+    #[test]
+    fn check_target_rejects_overlapping_or_occupied_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        seed_root(&from);
+        for to in [from.clone(), from.join("sub"), dir.path().to_path_buf()] {
+            assert!(matches!(
+                check_target(&from, &to),
+                Err(AppError::Validation(_))
+            ));
+        }
+        let occupied = dir.path().join("b");
+        std::fs::create_dir_all(occupied.join("data")).unwrap();
+        assert!(check_target(&from, &occupied).is_err());
+        assert!(check_target(&from, &dir.path().join("c")).is_ok());
+    }
+
+    /// `relocate`：复制校验后切换并删除旧目录；切换失败时回滚已复制内容且保留原数据。
+    #[test]
+    fn relocate_moves_data_and_rolls_back_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        let to = dir.path().join("b");
+        seed_root(&from);
+
+        let locator = RootLocator::new(dir.path().join("default"), None);
+        let err = relocate(&from, &to, &locator, || {
+            Err(AppError::Invariant("写入指针失败".to_string()))
+        })
+        .unwrap_err();
+        assert!(matches!(err, AppError::Invariant(_)));
+        assert!(ROOT_ENTRIES.iter().all(|e| !to.join(e).exists()));
+        assert!(from.join("data/profiles/p1/store.json").is_file());
+
+        let mut activated = false;
+        let report = relocate(&from, &to, &locator, || {
+            activated = true;
+            Ok(())
+        })
+        .unwrap();
+        assert!(activated);
+        assert_eq!(report.files, 5);
+        assert_eq!(report.bytes, 2 + 7 + 5 + 3 + 6);
+        assert!(report.leftovers.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(to.join("data/profiles/p1/store.json")).unwrap(),
+            "{\"p\":1}"
+        );
+        assert!(ROOT_ENTRIES.iter().all(|e| !from.join(e).exists()));
+    }
+
+    /// `relocate`：切换时已改写指针文件后失败，应恢复原指针与便携标记，数据仍按原目录解析。
+    #[test]
+    fn relocate_restores_pointers_when_activation_fails_midway() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_root = dir.path().join("default");
+        let exe_dir = dir.path().join("bin");
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        let locator = RootLocator::new(default_root.clone(), Some(exe_dir.clone()));
+        let from = dir.path().join("usb");
+        locator.activate(DataRootMode::Custom, &from).unwrap();
+        seed_root(&from);
+        let location = std::fs::read(default_root.join(LOCATION_FILE_NAME)).unwrap();
+
+        let to = exe_dir.join(PORTABLE_DIR_NAME);
+        let err = relocate(&from, &to, &locator, || {
+            locator.activate(DataRootMode::Portable, &to)?;
+            Err(AppError::Invariant("写入便携模式标记失败".to_string()))
+        })
+        .unwrap_err();
+        assert!(matches!(err, AppError::Invariant(_)));
+        assert_eq!(
+            std::fs::read(default_root.join(LOCATION_FILE_NAME)).unwrap(),
+            location
+        );
+        assert!(!exe_dir.join(PORTABLE_MARKER_FILE_NAME).exists());
+        assert_eq!(locator.resolve(), (DataRootMode::Custom, from.clone()));
+        assert!(ROOT_ENTRIES.iter().all(|e| !to.join(e).exists()));
+        assert!(from.join("data/store.json").is_file());
+    }
+
+    /// `apply_pending`：执行登记的迁移一次并切换到新目录；无请求时不做任何事。
+    #[test]
+    fn apply_pending_runs_once_and_switches_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_root = dir.path().join("default");
+        let locator = RootLocator::new(default_root.clone(), None);
+        seed_root(&default_root);
+        assert!(apply_pending(&locator).is_none());
+
+        let to = dir.path().join("usb");
+        save_pending(&PendingRelocation {
+            mode: DataRootMode::Custom,
+            from: default_root.clone(),
+            to: to.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            locator.info().pending_target,
+            Some(to.to_string_lossy().to_string())
+        );
+
+        let report = apply_pending(&locator).unwrap().unwrap();
+        assert_eq!(report.files, 5);
+        assert_eq!(locator.resolve(), (DataRootMode::Custom, to.clone()));
+        assert!(to.join("audio/rain.mp3").is_file());
+        assert!(load_pending(&default_root).is_none());
+        assert!(apply_pending(&locator).is_none());
+    }
+}
@@ -13,6 +13,10 @@ pub enum AppError {
     #[error("Store 失败：{0}")]
     Store(#[from] tauri_plugin_store::Error),
 
+    /// 历史数据库（SQLite）失败。
+    #[error("数据库失败：{0}")]
+    Database(#[from] rusqlite::Error),
+
     /// Tauri 框架错误。
     #[error("Tauri 运行失败：{0}")]
     Tauri(#[from] tauri::Error),
@@ -29,6 +33,23 @@ pub enum AppError {
     #[error("专注期内禁止移除黑名单进程")]
     BlacklistLocked,
 
+    /// 数据由更新版本的应用写入，当前版本拒绝打开（避免保存时丢弃未知字段）。
+    #[error("数据版本过新（v{found}），当前应用仅支持到 v{supported}，请升级应用后再打开")]
+    SchemaTooNew {
+        /// 数据中的版本号。
+        found: u32,
+        /// 当前应用支持的最高版本号。
+        supported: u32,
+    },
+
+    /// 加密数据的口令错误（或数据被篡改，MAC 校验失败）。
+    #[error("密码错误，无法解密数据")]
+    WrongPassphrase,
+
+    /// 数据已加密但尚未提供口令。
+    #[error("数据已加密，请先输入密码解锁")]
+    Locked,
+
     /// 平台不支持。
     #[error("当前平台不支持：{0}")]
     #[cfg_attr(windows, allow(dead_code))]
@@ -69,6 +90,14 @@ mod tests {
         assert!(app_err.to_string().contains("boom"));
     }
 
+    /// `AppError::Database`：Display 应包含“数据库失败”前缀。
+    #[test]
+    fn app_error_display_database() {
+        let err = rusqlite::Error::InvalidQuery;
+        let app_err = AppError::from(err);
+        assert!(app_err.to_string().contains("数据库失败："));
+    }
+
     /// `AppError::Tauri`：Display 应包含“Tauri 运行失败”前缀。
     #[test]
     fn app_error_display_tauri() {
@@ -97,6 +126,17 @@ mod tests {
         assert_eq!(app_err.to_string(), "参数不合法：bad");
     }
 
+    /// `AppError::SchemaTooNew`：Display 应包含数据版本与支持的版本。
+    #[test]
+    fn app_error_display_schema_too_new() {
+        let app_err = AppError::SchemaTooNew {
+            found: 9,
+            supported: 6,
+        };
+        assert!(app_err.to_string().contains("v9"));
+        assert!(app_err.to_string().contains("v6"));
+    }
+
     /// `AppError::BlacklistLocked`：Display 应为固定文案。
     #[test]
     fn app_error_display_blacklist_locked() {
@@ -104,6 +144,16 @@ mod tests {
         assert_eq!(app_err.to_string(), "专注期内禁止移除黑名单进程");
     }
 
+    /// `AppError::WrongPassphrase`/`Locked`：Display 应为固定文案。
+    #[test]
+    fn app_error_display_encryption() {
+        assert_eq!(
+            AppError::WrongPassphrase.to_string(),
+            "密码错误，无法解密数据"
+        );
+        assert_eq!(AppError::Locked.to_string(), "数据已加密，请先输入密码解锁");
+    }
+
     /// `AppError::UnsupportedPlatform`：Display 应包含“不支持”与原因。
     #[test]
     fn app_error_display_unsupported_platform() {
@@ -0,0 +1,1228 @@
+//! 历史数据库：历史与中断记录保存在内嵌 SQLite 中（按日期/标签建索引），store 只保存其余 `AppData`。
+//!
+//! 启动时 `load_into` 把全部记录载入内存中的 `AppData`（编辑、同步、导出与撤销直接修改它），修改记录时在
+//! `AppData::pending` 登记日期，每次持久化只重写登记过的日期。历史查询与分析按日期范围读取数据库；
+//! 计时器统计只做聚合查询（`WorkCountQuery`，按日期、阶段与开始小时列计数；加密时只解密没有标签 id 的记录）。
+//! 启用加密后记录 JSON 逐行加密，标签路径列留空，标签/任务 id 列改存带密钥哈希（见 `Cipher::index_hash`），索引仍然可用。
+//! 开始审计后，每次同步还会把命令登记的领域事件与记录在同一事务内追加到 `events` 表，
+//! 整体替换数据时改为把完整快照写入 `snapshots` 表并追加引用它的基线（只保留最近几个基线，见 `audit`）；
+//! 撤销/重做记录保存在 `undo_entries` 表（见 `undo`），随配置切换且重启后保留。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension as _};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::app_data::{
+    AppData, DayBoundary, HistoryDay, HistoryRecord, InterruptionDay, PendingChanges, Phase,
+    RecordSet,
+};
+use crate::audit::{AuditEntry, DomainEvent, BASELINE_LIMIT};
+use crate::crypto::Cipher;
+use crate::errors::{AppError, AppResult};
+use crate::timer::stats::{WorkCount, WorkCountQuery};
+use crate::undo::{UndoEntry, UndoStack, UndoState, UNDO_LIMIT};
+
+/// 记录来源：正式历史。
+const SOURCE_HISTORY: &str = "history";
+
+/// 记录来源：开发环境测试历史（`history_dev`）。
+const SOURCE_HISTORY_DEV: &str = "history_dev";
+
+/// 查询全部日期时使用的下界/上界（日期均为 `YYYY-MM-DD`）。
+pub(crate) const DATE_MIN: &str = "0000-00-00";
+pub(crate) const DATE_MAX: &str = "9999-99-99";
+
+/// 表结构：记录整体以 JSON 保存（兼容后续字段演进），常用筛选字段单独成列并建索引。
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history_records (
+    source TEXT NOT NULL,
+    date TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    tag_id TEXT,
+    task_id TEXT,
+    phase TEXT NOT NULL,
+    hour INTEGER,
+    record TEXT NOT NULL,
+    PRIMARY KEY (source, date, seq)
+);
+CREATE INDEX IF NOT EXISTS idx_history_records_date ON history_records (date);
+CREATE INDEX IF NOT EXISTS idx_history_records_tag ON history_records (source, tag_id, date);
+CREATE TABLE IF NOT EXISTS interruption_records (
+    date TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    tag_id TEXT,
+    kind TEXT NOT NULL,
+    record TEXT NOT NULL,
+    PRIMARY KEY (date, seq)
+);
+CREATE INDEX IF NOT EXISTS idx_interruption_records_tag ON interruption_records (tag_id, date);
+CREATE TABLE IF NOT EXISTS events (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    at TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    event TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_events_subject ON events (subject);
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    at TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS undo_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stack TEXT NOT NULL,
+    entry TEXT NOT NULL
+);
+";
+
+/// 一天内待写入的记录行：`(索引列, 记录 JSON)`。
+type DayRows = Vec<(RowColumns, String)>;
+
+/// 记录行中单独成列的筛选字段。
+struct RowColumns {
+    /// 标签（完整路径）。
+    tag: String,
+    /// 标签 id。
+    tag_id: Option<String>,
+    /// 任务 id（仅历史记录）。
+    task_id: Option<String>,
+    /// 阶段（历史记录）或中断类型（中断记录）。
+    kind: String,
+    /// 开始时刻所在的小时（仅历史记录；统计按日界线归属逻辑日期时使用）。
+    hour: Option<u32>,
+}
+
+impl RowColumns {
+    /// 加密时写入的索引列：标签路径留空，标签/任务 id 改存带密钥哈希，阶段/类型保持明文。
+    fn redacted(&self, cipher: &Cipher) -> Self {
+        Self {
+            tag: String::new(),
+            tag_id: self.tag_id.as_deref().map(|id| cipher.index_hash(id)),
+            task_id: self.task_id.as_deref().map(|id| cipher.index_hash(id)),
+            kind: self.kind.clone(),
+            hour: self.hour,
+        }
+    }
+}
+
+/// 历史数据库句柄（由 `AppState` 持有，持久化时与 store 一起写入）。
+pub struct HistoryDb {
+    /// SQLite 连接。
+    conn: Connection,
+    /// 当前配置的加密密钥（未启用加密时为 `None`）。
+    cipher: Option<Cipher>,
+    /// 是否已开始审计（未开始时同步不记录事件）。
+    auditing: bool,
+}
+
+impl HistoryDb {
+    /// 打开（必要时创建）数据库文件并初始化表结构。
+    pub fn open(path: &Path) -> AppResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::Invariant(format!("创建数据目录失败：{e}")))?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        Self::init(conn)
+    }
+
+    /// 打开内存数据库（测试用）。
+    #[cfg(test)]
+    pub fn open_in_memory() -> AppResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// 初始化表结构。
+    fn init(conn: Connection) -> AppResult<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn,
+            cipher: None,
+            auditing: false,
+        })
+    }
+
+    /// 设置加密密钥（之后写入的记录按新密钥加密；已有记录需调用 `replace_all` 重写）。
+    pub fn set_cipher(&mut self, cipher: Option<Cipher>) {
+        self.cipher = cipher;
+    }
+
+    /// 当前加密密钥（store 与备份也使用同一密钥）。
+    pub fn cipher(&self) -> Option<&Cipher> {
+        self.cipher.as_ref()
+    }
+
+    /// 从数据库加载全部记录到 `data`（按日期升序）。
+    pub fn load_into(&mut self, data: &mut AppData) -> AppResult<()> {
+        data.history = self.query_history(SOURCE_HISTORY, DATE_MIN, DATE_MAX)?;
+        data.history_dev = self.query_history(SOURCE_HISTORY_DEV, DATE_MIN, DATE_MAX)?;
+        data.interruptions = self.interruptions_in_range(DATE_MIN, DATE_MAX)?;
+        tracing::info!(
+            target: "storage",
+            "已从历史数据库加载：historyDays={} interruptionDays={}",
+            data.history.len(),
+            data.interruptions.len()
+        );
+        Ok(())
+    }
+
+    /// 在一个事务内以 `data` 覆盖数据库中的全部记录（用于旧版 store 迁移与更换密钥）；返回写入的天数。
+    pub fn replace_all(&mut self, data: &AppData) -> AppResult<usize> {
+        let tx = self.conn.transaction()?;
+        let written = replace_records(&tx, self.cipher.as_ref(), data)?;
+        tx.commit()?;
+        Ok(written)
+    }
+
+    /// 增量同步：取出 `data` 中登记的变更，只重写这些日期（登记了整体替换时全量重写）；返回重写的天数。
+    ///
+    /// 写入失败时登记保留在 `data` 中，下次持久化重试。已开始审计时，登记的事件与记录在同一事务内追加。
+    pub fn sync(&mut self, data: &mut AppData) -> AppResult<usize> {
+        let pending = std::mem::take(&mut data.pending);
+        let written = match self.write_pending(&pending, data) {
+            Ok(written) => written,
+            Err(e) => {
+                data.pending = pending;
+                return Err(e);
+            }
+        };
+        if written > 0 {
+            tracing::debug!(target: "storage", "历史数据库已同步：days={}", written);
+        }
+        Ok(written)
+    }
+
+    /// 在一个事务内写入登记的记录变更与审计事件（整体替换时改为追加新的基线）。
+    fn write_pending(&mut self, pending: &PendingChanges, data: &AppData) -> AppResult<usize> {
+        let cipher = self.cipher.as_ref();
+        let tx = self.conn.transaction()?;
+        let written = if pending.all_records {
+            replace_records(&tx, cipher, data)?
+        } else {
+            write_days(&tx, cipher, data, &pending.days)?
+        };
+        if self.auditing {
+            if pending.all_records {
+                append_baseline(&tx, cipher, data)?;
+            } else {
+                append_events(&tx, cipher, &crate::audit::pending_events(pending, data)?)?;
+            }
+        }
+        tx.commit()?;
+        Ok(written)
+    }
+
+    /// 开始审计：之后每次同步追加登记的领域事件（恢复备份、迁移等整体替换数据时追加新的基线）。
+    pub fn start_audit(&mut self) {
+        self.auditing = true;
+    }
+
+    /// 审计日志为空时以 `data` 写入首个基线（打开配置后调用，保证日志总有重放起点）。
+    pub fn ensure_baseline(&mut self, data: &AppData) -> AppResult<()> {
+        let logged: bool =
+            self.conn
+                .query_row("SELECT EXISTS (SELECT 1 FROM events)", [], |row| row.get(0))?;
+        if logged {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        let snapshot = append_baseline(&tx, self.cipher.as_ref(), data)?;
+        tx.commit()?;
+        tracing::info!(target: "storage", "审计日志已写入首个基线：snapshot={}", snapshot);
+        Ok(())
+    }
+
+    /// 读取基线引用的数据快照。
+    pub fn snapshot(&self, id: i64) -> AppResult<AppData> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT data FROM snapshots WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let json =
+            json.ok_or_else(|| AppError::Invariant(format!("审计日志引用的快照不存在：{id}")))?;
+        self.decode(&json)
+    }
+
+    /// 查询某个主体的变更历史（按序号升序）。
+    pub fn change_history(&self, subject: &str) -> AppResult<Vec<AuditEntry>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT seq, at, event FROM events WHERE subject = ?1 ORDER BY seq")?;
+        let rows = stmt
+            .query_map(params![subject], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<(i64, String, String)>, _>>()?;
+        rows.into_iter()
+            .map(|(seq, at, json)| {
+                let event: DomainEvent = self.decode(&json)?;
+                Ok(AuditEntry {
+                    seq: seq as u32,
+                    at,
+                    kind: event.kind().to_string(),
+                    subject: event.subject(),
+                    summary: event.summary(),
+                })
+            })
+            .collect()
+    }
+
+    /// 读取全部事件（按序号升序）。
+    pub fn events(&self) -> AppResult<Vec<DomainEvent>> {
+        let rows: Vec<String> = self
+            .conn
+            .prepare_cached("SELECT event FROM events ORDER BY seq")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        rows.iter().map(|json| self.decode(json)).collect()
+    }
+
+    /// 以当前密钥重写事件、快照与撤销记录（`old` 为切换前的密钥，用于读取已有行）；返回重写的行数。
+    pub fn reseal_logs(&mut self, old: Option<&Cipher>) -> AppResult<usize> {
+        let mut rewritten = 0usize;
+        for (table, key, column) in [
+            ("events", "seq", "event"),
+            ("snapshots", "id", "data"),
+            ("undo_entries", "id", "entry"),
+        ] {
+            let rows: Vec<(i64, String)> = self
+                .conn
+                .prepare(&format!("SELECT {key}, {column} FROM {table}"))?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            let tx = self.conn.transaction()?;
+            for (id, json) in &rows {
+                let plain = crate::crypto::unseal_json(old, serde_json::from_str(json)?)?;
+                let sealed = seal_text(self.cipher.as_ref(), serde_json::to_string(&plain)?)?;
+                tx.execute(
+                    &format!("UPDATE {table} SET {column} = ?1 WHERE {key} = ?2"),
+                    params![sealed, id],
+                )?;
+            }
+            tx.commit()?;
+            rewritten += rows.len();
+        }
+        Ok(rewritten)
+    }
+
+    /// 压入一条撤销记录（超出 `UNDO_LIMIT` 的最旧记录被丢弃）。
+    pub fn push_undo(&mut self, stack: UndoStack, entry: &UndoEntry) -> AppResult<()> {
+        let json = seal_text(self.cipher.as_ref(), serde_json::to_string(entry)?)?;
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO undo_entries (stack, entry) VALUES (?1, ?2)",
+            params![stack.as_str(), json],
+        )?;
+        tx.execute(
+            "DELETE FROM undo_entries WHERE stack = ?1 AND id NOT IN \
+             (SELECT id FROM undo_entries WHERE stack = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![stack.as_str(), UNDO_LIMIT as i64],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 取出栈顶的撤销记录（栈为空时返回 `None`）。
+    pub fn pop_undo(&mut self, stack: UndoStack) -> AppResult<Option<UndoEntry>> {
+        let Some((id, entry)) = self.peek_undo_row(stack)? else {
+            return Ok(None);
+        };
+        self.conn
+            .execute("DELETE FROM undo_entries WHERE id = ?1", params![id])?;
+        Ok(Some(entry))
+    }
+
+    /// 清空某个栈（新的可撤销操作发生后清空重做栈）。
+    pub fn clear_undo(&mut self, stack: UndoStack) -> AppResult<()> {
+        self.conn.execute(
+            "DELETE FROM undo_entries WHERE stack = ?1",
+            params![stack.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// 待撤销与待重做操作的描述。
+    pub fn undo_state(&self) -> AppResult<UndoState> {
+        Ok(UndoState {
+            undo: self
+                .peek_undo_row(UndoStack::Undo)?
+                .map(|(_, e)| e.description),
+            redo: self
+                .peek_undo_row(UndoStack::Redo)?
+                .map(|(_, e)| e.description),
+        })
+    }
+
+    /// 读取栈顶记录（不移除）。
+    fn peek_undo_row(&self, stack: UndoStack) -> AppResult<Option<(i64, UndoEntry)>> {
+        let row: Option<(i64, String)> = self
+            .conn
+            .query_row(
+                "SELECT id, entry FROM undo_entries WHERE stack = ?1 ORDER BY id DESC LIMIT 1",
+                params![stack.as_str()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        row.map(|(id, json)| Ok((id, self.decode(&json)?)))
+            .transpose()
+    }
+
+    /// 加密时没有标签 id 的工作记录（自然日期闭区间）：标签路径列为空，逐条解密取出记录中的路径。
+    fn untagged_work_counts(
+        &self,
+        from: &str,
+        to: &str,
+        rollover_hour: u32,
+    ) -> AppResult<Vec<WorkCount>> {
+        let rows = query_rows(
+            &self.conn,
+            "SELECT date, record FROM history_records \
+             WHERE source = ?1 AND phase = ?2 AND date >= ?3 AND date <= ?4 AND tag_id IS NULL",
+            params![SOURCE_HISTORY, enum_name(&Phase::Work)?, from, to],
+        )?;
+        let boundary = DayBoundary {
+            rollover_hour,
+            ..DayBoundary::default()
+        };
+        rows.into_iter()
+            .map(|(date, json)| {
+                let record: HistoryRecord = self.decode(&json)?;
+                Ok(WorkCount {
+                    date,
+                    before_rollover: boundary.before_rollover(record.start_hour()),
+                    tag_id: None,
+                    tag: record.tag,
+                    count: 1,
+                })
+            })
+            .collect()
+    }
+
+    /// 解码一行 JSON（加密行先解密）。
+    fn decode<T: DeserializeOwned>(&self, json: &str) -> AppResult<T> {
+        let value = crate::crypto::unseal_json(self.cipher(), serde_json::from_str(json)?)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// 按自然日期闭区间查询历史（`dev` 为 true 时查询开发环境测试历史）。
+    pub fn history_in_range(&self, dev: bool, from: &str, to: &str) -> AppResult<Vec<HistoryDay>> {
+        let source = if dev {
+            SOURCE_HISTORY_DEV
+        } else {
+            SOURCE_HISTORY
+        };
+        self.query_history(source, from, to)
+    }
+
+    /// 按自然日期闭区间查询中断记录。
+    pub fn interruptions_in_range(&self, from: &str, to: &str) -> AppResult<Vec<InterruptionDay>> {
+        let rows = query_rows(
+            &self.conn,
+            "SELECT date, record FROM interruption_records \
+             WHERE date >= ?1 AND date <= ?2 ORDER BY date, seq",
+            params![from, to],
+        )?;
+        Ok(group_rows(rows, self.cipher())?
+            .into_iter()
+            .map(|(date, records)| InterruptionDay { date, records })
+            .collect())
+    }
+
+    /// 查询某个来源在自然日期闭区间内的历史。
+    fn query_history(&self, source: &str, from: &str, to: &str) -> AppResult<Vec<HistoryDay>> {
+        let rows = query_rows(
+            &self.conn,
+            "SELECT date, record FROM history_records \
+             WHERE source = ?1 AND date >= ?2 AND date <= ?3 ORDER BY date, seq",
+            params![source, from, to],
+        )?;
+        Ok(group_rows(rows, self.cipher())?
+            .into_iter()
+            .map(|(date, records)| HistoryDay { date, records })
+            .collect())
+    }
+}
+
+impl WorkCountQuery for HistoryDb {
+    /// 按日期范围聚合正式历史中的工作记录；加密时由 `paths` 中的标签 id 反查哈希后的标签 id 列，
+    /// 没有标签 id 的记录（路径列为空）逐条解密。
+    fn work_counts(
+        &self,
+        from: &str,
+        to: &str,
+        rollover_hour: u32,
+        paths: &BTreeMap<String, String>,
+    ) -> AppResult<Vec<WorkCount>> {
+        let hashed: HashMap<String, &String> = match self.cipher() {
+            Some(cipher) => paths.keys().map(|id| (cipher.index_hash(id), id)).collect(),
+            None => HashMap::new(),
+        };
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT date, COALESCE(hour, 24) < ?5, tag_id, tag, COUNT(*) FROM history_records \
+             WHERE source = ?1 AND phase = ?2 AND date >= ?3 AND date <= ?4 \
+             GROUP BY 1, 2, 3, 4",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![
+                    SOURCE_HISTORY,
+                    enum_name(&Phase::Work)?,
+                    from,
+                    to,
+                    rollover_hour
+                ],
+                |row| {
+                    Ok(WorkCount {
+                        date: row.get(0)?,
+                        before_rollover: row.get(1)?,
+                        tag_id: row.get(2)?,
+                        tag: row.get(3)?,
+                        count: row.get(4)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<WorkCount>, _>>()?;
+        if self.cipher.is_none() {
+            return Ok(rows);
+        }
+        let mut out = self.untagged_work_counts(from, to, rollover_hour)?;
+        out.extend(rows.into_iter().filter_map(|row| {
+            let hash = row.tag_id.as_ref()?;
+            Some(WorkCount {
+                tag_id: hashed.get(hash).map(|id| id.to_string()),
+                ..row
+            })
+        }));
+        Ok(out)
+    }
+
+    /// 按自然日期聚合正式历史中的工作记录数（不分标签）。
+    fn daily_work_counts(&self, rollover_hour: u32) -> AppResult<Vec<WorkCount>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT date, COALESCE(hour, 24) < ?3, COUNT(*) FROM history_records \
+             WHERE source = ?1 AND phase = ?2 GROUP BY 1, 2",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![SOURCE_HISTORY, enum_name(&Phase::Work)?, rollover_hour],
+                |row| {
+                    Ok(WorkCount {
+                        date: row.get(0)?,
+                        before_rollover: row.get(1)?,
+                        tag_id: None,
+                        tag: String::new(),
+                        count: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<WorkCount>, _>>()?;
+        Ok(rows)
+    }
+}
+
+/// 生成写入 store 的 `AppData` JSON：历史与中断记录由数据库保存，不再写入 store。
+pub fn store_value(data: &mut AppData) -> AppResult<serde_json::Value> {
+    let history = std::mem::take(&mut data.history);
+    let history_dev = std::mem::take(&mut data.history_dev);
+    let interruptions = std::mem::take(&mut data.interruptions);
+    let value = serde_json::to_value(&*data);
+    data.history = history;
+    data.history_dev = history_dev;
+    data.interruptions = interruptions;
+    Ok(value?)
+}
+
+/// 判断 `AppData` 是否仍携带记录（旧版 store 直接保存记录；用于启动时判断是否需要迁移）。
+pub fn has_records(data: &AppData) -> bool {
+    !data.history.is_empty() || !data.history_dev.is_empty() || !data.interruptions.is_empty()
+}
+
+/// 以 `data` 覆盖全部记录行；返回写入的天数。
+fn replace_records(conn: &Connection, cipher: Option<&Cipher>, data: &AppData) -> AppResult<usize> {
+    conn.execute_batch("DELETE FROM history_records; DELETE FROM interruption_records;")?;
+    let days: BTreeSet<(RecordSet, &str)> = data
+        .history
+        .iter()
+        .map(|d| (RecordSet::History, d.date.as_str()))
+        .chain(
+            data.history_dev
+                .iter()
+                .map(|d| (RecordSet::HistoryDev, d.date.as_str())),
+        )
+        .chain(
+            data.interruptions
+                .iter()
+                .map(|d| (RecordSet::Interruptions, d.date.as_str())),
+        )
+        .collect();
+    let mut written = 0usize;
+    for (set, date) in days {
+        written += write_day(conn, cipher, set, date, data)?;
+    }
+    Ok(written)
+}
+
+/// 重写指定日期的记录（内存中已无记录的日期删除对应行）；返回重写的天数。
+fn write_days(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    data: &AppData,
+    days: &BTreeSet<(RecordSet, String)>,
+) -> AppResult<usize> {
+    for (set, date) in days {
+        delete_day(conn, *set, date)?;
+        write_day(conn, cipher, *set, date, data)?;
+    }
+    Ok(days.len())
+}
+
+/// 追加事件（同一批事件使用相同的记录时刻）。
+fn append_events(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    events: &[DomainEvent],
+) -> AppResult<()> {
+    let at = chrono::Local::now().to_rfc3339();
+    for event in events {
+        let json = seal_text(cipher, serde_json::to_string(event)?)?;
+        conn.execute(
+            "INSERT INTO events (at, kind, subject, event) VALUES (?1, ?2, ?3, ?4)",
+            params![at, event.kind(), event.subject(), json],
+        )?;
+    }
+    Ok(())
+}
+
+/// 保存 `data` 的完整快照并追加引用它的基线，再删除超出 `BASELINE_LIMIT` 的旧基线；返回快照 id。
+fn append_baseline(conn: &Connection, cipher: Option<&Cipher>, data: &AppData) -> AppResult<i64> {
+    let at = chrono::Local::now().to_rfc3339();
+    let json = seal_text(cipher, serde_json::to_string(data)?)?;
+    conn.execute(
+        "INSERT INTO snapshots (at, data) VALUES (?1, ?2)",
+        params![at, json],
+    )?;
+    let snapshot = conn.last_insert_rowid();
+    append_events(conn, cipher, &[DomainEvent::Baseline { snapshot }])?;
+    prune_baselines(conn)?;
+    Ok(snapshot)
+}
+
+/// 只保留最近 `BASELINE_LIMIT` 个基线：删除最早保留的基线之前的事件，以及不再被引用的快照。
+///
+/// 快照只随基线写入，两者一一对应，因此按 id 保留最近的快照即可（无需解密事件读取引用）。
+fn prune_baselines(conn: &Connection) -> AppResult<()> {
+    let oldest_kept: Option<i64> = conn
+        .query_row(
+            "SELECT seq FROM events WHERE kind = 'baseline' ORDER BY seq DESC LIMIT 1 OFFSET ?1",
+            params![BASELINE_LIMIT as i64 - 1],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(oldest_kept) = oldest_kept else {
+        return Ok(());
+    };
+    conn.execute("DELETE FROM events WHERE seq < ?1", params![oldest_kept])?;
+    conn.execute(
+        "DELETE FROM snapshots WHERE id NOT IN \
+         (SELECT id FROM snapshots ORDER BY id DESC LIMIT ?1)",
+        params![BASELINE_LIMIT as i64],
+    )?;
+    Ok(())
+}
+
+/// 将某个集合某日的内存记录写入数据库（同一日期出现多次时按顺序合并）；当日无记录时返回 0，否则返回 1。
+fn write_day(
+    conn: &Connection,
+    cipher: Option<&Cipher>,
+    set: RecordSet,
+    date: &str,
+    data: &AppData,
+) -> AppResult<usize> {
+    let rows = day_rows(set, date, data)?;
+    for (seq, (columns, json)) in rows.iter().enumerate() {
+        match cipher {
+            Some(cipher) => {
+                let sealed = cipher.seal_json_text(json)?;
+                insert_row(conn, set, date, seq, &columns.redacted(cipher), &sealed)?;
+            }
+            None => insert_row(conn, set, date, seq, columns, json)?,
+        }
+    }
+    Ok(usize::from(!rows.is_empty()))
+}
+
+/// 生成某个集合某日待写入的记录行。
+fn day_rows(set: RecordSet, date: &str, data: &AppData) -> AppResult<DayRows> {
+    let mut rows = DayRows::new();
+    match set {
+        RecordSet::History | RecordSet::HistoryDev => {
+            let days = if set == RecordSet::History {
+                &data.history
+            } else {
+                &data.history_dev
+            };
+            for r in days
+                .iter()
+                .filter(|d| d.date == date)
+                .flat_map(|d| &d.records)
+            {
+                let columns = RowColumns {
+                    tag: r.tag.clone(),
+                    tag_id: r.tag_id.clone(),
+                    task_id: r.task_id.clone(),
+                    kind: enum_name(&r.phase)?,
+                    hour: r.start_hour(),
+                };
+                rows.push((columns, serde_json::to_string(r)?));
+            }
+        }
+        RecordSet::Interruptions => {
+            for r in data
+                .interruptions
+                .iter()
+                .filter(|d| d.date == date)
+                .flat_map(|d| &d.records)
+            {
+                let columns = RowColumns {
+                    tag: r.tag.clone(),
+                    tag_id: r.tag_id.clone(),
+                    task_id: None,
+                    kind: enum_name(&r.r#type)?,
+                    hour: None,
+                };
+                rows.push((columns, serde_json::to_string(r)?));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// 取枚举的序列化名（与前端/JSON 中的取值一致）。
+fn enum_name<T: Serialize>(value: &T) -> AppResult<String> {
+    Ok(serde_json::to_value(value)?
+        .as_str()
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// 历史记录集合在 `history_records.source` 列中的取值（中断记录存于独立表，返回 `None`）。
+fn source_of(set: RecordSet) -> Option<&'static str> {
+    match set {
+        RecordSet::History => Some(SOURCE_HISTORY),
+        RecordSet::HistoryDev => Some(SOURCE_HISTORY_DEV),
+        RecordSet::Interruptions => None,
+    }
+}
+
+/// 删除某个集合某日的全部记录行。
+fn delete_day(conn: &Connection, set: RecordSet, date: &str) -> AppResult<()> {
+    match source_of(set) {
+        Some(source) => conn.execute(
+            "DELETE FROM history_records WHERE source = ?1 AND date = ?2",
+            params![source, date],
+        )?,
+        None => conn.execute(
+            "DELETE FROM interruption_records WHERE date = ?1",
+            params![date],
+        )?,
+    };
+    Ok(())
+}
+
+/// 写入一条记录行。
+fn insert_row(
+    conn: &Connection,
+    set: RecordSet,
+    date: &str,
+    seq: usize,
+    columns: &RowColumns,
+    json: &str,
+) -> AppResult<()> {
+    let seq = seq as i64;
+    match source_of(set) {
+        Some(source) => conn.execute(
+            "INSERT INTO history_records \
+             (source, date, seq, tag, tag_id, task_id, phase, hour, record) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                source,
+                date,
+                seq,
+                columns.tag,
+                columns.tag_id,
+                columns.task_id,
+                columns.kind,
+                columns.hour,
+                json
+            ],
+        )?,
+        None => conn.execute(
+            "INSERT INTO interruption_records (date, seq, tag, tag_id, kind, record) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![date, seq, columns.tag, columns.tag_id, columns.kind, json],
+        )?,
+    };
+    Ok(())
+}
+
+/// 执行查询并返回 `(日期, 记录 JSON)` 行（调用方保证按日期与序号排序）。
+fn query_rows(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> AppResult<Vec<(String, String)>> {
+    let mut stmt = conn.prepare_cached(sql)?;
+    let rows = stmt
+        .query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(String, String)>, _>>()?;
+    Ok(rows)
+}
+
+/// 按密钥加密一段 JSON 文本（未启用加密时原样返回）。
+fn seal_text(cipher: Option<&Cipher>, json: String) -> AppResult<String> {
+    match cipher {
+        Some(cipher) => cipher.seal_json_text(&json),
+        None => Ok(json),
+    }
+}
+
+/// 将有序的 `(日期, 记录 JSON)` 行按日期分组并反序列化（加密行先解密）。
+fn group_rows<T: DeserializeOwned>(
+    rows: Vec<(String, String)>,
+    cipher: Option<&Cipher>,
+) -> AppResult<Vec<(String, Vec<T>)>> {
+    let mut out: Vec<(String, Vec<T>)> = Vec::new();
+    for (date, json) in rows {
+        let value = crate::crypto::unseal_json(cipher, serde_json::from_str(&json)?)?;
+        let record: T = serde_json::from_value(value)?;
+        match out.last_mut() {
+            Some((last, records)) if *last == date => records.push(record),
+            _ => out.push((date, vec![record])),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{
+        HistoryRecord, InterruptionRecord, InterruptionType, PendingChanges, RecordSet, Section,
+    };
+
+    /// 构造一个带两天历史与一条中断记录的数据。
+    fn sample_data() -> AppData {
+        AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2025-01-01".to_string(),
+                    records: vec![
+                        HistoryRecord::for_test("工作", "09:00"),
+                        HistoryRecord::for_test("学习", "10:00"),
+                    ],
+                },
+                HistoryDay {
+                    date: "2025-01-03".to_string(),
+                    records: vec![HistoryRecord::for_test("工作", "11:00")],
+                },
+            ],
+            interruptions: vec![InterruptionDay {
+                date: "2025-01-03".to_string(),
+                records: vec![InterruptionRecord {
+                    timestamp: "2025-01-03T12:00:00+08:00".to_string(),
+                    remaining_seconds: 600,
+                    focused_seconds: 900,
+                    reason: String::new(),
+                    r#type: InterruptionType::Reset,
+                    tag: "工作".to_string(),
+                    tag_id: None,
+                    started_at: None,
+                    ended_at: None,
+                }],
+            }],
+            pending: PendingChanges {
+                all_records: true,
+                ..PendingChanges::default()
+            },
+            ..AppData::default()
+        }
+    }
+
+    /// `sync`/`load_into`：写入后应能按日期顺序完整读回。
+    #[test]
+    fn sync_and_load_round_trip() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        assert_eq!(db.sync(&mut data).unwrap(), 3);
+
+        let mut loaded = AppData::default();
+        db.load_into(&mut loaded).unwrap();
+        assert_eq!(loaded.history.len(), 2);
+        assert_eq!(loaded.history[0].records[1].tag, "学习");
+        assert_eq!(loaded.interruptions.len(), 1);
+        assert_eq!(
+            loaded.interruptions[0].records[0].r#type,
+            InterruptionType::Reset
+        );
+    }
+
+    /// `sync`：只重写登记过的日期，并删除已消失的日期。
+    #[test]
+    fn sync_rewrites_only_changed_days() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        db.sync(&mut data).unwrap();
+        assert_eq!(db.sync(&mut data).unwrap(), 0);
+
+        data.history_day_mut(false, "2025-01-03").records[0].remark = "复盘".to_string();
+        assert_eq!(db.sync(&mut data).unwrap(), 1);
+
+        data.touch_day(RecordSet::History, "2025-01-01");
+        data.history.remove(0);
+        assert_eq!(db.sync(&mut data).unwrap(), 1);
+        let out = db.history_in_range(false, DATE_MIN, DATE_MAX).unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].records[0].remark, "复盘");
+    }
+
+    /// `history_in_range`/`interruptions_in_range`：应按自然日期闭区间过滤。
+    #[test]
+    fn range_queries_filter_by_date() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        db.sync(&mut sample_data()).unwrap();
+
+        let out = db
+            .history_in_range(false, "2025-01-02", "2025-01-03")
+            .unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].date, "2025-01-03");
+        assert!(db
+            .history_in_range(true, DATE_MIN, DATE_MAX)
+            .unwrap()
+            .is_empty());
+        assert!(db
+            .interruptions_in_range("2025-01-01", "2025-01-02")
+            .unwrap()
+            .is_empty());
+    }
+
+    /// `replace_all`：应清除数据库中不在 `data` 内的旧记录。
+    #[test]
+    fn replace_all_overwrites_existing_rows() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        db.sync(&mut sample_data()).unwrap();
+
+        let mut fresh = HistoryDb::init(db.conn).unwrap();
+        let data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-02-01".to_string(),
+                records: vec![HistoryRecord::for_test("阅读", "08:00")],
+            }],
+            ..AppData::default()
+        };
+        fresh.replace_all(&data).unwrap();
+
+        let mut loaded = AppData::default();
+        fresh.load_into(&mut loaded).unwrap();
+        assert_eq!(loaded.history.len(), 1);
+        assert_eq!(loaded.history[0].date, "2025-02-01");
+        assert!(loaded.interruptions.is_empty());
+    }
+
+    /// 加密：记录列与标签列不含明文，需同一密钥才能读回（未设置密钥时报 `Locked`）。
+    #[test]
+    fn encrypted_rows_hide_remarks_and_tags() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        data.history[0].records[0].remark = "客户甲评审".to_string();
+        db.set_cipher(Some(Cipher::generate("correct horse").unwrap()));
+        db.replace_all(&data).unwrap();
+
+        let raw: Vec<(String, String)> = query_rows(
+            &db.conn,
+            "SELECT tag, record FROM history_records ORDER BY date, seq",
+            [],
+        )
+        .unwrap();
+        assert!(raw
+            .iter()
+            .all(|(tag, record)| tag.is_empty() && !record.contains("客户甲")));
+
+        let mut loaded = AppData::default();
+        db.load_into(&mut loaded).unwrap();
+        assert_eq!(loaded.history[0].records[0].remark, "客户甲评审");
+
+        db.set_cipher(None);
+        assert!(matches!(
+            db.history_in_range(false, DATE_MIN, DATE_MAX),
+            Err(AppError::Locked)
+        ));
+    }
+
+    /// 加密：标签 id 列保存带密钥哈希，按哈希可经标签索引查到对应记录。
+    #[test]
+    fn encrypted_rows_keep_tag_index_usable() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        data.history[0].records[1].tag_id = Some("tag-study".to_string());
+        let cipher = Cipher::generate("correct horse").unwrap();
+        let hash = cipher.index_hash("tag-study");
+        db.set_cipher(Some(cipher));
+        db.replace_all(&data).unwrap();
+
+        let plan: String = db
+            .conn
+            .query_row(
+                "EXPLAIN QUERY PLAN SELECT date FROM history_records \
+                 WHERE source = ?1 AND tag_id = ?2",
+                params![SOURCE_HISTORY, hash],
+                |row| row.get(3),
+            )
+            .unwrap();
+        assert!(plan.contains("idx_history_records_tag"));
+        let dates: Vec<String> = db
+            .conn
+            .prepare("SELECT date FROM history_records WHERE source = ?1 AND tag_id = ?2")
+            .unwrap()
+            .query_map(params![SOURCE_HISTORY, hash], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(dates, vec!["2025-01-01".to_string()]);
+    }
+
+    /// 统计聚合查询：加密与日切换下与逐条统计内存的结果一致，未同步的日期以内存为准。
+    #[test]
+    fn work_counts_match_in_memory_stats() {
+        use crate::app_data::Tag;
+        use crate::timer::stats::{compute_streak, compute_today_stats, HistoryCounts};
+
+        let mut data = sample_data();
+        data.tag_defs = vec![Tag {
+            id: "tag-work".to_string(),
+            name: "工作".to_string(),
+            color: String::new(),
+            icon: String::new(),
+            archived: false,
+            parent_id: None,
+        }];
+        for r in &mut data.history[1].records {
+            r.tag_id = Some("tag-work".to_string());
+        }
+        data.history[1]
+            .records
+            .push(HistoryRecord::for_test("夜读", "01:30"));
+        data.settings.day_rollover_hour = 4;
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        db.set_cipher(Some(Cipher::generate("correct horse").unwrap()));
+        db.sync(&mut data).unwrap();
+        data.tag_defs[0].name = "项目".to_string();
+
+        for date in ["2025-01-01", "2025-01-02", "2025-01-03"] {
+            let expected = compute_today_stats(&data, date);
+            let out = compute_today_stats(HistoryCounts::new(&data, Some(&db)), date);
+            assert_eq!((out.total, out.by_tag), (expected.total, expected.by_tag));
+        }
+        let today = compute_today_stats(HistoryCounts::new(&data, Some(&db)), "2025-01-03");
+        assert_eq!(today.by_tag[0].tag, "项目");
+
+        data.history_day_mut(false, "2025-01-03")
+            .records
+            .push(HistoryRecord::for_test("工作", "15:00"));
+        let counts = HistoryCounts::new(&data, Some(&db));
+        assert_eq!(compute_today_stats(counts, "2025-01-03").total, 2);
+        let counts = HistoryCounts::new(&data, Some(&db));
+        assert_eq!(
+            compute_streak(counts, "2025-01-03"),
+            compute_streak(&data, "2025-01-03")
+        );
+    }
+
+    /// 审计：首个基线引用完整快照；同步追加登记的事件（按记录 id 查询），整体替换数据时追加新的基线。
+    #[test]
+    fn audit_log_records_events_and_replays() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        db.sync(&mut data).unwrap();
+        db.start_audit();
+        db.ensure_baseline(&data).unwrap();
+        db.ensure_baseline(&data).unwrap();
+
+        let record_id = data.history[0].records[1].id.clone();
+        data.history_day_mut(false, "2025-01-01").records[1].remark = "复盘".to_string();
+        data.record_event(DomainEvent::RemarkEdited {
+            dev: false,
+            date: "2025-01-01".to_string(),
+            record_id: record_id.clone(),
+            from: String::new(),
+            to: "复盘".to_string(),
+        });
+        let record = HistoryRecord::for_test("工作", "12:00");
+        data.history_day_mut(false, "2025-01-03")
+            .records
+            .push(record.clone());
+        data.record_event(DomainEvent::PomodoroCompleted {
+            date: "2025-01-03".to_string(),
+            record,
+        });
+        data.touch_section(Section::Counters);
+        data.total_pomodoros = 1;
+        db.sync(&mut data).unwrap();
+        db.sync(&mut data).unwrap();
+
+        let entries = db.change_history(&format!("history:{record_id}")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, "remarkEdited");
+        let events = db.events().unwrap();
+        assert_eq!(events.len(), 5);
+        let (count, rebuilt) = crate::audit::replay(&events, |id| db.snapshot(id)).unwrap();
+        assert_eq!(count, 5);
+        assert!(crate::audit::differing_sections(&rebuilt, &data)
+            .unwrap()
+            .is_empty());
+
+        data.replace_with(AppData::default());
+        db.sync(&mut data).unwrap();
+        let events = db.events().unwrap();
+        assert!(matches!(events.last(), Some(DomainEvent::Baseline { .. })));
+        let (count, rebuilt) = crate::audit::replay(&events, |id| db.snapshot(id)).unwrap();
+        assert_eq!(count, 1);
+        assert!(rebuilt.history.is_empty());
+    }
+
+    /// 审计：整体替换数据多次后只保留最近 `BASELINE_LIMIT` 个基线与快照，日志仍从基线开始且可重放。
+    #[test]
+    fn old_baselines_are_pruned() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        db.sync(&mut data).unwrap();
+        db.start_audit();
+        db.ensure_baseline(&data).unwrap();
+        for i in 0..BASELINE_LIMIT + 2 {
+            data.touch_section(Section::Counters);
+            data.total_pomodoros = i as u64;
+            db.sync(&mut data).unwrap();
+            data.touch_all_records();
+            db.sync(&mut data).unwrap();
+        }
+
+        let snapshots: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM snapshots", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(snapshots, BASELINE_LIMIT as i64);
+        let events = db.events().unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, DomainEvent::Baseline { .. }))
+                .count(),
+            BASELINE_LIMIT
+        );
+        let Some(DomainEvent::Baseline { snapshot }) = events.first() else {
+            panic!("日志应从基线开始");
+        };
+        assert!(db.snapshot(*snapshot).is_ok());
+        assert!(db.snapshot(1).is_err());
+        let (_, rebuilt) = crate::audit::replay(&events, |id| db.snapshot(id)).unwrap();
+        assert_eq!(rebuilt.total_pomodoros, (BASELINE_LIMIT + 1) as u64);
+    }
+
+    /// 审计加密：事件与快照不含明文备注，`reseal_logs` 后仍可用新密钥读回。
+    #[test]
+    fn audit_events_are_sealed_and_resealed() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let old = Cipher::generate("correct horse").unwrap();
+        db.set_cipher(Some(old.clone()));
+        let mut data = sample_data();
+        data.history[0].records[1].remark = "客户乙复盘".to_string();
+        db.sync(&mut data).unwrap();
+        db.start_audit();
+        db.ensure_baseline(&data).unwrap();
+        let record_id = data.history[0].records[0].id.clone();
+        data.history_day_mut(false, "2025-01-01").records[0].remark = "客户甲评审".to_string();
+        data.record_event(DomainEvent::RemarkEdited {
+            dev: false,
+            date: "2025-01-01".to_string(),
+            record_id: record_id.clone(),
+            from: String::new(),
+            to: "客户甲评审".to_string(),
+        });
+        db.sync(&mut data).unwrap();
+
+        for (table, column) in [("events", "event"), ("snapshots", "data")] {
+            let raw: Vec<String> = db
+                .conn
+                .prepare(&format!("SELECT {column} FROM {table}"))
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert!(raw.iter().all(|row| !row.contains("客户")));
+        }
+
+        db.set_cipher(None);
+        assert_eq!(db.reseal_logs(Some(&old)).unwrap(), 3);
+        let entries = db.change_history(&format!("history:{record_id}")).unwrap();
+        assert!(entries[0].summary.contains("客户甲评审"));
+        let snapshot = db.snapshot(1).unwrap();
+        assert_eq!(snapshot.history[0].records[1].remark, "客户乙复盘");
+    }
+
+    /// 撤销栈：重新打开后仍可读取，且只保留最近 `UNDO_LIMIT` 条。
+    #[test]
+    fn undo_entries_survive_reopen_and_are_bounded() {
+        let mut db = HistoryDb::open_in_memory().unwrap();
+        let mut data = sample_data();
+        for i in 0..UNDO_LIMIT + 2 {
+            data.begin_undo_capture();
+            let date = data.history[0].date.clone();
+            data.history_day_mut(false, &date).records[0].remark = format!("第{i}次");
+            let capture = data.take_undo_capture();
+            let entry = UndoEntry::from_capture(&format!("编辑{i}"), capture, &data)
+                .unwrap()
+                .unwrap();
+            db.push_undo(UndoStack::Undo, &entry).unwrap();
+        }
+
+        let mut reopened = HistoryDb::init(db.conn).unwrap();
+        let state = reopened.undo_state().unwrap();
+        assert_eq!(
+            state.undo.as_deref(),
+            Some(format!("编辑{}", UNDO_LIMIT + 1).as_str())
+        );
+        assert!(state.redo.is_none());
+
+        let mut popped = 0;
+        while reopened.pop_undo(UndoStack::Undo).unwrap().is_some() {
+            popped += 1;
+        }
+        assert_eq!(popped, UNDO_LIMIT);
+    }
+
+    /// `store_value`：store 中不应包含记录，且内存数据保持不变。
+    #[test]
+    fn store_value_strips_records() {
+        let mut data = sample_data();
+        assert!(has_records(&data));
+
+        let value = store_value(&mut data).unwrap();
+        let stored: AppData = serde_json::from_value(value).unwrap();
+        assert!(!has_records(&stored));
+        assert_eq!(data.history.len(), 2);
+        assert_eq!(data.interruptions.len(), 1);
+    }
+}
@@ -0,0 +1,629 @@
+//! 数据完整性检查与修复：扫描 `AppData` 中的非法时间、时长与起止时间不一致、重复/乱序日期、
+//! 未知标签与失效模板引用等问题，报告其位置，并可按固定规则自动修复。
+//!
+//! 位置以路径形式表示（如 `history/2025-01-01#3`、`interruptions/2025-01-01`、`activeTemplateIds/custom-1`）。
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::app_data::{AppData, HistoryDay, HistoryRecord, InterruptionDay, InterruptionRecord};
+
+/// 起止时间与时长（含暂停）之间允许的误差（秒）：`HH:mm` 截断与时长取整各可能损失不足 1 分钟。
+const SPAN_TOLERANCE_SECONDS: i64 = 120;
+
+/// 完整性问题类型。
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub enum IntegrityIssueKind {
+    /// 日期不是合法的 `YYYY-MM-DD`。
+    InvalidDate,
+    /// 时间不是合法的 `HH:mm`。
+    InvalidTime,
+    /// 时长与起止时间不一致。
+    DurationMismatch,
+    /// 同一日期出现多次。
+    DuplicateDay,
+    /// 日期或当日记录未按时间排序。
+    Unordered,
+    /// 记录引用的标签不存在。
+    UnknownTag,
+    /// 标签列表与标签定义不同步。
+    TagListOutOfSync,
+    /// 启用的黑名单模板已不存在。
+    MissingTemplate,
+}
+
+/// 一个完整性问题。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    /// 问题类型。
+    pub kind: IntegrityIssueKind,
+    /// 问题位置（如 `history/2025-01-01#3`）。
+    pub location: String,
+    /// 问题说明。
+    pub message: String,
+    /// 是否可自动修复。
+    pub repairable: bool,
+}
+
+/// 完整性检查报告。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// 检查（或修复前）发现的问题。
+    pub issues: Vec<IntegrityIssue>,
+    /// 是否已执行修复（不可修复的问题保持原样）。
+    pub repaired: bool,
+}
+
+/// 按日分组的记录集合（历史与中断共用重复/乱序检查与修复）。
+trait Day {
+    /// 当日记录类型。
+    type Record;
+    /// 日期（YYYY-MM-DD）。
+    fn date(&self) -> &str;
+    /// 当日记录。
+    fn records(&self) -> &[Self::Record];
+    /// 当日记录（可变）。
+    fn records_mut(&mut self) -> &mut Vec<Self::Record>;
+    /// 记录排序键。
+    fn order_key(record: &Self::Record) -> &str;
+}
+
+impl Day for HistoryDay {
+    type Record = HistoryRecord;
+
+    /// 日期。
+    fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// 当日记录。
+    fn records(&self) -> &[HistoryRecord] {
+        &self.records
+    }
+
+    /// 当日记录（可变）。
+    fn records_mut(&mut self) -> &mut Vec<HistoryRecord> {
+        &mut self.records
+    }
+
+    /// 按开始时间排序。
+    fn order_key(record: &HistoryRecord) -> &str {
+        &record.start_time
+    }
+}
+
+impl Day for InterruptionDay {
+    type Record = InterruptionRecord;
+
+    /// 日期。
+    fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// 当日记录。
+    fn records(&self) -> &[InterruptionRecord] {
+        &self.records
+    }
+
+    /// 当日记录（可变）。
+    fn records_mut(&mut self) -> &mut Vec<InterruptionRecord> {
+        &mut self.records
+    }
+
+    /// 按中断时刻排序。
+    fn order_key(record: &InterruptionRecord) -> &str {
+        &record.timestamp
+    }
+}
+
+/// 扫描数据并返回全部完整性问题（不修改数据）。
+pub fn check(data: &AppData) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    for (section, list) in [
+        ("history", &data.history),
+        ("historyDev", &data.history_dev),
+    ] {
+        check_days(section, list, &mut issues);
+        for day in list {
+            for (index, record) in day.records.iter().enumerate() {
+                let location = format!("{section}/{}#{index}", day.date);
+                check_record_times(&location, &day.date, record, &mut issues);
+                check_tag(
+                    data,
+                    &location,
+                    &record.tag,
+                    record.tag_id.as_deref(),
+                    &mut issues,
+                );
+            }
+        }
+    }
+
+    check_days("interruptions", &data.interruptions, &mut issues);
+    for day in &data.interruptions {
+        for (index, record) in day.records.iter().enumerate() {
+            let location = format!("interruptions/{}#{index}", day.date);
+            check_tag(
+                data,
+                &location,
+                &record.tag,
+                record.tag_id.as_deref(),
+                &mut issues,
+            );
+        }
+    }
+
+    if data.tags != expected_tags(data) {
+        issues.push(issue(
+            IntegrityIssueKind::TagListOutOfSync,
+            "tags".to_string(),
+            "标签列表与标签定义不一致".to_string(),
+        ));
+    }
+
+    for id in &data.active_template_ids {
+        if !template_exists(data, id) {
+            issues.push(issue(
+                IntegrityIssueKind::MissingTemplate,
+                format!("activeTemplateIds/{id}"),
+                format!("启用的黑名单模板「{id}」不存在"),
+            ));
+        }
+    }
+    if let Some(id) = data
+        .active_template_id
+        .as_deref()
+        .filter(|id| !template_exists(data, id))
+    {
+        issues.push(issue(
+            IntegrityIssueKind::MissingTemplate,
+            "activeTemplateId".to_string(),
+            format!("启用的黑名单模板「{id}」不存在"),
+        ));
+    }
+    issues
+}
+
+/// 修复可自动修复的问题，返回修复前发现的全部问题。
+///
+/// 修复规则：非法开始时间由 RFC 3339 开始时刻回填；结束时间与时长不一致时，实际计时与起止时间吻合则以其修正时长，
+/// 否则按“开始 + 时长 + 暂停”重算结束时间；重复日期合并、日期与记录重新排序；未知标签按路径补建，
+/// 失效的标签 id 解除引用；失效模板从启用列表移除（不改变当前有效黑名单）。非法日期仅报告。
+pub fn repair(data: &mut AppData) -> Vec<IntegrityIssue> {
+    let issues = check(data);
+    if !issues.iter().any(|i| i.repairable) {
+        return issues;
+    }
+
+    data.touch_all_records();
+    for list in [&mut data.history, &mut data.history_dev] {
+        for day in list.iter_mut() {
+            for record in &mut day.records {
+                repair_record_times(record);
+            }
+        }
+        repair_days(list);
+    }
+    repair_days(&mut data.interruptions);
+
+    let mut history = std::mem::take(&mut data.history);
+    let mut history_dev = std::mem::take(&mut data.history_dev);
+    for record in history
+        .iter_mut()
+        .chain(history_dev.iter_mut())
+        .flat_map(|d| d.records.iter_mut())
+    {
+        repair_tag(data, &record.tag, &mut record.tag_id);
+    }
+    data.history = history;
+    data.history_dev = history_dev;
+    let mut interruptions = std::mem::take(&mut data.interruptions);
+    for record in interruptions.iter_mut().flat_map(|d| d.records.iter_mut()) {
+        repair_tag(data, &record.tag, &mut record.tag_id);
+    }
+    data.interruptions = interruptions;
+    data.sync_tags();
+
+    let templates: BTreeSet<String> = data
+        .blacklist_templates
+        .iter()
+        .map(|t| t.id.clone())
+        .collect();
+    data.active_template_ids.retain(|id| templates.contains(id));
+    data.active_template_id = data.active_template_ids.first().cloned();
+    issues
+}
+
+/// 构造一个问题（可修复性默认为是）。
+fn issue(kind: IntegrityIssueKind, location: String, message: String) -> IntegrityIssue {
+    IntegrityIssue {
+        kind,
+        location,
+        message,
+        repairable: true,
+    }
+}
+
+/// 检查日期格式、重复日期与排序（日期之间与当日记录之间）。
+fn check_days<D: Day>(section: &str, list: &[D], issues: &mut Vec<IntegrityIssue>) {
+    let mut seen = BTreeSet::new();
+    for day in list {
+        let location = format!("{section}/{}", day.date());
+        if chrono::NaiveDate::parse_from_str(day.date(), "%Y-%m-%d").is_err() {
+            issues.push(IntegrityIssue {
+                repairable: false,
+                ..issue(
+                    IntegrityIssueKind::InvalidDate,
+                    location.clone(),
+                    format!("日期「{}」不是合法的 YYYY-MM-DD", day.date()),
+                )
+            });
+        }
+        if !seen.insert(day.date()) {
+            issues.push(issue(
+                IntegrityIssueKind::DuplicateDay,
+                location.clone(),
+                format!("日期 {} 出现多次", day.date()),
+            ));
+        }
+        if !day
+            .records()
+            .windows(2)
+            .all(|w| D::order_key(&w[0]) <= D::order_key(&w[1]))
+        {
+            issues.push(issue(
+                IntegrityIssueKind::Unordered,
+                location,
+                "当日记录未按时间排序".to_string(),
+            ));
+        }
+    }
+    if !list.windows(2).all(|w| w[0].date() <= w[1].date()) {
+        issues.push(issue(
+            IntegrityIssueKind::Unordered,
+            section.to_string(),
+            "日期未按先后排序".to_string(),
+        ));
+    }
+}
+
+/// 合并重复日期，并按日期与记录时间排序。
+fn repair_days<D: Day>(list: &mut Vec<D>) {
+    let mut merged: Vec<D> = Vec::with_capacity(list.len());
+    for mut day in list.drain(..) {
+        match merged.iter_mut().find(|d| d.date() == day.date()) {
+            Some(existing) => existing.records_mut().append(day.records_mut()),
+            None => merged.push(day),
+        }
+    }
+    merged.sort_by(|a, b| a.date().cmp(b.date()));
+    for day in &mut merged {
+        day.records_mut()
+            .sort_by(|a, b| D::order_key(a).cmp(D::order_key(b)));
+    }
+    *list = merged;
+}
+
+/// 解析 `HH:mm`。
+fn parse_hhmm(time: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+/// 起止时间之间的秒数（结束早于开始时视为跨过午夜）。
+fn span_seconds(start: chrono::NaiveTime, end: chrono::NaiveTime) -> i64 {
+    (end - start).num_seconds().rem_euclid(24 * 60 * 60)
+}
+
+/// 起止时间与给定计时秒数（不含暂停）是否吻合。
+fn span_matches(record: &HistoryRecord, active_seconds: u64) -> bool {
+    let (Some(start), Some(end)) = (
+        parse_hhmm(&record.start_time),
+        record.end_time.as_deref().and_then(parse_hhmm),
+    ) else {
+        return true;
+    };
+    let expected = (active_seconds + record.paused_seconds()) as i64;
+    (span_seconds(start, end) - expected).abs() <= SPAN_TOLERANCE_SECONDS
+}
+
+/// 检查记录的开始/结束时间格式，以及时长与起止时间是否一致。
+fn check_record_times(
+    location: &str,
+    date: &str,
+    record: &HistoryRecord,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let start_valid = parse_hhmm(&record.start_time).is_some();
+    if !start_valid {
+        issues.push(IntegrityIssue {
+            repairable: record.started_at_local().is_some(),
+            ..issue(
+                IntegrityIssueKind::InvalidTime,
+                location.to_string(),
+                format!("开始时间「{}」不是合法的 HH:mm", record.start_time),
+            )
+        });
+    }
+    if let Some(end) = record
+        .end_time
+        .as_deref()
+        .filter(|t| parse_hhmm(t).is_none())
+    {
+        issues.push(IntegrityIssue {
+            repairable: start_valid || record.started_at_local().is_some(),
+            ..issue(
+                IntegrityIssueKind::InvalidTime,
+                location.to_string(),
+                format!("结束时间「{end}」不是合法的 HH:mm"),
+            )
+        });
+    }
+    if !span_matches(record, u64::from(record.duration) * 60) {
+        issues.push(issue(
+            IntegrityIssueKind::DurationMismatch,
+            location.to_string(),
+            format!(
+                "{date} {}-{} 与时长 {} 分钟不一致",
+                record.start_time,
+                record.end_time.as_deref().unwrap_or_default(),
+                record.duration
+            ),
+        ));
+    }
+}
+
+/// 修复记录的开始/结束时间与时长（规则见 [`repair`]）。
+fn repair_record_times(record: &mut HistoryRecord) {
+    if parse_hhmm(&record.start_time).is_none() {
+        if let Some(at) = record.started_at_local() {
+            record.start_time = at.format("%H:%M").to_string();
+        }
+    }
+    let Some(start) = parse_hhmm(&record.start_time) else {
+        return;
+    };
+    let end_valid = record.end_time.as_deref().and_then(parse_hhmm).is_some();
+    if end_valid && span_matches(record, u64::from(record.duration) * 60) {
+        return;
+    }
+    if let Some(elapsed) = record
+        .elapsed_seconds
+        .filter(|s| end_valid && span_matches(record, *s))
+    {
+        record.duration = (elapsed / 60) as u32;
+        return;
+    }
+
+    let wall = chrono::Duration::seconds(
+        (u64::from(record.duration) * 60 + record.paused_seconds()) as i64,
+    );
+    record.end_time = Some((start + wall).format("%H:%M").to_string());
+    if let Some(at) = record.started_at_local() {
+        record.ended_at = Some((at + wall).to_rfc3339());
+    }
+}
+
+/// 检查记录的标签：标签 id 需指向已有标签定义，没有 id 时路径需对应已有标签
+/// （记录中的路径只是写入时的快照，与 id 指向的当前路径不同不算问题）。
+fn check_tag(
+    data: &AppData,
+    location: &str,
+    tag: &str,
+    tag_id: Option<&str>,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let id_path = tag_id.map(|id| data.tag_path(id));
+    let message = match id_path {
+        Some(None) => format!("标签 id「{}」不存在", tag_id.unwrap_or_default()),
+        None if !tag.is_empty() && data.tag_id_by_path(tag).is_none() => {
+            format!("标签「{tag}」不在标签列表中")
+        }
+        _ => return,
+    };
+    issues.push(issue(
+        IntegrityIssueKind::UnknownTag,
+        location.to_string(),
+        message,
+    ));
+}
+
+/// 修复记录的标签引用：失效的 id 解除引用，缺失的标签按路径补建。
+fn repair_tag(data: &mut AppData, tag: &str, tag_id: &mut Option<String>) {
+    if tag_id
+        .as_deref()
+        .is_some_and(|id| data.tag_path(id).is_none())
+    {
+        *tag_id = None;
+    }
+    if tag_id.is_none() && !tag.is_empty() {
+        *tag_id = Some(data.ensure_tag_path(tag));
+    }
+}
+
+/// 由标签定义推导的标签列表（与 `AppData::sync_tags` 一致）。
+fn expected_tags(data: &AppData) -> Vec<String> {
+    data.tag_defs
+        .iter()
+        .filter(|t| !t.archived)
+        .filter_map(|t| data.tag_path(&t.id))
+        .collect()
+}
+
+/// 黑名单模板是否存在。
+fn template_exists(data: &AppData, id: &str) -> bool {
+    data.blacklist_templates.iter().any(|t| t.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone as _;
+
+    use crate::app_data::PauseSegment;
+
+    /// 构造一条工作记录。
+    fn record(start: &str, end: Option<&str>, duration: u32, tag: &str) -> HistoryRecord {
+        let record = HistoryRecord::for_test(tag, start).with_duration(duration);
+        match end {
+            Some(end) => record.with_end(end),
+            None => record,
+        }
+    }
+
+    /// 位置与类型的简写集合（便于断言）。
+    fn kinds(issues: &[IntegrityIssue]) -> Vec<(IntegrityIssueKind, &str)> {
+        issues
+            .iter()
+            .map(|i| (i.kind, i.location.as_str()))
+            .collect()
+    }
+
+    /// 默认数据应无问题；带暂停或跨午夜的记录不应误报时长不一致。
+    #[test]
+    fn check_accepts_default_and_consistent_records() {
+        let mut data = AppData::default();
+        assert!(check(&data).is_empty());
+
+        let mut paused = record("09:00", Some("09:30"), 25, "");
+        paused.pauses = vec![PauseSegment {
+            start_time: "09:10".to_string(),
+            end_time: "09:15".to_string(),
+            seconds: 300,
+        }];
+        data.history = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![paused, record("23:50", Some("00:15"), 25, "")],
+        }];
+        assert!(check(&data).is_empty());
+    }
+
+    /// 应报告各类问题的位置，并标记不可修复的问题。
+    #[test]
+    fn check_reports_issue_locations() {
+        let mut data = AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2025-01-02".to_string(),
+                    records: vec![
+                        record("9:77", None, 25, ""),
+                        record("08:00", Some("08:40"), 25, "不存在"),
+                    ],
+                },
+                HistoryDay {
+                    date: "2025-01-02".to_string(),
+                    records: vec![record("10:00", Some("10:25"), 25, "")],
+                },
+                HistoryDay {
+                    date: "2025-13-01".to_string(),
+                    records: Vec::new(),
+                },
+            ],
+            ..AppData::default()
+        };
+        data.history[1].records[0].tag_id = Some("tag-missing".to_string());
+        data.active_template_ids.push("gone".to_string());
+
+        let issues = check(&data);
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                (IntegrityIssueKind::Unordered, "history/2025-01-02"),
+                (IntegrityIssueKind::DuplicateDay, "history/2025-01-02"),
+                (IntegrityIssueKind::InvalidDate, "history/2025-13-01"),
+                (IntegrityIssueKind::InvalidTime, "history/2025-01-02#0"),
+                (IntegrityIssueKind::DurationMismatch, "history/2025-01-02#1"),
+                (IntegrityIssueKind::UnknownTag, "history/2025-01-02#1"),
+                (IntegrityIssueKind::UnknownTag, "history/2025-01-02#0"),
+                (
+                    IntegrityIssueKind::MissingTemplate,
+                    "activeTemplateIds/gone"
+                ),
+            ]
+        );
+        let unrepairable: Vec<_> = issues.iter().filter(|i| !i.repairable).collect();
+        assert_eq!(unrepairable.len(), 2);
+        assert_eq!(unrepairable[0].kind, IntegrityIssueKind::InvalidDate);
+        assert_eq!(unrepairable[1].kind, IntegrityIssueKind::InvalidTime);
+    }
+
+    /// 修复后仅剩不可修复的问题：合并重复日期、修正时长/结束时间、补建标签并移除失效模板。
+    #[test]
+    fn repair_fixes_repairable_issues() {
+        let mut data = AppData::default();
+        let mut measured = record("08:00", Some("08:40"), 25, "学习/英语");
+        measured.elapsed_seconds = Some(40 * 60);
+        let mut backfilled = record("bad", Some("also bad"), 25, "");
+        backfilled.started_at = Some(
+            chrono::Local
+                .with_ymd_and_hms(2025, 1, 2, 7, 0, 0)
+                .unwrap()
+                .to_rfc3339(),
+        );
+        data.history = vec![
+            HistoryDay {
+                date: "2025-01-02".to_string(),
+                records: vec![record("10:00", Some("10:05"), 25, ""), measured],
+            },
+            HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![record("09:00", Some("09:25"), 25, "")],
+            },
+            HistoryDay {
+                date: "2025-01-02".to_string(),
+                records: vec![backfilled],
+            },
+        ];
+        data.active_template_ids.push("gone".to_string());
+        data.active_template_id = Some("gone".to_string());
+
+        let found = repair(&mut data);
+        assert!(found.len() >= 6);
+        assert!(check(&data).is_empty());
+
+        let dates: Vec<_> = data.history.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2025-01-01", "2025-01-02"]);
+        let day = &data.history[1].records;
+        let starts: Vec<_> = day.iter().map(|r| r.start_time.as_str()).collect();
+        assert_eq!(starts, ["07:00", "08:00", "10:00"]);
+        assert_eq!(day[0].end_time.as_deref(), Some("07:25"));
+        assert_eq!(day[1].duration, 40);
+        assert_eq!(day[2].end_time.as_deref(), Some("10:25"));
+        assert_eq!(day[1].tag_id, data.tag_id_by_path("学习/英语"));
+        assert!(data.tags.iter().any(|t| t == "学习/英语"));
+        assert!(!data.active_template_ids.contains(&"gone".to_string()));
+        assert_eq!(
+            data.active_template_id,
+            data.active_template_ids.first().cloned()
+        );
+    }
+
+    /// 不可修复的问题在修复后保持原样。
+    #[test]
+    fn repair_leaves_unrepairable_issues() {
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025/01/01".to_string(),
+                records: vec![record("xx", None, 25, "")],
+            }],
+            ..AppData::default()
+        };
+        let before = data.history.clone();
+        let found = repair(&mut data);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|i| !i.repairable));
+        assert_eq!(
+            data.history[0].records[0].start_time,
+            before[0].records[0].start_time
+        );
+        assert_eq!(check(&data), found);
+    }
+}
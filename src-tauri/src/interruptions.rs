@@ -1,13 +1,15 @@
-//! 中断记录统计：频率、时段、原因分布与中断率等（PRD v4）。
+//! 中断记录：按日归组写入，以及频率、时段、原因分布与中断率等统计（PRD v4）。
 
 use std::collections::BTreeMap;
 
-use chrono::{Datelike as _, NaiveDate, Timelike as _};
+use chrono::{NaiveDate, Timelike as _};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::app_data::{AppData, DateRange, InterruptionDay, InterruptionRecord};
-use crate::commands::validation::{history_for_ui, validate_date_range};
+use crate::app_data::{
+    DateRange, DayBoundary, HistoryDay, InterruptionDay, InterruptionRecord, Phase,
+};
+use crate::commands::validation::validate_date_range;
 use crate::errors::{AppError, AppResult};
 
 /// 原因统计条目（用于饼图/列表展示）。
@@ -42,18 +44,20 @@ pub struct InterruptionStats {
     pub average_focused_seconds: f64,
 }
 
-/// 计算指定日期范围内的中断统计（闭区间）。
+/// 计算指定日期范围内的中断统计（闭区间；`history`/`interruptions` 至少需覆盖该范围）。
 pub fn compute_interruption_stats(
-    data: &AppData,
+    history: &[HistoryDay],
+    interruptions: &[InterruptionDay],
     range: &DateRange,
+    boundary: DayBoundary,
 ) -> AppResult<InterruptionStats> {
     validate_date_range(range)?;
 
-    let records = collect_records_in_range(&data.interruptions, range);
+    let records = collect_records_in_range(interruptions, range, boundary);
     let total_interruptions = records.len() as u32;
 
     let day_count = day_count_inclusive(&range.from, &range.to)?;
-    let week_count = week_count_covered(&range.from, &range.to)?;
+    let week_count = week_count_covered(&range.from, &range.to, boundary)?;
 
     let mut hourly_counts = vec![0u32; 24];
     let mut reason_map = BTreeMap::<String, u32>::new();
@@ -79,7 +83,7 @@ pub fn compute_interruption_stats(
         .collect();
     reason_distribution.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.reason.cmp(&b.reason)));
 
-    let completed = completed_pomodoros_in_range(data, range);
+    let completed = completed_pomodoros_in_range(history, range, boundary);
     let started = completed.saturating_add(total_interruptions);
     let interruption_rate = if started == 0 {
         0.0
@@ -104,17 +108,43 @@ pub fn compute_interruption_stats(
     })
 }
 
-/// 在给定范围内收集中断记录（按 day 过滤后扁平化）。
+/// 在 `interruptions` 数组中确保存在指定日期的 `InterruptionDay`，并返回可变引用。
+pub fn ensure_interruption_day<'a>(
+    items: &'a mut Vec<InterruptionDay>,
+    date: &str,
+) -> &'a mut InterruptionDay {
+    if let Some(index) = items.iter().position(|d| d.date == date) {
+        return &mut items[index];
+    }
+    items.push(InterruptionDay {
+        date: date.to_string(),
+        records: Vec::new(),
+    });
+    let last = items.len().saturating_sub(1);
+    &mut items[last]
+}
+
+/// 在给定范围内收集中断记录（按中断时刻归属的逻辑日期过滤后扁平化）。
 fn collect_records_in_range(
     days: &[InterruptionDay],
     range: &DateRange,
+    boundary: DayBoundary,
 ) -> Vec<InterruptionRecord> {
     let mut out = Vec::new();
-    for d in days
-        .iter()
-        .filter(|d| d.date >= range.from && d.date <= range.to)
-    {
-        out.extend(d.records.clone());
+    for d in days.iter().filter(|d| d.date >= range.from) {
+        for r in &d.records {
+            let date = chrono::DateTime::parse_from_rfc3339(&r.timestamp)
+                .map(|ts| {
+                    boundary
+                        .logical_date(ts.with_timezone(&chrono::Local).naive_local())
+                        .format("%Y-%m-%d")
+                        .to_string()
+                })
+                .unwrap_or_else(|_| d.date.clone());
+            if date >= range.from && date <= range.to {
+                out.push(r.clone());
+            }
+        }
     }
     out
 }
@@ -129,18 +159,17 @@ fn day_count_inclusive(from: &str, to: &str) -> AppResult<u32> {
     Ok((diff.max(0) as u32).saturating_add(1))
 }
 
-/// 计算范围覆盖的周数（按 ISO week 统计，包含起止所在周）。
-fn week_count_covered(from: &str, to: &str) -> AppResult<u32> {
+/// 计算范围覆盖的周数（按设置的每周首日切分，包含起止所在周）。
+fn week_count_covered(from: &str, to: &str, boundary: DayBoundary) -> AppResult<u32> {
     let f = NaiveDate::parse_from_str(from, "%Y-%m-%d")
         .map_err(|_| AppError::Validation("日期格式必须为 YYYY-MM-DD".to_string()))?;
     let t = NaiveDate::parse_from_str(to, "%Y-%m-%d")
         .map_err(|_| AppError::Validation("日期格式必须为 YYYY-MM-DD".to_string()))?;
 
     let mut cur = f;
-    let mut seen = std::collections::BTreeSet::<(i32, u32)>::new();
+    let mut seen = std::collections::BTreeSet::<NaiveDate>::new();
     while cur <= t {
-        let w = cur.iso_week();
-        seen.insert((w.year(), w.week()));
+        seen.insert(boundary.week_start_of(cur));
         if let Some(next) = cur.succ_opt() {
             cur = next;
         } else {
@@ -166,11 +195,48 @@ fn normalize_reason(reason: &str) -> String {
     }
 }
 
-/// 统计范围内完成番茄数（与历史页面一致：开发环境优先 `history_dev`）。
-fn completed_pomodoros_in_range(data: &AppData, range: &DateRange) -> u32 {
-    history_for_ui(data)
+/// 统计范围内完成番茄数（仅工作阶段，按逻辑日期归属）。
+fn completed_pomodoros_in_range(
+    history: &[HistoryDay],
+    range: &DateRange,
+    boundary: DayBoundary,
+) -> u32 {
+    history
         .iter()
-        .filter(|d| d.date >= range.from && d.date <= range.to)
-        .map(|d| d.records.len() as u32)
+        .filter(|d| d.date >= range.from)
+        .map(|d| {
+            d.records
+                .iter()
+                .filter(|r| r.phase == Phase::Work)
+                .filter(|r| {
+                    let date = boundary.record_date(&d.date, r);
+                    date >= range.from && date <= range.to
+                })
+                .count() as u32
+        })
         .sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `week_count_covered`：周数按每周首日切分（周日为首日时，周六到周日跨两周）。
+    #[test]
+    fn week_count_covered_respects_first_weekday() {
+        // 2025-01-04 是周六，2025-01-05 是周日。
+        let monday = DayBoundary::default();
+        let sunday = DayBoundary {
+            first_weekday: chrono::Weekday::Sun,
+            ..DayBoundary::default()
+        };
+        assert_eq!(
+            week_count_covered("2025-01-04", "2025-01-05", monday).unwrap(),
+            1
+        );
+        assert_eq!(
+            week_count_covered("2025-01-04", "2025-01-05", sunday).unwrap(),
+            2
+        );
+    }
+}
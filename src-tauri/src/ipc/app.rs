@@ -7,6 +7,7 @@ use crate::commands::app::get_store_paths_impl;
 use crate::commands::common::to_ipc_result;
 use crate::commands::types::{AppSnapshot, StorePaths};
 use crate::errors::{AppError, AppResult};
+use crate::recovery::StoreRecoveryReport;
 use crate::state::AppState;
 
 /// 获取应用完整快照（用于前端首屏渲染与恢复）。
@@ -16,6 +17,7 @@ pub fn get_app_snapshot(state: tauri::State<'_, AppState>) -> Result<AppSnapshot
     to_ipc_result(Ok(AppSnapshot {
         data: state.data_snapshot(),
         timer: state.timer_snapshot(),
+        profile: state.active_profile(),
     }))
 }
 
@@ -26,6 +28,15 @@ pub fn get_store_paths(app: tauri::AppHandle) -> Result<StorePaths, String> {
     to_ipc_result(get_store_paths_impl(&app))
 }
 
+/// 获取本次启动的 store 恢复报告（未发生恢复时为 `null`；用于首屏提示被丢弃的数据）。
+#[tauri::command]
+pub fn get_store_recovery_report(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<StoreRecoveryReport>, String> {
+    tracing::info!(target: "ipc", "get_store_recovery_report called");
+    to_ipc_result(Ok(state.store_recovery()))
+}
+
 /// 打开应用数据根目录（文件管理器，统一入口）。
 #[tauri::command]
 pub fn open_store_dir(app: tauri::AppHandle) -> Result<(), String> {
@@ -0,0 +1,31 @@
+//! 历史归档相关 IPC 命令：查询归档状态与立即归档（作用于当前配置）。
+
+use crate::archive::{ArchiveReport, ArchiveStatus};
+use crate::commands::archive::{archive_history_impl, get_archive_status_impl};
+use crate::commands::common::to_ipc_result;
+use crate::errors::AppResult;
+use crate::state::AppState;
+
+/// 当前配置的数据目录（归档目录所在目录）。
+fn profile_dir(app: &tauri::AppHandle, state: &AppState) -> AppResult<std::path::PathBuf> {
+    crate::app_paths::profile_data_dir(app, &state.active_profile().id)
+}
+
+/// 查询归档状态（归档线、汇总与归档文件）。
+#[tauri::command]
+pub fn get_archive_status(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ArchiveStatus, String> {
+    to_ipc_result(profile_dir(&app, &state).and_then(|dir| get_archive_status_impl(&*state, &dir)))
+}
+
+/// 按设置的保留月数立即归档旧历史。
+#[tauri::command]
+pub fn archive_history(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ArchiveReport, String> {
+    tracing::info!(target: "ipc", "archive_history called");
+    to_ipc_result(profile_dir(&app, &state).and_then(|dir| archive_history_impl(&*state, &dir)))
+}
@@ -1,6 +1,6 @@
 //! 音效相关 IPC 命令：播放/暂停/音量/导入/删除/列表（PRD v4）。
 
-use crate::app_data::CustomAudio;
+use crate::app_data::{CustomAudio, Section};
 use crate::commands::common::to_ipc_result;
 use crate::errors::{AppError, AppResult};
 use crate::state::AppState;
@@ -88,6 +88,7 @@ fn audio_play_impl(state: &AppState, audio_id: String) -> AppResult<bool> {
         let Some(found) = crate::audio::find_audio_by_id(data, &audio_id) else {
             return Err(AppError::Validation("找不到指定音效".to_string()));
         };
+        data.touch_section(Section::Settings);
         data.settings.audio.current_audio_id = audio_id.clone();
         Ok(found)
     })?;
@@ -110,6 +111,7 @@ fn audio_set_volume_impl(state: &AppState, volume: u8) -> AppResult<bool> {
         return Err(AppError::Validation("音量需在 0-100".to_string()));
     }
     state.update_data(|data| {
+        data.touch_section(Section::Settings);
         data.settings.audio.volume = volume;
         Ok(())
     })?;
@@ -167,6 +169,7 @@ fn audio_import_impl(state: &AppState, file_path: String, name: String) -> AppRe
     };
 
     let custom_audios = state.update_data_with(|data| {
+        data.touch_section(Section::CustomAudios);
         data.custom_audios.push(item.clone());
         Ok(data.custom_audios.clone())
     })?;
@@ -197,9 +200,11 @@ fn audio_delete_impl(state: &AppState, audio_id: String) -> AppResult<bool> {
         else {
             return Err(AppError::Validation("找不到要删除的自定义音效".to_string()));
         };
+        data.touch_section(Section::CustomAudios);
         let removed = data.custom_audios.remove(i);
         let should_fallback = data.settings.audio.current_audio_id == audio_id_for_cmp;
         if should_fallback {
+            data.touch_section(Section::Settings);
             data.settings.audio.current_audio_id = "".to_string();
         }
         Ok((
@@ -0,0 +1,25 @@
+//! 审计日志相关 IPC 命令：查询变更历史、按事件日志重放校验与重建。
+
+use crate::audit::{AuditEntry, ReplayReport};
+use crate::commands::audit::{get_change_history_impl, rebuild_from_event_log_impl};
+use crate::commands::common::to_ipc_result;
+use crate::state::AppState;
+
+/// 查询某个主体的变更历史（按发生顺序）。
+#[tauri::command]
+pub fn get_change_history(
+    state: tauri::State<'_, AppState>,
+    subject: String,
+) -> Result<Vec<AuditEntry>, String> {
+    to_ipc_result(get_change_history_impl(&*state, &subject))
+}
+
+/// 按事件日志重放校验；`commit` 为 true 且不一致时以重放结果替换当前数据（替换前自动备份）。
+#[tauri::command]
+pub fn rebuild_from_event_log(
+    state: tauri::State<'_, AppState>,
+    commit: bool,
+) -> Result<ReplayReport, String> {
+    tracing::info!(target: "ipc", "rebuild_from_event_log called: commit={}", commit);
+    to_ipc_result(rebuild_from_event_log_impl(&*state, commit))
+}
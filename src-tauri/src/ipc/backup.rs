@@ -0,0 +1,39 @@
+//! 备份相关 IPC 命令：列出、预览与恢复当前配置备份目录下的备份。
+
+use crate::backup::{BackupInfo, BackupPreview};
+use crate::commands::backup::{list_backups_impl, preview_backup_impl, restore_backup_impl};
+use crate::commands::common::to_ipc_result;
+use crate::commands::types::AppSnapshot;
+use crate::state::AppState;
+
+/// 列出全部备份（按创建时间倒序）。
+#[tauri::command]
+pub fn list_backups(state: tauri::State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
+    to_ipc_result(state.backup_dir().and_then(|dir| list_backups_impl(&dir)))
+}
+
+/// 预览备份内容（恢复前确认用）。
+#[tauri::command]
+pub fn preview_backup(
+    state: tauri::State<'_, AppState>,
+    file_name: String,
+) -> Result<BackupPreview, String> {
+    to_ipc_result(
+        state
+            .backup_dir()
+            .and_then(|dir| preview_backup_impl(&dir, file_name, state.cipher().as_ref())),
+    )
+}
+
+/// 恢复备份（恢复前会自动备份当前数据）。
+#[tauri::command]
+pub fn restore_backup(
+    state: tauri::State<'_, AppState>,
+    file_name: String,
+) -> Result<AppSnapshot, String> {
+    tracing::info!(target: "ipc", "restore_backup called: file={}", file_name);
+    to_ipc_result(state.backup_dir().and_then(|dir| {
+        let archive_dir = state.archive_dir()?;
+        restore_backup_impl(&*state, &dir, &archive_dir, file_name)
+    }))
+}
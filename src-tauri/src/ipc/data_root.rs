@@ -0,0 +1,37 @@
+//! 数据根目录 IPC 命令：查询当前位置与登记迁移（登记成功后重启应用，迁移在启动时执行）。
+
+use crate::app_paths;
+use crate::commands::common::to_ipc_result;
+use crate::commands::data_root::{get_data_root_impl, request_relocation_impl};
+use crate::commands::types::RelocateRequest;
+use crate::data_root::DataRootInfo;
+use crate::state::AppState;
+use crate::RelocationOutcome;
+
+/// 获取数据根目录信息（模式、路径、待迁移目标与上次迁移失败原因）。
+#[tauri::command]
+pub fn get_data_root(
+    app: tauri::AppHandle,
+    outcome: tauri::State<'_, RelocationOutcome>,
+) -> Result<DataRootInfo, String> {
+    to_ipc_result(
+        app_paths::root_locator(&app)
+            .map(|locator| get_data_root_impl(&locator, outcome.0.clone())),
+    )
+}
+
+/// 登记数据根目录迁移并重启应用（计时进行中或目标无效时拒绝）。
+#[tauri::command]
+pub fn relocate_data_root(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    request: RelocateRequest,
+) -> Result<(), String> {
+    tracing::info!(target: "ipc", "relocate_data_root called: mode={:?}", request.mode);
+    to_ipc_result(
+        app_paths::root_locator(&app)
+            .and_then(|locator| request_relocation_impl(&*state, &locator, &request)),
+    )?;
+    app.request_restart();
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! 数据加密相关 IPC 命令：启动解锁、查询状态、启用/更换口令/关闭加密（日志中不记录口令）。
+
+use tauri::Manager as _;
+
+use crate::commands::common::to_ipc_result;
+use crate::commands::encryption::{
+    change_passphrase_impl, disable_encryption_impl, enable_encryption_impl, encryption_status_impl,
+};
+use crate::commands::types::AppSnapshot;
+use crate::errors::{AppError, AppResult};
+use crate::profiles::ProfileInfo;
+use crate::state::AppState;
+use crate::StartupLock;
+
+/// 获取启动锁状态：当前配置已加密且尚未解锁时返回待解锁的配置，否则为 `null`。
+#[tauri::command]
+pub fn get_lock_state(lock: tauri::State<'_, StartupLock>) -> Result<Option<ProfileInfo>, String> {
+    to_ipc_result(Ok(lock.0.lock().unwrap().clone()))
+}
+
+/// 以口令解锁并完成启动，返回应用快照（口令错误时保持锁定）。
+#[tauri::command]
+pub fn unlock_store(
+    app: tauri::AppHandle,
+    lock: tauri::State<'_, StartupLock>,
+    passphrase: String,
+) -> Result<AppSnapshot, String> {
+    tracing::info!(target: "ipc", "unlock_store called");
+    to_ipc_result((|| -> AppResult<AppSnapshot> {
+        let mut pending = lock.0.lock().unwrap();
+        let profile = pending
+            .clone()
+            .ok_or_else(|| AppError::Validation("数据已解锁".to_string()))?;
+        crate::start_app(&app, profile, Some(&passphrase))?;
+        *pending = None;
+        let state = app
+            .try_state::<AppState>()
+            .ok_or_else(|| AppError::Invariant("解锁后未能加载数据".to_string()))?;
+        Ok(AppSnapshot {
+            data: state.data_snapshot(),
+            timer: state.timer_snapshot(),
+            profile: state.active_profile(),
+        })
+    })())
+}
+
+/// 查询当前配置是否已启用加密。
+#[tauri::command]
+pub fn get_encryption_status(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    to_ipc_result(Ok(encryption_status_impl(&*state)))
+}
+
+/// 启用加密（口令至少 8 个字符）。
+#[tauri::command]
+pub fn enable_encryption(
+    state: tauri::State<'_, AppState>,
+    passphrase: String,
+) -> Result<(), String> {
+    tracing::info!(target: "ipc", "enable_encryption called");
+    to_ipc_result(enable_encryption_impl(&*state, &passphrase))
+}
+
+/// 更换口令（旧口令错误时拒绝）。
+#[tauri::command]
+pub fn change_passphrase(
+    state: tauri::State<'_, AppState>,
+    old_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    tracing::info!(target: "ipc", "change_passphrase called");
+    to_ipc_result(change_passphrase_impl(
+        &*state,
+        &old_passphrase,
+        &new_passphrase,
+    ))
+}
+
+/// 关闭加密（口令错误时拒绝）。
+#[tauri::command]
+pub fn disable_encryption(
+    state: tauri::State<'_, AppState>,
+    passphrase: String,
+) -> Result<(), String> {
+    tracing::info!(target: "ipc", "disable_encryption called");
+    to_ipc_result(disable_encryption_impl(&*state, &passphrase))
+}
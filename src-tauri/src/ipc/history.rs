@@ -2,7 +2,12 @@
 
 use crate::app_data::{DateRange, HistoryRecord};
 use crate::commands::common::to_ipc_result;
-use crate::commands::history::{get_history_impl, set_history_remark_impl};
+use crate::commands::history::{
+    add_history_record_impl, delete_history_record_impl, get_history_impl,
+    merge_history_records_impl, set_history_remark_impl, split_history_record_impl,
+    update_history_record_impl,
+};
+use crate::commands::types::{AppSnapshot, HistoryRecordInput};
 use crate::state::AppState;
 
 /// 获取历史记录（按日期范围筛选；用于历史列表与统计）。
@@ -24,3 +29,64 @@ pub fn set_history_remark(
 ) -> Result<HistoryRecord, String> {
     to_ipc_result(set_history_remark_impl(&*state, date, record_index, remark))
 }
+
+/// 手动补录一条历史记录。
+#[tauri::command]
+pub fn add_history_record(
+    state: tauri::State<'_, AppState>,
+    input: HistoryRecordInput,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(add_history_record_impl(&*state, &input))
+}
+
+/// 编辑指定历史记录（标签、时间、时长、阶段与备注）。
+#[tauri::command]
+pub fn update_history_record(
+    state: tauri::State<'_, AppState>,
+    date: String,
+    record_index: usize,
+    input: HistoryRecordInput,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(update_history_record_impl(
+        &*state,
+        date,
+        record_index,
+        &input,
+    ))
+}
+
+/// 删除指定历史记录。
+#[tauri::command]
+pub fn delete_history_record(
+    state: tauri::State<'_, AppState>,
+    date: String,
+    record_index: usize,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(delete_history_record_impl(&*state, date, record_index))
+}
+
+/// 在专注第 `minutes` 分钟处拆分指定历史记录（之前的暂停不计入）。
+#[tauri::command]
+pub fn split_history_record(
+    state: tauri::State<'_, AppState>,
+    date: String,
+    record_index: usize,
+    minutes: u32,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(split_history_record_impl(
+        &*state,
+        date,
+        record_index,
+        minutes,
+    ))
+}
+
+/// 将指定历史记录与其后一条相邻记录合并。
+#[tauri::command]
+pub fn merge_history_records(
+    state: tauri::State<'_, AppState>,
+    date: String,
+    record_index: usize,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(merge_history_records_impl(&*state, date, record_index))
+}
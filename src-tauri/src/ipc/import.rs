@@ -0,0 +1,38 @@
+//! 导入相关 IPC 命令：读取前端选择的导出文件或外部工具 CSV，预览或写入历史记录。
+
+use crate::commands::common::to_ipc_result;
+use crate::commands::import::import_history_impl;
+use crate::commands::import_external::import_external_impl;
+use crate::commands::types::{ExternalImportRequest, ImportReport, ImportRequest};
+use crate::state::AppState;
+
+/// 导入历史记录：`commit=false` 时仅返回预览报告，否则先备份再写入。
+#[tauri::command]
+pub fn import_history(
+    state: tauri::State<'_, AppState>,
+    request: ImportRequest,
+) -> Result<ImportReport, String> {
+    tracing::info!(
+        target: "ipc",
+        "import_history called: mode={:?} commit={}",
+        request.mode,
+        request.commit
+    );
+    to_ipc_result(import_history_impl(&*state, &request))
+}
+
+/// 从外部时间记录工具导入（Toggl 或通用 CSV 列映射）。
+#[tauri::command]
+pub fn import_external_history(
+    state: tauri::State<'_, AppState>,
+    request: ExternalImportRequest,
+) -> Result<ImportReport, String> {
+    tracing::info!(
+        target: "ipc",
+        "import_external_history called: source={:?} mode={:?} commit={}",
+        request.source,
+        request.mode,
+        request.commit
+    );
+    to_ipc_result(import_external_impl(&*state, &request))
+}
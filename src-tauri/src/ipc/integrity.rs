@@ -0,0 +1,16 @@
+//! 数据完整性相关 IPC 命令：检查并可选修复 `AppData`。
+
+use crate::commands::common::to_ipc_result;
+use crate::commands::integrity::check_data_integrity_impl;
+use crate::integrity::IntegrityReport;
+use crate::state::AppState;
+
+/// 检查数据完整性；`repair` 为 true 时自动修复可修复的问题（修复前自动备份）。
+#[tauri::command]
+pub fn check_data_integrity(
+    state: tauri::State<'_, AppState>,
+    repair: bool,
+) -> Result<IntegrityReport, String> {
+    tracing::info!(target: "ipc", "check_data_integrity called: repair={}", repair);
+    to_ipc_result(check_data_integrity_impl(&*state, repair))
+}
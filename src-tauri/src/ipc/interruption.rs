@@ -1,6 +1,6 @@
 //! 中断相关 IPC 命令：记录中断、获取统计、读取 combo/累计番茄数（PRD v4）。
 
-use crate::app_data::{DateRange, InterruptionDay, InterruptionRecord, InterruptionType};
+use crate::app_data::{DateRange, InterruptionRecord, InterruptionType};
 use crate::commands::common::to_ipc_result;
 use crate::errors::{AppError, AppResult};
 use crate::interruptions::InterruptionStats;
@@ -61,11 +61,13 @@ fn record_interruption_impl(
             }
 
             let remaining_seconds = timer_runtime.remaining_seconds;
-            let focused_seconds = timer_runtime.focused_seconds(&data.settings);
+            let focused_seconds = timer_runtime.focused_seconds();
             let tag = timer_runtime.current_tag.clone();
 
-            let timestamp = chrono::Utc::now().to_rfc3339();
-            let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+            let now = chrono::Local::now();
+            let timestamp = now.with_timezone(&chrono::Utc).to_rfc3339();
+            let date = now.format("%Y-%m-%d").to_string();
+            let started_at = timer_runtime.phase_started_at().map(|at| at.to_rfc3339());
 
             let record = InterruptionRecord {
                 timestamp,
@@ -73,12 +75,13 @@ fn record_interruption_impl(
                 focused_seconds,
                 reason,
                 r#type: kind,
+                tag_id: data.tag_id_by_path(&tag),
                 tag,
+                started_at,
+                ended_at: Some(now.to_rfc3339()),
             };
 
-            ensure_interruption_day(&mut data.interruptions, &date)
-                .records
-                .push(record.clone());
+            data.push_interruption(&date, record.clone());
 
             // PRD v4：中断后 Combo 重置为 0。
             state.reset_combo_locked(data);
@@ -91,13 +94,19 @@ fn record_interruption_impl(
     Ok(record)
 }
 
-/// `get_interruption_stats` 的内部实现：基于快照计算。
+/// `get_interruption_stats` 的内部实现：只查询范围内（按日界线顺延）的历史与中断记录。
+///
+/// 完成番茄数与历史页面一致：开发环境优先 `history_dev`。
 fn get_interruption_stats_impl(
     state: &AppState,
     range: &DateRange,
 ) -> AppResult<InterruptionStats> {
-    let data = state.data_snapshot();
-    crate::interruptions::compute_interruption_stats(&data, range)
+    crate::commands::validation::validate_date_range(range)?;
+    let boundary = state.settings_snapshot().day_boundary();
+    let natural = boundary.natural_range(range);
+    let history = state.history_in_range(&natural.from, &natural.to)?;
+    let interruptions = state.interruptions_in_range(&natural.from, &natural.to)?;
+    crate::interruptions::compute_interruption_stats(&history, &interruptions, range, boundary)
 }
 
 /// 将字符串解析为 `InterruptionType`（PRD v4：reset/skip/quit）。
@@ -111,19 +120,3 @@ fn parse_interruption_type(s: &str) -> AppResult<InterruptionType> {
         )),
     }
 }
-
-/// 在 `interruptions` 数组中确保存在指定日期的 `InterruptionDay`，并返回可变引用。
-fn ensure_interruption_day<'a>(
-    items: &'a mut Vec<InterruptionDay>,
-    date: &str,
-) -> &'a mut InterruptionDay {
-    if let Some(index) = items.iter().position(|d| d.date == date) {
-        return &mut items[index];
-    }
-    items.push(InterruptionDay {
-        date: date.to_string(),
-        records: Vec::new(),
-    });
-    let last = items.len().saturating_sub(1);
-    &mut items[last]
-}
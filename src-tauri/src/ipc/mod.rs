@@ -2,16 +2,28 @@
 
 pub mod analysis;
 pub mod app;
+pub mod archive;
 pub mod audio;
+pub mod audit;
+pub mod backup;
 pub mod blacklist;
+pub mod data_root;
 pub mod debug;
+pub mod encryption;
 pub mod export;
 pub mod history;
+pub mod import;
+pub mod integrity;
 pub mod interruption;
 pub mod logging;
+pub mod plans;
 pub mod processes;
+pub mod profiles;
 pub mod settings;
+pub mod sync;
 pub mod tags;
+pub mod tasks;
 pub mod templates;
 pub mod timer;
+pub mod undo;
 pub mod window;
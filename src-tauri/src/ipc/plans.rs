@@ -0,0 +1,40 @@
+//! 会话计划相关 IPC 命令：将前端调用转发到可测试的命令逻辑实现。
+
+use crate::app_data::SessionPlan;
+use crate::commands::common::to_ipc_result;
+use crate::commands::plans::{
+    delete_session_plan_impl, get_session_plans_impl, save_session_plan_impl,
+    set_active_session_plan_impl,
+};
+use crate::commands::types::AppSnapshot;
+use crate::state::AppState;
+
+/// 获取会话计划列表。
+#[tauri::command]
+pub fn get_session_plans(state: tauri::State<'_, AppState>) -> Result<Vec<SessionPlan>, String> {
+    to_ipc_result(get_session_plans_impl(&*state))
+}
+
+/// 保存会话计划：新增或按 id 覆盖。
+#[tauri::command]
+pub fn save_session_plan(
+    state: tauri::State<'_, AppState>,
+    plan: SessionPlan,
+) -> Result<SessionPlan, String> {
+    to_ipc_result(save_session_plan_impl(&*state, plan))
+}
+
+/// 删除会话计划（若为当前启用的计划则回退为经典循环）。
+#[tauri::command]
+pub fn delete_session_plan(state: tauri::State<'_, AppState>, id: String) -> Result<bool, String> {
+    to_ipc_result(delete_session_plan_impl(&*state, id))
+}
+
+/// 启用/取消会话计划（`id` 为空表示回到经典循环）。
+#[tauri::command]
+pub fn set_active_session_plan(
+    state: tauri::State<'_, AppState>,
+    id: Option<String>,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(set_active_session_plan_impl(&*state, id))
+}
@@ -0,0 +1,42 @@
+//! 配置相关 IPC 命令：列出、新建与切换配置（切换后刷新托盘显示）。
+
+use crate::app_paths;
+use crate::commands::common::to_ipc_result;
+use crate::commands::profiles::{create_profile_impl, list_profiles_impl, switch_profile_impl};
+use crate::commands::types::AppSnapshot;
+use crate::errors::AppResult;
+use crate::profiles::ProfileRegistry;
+use crate::state::AppState;
+
+/// 列出全部配置与当前配置。
+#[tauri::command]
+pub fn list_profiles(app: tauri::AppHandle) -> Result<ProfileRegistry, String> {
+    to_ipc_result(app_paths::app_data_dir(&app).and_then(|dir| list_profiles_impl(&dir)))
+}
+
+/// 新建配置（不切换）。
+#[tauri::command]
+pub fn create_profile(app: tauri::AppHandle, name: String) -> Result<ProfileRegistry, String> {
+    tracing::info!(target: "ipc", "create_profile called: name={}", name);
+    to_ipc_result(
+        app_paths::app_data_dir(&app)
+            .and_then(|dir| create_profile_impl(&dir, &name, chrono::Local::now())),
+    )
+}
+
+/// 切换配置（专注锁定中拒绝；目标配置已加密时需提供口令），返回新配置的快照。
+#[tauri::command]
+pub fn switch_profile(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    profile_id: String,
+    passphrase: Option<String>,
+) -> Result<AppSnapshot, String> {
+    tracing::info!(target: "ipc", "switch_profile called: id={}", profile_id);
+    to_ipc_result((|| -> AppResult<AppSnapshot> {
+        let dir = app_paths::app_data_dir(&app)?;
+        let out = switch_profile_impl(&*state, &dir, &profile_id, passphrase.as_deref())?;
+        let _ = crate::tray::refresh_tray(&state);
+        Ok(out)
+    })())
+}
@@ -2,10 +2,11 @@
 
 use crate::app_data::Settings;
 use crate::commands::common::to_ipc_result;
-use crate::commands::settings::{set_goals_impl, update_settings_impl};
+use crate::commands::settings::{get_goal_progress_impl, set_goals_impl, update_settings_impl};
 use crate::commands::types::AppSnapshot;
 use crate::errors::AppResult;
 use crate::state::AppState;
+use crate::timer::stats::GoalProgress;
 
 /// 更新设置（带范围校验），并在必要时重置当前阶段的剩余时间。
 #[tauri::command]
@@ -30,6 +31,12 @@ pub fn set_goals(
     to_ipc_result(set_goals_ipc_impl(&*state, daily, weekly))
 }
 
+/// 获取目标进度（每日/每周/每月/每年、标签目标与连续达标）。
+#[tauri::command]
+pub fn get_goal_progress(state: tauri::State<'_, AppState>) -> Result<GoalProgress, String> {
+    to_ipc_result(get_goal_progress_impl(&*state))
+}
+
 /// IPC 内部实现：复用 `commands::settings` 的可测试实现。
 fn update_settings_ipc_impl(state: &AppState, settings: Settings) -> AppResult<AppSnapshot> {
     update_settings_impl(state, settings)
@@ -0,0 +1,58 @@
+//! 文件夹同步相关 IPC 命令：查询状态、启用/关闭同步与立即同步（作用于当前配置）。
+
+use crate::commands::common::to_ipc_result;
+use crate::commands::sync::{
+    disable_sync_impl, enable_sync_impl, get_sync_status_impl, sync_now_impl,
+};
+use crate::errors::AppResult;
+use crate::state::AppState;
+use crate::sync::{SyncReport, SyncStatus};
+
+/// 当前配置的数据目录（同步配置所在目录）。
+fn profile_dir(app: &tauri::AppHandle, state: &AppState) -> AppResult<std::path::PathBuf> {
+    crate::app_paths::profile_data_dir(app, &state.active_profile().id)
+}
+
+/// 查询同步状态（含共享文件夹中各设备的日志概况）。
+#[tauri::command]
+pub fn get_sync_status(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<SyncStatus, String> {
+    to_ipc_result(profile_dir(&app, &state).and_then(|dir| get_sync_status_impl(&dir)))
+}
+
+/// 启用同步（或更换共享文件夹/设备名称）并立即同步一次。
+#[tauri::command]
+pub fn enable_sync(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    folder: String,
+    device_name: String,
+) -> Result<SyncStatus, String> {
+    tracing::info!(target: "ipc", "enable_sync called: folder={}", folder);
+    to_ipc_result(
+        profile_dir(&app, &state)
+            .and_then(|dir| enable_sync_impl(&*state, &dir, &folder, &device_name)),
+    )
+}
+
+/// 关闭同步（共享文件夹中的日志保持不变）。
+#[tauri::command]
+pub fn disable_sync(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<SyncStatus, String> {
+    tracing::info!(target: "ipc", "disable_sync called");
+    to_ipc_result(profile_dir(&app, &state).and_then(|dir| disable_sync_impl(&dir)))
+}
+
+/// 立即同步，返回发布/合并数量与冲突报告。
+#[tauri::command]
+pub fn sync_now(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<SyncReport, String> {
+    tracing::info!(target: "ipc", "sync_now called");
+    to_ipc_result(profile_dir(&app, &state).and_then(|dir| sync_now_impl(&*state, &dir)))
+}
@@ -1,7 +1,10 @@
 //! 标签相关 IPC 命令：将前端调用转发到可测试的命令逻辑实现。
 
+use crate::app_data::Tag;
 use crate::commands::common::to_ipc_result;
-use crate::commands::tags::{add_tag_impl, delete_tag_impl, rename_tag_impl, set_current_tag_impl};
+use crate::commands::tags::{
+    add_tag_impl, delete_tag_impl, rename_tag_impl, set_current_tag_impl, update_tag_impl,
+};
 use crate::commands::types::AppSnapshot;
 use crate::state::AppState;
 
@@ -20,7 +23,7 @@ pub fn add_tag(state: tauri::State<'_, AppState>, tag: String) -> Result<Vec<Str
     to_ipc_result(add_tag_impl(&*state, tag))
 }
 
-/// 重命名标签（同时更新历史记录中的标签字段）。
+/// 重命名/移动标签（`to` 为新的完整路径；历史记录按标签 id 同步刷新）。
 #[tauri::command]
 pub fn rename_tag(
     state: tauri::State<'_, AppState>,
@@ -30,8 +33,14 @@ pub fn rename_tag(
     to_ipc_result(rename_tag_impl(&*state, from, to))
 }
 
-/// 删除标签（同时清空历史记录中的该标签）。
+/// 删除标签（同时清空历史记录中的该标签；含子标签时拒绝）。
 #[tauri::command]
 pub fn delete_tag(state: tauri::State<'_, AppState>, tag: String) -> Result<AppSnapshot, String> {
     to_ipc_result(delete_tag_impl(&*state, tag))
 }
+
+/// 更新标签的颜色/图标/归档状态。
+#[tauri::command]
+pub fn update_tag(state: tauri::State<'_, AppState>, tag: Tag) -> Result<AppSnapshot, String> {
+    to_ipc_result(update_tag_impl(&*state, tag))
+}
@@ -0,0 +1,54 @@
+//! 任务相关 IPC 命令：将前端调用转发到可测试的命令逻辑实现。
+
+use crate::app_data::Task;
+use crate::commands::common::to_ipc_result;
+use crate::commands::tasks::{
+    delete_task_impl, get_task_estimates_impl, get_tasks_impl, save_task_impl,
+    set_current_task_impl, set_task_done_impl,
+};
+use crate::commands::types::AppSnapshot;
+use crate::state::AppState;
+use crate::tasks::TaskEstimateReport;
+
+/// 获取任务列表。
+#[tauri::command]
+pub fn get_tasks(state: tauri::State<'_, AppState>) -> Result<Vec<Task>, String> {
+    to_ipc_result(get_tasks_impl(&*state))
+}
+
+/// 保存任务：新增或按 id 覆盖。
+#[tauri::command]
+pub fn save_task(state: tauri::State<'_, AppState>, task: Task) -> Result<Task, String> {
+    to_ipc_result(save_task_impl(&*state, task))
+}
+
+/// 删除任务（若为当前任务则取消选择）。
+#[tauri::command]
+pub fn delete_task(state: tauri::State<'_, AppState>, id: String) -> Result<bool, String> {
+    to_ipc_result(delete_task_impl(&*state, id))
+}
+
+/// 标记任务完成/未完成。
+#[tauri::command]
+pub fn set_task_done(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    done: bool,
+) -> Result<Task, String> {
+    to_ipc_result(set_task_done_impl(&*state, id, done))
+}
+
+/// 选择当前任务（`id` 为空表示取消选择）。
+#[tauri::command]
+pub fn set_current_task(
+    state: tauri::State<'_, AppState>,
+    id: Option<String>,
+) -> Result<AppSnapshot, String> {
+    to_ipc_result(set_current_task_impl(&*state, id))
+}
+
+/// 获取任务预估准确度报告（按任务与按标签）。
+#[tauri::command]
+pub fn get_task_estimates(state: tauri::State<'_, AppState>) -> Result<TaskEstimateReport, String> {
+    to_ipc_result(get_task_estimates_impl(&*state))
+}
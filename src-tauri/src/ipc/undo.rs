@@ -0,0 +1,27 @@
+//! 撤销/重做相关 IPC 命令。
+
+use crate::commands::common::to_ipc_result;
+use crate::commands::types::UndoOutcome;
+use crate::commands::undo::{get_undo_state_impl, redo_impl, undo_impl};
+use crate::state::AppState;
+use crate::undo::UndoState;
+
+/// 查询待撤销与待重做的操作描述。
+#[tauri::command]
+pub fn get_undo_state(state: tauri::State<'_, AppState>) -> Result<UndoState, String> {
+    to_ipc_result(get_undo_state_impl(&*state))
+}
+
+/// 撤销最近一次标签/历史/模板/黑名单修改。
+#[tauri::command]
+pub fn undo(state: tauri::State<'_, AppState>) -> Result<UndoOutcome, String> {
+    tracing::info!(target: "ipc", "undo called");
+    to_ipc_result(undo_impl(&*state))
+}
+
+/// 重做最近一次撤销的操作。
+#[tauri::command]
+pub fn redo(state: tauri::State<'_, AppState>) -> Result<UndoOutcome, String> {
+    tracing::info!(target: "ipc", "redo called");
+    to_ipc_result(redo_impl(&*state))
+}
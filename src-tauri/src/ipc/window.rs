@@ -2,6 +2,7 @@
 
 use tauri::{LogicalPosition, LogicalSize, Manager as _};
 
+use crate::app_data::Section;
 use crate::errors::{AppError, AppResult};
 use crate::state::AppState;
 
@@ -29,6 +30,7 @@ fn set_always_on_top_impl(
     window.set_always_on_top(enabled)?;
 
     state.update_data(|data| {
+        data.touch_section(Section::Settings);
         data.settings.always_on_top = enabled;
         Ok(())
     })?;
@@ -5,22 +5,35 @@
 mod analysis;
 mod app_data;
 mod app_paths;
+mod archive;
 mod audio;
+mod audit;
+mod backup;
 mod combo;
 mod commands;
+mod crypto;
+mod data_root;
 mod errors;
 mod events;
+mod history_db;
+mod integrity;
 mod interruptions;
 #[cfg(not(test))]
 mod ipc;
 mod logging;
+mod migrations;
 mod processes;
+mod profiles;
+mod recovery;
 #[cfg(not(test))]
 mod state;
+mod sync;
+mod tasks;
 mod timer;
 #[cfg(not(test))]
 mod tray;
 pub mod typegen;
+mod undo;
 mod window_events;
 
 #[cfg(not(test))]
@@ -34,14 +47,18 @@ use tauri_plugin_store::StoreExt;
 #[cfg(not(test))]
 use crate::app_data::STORE_FILE_NAME;
 #[cfg(not(test))]
-use crate::app_data::{AppData, STORE_KEY};
+use crate::app_data::{AppData, STORE_KEY, TIMER_STORE_KEY};
 #[cfg(not(test))]
 use crate::errors::{AppError, AppResult};
+#[cfg(not(test))]
+use crate::history_db::HistoryDb;
+#[cfg(not(test))]
+use crate::recovery::StoreRecoveryReport;
 
 #[cfg(not(test))]
-use crate::state::AppState;
+use crate::state::{persist_app_data, AppState};
 #[cfg(not(test))]
-use crate::timer::spawn_timer_task;
+use crate::timer::{spawn_timer_task, TimerRuntime};
 #[cfg(not(test))]
 use crate::tray::setup_tray;
 
@@ -55,45 +72,83 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
+            // 迁移必须在日志与 store 打开之前执行（旧目录中的文件此时均未被占用）。
+            let relocation = data_root::apply_pending(&app_paths::root_locator(app.handle())?);
             logging::init_logging(app.handle())?;
+            app.manage(RelocationOutcome(log_relocation(relocation)));
             migrate_legacy_store_file(app.handle())?;
 
-            let store_path = app_paths::store_file_path(app.handle())?;
-            let store = app
-                .store_builder(store_path)
-                .auto_save(Duration::from_millis(0))
-                .build()?;
-
-            let data = load_or_init_app_data(&store)?;
-
-            app.manage(AppState::new(app.handle().clone(), store, data)?);
-
-            setup_tray(app)?;
-            setup_window_close_to_tray(app)?;
-            spawn_timer_task(app.handle().clone());
-
-            // PRD v2：启动时应用“窗口置顶”设置。
-            if let Some(window) = app.get_webview_window("main") {
-                let state = app.state::<AppState>();
-                let always_on_top = state.data_snapshot().settings.always_on_top;
-                let _ = window.set_always_on_top(always_on_top);
+            let registry =
+                profiles::ProfileRegistry::load(&app_paths::app_data_dir(app.handle())?)?;
+            let profile = registry.active_profile();
+            let encrypted = crypto::EncryptionHeader::load(&app_paths::profile_data_dir(
+                app.handle(),
+                &profile.id,
+            )?)?
+            .is_some();
+            if encrypted {
+                // 已加密：等待前端提交口令（`unlock_store`）后再创建 `AppState`。
+                tracing::info!(target: "storage", "数据已加密，等待解锁：profile={}", profile.id);
+                app.manage(StartupLock(std::sync::Mutex::new(Some(profile))));
+                return Ok(());
             }
-
+            app.manage(StartupLock(std::sync::Mutex::new(None)));
+            start_app(app.handle(), profile, None)?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             ipc::app::get_app_snapshot,
             ipc::app::get_store_paths,
             ipc::app::open_store_dir,
+            ipc::app::get_store_recovery_report,
+            ipc::backup::list_backups,
+            ipc::backup::preview_backup,
+            ipc::backup::restore_backup,
+            ipc::audit::get_change_history,
+            ipc::audit::rebuild_from_event_log,
+            ipc::integrity::check_data_integrity,
+            ipc::sync::get_sync_status,
+            ipc::sync::enable_sync,
+            ipc::sync::disable_sync,
+            ipc::sync::sync_now,
+            ipc::archive::get_archive_status,
+            ipc::archive::archive_history,
+            ipc::undo::get_undo_state,
+            ipc::undo::undo,
+            ipc::undo::redo,
+            ipc::data_root::get_data_root,
+            ipc::data_root::relocate_data_root,
+            ipc::profiles::list_profiles,
+            ipc::profiles::create_profile,
+            ipc::profiles::switch_profile,
+            ipc::encryption::get_lock_state,
+            ipc::encryption::unlock_store,
+            ipc::encryption::get_encryption_status,
+            ipc::encryption::enable_encryption,
+            ipc::encryption::change_passphrase,
+            ipc::encryption::disable_encryption,
             ipc::settings::update_settings,
             ipc::settings::set_goals,
+            ipc::settings::get_goal_progress,
             ipc::tags::set_current_tag,
             ipc::tags::add_tag,
             ipc::tags::rename_tag,
             ipc::tags::delete_tag,
+            ipc::tags::update_tag,
+            ipc::tasks::get_tasks,
+            ipc::tasks::save_task,
+            ipc::tasks::delete_task,
+            ipc::tasks::set_task_done,
+            ipc::tasks::set_current_task,
+            ipc::tasks::get_task_estimates,
             ipc::blacklist::set_blacklist,
             ipc::history::get_history,
             ipc::history::set_history_remark,
+            ipc::history::add_history_record,
+            ipc::history::update_history_record,
+            ipc::history::delete_history_record,
+            ipc::history::split_history_record,
+            ipc::history::merge_history_records,
             ipc::analysis::get_focus_analysis,
             ipc::audio::audio_list,
             ipc::audio::audio_play,
@@ -105,9 +160,15 @@ pub fn run() {
             ipc::templates::save_template,
             ipc::templates::delete_template,
             ipc::templates::apply_template,
+            ipc::plans::get_session_plans,
+            ipc::plans::save_session_plan,
+            ipc::plans::delete_session_plan,
+            ipc::plans::set_active_session_plan,
             ipc::window::set_always_on_top,
             ipc::window::set_mini_mode,
             ipc::export::export_history,
+            ipc::import::import_history,
+            ipc::import::import_external_history,
             ipc::logging::open_log_dir,
             ipc::logging::frontend_log,
             ipc::debug::debug_generate_history,
@@ -201,37 +262,299 @@ fn migrate_legacy_store_file(app: &tauri::AppHandle) -> AppResult<()> {
     }
 }
 
-/// 从 store 中加载 `AppData`；若为空则写入默认值并返回。
+/// 本次启动执行数据目录迁移失败的原因（未迁移或迁移成功时为 `None`）。
+#[cfg(not(test))]
+pub(crate) struct RelocationOutcome(pub Option<String>);
+
+/// 记录启动时的数据目录迁移结果，返回失败原因（供设置页提示）。
+#[cfg(not(test))]
+fn log_relocation(relocation: Option<AppResult<data_root::RelocateReport>>) -> Option<String> {
+    match relocation? {
+        Ok(report) => {
+            tracing::info!(
+                target: "storage",
+                "数据目录迁移完成：files={} bytes={} leftovers={}",
+                report.files,
+                report.bytes,
+                report.leftovers.len()
+            );
+            None
+        }
+        Err(e) => {
+            tracing::error!(target: "storage", "数据目录迁移失败，数据仍位于原目录：{e}");
+            Some(e.to_string())
+        }
+    }
+}
+
+/// 启动锁：当前配置已加密且尚未解锁时保存待解锁的配置（解锁后为 `None`）。
+#[cfg(not(test))]
+pub(crate) struct StartupLock(pub std::sync::Mutex<Option<profiles::ProfileInfo>>);
+
+/// 打开配置并完成启动：注入 `AppState`、创建托盘、启动后台任务并应用窗口设置。
+///
+/// 未加密时在 `setup` 中直接调用；已加密时由 `unlock_store` 在口令校验通过后调用。
+#[cfg(not(test))]
+pub(crate) fn start_app(
+    app: &tauri::AppHandle,
+    profile: profiles::ProfileInfo,
+    passphrase: Option<&str>,
+) -> AppResult<()> {
+    let loaded = match open_profile(app, &profile.id, passphrase) {
+        Err(e @ AppError::SchemaTooNew { .. }) => {
+            refuse_newer_schema(app, &e);
+            return Ok(());
+        }
+        loaded => loaded?,
+    };
+
+    app.manage(AppState::new(
+        app.clone(),
+        profile,
+        loaded.store,
+        loaded.history_db,
+        loaded.data,
+        loaded.timer,
+    )?);
+    if let Some(report) = loaded.recovery {
+        app.state::<AppState>().report_store_recovery(report);
+    }
+
+    setup_tray(app)?;
+    setup_window_close_to_tray(app)?;
+    spawn_timer_task(app.clone());
+    backup::spawn_backup_task(app.clone());
+    sync::spawn_sync_task(app.clone());
+    archive::spawn_archive_task(app.clone());
+
+    // PRD v2：启动时应用“窗口置顶”设置。
+    if let Some(window) = app.get_webview_window("main") {
+        let state = app.state::<AppState>();
+        let always_on_top = state.data_snapshot().settings.always_on_top;
+        let _ = window.set_always_on_top(always_on_top);
+    }
+    Ok(())
+}
+
+/// 已打开的配置数据（store、历史数据库、`AppData` 与恢复后的计时器）。
+#[cfg(not(test))]
+pub(crate) struct LoadedProfile {
+    /// 配置的 store 句柄。
+    pub store: std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>,
+    /// 配置的历史数据库。
+    pub history_db: HistoryDb,
+    /// 已迁移的 `AppData`。
+    pub data: AppData,
+    /// 从检查点恢复（或新建）的计时器。
+    pub timer: TimerRuntime,
+    /// store 损坏时的恢复报告。
+    pub recovery: Option<StoreRecoveryReport>,
+}
+
+/// 打开某个配置：隔离无法解析的 store，加载（必要时迁移/抢救）`AppData` 并恢复计时器运行态。
+///
+/// 配置已加密时先以口令解锁（未提供口令返回 `AppError::Locked`，口令错误返回 `AppError::WrongPassphrase`）。
+#[cfg(not(test))]
+pub(crate) fn open_profile(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    passphrase: Option<&str>,
+) -> AppResult<LoadedProfile> {
+    let cipher = unlock_profile(app, profile_id, passphrase)?;
+    let store_path = app_paths::profile_store_file_path(app, profile_id)?;
+    let unreadable = recovery::quarantine_unparsable_store(
+        &store_path,
+        &app_paths::app_quarantine_dir(app)?,
+        chrono::Local::now(),
+    )?;
+    let store = app
+        .store_builder(&store_path)
+        .auto_save(Duration::from_millis(0))
+        .build()?;
+
+    let mut history_db = HistoryDb::open(&app_paths::profile_history_db_path(app, profile_id)?)?;
+    history_db.set_cipher(cipher);
+    history_db.start_audit();
+    let (mut data, salvaged) =
+        load_or_init_app_data(app, profile_id, &store_path, &store, &mut history_db)?;
+    history_db.ensure_baseline(&data)?;
+    let timer = restore_timer_runtime(&store, &mut history_db, &mut data)?;
+    Ok(LoadedProfile {
+        store,
+        history_db,
+        data,
+        timer,
+        recovery: unreadable.or(salvaged),
+    })
+}
+
+/// 读取配置的加密文件并以口令解锁（未启用加密时返回 `None`）。
 #[cfg(not(test))]
-fn load_or_init_app_data(store: &tauri_plugin_store::Store<tauri::Wry>) -> AppResult<AppData> {
+fn unlock_profile(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    passphrase: Option<&str>,
+) -> AppResult<Option<crypto::Cipher>> {
+    let dir = app_paths::profile_data_dir(app, profile_id)?;
+    let Some(header) = crypto::EncryptionHeader::load(&dir)? else {
+        return Ok(None);
+    };
+    let passphrase = passphrase.ok_or(AppError::Locked)?;
+    header.unlock(passphrase).map(Some)
+}
+
+/// 从 store 与历史数据库中加载 `AppData`；若为空则写入默认值并返回。
+///
+/// 旧版本将历史/中断记录直接保存在 store 中：首次启动时以 store 为准一次性迁移到数据库，并从 store 中移除。
+/// `AppData` 无法完整解析时先隔离 store 副本，再逐段抢救可用数据并返回恢复报告。
+/// 数据版本落后时先写入迁移前备份再按注册表迁移；由更新版本写入的数据直接拒绝（`AppError::SchemaTooNew`）。
+#[cfg(not(test))]
+fn load_or_init_app_data(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    store_path: &std::path::Path,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    history_db: &mut HistoryDb,
+) -> AppResult<(AppData, Option<StoreRecoveryReport>)> {
     if let Some(value) = store.get(STORE_KEY) {
-        let mut data: AppData = serde_json::from_value(value)?;
+        // 加密数据外层带明文版本：更新版本写入的数据在解密前即拒绝。
+        migrations::ensure_supported_value(&value)?;
+        let value = crypto::unseal_json(history_db.cipher(), value)?;
+        migrations::ensure_supported_value(&value)?;
+        let (mut data, report) = match serde_json::from_value::<AppData>(value.clone()) {
+            Ok(data) => (data, None),
+            Err(e) => {
+                let quarantined = recovery::quarantine_store_file(
+                    store_path,
+                    &app_paths::app_quarantine_dir(app)?,
+                    chrono::Local::now(),
+                )?;
+                let (data, dropped) = recovery::salvage_app_data(value);
+                tracing::warn!(
+                    target: "storage",
+                    "AppData 无法完整解析，已隔离原文件并逐段恢复：quarantined={} dropped={:?} err={}",
+                    quarantined.to_string_lossy(),
+                    dropped,
+                    e
+                );
+                let report = StoreRecoveryReport {
+                    quarantined_path: quarantined.to_string_lossy().to_string(),
+                    file_unreadable: false,
+                    dropped,
+                };
+                (data, Some(report))
+            }
+        };
         tracing::info!(target: "storage", "已从 store 加载 AppData");
-        let mut changed = false;
-        if data.migrate_v2() {
+        let mut changed = report.is_some();
+        if crate::history_db::has_records(&data) {
+            let days = history_db.replace_all(&data)?;
+            tracing::info!(target: "storage", "已将 store 中的历史记录迁移到数据库：days={}", days);
             changed = true;
+        } else {
+            history_db.load_into(&mut data)?;
         }
-        if data.migrate_v4() {
+        if data.schema_version < migrations::CURRENT_SCHEMA_VERSION {
+            let info = backup::create_backup(
+                &app_paths::profile_backup_dir(app, profile_id)?,
+                &archive::archive_dir(&app_paths::profile_data_dir(app, profile_id)?),
+                &data,
+                backup::BackupKind::BeforeOperation,
+                &format!("migrate-v{}", data.schema_version),
+                &data.settings.backup,
+                chrono::Local::now(),
+                history_db.cipher(),
+            )?;
+            let migration = migrations::migrate(&mut data)?;
+            tracing::info!(
+                target: "storage",
+                "已迁移 AppData：v{} -> v{} steps={:?} backup={}",
+                migration.from_version,
+                migration.to_version,
+                migration.steps,
+                info.file_name
+            );
+            let repaired = integrity::repair(&mut data);
+            if !repaired.is_empty() {
+                tracing::warn!(
+                    target: "storage",
+                    "迁移后完整性检查发现问题：{:?}",
+                    repaired
+                );
+            }
             changed = true;
         }
         if changed {
-            store.set(STORE_KEY, serde_json::to_value(&data)?);
-            store.save()?;
+            persist_app_data(store, history_db, &mut data)?;
             tracing::info!(target: "storage", "已完成 AppData 迁移并写回 store");
         }
-        return Ok(data);
+        return Ok((data, report));
     }
 
-    let data = AppData::default();
-    store.set(STORE_KEY, serde_json::to_value(&data)?);
-    store.save()?;
+    let mut data = AppData::default();
+    history_db.load_into(&mut data)?;
+    persist_app_data(store, history_db, &mut data)?;
     tracing::info!(target: "storage", "首次启动：已写入默认 AppData");
-    Ok(data)
+    Ok((data, None))
+}
+
+/// 数据由更新版本写入：提示用户升级并在确认后退出（不注入状态、不写回任何数据）。
+#[cfg(not(test))]
+fn refuse_newer_schema(app: &tauri::AppHandle, err: &AppError) {
+    use tauri_plugin_dialog::{DialogExt as _, MessageDialogKind};
+
+    tracing::error!(target: "storage", "拒绝打开数据：{}", err);
+    let handle = app.clone();
+    app.dialog()
+        .message(err.to_string())
+        .title("无法打开数据")
+        .kind(MessageDialogKind::Error)
+        .show(move |_| handle.exit(1));
+}
+
+/// 从 store 恢复计时器运行态：运行中的计时按墙钟补算关闭期间流逝的时间。
+///
+/// 关闭期间自然结束的阶段会写入历史，并与补算后的新检查点一起回写 store；检查点缺失或损坏时回退为默认工作阶段。
+#[cfg(not(test))]
+fn restore_timer_runtime(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    history_db: &mut HistoryDb,
+    data: &mut AppData,
+) -> AppResult<TimerRuntime> {
+    let fresh = |data: &AppData| TimerRuntime::new(&data.settings, &data.tags, &timer::SystemClock);
+
+    let Some(value) = store.get(TIMER_STORE_KEY) else {
+        return Ok(fresh(data));
+    };
+    let checkpoint: timer::TimerCheckpoint = match serde_json::from_value(value) {
+        Ok(checkpoint) => checkpoint,
+        Err(e) => {
+            tracing::warn!(target: "storage", "计时器检查点无法解析，已回退为默认状态：{e}");
+            return Ok(fresh(data));
+        }
+    };
+
+    let (runtime, summary, rewritten) =
+        timer::resume_from_checkpoint(checkpoint, data, Some(&*history_db), &timer::SystemClock)?;
+    if let Some(rewritten) = rewritten {
+        store.set(TIMER_STORE_KEY, serde_json::to_value(&rewritten)?);
+        persist_app_data(store, history_db, data)?;
+    }
+    tracing::info!(
+        target: "timer",
+        "已从检查点恢复计时器：phase={:?} running={} remaining={}s phasesEnded={} workCompleted={}",
+        runtime.phase,
+        runtime.is_running,
+        runtime.remaining_seconds,
+        summary.phases_ended,
+        summary.work_completed
+    );
+    Ok(runtime)
 }
 
 /// 将窗口关闭行为改为“隐藏到托盘”（满足 PRD 的“最小化到托盘”）。
 #[cfg(not(test))]
-fn setup_window_close_to_tray(app: &mut tauri::App) -> AppResult<()> {
+fn setup_window_close_to_tray(app: &tauri::AppHandle) -> AppResult<()> {
     use tauri::Manager as _;
     use tauri::WindowEvent;
 
@@ -0,0 +1,238 @@
+//! 数据结构版本与迁移注册表：按 `schema_version` 顺序执行迁移步骤，支持预演（dry-run）并拒绝打开更新版本写入的数据。
+//!
+//! 新增迁移时：在 `AppData` 上实现 `migrate_vN`，追加到 `MIGRATIONS` 末尾并提升 `CURRENT_SCHEMA_VERSION`。
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::app_data::AppData;
+use crate::errors::{AppError, AppResult};
+
+/// 当前应用写入的数据结构版本。
+pub const CURRENT_SCHEMA_VERSION: u32 = 8;
+
+/// 一个迁移步骤：将数据从 `version - 1`（或更早）升级到 `version`。
+pub struct MigrationStep {
+    /// 迁移后的版本号。
+    pub version: u32,
+    /// 迁移说明（用于日志与预演报告）。
+    pub description: &'static str,
+    /// 执行迁移，返回是否有改动（步骤需幂等：对已满足新结构的数据不做修改）。
+    pub apply: fn(&mut AppData) -> bool,
+}
+
+/// 迁移注册表（按版本号严格递增排列）。
+pub const MIGRATIONS: &[MigrationStep] = &[
+    MigrationStep {
+        version: 2,
+        description: "补齐内置黑名单模板并迁移单模板字段",
+        apply: AppData::migrate_v2,
+    },
+    MigrationStep {
+        version: 4,
+        description: "修复默认音效并由历史回填累计番茄数",
+        apply: AppData::migrate_v4,
+    },
+    MigrationStep {
+        version: 5,
+        description: "回填历史与中断记录的起止时刻",
+        apply: AppData::migrate_v5,
+    },
+    MigrationStep {
+        version: 6,
+        description: "由字符串标签生成标签定义并回填记录的标签 id",
+        apply: AppData::migrate_v6,
+    },
+    MigrationStep {
+        version: 7,
+        description: "为历史记录补齐稳定的记录 id",
+        apply: AppData::migrate_v7,
+    },
+    MigrationStep {
+        version: 8,
+        description: "新增历史归档与历史保留月数（取默认值）",
+        apply: AppData::migrate_v8,
+    },
+];
+
+/// 单个迁移步骤的执行结果。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct MigrationStepReport {
+    /// 迁移后的版本号。
+    pub version: u32,
+    /// 迁移说明。
+    pub description: String,
+    /// 是否修改了数据。
+    pub changed: bool,
+}
+
+/// 迁移报告（实际执行或预演）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// 迁移前的版本号（0 表示引入版本号之前写入的数据）。
+    pub from_version: u32,
+    /// 迁移后的版本号。
+    pub to_version: u32,
+    /// 执行（或将要执行）的步骤。
+    pub steps: Vec<MigrationStepReport>,
+}
+
+impl MigrationReport {
+    /// 是否需要迁移（版本号落后于当前版本）。
+    pub fn is_needed(&self) -> bool {
+        self.from_version < self.to_version
+    }
+}
+
+/// 检查数据版本是否受支持：由更新版本写入的数据拒绝打开（避免保存时静默丢弃未知字段）。
+pub fn ensure_supported(schema_version: u32) -> AppResult<()> {
+    if schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(AppError::SchemaTooNew {
+            found: schema_version,
+            supported: CURRENT_SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// 从原始 JSON 读取数据版本并检查（反序列化前调用，缺失时视为 0）。
+pub fn ensure_supported_value(value: &serde_json::Value) -> AppResult<()> {
+    let version = value
+        .get("schemaVersion")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    ensure_supported(u32::try_from(version).unwrap_or(u32::MAX))
+}
+
+/// 依次执行版本号高于数据当前版本的迁移步骤，并将版本号提升到当前版本（有步骤改动数据时登记全部记录待重写）。
+pub fn migrate(data: &mut AppData) -> AppResult<MigrationReport> {
+    ensure_supported(data.schema_version)?;
+    let from_version = data.schema_version;
+    let steps: Vec<MigrationStepReport> = MIGRATIONS
+        .iter()
+        .filter(|step| step.version > from_version)
+        .map(|step| MigrationStepReport {
+            version: step.version,
+            description: step.description.to_string(),
+            changed: (step.apply)(data),
+        })
+        .collect();
+    data.schema_version = CURRENT_SCHEMA_VERSION;
+    if steps.iter().any(|step| step.changed) {
+        data.touch_all_records();
+    }
+    Ok(MigrationReport {
+        from_version,
+        to_version: CURRENT_SCHEMA_VERSION,
+        steps,
+    })
+}
+
+/// 预演迁移：在副本上执行并返回报告，不修改传入的数据。
+pub fn dry_run(data: &AppData) -> AppResult<MigrationReport> {
+    migrate(&mut data.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{HistoryDay, HistoryRecord};
+
+    /// 构造一份“引入版本号之前”的旧数据（含一条字符串标签的工作记录）。
+    fn legacy_data() -> AppData {
+        let mut data = AppData {
+            schema_version: 0,
+            ..AppData::default()
+        };
+        data.blacklist_templates.clear();
+        data.tags = vec!["工作".to_string()];
+        data.tag_defs.clear();
+        data.history = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![HistoryRecord {
+                id: String::new(),
+                ..HistoryRecord::for_test("工作", "09:00").with_end("09:25")
+            }],
+        }];
+        data
+    }
+
+    /// 注册表：版本号严格递增，且最后一步即当前版本。
+    #[test]
+    fn registry_is_ordered_and_ends_at_current_version() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(
+            MIGRATIONS.last().map(|step| step.version),
+            Some(CURRENT_SCHEMA_VERSION)
+        );
+    }
+
+    /// `migrate`：旧数据应执行全部步骤并提升版本号；再次执行不再有步骤。
+    #[test]
+    fn migrate_runs_pending_steps_and_bumps_version() {
+        let mut data = legacy_data();
+        let report = migrate(&mut data).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CURRENT_SCHEMA_VERSION);
+        let versions: Vec<u32> = report.steps.iter().map(|s| s.version).collect();
+        assert_eq!(versions, vec![2, 4, 5, 6, 7, 8]);
+        // v8 只新增字段：旧数据取默认值，无需改动。
+        assert!(report.steps.iter().all(|s| s.changed == (s.version != 8)));
+        assert_eq!(data.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(data.total_pomodoros, 1);
+        assert!(data.history[0].records[0].tag_id.is_some());
+        assert!(!data.history[0].records[0].id.is_empty());
+
+        let again = migrate(&mut data).unwrap();
+        assert!(!again.is_needed());
+        assert!(again.steps.is_empty());
+    }
+
+    /// `migrate`：只执行版本号高于数据当前版本的步骤。
+    #[test]
+    fn migrate_skips_applied_steps() {
+        let mut data = AppData {
+            schema_version: 5,
+            ..legacy_data()
+        };
+        let report = migrate(&mut data).unwrap();
+        let versions: Vec<u32> = report.steps.iter().map(|s| s.version).collect();
+        assert_eq!(versions, vec![6, 7, 8]);
+        assert_eq!(data.total_pomodoros, 0);
+        assert!(data.history[0].records[0].started_at.is_none());
+    }
+
+    /// `dry_run`：应返回与实际迁移一致的报告，且不修改原数据。
+    #[test]
+    fn dry_run_does_not_modify_data() {
+        let data = legacy_data();
+        let report = dry_run(&data).unwrap();
+        assert!(report.is_needed());
+        assert_eq!(report.steps.len(), MIGRATIONS.len());
+        assert_eq!(data.schema_version, 0);
+        assert!(data.tag_defs.is_empty());
+    }
+
+    /// 更新版本写入的数据：应拒绝迁移与打开。
+    #[test]
+    fn newer_schema_is_refused() {
+        let mut data = AppData {
+            schema_version: CURRENT_SCHEMA_VERSION + 1,
+            ..AppData::default()
+        };
+        assert!(matches!(
+            migrate(&mut data),
+            Err(AppError::SchemaTooNew { found, supported })
+                if found == CURRENT_SCHEMA_VERSION + 1 && supported == CURRENT_SCHEMA_VERSION
+        ));
+
+        let value = serde_json::json!({ "schemaVersion": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(ensure_supported_value(&value).is_err());
+        assert!(ensure_supported_value(&serde_json::json!({})).is_ok());
+    }
+}
@@ -0,0 +1,241 @@
+//! 多配置（profile）：每个配置拥有独立的 store 文件、历史数据库与备份目录，注册表记录全部配置与当前配置。
+//!
+//! 默认配置沿用 `data/` 下的原有文件，其余配置位于 `data/profiles/<id>/`。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::errors::{AppError, AppResult};
+
+/// 默认配置 id（数据位于 `data/` 根目录，兼容引入配置之前的数据）。
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// 配置注册表文件名（位于 `data/`）。
+pub const PROFILES_FILE_NAME: &str = "profiles.json";
+
+/// 非默认配置的数据子目录名。
+const PROFILES_DIR_NAME: &str = "profiles";
+
+/// 配置名称最大长度（字符）。
+const MAX_PROFILE_NAME_CHARS: usize = 32;
+
+/// 单个配置的描述。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    /// 配置 id（默认配置为 `default`，其余为 uuid）。
+    pub id: String,
+    /// 展示名称。
+    pub name: String,
+    /// 创建时间（RFC 3339；默认配置为空）。
+    #[serde(default)]
+    pub created_at: String,
+}
+
+impl ProfileInfo {
+    /// 默认配置。
+    pub fn default_profile() -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "默认".to_string(),
+            created_at: String::new(),
+        }
+    }
+}
+
+/// 配置注册表：全部配置与当前配置 id。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct ProfileRegistry {
+    /// 当前配置 id。
+    pub active: String,
+    /// 全部配置（默认配置总在首位）。
+    pub profiles: Vec<ProfileInfo>,
+}
+
+impl Default for ProfileRegistry {
+    /// 仅包含默认配置的注册表。
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![ProfileInfo::default_profile()],
+        }
+    }
+}
+
+impl ProfileRegistry {
+    /// 从数据目录读取注册表（文件缺失时返回默认注册表；无法解析时记录日志并回退默认）。
+    pub fn load(data_dir: &Path) -> AppResult<Self> {
+        let path = data_dir.join(PROFILES_FILE_NAME);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(AppError::Invariant(format!("读取配置注册表失败：{e}"))),
+        };
+        let mut registry = match serde_json::from_slice::<Self>(&bytes) {
+            Ok(registry) => registry,
+            Err(e) => {
+                tracing::warn!(target: "storage", "配置注册表无法解析，已回退为默认配置：{e}");
+                return Ok(Self::default());
+            }
+        };
+        registry.normalize();
+        Ok(registry)
+    }
+
+    /// 写入注册表到数据目录。
+    pub fn save(&self, data_dir: &Path) -> AppResult<()> {
+        std::fs::create_dir_all(data_dir)
+            .map_err(|e| AppError::Invariant(format!("创建数据目录失败：{e}")))?;
+        let bytes = serde_json::to_vec_pretty(self)?;
+        std::fs::write(data_dir.join(PROFILES_FILE_NAME), bytes)
+            .map_err(|e| AppError::Invariant(format!("写入配置注册表失败：{e}")))
+    }
+
+    /// 确保默认配置存在且位于首位，当前配置无效时回退为默认配置。
+    fn normalize(&mut self) {
+        self.profiles.retain(|p| p.id != DEFAULT_PROFILE_ID);
+        self.profiles.insert(0, ProfileInfo::default_profile());
+        if self.find(&self.active).is_none() {
+            self.active = DEFAULT_PROFILE_ID.to_string();
+        }
+    }
+
+    /// 按 id 查找配置。
+    pub fn find(&self, id: &str) -> Option<&ProfileInfo> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// 当前配置。
+    pub fn active_profile(&self) -> ProfileInfo {
+        self.find(&self.active)
+            .cloned()
+            .unwrap_or_else(ProfileInfo::default_profile)
+    }
+
+    /// 新建配置（名称去首尾空白后不可为空、不可重名）。
+    pub fn create(
+        &mut self,
+        name: &str,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> AppResult<ProfileInfo> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("配置名称不能为空".to_string()));
+        }
+        if name.chars().count() > MAX_PROFILE_NAME_CHARS {
+            return Err(AppError::Validation(format!(
+                "配置名称不能超过 {MAX_PROFILE_NAME_CHARS} 个字符"
+            )));
+        }
+        if self.profiles.iter().any(|p| p.name == name) {
+            return Err(AppError::Validation("配置名称已存在".to_string()));
+        }
+        let profile = ProfileInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            created_at: now.to_rfc3339(),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+}
+
+/// 配置的数据目录：默认配置为 `data_dir` 本身，其余为 `data_dir/profiles/<id>`。
+pub fn profile_dir(data_dir: &Path, profile_id: &str) -> PathBuf {
+    if profile_id == DEFAULT_PROFILE_ID {
+        data_dir.to_path_buf()
+    } else {
+        data_dir.join(PROFILES_DIR_NAME).join(profile_id)
+    }
+}
+
+/// 配置的备份目录：默认配置为 `backup_dir` 本身，其余为 `backup_dir/profiles/<id>`。
+pub fn profile_backup_dir(backup_dir: &Path, profile_id: &str) -> PathBuf {
+    profile_dir(backup_dir, profile_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone as _;
+
+    /// 构造一个固定的本地时刻。
+    fn now() -> chrono::DateTime<chrono::Local> {
+        chrono::Local
+            .with_ymd_and_hms(2025, 1, 2, 8, 0, 0)
+            .single()
+            .unwrap()
+    }
+
+    /// `load/save`：文件缺失时为默认注册表；新建配置后可原样读回。
+    #[test]
+    fn registry_round_trips_and_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = ProfileRegistry::load(dir.path()).unwrap();
+        assert_eq!(registry, ProfileRegistry::default());
+        assert_eq!(registry.active_profile().id, DEFAULT_PROFILE_ID);
+
+        let work = registry.create(" 工作 ", now()).unwrap();
+        assert_eq!(work.name, "工作");
+        registry.active = work.id.clone();
+        registry.save(dir.path()).unwrap();
+
+        let loaded = ProfileRegistry::load(dir.path()).unwrap();
+        assert_eq!(loaded, registry);
+        assert_eq!(loaded.active_profile(), work);
+    }
+
+    /// `create`：空名称、超长名称与重名应被拒绝。
+    #[test]
+    fn create_rejects_invalid_names() {
+        let mut registry = ProfileRegistry::default();
+        registry.create("学习", now()).unwrap();
+        for name in ["  ", "学习", &"长".repeat(MAX_PROFILE_NAME_CHARS + 1)] {
+            assert!(matches!(
+                registry.create(name, now()),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert_eq!(registry.profiles.len(), 2);
+    }
+
+    /// `load`：缺失默认配置或当前配置无效时应自动修正；无法解析时回退默认。
+    #[test]
+    fn load_normalizes_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROFILES_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"{"active":"missing","profiles":[{"id":"a","name":"工作"}]}"#,
+        )
+        .unwrap();
+        let registry = ProfileRegistry::load(dir.path()).unwrap();
+        let ids: Vec<&str> = registry.profiles.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec![DEFAULT_PROFILE_ID, "a"]);
+        assert_eq!(registry.active, DEFAULT_PROFILE_ID);
+
+        std::fs::write(&path, b"{").unwrap();
+        assert_eq!(
+            ProfileRegistry::load(dir.path()).unwrap(),
+            ProfileRegistry::default()
+        );
+    }
+
+    /// `profile_dir`：默认配置沿用数据目录，其余配置位于 `profiles/<id>`。
+    #[test]
+    fn profile_dir_keeps_default_at_root() {
+        let root = Path::new("/data");
+        assert_eq!(profile_dir(root, DEFAULT_PROFILE_ID), root);
+        assert_eq!(profile_dir(root, "abc"), root.join("profiles").join("abc"));
+        assert_eq!(
+            profile_backup_dir(Path::new("/backups"), "abc"),
+            Path::new("/backups").join("profiles").join("abc")
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! store 损坏恢复：隔离无法解析的 store 文件，并逐段抢救仍可反序列化的数据（设置、标签、每个历史日等）。
+//!
+//! 被丢弃的数据段以路径形式记录（如 `settings.pomodoro`、`history[3]`），通过日志与前端事件告知用户。
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use crate::app_data::{
+    AppData, BlacklistItem, BlacklistTemplate, CustomAudio, HistoryDay, InterruptionDay,
+    SessionPlan, Settings, Tag, Task,
+};
+use crate::errors::{AppError, AppResult};
+
+/// 前端事件：启动时 store 已损坏并完成恢复（负载为 `StoreRecoveryReport`）。
+pub const EVENT_STORE_RECOVERED: &str = "pomodoro://store_recovered";
+
+/// 隔离文件名中的时间格式（本地时间，精确到毫秒以避免重名）。
+const QUARANTINE_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+/// store 恢复报告（启动时生成，供前端提示用户）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct StoreRecoveryReport {
+    /// 被隔离的原始 store 副本路径。
+    pub quarantined_path: String,
+    /// 整个文件无法解析（此时仅历史数据库中的记录得以保留）。
+    pub file_unreadable: bool,
+    /// 被丢弃的数据段路径（如 `settings.pomodoro`、`history[3]`）。
+    pub dropped: Vec<String>,
+}
+
+/// 将 store 文件复制到隔离目录，返回副本路径（原文件保持不动，由调用方决定是否覆盖/删除）。
+pub fn quarantine_store_file(
+    store_path: &Path,
+    quarantine_dir: &Path,
+    now: chrono::DateTime<chrono::Local>,
+) -> AppResult<PathBuf> {
+    std::fs::create_dir_all(quarantine_dir)
+        .map_err(|e| AppError::Invariant(format!("创建隔离目录失败：{e}")))?;
+    let file_name = store_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "store.json".to_string());
+    let target = quarantine_dir.join(format!(
+        "{}-{}",
+        now.format(QUARANTINE_TIME_FORMAT),
+        file_name
+    ));
+    std::fs::copy(store_path, &target)
+        .map_err(|e| AppError::Invariant(format!("隔离 store 文件失败：{e}")))?;
+    Ok(target)
+}
+
+/// 启动前检查 store 文件：若整个文件无法解析为 JSON 对象，则隔离并删除原文件（避免被默认数据静默覆盖）。
+///
+/// 文件不存在或可正常解析时返回 `None`。
+pub fn quarantine_unparsable_store(
+    store_path: &Path,
+    quarantine_dir: &Path,
+    now: chrono::DateTime<chrono::Local>,
+) -> AppResult<Option<StoreRecoveryReport>> {
+    let bytes = match std::fs::read(store_path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::Invariant(format!("读取 store 文件失败：{e}"))),
+    };
+    let err = match serde_json::from_slice::<Map<String, Value>>(&bytes) {
+        Ok(_) => return Ok(None),
+        Err(e) => e,
+    };
+
+    let quarantined = quarantine_store_file(store_path, quarantine_dir, now)?;
+    std::fs::remove_file(store_path)
+        .map_err(|e| AppError::Invariant(format!("移除损坏的 store 文件失败：{e}")))?;
+    tracing::warn!(
+        target: "storage",
+        "store 文件无法解析，已隔离：{} -> {} err={}",
+        store_path.to_string_lossy(),
+        quarantined.to_string_lossy(),
+        err
+    );
+    Ok(Some(StoreRecoveryReport {
+        quarantined_path: quarantined.to_string_lossy().to_string(),
+        file_unreadable: true,
+        dropped: Vec::new(),
+    }))
+}
+
+/// 逐段抢救 `AppData`：列表字段逐项校验，其余字段逐个合并到默认值上，返回恢复后的数据与被丢弃的路径。
+pub fn salvage_app_data(value: Value) -> (AppData, Vec<String>) {
+    let mut dropped = Vec::new();
+    let Value::Object(fields) = value else {
+        dropped.push("$".to_string());
+        return (AppData::default(), dropped);
+    };
+
+    let fields = fields
+        .into_iter()
+        .map(|(key, field)| {
+            let field = salvage_section(&key, field, &mut dropped);
+            (key, field)
+        })
+        .collect();
+    // 版本号缺失时按最旧数据处理，确保后续迁移完整执行。
+    let base = AppData {
+        schema_version: 0,
+        ..AppData::default()
+    };
+    let data = salvage_fields(base, fields, "", &mut dropped);
+    (data, dropped)
+}
+
+/// 按字段名抢救单个数据段（设置逐字段、列表逐项）；其他字段原样返回。
+fn salvage_section(key: &str, field: Value, dropped: &mut Vec<String>) -> Value {
+    match key {
+        "settings" => {
+            let Value::Object(fields) = field else {
+                dropped.push(key.to_string());
+                return serde_json::to_value(Settings::default()).unwrap_or(Value::Null);
+            };
+            let settings = salvage_fields(Settings::default(), fields, key, dropped);
+            serde_json::to_value(settings).unwrap_or(Value::Null)
+        }
+        "blacklist" => salvage_list::<BlacklistItem>(key, field, dropped),
+        "blacklistTemplates" => salvage_list::<BlacklistTemplate>(key, field, dropped),
+        "tags" => salvage_list::<String>(key, field, dropped),
+        "tagDefs" => salvage_list::<Tag>(key, field, dropped),
+        "history" | "historyDev" => salvage_list::<HistoryDay>(key, field, dropped),
+        "customAudios" => salvage_list::<CustomAudio>(key, field, dropped),
+        "interruptions" => salvage_list::<InterruptionDay>(key, field, dropped),
+        "sessionPlans" => salvage_list::<SessionPlan>(key, field, dropped),
+        "tasks" => salvage_list::<Task>(key, field, dropped),
+        _ => field,
+    }
+}
+
+/// 逐项校验列表：仅保留可反序列化为 `T` 的元素（非数组时整体丢弃并返回空数组）。
+fn salvage_list<T: DeserializeOwned>(key: &str, field: Value, dropped: &mut Vec<String>) -> Value {
+    let Value::Array(items) = field else {
+        dropped.push(key.to_string());
+        return Value::Array(Vec::new());
+    };
+    let kept = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            if serde_json::from_value::<T>(item.clone()).is_ok() {
+                Some(item)
+            } else {
+                dropped.push(format!("{key}[{i}]"));
+                None
+            }
+        })
+        .collect();
+    Value::Array(kept)
+}
+
+/// 将字段逐个合并到 `base` 的序列化结果上：合并后仍可反序列化则保留，否则回退并记录被丢弃的字段。
+fn salvage_fields<T: Serialize + DeserializeOwned>(
+    base: T,
+    fields: Map<String, Value>,
+    section: &str,
+    dropped: &mut Vec<String>,
+) -> T {
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(&base) else {
+        return base;
+    };
+    for (key, field) in fields {
+        let previous = merged.insert(key.clone(), field);
+        if serde_json::from_value::<T>(Value::Object(merged.clone())).is_err() {
+            match previous {
+                Some(previous) => merged.insert(key.clone(), previous),
+                None => merged.remove(&key),
+            };
+            dropped.push(if section.is_empty() {
+                key
+            } else {
+                format!("{section}.{key}")
+            });
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone as _;
+
+    /// 构造一个固定的本地时刻。
+    fn now() -> chrono::DateTime<chrono::Local> {
+        chrono::Local
+            .with_ymd_and_hms(2025, 1, 2, 8, 0, 0)
+            .single()
+            .unwrap()
+    }
+
+    /// 构造一条可正常解析的历史日 JSON。
+    fn history_day(date: &str) -> Value {
+        serde_json::to_value(HistoryDay {
+            date: date.to_string(),
+            records: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    /// `salvage_app_data`：损坏的历史日与设置字段应被单独丢弃，其余数据完整保留。
+    #[test]
+    fn salvage_keeps_valid_sections_and_reports_dropped() {
+        let mut value = serde_json::to_value(AppData {
+            total_pomodoros: 7,
+            ..AppData::default()
+        })
+        .unwrap();
+        value["settings"]["pomodoro"] = Value::String("abc".to_string());
+        value["settings"]["longBreak"] = Value::from(30);
+        value["history"] = Value::Array(vec![
+            history_day("2025-01-01"),
+            serde_json::json!({ "date": 20250102 }),
+            history_day("2025-01-03"),
+        ]);
+        value["tags"] = serde_json::json!(["工作", 1, "学习"]);
+
+        let (data, mut dropped) = salvage_app_data(value);
+        dropped.sort();
+        assert_eq!(
+            dropped,
+            vec![
+                "history[1]".to_string(),
+                "settings.pomodoro".to_string(),
+                "tags[1]".to_string(),
+            ]
+        );
+        assert_eq!(data.total_pomodoros, 7);
+        assert_eq!(data.settings.pomodoro, Settings::default().pomodoro);
+        assert_eq!(data.settings.long_break, 30);
+        let dates: Vec<&str> = data.history.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-01-01", "2025-01-03"]);
+        assert_eq!(data.tags, vec!["工作".to_string(), "学习".to_string()]);
+    }
+
+    /// `salvage_app_data`：顶层标量字段类型错误时回退默认值；根节点非对象时整体回退。
+    #[test]
+    fn salvage_falls_back_to_defaults() {
+        let mut value = serde_json::to_value(AppData::default()).unwrap();
+        value["totalPomodoros"] = Value::String("many".to_string());
+        value["settings"] = Value::Null;
+        let (data, mut dropped) = salvage_app_data(value);
+        dropped.sort();
+        assert_eq!(
+            dropped,
+            vec!["settings".to_string(), "totalPomodoros".to_string()]
+        );
+        assert_eq!(data.total_pomodoros, 0);
+
+        let (_, dropped) = salvage_app_data(Value::Array(Vec::new()));
+        assert_eq!(dropped, vec!["$".to_string()]);
+    }
+
+    /// `quarantine_unparsable_store`：可解析的文件不处理；无法解析的文件移入隔离目录。
+    #[test]
+    fn quarantine_unparsable_store_moves_broken_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join("pomodoro-data.json");
+        let quarantine_dir = dir.path().join("quarantine");
+
+        assert_eq!(
+            quarantine_unparsable_store(&store_path, &quarantine_dir, now()).unwrap(),
+            None
+        );
+        std::fs::write(&store_path, b"{\"appData\": {}}").unwrap();
+        assert_eq!(
+            quarantine_unparsable_store(&store_path, &quarantine_dir, now()).unwrap(),
+            None
+        );
+
+        std::fs::write(&store_path, b"{\"appData\": {").unwrap();
+        let report = quarantine_unparsable_store(&store_path, &quarantine_dir, now())
+            .unwrap()
+            .unwrap();
+        assert!(report.file_unreadable);
+        assert!(!store_path.exists());
+        let quarantined = PathBuf::from(&report.quarantined_path);
+        assert_eq!(
+            quarantined.file_name().unwrap().to_string_lossy(),
+            "20250102-080000000-pomodoro-data.json"
+        );
+        assert_eq!(std::fs::read(quarantined).unwrap(), b"{\"appData\": {");
+    }
+}
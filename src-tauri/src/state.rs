@@ -4,21 +4,26 @@ use std::sync::Mutex;
 
 use tauri::Emitter as _;
 
-use crate::app_data::{AppData, STORE_KEY};
+use crate::app_data::{AppData, HistoryDay, InterruptionDay, Settings, STORE_KEY, TIMER_STORE_KEY};
 use crate::errors::{AppError, AppResult};
+use crate::history_db::HistoryDb;
 use crate::timer::{TickResult, TimerClock, TimerRuntime, TimerSnapshot, WorkCompletedEvent};
 use crate::tray::TrayHandles;
+use crate::undo::UndoStack;
 
 /// 后端全局状态（通过 `app.manage(...)` 注入 Tauri State）。
 pub struct AppState {
     app: tauri::AppHandle,
-    store: std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>,
+    profile: Mutex<crate::profiles::ProfileInfo>,
+    store: Mutex<std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>>,
+    history_db: Mutex<HistoryDb>,
     data: Mutex<AppData>,
     timer: Mutex<TimerRuntime>,
     audio: crate::audio::AudioController,
     combo: Mutex<crate::combo::ComboRuntime>,
     tray: Mutex<Option<TrayHandles>>,
     window_mode: Mutex<WindowModeState>,
+    store_recovery: Mutex<Option<crate::recovery::StoreRecoveryReport>>,
 }
 
 /// 窗口模式运行态（用于迷你模式恢复窗口大小/位置）。
@@ -33,14 +38,15 @@ pub struct WindowModeState {
 }
 
 impl AppState {
-    /// 创建应用状态并初始化计时器为“工作阶段 + 默认时长”。
+    /// 创建应用状态（计时器运行态由调用方从检查点恢复或新建）。
     pub fn new(
         app: tauri::AppHandle,
+        profile: crate::profiles::ProfileInfo,
         store: std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>,
+        history_db: HistoryDb,
         data: AppData,
+        timer: TimerRuntime,
     ) -> AppResult<Self> {
-        let clock = crate::timer::SystemClock;
-        let timer = TimerRuntime::new(&data.settings, &data.tags, &clock);
         let audio_dir = crate::app_paths::app_audio_dir(&app)?;
         if let Ok(removed) = crate::audio::cleanup_legacy_builtin_audio_files(&audio_dir) {
             if removed > 0 {
@@ -55,19 +61,95 @@ impl AppState {
         audio.update_custom_audios(data.custom_audios.clone())?;
         Ok(Self {
             app,
-            store,
+            profile: Mutex::new(profile),
+            store: Mutex::new(store),
+            history_db: Mutex::new(history_db),
             data: Mutex::new(data),
             timer: Mutex::new(timer),
             audio,
             combo: Mutex::new(crate::combo::ComboRuntime::new()),
             tray: Mutex::new(None),
             window_mode: Mutex::new(WindowModeState::default()),
+            store_recovery: Mutex::new(None),
         })
     }
 
     /// 读取一份 `AppData` 的快照（用于向前端返回）。
     pub fn data_snapshot(&self) -> AppData {
-        self.data.lock().unwrap().clone()
+        self.data.lock().unwrap().resolved()
+    }
+
+    /// 读取设置快照（避免为读取设置克隆整个 `AppData`）。
+    pub fn settings_snapshot(&self) -> Settings {
+        self.data.lock().unwrap().settings.clone()
+    }
+
+    /// 按自然日期闭区间从历史数据库查询历史（开发环境：`history_dev` 非空时优先）。
+    ///
+    /// 范围早于归档线时按需读取归档文件并合并（开发环境测试历史不参与归档）。
+    pub fn history_in_range(&self, from: &str, to: &str) -> AppResult<Vec<HistoryDay>> {
+        let (dev, before, paths) = {
+            let data = self.data.lock().unwrap();
+            (
+                cfg!(debug_assertions) && !data.history_dev.is_empty(),
+                data.history_archive.before.clone(),
+                data.tag_paths(),
+            )
+        };
+        let (days, cipher) = {
+            let history_db = self.history_db.lock().unwrap();
+            (
+                history_db.history_in_range(dev, from, to)?,
+                history_db.cipher().cloned(),
+            )
+        };
+        let mut days = if dev {
+            days
+        } else {
+            let profile_dir =
+                crate::app_paths::profile_data_dir(&self.app, &self.active_profile().id)?;
+            crate::archive::include_archived(
+                &crate::archive::archive_dir(&profile_dir),
+                cipher.as_ref(),
+                before.as_deref(),
+                from,
+                to,
+                days,
+            )?
+        };
+        crate::app_data::resolve_history_tags(&mut days, &paths);
+        Ok(days)
+    }
+
+    /// 逻辑日期 `date` 已完成的工作番茄数（已同步的日期按历史数据库聚合）。
+    pub fn completed_on(&self, date: &str) -> u32 {
+        let data = self.data.lock().unwrap();
+        let history_db = self.history_db.lock().unwrap();
+        let counts = crate::timer::HistoryCounts::new(&data, Some(&*history_db));
+        crate::timer::compute_today_stats(counts, date).total
+    }
+
+    /// 查询某个主体在审计日志中的变更历史。
+    pub fn change_history(&self, subject: &str) -> AppResult<Vec<crate::audit::AuditEntry>> {
+        self.history_db.lock().unwrap().change_history(subject)
+    }
+
+    /// 读取审计日志中的全部事件。
+    pub fn audit_events(&self) -> AppResult<Vec<crate::audit::DomainEvent>> {
+        self.history_db.lock().unwrap().events()
+    }
+
+    /// 读取审计基线引用的数据快照。
+    pub fn audit_snapshot(&self, id: i64) -> AppResult<AppData> {
+        self.history_db.lock().unwrap().snapshot(id)
+    }
+
+    /// 按自然日期闭区间从历史数据库查询中断记录。
+    pub fn interruptions_in_range(&self, from: &str, to: &str) -> AppResult<Vec<InterruptionDay>> {
+        self.history_db
+            .lock()
+            .unwrap()
+            .interruptions_in_range(from, to)
     }
 
     /// 读取黑名单进程名列表（用于后台守护/终止逻辑，避免克隆整个 `AppData`）。
@@ -85,7 +167,8 @@ impl AppState {
     pub fn timer_snapshot(&self) -> TimerSnapshot {
         let data = self.data.lock().unwrap();
         let timer = self.timer.lock().unwrap();
-        timer.snapshot(&data)
+        let history_db = self.history_db.lock().unwrap();
+        timer.snapshot(&data, Some(&*history_db))
     }
 
     /// 设置托盘句柄，供后续更新图标/菜单。
@@ -98,6 +181,19 @@ impl AppState {
         self.tray.lock().unwrap().clone()
     }
 
+    /// 记录启动时的 store 恢复报告，并推送事件给前端（前端也可在首屏通过命令拉取）。
+    pub fn report_store_recovery(&self, report: crate::recovery::StoreRecoveryReport) {
+        *self.store_recovery.lock().unwrap() = Some(report.clone());
+        let _ = self
+            .app
+            .emit(crate::recovery::EVENT_STORE_RECOVERED, report);
+    }
+
+    /// 获取本次启动的 store 恢复报告（未发生恢复时为 `None`）。
+    pub fn store_recovery(&self) -> Option<crate::recovery::StoreRecoveryReport> {
+        self.store_recovery.lock().unwrap().clone()
+    }
+
     /// 获取窗口模式状态快照（用于命令内部决策）。
     pub fn window_mode_snapshot(&self) -> WindowModeState {
         self.window_mode.lock().unwrap().clone()
@@ -125,21 +221,22 @@ impl AppState {
     ) -> AppResult<T> {
         let mut data = self.data.lock().unwrap();
         let out = f(&mut data)?;
-        self.persist_locked(&data)?;
+        self.persist_locked(&mut data)?;
         Ok(out)
     }
 
-    /// 修改计时器运行态（不会持久化）。
+    /// 修改计时器运行态（仅写入计时器检查点，不持久化 `AppData`）。
     pub fn update_timer(
         &self,
         f: impl FnOnce(&mut TimerRuntime, &AppData) -> AppResult<()>,
     ) -> AppResult<()> {
         let data = self.data.lock().unwrap();
         let mut timer = self.timer.lock().unwrap();
-        f(&mut timer, &data)
+        f(&mut timer, &data)?;
+        self.persist_timer_locked(&timer)
     }
 
-    /// 同时修改 `AppData` 与 `TimerRuntime`（需要时可持久化）。
+    /// 同时修改 `AppData` 与 `TimerRuntime`（需要时可持久化；计时器检查点总会写入）。
     pub fn update_data_and_timer<T>(
         &self,
         f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
@@ -149,11 +246,49 @@ impl AppState {
         let mut timer = self.timer.lock().unwrap();
         let out = f(&mut data, &mut timer)?;
         if persist {
-            self.persist_locked(&data)?;
+            self.persist_locked(&mut data)?;
         }
+        self.persist_timer_locked(&timer)?;
         Ok(out)
     }
 
+    /// 可撤销的原子更新：修改并持久化数据后，把操作登记过的片段的前后值压入撤销栈（清空重做栈）。
+    pub fn update_undoable<T>(
+        &self,
+        description: &str,
+        f: impl FnOnce(&mut AppData, &mut TimerRuntime) -> AppResult<T>,
+    ) -> AppResult<T> {
+        let mut data = self.data.lock().unwrap();
+        let mut timer = self.timer.lock().unwrap();
+        data.begin_undo_capture();
+        let out = f(&mut data, &mut timer);
+        let capture = data.take_undo_capture();
+        let out = out?;
+        self.persist_locked(&mut data)?;
+        if let Some(entry) = crate::undo::UndoEntry::from_capture(description, capture, &data)? {
+            let mut history_db = self.history_db.lock().unwrap();
+            history_db.clear_undo(UndoStack::Redo)?;
+            history_db.push_undo(UndoStack::Undo, &entry)?;
+        }
+        self.persist_timer_locked(&timer)?;
+        Ok(out)
+    }
+
+    /// 取出撤销栈或重做栈顶的操作。
+    pub fn pop_undo(&self, stack: UndoStack) -> AppResult<Option<crate::undo::UndoEntry>> {
+        self.history_db.lock().unwrap().pop_undo(stack)
+    }
+
+    /// 向撤销栈或重做栈压入操作。
+    pub fn push_undo(&self, stack: UndoStack, entry: &crate::undo::UndoEntry) -> AppResult<()> {
+        self.history_db.lock().unwrap().push_undo(stack, entry)
+    }
+
+    /// 待撤销与待重做操作的描述。
+    pub fn undo_state(&self) -> AppResult<crate::undo::UndoState> {
+        self.history_db.lock().unwrap().undo_state()
+    }
+
     /// 获取音频控制器引用（命令层用于触发播放/暂停/音量等操作）。
     pub fn audio_controller(&self) -> &crate::audio::AudioController {
         &self.audio
@@ -223,25 +358,38 @@ impl AppState {
         Ok(())
     }
 
-    /// 执行一次 tick：若计时器运行中则可能写入历史并持久化。
+    /// 执行一次 tick：若计时器运行中则可能写入历史/中断并持久化。
     pub fn tick(&self) -> AppResult<TickResult> {
         let mut data = self.data.lock().unwrap();
         let mut timer = self.timer.lock().unwrap();
         let clock = crate::timer::SystemClock;
         let notifier = crate::timer::TauriNotifier::new(&self.app);
-        let result = timer.tick(&mut data, &clock, &notifier)?;
+        let result = {
+            let history_db = self.history_db.lock().unwrap();
+            timer.tick(&mut data, Some(&*history_db), &clock, &notifier)?
+        };
         let mut persist_needed = result.history_changed;
 
         if result.work_auto_started {
             self.combo.lock().unwrap().on_work_started(&clock)?;
         }
 
+        if result.work_interrupted {
+            // 挂起期间到期且记为中断：与手动中断一致地打断 Combo。
+            self.combo.lock().unwrap().on_interrupted(&mut data);
+        }
+
         if let Some(payload) = result.work_completed_event.clone() {
-            let today = clock.today_date();
-            let today_completed_after = crate::timer::compute_today_stats(&data, &today).total;
+            let today = clock.logical_today(data.settings.day_boundary());
+            let today_completed_after = {
+                let history_db = self.history_db.lock().unwrap();
+                let counts = crate::timer::HistoryCounts::new(&data, Some(&*history_db));
+                crate::timer::compute_today_stats(counts, &today).total
+            };
             let daily_goal_reached =
                 data.settings.daily_goal > 0 && today_completed_after == data.settings.daily_goal;
 
+            data.touch_section(crate::app_data::Section::Counters);
             data.total_pomodoros = data.total_pomodoros.saturating_add(1);
             persist_needed = true;
 
@@ -272,7 +420,10 @@ impl AppState {
         );
 
         if persist_needed {
-            self.persist_locked(&data)?;
+            self.persist_locked(&mut data)?;
+        }
+        if result.phase_ended || result.resumed_from_suspend {
+            self.persist_timer_locked(&timer)?;
         }
         Ok(result)
     }
@@ -332,70 +483,212 @@ impl AppState {
         self.combo.lock().unwrap().on_interrupted(data);
     }
 
-    /// 退出前记录“quit 中断”（仅在工作阶段已开始且启用记录中断时写入）。
+    /// 退出前记录“quit 中断”并重置计时器（仅在工作阶段已开始且启用记录中断时），数据与检查点在同一锁内持久化。
     pub fn record_quit_interruption_before_exit(&self) -> AppResult<()> {
-        let wrote = self.update_data_and_timer(
+        self.update_data_and_timer(
             |data, timer_runtime| {
-                if !data.settings.interruption.enabled {
-                    return Ok(false);
-                }
-                if timer_runtime.phase != crate::app_data::Phase::Work
-                    || !timer_runtime.is_work_started()
-                {
-                    return Ok(false);
+                if timer_runtime.quit(data, &crate::timer::SystemClock) {
+                    // PRD v4：退出中断也会打断 streak。
+                    self.combo.lock().unwrap().on_interrupted(data);
                 }
+                Ok(())
+            },
+            true,
+        )
+    }
 
-                let timestamp = chrono::Utc::now().to_rfc3339();
-                let date = chrono::Local::now().format("%Y-%m-%d").to_string();
-                let record = crate::app_data::InterruptionRecord {
-                    timestamp,
-                    remaining_seconds: timer_runtime.remaining_seconds,
-                    focused_seconds: timer_runtime.focused_seconds(&data.settings),
-                    reason: String::new(),
-                    r#type: crate::app_data::InterruptionType::Quit,
-                    tag: timer_runtime.current_tag.clone(),
-                };
-
-                // 写入中断记录。
-                {
-                    let list = &mut data.interruptions;
-                    if let Some(idx) = list.iter().position(|d| d.date == date) {
-                        list[idx].records.push(record);
-                    } else {
-                        list.push(crate::app_data::InterruptionDay {
-                            date,
-                            records: vec![record],
-                        });
-                    }
-                }
+    /// 持久化 `AppData`（要求调用方已持有数据锁，避免重复锁）。
+    fn persist_locked(&self, data: &mut AppData) -> AppResult<()> {
+        persist_app_data(&self.store(), &mut self.history_db.lock().unwrap(), data)
+    }
 
-                // PRD v4：退出中断也会打断 streak。
-                self.combo.lock().unwrap().on_interrupted(data);
-                Ok(true)
-            },
-            false,
+    /// 当前配置的 store 句柄。
+    fn store(&self) -> std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>> {
+        self.store.lock().unwrap().clone()
+    }
+
+    /// 获取当前配置。
+    pub fn active_profile(&self) -> crate::profiles::ProfileInfo {
+        self.profile.lock().unwrap().clone()
+    }
+
+    /// 当前配置的备份目录。
+    pub fn backup_dir(&self) -> AppResult<std::path::PathBuf> {
+        crate::app_paths::profile_backup_dir(&self.app, &self.active_profile().id)
+    }
+
+    /// 当前配置的归档目录。
+    pub fn archive_dir(&self) -> AppResult<std::path::PathBuf> {
+        crate::app_paths::profile_data_dir(&self.app, &self.active_profile().id)
+            .map(|dir| crate::archive::archive_dir(&dir))
+    }
+
+    /// 当前配置的加密密钥（未启用加密时为 `None`）。
+    pub fn cipher(&self) -> Option<crate::crypto::Cipher> {
+        self.history_db.lock().unwrap().cipher().cloned()
+    }
+
+    /// 启用、更换或关闭当前配置的加密：以新密钥（或明文）重写 store、历史数据库、已有备份与归档文件。
+    ///
+    /// 启用/更换时先写入加密配置文件，关闭时最后删除，保证中途失败也能以新旧任一状态读取。
+    pub fn set_cipher(&self, cipher: Option<crate::crypto::Cipher>) -> AppResult<()> {
+        let profile_dir = crate::app_paths::profile_data_dir(&self.app, &self.active_profile().id)?;
+        let backup_dir = self.backup_dir()?;
+        let mut data = self.data.lock().unwrap();
+        if let Some(cipher) = &cipher {
+            crate::crypto::EncryptionHeader::new(cipher).save(&profile_dir)?;
+        }
+        let old = {
+            let mut history_db = self.history_db.lock().unwrap();
+            let old = history_db.cipher().cloned();
+            history_db.set_cipher(cipher.clone());
+            history_db.replace_all(&data)?;
+            history_db.reseal_logs(old.as_ref())?;
+            old
+        };
+        self.persist_locked(&mut data)?;
+        if cipher.is_none() {
+            crate::crypto::EncryptionHeader::remove(&profile_dir)?;
+        }
+        let resealed = crate::backup::reseal_backups(
+            &backup_dir,
+            old.as_ref(),
+            cipher.as_ref(),
+            &data.settings.backup,
+        )?;
+        let resealed_archives = crate::archive::reseal(
+            &crate::archive::archive_dir(&profile_dir),
+            old.as_ref(),
+            cipher.as_ref(),
         )?;
+        tracing::info!(
+            target: "storage",
+            "已更新数据加密：enabled={} resealedBackups={} resealedArchives={}",
+            cipher.is_some(),
+            resealed,
+            resealed_archives
+        );
+        Ok(())
+    }
 
-        if wrote {
-            let data = self.data.lock().unwrap();
-            self.persist_locked(&data)?;
+    /// 切换配置：专注锁定中拒绝；先保存当前配置的数据，加载目标配置成功后保存计时器检查点并换入（加载失败时保持不变）。
+    ///
+    /// 运行中的计时器（如休息倒计时）先暂停再保存：否则切回时会按离开的时长补算从未发生的阶段。
+    /// 目标配置已加密时需提供口令。
+    pub fn switch_profile(
+        &self,
+        profile: &crate::profiles::ProfileInfo,
+        passphrase: Option<&str>,
+    ) -> AppResult<()> {
+        let mut data = self.data.lock().unwrap();
+        let mut timer = self.timer.lock().unwrap();
+        if timer.blacklist_locked() {
+            return Err(AppError::Validation("专注进行中，无法切换配置".to_string()));
+        }
+        self.persist_locked(&mut data)?;
+        let loaded = crate::open_profile(&self.app, &profile.id, passphrase)?;
+        timer.pause(&crate::timer::SystemClock);
+        self.persist_timer_locked(&timer)?;
+
+        *self.store.lock().unwrap() = loaded.store;
+        *self.history_db.lock().unwrap() = loaded.history_db;
+        *data = loaded.data;
+        *timer = loaded.timer;
+        *self.combo.lock().unwrap() = crate::combo::ComboRuntime::new();
+        *self.profile.lock().unwrap() = profile.clone();
+        self.audio
+            .update_custom_audios(data.custom_audios.clone())
+            .ok();
+        drop(timer);
+        drop(data);
+
+        tracing::info!(
+            target: "storage",
+            "已切换配置：id={} name={}",
+            profile.id,
+            profile.name
+        );
+        if let Some(report) = loaded.recovery {
+            self.report_store_recovery(report);
         }
         Ok(())
     }
 
-    /// 持久化 `AppData` 到 store（要求调用方已持有锁，避免重复锁）。
-    fn persist_locked(&self, data: &AppData) -> AppResult<()> {
-        self.store.set(
-            STORE_KEY,
-            serde_json::to_value(data).map_err(AppError::from)?,
+    /// 将计时器检查点写入 store（要求调用方已持有计时器锁；用于重启后恢复运行态）。
+    fn persist_timer_locked(&self, timer: &TimerRuntime) -> AppResult<()> {
+        let checkpoint = timer.checkpoint(chrono::Local::now());
+        let store = self.store();
+        store.set(
+            TIMER_STORE_KEY,
+            serde_json::to_value(&checkpoint).map_err(AppError::from)?,
         );
-        self.store.save()?;
-        tracing::debug!(target: "storage", "数据已持久化到 store");
+        store.save()?;
+        tracing::debug!(target: "storage", "计时器检查点已写入 store");
+        Ok(())
+    }
+
+    /// 在破坏性操作前备份当前数据（备份失败时返回错误，调用方应中止操作）。
+    pub fn backup_before(&self, reason: &str) -> AppResult<()> {
+        let dir = self.backup_dir()?;
+        let archive_dir = self.archive_dir()?;
+        let cipher = self.cipher();
+        let data = self.data.lock().unwrap();
+        crate::backup::create_backup(
+            &dir,
+            &archive_dir,
+            &data,
+            crate::backup::BackupKind::BeforeOperation,
+            reason,
+            &data.settings.backup,
+            chrono::Local::now(),
+            cipher.as_ref(),
+        )?;
         Ok(())
     }
 
+    /// 若定时备份已到期则写入一份定时备份，返回是否写入。
+    pub fn run_scheduled_backup_if_due(&self) -> AppResult<bool> {
+        let dir = self.backup_dir()?;
+        let archive_dir = self.archive_dir()?;
+        let cipher = self.cipher();
+        let now = chrono::Local::now();
+        let data = self.data.lock().unwrap();
+        if !crate::backup::scheduled_backup_due(&dir, &data.settings.backup, now)? {
+            return Ok(false);
+        }
+        crate::backup::create_backup(
+            &dir,
+            &archive_dir,
+            &data,
+            crate::backup::BackupKind::Scheduled,
+            "",
+            &data.settings.backup,
+            now,
+            cipher.as_ref(),
+        )?;
+        Ok(true)
+    }
+
     /// 判断计时器是否运行中（给托盘菜单逻辑使用）。
     pub fn is_running(&self) -> bool {
         self.timer.lock().unwrap().is_running
     }
 }
+
+/// 持久化 `AppData`：先将变化的记录同步到历史数据库，再将其余数据写入 store（启用加密时写入密文）。
+pub fn persist_app_data(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    history_db: &mut HistoryDb,
+    data: &mut AppData,
+) -> AppResult<()> {
+    history_db.sync(data)?;
+    let value = crate::history_db::store_value(data)?;
+    let value = match history_db.cipher() {
+        Some(cipher) => cipher.seal_json_with_schema(&value, data.schema_version)?,
+        None => value,
+    };
+    store.set(STORE_KEY, value);
+    store.save()?;
+    tracing::debug!(target: "storage", "数据已持久化到 store");
+    Ok(())
+}
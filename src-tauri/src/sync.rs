@@ -0,0 +1,1026 @@
+//! 文件夹同步：多台设备通过用户选择的共享文件夹（可由任意文件同步工具同步）交换变更日志。
+//!
+//! 每台设备只写自己的日志文件 `<设备 id>.journal.json`，内容为按序号追加的操作：写入/删除（墓碑）一条记录、整段替换一个字段。
+//! 合并时把所有日志的操作按 `(时钟, 设备 id, 序号)` 排序后依次应用，因此任意设备得到的结果一致：
+//! 历史与中断记录按稳定 id（开始时刻 + 阶段 / 中断时刻）取并集，同一条记录与设置等其余字段都是最后写入者胜出。
+//!
+//! 早于本机归档线的历史（见 `archive`）不参与比较与合并，归档不会在其他设备上表现为删除。
+//!
+//! 本机配置（共享文件夹、设备 id 与各设备日志的已读位置）保存在配置数据目录下的 `sync.json`，不参与同步。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::app_data::{AppData, HistoryRecord, InterruptionRecord};
+use crate::errors::{AppError, AppResult};
+
+/// 本机同步配置文件名（位于配置数据目录）。
+pub const SYNC_CONFIG_FILE_NAME: &str = "sync.json";
+
+/// 同步完成且应用了其他设备的变更时推送的事件（前端据此刷新快照）。
+pub const EVENT_SYNCED: &str = "pomodoro://synced";
+
+/// 共享文件夹中日志文件的后缀。
+const JOURNAL_SUFFIX: &str = ".journal.json";
+
+/// 仅属于本机、不参与同步的字段（camelCase 名）：派生的标签列表、运行时计数与开发数据。
+const LOCAL_SECTIONS: [&str; 6] = [
+    "schemaVersion",
+    "tags",
+    "currentCombo",
+    "totalPomodoros",
+    "historyDev",
+    "historyArchive",
+];
+
+/// 本机同步配置。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConfig {
+    /// 共享文件夹。
+    pub folder: PathBuf,
+    /// 本机设备 id（即日志文件名）。
+    pub device_id: String,
+    /// 本机设备名称（用于状态与冲突报告）。
+    pub device_name: String,
+    /// 各设备日志已合并到的序号（设备 id → 序号）。
+    #[serde(default)]
+    pub seen: BTreeMap<String, u64>,
+    /// 上次同步完成时刻（RFC 3339）。
+    #[serde(default)]
+    pub last_sync_at: Option<String>,
+}
+
+impl SyncConfig {
+    /// 读取配置目录下的同步配置（不存在表示未启用同步）。
+    pub fn load(dir: &Path) -> AppResult<Option<Self>> {
+        let bytes = match std::fs::read(dir.join(SYNC_CONFIG_FILE_NAME)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AppError::Invariant(format!("读取同步配置失败：{e}"))),
+        };
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    /// 写入同步配置（先写临时文件再重命名）。
+    pub fn save(&self, dir: &Path) -> AppResult<()> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::Invariant(format!("创建数据目录失败：{e}")))?;
+        write_atomically(
+            &dir.join(SYNC_CONFIG_FILE_NAME),
+            &serde_json::to_vec_pretty(self)?,
+        )
+    }
+
+    /// 删除同步配置（关闭同步后调用；文件不存在时忽略）。
+    pub fn remove(dir: &Path) -> AppResult<()> {
+        match std::fs::remove_file(dir.join(SYNC_CONFIG_FILE_NAME)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Invariant(format!("删除同步配置失败：{e}"))),
+        }
+    }
+
+    /// 本机日志文件路径。
+    fn journal_path(&self) -> PathBuf {
+        self.folder
+            .join(format!("{}{JOURNAL_SUFFIX}", self.device_id))
+    }
+}
+
+/// 一台设备的变更日志。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Journal {
+    /// 设备 id。
+    pub device_id: String,
+    /// 设备名称。
+    pub device_name: String,
+    /// 写入时的数据结构版本（高于本机时跳过该日志）。
+    pub schema_version: u32,
+    /// 操作（按序号递增）。
+    pub entries: Vec<JournalEntry>,
+}
+
+/// 日志中的一条操作。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// 设备内序号（从 1 开始递增）。
+    pub seq: u64,
+    /// 逻辑时钟（毫秒）：不小于写入时刻，且大于写入前已见过的所有操作。
+    pub clock: i64,
+    /// 操作内容。
+    pub op: SyncOp,
+}
+
+/// 同步操作。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SyncOp {
+    /// 新增或修改一条记录。
+    PutRecord {
+        key: String,
+        date: String,
+        record: Value,
+    },
+    /// 删除一条记录（墓碑）。
+    DeleteRecord { key: String },
+    /// 整段替换一个字段。
+    SetSection { section: String, value: Value },
+}
+
+impl SyncOp {
+    /// 操作主体：记录为其稳定 id，字段为字段名。
+    fn subject(&self) -> &str {
+        match self {
+            Self::PutRecord { key, .. } | Self::DeleteRecord { key } => key,
+            Self::SetSection { section, .. } => section,
+        }
+    }
+}
+
+/// 一次同步中被覆盖的并发修改。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// 冲突主体（记录 id，如 `history:2025-01-01T01:00:00Z/work`；或字段名，如 `settings`）。
+    pub subject: String,
+    /// 参与冲突的设备名称。
+    pub devices: Vec<String>,
+    /// 胜出（最后写入）的设备名称。
+    pub winner: String,
+}
+
+/// 一次同步的结果。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// 本机写入日志的操作数。
+    pub published: u32,
+    /// 合并的其他设备操作数。
+    pub received: u32,
+    /// 并发修改冲突（已按最后写入者胜出解决）。
+    pub conflicts: Vec<SyncConflict>,
+    /// 本次跳过的日志（文件名与原因，如无法解析或版本过新）。
+    pub skipped: Vec<String>,
+}
+
+/// 共享文件夹中一台设备的同步状态。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SyncDeviceStatus {
+    /// 设备 id。
+    pub device_id: String,
+    /// 设备名称。
+    pub device_name: String,
+    /// 是否为本机。
+    pub is_local: bool,
+    /// 日志中的操作数。
+    pub entries: u32,
+    /// 尚未合并到本机的操作数。
+    pub pending: u32,
+    /// 最近一次写入的逻辑时刻（RFC 3339；日志为空时为 `null`）。
+    pub last_change_at: Option<String>,
+}
+
+/// 同步状态（用于设置页展示）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// 是否已启用同步。
+    pub enabled: bool,
+    /// 共享文件夹。
+    pub folder: Option<String>,
+    /// 本机设备名称。
+    pub device_name: Option<String>,
+    /// 上次同步完成时刻（RFC 3339）。
+    pub last_sync_at: Option<String>,
+    /// 共享文件夹中的设备（本机在前）。
+    pub devices: Vec<SyncDeviceStatus>,
+    /// 无法读取的日志（文件名与原因）。
+    pub skipped: Vec<String>,
+}
+
+/// 可同步内容的视图：记录按稳定 id，字段按名称。
+#[derive(Debug, Clone, Default, PartialEq)]
+struct View {
+    /// 稳定 id → `(日期, 记录 JSON)`。
+    records: BTreeMap<String, (String, Value)>,
+    /// 字段名 → 字段 JSON。
+    sections: BTreeMap<String, Value>,
+}
+
+impl View {
+    /// 应用一条操作。
+    fn apply(&mut self, op: &SyncOp) {
+        match op {
+            SyncOp::PutRecord { key, date, record } => {
+                self.records
+                    .insert(key.clone(), (date.clone(), record.clone()));
+            }
+            SyncOp::DeleteRecord { key } => {
+                self.records.remove(key);
+            }
+            SyncOp::SetSection { section, value } => {
+                self.sections.insert(section.clone(), value.clone());
+            }
+        }
+    }
+
+    /// 去掉自然日期早于本机归档线的历史记录（已移入归档文件，不再参与比较与合并）。
+    fn without_archived(mut self, before: Option<&str>) -> Self {
+        if let Some(before) = before {
+            self.records
+                .retain(|key, (date, _)| !key.starts_with("history:") || date.as_str() >= before);
+        }
+        self
+    }
+}
+
+/// 共享文件夹中读取到的日志集合。
+struct Journals {
+    /// 设备 id → 日志（含本机）。
+    by_device: BTreeMap<String, Journal>,
+    /// 跳过的日志（文件名与原因）。
+    skipped: Vec<String>,
+}
+
+/// 新建同步配置（设备 id 随机生成，已有配置时沿用其设备 id）。
+pub fn new_config(folder: PathBuf, device_name: &str, previous: Option<&SyncConfig>) -> SyncConfig {
+    SyncConfig {
+        folder,
+        device_id: previous
+            .map(|c| c.device_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        device_name: device_name.trim().to_string(),
+        seen: BTreeMap::new(),
+        last_sync_at: None,
+    }
+}
+
+/// 查询同步状态（未启用同步时只返回 `enabled: false`）。
+pub fn status(config: Option<&SyncConfig>) -> AppResult<SyncStatus> {
+    let Some(config) = config else {
+        return Ok(SyncStatus {
+            enabled: false,
+            folder: None,
+            device_name: None,
+            last_sync_at: None,
+            devices: Vec::new(),
+            skipped: Vec::new(),
+        });
+    };
+    let journals = read_journals(config)?;
+    let mut devices: Vec<SyncDeviceStatus> = journals
+        .by_device
+        .values()
+        .map(|journal| {
+            let is_local = journal.device_id == config.device_id;
+            let seen = config.seen.get(&journal.device_id).copied().unwrap_or(0);
+            SyncDeviceStatus {
+                device_id: journal.device_id.clone(),
+                device_name: journal.device_name.clone(),
+                is_local,
+                entries: journal.entries.len() as u32,
+                pending: if is_local {
+                    0
+                } else {
+                    journal.entries.iter().filter(|e| e.seq > seen).count() as u32
+                },
+                last_change_at: journal
+                    .entries
+                    .iter()
+                    .map(|e| e.clock)
+                    .max()
+                    .and_then(chrono::DateTime::<chrono::Utc>::from_timestamp_millis)
+                    .map(|at| at.to_rfc3339()),
+            }
+        })
+        .collect();
+    devices.sort_by_key(|d| !d.is_local);
+    Ok(SyncStatus {
+        enabled: true,
+        folder: Some(config.folder.to_string_lossy().to_string()),
+        device_name: Some(config.device_name.clone()),
+        last_sync_at: config.last_sync_at.clone(),
+        devices,
+        skipped: journals.skipped,
+    })
+}
+
+/// 是否有其他设备尚未合并的操作（用于决定同步前是否需要备份）。
+pub fn has_pending(config: &SyncConfig) -> AppResult<bool> {
+    Ok(read_journals(config)?.by_device.values().any(|journal| {
+        let seen = config.seen.get(&journal.device_id).copied().unwrap_or(0);
+        journal.device_id != config.device_id && journal.entries.iter().any(|e| e.seq > seen)
+    }))
+}
+
+/// 同步一次：把本机自上次同步以来的变更追加到本机日志，再按全部日志合并出新的数据并写回 `data`。
+///
+/// 本机首次加入已有同步组时只发布记录，设置等字段以共享文件夹中的为准。
+/// 返回报告；`config` 中的已读位置与同步时刻随之更新（由调用方保存）。
+pub fn run(
+    config: &mut SyncConfig,
+    data: &mut AppData,
+    now: chrono::DateTime<chrono::Local>,
+) -> AppResult<SyncReport> {
+    std::fs::create_dir_all(&config.folder)
+        .map_err(|e| AppError::Invariant(format!("无法访问同步文件夹：{e}")))?;
+    let mut journals = read_journals(config)?;
+    let mut own = journals
+        .by_device
+        .remove(&config.device_id)
+        .unwrap_or_else(|| Journal {
+            device_id: config.device_id.clone(),
+            device_name: config.device_name.clone(),
+            schema_version: crate::migrations::CURRENT_SCHEMA_VERSION,
+            entries: Vec::new(),
+        });
+    own.device_name = config.device_name.clone();
+    own.schema_version = crate::migrations::CURRENT_SCHEMA_VERSION;
+    let joining = config.last_sync_at.is_none()
+        && journals
+            .by_device
+            .values()
+            .any(|journal| !journal.entries.is_empty());
+
+    // 基准：上次同步时已合并的全部操作（本机日志全部视为已合并）。
+    // 早于本机归档线的记录两侧都不参与，归档不会被当作删除发布出去。
+    let archived_before = data.history_archive.before.clone();
+    let seen_entries = |device: &str, seq: u64| {
+        device == config.device_id || seq <= config.seen.get(device).copied().unwrap_or(0)
+    };
+    let base = fold(
+        journals
+            .by_device
+            .values()
+            .chain(std::iter::once(&own))
+            .flat_map(|j| j.entries.iter().map(move |e| (j.device_id.as_str(), e)))
+            .filter(|(device, e)| seen_entries(device, e.seq)),
+    )
+    .without_archived(archived_before.as_deref());
+
+    let current = view_of(data)?;
+    let mut ops = diff(&base, &current);
+    if joining {
+        ops.retain(|op| !matches!(op, SyncOp::SetSection { .. }));
+    }
+    let clock = journals
+        .by_device
+        .values()
+        .chain(std::iter::once(&own))
+        .flat_map(|j| j.entries.iter().map(|e| e.clock + 1))
+        .fold(now.timestamp_millis(), i64::max);
+    let first_new_seq = own.entries.last().map_or(1, |e| e.seq + 1);
+    let published = ops.len() as u32;
+    for (offset, op) in ops.into_iter().enumerate() {
+        own.entries.push(JournalEntry {
+            seq: first_new_seq + offset as u64,
+            clock,
+            op,
+        });
+    }
+    if published > 0 || !config.journal_path().exists() {
+        write_atomically(&config.journal_path(), &serde_json::to_vec(&own)?)?;
+    }
+
+    let received = journals
+        .by_device
+        .values()
+        .flat_map(|j| j.entries.iter().map(move |e| (j.device_id.as_str(), e)))
+        .filter(|(device, e)| !seen_entries(device, e.seq))
+        .count() as u32;
+    let conflicts = conflicts(&journals, &own, config, first_new_seq);
+
+    journals.by_device.insert(own.device_id.clone(), own);
+    let merged = fold(
+        journals
+            .by_device
+            .values()
+            .flat_map(|j| j.entries.iter().map(move |e| (j.device_id.as_str(), e))),
+    )
+    .without_archived(archived_before.as_deref());
+    // 合并结果与本机一致（没有收到变更或收到的都已在本机）时不改动数据，避免每次同步都重写记录。
+    if merged != current.without_archived(archived_before.as_deref()) {
+        apply_view(data, &merged)?;
+    }
+
+    for journal in journals.by_device.values() {
+        if let Some(last) = journal.entries.last() {
+            config.seen.insert(journal.device_id.clone(), last.seq);
+        }
+    }
+    config.last_sync_at = Some(now.to_rfc3339());
+    Ok(SyncReport {
+        published,
+        received,
+        conflicts,
+        skipped: journals.skipped,
+    })
+}
+
+/// 读取共享文件夹中的全部日志（无法解析或版本过新的日志记入 `skipped`；本机日志无法解析时视为空日志重新发布）。
+fn read_journals(config: &SyncConfig) -> AppResult<Journals> {
+    let mut journals = Journals {
+        by_device: BTreeMap::new(),
+        skipped: Vec::new(),
+    };
+    let entries = match std::fs::read_dir(&config.folder) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(journals),
+        Err(e) => return Err(AppError::Invariant(format!("读取同步文件夹失败：{e}"))),
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.ends_with(JOURNAL_SUFFIX) {
+            continue;
+        }
+        let parsed = std::fs::read(entry.path())
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| serde_json::from_slice::<Journal>(&bytes).map_err(|e| e.to_string()));
+        let reason = match parsed {
+            Ok(journal) if journal.schema_version > crate::migrations::CURRENT_SCHEMA_VERSION => {
+                format!("数据版本过新（v{}）", journal.schema_version)
+            }
+            Ok(journal) if format!("{}{JOURNAL_SUFFIX}", journal.device_id) != name => {
+                "设备 id 与文件名不一致".to_string()
+            }
+            Ok(journal) => {
+                journals
+                    .by_device
+                    .insert(journal.device_id.clone(), journal);
+                continue;
+            }
+            Err(e) => e,
+        };
+        tracing::warn!(target: "sync", "跳过同步日志：file={} reason={}", name, reason);
+        journals.skipped.push(format!("{name}：{reason}"));
+    }
+    Ok(journals)
+}
+
+/// 按 `(时钟, 设备 id, 序号)` 排序后依次应用操作，得到合并视图。
+fn fold<'a>(entries: impl Iterator<Item = (&'a str, &'a JournalEntry)>) -> View {
+    let mut entries: Vec<(&str, &JournalEntry)> = entries.collect();
+    entries.sort_by(|(da, a), (db, b)| (a.clock, *da, a.seq).cmp(&(b.clock, *db, b.seq)));
+    let mut view = View::default();
+    for (_, entry) in entries {
+        view.apply(&entry.op);
+    }
+    view
+}
+
+/// 列出本次新增操作中的冲突：同一主体被两台及以上设备写入了不同内容（胜出者为排序最后的一条）。
+fn conflicts(
+    journals: &Journals,
+    own: &Journal,
+    config: &SyncConfig,
+    first_new_seq: u64,
+) -> Vec<SyncConflict> {
+    let names: BTreeMap<&str, &str> = journals
+        .by_device
+        .values()
+        .chain(std::iter::once(own))
+        .map(|j| (j.device_id.as_str(), j.device_name.as_str()))
+        .collect();
+    let mut fresh: Vec<(&str, &JournalEntry)> = journals
+        .by_device
+        .values()
+        .flat_map(|j| {
+            let seen = config.seen.get(&j.device_id).copied().unwrap_or(0);
+            j.entries
+                .iter()
+                .filter(move |e| e.seq > seen)
+                .map(move |e| (j.device_id.as_str(), e))
+        })
+        .chain(
+            own.entries
+                .iter()
+                .filter(|e| e.seq >= first_new_seq)
+                .map(|e| (own.device_id.as_str(), e)),
+        )
+        .collect();
+    fresh.sort_by(|(da, a), (db, b)| (a.clock, *da, a.seq).cmp(&(b.clock, *db, b.seq)));
+
+    let mut by_subject: BTreeMap<&str, Vec<(&str, &SyncOp)>> = BTreeMap::new();
+    for &(device, entry) in &fresh {
+        by_subject
+            .entry(entry.op.subject())
+            .or_default()
+            .push((device, &entry.op));
+    }
+    by_subject
+        .into_iter()
+        .filter_map(|(subject, writes)| {
+            let devices: BTreeSet<&str> = writes.iter().map(|(d, _)| *d).collect();
+            let (winner, last) = writes.last()?;
+            let differs = writes.iter().any(|(_, op)| op != last);
+            (devices.len() > 1 && differs).then(|| SyncConflict {
+                subject: subject.to_string(),
+                devices: devices
+                    .iter()
+                    .map(|d| names.get(d).copied().unwrap_or(*d).to_string())
+                    .collect(),
+                winner: names.get(winner).copied().unwrap_or(*winner).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 比较两个视图，生成从 `base` 变为 `current` 的操作。
+fn diff(base: &View, current: &View) -> Vec<SyncOp> {
+    let mut ops = Vec::new();
+    for (section, value) in &current.sections {
+        if base.sections.get(section) != Some(value) {
+            ops.push(SyncOp::SetSection {
+                section: section.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    for (key, (date, record)) in &current.records {
+        if base.records.get(key) != Some(&(date.clone(), record.clone())) {
+            ops.push(SyncOp::PutRecord {
+                key: key.clone(),
+                date: date.clone(),
+                record: record.clone(),
+            });
+        }
+    }
+    for key in base.records.keys() {
+        if !current.records.contains_key(key) {
+            ops.push(SyncOp::DeleteRecord { key: key.clone() });
+        }
+    }
+    ops
+}
+
+/// 由数据生成可同步视图（记录的标签路径按 `tag_id` 解析为当前路径，合并时据此修正标签引用）。
+fn view_of(data: &AppData) -> AppResult<View> {
+    let data = &data.resolved();
+    let mut view = View::default();
+    let Value::Object(object) = serde_json::to_value(data)? else {
+        return Err(AppError::Invariant(
+            "AppData 序列化结果不是对象".to_string(),
+        ));
+    };
+    for (name, value) in object {
+        if !crate::audit::RECORD_SECTIONS.contains(&name.as_str())
+            && !LOCAL_SECTIONS.contains(&name.as_str())
+        {
+            view.sections.insert(name, value);
+        }
+    }
+    for day in &data.history {
+        for record in &day.records {
+            insert_record(
+                &mut view,
+                history_key(&day.date, record),
+                &day.date,
+                serde_json::to_value(record)?,
+            );
+        }
+    }
+    for day in &data.interruptions {
+        for record in &day.records {
+            insert_record(
+                &mut view,
+                interruption_key(&day.date, record),
+                &day.date,
+                serde_json::to_value(record)?,
+            );
+        }
+    }
+    Ok(view)
+}
+
+/// 插入一条记录（稳定 id 重复时追加 `#<序号>` 区分）。
+fn insert_record(view: &mut View, key: String, date: &str, record: Value) {
+    let mut unique = key.clone();
+    let mut n = 1;
+    while view.records.contains_key(&unique) {
+        n += 1;
+        unique = format!("{key}#{n}");
+    }
+    view.records.insert(unique, (date.to_string(), record));
+}
+
+/// 历史记录的稳定 id：`history:<UTC 开始时刻>/<阶段>`（缺少开始时刻时以“日期 + HH:mm”代替）。
+fn history_key(date: &str, record: &HistoryRecord) -> String {
+    let started = normalized_instant(record.started_at.as_deref())
+        .unwrap_or_else(|| format!("{date}T{}", record.start_time));
+    let phase = serde_json::to_value(record.phase)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    format!("history:{started}/{phase}")
+}
+
+/// 中断记录的稳定 id：`interruption:<UTC 中断时刻>`。
+fn interruption_key(date: &str, record: &InterruptionRecord) -> String {
+    let at = normalized_instant(Some(&record.timestamp))
+        .unwrap_or_else(|| format!("{date}/{}", record.timestamp));
+    format!("interruption:{at}")
+}
+
+/// 将 RFC 3339 时刻统一为 UTC 秒精度（不同时区写入的同一时刻得到相同 id）。
+fn normalized_instant(at: Option<&str>) -> Option<String> {
+    let at = chrono::DateTime::parse_from_rfc3339(at?).ok()?;
+    Some(
+        at.with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    )
+}
+
+/// 以合并视图替换数据中的可同步内容（本机字段保持不变），并按记录的标签路径修正标签引用。
+///
+/// 只登记与本机不同的日期与字段；归档线之前尚未并入归档文件的本机记录（如归档后补录的）原样保留。
+fn apply_view(data: &mut AppData, view: &View) -> AppResult<()> {
+    let mut value = serde_json::to_value(&*data)?;
+    let Some(object) = value.as_object_mut() else {
+        return Err(AppError::Invariant(
+            "AppData 序列化结果不是对象".to_string(),
+        ));
+    };
+    for (section, section_value) in &view.sections {
+        object.insert(section.clone(), section_value.clone());
+    }
+    let mut history: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    let mut interruptions: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    for (key, (date, record)) in &view.records {
+        let days = if key.starts_with("history:") {
+            &mut history
+        } else {
+            &mut interruptions
+        };
+        days.entry(date.as_str()).or_default().push(record.clone());
+    }
+    let days = |days: BTreeMap<&str, Vec<Value>>| -> Value {
+        days.into_iter()
+            .map(|(date, records)| serde_json::json!({ "date": date, "records": records }))
+            .collect()
+    };
+    object.insert("history".to_string(), days(history));
+    object.insert("interruptions".to_string(), days(interruptions));
+    let mut merged: AppData = serde_json::from_value(value)?;
+    reconcile_tags(&mut merged);
+    if let Some(before) = data.history_archive.before.as_deref() {
+        let unarchived = data.history.iter().filter(|d| d.date.as_str() < before);
+        merged.history.splice(0..0, unarchived.cloned());
+    }
+    data.replace_changed(merged)?;
+    Ok(())
+}
+
+/// 以记录自身的标签路径为准修正标签引用：不同设备可能为不同标签分配了同一个 id，
+/// 或合并后的标签定义中缺少某些记录使用的标签（缺失的按路径补建）。
+fn reconcile_tags(data: &mut AppData) {
+    let mut history = std::mem::take(&mut data.history);
+    for record in history.iter_mut().flat_map(|d| d.records.iter_mut()) {
+        reconcile_tag(data, &record.tag, &mut record.tag_id);
+    }
+    data.history = history;
+    let mut interruptions = std::mem::take(&mut data.interruptions);
+    for record in interruptions.iter_mut().flat_map(|d| d.records.iter_mut()) {
+        reconcile_tag(data, &record.tag, &mut record.tag_id);
+    }
+    data.interruptions = interruptions;
+    data.sync_tags();
+}
+
+/// 修正一条记录的标签引用（标签 id 指向的路径与记录标签不一致时按路径重新查找或补建）。
+fn reconcile_tag(data: &mut AppData, tag: &str, tag_id: &mut Option<String>) {
+    if tag.is_empty() {
+        return;
+    }
+    if tag_id
+        .as_deref()
+        .and_then(|id| data.tag_path(id))
+        .as_deref()
+        != Some(tag)
+    {
+        *tag_id = Some(data.ensure_tag_path(tag));
+    }
+}
+
+/// 先写临时文件再重命名，避免其他设备读到半个文件。
+fn write_atomically(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, bytes)
+        .map_err(|e| AppError::Invariant(format!("写入同步文件失败：{e}")))?;
+    std::fs::rename(&tmp, path).map_err(|e| AppError::Invariant(format!("保存同步文件失败：{e}")))
+}
+
+/// 启动后台同步任务：已启用同步时每 5 分钟同步一次当前配置。
+#[cfg(not(test))]
+pub fn spawn_sync_task(app: tauri::AppHandle) {
+    use tauri::Manager as _;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+            let state = app.state::<crate::state::AppState>();
+            let dir = match crate::app_paths::profile_data_dir(&app, &state.active_profile().id) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    tracing::warn!(target: "sync", "定时同步失败：{e}");
+                    continue;
+                }
+            };
+            match SyncConfig::load(&dir) {
+                Ok(Some(_)) => {
+                    if let Err(e) = crate::commands::sync::sync_now_impl(&*state, &dir) {
+                        tracing::warn!(target: "sync", "定时同步失败：{e}");
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(target: "sync", "定时同步失败：{e}"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone as _;
+
+    use crate::app_data::RecordSet;
+
+    /// 构造一条带开始时刻的工作记录（标签 id 取默认数据中的同名标签）。
+    fn record(tag: &str, date: &str, start: &str) -> HistoryRecord {
+        let started =
+            chrono::NaiveDateTime::parse_from_str(&format!("{date} {start}"), "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_utc();
+        HistoryRecord {
+            started_at: Some(started.to_rfc3339()),
+            ..HistoryRecord::for_test(tag, start)
+                .with_tag_id(AppData::default().tag_id_by_path(tag))
+        }
+    }
+
+    /// 在数据中追加一条历史记录（按日期归组，日期按顺序插入）。
+    fn push(data: &mut AppData, date: &str, record: HistoryRecord) {
+        data.history_day_mut(false, date).records.push(record);
+    }
+
+    /// 同步时刻（按分钟递增，保证时钟有先后）。
+    fn at(minute: u32) -> chrono::DateTime<chrono::Local> {
+        chrono::Local
+            .with_ymd_and_hms(2025, 3, 1, 12, minute, 0)
+            .unwrap()
+    }
+
+    /// 全部历史记录的 `(日期, 开始时间, 备注)`。
+    fn records(data: &AppData) -> Vec<(String, String, String)> {
+        let mut out: Vec<_> = data
+            .history
+            .iter()
+            .flat_map(|d| {
+                d.records
+                    .iter()
+                    .map(|r| (d.date.clone(), r.start_time.clone(), r.remark.clone()))
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// 没有新变更的同步不改动数据；收到变更时只登记变化的日期，不整体重写记录。
+    #[test]
+    fn sync_touches_only_changed_days() {
+        let folder = tempfile::tempdir().unwrap();
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+        let mut a = AppData::default();
+        push(&mut a, "2025-03-01", record("工作", "2025-03-01", "09:00"));
+        let mut b = AppData::default();
+        push(&mut b, "2025-02-01", record("学习", "2025-02-01", "09:00"));
+        run(&mut desktop, &mut a, at(0)).unwrap();
+        run(&mut laptop, &mut b, at(1)).unwrap();
+        run(&mut desktop, &mut a, at(2)).unwrap();
+
+        a.pending = Default::default();
+        let report = run(&mut desktop, &mut a, at(3)).unwrap();
+        assert_eq!((report.published, report.received), (0, 0));
+        assert!(!a.pending.all_records);
+        assert!(a.pending.days.is_empty() && a.pending.sections.is_empty());
+
+        push(&mut b, "2025-02-02", record("学习", "2025-02-02", "10:00"));
+        run(&mut laptop, &mut b, at(4)).unwrap();
+        run(&mut desktop, &mut a, at(5)).unwrap();
+        assert!(!a.pending.all_records);
+        assert_eq!(
+            a.pending.days.iter().cloned().collect::<Vec<_>>(),
+            [(RecordSet::History, "2025-02-02".to_string())]
+        );
+        assert_eq!(records(&a).len(), 3);
+    }
+
+    /// 一台设备归档旧历史后，不会把归档当作删除发布，也不会把已归档的记录合并回来。
+    #[test]
+    fn archiving_is_not_published_as_deletion() {
+        let folder = tempfile::tempdir().unwrap();
+        let archive = tempfile::tempdir().unwrap();
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+
+        let mut a = AppData::default();
+        push(&mut a, "2024-01-01", record("工作", "2024-01-01", "09:00"));
+        push(&mut a, "2025-03-01", record("工作", "2025-03-01", "09:00"));
+        let mut b = AppData::default();
+        run(&mut desktop, &mut a, at(0)).unwrap();
+        run(&mut laptop, &mut b, at(1)).unwrap();
+        assert_eq!(records(&b).len(), 2);
+
+        crate::archive::archive(archive.path(), &mut a, "2025-01-01", None).unwrap();
+        push(&mut a, "2024-06-01", record("学习", "2024-06-01", "09:00"));
+        let report = run(&mut desktop, &mut a, at(2)).unwrap();
+        assert_eq!(report.published, 1);
+        assert_eq!(records(&a).len(), 2);
+        assert_eq!(a.history[0].date, "2024-06-01");
+
+        run(&mut laptop, &mut b, at(3)).unwrap();
+        assert_eq!(records(&b).len(), 3);
+    }
+
+    /// 两台设备：记录取并集、删除以墓碑传播，最终数据一致。
+    #[test]
+    fn devices_converge_on_union_and_tombstones() {
+        let folder = tempfile::tempdir().unwrap();
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+
+        let mut a = AppData::default();
+        push(&mut a, "2025-03-01", record("工作", "2025-03-01", "09:00"));
+        let mut b = AppData::default();
+        push(&mut b, "2025-03-01", record("学习", "2025-03-01", "10:00"));
+        push(&mut b, "2025-02-28", record("学习", "2025-02-28", "21:00"));
+
+        let report = run(&mut desktop, &mut a, at(0)).unwrap();
+        assert_eq!(report.received, 0);
+        assert!(report.published > 1);
+        let report = run(&mut laptop, &mut b, at(1)).unwrap();
+        assert_eq!(report.published, 2);
+        assert!(report.conflicts.is_empty());
+        run(&mut desktop, &mut a, at(2)).unwrap();
+
+        assert_eq!(records(&a), records(&b));
+        assert_eq!(records(&a).len(), 3);
+        assert_eq!(
+            a.history
+                .iter()
+                .map(|d| d.date.as_str())
+                .collect::<Vec<_>>(),
+            ["2025-02-28", "2025-03-01"]
+        );
+
+        b.history.retain(|d| d.date != "2025-02-28");
+        let report = run(&mut laptop, &mut b, at(3)).unwrap();
+        assert_eq!(report.published, 1);
+        let report = run(&mut desktop, &mut a, at(4)).unwrap();
+        assert_eq!(report.received, 1);
+        assert_eq!(records(&a), records(&b));
+        assert_eq!(records(&a).len(), 2);
+
+        let again = run(&mut desktop, &mut a, at(5)).unwrap();
+        assert_eq!((again.published, again.received), (0, 0));
+    }
+
+    /// 首次加入的设备采用共享文件夹中的设置；之后并发修改设置与同一条记录时最后写入者胜出并报告冲突。
+    #[test]
+    fn settings_and_records_are_last_writer_wins_with_conflicts() {
+        let folder = tempfile::tempdir().unwrap();
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+
+        let mut a = AppData::default();
+        a.settings.pomodoro = 50;
+        push(&mut a, "2025-03-01", record("工作", "2025-03-01", "09:00"));
+        run(&mut desktop, &mut a, at(0)).unwrap();
+
+        let mut b = AppData::default();
+        let report = run(&mut laptop, &mut b, at(1)).unwrap();
+        assert_eq!(report.published, 0);
+        assert_eq!(b.settings.pomodoro, 50);
+        assert_eq!(records(&b), records(&a));
+
+        a.settings.pomodoro = 45;
+        a.history[0].records[0].remark = "台式机备注".to_string();
+        b.settings.pomodoro = 30;
+        b.history[0].records[0].remark = "笔记本备注".to_string();
+        run(&mut desktop, &mut a, at(2)).unwrap();
+        let report = run(&mut laptop, &mut b, at(3)).unwrap();
+        let mut subjects: Vec<&str> = report
+            .conflicts
+            .iter()
+            .map(|c| c.subject.as_str())
+            .collect();
+        subjects.sort();
+        assert_eq!(subjects, ["history:2025-03-01T09:00:00Z/work", "settings"]);
+        assert!(report.conflicts.iter().all(|c| c.winner == "笔记本"));
+        assert_eq!(report.conflicts[0].devices.len(), 2);
+        assert_eq!(b.settings.pomodoro, 30);
+
+        run(&mut desktop, &mut a, at(4)).unwrap();
+        assert_eq!(a.settings.pomodoro, 30);
+        assert_eq!(records(&a), records(&b));
+        assert_eq!(a.history[0].records[0].remark, "笔记本备注");
+    }
+
+    /// 两台设备为不同标签分配了同一个 id 时，记录保留各自的标签路径。
+    #[test]
+    fn conflicting_tag_ids_keep_record_paths() {
+        let folder = tempfile::tempdir().unwrap();
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+        let mut a = AppData::default();
+        let mut b = AppData::default();
+        run(&mut desktop, &mut a, at(0)).unwrap();
+        run(&mut laptop, &mut b, at(1)).unwrap();
+
+        let mut ra = record("健身", "2025-03-01", "07:00");
+        ra.tag_id = Some(a.ensure_tag_path("健身"));
+        push(&mut a, "2025-03-01", ra);
+        a.sync_tags();
+        let mut rb = record("绘画", "2025-03-01", "20:00");
+        rb.tag_id = Some(b.ensure_tag_path("绘画"));
+        push(&mut b, "2025-03-01", rb);
+        b.sync_tags();
+        assert_eq!(a.tag_id_by_path("健身"), b.tag_id_by_path("绘画"));
+
+        run(&mut desktop, &mut a, at(2)).unwrap();
+        run(&mut laptop, &mut b, at(3)).unwrap();
+        let tags: Vec<&str> = b.history[0]
+            .records
+            .iter()
+            .map(|r| r.tag.as_str())
+            .collect();
+        assert_eq!(tags, ["健身", "绘画"]);
+        for r in &b.history[0].records {
+            assert_eq!(
+                b.tag_path(r.tag_id.as_deref().unwrap()).as_deref(),
+                Some(r.tag.as_str())
+            );
+        }
+    }
+
+    /// 无法解析或版本过新的日志被跳过并在状态中报告；状态列出各设备待合并的操作数。
+    #[test]
+    fn status_reports_devices_and_skipped_journals() {
+        let folder = tempfile::tempdir().unwrap();
+        assert!(!status(None).unwrap().enabled);
+
+        let mut desktop = new_config(folder.path().to_path_buf(), "台式机", None);
+        let mut laptop = new_config(folder.path().to_path_buf(), "笔记本", None);
+        let mut a = AppData::default();
+        push(&mut a, "2025-03-01", record("工作", "2025-03-01", "09:00"));
+        run(&mut desktop, &mut a, at(0)).unwrap();
+        std::fs::write(folder.path().join("broken.journal.json"), b"{").unwrap();
+        let newer = Journal {
+            device_id: "future".to_string(),
+            device_name: "新版本".to_string(),
+            schema_version: crate::migrations::CURRENT_SCHEMA_VERSION + 1,
+            entries: Vec::new(),
+        };
+        std::fs::write(
+            folder.path().join("future.journal.json"),
+            serde_json::to_vec(&newer).unwrap(),
+        )
+        .unwrap();
+
+        let mut b = AppData::default();
+        assert!(has_pending(&laptop).unwrap());
+        let before = status(Some(&laptop)).unwrap();
+        assert_eq!(before.devices.len(), 1);
+        assert!(before.devices[0].pending > 0);
+        assert_eq!(before.skipped.len(), 2);
+
+        let report = run(&mut laptop, &mut b, at(1)).unwrap();
+        assert_eq!(report.skipped.len(), 2);
+        assert!(!has_pending(&laptop).unwrap());
+        let after = status(Some(&laptop)).unwrap();
+        assert_eq!(after.devices.len(), 2);
+        assert!(after.devices[0].is_local);
+        assert_eq!(after.devices[0].device_name, "笔记本");
+        assert_eq!(after.devices[1].pending, 0);
+        assert!(after.last_sync_at.is_some());
+    }
+}
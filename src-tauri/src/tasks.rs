@@ -0,0 +1,181 @@
+//! 任务预估复盘：对比任务的预估番茄数与实际完成的工作记录数。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::app_data::{HistoryDay, Phase, Task};
+
+/// 单个任务的预估对比。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TaskEstimate {
+    /// 任务 id。
+    pub task_id: String,
+    /// 任务标题。
+    pub title: String,
+    /// 所属标签。
+    pub tag: String,
+    /// 预估番茄数。
+    pub estimated: u32,
+    /// 实际完成的番茄数（关联到该任务的工作记录数）。
+    pub actual: u32,
+    /// 是否已完成。
+    pub done: bool,
+    /// 实际/预估比值（>1 表示低估；未预估时为空）。
+    pub ratio: Option<f64>,
+}
+
+/// 某个标签下已完成任务的预估对比汇总。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TagEstimate {
+    /// 标签名。
+    pub tag: String,
+    /// 参与统计的任务数（已完成且有预估）。
+    pub task_count: u32,
+    /// 预估番茄数合计。
+    pub estimated: u32,
+    /// 实际番茄数合计。
+    pub actual: u32,
+    /// 实际/预估比值。
+    pub ratio: f64,
+}
+
+/// 任务预估准确度报告（用于任务复盘面板）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TaskEstimateReport {
+    /// 各任务的预估对比（顺序同任务列表）。
+    pub tasks: Vec<TaskEstimate>,
+    /// 按标签汇总（仅统计已完成且有预估的任务，按标签名排序）。
+    pub tags: Vec<TagEstimate>,
+}
+
+/// 计算任务预估准确度：实际番茄数取历史中关联到任务的工作记录数（跳过的记录不计）。
+pub fn compute_estimate_report(tasks: &[Task], days: &[HistoryDay]) -> TaskEstimateReport {
+    let mut actual_by_task: BTreeMap<&str, u32> = BTreeMap::new();
+    for r in days.iter().flat_map(|d| d.records.iter()) {
+        if r.phase != Phase::Work || r.skipped {
+            continue;
+        }
+        if let Some(task_id) = r.task_id.as_deref() {
+            *actual_by_task.entry(task_id).or_insert(0) += 1;
+        }
+    }
+
+    let estimates: Vec<TaskEstimate> = tasks
+        .iter()
+        .map(|t| {
+            let actual = actual_by_task.get(t.id.as_str()).copied().unwrap_or(0);
+            TaskEstimate {
+                task_id: t.id.clone(),
+                title: t.title.clone(),
+                tag: t.tag.clone(),
+                estimated: t.estimated_pomodoros,
+                actual,
+                done: t.done,
+                ratio: ratio(actual, t.estimated_pomodoros),
+            }
+        })
+        .collect();
+
+    let mut by_tag: BTreeMap<String, (u32, u32, u32)> = BTreeMap::new();
+    for e in estimates.iter().filter(|e| e.done && e.estimated > 0) {
+        let entry = by_tag.entry(e.tag.clone()).or_insert((0, 0, 0));
+        entry.0 += 1;
+        entry.1 += e.estimated;
+        entry.2 += e.actual;
+    }
+    let tags = by_tag
+        .into_iter()
+        .map(|(tag, (task_count, estimated, actual))| TagEstimate {
+            tag,
+            task_count,
+            estimated,
+            actual,
+            ratio: ratio(actual, estimated).unwrap_or(0.0),
+        })
+        .collect();
+
+    TaskEstimateReport {
+        tasks: estimates,
+        tags,
+    }
+}
+
+/// 实际/预估比值（预估为 0 时无意义，返回 `None`）。
+fn ratio(actual: u32, estimated: u32) -> Option<f64> {
+    (estimated > 0).then(|| actual as f64 / estimated as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_data::HistoryRecord;
+
+    /// 构造一条关联到任务的历史记录。
+    fn record(task_id: Option<&str>, phase: Phase) -> HistoryRecord {
+        HistoryRecord {
+            task_id: task_id.map(str::to_string),
+            ..HistoryRecord::for_test("工作", "09:00").with_phase(phase)
+        }
+    }
+
+    /// 构造一个任务。
+    fn task(id: &str, tag: &str, estimated: u32, done: bool) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            tag: tag.to_string(),
+            tag_id: None,
+            estimated_pomodoros: estimated,
+            due_date: None,
+            done,
+        }
+    }
+
+    /// `compute_estimate_report`：按任务统计实际番茄数，标签汇总仅包含已完成且有预估的任务。
+    #[test]
+    fn compute_estimate_report_counts_work_records_per_task() {
+        let days = vec![HistoryDay {
+            date: "2025-01-01".to_string(),
+            records: vec![
+                record(Some("a"), Phase::Work),
+                record(Some("a"), Phase::Work),
+                record(Some("a"), Phase::ShortBreak),
+                record(Some("b"), Phase::Work),
+                record(Some("c"), Phase::Work),
+                record(None, Phase::Work),
+            ],
+        }];
+        let tasks = vec![
+            task("a", "工作", 4, true),
+            task("b", "工作", 2, true),
+            task("c", "学习", 0, true),
+            task("d", "学习", 3, false),
+        ];
+
+        let out = compute_estimate_report(&tasks, &days);
+        assert_eq!(
+            out.tasks.iter().map(|t| t.actual).collect::<Vec<_>>(),
+            vec![2, 1, 1, 0]
+        );
+        assert_eq!(out.tasks[0].ratio, Some(0.5));
+        assert_eq!(out.tasks[2].ratio, None);
+        assert_eq!(
+            out.tags,
+            vec![TagEstimate {
+                tag: "工作".to_string(),
+                task_count: 2,
+                estimated: 6,
+                actual: 3,
+                ratio: 0.5,
+            }]
+        );
+    }
+}
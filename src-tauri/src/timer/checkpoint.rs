@@ -0,0 +1,400 @@
+//! 计时器运行态检查点：持久化到 store，重启后按墙钟补算并恢复。
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_data::{AppData, PauseSegment, Phase, Section};
+use crate::errors::AppResult;
+use crate::timer::notification::SilentNotifier;
+use crate::timer::runtime::{InstantClock, TimerClock, TimerRuntime};
+use crate::timer::stats::WorkCountQuery;
+
+/// 计时器运行态检查点（与 `AppData` 分开保存在同一 store 的独立键下）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerCheckpoint {
+    /// 当前阶段。
+    pub phase: Phase,
+    /// 写入检查点时的剩余秒数。
+    pub remaining_seconds: u64,
+    /// 写入检查点时是否运行中。
+    pub is_running: bool,
+    /// 当前任务标签。
+    pub current_tag: String,
+    /// 当前任务 id。
+    #[serde(default)]
+    pub current_task_id: Option<String>,
+    /// 当前阶段首次开始的时刻（RFC 3339，带 UTC 偏移）。
+    #[serde(default)]
+    pub phase_started_at: Option<String>,
+    /// 旧版检查点：阶段首次开始时的日期（YYYY-MM-DD，仅用于读取）。
+    #[serde(default, alias = "workStartedDate", skip_serializing)]
+    pub phase_started_date: Option<String>,
+    /// 旧版检查点：阶段首次开始时的时间（HH:mm，仅用于读取）。
+    #[serde(default, alias = "workStartedTime", skip_serializing)]
+    pub phase_started_time: Option<String>,
+    /// 专注期黑名单锁定标记。
+    #[serde(default)]
+    pub work_lock_active: bool,
+    /// 连续番茄“自动推进”剩余工作次数。
+    #[serde(default)]
+    pub auto_work_remaining: u32,
+    /// 当前阶段总秒数（缺失时以剩余秒数回填）。
+    #[serde(default)]
+    pub phase_total_seconds: u64,
+    /// 启用会话计划时所处的步骤索引。
+    #[serde(default)]
+    pub plan_step: Option<usize>,
+    /// 当前阶段内已结束的暂停片段。
+    #[serde(default)]
+    pub pauses: Vec<PauseSegment>,
+    /// 若处于暂停中：暂停开始的时间（RFC 3339）。
+    #[serde(default)]
+    pub paused_at: Option<String>,
+    /// 写入检查点的时间（RFC 3339，带 UTC 偏移）。
+    pub saved_at: String,
+}
+
+impl TimerCheckpoint {
+    /// 解析阶段开始时刻：优先使用 RFC 3339 字段，旧版检查点按本地日期 + `HH:mm` 回填。
+    pub fn started_at(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        if let Some(at) = self.phase_started_at.as_deref() {
+            return chrono::DateTime::parse_from_rfc3339(at)
+                .ok()
+                .map(|at| at.with_timezone(&chrono::Local));
+        }
+        crate::app_data::local_instant(
+            self.phase_started_date.as_deref()?,
+            self.phase_started_time.as_deref()?,
+        )
+    }
+}
+
+/// 恢复补算结果：用于决定是否需要回写 `AppData` 与累计番茄数。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CatchUpSummary {
+    /// 关闭期间自然结束的阶段数量。
+    pub phases_ended: u32,
+    /// 关闭期间完成并写入历史的工作阶段数量。
+    pub work_completed: u32,
+}
+
+/// 由检查点恢复计时器运行态：若检查点处于运行中，则把 `saved_at` 到 `clock` 当前时刻的墙钟时间补算进去。
+///
+/// 补算期间自然结束的阶段会按“本应结束的时刻”写入历史，且不会发送系统通知；
+/// 补算完成后按 `clock` 重新锚定截止时刻。
+pub fn restore_from_checkpoint(
+    checkpoint: TimerCheckpoint,
+    data: &mut AppData,
+    db: Option<&dyn WorkCountQuery>,
+    clock: &dyn TimerClock,
+) -> AppResult<(TimerRuntime, CatchUpSummary)> {
+    let now = clock.now();
+    let saved_at = chrono::DateTime::parse_from_rfc3339(&checkpoint.saved_at)
+        .map(|dt| dt.with_timezone(&chrono::Local))
+        .unwrap_or(now);
+    let elapsed_seconds = (now - saved_at).num_seconds().max(0) as u64;
+
+    let mut runtime = TimerRuntime::from_checkpoint(checkpoint, &InstantClock::new(saved_at));
+    let summary = runtime.catch_up(data, db, elapsed_seconds, saved_at, &SilentNotifier)?;
+    runtime.resume_deadline(clock);
+    Ok((runtime, summary))
+}
+
+/// 启动时由 store 中的检查点恢复：补算后累加完成的番茄数，并在有阶段结束时给出应写回 store 的新检查点。
+///
+/// 补算写入的历史与旧检查点不能同时留在 store 中，否则下次启动会重放同一段补算；
+/// 调用方应先写入新检查点，再与 `AppData` 一起持久化。
+pub fn resume_from_checkpoint(
+    checkpoint: TimerCheckpoint,
+    data: &mut AppData,
+    db: Option<&dyn WorkCountQuery>,
+    clock: &dyn TimerClock,
+) -> AppResult<(TimerRuntime, CatchUpSummary, Option<TimerCheckpoint>)> {
+    let (runtime, summary) = restore_from_checkpoint(checkpoint, data, db, clock)?;
+    if summary.work_completed > 0 {
+        data.touch_section(Section::Counters);
+        data.total_pomodoros = data
+            .total_pomodoros
+            .saturating_add(u64::from(summary.work_completed));
+    }
+    let rewritten = (summary.phases_ended > 0).then(|| runtime.checkpoint(clock.now()));
+    Ok((runtime, summary, rewritten))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone as _;
+
+    use crate::app_data::{DayBoundary, Settings};
+
+    /// 构造一个本地时间点（测试用）。
+    fn local(y: i32, m: u32, d: u32, hh: u32, mm: u32) -> chrono::DateTime<chrono::Local> {
+        chrono::Local
+            .with_ymd_and_hms(y, m, d, hh, mm, 0)
+            .single()
+            .expect("测试时间必须唯一")
+    }
+
+    /// 构造一条“工作阶段运行中”的检查点。
+    fn running_work_checkpoint(remaining_seconds: u64, saved_at: &str) -> TimerCheckpoint {
+        TimerCheckpoint {
+            phase: Phase::Work,
+            remaining_seconds,
+            is_running: true,
+            current_tag: "学习".to_string(),
+            current_task_id: None,
+            phase_started_at: Some(local(2025, 1, 1, 9, 0).to_rfc3339()),
+            phase_started_date: None,
+            phase_started_time: None,
+            work_lock_active: true,
+            auto_work_remaining: 0,
+            phase_total_seconds: 25 * 60,
+            plan_step: None,
+            pauses: Vec::new(),
+            paused_at: None,
+            saved_at: saved_at.to_string(),
+        }
+    }
+
+    /// `checkpoint/from_checkpoint`：往返后应保留全部运行态字段。
+    #[test]
+    fn checkpoint_round_trips_runtime_fields() {
+        let settings = Settings::default();
+        let at = local(2025, 1, 1, 9, 0);
+        let clock = InstantClock::new(at);
+        let mut runtime = TimerRuntime::new(&settings, &["学习".to_string()], &clock);
+        runtime.remaining_seconds = 100;
+        runtime.start(&AppData::default(), &clock);
+
+        let checkpoint = runtime.checkpoint(at);
+        assert_eq!(checkpoint.phase, Phase::Work);
+        assert_eq!(checkpoint.remaining_seconds, 100);
+        assert!(checkpoint.is_running);
+        assert!(checkpoint.work_lock_active);
+        assert_eq!(checkpoint.started_at(), Some(at));
+
+        let restored = TimerRuntime::from_checkpoint(checkpoint.clone(), &clock);
+        assert_eq!(restored.checkpoint(at), checkpoint);
+        assert!(restored.blacklist_locked());
+    }
+
+    /// `started_at`：旧版检查点的“日期 + HH:mm”字段应按本地时区回填为开始时刻。
+    #[test]
+    fn legacy_checkpoint_start_fields_are_parsed_as_local_instant() {
+        let checkpoint: TimerCheckpoint = serde_json::from_value(serde_json::json!({
+            "phase": "work",
+            "remainingSeconds": 60,
+            "isRunning": false,
+            "currentTag": "学习",
+            "workStartedDate": "2025-01-01",
+            "workStartedTime": "09:00",
+            "savedAt": local(2025, 1, 1, 9, 24).to_rfc3339(),
+        }))
+        .unwrap();
+        assert_eq!(checkpoint.started_at(), Some(local(2025, 1, 1, 9, 0)));
+
+        let value = serde_json::to_value(&checkpoint).unwrap();
+        assert!(value.get("phaseStartedDate").is_none());
+    }
+
+    /// `restore_from_checkpoint`：暂停中的检查点不应补算流逝时间。
+    #[test]
+    fn restore_keeps_paused_runtime_unchanged() {
+        let mut data = AppData::default();
+        let mut checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 9, 0).to_rfc3339());
+        checkpoint.is_running = false;
+
+        let (runtime, summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 12, 0)),
+        )
+        .unwrap();
+        assert_eq!(runtime.remaining_seconds, 600);
+        assert!(!runtime.is_running);
+        assert!(runtime.blacklist_locked());
+        assert_eq!(summary, CatchUpSummary::default());
+        assert!(data.history.is_empty());
+    }
+
+    /// `restore_from_checkpoint`：运行中且未到期时应扣除关闭期间的墙钟时间。
+    #[test]
+    fn restore_applies_elapsed_wall_clock_time() {
+        let mut data = AppData::default();
+        let checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 9, 0).to_rfc3339());
+
+        let (runtime, summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 9, 4)),
+        )
+        .unwrap();
+        assert_eq!(runtime.phase, Phase::Work);
+        assert_eq!(runtime.remaining_seconds, 600 - 240);
+        assert!(runtime.is_running);
+        assert_eq!(summary.phases_ended, 0);
+        assert!(data.history.is_empty());
+    }
+
+    /// `restore_from_checkpoint`：关闭期间结束的工作阶段应按本应结束的时刻写入历史，并进入休息。
+    #[test]
+    fn restore_records_work_phase_that_ended_while_closed() {
+        let mut data = AppData::default();
+        data.settings.pomodoro = 25;
+        data.settings.short_break = 5;
+        let checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 9, 15).to_rfc3339());
+
+        let (runtime, summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 9, 27)),
+        )
+        .unwrap();
+        assert_eq!(summary.work_completed, 1);
+        assert_eq!(summary.phases_ended, 1);
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+        assert!(runtime.is_running);
+        assert_eq!(runtime.remaining_seconds, 5 * 60 - 120);
+
+        assert_eq!(data.history.len(), 1);
+        let record = &data.history[0].records[0];
+        assert_eq!(data.history[0].date, "2025-01-01");
+        assert_eq!(record.start_time, "09:00");
+        assert_eq!(record.end_time.as_deref(), Some("09:25"));
+        assert_eq!(record.tag, "学习");
+    }
+
+    /// 退出时记为中断的工作阶段：检查点不再处于运行中，重启后不会补算为完成（不写历史、不计入累计番茄）。
+    #[test]
+    fn quit_interruption_is_not_replayed_as_completion() {
+        let mut data = AppData::default();
+        let checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 9, 15).to_rfc3339());
+        let mut runtime =
+            TimerRuntime::from_checkpoint(checkpoint, &InstantClock::new(local(2025, 1, 1, 9, 15)));
+
+        assert!(runtime.quit(&mut data, &InstantClock::new(local(2025, 1, 1, 9, 16))));
+        assert_eq!(data.interruptions.len(), 1);
+        let record = &data.interruptions[0].records[0];
+        assert_eq!(record.r#type, crate::app_data::InterruptionType::Quit);
+        assert_eq!(record.tag, "学习");
+
+        let checkpoint = runtime.checkpoint(local(2025, 1, 1, 9, 16));
+        assert!(!checkpoint.is_running);
+        let (runtime, summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 10, 0)),
+        )
+        .unwrap();
+        assert_eq!(summary, CatchUpSummary::default());
+        assert_eq!(runtime.phase, Phase::Work);
+        assert!(!runtime.is_running);
+        assert!(data.history.is_empty());
+        assert_eq!(data.total_pomodoros, 0);
+    }
+
+    /// `resume_from_checkpoint`：补算后写回的新检查点再次恢复时不会重放已写入历史的阶段。
+    #[test]
+    fn resume_rewrites_checkpoint_so_restart_does_not_duplicate() {
+        let mut data = AppData::default();
+        data.settings.short_break = 5;
+        let checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 9, 15).to_rfc3339());
+
+        let (_, summary, rewritten) = resume_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 9, 27)),
+        )
+        .unwrap();
+        assert_eq!(summary.work_completed, 1);
+        assert_eq!(data.total_pomodoros, 1);
+        let rewritten = rewritten.unwrap();
+        assert_eq!(rewritten.phase, Phase::ShortBreak);
+
+        let (runtime, summary, again) = resume_from_checkpoint(
+            rewritten,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 9, 28)),
+        )
+        .unwrap();
+        assert_eq!(summary, CatchUpSummary::default());
+        assert!(again.is_none());
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+        assert_eq!(data.history[0].records.len(), 1);
+        assert_eq!(data.total_pomodoros, 1);
+    }
+
+    /// `restore_from_checkpoint`：若休息也在关闭期间结束，应停在未自动开始的工作阶段。
+    #[test]
+    fn restore_walks_through_multiple_phases() {
+        let mut data = AppData::default();
+        data.settings.short_break = 5;
+        data.settings.auto_continue_enabled = false;
+        let checkpoint = running_work_checkpoint(60, &local(2025, 1, 1, 9, 24).to_rfc3339());
+
+        let (runtime, summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 18, 0)),
+        )
+        .unwrap();
+        assert_eq!(summary.phases_ended, 2);
+        assert_eq!(summary.work_completed, 1);
+        assert_eq!(runtime.phase, Phase::Work);
+        assert!(!runtime.is_running);
+        assert!(!runtime.blacklist_locked());
+        assert_eq!(
+            runtime.remaining_seconds,
+            data.settings.pomodoro as u64 * 60
+        );
+    }
+
+    /// `restore_from_checkpoint`：`saved_at` 晚于当前时间（系统时间回拨）时不应补算。
+    #[test]
+    fn restore_ignores_negative_elapsed_time() {
+        let mut data = AppData::default();
+        let checkpoint = running_work_checkpoint(600, &local(2025, 1, 1, 10, 0).to_rfc3339());
+
+        let (runtime, _summary) = restore_from_checkpoint(
+            checkpoint,
+            &mut data,
+            None,
+            &InstantClock::new(local(2025, 1, 1, 9, 0)),
+        )
+        .unwrap();
+        assert_eq!(runtime.remaining_seconds, 600);
+    }
+
+    /// `InstantClock`：应基于给定时间点返回日期/时间，并按日界线与周首日计算逻辑日期与周范围。
+    #[test]
+    fn instant_clock_reports_fixed_instant() {
+        let clock = InstantClock::new(local(2025, 1, 1, 9, 5));
+        assert_eq!(clock.today_date(), "2025-01-01");
+        assert_eq!(clock.now_hhmm(), "09:05");
+        assert_eq!(
+            clock.current_week_range(DayBoundary::default()),
+            ("2024-12-30".to_string(), "2025-01-05".to_string())
+        );
+
+        // 凌晨 0:30、日界线 4 点、周日为首日：逻辑日期为前一天（周二），所在周从周日开始。
+        let clock = InstantClock::new(local(2025, 1, 1, 0, 30));
+        let boundary = DayBoundary {
+            rollover_hour: 4,
+            first_weekday: chrono::Weekday::Sun,
+        };
+        assert_eq!(clock.logical_today(boundary), "2024-12-31");
+        assert_eq!(
+            clock.current_week_range(boundary),
+            ("2024-12-29".to_string(), "2025-01-04".to_string())
+        );
+    }
+}
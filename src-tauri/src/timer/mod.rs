@@ -1,16 +1,21 @@
 //! 计时器引擎：阶段切换、倒计时、历史记录与通知触发。
 
+pub(crate) mod checkpoint;
 pub(crate) mod notification;
 mod runtime;
 pub(crate) mod stats;
 mod validation;
 
+#[cfg(not(test))]
+pub use checkpoint::{resume_from_checkpoint, TimerCheckpoint};
 #[cfg(not(test))]
 pub use notification::TauriNotifier;
 #[cfg(not(test))]
 pub use runtime::TickResult;
 pub use runtime::{SystemClock, TimerClock, TimerRuntime, TimerSnapshot, WorkCompletedEvent};
 pub use stats::compute_today_stats;
+#[cfg(not(test))]
+pub use stats::HistoryCounts;
 pub use validation::validate_settings;
 
 #[cfg(not(test))]
@@ -37,7 +42,7 @@ pub const EVENT_SNAPSHOT: &str = "pomodoro://snapshot";
 /// 向前端广播“工作阶段自然完成”的事件名。
 pub const EVENT_WORK_COMPLETED: &str = "pomodoro://work_completed";
 
-/// 启动后台 tick 任务：每秒更新计时器、推送事件与刷新托盘。
+/// 启动后台 tick 任务：每秒按截止时刻刷新计时器（含挂起检测）、推送事件与刷新托盘。
 #[cfg(not(test))]
 pub fn spawn_timer_task(app: tauri::AppHandle) {
     tauri::async_runtime::spawn(async move {
@@ -1,6 +1,6 @@
 //! 阶段结束通知与目标达成提醒（通过可注入 Notifier 实现，便于测试）。
 
-use crate::app_data::{Phase, Settings};
+use crate::app_data::{Phase, PlanStep, Settings};
 use crate::errors::AppResult;
 
 /// 通知发送抽象：用于将“通知内容生成”与“通知实现（Tauri/其它）”解耦。
@@ -9,6 +9,16 @@ pub trait Notifier {
     fn notify(&self, title: &str, body: &str) -> AppResult<()>;
 }
 
+/// 静默通知器：用于重启补算等不应打扰用户的场景（丢弃所有通知）。
+pub struct SilentNotifier;
+
+impl Notifier for SilentNotifier {
+    /// 忽略通知并返回成功。
+    fn notify(&self, _title: &str, _body: &str) -> AppResult<()> {
+        Ok(())
+    }
+}
+
 /// Tauri 通知实现（基于 `tauri-plugin-notification`）。
 pub struct TauriNotifier<'a> {
     app: &'a tauri::AppHandle,
@@ -57,6 +67,29 @@ pub fn notify_phase_end(
     Ok(())
 }
 
+/// 会话计划中某一步结束时发送系统通知（标题按结束阶段类型，正文预告下一步的名称与时长）。
+pub fn notify_plan_step_end(
+    notifier: &dyn Notifier,
+    ended: Phase,
+    next: &PlanStep,
+    next_auto_started: bool,
+) -> AppResult<()> {
+    let prefix = if next_auto_started {
+        "已自动开始"
+    } else {
+        "即将开始"
+    };
+    let preview = format!("{prefix}{} {} 分钟", next.name, next.minutes);
+    let (title, body) = match ended {
+        Phase::Work => ("专注完成", format!("本阶段已结束。{preview}")),
+        Phase::ShortBreak => ("短休息结束", preview),
+        Phase::LongBreak => ("长休息结束", preview),
+    };
+
+    notifier.notify(title, &body)?;
+    Ok(())
+}
+
 /// 在工作阶段完成后，根据每日/每周目标的阈值触发提醒。
 pub fn notify_goal_progress_if_needed(
     notifier: &dyn Notifier,
@@ -103,6 +136,23 @@ pub fn notify_goal_progress_if_needed(
     Ok(())
 }
 
+/// 今天尚未达成每日目标且会中断连续记录时发送提醒。
+pub fn notify_streak_at_risk(
+    notifier: &dyn Notifier,
+    current_days: u32,
+    daily_completed: u32,
+    daily_goal: u32,
+) -> AppResult<()> {
+    notifier.notify(
+        "连续记录即将中断",
+        &format!(
+            "已连续达标 {current_days} 天，今日还差 {} 个番茄（{daily_completed}/{daily_goal}）",
+            daily_goal.saturating_sub(daily_completed)
+        ),
+    )?;
+    Ok(())
+}
+
 /// 生成“下一阶段预告”文案（区分是否已自动开始）。
 fn phase_preview(next: Phase, next_auto_started: bool, settings: &Settings) -> String {
     let prefix = if next_auto_started {
@@ -201,6 +251,31 @@ mod tests {
         assert_eq!(calls[2].1, "即将开始工作 25 分钟");
     }
 
+    /// `notify_plan_step_end`：标题按结束阶段类型，正文应预告下一步的名称与时长。
+    #[test]
+    fn notify_plan_step_end_previews_next_step_by_name() {
+        let notifier = RecordingNotifier::new();
+        let warm_up = PlanStep {
+            name: "热身".to_string(),
+            kind: Phase::Work,
+            minutes: 15,
+        };
+        let rest = PlanStep {
+            name: "散步".to_string(),
+            kind: Phase::LongBreak,
+            minutes: 30,
+        };
+
+        notify_plan_step_end(&notifier, Phase::Work, &rest, true).unwrap();
+        notify_plan_step_end(&notifier, Phase::LongBreak, &warm_up, false).unwrap();
+
+        let calls = notifier.take();
+        assert_eq!(calls[0].0, "专注完成");
+        assert_eq!(calls[0].1, "本阶段已结束。已自动开始散步 30 分钟");
+        assert_eq!(calls[1].0, "长休息结束");
+        assert_eq!(calls[1].1, "即将开始热身 15 分钟");
+    }
+
     /// `notify_goal_progress_if_needed`：当目标为 0 时不应发送任何通知。
     #[test]
     fn notify_goal_progress_skips_when_goals_are_zero() {
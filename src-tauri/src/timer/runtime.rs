@@ -3,49 +3,153 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::app_data::{AppData, HistoryDay, HistoryRecord, Phase, Settings};
+use std::time::{Duration, Instant};
+
+use crate::app_data::{
+    AppData, DayBoundary, HistoryRecord, InterruptionRecord, InterruptionType, PauseSegment, Phase,
+    SessionPlan, Settings, SuspendExpiryPolicy,
+};
+use crate::audit::DomainEvent;
 use crate::errors::AppResult;
+use crate::timer::checkpoint::{CatchUpSummary, TimerCheckpoint};
 use crate::timer::notification;
-use crate::timer::stats;
+use crate::timer::stats::{self, HistoryCounts, WorkCountQuery};
 
 /// 时间来源：用于将状态机与系统时间解耦，便于单元测试。
+///
+/// 只需提供 `now`（墙钟）与 `monotonic`（单调时钟）；日期/时间字符串均由 `now` 推导。
 pub trait TimerClock {
+    /// 获取当前墙钟时间（本地时区）。
+    fn now(&self) -> chrono::DateTime<chrono::Local>;
+    /// 获取当前单调时钟读数（不受系统时间调整影响，用于推导剩余时间）。
+    fn monotonic(&self) -> Instant;
+
     /// 获取今天日期字符串（YYYY-MM-DD）。
-    fn today_date(&self) -> String;
+    fn today_date(&self) -> String {
+        self.now().format("%Y-%m-%d").to_string()
+    }
+
     /// 获取当前时间字符串（HH:mm）。
-    fn now_hhmm(&self) -> String;
-    /// 获取本周日期范围（周一为起始），返回 `(from, to)`（YYYY-MM-DD）。
-    fn current_week_range(&self) -> (String, String);
+    fn now_hhmm(&self) -> String {
+        self.now().format("%H:%M").to_string()
+    }
+
+    /// 获取按 `boundary` 换算的“逻辑今天”（YYYY-MM-DD）：日界线之前仍算前一天。
+    fn logical_today(&self, boundary: DayBoundary) -> String {
+        boundary
+            .logical_date(self.now().naive_local())
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    /// 获取逻辑今天所在周的日期范围（按 `boundary` 的周首日），返回 `(from, to)`（YYYY-MM-DD）。
+    fn current_week_range(&self, boundary: DayBoundary) -> (String, String) {
+        week_range_of(boundary, boundary.logical_date(self.now().naive_local()))
+    }
 }
 
 /// 默认时间来源：使用本机时钟（`chrono::Local`）。
 pub struct SystemClock;
 
 impl TimerClock for SystemClock {
-    /// 获取今天日期字符串（YYYY-MM-DD）。
-    fn today_date(&self) -> String {
-        chrono::Local::now().format("%Y-%m-%d").to_string()
+    /// 获取当前墙钟时间（本地时区）。
+    fn now(&self) -> chrono::DateTime<chrono::Local> {
+        chrono::Local::now()
     }
 
-    /// 获取当前时间字符串（HH:mm）。
-    fn now_hhmm(&self) -> String {
-        chrono::Local::now().format("%H:%M").to_string()
+    /// 获取当前单调时钟读数。
+    fn monotonic(&self) -> Instant {
+        Instant::now()
     }
+}
 
-    /// 获取本周日期范围（周一为起始），返回 `(from, to)`（YYYY-MM-DD）。
-    fn current_week_range(&self) -> (String, String) {
-        use chrono::{Datelike as _, Duration as ChronoDuration};
-        let today = chrono::Local::now().date_naive();
-        let offset_days = i64::from(today.weekday().num_days_from_monday());
-        let from = today - ChronoDuration::days(offset_days);
-        let to = from + ChronoDuration::days(6);
-        (
-            from.format("%Y-%m-%d").to_string(),
-            to.format("%Y-%m-%d").to_string(),
-        )
+/// 固定时间点的时间来源：用于重启补算时按“阶段本应结束的时刻”写入历史。
+pub struct InstantClock {
+    /// 固定的本地时间点。
+    at: chrono::DateTime<chrono::Local>,
+}
+
+impl InstantClock {
+    /// 创建一个固定在 `at` 的时间来源。
+    pub fn new(at: chrono::DateTime<chrono::Local>) -> Self {
+        Self { at }
+    }
+}
+
+impl TimerClock for InstantClock {
+    /// 返回固定的本地时间点。
+    fn now(&self) -> chrono::DateTime<chrono::Local> {
+        self.at
+    }
+
+    /// 固定时间点没有对应的单调读数：返回当前读数（补算结束后会按真实时钟重新锚定截止时刻）。
+    fn monotonic(&self) -> Instant {
+        Instant::now()
     }
 }
 
+/// 计算 `date` 所在周的日期范围（按 `boundary` 的周首日），返回 `(from, to)`（YYYY-MM-DD）。
+fn week_range_of(boundary: DayBoundary, date: chrono::NaiveDate) -> (String, String) {
+    let from = boundary.week_start_of(date);
+    let to = from + chrono::Duration::days(6);
+    (
+        from.format("%Y-%m-%d").to_string(),
+        to.format("%Y-%m-%d").to_string(),
+    )
+}
+
+/// 两次 tick 之间的间隔超过该秒数时视为发生了挂起/恢复（正常情况下约为 1 秒）。
+const SUSPEND_GAP_SECONDS: u64 = 15;
+
+/// 一次时钟读数（单调时钟 + 墙钟双锚点）：既用于表示截止时刻，也用于记录最近一次观测。
+#[derive(Debug, Clone, Copy)]
+struct ClockReading {
+    /// 单调时钟读数（常规情况下用于推导剩余时间）。
+    monotonic: Instant,
+    /// 墙钟读数（用于挂起恢复后的重新同步与检查点）。
+    wall: chrono::DateTime<chrono::Local>,
+}
+
+impl ClockReading {
+    /// 读取 `clock` 的当前时刻。
+    fn of(clock: &dyn TimerClock) -> Self {
+        Self {
+            monotonic: clock.monotonic(),
+            wall: clock.now(),
+        }
+    }
+
+    /// 计算从当前读数起 `seconds` 秒后的时刻。
+    fn after(self, seconds: u64) -> Self {
+        Self {
+            monotonic: self.monotonic + Duration::from_secs(seconds),
+            wall: self.wall + chrono::Duration::seconds(seconds as i64),
+        }
+    }
+
+    /// 以单调时钟计算距离该时刻还剩多少秒（向上取整，已过期则为 0）。
+    fn seconds_until(self, now: Instant) -> u64 {
+        ceil_seconds(self.monotonic.saturating_duration_since(now))
+    }
+
+    /// 判断从 `self` 到 `now` 是否出现了挂起间隙：墙钟或单调时钟任一方向上的跨度过大。
+    ///
+    /// 部分平台的单调时钟在睡眠期间不计时，因此同时比较墙钟跨度。
+    fn suspended_until(self, now: Self) -> bool {
+        let wall_gap = (now.wall - self.wall).num_seconds().max(0) as u64;
+        let monotonic_gap = now
+            .monotonic
+            .saturating_duration_since(self.monotonic)
+            .as_secs();
+        wall_gap.max(monotonic_gap) >= SUSPEND_GAP_SECONDS
+    }
+}
+
+/// 将时长向上取整为秒数。
+fn ceil_seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
 /// 前端渲染/托盘展示所需的计时器快照。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -59,9 +163,11 @@ pub struct TimerSnapshot {
     pub is_running: bool,
     /// 当前任务标签。
     pub current_tag: String,
+    /// 当前任务 id（未选择任务时为空）。
+    pub current_task_id: Option<String>,
     /// 专注期内黑名单是否锁定（只能增不能减）。
     pub blacklist_locked: bool,
-    /// 当前设置（用于前端展示/校验）。
+    /// 当前设置（已合并当前标签的覆盖项，用于前端展示/校验）。
     pub settings: Settings,
     /// 今日统计（用于主界面展示）。
     pub today_stats: stats::TodayStats,
@@ -81,6 +187,24 @@ pub struct TickResult {
     pub work_auto_started: bool,
     /// 若本次 tick 完成了工作阶段，则携带“新记录已写入”的事件负载。
     pub work_completed_event: Option<WorkCompletedEvent>,
+    /// 是否因挂起期间工作阶段到期而记为中断（用于清空 Combo）。
+    pub work_interrupted: bool,
+    /// 是否检测到挂起/恢复并重新同步了运行态（需要写入检查点）。
+    pub resumed_from_suspend: bool,
+}
+
+impl TickResult {
+    /// 无任何变化的 tick 结果。
+    fn idle() -> Self {
+        Self {
+            history_changed: false,
+            phase_ended: false,
+            work_auto_started: false,
+            work_completed_event: None,
+            work_interrupted: false,
+            resumed_from_suspend: false,
+        }
+    }
 }
 
 /// 工作阶段完成事件：用于前端弹出“备注填写”并定位到对应记录。
@@ -96,24 +220,38 @@ pub struct WorkCompletedEvent {
     pub record: HistoryRecord,
 }
 
-/// 计时器运行态（不持久化；重启后回到默认工作阶段）。
+/// 计时器运行态（状态变化时以 `TimerCheckpoint` 写入 store，重启后按墙钟补算恢复）。
 pub struct TimerRuntime {
     /// 当前阶段。
     pub phase: Phase,
-    /// 剩余秒数。
+    /// 剩余秒数（运行中时为最近一次由截止时刻推导出的值，实时值见 `remaining_seconds_at`）。
     pub remaining_seconds: u64,
     /// 是否运行中。
     pub is_running: bool,
     /// 当前任务标签（用于下一次完成记录）。
     pub current_tag: String,
-    /// 工作阶段首次开始时的日期（YYYY-MM-DD）。
-    work_started_date: Option<String>,
-    /// 工作阶段首次开始时的时间（HH:mm）。
-    work_started_time: Option<String>,
+    /// 当前任务 id（工作记录写入时关联到该任务）。
+    pub current_task_id: Option<String>,
+    /// 当前阶段首次开始的时刻（本地时区；写入历史时作为开始时刻与日期归属）。
+    phase_started_at: Option<chrono::DateTime<chrono::Local>>,
     /// 专注期黑名单锁定标记：一旦工作阶段开始，就禁止移除黑名单条目。
     work_lock_active: bool,
     /// 连续番茄“自动推进”剩余工作次数（仅影响：休息结束后是否自动开始工作）。
     auto_work_remaining: u32,
+    /// 当前阶段的总秒数（经典循环取自设置，会话计划取自步骤时长）。
+    phase_total_seconds: u64,
+    /// 启用会话计划时当前所处的步骤索引（经典循环为 `None`）。
+    plan_step: Option<usize>,
+    /// 运行中阶段的截止时刻（未运行时为 `None`，剩余时间以 `remaining_seconds` 为准）。
+    deadline: Option<ClockReading>,
+    /// 最近一次观测到的时钟读数（用于检测挂起/恢复造成的时间间隙）。
+    last_seen: Option<ClockReading>,
+    /// 当前阶段内已结束的暂停片段（阶段结束时随记录写入历史）。
+    pauses: Vec<PauseSegment>,
+    /// 暂停中时记录暂停开始的墙钟时间（继续/跳过时结算为暂停片段）。
+    paused_at: Option<chrono::DateTime<chrono::Local>>,
+    /// 最近一次检查“连续记录即将中断”提醒的逻辑日期（仅内存，避免同一天重复提醒）。
+    streak_checked_on: Option<String>,
 }
 
 impl TimerRuntime {
@@ -124,41 +262,110 @@ impl TimerRuntime {
             remaining_seconds: settings.pomodoro as u64 * 60,
             is_running: false,
             current_tag: tags.first().cloned().unwrap_or_else(|| "工作".to_string()),
-            work_started_date: None,
-            work_started_time: None,
+            current_task_id: None,
+            phase_started_at: None,
             work_lock_active: false,
             auto_work_remaining: 0,
+            phase_total_seconds: settings.pomodoro as u64 * 60,
+            plan_step: None,
+            deadline: None,
+            last_seen: None,
+            pauses: Vec::new(),
+            paused_at: None,
+            streak_checked_on: None,
         }
         .with_normalized_tag(clock)
     }
 
+    /// 由持久化的检查点重建运行态（不做墙钟补算，补算见 `catch_up`）。
+    pub fn from_checkpoint(checkpoint: TimerCheckpoint, clock: &dyn TimerClock) -> Self {
+        let phase_started_at = checkpoint.started_at();
+        Self {
+            phase: checkpoint.phase,
+            remaining_seconds: checkpoint.remaining_seconds,
+            is_running: checkpoint.is_running,
+            current_tag: checkpoint.current_tag,
+            current_task_id: checkpoint.current_task_id,
+            phase_started_at,
+            work_lock_active: checkpoint.work_lock_active,
+            auto_work_remaining: checkpoint.auto_work_remaining,
+            phase_total_seconds: if checkpoint.phase_total_seconds > 0 {
+                checkpoint.phase_total_seconds
+            } else {
+                checkpoint.remaining_seconds
+            },
+            plan_step: checkpoint.plan_step,
+            deadline: None,
+            last_seen: None,
+            pauses: checkpoint.pauses,
+            paused_at: checkpoint
+                .paused_at
+                .and_then(|at| chrono::DateTime::parse_from_rfc3339(&at).ok())
+                .map(|at| at.with_timezone(&chrono::Local)),
+            streak_checked_on: None,
+        }
+        .with_normalized_tag(clock)
+    }
+
+    /// 生成当前运行态的检查点（`saved_at` 作为重启补算的墙钟锚点）。
+    pub fn checkpoint(&self, saved_at: chrono::DateTime<chrono::Local>) -> TimerCheckpoint {
+        TimerCheckpoint {
+            phase: self.phase,
+            remaining_seconds: self.remaining_seconds,
+            is_running: self.is_running,
+            current_tag: self.current_tag.clone(),
+            current_task_id: self.current_task_id.clone(),
+            phase_started_at: self.phase_started_at.map(|at| at.to_rfc3339()),
+            phase_started_date: None,
+            phase_started_time: None,
+            work_lock_active: self.work_lock_active,
+            auto_work_remaining: self.auto_work_remaining,
+            phase_total_seconds: self.phase_total_seconds,
+            plan_step: self.plan_step,
+            pauses: self.pauses.clone(),
+            paused_at: self.paused_at.map(|at| at.to_rfc3339()),
+            saved_at: saved_at.to_rfc3339(),
+        }
+    }
+
     /// 基于当前数据生成快照（使用系统时钟计算今日/本周统计）。
-    pub fn snapshot(&self, data: &AppData) -> TimerSnapshot {
-        self.snapshot_with_clock(data, &SystemClock)
+    pub fn snapshot(&self, data: &AppData, db: Option<&dyn WorkCountQuery>) -> TimerSnapshot {
+        self.snapshot_with_clock(data, db, &SystemClock)
     }
 
     /// 基于当前数据生成快照（可注入 `clock`，用于测试与边界场景）。
-    pub fn snapshot_with_clock(&self, data: &AppData, clock: &dyn TimerClock) -> TimerSnapshot {
-        let today = clock.today_date();
-        let (from, to) = clock.current_week_range();
-        let today_stats = stats::compute_today_stats(data, &today);
-        let week_stats = stats::compute_week_stats(data, &from, &to);
+    pub fn snapshot_with_clock(
+        &self,
+        data: &AppData,
+        db: Option<&dyn WorkCountQuery>,
+        clock: &dyn TimerClock,
+    ) -> TimerSnapshot {
+        let boundary = data.settings.day_boundary();
+        let today = clock.logical_today(boundary);
+        let (from, to) = clock.current_week_range(boundary);
+        let counts = || HistoryCounts::new(data, db);
+        let today_stats = stats::compute_today_stats(counts(), &today);
+        let week_stats = stats::compute_week_stats(counts(), &from, &to);
 
         TimerSnapshot {
             phase: self.phase,
-            remaining_seconds: self.remaining_seconds,
+            remaining_seconds: self.remaining_seconds_at(clock),
             is_running: self.is_running,
             current_tag: self.current_tag.clone(),
+            current_task_id: self.current_task_id.clone(),
             blacklist_locked: self.blacklist_locked(),
-            settings: data.settings.clone(),
-            today_stats: today_stats.clone(),
-            week_stats: week_stats.clone(),
-            goal_progress: stats::GoalProgress {
-                daily_goal: data.settings.daily_goal,
-                daily_completed: today_stats.total,
-                weekly_goal: data.settings.weekly_goal,
-                weekly_completed: week_stats.total,
-            },
+            settings: data.settings_for_tag(&self.current_tag),
+            today_stats,
+            week_stats,
+            goal_progress: stats::compute_goal_progress(counts(), &today, &from, &to),
+        }
+    }
+
+    /// 计算 `clock` 当前时刻的剩余秒数：运行中由截止时刻推导，否则为暂停时冻结的值。
+    pub fn remaining_seconds_at(&self, clock: &dyn TimerClock) -> u64 {
+        match self.deadline {
+            Some(deadline) if self.is_running => deadline.seconds_until(clock.monotonic()),
+            _ => self.remaining_seconds,
         }
     }
 
@@ -173,108 +380,365 @@ impl TimerRuntime {
     }
 
     /// 计算本次工作阶段已专注秒数（用于 PRD v4：中断记录）。
-    pub fn focused_seconds(&self, settings: &Settings) -> u64 {
+    pub fn focused_seconds(&self) -> u64 {
         if !self.is_work_started() {
             return 0;
         }
-        let total = settings.pomodoro as u64 * 60;
+        let total = self.phase_total_seconds;
         total.saturating_sub(self.remaining_seconds.min(total))
     }
 
+    /// 当前阶段首次开始的时刻（未开始时为 `None`）。
+    pub fn phase_started_at(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.phase_started_at
+    }
+
+    /// 未运行时按当前阶段（或会话计划步骤）的时长同步剩余时间（用于设置/计划变更后保持 UI 一致）。
+    pub fn sync_idle_duration(&mut self, data: &AppData) {
+        if self.is_running {
+            return;
+        }
+        let target = PhaseTarget::current(self, data);
+        self.plan_step = target.plan_step;
+        self.phase_total_seconds = target.seconds;
+        self.remaining_seconds = target.seconds;
+    }
+
     /// 更新当前标签。
     pub fn set_current_tag(&mut self, tag: String, clock: &dyn TimerClock) {
         self.current_tag = tag;
         *self = std::mem::take(self).with_normalized_tag(clock);
     }
 
-    /// 启动计时；若为工作阶段首次开始则记录开始时间并锁定黑名单。
-    pub fn start(&mut self, settings: &Settings, clock: &dyn TimerClock) {
+    /// 启动计时；阶段首次开始时记录开始时间（工作阶段同时锁定黑名单），从暂停继续时结算暂停片段。
+    pub fn start(&mut self, data: &AppData, clock: &dyn TimerClock) {
         if self.is_running {
             return;
         }
         self.is_running = true;
+        self.arm_deadline(clock);
+        self.close_pause(clock);
+        if self.phase_started_at.is_none() {
+            self.phase_started_at = Some(clock.now());
+        }
         if self.phase == Phase::Work && !self.work_lock_active {
             self.work_lock_active = true;
-            self.work_started_date = Some(clock.today_date());
-            self.work_started_time = Some(clock.now_hhmm());
-            self.init_auto_work_remaining_if_needed(settings);
+            self.init_auto_work_remaining_if_needed(&data.settings_for_tag(&self.current_tag));
         }
     }
 
-    /// 暂停计时。
-    pub fn pause(&mut self) {
-        self.is_running = false;
+    /// 暂停计时：按截止时刻冻结剩余时间，并开始记录暂停片段。
+    pub fn pause(&mut self, clock: &dyn TimerClock) {
+        if self.is_running {
+            self.paused_at = Some(clock.now());
+        }
+        self.remaining_seconds = self.remaining_seconds_at(clock);
+        self.stop();
     }
 
-    /// 重置为工作阶段初始状态（不会清空历史）。
-    pub fn reset(&mut self, settings: &Settings) {
-        self.phase = Phase::Work;
-        self.remaining_seconds = settings.pomodoro as u64 * 60;
-        self.is_running = false;
-        self.work_started_date = None;
-        self.work_started_time = None;
-        self.work_lock_active = false;
+    /// 按真实时钟重新锚定运行中阶段的截止时刻（用于检查点补算之后）。
+    pub(crate) fn resume_deadline(&mut self, clock: &dyn TimerClock) {
+        if self.is_running {
+            self.arm_deadline(clock);
+        }
+    }
+
+    /// 重置为初始状态（不会清空历史）：经典循环回到工作阶段，会话计划回到第一步。
+    pub fn reset(&mut self, data: &AppData) {
+        self.apply_target(PhaseTarget::first(data, &self.current_tag));
+        self.stop();
         self.auto_work_remaining = 0;
     }
 
-    /// 跳过当前阶段（工作阶段不会写入历史）。
-    pub fn skip(&mut self, settings: &Settings, completed_today: u32) {
-        let next = next_phase(self.phase, settings.long_break_interval, completed_today);
-        self.apply_phase(next, settings);
-        self.is_running = false;
+    /// 跳过当前阶段：已开始的休息阶段按实际时长写入一条“已跳过”记录（工作阶段不会写入历史）；返回是否写入了历史。
+    pub fn skip(
+        &mut self,
+        data: &mut AppData,
+        completed_today: u32,
+        clock: &dyn TimerClock,
+    ) -> bool {
+        let recorded = self.phase != Phase::Work && self.phase_started_at.is_some();
+        if recorded {
+            self.remaining_seconds = self.remaining_seconds_at(clock);
+            self.append_record(data, clock, true);
+        }
+        let next = PhaseTarget::next(self, data, completed_today);
+        self.apply_target(next);
+        self.stop();
+        recorded
     }
 
-    /// 每秒 tick：递减剩余时间，并在归零时完成阶段切换与（必要时）写入历史。
+    /// 退出应用：工作阶段已开始且启用记录中断时写入一条“quit 中断”，并重置运行态（检查点随之不再处于运行中，
+    /// 下次启动不会把该阶段补算为完成而重复计数）；返回是否已写入。
+    pub fn quit(&mut self, data: &mut AppData, clock: &dyn TimerClock) -> bool {
+        if !data.settings.interruption.enabled || !self.is_work_started() {
+            return false;
+        }
+        self.remaining_seconds = self.remaining_seconds_at(clock);
+        let now = clock.now();
+        let record = InterruptionRecord {
+            timestamp: now.with_timezone(&chrono::Utc).to_rfc3339(),
+            remaining_seconds: self.remaining_seconds,
+            focused_seconds: self.focused_seconds(),
+            reason: String::new(),
+            r#type: InterruptionType::Quit,
+            tag: self.current_tag.clone(),
+            tag_id: data.tag_id_by_path(&self.current_tag),
+            started_at: self.phase_started_at.map(|at| at.to_rfc3339()),
+            ended_at: Some(now.to_rfc3339()),
+        };
+        let date = now.format("%Y-%m-%d").to_string();
+        data.push_interruption(&date, record);
+        self.reset(data);
+        true
+    }
+
+    /// 周期 tick：由截止时刻推导剩余时间，检测挂起/恢复，并在到期时完成阶段切换与（必要时）写入历史。
+    ///
+    /// tick 的调用间隔不影响计时精度：调度抖动或锁等待只会推迟刷新，不会拉长阶段。
     pub fn tick(
         &mut self,
         data: &mut AppData,
+        db: Option<&dyn WorkCountQuery>,
         clock: &dyn TimerClock,
         notifier: &dyn notification::Notifier,
     ) -> AppResult<TickResult> {
+        self.remind_streak_if_needed(data, db, clock, notifier)?;
         if !self.is_running {
-            return Ok(TickResult {
-                history_changed: false,
-                phase_ended: false,
-                work_auto_started: false,
-                work_completed_event: None,
-            });
+            return Ok(TickResult::idle());
         }
-        if self.remaining_seconds > 0 {
-            self.remaining_seconds -= 1;
+        let now = ClockReading::of(clock);
+        let (deadline, last_seen) = match (self.deadline, self.last_seen) {
+            (Some(deadline), Some(last_seen)) => (deadline, last_seen),
+            _ => {
+                // 运行态由外部直接置为运行中（如旧检查点）：以当前剩余时间补建截止时刻。
+                self.arm_deadline(clock);
+                return Ok(TickResult::idle());
+            }
+        };
+
+        if last_seen.suspended_until(now) {
+            return self.resume_after_suspend(data, db, clock, notifier, deadline, last_seen, now);
         }
+
+        self.last_seen = Some(now);
+        self.remaining_seconds = deadline.seconds_until(now.monotonic);
         if self.remaining_seconds > 0 {
+            return Ok(TickResult::idle());
+        }
+
+        self.finish_phase(data, db, clock, notifier)
+    }
+
+    /// 逻辑日内到达提醒时刻后检查一次连续达标：若今天未达标且会中断连续记录，则发送提醒。
+    fn remind_streak_if_needed(
+        &mut self,
+        data: &AppData,
+        db: Option<&dyn WorkCountQuery>,
+        clock: &dyn TimerClock,
+        notifier: &dyn notification::Notifier,
+    ) -> AppResult<()> {
+        use chrono::Timelike as _;
+        let settings = &data.settings;
+        if !settings.streak.reminder_enabled || settings.daily_goal == 0 {
+            return Ok(());
+        }
+        // 以日界线为起点比较“逻辑日内的小时”，使凌晨仍属于前一天的时段也算作较晚。
+        let boundary = settings.day_boundary();
+        let logical_hour = |hour: u32| (hour + 24 - boundary.rollover_hour) % 24;
+        if logical_hour(clock.now().hour()) < logical_hour(u32::from(settings.streak.reminder_hour))
+        {
+            return Ok(());
+        }
+        let today = clock.logical_today(boundary);
+        if self.streak_checked_on.as_deref() == Some(today.as_str()) {
+            return Ok(());
+        }
+        self.streak_checked_on = Some(today.clone());
+
+        let streak = stats::compute_streak(HistoryCounts::new(data, db), &today);
+        if streak.is_at_risk() {
+            let completed = stats::compute_today_stats(HistoryCounts::new(data, db), &today).total;
+            notification::notify_streak_at_risk(
+                notifier,
+                streak.current_days,
+                completed,
+                settings.daily_goal,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// 挂起/恢复后的处理：以墙钟截止时刻为准重新同步；若阶段在挂起期间已到期，则按设置记为完成、中断或暂停。
+    #[allow(clippy::too_many_arguments)]
+    fn resume_after_suspend(
+        &mut self,
+        data: &mut AppData,
+        db: Option<&dyn WorkCountQuery>,
+        clock: &dyn TimerClock,
+        notifier: &dyn notification::Notifier,
+        deadline: ClockReading,
+        suspended_at: ClockReading,
+        now: ClockReading,
+    ) -> AppResult<TickResult> {
+        let policy = data.settings.suspend_expiry_policy;
+        tracing::info!(
+            target: "timer",
+            "检测到挂起/恢复：suspendedAt={} resumedAt={} phase={:?} deadline={} policy={:?}",
+            suspended_at.wall.to_rfc3339(),
+            now.wall.to_rfc3339(),
+            self.phase,
+            deadline.wall.to_rfc3339(),
+            policy
+        );
+
+        if let Ok(left) = (deadline.wall - now.wall).to_std() {
+            // 未到期：挂起期间的墙钟时间同样计入当前阶段，仅需按墙钟重新锚定单调截止时刻。
+            self.deadline = Some(ClockReading {
+                monotonic: now.monotonic + left,
+                wall: deadline.wall,
+            });
+            self.last_seen = Some(now);
+            self.remaining_seconds = ceil_seconds(left);
             return Ok(TickResult {
-                history_changed: false,
-                phase_ended: false,
-                work_auto_started: false,
-                work_completed_event: None,
+                resumed_from_suspend: true,
+                ..TickResult::idle()
             });
         }
 
+        let mut result = match policy {
+            SuspendExpiryPolicy::Completed => {
+                self.remaining_seconds = 0;
+                self.finish_phase_at(data, db, &InstantClock::new(deadline.wall), clock, notifier)?
+            }
+            SuspendExpiryPolicy::Interrupted => {
+                let work_interrupted = self.record_suspend_interruption(data, deadline.wall);
+                self.reset(data);
+                TickResult {
+                    history_changed: work_interrupted,
+                    phase_ended: true,
+                    work_interrupted,
+                    ..TickResult::idle()
+                }
+            }
+            SuspendExpiryPolicy::Paused => {
+                let left = (deadline.wall - suspended_at.wall)
+                    .to_std()
+                    .unwrap_or_default();
+                self.remaining_seconds = ceil_seconds(left).max(1);
+                self.stop();
+                self.paused_at = Some(suspended_at.wall);
+                TickResult::idle()
+            }
+        };
+        result.resumed_from_suspend = true;
+        Ok(result)
+    }
+
+    /// 挂起期间工作阶段到期且策略为“记为中断”时，写入一条挂起中断记录；返回是否已写入。
+    fn record_suspend_interruption(
+        &self,
+        data: &mut AppData,
+        expired_at: chrono::DateTime<chrono::Local>,
+    ) -> bool {
+        if !data.settings.interruption.enabled || !self.is_work_started() {
+            return false;
+        }
+        let record = InterruptionRecord {
+            timestamp: expired_at.with_timezone(&chrono::Utc).to_rfc3339(),
+            remaining_seconds: 0,
+            focused_seconds: self.phase_total_seconds,
+            reason: String::new(),
+            r#type: InterruptionType::Suspend,
+            tag: self.current_tag.clone(),
+            tag_id: data.tag_id_by_path(&self.current_tag),
+            started_at: self.phase_started_at.map(|at| at.to_rfc3339()),
+            ended_at: Some(expired_at.to_rfc3339()),
+        };
+        let date = expired_at.format("%Y-%m-%d").to_string();
+        data.push_interruption(&date, record);
+        true
+    }
+
+    /// 将应用关闭期间流逝的 `elapsed_seconds` 补算到运行态（从 `anchor` 时刻开始计）。
+    ///
+    /// 期间自然结束的阶段按其结束时刻完成切换并写入历史；计时停止后剩余的时间不再计入。
+    pub fn catch_up(
+        &mut self,
+        data: &mut AppData,
+        db: Option<&dyn WorkCountQuery>,
+        mut elapsed_seconds: u64,
+        anchor: chrono::DateTime<chrono::Local>,
+        notifier: &dyn notification::Notifier,
+    ) -> AppResult<CatchUpSummary> {
+        let mut summary = CatchUpSummary::default();
+        let mut cursor = anchor;
+        while self.is_running && elapsed_seconds > 0 {
+            if self.remaining_seconds > elapsed_seconds {
+                self.remaining_seconds -= elapsed_seconds;
+                break;
+            }
+            elapsed_seconds -= self.remaining_seconds;
+            cursor += chrono::Duration::seconds(self.remaining_seconds as i64);
+            self.remaining_seconds = 0;
+
+            let result = self.finish_phase(data, db, &InstantClock::new(cursor), notifier)?;
+            summary.phases_ended += 1;
+            if result.work_completed_event.is_some() {
+                summary.work_completed += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    /// 当前阶段归零后的收尾：写入历史（工作与休息阶段）、目标提醒（工作阶段）、切换到下一阶段并按规则自动开始。
+    fn finish_phase(
+        &mut self,
+        data: &mut AppData,
+        db: Option<&dyn WorkCountQuery>,
+        clock: &dyn TimerClock,
+        notifier: &dyn notification::Notifier,
+    ) -> AppResult<TickResult> {
+        self.finish_phase_at(data, db, clock, clock, notifier)
+    }
+
+    /// `finish_phase` 的实现：`clock` 为阶段结束时刻（写入历史/统计），`next_clock` 为下一阶段的开始时刻。
+    fn finish_phase_at(
+        &mut self,
+        data: &mut AppData,
+        db: Option<&dyn WorkCountQuery>,
+        clock: &dyn TimerClock,
+        next_clock: &dyn TimerClock,
+        notifier: &dyn notification::Notifier,
+    ) -> AppResult<TickResult> {
         let ended_phase = self.phase;
-        let mut history_changed = false;
+        let settings = data.settings_for_tag(&self.current_tag);
         let mut work_completed_event: Option<WorkCompletedEvent> = None;
 
-        let today = clock.today_date();
-        let (from, to) = clock.current_week_range();
-        let mut completed_today_after = stats::compute_today_stats(data, &today).total;
+        let boundary = data.settings.day_boundary();
+        let today = clock.logical_today(boundary);
+        let (from, to) = clock.current_week_range(boundary);
+        let mut completed_today_after =
+            stats::compute_today_stats(HistoryCounts::new(data, db), &today).total;
         let completed_today_before = completed_today_after;
-        let completed_week_before = stats::compute_week_stats(data, &from, &to).total;
+        let completed_week_before =
+            stats::compute_week_stats(HistoryCounts::new(data, db), &from, &to).total;
         let mut completed_week_after = completed_week_before;
 
         if ended_phase == Phase::Work {
             let created = self.append_work_record(data, clock)?;
-            history_changed = true;
             completed_today_after += 1;
             completed_week_after += 1;
-            self.decrease_auto_work_remaining_after_work_end(&data.settings);
+            self.decrease_auto_work_remaining_after_work_end(&settings);
             work_completed_event = Some(created);
             tracing::info!(
                 target: "timer",
                 "工作阶段完成：date={} tag={} duration={}m todayCompleted={} weekCompleted={}",
-                self.work_started_date.clone().unwrap_or_else(|| today.clone()),
+                self.phase_started_at
+                    .map(|at| at.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| today.clone()),
                 self.current_tag,
-                data.settings.pomodoro,
+                self.phase_total_seconds / 60,
                 completed_today_after,
                 completed_week_after
             );
@@ -286,25 +750,32 @@ impl TimerRuntime {
                 completed_week_before,
                 completed_week_after,
             )?;
+        } else {
+            self.append_record(data, clock, false);
         }
 
-        let next = next_phase(
-            ended_phase,
-            data.settings.long_break_interval,
-            completed_today_after,
-        );
-        self.apply_phase(next, &data.settings);
-        self.is_running = false;
+        let target = PhaseTarget::next(self, data, completed_today_after);
+        let next = target.phase;
+        self.apply_target(target);
+        self.stop();
 
-        let next_auto_started = self.start_next_phase_if_needed(next, &data.settings, clock);
+        let next_auto_started = self.start_next_phase_if_needed(next, data, next_clock);
 
-        notification::notify_phase_end(
-            notifier,
-            ended_phase,
-            next,
-            next_auto_started,
-            &data.settings,
-        )?;
+        match target
+            .plan_step
+            .and_then(|i| data.active_session_plan()?.steps.get(i))
+        {
+            Some(step) => {
+                notification::notify_plan_step_end(notifier, ended_phase, step, next_auto_started)?
+            }
+            None => notification::notify_phase_end(
+                notifier,
+                ended_phase,
+                next,
+                next_auto_started,
+                &settings,
+            )?,
+        }
 
         tracing::info!(
             target: "timer",
@@ -315,10 +786,11 @@ impl TimerRuntime {
         );
 
         Ok(TickResult {
-            history_changed,
+            history_changed: true,
             phase_ended: true,
             work_auto_started: next == Phase::Work && next_auto_started,
             work_completed_event,
+            ..TickResult::idle()
         })
     }
 
@@ -328,42 +800,96 @@ impl TimerRuntime {
         data: &mut AppData,
         clock: &dyn TimerClock,
     ) -> AppResult<WorkCompletedEvent> {
-        let date = self
-            .work_started_date
-            .clone()
-            .unwrap_or_else(|| clock.today_date());
-        let start_time = self
-            .work_started_time
-            .clone()
-            .unwrap_or_else(|| clock.now_hhmm());
-        let end_time = clock.now_hhmm();
+        let (date, record_index, record) = self.append_record(data, clock, false);
+        Ok(WorkCompletedEvent {
+            date,
+            record_index,
+            record,
+        })
+    }
 
+    /// 将当前阶段（含实际计时与暂停片段）写入 `history`，返回 `(日期, 当日索引, 记录)`。
+    fn append_record(
+        &mut self,
+        data: &mut AppData,
+        clock: &dyn TimerClock,
+        skipped: bool,
+    ) -> (String, usize, HistoryRecord) {
+        self.close_pause(clock);
+        let ended_at = clock.now();
+        let started_at = self.phase_started_at.unwrap_or(ended_at);
+        let elapsed_seconds = self
+            .phase_total_seconds
+            .saturating_sub(self.remaining_seconds.min(self.phase_total_seconds));
+
+        let date = started_at.format("%Y-%m-%d").to_string();
         let record = HistoryRecord {
+            id: uuid::Uuid::new_v4().to_string(),
             tag: self.current_tag.clone(),
-            start_time,
-            end_time: Some(end_time),
-            duration: data.settings.pomodoro,
-            phase: Phase::Work,
+            tag_id: data.tag_id_by_path(&self.current_tag),
+            task_id: match self.phase {
+                Phase::Work => self.current_task_id.clone(),
+                Phase::ShortBreak | Phase::LongBreak => None,
+            },
+            start_time: started_at.format("%H:%M").to_string(),
+            end_time: Some(ended_at.format("%H:%M").to_string()),
+            started_at: Some(started_at.to_rfc3339()),
+            ended_at: Some(ended_at.to_rfc3339()),
+            duration: (elapsed_seconds / 60) as u32,
+            phase: self.phase,
             remark: String::new(),
+            elapsed_seconds: Some(elapsed_seconds),
+            pauses: std::mem::take(&mut self.pauses),
+            skipped,
         };
 
-        let day = ensure_day(&mut data.history, &date);
+        let day = data.history_day_mut(false, &date);
         day.records.push(record.clone());
         let record_index = day.records.len().saturating_sub(1);
-        Ok(WorkCompletedEvent {
-            date,
-            record_index,
-            record,
-        })
+        data.record_event(DomainEvent::PomodoroCompleted {
+            date: date.clone(),
+            record: record.clone(),
+        });
+        (date, record_index, record)
+    }
+
+    /// 若处于暂停中，则以 `clock` 当前时刻结束暂停并记为一个暂停片段。
+    fn close_pause(&mut self, clock: &dyn TimerClock) {
+        let Some(at) = self.paused_at.take() else {
+            return;
+        };
+        let now = clock.now();
+        self.pauses.push(PauseSegment {
+            start_time: at.format("%H:%M").to_string(),
+            end_time: now.format("%H:%M").to_string(),
+            seconds: (now - at).num_seconds().max(0) as u64,
+        });
     }
 
-    /// 应用阶段切换：重置剩余时间与锁定标记。
-    fn apply_phase(&mut self, phase: Phase, settings: &Settings) {
-        self.phase = phase;
-        self.remaining_seconds = phase_seconds(phase, settings);
-        self.work_started_date = None;
-        self.work_started_time = None;
+    /// 以当前 `remaining_seconds` 设置截止时刻，并记录本次观测。
+    fn arm_deadline(&mut self, clock: &dyn TimerClock) {
+        let now = ClockReading::of(clock);
+        self.deadline = Some(now.after(self.remaining_seconds));
+        self.last_seen = Some(now);
+    }
+
+    /// 停止计时并清除截止时刻（剩余时间保留在 `remaining_seconds`）。
+    fn stop(&mut self) {
+        self.is_running = false;
+        self.deadline = None;
+        self.last_seen = None;
+    }
+
+    /// 应用阶段切换：重置剩余时间、步骤索引与锁定标记。
+    fn apply_target(&mut self, target: PhaseTarget) {
+        self.phase = target.phase;
+        self.remaining_seconds = target.seconds;
+        self.phase_total_seconds = target.seconds;
+        self.plan_step = target.plan_step;
+        self.phase_started_at = None;
         self.work_lock_active = false;
+        self.pauses.clear();
+        self.paused_at = None;
     }
 
     /// 初始化“连续番茄自动推进”的剩余工作次数（仅在工作阶段首次开始时触发）。
@@ -392,19 +918,20 @@ impl TimerRuntime {
     fn start_next_phase_if_needed(
         &mut self,
         next: Phase,
-        settings: &Settings,
+        data: &AppData,
         clock: &dyn TimerClock,
     ) -> bool {
+        let settings = data.settings_for_tag(&self.current_tag);
         match next {
             Phase::ShortBreak | Phase::LongBreak => {
                 // 工作结束后始终自动进入休息倒计时。
-                self.start(settings, clock);
+                self.start(data, clock);
                 true
             }
             Phase::Work => {
                 // 休息结束后：仅在“连续番茄自动推进”开启且仍有剩余时自动开始工作。
                 if settings.auto_continue_enabled && self.auto_work_remaining > 0 {
-                    self.start(settings, clock);
+                    self.start(data, clock);
                     true
                 } else {
                     false
@@ -418,24 +945,21 @@ impl TimerRuntime {
         if self.current_tag.trim().is_empty() {
             self.current_tag = "工作".to_string();
         }
-        // 若计时器已经在工作阶段运行中，且日期/时间缺失，则补齐（避免中途迁移导致的 None）。
-        if self.phase == Phase::Work && self.is_running {
-            if self.work_started_date.is_none() {
-                self.work_started_date = Some(clock.today_date());
-            }
-            if self.work_started_time.is_none() {
-                self.work_started_time = Some(clock.now_hhmm());
-            }
+        // 若计时器已经在运行中，且阶段开始时刻缺失，则补齐（避免中途迁移导致的 None）。
+        if self.is_running && self.phase_started_at.is_none() {
+            self.phase_started_at = Some(clock.now());
         }
         self
     }
 
-    /// 测试辅助：读取工作阶段启动的日期/时间（仅用于覆盖迁移防御逻辑）。
+    /// 测试辅助：读取阶段首次开始的日期/时间（仅用于覆盖迁移防御逻辑）。
     #[cfg(test)]
-    pub(crate) fn debug_work_started_at(&self) -> (Option<String>, Option<String>) {
+    pub(crate) fn debug_phase_started_at(&self) -> (Option<String>, Option<String>) {
         (
-            self.work_started_date.clone(),
-            self.work_started_time.clone(),
+            self.phase_started_at
+                .map(|at| at.format("%Y-%m-%d").to_string()),
+            self.phase_started_at
+                .map(|at| at.format("%H:%M").to_string()),
         )
     }
 }
@@ -448,11 +972,80 @@ impl Default for TimerRuntime {
             remaining_seconds: 0,
             is_running: false,
             current_tag: "工作".to_string(),
-            work_started_date: None,
-            work_started_time: None,
+            current_task_id: None,
+            phase_started_at: None,
             work_lock_active: false,
             auto_work_remaining: 0,
+            phase_total_seconds: 0,
+            plan_step: None,
+            deadline: None,
+            last_seen: None,
+            pauses: Vec::new(),
+            paused_at: None,
+            streak_checked_on: None,
+        }
+    }
+}
+
+/// 阶段切换目标：阶段类型、时长，以及（启用会话计划时的）步骤索引。
+#[derive(Debug, Clone, Copy)]
+struct PhaseTarget {
+    /// 目标阶段类型。
+    phase: Phase,
+    /// 目标阶段总秒数。
+    seconds: u64,
+    /// 会话计划步骤索引（经典循环为 `None`）。
+    plan_step: Option<usize>,
+}
+
+impl PhaseTarget {
+    /// 经典循环中的某个阶段。
+    fn classic(phase: Phase, settings: &Settings) -> Self {
+        Self {
+            phase,
+            seconds: phase_seconds(phase, settings),
+            plan_step: None,
+        }
+    }
+
+    /// 会话计划中的第 `index` 步（调用方保证索引有效）。
+    fn step(plan: &SessionPlan, index: usize) -> Self {
+        let step = &plan.steps[index];
+        Self {
+            phase: step.kind,
+            seconds: step.minutes as u64 * 60,
+            plan_step: Some(index),
+        }
+    }
+
+    /// 初始目标：会话计划的第一步，或经典循环的工作阶段（时长按 `tag` 的覆盖设置）。
+    fn first(data: &AppData, tag: &str) -> Self {
+        match data.active_session_plan() {
+            Some(plan) => Self::step(plan, 0),
+            None => Self::classic(Phase::Work, &data.settings_for_tag(tag)),
+        }
+    }
+
+    /// 运行态当前所处的目标（计划步骤已失效时回退为经典循环的同类阶段）。
+    fn current(runtime: &TimerRuntime, data: &AppData) -> Self {
+        match (data.active_session_plan(), runtime.plan_step) {
+            (Some(plan), Some(index)) if index < plan.steps.len() => Self::step(plan, index),
+            _ => Self::classic(runtime.phase, &data.settings_for_tag(&runtime.current_tag)),
+        }
+    }
+
+    /// 下一目标：启用会话计划时按步骤顺序循环推进，否则按经典循环（`next_phase`）推导。
+    fn next(runtime: &TimerRuntime, data: &AppData, completed_today_after: u32) -> Self {
+        if let Some(plan) = data.active_session_plan() {
+            let index = runtime.plan_step.map_or(0, |i| (i + 1) % plan.steps.len());
+            return Self::step(plan, index);
         }
+        let phase = next_phase(
+            runtime.phase,
+            data.settings.long_break_interval,
+            completed_today_after,
+        );
+        Self::classic(phase, &data.settings_for_tag(&runtime.current_tag))
     }
 }
 
@@ -480,26 +1073,16 @@ fn next_phase(current: Phase, long_break_interval: u32, completed_today_after: u
     }
 }
 
-/// 在历史数组中确保存在指定日期的 `HistoryDay`，并返回可变引用。
-fn ensure_day<'a>(history: &'a mut Vec<HistoryDay>, date: &str) -> &'a mut HistoryDay {
-    if let Some(index) = history.iter().position(|d| d.date == date) {
-        return &mut history[index];
-    }
-
-    history.push(HistoryDay {
-        date: date.to_string(),
-        records: Vec::new(),
-    });
-    let last_index = history.len().saturating_sub(1);
-    &mut history[last_index]
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::app_data::HistoryDay;
+    use std::cell::Cell;
     use std::sync::Once;
 
+    use chrono::TimeZone as _;
+
     /// 初始化 `tracing`（仅一次）：确保 `tracing::info!` 的字段参数会被求值，便于覆盖率统计。
     fn init_tracing_once() {
         static INIT: Once = Once::new();
@@ -512,11 +1095,16 @@ mod tests {
     }
 
     /// 固定时间源：用于让单元测试在任意时间运行都可复现。
+    ///
+    /// 日期/时间字符串保持固定；墙钟与单调时钟可分别推进，用于模拟流逝与挂起。
     struct FixedClock {
         today: String,
         now: String,
         week_from: String,
         week_to: String,
+        base: Instant,
+        wall_offset: Cell<i64>,
+        monotonic_offset: Cell<u64>,
     }
 
     impl FixedClock {
@@ -527,9 +1115,26 @@ mod tests {
                 now: now.to_string(),
                 week_from: today.to_string(),
                 week_to: today.to_string(),
+                base: Instant::now(),
+                wall_offset: Cell::new(0),
+                monotonic_offset: Cell::new(0),
             }
         }
 
+        /// 正常推进 `seconds` 秒（墙钟与单调时钟同步前进）。
+        fn advance(&self, seconds: u64) {
+            self.wall_offset
+                .set(self.wall_offset.get() + seconds as i64);
+            self.monotonic_offset
+                .set(self.monotonic_offset.get() + seconds);
+        }
+
+        /// 模拟系统挂起 `seconds` 秒（仅墙钟前进，单调时钟停止）。
+        fn suspend(&self, seconds: u64) {
+            self.wall_offset
+                .set(self.wall_offset.get() + seconds as i64);
+        }
+
         /// 覆盖本周范围（闭区间）。
         fn with_week_range(mut self, from: &str, to: &str) -> Self {
             self.week_from = from.to_string();
@@ -549,10 +1154,34 @@ mod tests {
             self.now.clone()
         }
 
+        /// 返回固定的逻辑日期（忽略日界线）。
+        fn logical_today(&self, _boundary: DayBoundary) -> String {
+            self.today.clone()
+        }
+
         /// 返回固定的周范围。
-        fn current_week_range(&self) -> (String, String) {
+        fn current_week_range(&self, _boundary: DayBoundary) -> (String, String) {
             (self.week_from.clone(), self.week_to.clone())
         }
+
+        /// 返回固定日期/时间加上已推进墙钟秒数后的时间点。
+        fn now(&self) -> chrono::DateTime<chrono::Local> {
+            let naive = chrono::NaiveDateTime::parse_from_str(
+                &format!("{} {}", self.today, self.now),
+                "%Y-%m-%d %H:%M",
+            )
+            .expect("测试时间格式必须合法");
+            chrono::Local
+                .from_local_datetime(&naive)
+                .single()
+                .expect("测试时间必须唯一")
+                + chrono::Duration::seconds(self.wall_offset.get())
+        }
+
+        /// 返回构造时读数加上已推进单调秒数后的读数。
+        fn monotonic(&self) -> Instant {
+            self.base + Duration::from_secs(self.monotonic_offset.get())
+        }
     }
 
     /// 空通知器：测试时忽略通知副作用。
@@ -565,6 +1194,48 @@ mod tests {
         }
     }
 
+    /// 记录型通知器：收集通知标题，便于断言提醒是否发送。
+    struct RecordingNotifier {
+        titles: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl notification::Notifier for RecordingNotifier {
+        /// 记录通知标题并返回成功。
+        fn notify(&self, title: &str, _body: &str) -> AppResult<()> {
+            self.titles.borrow_mut().push(title.to_string());
+            Ok(())
+        }
+    }
+
+    /// `tick`：到达提醒时刻且今天未达标会中断连续记录时提醒一次，同一天不重复。
+    #[test]
+    fn tick_reminds_streak_at_risk_once_per_day() {
+        let clock = FixedClock::new("2025-01-06", "20:59");
+        let notifier = RecordingNotifier {
+            titles: std::cell::RefCell::new(Vec::new()),
+        };
+        let mut data = AppData::default();
+        data.settings.daily_goal = 1;
+        for date in ["2025-01-04", "2025-01-05"] {
+            data.history.push(HistoryDay {
+                date: date.to_string(),
+                records: vec![HistoryRecord::for_test("学习", "09:00").with_end("09:25")],
+            });
+        }
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+
+        runtime.tick(&mut data, None, &clock, &notifier).unwrap();
+        assert!(notifier.titles.borrow().is_empty());
+
+        clock.advance(60);
+        runtime.tick(&mut data, None, &clock, &notifier).unwrap();
+        runtime.tick(&mut data, None, &clock, &notifier).unwrap();
+        assert_eq!(
+            *notifier.titles.borrow(),
+            vec!["连续记录即将中断".to_string()]
+        );
+    }
+
     /// `tick` 在归零时会写入历史并切换到休息阶段。
     #[test]
     fn tick_completes_work_and_switches_to_break() {
@@ -580,10 +1251,11 @@ mod tests {
         data.tags = vec!["学习".to_string()];
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
         runtime.remaining_seconds = 1;
+        runtime.start(&data, &clock);
+        clock.advance(1);
 
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(out.history_changed);
         assert!(out.phase_ended);
         assert!(out.work_completed_event.is_some());
@@ -612,10 +1284,11 @@ mod tests {
         data.tags = vec!["学习".to_string()];
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
         runtime.remaining_seconds = 1;
+        runtime.start(&data, &clock);
+        clock.advance(1);
 
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(out.phase_ended);
     }
 
@@ -630,7 +1303,7 @@ mod tests {
         runtime.remaining_seconds = 10;
         runtime.is_running = false;
 
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(!out.history_changed);
         assert!(!out.phase_ended);
         assert!(!out.work_auto_started);
@@ -638,23 +1311,160 @@ mod tests {
         assert_eq!(runtime.remaining_seconds, 10);
     }
 
-    /// `tick`：运行中且剩余时间未归零时应仅递减秒数，不触发阶段结束。
+    /// `tick`：运行中且未到截止时刻时应仅刷新剩余秒数，不触发阶段结束。
     #[test]
-    fn tick_decrements_without_ending_phase() {
+    fn tick_derives_remaining_without_ending_phase() {
         let clock = FixedClock::new("2025-01-01", "09:00");
         let notifier = NoopNotifier;
         let mut data = AppData::default();
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
         runtime.remaining_seconds = 2;
+        runtime.start(&data, &clock);
+        clock.advance(1);
 
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(!out.history_changed);
         assert!(!out.phase_ended);
+        assert!(!out.resumed_from_suspend);
         assert_eq!(runtime.remaining_seconds, 1);
     }
 
+    /// `tick`：调用间隔变长（调度抖动/锁等待）不应拉长阶段，剩余时间始终按截止时刻推导。
+    #[test]
+    fn tick_is_not_slowed_down_by_late_ticks() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let notifier = NoopNotifier;
+        let mut data = AppData::default();
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.remaining_seconds = 10;
+        runtime.start(&data, &clock);
+        clock.advance(7);
+
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
+        assert!(!out.phase_ended);
+        assert_eq!(runtime.remaining_seconds, 3);
+
+        clock.advance(3);
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
+        assert!(out.phase_ended);
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+    }
+
+    /// 构造“工作阶段剩余 60 秒且运行中”的运行态，并设置挂起到期策略（挂起相关测试复用）。
+    fn running_work_with_policy(
+        clock: &FixedClock,
+        policy: SuspendExpiryPolicy,
+    ) -> (AppData, TimerRuntime) {
+        let mut data = AppData::default();
+        data.settings.suspend_expiry_policy = policy;
+        data.tags = vec!["学习".to_string()];
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, clock);
+        runtime.remaining_seconds = 60;
+        runtime.start(&data, clock);
+        (data, runtime)
+    }
+
+    /// 挂起期间未到期：挂起时长同样计入当前阶段，恢复后继续运行。
+    #[test]
+    fn suspend_without_expiry_keeps_running_on_wall_clock() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let (mut data, mut runtime) = running_work_with_policy(&clock, SuspendExpiryPolicy::Paused);
+
+        clock.suspend(40);
+        let out = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert!(out.resumed_from_suspend);
+        assert!(!out.phase_ended);
+        assert!(runtime.is_running);
+        assert_eq!(runtime.remaining_seconds, 20);
+
+        // 重新锚定后，单调时钟继续正常推导。
+        clock.advance(5);
+        let _ = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert_eq!(runtime.remaining_seconds, 15);
+    }
+
+    /// 挂起期间到期 + “记为完成”：按截止时刻写入历史，并从恢复时刻开始休息。
+    #[test]
+    fn suspend_expiry_completed_records_history_at_deadline() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let (mut data, mut runtime) =
+            running_work_with_policy(&clock, SuspendExpiryPolicy::Completed);
+
+        clock.suspend(600);
+        let out = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert!(out.resumed_from_suspend);
+        assert!(out.phase_ended);
+        assert!(out.work_completed_event.is_some());
+        assert_eq!(data.history.len(), 1);
+        let record = &data.history[0].records[0];
+        assert_eq!(record.start_time, "09:00");
+        assert_eq!(record.end_time.as_deref(), Some("09:01"));
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+        assert!(runtime.is_running);
+        assert_eq!(
+            runtime.remaining_seconds_at(&clock),
+            data.settings.short_break as u64 * 60
+        );
+    }
+
+    /// 挂起期间到期 + “记为中断”：写入挂起中断记录并回到工作阶段初始状态。
+    #[test]
+    fn suspend_expiry_interrupted_records_interruption() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let (mut data, mut runtime) =
+            running_work_with_policy(&clock, SuspendExpiryPolicy::Interrupted);
+
+        clock.suspend(600);
+        let out = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert!(out.work_interrupted);
+        assert!(out.history_changed);
+        assert!(out.work_completed_event.is_none());
+        assert!(data.history.is_empty());
+        assert_eq!(data.interruptions.len(), 1);
+        let record = &data.interruptions[0].records[0];
+        assert_eq!(record.r#type, InterruptionType::Suspend);
+        assert_eq!(record.tag, "学习");
+        assert_eq!(runtime.phase, Phase::Work);
+        assert!(!runtime.is_running);
+        assert!(!runtime.blacklist_locked());
+    }
+
+    /// 挂起期间到期 + “暂停”：恢复为挂起前的剩余时间并暂停，保留专注锁定。
+    #[test]
+    fn suspend_expiry_paused_restores_remaining_before_suspend() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let (mut data, mut runtime) = running_work_with_policy(&clock, SuspendExpiryPolicy::Paused);
+
+        clock.advance(10);
+        let _ = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert_eq!(runtime.remaining_seconds, 50);
+
+        // 单调时钟在睡眠期间也计时的平台：两种时钟同时跨越大间隙。
+        clock.advance(600);
+        let out = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert!(out.resumed_from_suspend);
+        assert!(!out.phase_ended);
+        assert!(!runtime.is_running);
+        assert_eq!(runtime.remaining_seconds, 50);
+        assert!(runtime.blacklist_locked());
+        assert!(data.history.is_empty());
+    }
+
     /// `with_normalized_tag`：运行中且缺失开始时间时应自动补齐（用于中途迁移/恢复的防御逻辑）。
     #[test]
     fn with_normalized_tag_fills_started_at_when_running() {
@@ -665,7 +1475,7 @@ mod tests {
         runtime.current_tag = "学习".to_string();
 
         runtime = runtime.with_normalized_tag(&clock);
-        let (date, time) = runtime.debug_work_started_at();
+        let (date, time) = runtime.debug_phase_started_at();
         assert_eq!(date.as_deref(), Some("2025-01-01"));
         assert_eq!(time.as_deref(), Some("09:00"));
     }
@@ -687,21 +1497,15 @@ mod tests {
         // 预置 1 条工作记录：让本次完成后达到 2，从而触发长休息。
         data.history.push(HistoryDay {
             date: "2025-01-01".to_string(),
-            records: vec![HistoryRecord {
-                tag: "A".to_string(),
-                start_time: "08:00".to_string(),
-                end_time: Some("08:25".to_string()),
-                duration: 25,
-                phase: Phase::Work,
-                remark: String::new(),
-            }],
+            records: vec![HistoryRecord::for_test("A", "08:00").with_end("08:25")],
         });
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
         runtime.remaining_seconds = 1;
+        runtime.start(&data, &clock);
+        clock.advance(1);
 
-        let _ = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let _ = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert_eq!(runtime.phase, Phase::LongBreak);
         assert!(runtime.is_running);
         assert_eq!(
@@ -725,14 +1529,161 @@ mod tests {
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
         runtime.phase = Phase::ShortBreak;
         runtime.remaining_seconds = 1;
-        runtime.is_running = true;
+        runtime.start(&data, &clock);
+        clock.advance(1);
 
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(out.phase_ended);
         assert_eq!(runtime.phase, Phase::Work);
         assert!(!runtime.is_running);
     }
 
+    /// 按秒推进并逐次 tick `seconds` 秒，返回最后一次 tick 结果。
+    fn run_for(
+        runtime: &mut TimerRuntime,
+        data: &mut AppData,
+        clock: &FixedClock,
+        seconds: u64,
+    ) -> TickResult {
+        let mut out = TickResult::idle();
+        for _ in 0..seconds {
+            clock.advance(1);
+            out = runtime.tick(data, None, clock, &NoopNotifier).unwrap();
+        }
+        out
+    }
+
+    /// 休息自然结束：写入休息记录（实际时长、未跳过），但不计入今日番茄数。
+    #[test]
+    fn break_completion_is_recorded_without_counting_as_pomodoro() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = AppData::default();
+        data.settings.pomodoro = 1;
+        data.settings.short_break = 1;
+        data.tags = vec!["A".to_string()];
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.start(&data, &clock);
+        run_for(&mut runtime, &mut data, &clock, 60);
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+
+        let out = run_for(&mut runtime, &mut data, &clock, 60);
+        assert!(out.phase_ended);
+        assert!(out.history_changed);
+        assert!(out.work_completed_event.is_none());
+
+        let records = &data.history[0].records;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].phase, Phase::ShortBreak);
+        assert_eq!(records[1].elapsed_seconds, Some(60));
+        assert!(!records[1].skipped);
+        assert_eq!(stats::compute_today_stats(&data, "2025-01-01").total, 1);
+    }
+
+    /// 跨午夜的工作阶段：记录归属开始日期，起止时刻为带偏移的 RFC 3339 时间。
+    #[test]
+    fn work_record_across_midnight_carries_full_instants() {
+        let clock = FixedClock::new("2025-01-01", "23:59");
+        let mut data = AppData::default();
+        data.settings.pomodoro = 2;
+        data.tags = vec!["A".to_string()];
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.start(&data, &clock);
+        let out = run_for(&mut runtime, &mut data, &clock, 120);
+
+        let event = out.work_completed_event.unwrap();
+        assert_eq!(event.date, "2025-01-01");
+        assert_eq!(event.record.start_time, "23:59");
+        assert_eq!(event.record.end_time.as_deref(), Some("00:01"));
+        let ended_at =
+            chrono::DateTime::parse_from_rfc3339(event.record.ended_at.as_deref().unwrap())
+                .unwrap()
+                .with_timezone(&chrono::Local);
+        assert_eq!(ended_at, clock.now());
+        assert_eq!(ended_at.format("%Y-%m-%d").to_string(), "2025-01-02");
+    }
+
+    /// 选择了当前任务：工作记录关联任务 id，休息记录不关联。
+    #[test]
+    fn work_record_links_current_task() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = AppData::default();
+        data.settings.pomodoro = 1;
+        data.settings.short_break = 1;
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.current_task_id = Some("task-1".to_string());
+        runtime.start(&data, &clock);
+        let out = run_for(&mut runtime, &mut data, &clock, 60);
+        let record = out.work_completed_event.unwrap().record;
+        assert_eq!(record.task_id.as_deref(), Some("task-1"));
+        assert_eq!(record.tag_id, data.tag_id_by_path("工作"));
+
+        runtime.start(&data, &clock);
+        run_for(&mut runtime, &mut data, &clock, 60);
+        let records = &data.history[0].records;
+        assert_eq!(records[1].phase, Phase::ShortBreak);
+        assert!(records[1].task_id.is_none());
+    }
+
+    /// 工作阶段内的暂停：按暂停片段写入记录，实际计时不含暂停时长。
+    #[test]
+    fn pauses_within_work_phase_are_recorded_as_segments() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = AppData::default();
+        data.settings.pomodoro = 1;
+        data.tags = vec!["A".to_string()];
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.start(&data, &clock);
+        run_for(&mut runtime, &mut data, &clock, 20);
+        runtime.pause(&clock);
+        clock.advance(300);
+        runtime.start(&data, &clock);
+        let out = run_for(&mut runtime, &mut data, &clock, 40);
+        assert!(out.phase_ended);
+
+        let record = &out.work_completed_event.unwrap().record;
+        assert_eq!(record.elapsed_seconds, Some(60));
+        assert_eq!(
+            record.pauses,
+            vec![PauseSegment {
+                start_time: "09:00".to_string(),
+                end_time: "09:05".to_string(),
+                seconds: 300,
+            }]
+        );
+        assert_eq!(record.paused_seconds(), 300);
+    }
+
+    /// 跳过已开始的休息：写入“已跳过”记录并保留实际已休时长；未开始的休息跳过时不写入。
+    #[test]
+    fn skipping_started_break_records_skipped_record() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = AppData::default();
+        data.settings.pomodoro = 1;
+        data.settings.short_break = 5;
+        data.tags = vec!["A".to_string()];
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.start(&data, &clock);
+        run_for(&mut runtime, &mut data, &clock, 60);
+        run_for(&mut runtime, &mut data, &clock, 30);
+
+        assert!(runtime.skip(&mut data, 1, &clock));
+        let record = data.history[0].records.last().unwrap();
+        assert_eq!(record.phase, Phase::ShortBreak);
+        assert!(record.skipped);
+        assert_eq!(record.elapsed_seconds, Some(30));
+        assert_eq!(runtime.phase, Phase::Work);
+
+        // 工作阶段未开始即跳过 -> 休息阶段未开始即跳过：均不写入历史。
+        assert!(!runtime.skip(&mut data, 1, &clock));
+        assert!(!runtime.skip(&mut data, 1, &clock));
+        assert_eq!(data.history[0].records.len(), 2);
+    }
+
     /// 休息结束后：开启连续番茄且仍有剩余时应自动开始工作倒计时。
     #[test]
     fn break_end_auto_starts_work_when_enabled_and_remaining() {
@@ -748,17 +1699,21 @@ mod tests {
         data.tags = vec!["A".to_string()];
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
+        runtime.remaining_seconds = 1;
+        runtime.start(&data, &clock);
 
         // 快速完成一次工作 -> 自动进入短休息并开始。
-        runtime.remaining_seconds = 1;
-        let _ = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        clock.advance(1);
+        let _ = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert_eq!(runtime.phase, Phase::ShortBreak);
         assert!(runtime.is_running);
 
         // 快速结束短休息 -> 自动开始下一次工作。
+        runtime.pause(&clock);
         runtime.remaining_seconds = 1;
-        let out = runtime.tick(&mut data, &clock, &notifier).unwrap();
+        runtime.start(&data, &clock);
+        clock.advance(1);
+        let out = runtime.tick(&mut data, None, &clock, &notifier).unwrap();
         assert!(out.phase_ended);
         assert!(out.work_auto_started);
         assert_eq!(runtime.phase, Phase::Work);
@@ -788,65 +1743,183 @@ mod tests {
         let clock = FixedClock::new("2025-01-01", "09:00");
         let mut runtime = TimerRuntime::new(&Settings::default(), &["学习".to_string()], &clock);
 
-        runtime.start(&Settings::default(), &clock);
+        runtime.start(&AppData::default(), &clock);
         assert!(runtime.is_running);
         assert!(runtime.blacklist_locked());
 
-        runtime.start(&Settings::default(), &clock);
+        runtime.start(&AppData::default(), &clock);
         assert!(runtime.is_running);
         assert!(runtime.blacklist_locked());
     }
 
-    /// `pause`：暂停后应处于非运行态。
+    /// `pause`：暂停后应处于非运行态，并冻结按截止时刻推导出的剩余时间。
     #[test]
     fn pause_stops_running() {
         let clock = FixedClock::new("2025-01-01", "09:00");
         let mut runtime = TimerRuntime::new(&Settings::default(), &["学习".to_string()], &clock);
-        runtime.start(&Settings::default(), &clock);
-        runtime.pause();
+        runtime.start(&AppData::default(), &clock);
+        clock.advance(5);
+        runtime.pause(&clock);
         assert!(!runtime.is_running);
+        assert_eq!(runtime.remaining_seconds, 25 * 60 - 5);
+
+        clock.advance(5);
+        assert_eq!(runtime.remaining_seconds_at(&clock), 25 * 60 - 5);
     }
 
     /// `reset`：应回到工作阶段初始剩余时间，并解除锁定与运行态。
     #[test]
     fn reset_restores_initial_state() {
         let clock = FixedClock::new("2025-01-01", "09:00");
-        let mut settings = Settings::default();
-        settings.pomodoro = 10;
-        settings.short_break = 1;
-        settings.long_break = 1;
+        let mut data = AppData::default();
+        data.settings.pomodoro = 10;
+        data.settings.short_break = 1;
+        data.settings.long_break = 1;
 
-        let mut runtime = TimerRuntime::new(&settings, &["学习".to_string()], &clock);
-        runtime.start(&settings, &clock);
+        let mut runtime = TimerRuntime::new(&data.settings, &["学习".to_string()], &clock);
+        runtime.start(&data, &clock);
         runtime.remaining_seconds = 3;
         assert!(runtime.blacklist_locked());
 
-        runtime.reset(&settings);
+        runtime.reset(&data);
         assert_eq!(runtime.phase, Phase::Work);
         assert_eq!(runtime.remaining_seconds, 10 * 60);
         assert!(!runtime.is_running);
         assert!(!runtime.blacklist_locked());
     }
 
-    /// `skip`：跳过不会写入历史（不依赖 data），并应切换到下一阶段且解除锁定。
+    /// `skip`：跳过不会写入历史，并应切换到下一阶段且解除锁定。
     #[test]
     fn skip_switches_phase_and_unlocks_blacklist() {
         let clock = FixedClock::new("2025-01-01", "09:00");
-        let mut settings = Settings::default();
-        settings.pomodoro = 1;
-        settings.short_break = 2;
-        settings.long_break = 3;
-        settings.long_break_interval = 4;
-
-        let mut runtime = TimerRuntime::new(&settings, &["学习".to_string()], &clock);
-        runtime.start(&settings, &clock);
+        let mut data = AppData::default();
+        data.settings.pomodoro = 1;
+        data.settings.short_break = 2;
+        data.settings.long_break = 3;
+        data.settings.long_break_interval = 4;
+
+        let mut runtime = TimerRuntime::new(&data.settings, &["学习".to_string()], &clock);
+        runtime.start(&data, &clock);
         assert!(runtime.blacklist_locked());
 
-        runtime.skip(&settings, 1);
+        runtime.skip(&mut data, 1, &clock);
         assert_eq!(runtime.phase, Phase::ShortBreak);
         assert_eq!(runtime.remaining_seconds, 2 * 60);
         assert!(!runtime.is_running);
         assert!(!runtime.blacklist_locked());
+        assert!(data.history.is_empty());
+    }
+
+    /// 构造启用了会话计划的数据：热身 1 分钟 -> 休息 2 分钟 -> 深度工作 3 分钟 -> 长休息 4 分钟。
+    fn data_with_plan() -> AppData {
+        use crate::app_data::PlanStep;
+        let step = |name: &str, kind: Phase, minutes: u32| PlanStep {
+            name: name.to_string(),
+            kind,
+            minutes,
+        };
+        let mut data = AppData::default();
+        data.tags = vec!["学习".to_string()];
+        data.session_plans = vec![SessionPlan {
+            id: "plan-1".to_string(),
+            name: "热身 + 深度".to_string(),
+            steps: vec![
+                step("热身", Phase::Work, 1),
+                step("休息", Phase::ShortBreak, 2),
+                step("深度工作", Phase::Work, 3),
+                step("长休息", Phase::LongBreak, 4),
+            ],
+        }];
+        data.active_plan_id = Some("plan-1".to_string());
+        data
+    }
+
+    /// 会话计划：`reset` 回到第一步，完成的工作步骤按步骤时长写入历史并按计划进入下一步。
+    #[test]
+    fn plan_step_completion_records_step_duration_and_advances() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = data_with_plan();
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.reset(&data);
+        assert_eq!(runtime.phase, Phase::Work);
+        assert_eq!(runtime.remaining_seconds, 60);
+
+        runtime.start(&data, &clock);
+        for _ in 0..59 {
+            clock.advance(1);
+            assert!(
+                !runtime
+                    .tick(&mut data, None, &clock, &NoopNotifier)
+                    .unwrap()
+                    .phase_ended
+            );
+        }
+        clock.advance(1);
+        let out = runtime
+            .tick(&mut data, None, &clock, &NoopNotifier)
+            .unwrap();
+        assert!(out.phase_ended);
+        assert_eq!(data.history[0].records[0].duration, 1);
+        assert_eq!(data.history[0].records[0].phase, Phase::Work);
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+        assert!(runtime.is_running);
+        assert_eq!(runtime.remaining_seconds, 2 * 60);
+    }
+
+    /// 会话计划：跳过按步骤顺序推进，最后一步之后回到第一步（不受长休息间隔影响）。
+    #[test]
+    fn plan_skip_walks_steps_and_wraps_around() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = data_with_plan();
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.reset(&data);
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            runtime.skip(&mut data, 0, &clock);
+            seen.push((runtime.phase, runtime.remaining_seconds));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                (Phase::ShortBreak, 2 * 60),
+                (Phase::Work, 3 * 60),
+                (Phase::LongBreak, 4 * 60),
+                (Phase::Work, 60),
+            ]
+        );
+    }
+
+    /// 会话计划：取消启用后回退为经典循环，未运行时同步为设置中的时长。
+    #[test]
+    fn plan_deactivation_falls_back_to_classic_cycle() {
+        let clock = FixedClock::new("2025-01-01", "09:00");
+        let mut data = data_with_plan();
+
+        let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
+        runtime.reset(&data);
+        runtime.skip(&mut data, 0, &clock);
+        runtime.skip(&mut data, 0, &clock);
+        assert_eq!(runtime.remaining_seconds, 3 * 60);
+
+        data.session_plans[0].steps[2].minutes = 5;
+        runtime.sync_idle_duration(&data);
+        assert_eq!(runtime.remaining_seconds, 5 * 60);
+
+        data.active_plan_id = None;
+        runtime.sync_idle_duration(&data);
+        assert_eq!(
+            runtime.remaining_seconds,
+            data.settings.pomodoro as u64 * 60
+        );
+        runtime.skip(&mut data, 1, &clock);
+        assert_eq!(runtime.phase, Phase::ShortBreak);
+        assert_eq!(
+            runtime.remaining_seconds,
+            data.settings.short_break as u64 * 60
+        );
     }
 
     /// `set_current_tag`：空白标签应规范化为“工作”。
@@ -870,31 +1943,21 @@ mod tests {
         data.history = vec![HistoryDay {
             date: "2025-01-01".to_string(),
             records: vec![
-                HistoryRecord {
-                    tag: "学习".to_string(),
-                    start_time: "08:00".to_string(),
-                    end_time: Some("08:25".to_string()),
-                    duration: 25,
-                    phase: Phase::Work,
-                    remark: String::new(),
-                },
-                HistoryRecord {
-                    tag: "学习".to_string(),
-                    start_time: "08:30".to_string(),
-                    end_time: Some("08:55".to_string()),
-                    duration: 25,
-                    phase: Phase::ShortBreak,
-                    remark: String::new(),
-                },
+                HistoryRecord::for_test("学习", "08:00").with_end("08:25"),
+                HistoryRecord::for_test("学习", "08:30")
+                    .with_phase(Phase::ShortBreak)
+                    .with_end("08:55"),
             ],
         }];
 
         let mut runtime = TimerRuntime::new(&data.settings, &data.tags, &clock);
-        runtime.start(&data.settings, &clock);
+        runtime.start(&data, &clock);
+        clock.advance(3);
 
-        let snapshot = runtime.snapshot_with_clock(&data, &clock);
+        let snapshot = runtime.snapshot_with_clock(&data, None, &clock);
         assert_eq!(snapshot.phase, Phase::Work);
         assert!(snapshot.is_running);
+        assert_eq!(snapshot.remaining_seconds, 25 * 60 - 3);
         assert_eq!(snapshot.current_tag, "学习");
         assert!(snapshot.blacklist_locked);
         assert_eq!(snapshot.settings.daily_goal, 3);
@@ -1,11 +1,17 @@
-//! 计时器统计计算：今日/本周数据与目标进度。
+//! 计时器统计计算：今日/本周数据、目标进度与连续达标天数。
 
 use std::collections::BTreeMap;
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::app_data::{AppData, Phase};
+use crate::app_data::{
+    current_tag_path, root_tag_path, tag_path_within, AppData, DateRange, GoalPeriod, HistoryDay,
+    Phase, RecordSet,
+};
+use crate::errors::AppResult;
+use crate::history_db::{DATE_MAX, DATE_MIN};
 
 /// 标签计数条目。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -40,7 +46,7 @@ pub struct WeekStats {
     pub by_tag: Vec<TagCount>,
 }
 
-/// 目标进度（每日/每周）。
+/// 目标进度（每日/每周/每月/每年 + 标签目标 + 连续达标）。
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(rename_all = "camelCase")]
@@ -53,59 +59,468 @@ pub struct GoalProgress {
     pub weekly_goal: u32,
     /// 本周已完成。
     pub weekly_completed: u32,
+    /// 每月目标（0 表示未设置）。
+    pub monthly_goal: u32,
+    /// 本月已完成。
+    pub monthly_completed: u32,
+    /// 每年目标（0 表示未设置）。
+    pub yearly_goal: u32,
+    /// 今年已完成。
+    pub yearly_completed: u32,
+    /// 标签目标进度（顺序与设置一致）。
+    pub tag_goals: Vec<TagGoalProgress>,
+    /// 连续达标（每日目标）。
+    pub streak: StreakProgress,
 }
 
-/// 计算指定日期（YYYY-MM-DD）的“今日统计”（仅统计工作阶段记录）。
-pub fn compute_today_stats(data: &AppData, today: &str) -> TodayStats {
-    let mut map: BTreeMap<String, u32> = BTreeMap::new();
-    let mut total = 0u32;
+/// 单个标签目标的进度。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct TagGoalProgress {
+    /// 标签名。
+    pub tag: String,
+    /// 统计周期。
+    pub period: GoalPeriod,
+    /// 目标番茄数量。
+    pub target: u32,
+    /// 当前周期内已完成。
+    pub completed: u32,
+}
+
+/// 连续达标进度（未设置每日目标时均为 0）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct StreakProgress {
+    /// 当前连续达标天数（今天未达标时不计入，也不中断）。
+    pub current_days: u32,
+    /// 历史最长连续达标天数。
+    pub longest_days: u32,
+    /// 今天是否已达标。
+    pub today_met: bool,
+    /// 本周剩余可用休息日数量。
+    pub rest_days_left: u32,
+}
 
-    if let Some(day) = data.history.iter().find(|d| d.date == today) {
-        for r in &day.records {
-            if r.phase != Phase::Work {
+impl StreakProgress {
+    /// 连续记录是否面临中断：已有连续记录、今天尚未达标，且本周已无休息日可用。
+    pub fn is_at_risk(&self) -> bool {
+        self.current_days > 0 && !self.today_met && self.rest_days_left == 0
+    }
+}
+
+/// 一组工作记录的计数（统计聚合查询的结果行）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkCount {
+    /// 记录所在的自然日期（YYYY-MM-DD）。
+    pub date: String,
+    /// 开始时刻是否早于日界线（是则归入前一逻辑日）。
+    pub before_rollover: bool,
+    /// 标签 id（无标签或无法还原时为 `None`）。
+    pub tag_id: Option<String>,
+    /// 记录中的标签路径（按标签 id 无法解析时使用；不分标签的查询为空）。
+    pub tag: String,
+    /// 记录数。
+    pub count: u32,
+}
+
+/// 统计所需的聚合查询（由历史数据库实现，按列计数而不逐条读取记录）。
+pub trait WorkCountQuery {
+    /// 自然日期闭区间内正式历史的工作记录数，按日期、是否早于 `rollover_hour` 与标签分组
+    /// （`paths` 为全部标签 id → 路径，供加密时还原标签 id 列）。
+    fn work_counts(
+        &self,
+        from: &str,
+        to: &str,
+        rollover_hour: u32,
+        paths: &BTreeMap<String, String>,
+    ) -> AppResult<Vec<WorkCount>>;
+
+    /// 正式历史中每个自然日的工作记录数，按是否早于 `rollover_hour` 分组（不分标签）。
+    fn daily_work_counts(&self, rollover_hour: u32) -> AppResult<Vec<WorkCount>>;
+}
+
+/// 统计的计数来源：已写入数据库的日期走聚合查询，尚未同步（在 `pending` 中登记）的日期统计内存中的记录。
+///
+/// 未提供数据库（测试）或登记了整体替换时全部统计内存中的记录；查询失败时同样退回内存并记录警告。
+pub struct HistoryCounts<'a> {
+    /// 设置、标签与归档汇总的来源（以及尚未同步的记录）。
+    data: &'a AppData,
+    /// 历史数据库。
+    db: Option<&'a dyn WorkCountQuery>,
+}
+
+impl<'a> HistoryCounts<'a> {
+    /// 以数据库（可选）与内存数据创建计数来源。
+    pub fn new(data: &'a AppData, db: Option<&'a dyn WorkCountQuery>) -> Self {
+        Self { data, db }
+    }
+
+    /// 逻辑日期闭区间 `[from, to]` 内的工作记录数：逻辑日期 → 当前标签路径 → 数量（含已归档历史的汇总）。
+    fn by_date(&self, from: &str, to: &str) -> BTreeMap<String, BTreeMap<String, u32>> {
+        let boundary = self.data.settings.day_boundary();
+        let paths = self.data.tag_paths();
+        let natural = boundary.natural_range(&DateRange {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+        let rows = self.rows(&natural.from, &natural.to, |db| {
+            db.work_counts(&natural.from, &natural.to, boundary.rollover_hour, &paths)
+        });
+
+        let mut out: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+        for row in rows {
+            let date = boundary.date_of(&row.date, row.before_rollover);
+            if date.as_str() < from || date.as_str() > to {
                 continue;
             }
-            total += 1;
-            *map.entry(r.tag.clone()).or_insert(0) += 1;
+            let tag = current_tag_path(&paths, &row.tag, row.tag_id.as_deref());
+            *out.entry(date)
+                .or_default()
+                .entry(tag.to_string())
+                .or_insert(0) += row.count;
+        }
+        for day in &self.data.history_archive.days {
+            if day.date.as_str() < from || day.date.as_str() > to {
+                continue;
+            }
+            let tags = out.entry(day.date.clone()).or_default();
+            for t in &day.by_tag {
+                let tag = current_tag_path(&paths, &t.tag, t.tag_id.as_deref());
+                *tags.entry(tag.to_string()).or_insert(0) += t.count;
+            }
+        }
+        out
+    }
+
+    /// 每个逻辑日期的工作记录总数（含已归档历史的汇总）。
+    fn daily_totals(&self) -> BTreeMap<NaiveDate, u32> {
+        let boundary = self.data.settings.day_boundary();
+        let rows = self.rows(DATE_MIN, DATE_MAX, |db| {
+            db.daily_work_counts(boundary.rollover_hour)
+        });
+        let mut daily: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+        let dated = rows
+            .iter()
+            .map(|row| (boundary.date_of(&row.date, row.before_rollover), row.count))
+            .chain(self.data.history_archive.days.iter().map(|day| {
+                (
+                    day.date.clone(),
+                    day.by_tag.iter().map(|t| t.count).sum::<u32>(),
+                )
+            }));
+        for (date, count) in dated {
+            if let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                *daily.entry(date).or_insert(0) += count;
+            }
+        }
+        daily
+    }
+
+    /// 自然日期闭区间内的计数行：数据库中已同步的日期取 `query` 的结果，其余日期逐条统计内存中的记录。
+    fn rows(
+        &self,
+        from: &str,
+        to: &str,
+        query: impl FnOnce(&dyn WorkCountQuery) -> AppResult<Vec<WorkCount>>,
+    ) -> Vec<WorkCount> {
+        let pending = &self.data.pending;
+        let queried = match self.db.filter(|_| !pending.all_records) {
+            Some(db) => match query(db) {
+                Ok(rows) => Some(rows),
+                Err(e) => {
+                    tracing::warn!(target: "storage", "统计查询失败，改为统计内存中的记录：{e}");
+                    None
+                }
+            },
+            None => None,
+        };
+        let unsynced = |date: &str| {
+            pending
+                .days
+                .contains(&(RecordSet::History, date.to_string()))
+        };
+        let in_range = |date: &str| date >= from && date <= to;
+        let days: Vec<&HistoryDay> = match &queried {
+            Some(_) => pending
+                .days
+                .iter()
+                .filter(|(set, date)| *set == RecordSet::History && in_range(date))
+                .flat_map(|(_, date)| {
+                    let history = &self.data.history;
+                    let start = history.partition_point(|d| d.date < *date);
+                    history[start..].iter().take_while(move |d| d.date == *date)
+                })
+                .collect(),
+            None => self
+                .data
+                .history
+                .iter()
+                .filter(|d| in_range(&d.date))
+                .collect(),
+        };
+        let mut rows: Vec<WorkCount> = queried
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| !unsynced(&r.date))
+            .collect();
+        let boundary = self.data.settings.day_boundary();
+        for day in days {
+            for r in day.records.iter().filter(|r| r.phase == Phase::Work) {
+                rows.push(WorkCount {
+                    date: day.date.clone(),
+                    before_rollover: boundary.before_rollover(r.start_hour()),
+                    tag_id: r.tag_id.clone(),
+                    tag: r.tag.clone(),
+                    count: 1,
+                });
+            }
         }
+        rows
     }
+}
+
+impl<'a> From<&'a AppData> for HistoryCounts<'a> {
+    /// 只统计内存中的记录（测试与无数据库的场景）。
+    fn from(data: &'a AppData) -> Self {
+        Self::new(data, None)
+    }
+}
 
+/// 计算指定逻辑日期（YYYY-MM-DD）的“今日统计”（仅统计工作阶段记录；按设置的日界线归属）。
+pub fn compute_today_stats<'a>(counts: impl Into<HistoryCounts<'a>>, today: &str) -> TodayStats {
+    let counts = counts.into();
+    let (total, by_tag) = tally(&counts.by_date(today, today), today, today);
     TodayStats {
         total,
-        by_tag: map
-            .into_iter()
-            .map(|(tag, count)| TagCount { tag, count })
-            .collect(),
+        by_tag: roll_up_tag_counts(by_tag, counts.data.settings.roll_up_tags),
     }
 }
 
-/// 计算闭区间 `[from, to]`（YYYY-MM-DD）的“本周统计”（仅统计工作阶段记录）。
-pub fn compute_week_stats(data: &AppData, from: &str, to: &str) -> WeekStats {
-    let mut map: BTreeMap<String, u32> = BTreeMap::new();
-    let mut total = 0u32;
+/// 计算逻辑日期闭区间 `[from, to]`（YYYY-MM-DD）的“本周统计”（仅统计工作阶段记录）。
+pub fn compute_week_stats<'a>(
+    counts: impl Into<HistoryCounts<'a>>,
+    from: &str,
+    to: &str,
+) -> WeekStats {
+    let counts = counts.into();
+    let (total, by_tag) = tally(&counts.by_date(from, to), from, to);
+    WeekStats {
+        total,
+        by_tag: roll_up_tag_counts(by_tag, counts.data.settings.roll_up_tags),
+    }
+}
 
-    for day in &data.history {
-        if day.date.as_str() < from || day.date.as_str() > to {
-            continue;
+/// 计算目标进度：`today` 为逻辑今天，`[week_from, week_to]` 为本周范围（YYYY-MM-DD）。
+///
+/// 只查询一次覆盖本周与今年的范围，各周期在查询结果上分别汇总。
+pub fn compute_goal_progress<'a>(
+    counts: impl Into<HistoryCounts<'a>>,
+    today: &str,
+    week_from: &str,
+    week_to: &str,
+) -> GoalProgress {
+    let counts = counts.into();
+    let settings = &counts.data.settings;
+    let month = today.get(..7).unwrap_or(today);
+    let year = today.get(..4).unwrap_or(today);
+    let range_of = |period: GoalPeriod| -> (String, String) {
+        match period {
+            GoalPeriod::Daily => (today.to_string(), today.to_string()),
+            GoalPeriod::Weekly => (week_from.to_string(), week_to.to_string()),
+            // YYYY-MM-DD 可按字典序比较，因此月末统一写作 31 日即可覆盖整月。
+            GoalPeriod::Monthly => (format!("{month}-01"), format!("{month}-31")),
+            GoalPeriod::Yearly => (format!("{year}-01-01"), format!("{year}-12-31")),
         }
-        for r in &day.records {
-            if r.phase != Phase::Work {
-                continue;
+    };
+    let (year_from, year_to) = range_of(GoalPeriod::Yearly);
+    let by_date = counts.by_date(
+        week_from.min(year_from.as_str()),
+        week_to.max(year_to.as_str()),
+    );
+    let counts_of = |period: GoalPeriod| {
+        let (from, to) = range_of(period);
+        tally(&by_date, &from, &to)
+    };
+    let periods: Vec<(GoalPeriod, (u32, Vec<TagCount>))> = [
+        GoalPeriod::Daily,
+        GoalPeriod::Weekly,
+        GoalPeriod::Monthly,
+        GoalPeriod::Yearly,
+    ]
+    .into_iter()
+    .map(|period| (period, counts_of(period)))
+    .collect();
+    let total_of = |period: GoalPeriod| {
+        periods
+            .iter()
+            .find(|(p, _)| *p == period)
+            .map(|(_, (total, _))| *total)
+            .unwrap_or(0)
+    };
+
+    let paths = counts.data.tag_paths();
+    let tag_goals = settings
+        .tag_goals
+        .iter()
+        .map(|goal| {
+            let tag = current_tag_path(&paths, &goal.tag, goal.tag_id.as_deref());
+            TagGoalProgress {
+                tag: tag.to_string(),
+                period: goal.period,
+                target: goal.target,
+                completed: periods
+                    .iter()
+                    .find(|(p, _)| *p == goal.period)
+                    .map(|(_, (_, by_tag))| {
+                        // 父标签的目标同时计入其子标签的番茄。
+                        by_tag
+                            .iter()
+                            .filter(|c| tag_path_within(&c.tag, tag))
+                            .map(|c| c.count)
+                            .sum()
+                    })
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
+
+    GoalProgress {
+        daily_goal: settings.daily_goal,
+        daily_completed: total_of(GoalPeriod::Daily),
+        weekly_goal: settings.weekly_goal,
+        weekly_completed: total_of(GoalPeriod::Weekly),
+        monthly_goal: settings.monthly_goal,
+        monthly_completed: total_of(GoalPeriod::Monthly),
+        yearly_goal: settings.yearly_goal,
+        yearly_completed: total_of(GoalPeriod::Yearly),
+        tag_goals,
+        streak: compute_streak(counts, today),
+    }
+}
+
+/// 计算连续达标天数（以每日目标为准；每周可有 `rest_days_per_week` 天未达标而不中断）。
+///
+/// 今天仍在进行中：未达标时既不计入也不中断；日期按日界线归属，每日数量来自按日聚合的计数。
+pub fn compute_streak<'a>(counts: impl Into<HistoryCounts<'a>>, today: &str) -> StreakProgress {
+    let counts = counts.into();
+    let settings = &counts.data.settings;
+    let goal = settings.daily_goal;
+    let allowance = u32::from(settings.streak.rest_days_per_week);
+    let Ok(today) = NaiveDate::parse_from_str(today, "%Y-%m-%d") else {
+        return StreakProgress::default();
+    };
+    if goal == 0 {
+        return StreakProgress::default();
+    }
+
+    let boundary = settings.day_boundary();
+    let daily = counts.daily_totals();
+    let met = |date: NaiveDate| daily.get(&date).copied().unwrap_or(0) >= goal;
+    let today_met = met(today);
+    let Some(first) = daily.keys().next().copied().filter(|d| *d <= today) else {
+        return StreakProgress {
+            rest_days_left: allowance,
+            ..StreakProgress::default()
+        };
+    };
+
+    // 当前连续：从今天（未达标则从昨天）向前回溯，每周的休息日额度单独计算。
+    let mut current_days = 0u32;
+    let mut rests_used: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut cursor = if today_met {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(date) = cursor.filter(|d| *d >= first) {
+        if met(date) {
+            current_days += 1;
+        } else {
+            let used = rests_used.entry(boundary.week_start_of(date)).or_insert(0);
+            if *used >= allowance {
+                break;
             }
-            total += 1;
-            *map.entry(r.tag.clone()).or_insert(0) += 1;
+            *used += 1;
         }
+        cursor = date.pred_opt();
     }
+    let used_this_week = rests_used
+        .get(&boundary.week_start_of(today))
+        .copied()
+        .unwrap_or(0);
 
-    WeekStats {
-        total,
-        by_tag: map
-            .into_iter()
-            .map(|(tag, count)| TagCount { tag, count })
-            .collect(),
+    // 最长连续：从最早记录日向后扫描到昨天（今天由当前连续覆盖）。
+    let mut longest_days = current_days;
+    let mut run = 0u32;
+    let mut run_rests: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut cursor = Some(first);
+    while let Some(date) = cursor.filter(|d| *d < today) {
+        if met(date) {
+            run += 1;
+            longest_days = longest_days.max(run);
+        } else if run > 0 {
+            let used = run_rests.entry(boundary.week_start_of(date)).or_insert(0);
+            if *used < allowance {
+                *used += 1;
+            } else {
+                run = 0;
+                run_rests.clear();
+            }
+        }
+        cursor = date.succ_opt();
+    }
+
+    StreakProgress {
+        current_days,
+        longest_days,
+        today_met,
+        rest_days_left: allowance.saturating_sub(used_this_week),
     }
 }
 
+/// 按需将各标签计数汇总到顶层标签（保持按标签名排序）。
+fn roll_up_tag_counts(by_tag: Vec<TagCount>, enabled: bool) -> Vec<TagCount> {
+    if !enabled {
+        return by_tag;
+    }
+    let mut map: BTreeMap<String, u32> = BTreeMap::new();
+    for c in by_tag {
+        *map.entry(root_tag_path(&c.tag).to_string()).or_insert(0) += c.count;
+    }
+    map.into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect()
+}
+
+/// 汇总 `by_date` 中逻辑日期落在 `[from, to]` 内的计数：返回总数与按标签分组的计数（按标签名排序）。
+fn tally(
+    by_date: &BTreeMap<String, BTreeMap<String, u32>>,
+    from: &str,
+    to: &str,
+) -> (u32, Vec<TagCount>) {
+    let mut map: BTreeMap<&str, u32> = BTreeMap::new();
+    let range = (
+        std::ops::Bound::Included(from.to_string()),
+        std::ops::Bound::Included(to.to_string()),
+    );
+    for tags in by_date.range(range).map(|(_, tags)| tags) {
+        for (tag, count) in tags {
+            *map.entry(tag.as_str()).or_insert(0) += count;
+        }
+    }
+    (
+        map.values().sum(),
+        map.into_iter()
+            .map(|(tag, count)| TagCount {
+                tag: tag.to_string(),
+                count,
+            })
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,14 +528,9 @@ mod tests {
 
     /// 构造一条测试用历史记录（默认仅填充必要字段）。
     fn record(tag: &str, phase: Phase) -> HistoryRecord {
-        HistoryRecord {
-            tag: tag.to_string(),
-            start_time: "09:00".to_string(),
-            end_time: Some("09:25".to_string()),
-            duration: 25,
-            phase,
-            remark: String::new(),
-        }
+        HistoryRecord::for_test(tag, "09:00")
+            .with_phase(phase)
+            .with_end("09:25")
     }
 
     /// `compute_today_stats` 只统计工作阶段，并按标签汇总。
@@ -194,6 +604,48 @@ mod tests {
         );
     }
 
+    /// 已归档日期按汇总计入统计与连续达标（与未归档的记录合并）。
+    #[test]
+    fn archived_summaries_count_towards_stats_and_streak() {
+        use crate::app_data::{ArchivedDay, ArchivedTagTotal, HistoryArchive};
+
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-03".to_string(),
+                records: vec![record("A", Phase::Work)],
+            }],
+            history_archive: HistoryArchive {
+                before: Some("2025-01-03".to_string()),
+                days: vec![ArchivedDay {
+                    date: "2025-01-02".to_string(),
+                    by_tag: vec![ArchivedTagTotal {
+                        tag: "A".to_string(),
+                        tag_id: None,
+                        count: 2,
+                        minutes: 50,
+                    }],
+                }],
+            },
+            ..AppData::default()
+        };
+        data.settings.daily_goal = 1;
+
+        let out = compute_week_stats(&data, "2025-01-01", "2025-01-07");
+        assert_eq!(out.total, 3);
+        assert_eq!(
+            out.by_tag,
+            vec![TagCount {
+                tag: "A".to_string(),
+                count: 3
+            }]
+        );
+        assert_eq!(
+            compute_week_stats(&data, "2025-01-03", "2025-01-07").total,
+            1
+        );
+        assert_eq!(compute_streak(&data, "2025-01-03").current_days, 2);
+    }
+
     /// `compute_today_stats`：当指定日期不存在时应返回 0 与空分组。
     #[test]
     fn compute_today_stats_returns_zero_when_missing_day() {
@@ -247,4 +699,166 @@ mod tests {
             ]
         );
     }
+
+    /// 日界线：凌晨未到换日时刻的番茄计入前一天的今日/本周统计。
+    #[test]
+    fn stats_follow_day_rollover_hour() {
+        let mut late = record("夜读", Phase::Work);
+        late.start_time = "00:30".to_string();
+        let mut data = AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2025-01-01".to_string(),
+                    records: vec![record("A", Phase::Work)],
+                },
+                HistoryDay {
+                    date: "2025-01-02".to_string(),
+                    records: vec![late, record("B", Phase::Work)],
+                },
+            ],
+            ..AppData::default()
+        };
+        assert_eq!(compute_today_stats(&data, "2025-01-01").total, 1);
+
+        data.settings.day_rollover_hour = 4;
+        assert_eq!(compute_today_stats(&data, "2025-01-01").total, 2);
+        assert_eq!(compute_today_stats(&data, "2025-01-02").total, 1);
+        assert_eq!(
+            compute_week_stats(&data, "2025-01-02", "2025-01-08").total,
+            1
+        );
+    }
+
+    /// 构造每天完成 `counts[i]` 个番茄的历史（从 `2025-01-01` 起连续编号）。
+    fn history_with_counts(counts: &[usize]) -> Vec<HistoryDay> {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, n)| HistoryDay {
+                date: format!("2025-01-{:02}", i + 1),
+                records: (0..*n).map(|_| record("A", Phase::Work)).collect(),
+            })
+            .collect()
+    }
+
+    /// `compute_goal_progress`：按月/年/标签周期聚合，并回填设置中的目标值。
+    #[test]
+    fn compute_goal_progress_covers_month_year_and_tags() {
+        use crate::app_data::TagGoal;
+        let mut data = AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2024-12-31".to_string(),
+                    records: vec![record("A", Phase::Work)],
+                },
+                HistoryDay {
+                    date: "2025-01-01".to_string(),
+                    records: vec![record("A", Phase::Work), record("B", Phase::Work)],
+                },
+                HistoryDay {
+                    date: "2025-02-03".to_string(),
+                    records: vec![record("A", Phase::Work)],
+                },
+            ],
+            ..AppData::default()
+        };
+        data.settings.monthly_goal = 20;
+        data.settings.yearly_goal = 200;
+        data.settings.tag_goals = vec![TagGoal {
+            tag: "A".to_string(),
+            tag_id: None,
+            period: GoalPeriod::Yearly,
+            target: 100,
+        }];
+
+        let out = compute_goal_progress(&data, "2025-02-03", "2025-02-03", "2025-02-09");
+        assert_eq!(out.daily_completed, 1);
+        assert_eq!(out.weekly_completed, 1);
+        assert_eq!((out.monthly_goal, out.monthly_completed), (20, 1));
+        assert_eq!((out.yearly_goal, out.yearly_completed), (200, 3));
+        assert_eq!(
+            out.tag_goals,
+            vec![TagGoalProgress {
+                tag: "A".to_string(),
+                period: GoalPeriod::Yearly,
+                target: 100,
+                completed: 2,
+            }]
+        );
+    }
+
+    /// 子标签：开启汇总时计入顶层标签；父标签的标签目标始终包含子标签。
+    #[test]
+    fn roll_up_tags_merges_children_into_parents() {
+        use crate::app_data::TagGoal;
+        let mut data = AppData {
+            history: vec![HistoryDay {
+                date: "2025-01-01".to_string(),
+                records: vec![
+                    record("工作", Phase::Work),
+                    record("工作/项目X", Phase::Work),
+                    record("工作/项目Y", Phase::Work),
+                    record("工作项目", Phase::Work),
+                ],
+            }],
+            ..AppData::default()
+        };
+        data.settings.tag_goals = vec![TagGoal {
+            tag: "工作".to_string(),
+            tag_id: None,
+            period: GoalPeriod::Daily,
+            target: 4,
+        }];
+
+        assert_eq!(compute_today_stats(&data, "2025-01-01").by_tag.len(), 4);
+        let goals = compute_goal_progress(&data, "2025-01-01", "2024-12-30", "2025-01-05");
+        assert_eq!(goals.tag_goals[0].completed, 3);
+
+        data.settings.roll_up_tags = true;
+        assert_eq!(
+            compute_week_stats(&data, "2024-12-30", "2025-01-05").by_tag,
+            vec![
+                TagCount {
+                    tag: "工作".to_string(),
+                    count: 3,
+                },
+                TagCount {
+                    tag: "工作项目".to_string(),
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    /// `compute_streak`：今天未达标不中断连续记录；未达标的一天会中断，除非仍有休息日额度。
+    #[test]
+    fn compute_streak_counts_runs_and_rest_days() {
+        // 2025-01-01..05：达标、达标、未达标、达标、达标；2025-01-06（周一）为今天且未达标。
+        let mut data = AppData {
+            history: history_with_counts(&[2, 2, 0, 2, 2, 1]),
+            ..AppData::default()
+        };
+        data.settings.daily_goal = 2;
+
+        let out = compute_streak(&data, "2025-01-06");
+        assert_eq!(out.current_days, 2);
+        assert_eq!(out.longest_days, 2);
+        assert!(!out.today_met);
+        assert!(out.is_at_risk());
+
+        // 每周允许 1 天休息：01-03 不再中断，本周（从 01-06 起）仍有 1 天额度。
+        data.settings.streak.rest_days_per_week = 1;
+        let out = compute_streak(&data, "2025-01-06");
+        assert_eq!(out.current_days, 4);
+        assert_eq!(out.longest_days, 4);
+        assert_eq!(out.rest_days_left, 1);
+        assert!(!out.is_at_risk());
+
+        // 未设置每日目标时不计算连续记录。
+        data.settings.daily_goal = 0;
+        assert_eq!(
+            compute_streak(&data, "2025-01-06"),
+            StreakProgress::default()
+        );
+    }
 }
@@ -33,6 +33,91 @@ pub fn validate_settings(settings: &Settings) -> AppResult<()> {
     if settings.audio.volume > 100 {
         return Err(AppError::Validation("音效音量需在 0-100".to_string()));
     }
+    if settings.day_rollover_hour > 23 {
+        return Err(AppError::Validation("日界线需在 0-23 点".to_string()));
+    }
+    if settings.monthly_goal > 50000 {
+        return Err(AppError::Validation("每月目标建议不超过 50000".to_string()));
+    }
+    if settings.yearly_goal > 500000 {
+        return Err(AppError::Validation(
+            "每年目标建议不超过 500000".to_string(),
+        ));
+    }
+    let mut seen = std::collections::BTreeSet::new();
+    for goal in &settings.tag_goals {
+        if goal.tag.trim().is_empty() {
+            return Err(AppError::Validation("标签目标的标签不能为空".to_string()));
+        }
+        if !(1..=500000).contains(&goal.target) {
+            return Err(AppError::Validation(
+                "标签目标数量需在 1-500000".to_string(),
+            ));
+        }
+        if !seen.insert((goal.tag.trim(), goal.period as u8)) {
+            return Err(AppError::Validation(
+                "同一标签在同一周期只能设置一个目标".to_string(),
+            ));
+        }
+    }
+    let mut override_tags = std::collections::BTreeSet::new();
+    for o in &settings.tag_overrides {
+        if o.tag.trim().is_empty() {
+            return Err(AppError::Validation("标签覆盖的标签不能为空".to_string()));
+        }
+        if !override_tags.insert(o.tag.trim()) {
+            return Err(AppError::Validation("同一标签只能设置一组覆盖".to_string()));
+        }
+        if o.pomodoro.is_some_and(|m| !(1..=60).contains(&m)) {
+            return Err(AppError::Validation(
+                "标签覆盖的番茄时长需在 1-60 分钟".to_string(),
+            ));
+        }
+        if o.short_break.is_some_and(|m| !(1..=30).contains(&m)) {
+            return Err(AppError::Validation(
+                "标签覆盖的短休息需在 1-30 分钟".to_string(),
+            ));
+        }
+        if o.long_break.is_some_and(|m| !(1..=60).contains(&m)) {
+            return Err(AppError::Validation(
+                "标签覆盖的长休息需在 1-60 分钟".to_string(),
+            ));
+        }
+        if o.auto_continue_pomodoros
+            .is_some_and(|n| !(1..=20).contains(&n))
+        {
+            return Err(AppError::Validation(
+                "标签覆盖的连续番茄数量需在 1-20 个番茄".to_string(),
+            ));
+        }
+        if o.blacklist_template_ids
+            .as_ref()
+            .is_some_and(|ids| ids.iter().any(|id| id.trim().is_empty()))
+        {
+            return Err(AppError::Validation(
+                "标签覆盖的黑名单模板 id 不能为空".to_string(),
+            ));
+        }
+    }
+    if settings.streak.rest_days_per_week > 6 {
+        return Err(AppError::Validation("每周休息日需在 0-6 天".to_string()));
+    }
+    if settings.streak.reminder_hour > 23 {
+        return Err(AppError::Validation(
+            "连续记录提醒时刻需在 0-23 点".to_string(),
+        ));
+    }
+    if !(1..=168).contains(&settings.backup.interval_hours) {
+        return Err(AppError::Validation("备份间隔需在 1-168 小时".to_string()));
+    }
+    if !(1..=100).contains(&settings.backup.keep_count) {
+        return Err(AppError::Validation("备份保留份数需在 1-100".to_string()));
+    }
+    if settings.archive_after_months > crate::app_data::MAX_ARCHIVE_AFTER_MONTHS {
+        return Err(AppError::Validation(
+            "历史保留月数需在 0-120 个月（0 表示不归档）".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -156,4 +241,150 @@ mod tests {
             Err(AppError::Validation(_))
         ));
     }
+
+    /// 校验：日界线需在 0-23 点。
+    #[test]
+    fn validate_settings_rejects_day_rollover_hour_out_of_range() {
+        assert!(validate_settings(&Settings {
+            day_rollover_hour: 23,
+            ..Settings::default()
+        })
+        .is_ok());
+        assert!(matches!(
+            validate_settings(&Settings {
+                day_rollover_hour: 24,
+                ..Settings::default()
+            }),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// 校验：标签目标需非空、数量合法且同一标签同一周期不重复；休息日不超过 6 天。
+    #[test]
+    fn validate_settings_rejects_invalid_tag_goals_and_streak() {
+        use crate::app_data::{GoalPeriod, TagGoal};
+        let goal = |tag: &str, period: GoalPeriod, target: u32| TagGoal {
+            tag: tag.to_string(),
+            tag_id: None,
+            period,
+            target,
+        };
+        assert!(validate_settings(&Settings {
+            tag_goals: vec![
+                goal("学习", GoalPeriod::Daily, 4),
+                goal("学习", GoalPeriod::Weekly, 20),
+            ],
+            ..Settings::default()
+        })
+        .is_ok());
+
+        for tag_goals in [
+            vec![goal(" ", GoalPeriod::Daily, 4)],
+            vec![goal("学习", GoalPeriod::Daily, 0)],
+            vec![
+                goal("学习", GoalPeriod::Daily, 4),
+                goal("学习", GoalPeriod::Daily, 6),
+            ],
+        ] {
+            assert!(matches!(
+                validate_settings(&Settings {
+                    tag_goals,
+                    ..Settings::default()
+                }),
+                Err(AppError::Validation(_))
+            ));
+        }
+
+        let mut settings = Settings::default();
+        settings.streak.rest_days_per_week = 7;
+        assert!(matches!(
+            validate_settings(&settings),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    /// 校验：标签覆盖需非空且不重复，覆盖的时长/连续番茄数沿用全局范围约束。
+    #[test]
+    fn validate_settings_rejects_invalid_tag_overrides() {
+        use crate::app_data::TagOverride;
+        let ok = TagOverride {
+            tag: "学习".to_string(),
+            pomodoro: Some(45),
+            blacklist_template_ids: Some(vec!["tpl-1".to_string()]),
+            ..TagOverride::default()
+        };
+        assert!(validate_settings(&Settings {
+            tag_overrides: vec![ok.clone()],
+            ..Settings::default()
+        })
+        .is_ok());
+
+        for tag_overrides in [
+            vec![TagOverride {
+                tag: " ".to_string(),
+                ..ok.clone()
+            }],
+            vec![ok.clone(), ok.clone()],
+            vec![TagOverride {
+                pomodoro: Some(61),
+                ..ok.clone()
+            }],
+            vec![TagOverride {
+                short_break: Some(0),
+                ..ok.clone()
+            }],
+            vec![TagOverride {
+                auto_continue_pomodoros: Some(21),
+                ..ok.clone()
+            }],
+            vec![TagOverride {
+                blacklist_template_ids: Some(vec!["  ".to_string()]),
+                ..ok.clone()
+            }],
+        ] {
+            assert!(matches!(
+                validate_settings(&Settings {
+                    tag_overrides,
+                    ..Settings::default()
+                }),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+
+    /// 校验：备份间隔与保留份数越界应被拒绝。
+    #[test]
+    fn validate_settings_rejects_invalid_backup_settings() {
+        use crate::app_data::BackupSettings;
+
+        for backup in [
+            BackupSettings {
+                interval_hours: 0,
+                ..BackupSettings::default()
+            },
+            BackupSettings {
+                interval_hours: 169,
+                ..BackupSettings::default()
+            },
+            BackupSettings {
+                keep_count: 0,
+                ..BackupSettings::default()
+            },
+        ] {
+            assert!(matches!(
+                validate_settings(&Settings {
+                    backup,
+                    ..Settings::default()
+                }),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            validate_settings(&Settings {
+                archive_after_months: 121,
+                ..Settings::default()
+            }),
+            Err(AppError::Validation(_))
+        ));
+    }
 }
@@ -8,6 +8,8 @@ use crate::app_data::Phase;
 use crate::errors::{AppError, AppResult};
 use crate::state::AppState;
 
+/// 托盘菜单项 id：当前配置（仅展示，不可点击）。
+const MENU_PROFILE_ID: &str = "tray.profile";
 /// 托盘菜单项 id：开始。
 const MENU_START_ID: &str = "tray.start";
 /// 托盘菜单项 id：暂停。
@@ -26,6 +28,8 @@ const MENU_QUIT_ID: &str = "tray.quit";
 pub struct TrayHandles {
     /// 托盘图标句柄。
     pub tray: TrayIcon<tauri::Wry>,
+    /// “当前配置”菜单项（仅展示）。
+    pub profile_item: MenuItem<tauri::Wry>,
     /// “开始”菜单项。
     pub start_item: MenuItem<tauri::Wry>,
     /// “暂停”菜单项。
@@ -37,13 +41,22 @@ pub struct TrayHandles {
 }
 
 /// 创建托盘与菜单，并写入 `AppState`。
-pub fn setup_tray(app: &mut tauri::App) -> AppResult<()> {
+pub fn setup_tray(app: &tauri::AppHandle) -> AppResult<()> {
     let state = app.state::<AppState>();
     let snapshot = state.timer_snapshot();
     let initial_text = format_mm_ss(snapshot.remaining_seconds);
     let window_mode = state.window_mode_snapshot();
 
+    let profile = state.active_profile();
+
     let menu = Menu::new(app)?;
+    let profile_item = MenuItem::with_id(
+        app,
+        MENU_PROFILE_ID,
+        profile_label(&profile.name),
+        false,
+        None::<&str>,
+    )?;
     let start_item = MenuItem::with_id(app, MENU_START_ID, "开始", true, None::<&str>)?;
     let pause_item = MenuItem::with_id(app, MENU_PAUSE_ID, "暂停", true, None::<&str>)?;
     let show_item = MenuItem::with_id(app, MENU_SHOW_ID, "显示窗口", true, None::<&str>)?;
@@ -63,6 +76,7 @@ pub fn setup_tray(app: &mut tauri::App) -> AppResult<()> {
     )?;
     let quit_item = MenuItem::with_id(app, MENU_QUIT_ID, "退出", true, None::<&str>)?;
     menu.append_items(&[
+        &profile_item,
         &start_item,
         &pause_item,
         &show_item,
@@ -77,7 +91,7 @@ pub fn setup_tray(app: &mut tauri::App) -> AppResult<()> {
         // 禁用“左键显示托盘菜单”：避免左键点击时系统菜单闪现（我们仅在左键时显示主窗口）。
         .show_menu_on_left_click(false)
         .icon(Image::new_owned(initial_icon, 32, 32))
-        .tooltip("番茄钟")
+        .tooltip(tray_tooltip(&profile.name))
         .on_menu_event(|app_handle, event| {
             let state = app_handle.state::<AppState>();
             let id = event.id().as_ref();
@@ -130,6 +144,7 @@ pub fn setup_tray(app: &mut tauri::App) -> AppResult<()> {
 
     state.set_tray(TrayHandles {
         tray: tray.clone(),
+        profile_item: profile_item.clone(),
         start_item: start_item.clone(),
         pause_item: pause_item.clone(),
         mini_on_item: mini_on_item.clone(),
@@ -160,9 +175,23 @@ pub fn refresh_tray(state: &AppState) -> AppResult<()> {
     let _ = handles.mini_on_item.set_enabled(!window_mode.mini_mode);
     let _ = handles.mini_off_item.set_enabled(window_mode.mini_mode);
 
+    let profile = state.active_profile();
+    let _ = handles.profile_item.set_text(profile_label(&profile.name));
+    let _ = handles.tray.set_tooltip(Some(tray_tooltip(&profile.name)));
+
     Ok(())
 }
 
+/// 托盘菜单中的当前配置文案。
+fn profile_label(name: &str) -> String {
+    format!("配置：{name}")
+}
+
+/// 托盘提示文案（包含当前配置）。
+fn tray_tooltip(name: &str) -> String {
+    format!("番茄钟 · {name}")
+}
+
 /// 将秒数格式化为 `mm:ss`。
 fn format_mm_ss(seconds: u64) -> String {
     let m = seconds / 60;
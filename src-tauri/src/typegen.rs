@@ -2,16 +2,33 @@
 
 pub use crate::analysis::{FocusAnalysis, TagEfficiency};
 pub use crate::app_data::{
-    AnimationIntensity, AnimationSettings, AppData, AudioSettings, BlacklistItem,
-    BlacklistTemplate, CustomAudio, DateRange, HistoryDay, HistoryRecord, InterruptionDay,
-    InterruptionRecord, InterruptionSettings, InterruptionType, Phase, Settings,
+    AnimationIntensity, AnimationSettings, AppData, ArchivedDay, ArchivedTagTotal, AudioSettings,
+    BackupSettings, BlacklistItem, BlacklistTemplate, CustomAudio, DateRange, FirstWeekday,
+    GoalPeriod, HistoryArchive, HistoryDay, HistoryRecord, InterruptionDay, InterruptionRecord,
+    InterruptionSettings, InterruptionType, PauseSegment, Phase, PlanStep, SessionPlan, Settings,
+    StreakSettings, SuspendExpiryPolicy, Tag, TagGoal, TagOverride, Task,
 };
+pub use crate::archive::{ArchiveFileInfo, ArchiveReport, ArchiveStatus};
+pub use crate::audit::{AuditEntry, ReplayReport};
+pub use crate::backup::{BackupInfo, BackupKind, BackupPreview};
 pub use crate::commands::types::{
-    AppSnapshot, ExportField, ExportFormat, ExportRequest, StorePaths,
+    AppSnapshot, CsvColumnMapping, ExportField, ExportFormat, ExportRequest, ExternalImportRequest,
+    ExternalImportSource, HistoryRecordInput, ImportMode, ImportReport, ImportRequest,
+    ImportRowError, ProjectTagMapping, RelocateRequest, StorePaths, UndoOutcome,
 };
+pub use crate::data_root::{DataRootInfo, DataRootMode};
 pub use crate::events::{MilestoneReachedPayload, PomodoroCompletedPayload};
+pub use crate::integrity::{IntegrityIssue, IntegrityIssueKind, IntegrityReport};
 pub use crate::interruptions::{InterruptionReasonCount, InterruptionStats};
+pub use crate::migrations::{MigrationReport, MigrationStepReport};
 pub use crate::processes::termination::KillItem;
 pub use crate::processes::{KillSummary, ProcessInfo};
-pub use crate::timer::stats::{GoalProgress, TagCount, TodayStats, WeekStats};
+pub use crate::profiles::{ProfileInfo, ProfileRegistry};
+pub use crate::recovery::StoreRecoveryReport;
+pub use crate::sync::{SyncConflict, SyncDeviceStatus, SyncReport, SyncStatus};
+pub use crate::tasks::{TagEstimate, TaskEstimate, TaskEstimateReport};
+pub use crate::timer::stats::{
+    GoalProgress, StreakProgress, TagCount, TagGoalProgress, TodayStats, WeekStats,
+};
 pub use crate::timer::{TimerSnapshot, WorkCompletedEvent};
+pub use crate::undo::UndoState;
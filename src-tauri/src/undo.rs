@@ -0,0 +1,563 @@
+//! 撤销/重做：可撤销操作记录为“变更前后的数据片段”，撤销时校验片段未被后续修改后再写回。
+//!
+//! 片段只覆盖操作修改前登记过的部分（见 `AppData::begin_undo_capture`）：字段分组按顶层字段（如 `tagDefs`），
+//! 历史记录按记录 id（如 `history/<id>`），中断记录按日期与中断时刻（如 `interruptions/2025-01-01/<时刻>`）。
+//! 累计番茄数不作为片段，写回时按增减的工作记录调整，因此之后完成的番茄等无关修改不影响撤销。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::app_data::{AppData, HistoryRecord, InterruptionRecord, Phase, RecordSet, Section};
+use crate::errors::{AppError, AppResult};
+
+/// 撤销栈保留的最近操作数（重启后仍可撤销）。
+pub const UNDO_LIMIT: usize = 20;
+
+/// 与专注期黑名单锁定相关的片段（锁定期间不允许撤销/重做）。
+const BLACKLIST_SECTIONS: [&str; 4] = [
+    "blacklist",
+    "blacklistTemplates",
+    "activeTemplateIds",
+    "activeTemplateId",
+];
+
+/// 数据片段集合：片段键 → JSON 值（片段不存在时为 `null`）。
+type Fragments = BTreeMap<String, Value>;
+
+/// 栈类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoStack {
+    /// 撤销栈。
+    Undo,
+    /// 重做栈。
+    Redo,
+}
+
+impl UndoStack {
+    /// 持久化时使用的名称。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
+    }
+}
+
+/// 一条可撤销操作。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoEntry {
+    /// 操作描述（如 `删除标签「阅读」`）。
+    pub description: String,
+    /// 操作时刻（RFC 3339）。
+    pub at: String,
+    /// 变更前的片段。
+    before: Fragments,
+    /// 变更后的片段。
+    after: Fragments,
+}
+
+/// 撤销/重做状态：待撤销与待重做操作的描述（无可用操作时为 `null`）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase")]
+pub struct UndoState {
+    /// 下一次撤销将恢复的操作。
+    pub undo: Option<String>,
+    /// 下一次重做将重新执行的操作。
+    pub redo: Option<String>,
+}
+
+/// 撤销片段采集：操作修改数据前经 `touch_*` 登记的日期与字段分组，首次登记时保存其原值。
+#[derive(Debug, Clone, Default)]
+pub struct UndoCapture {
+    /// 登记过的 `(集合, 日期)`。
+    days: BTreeSet<(RecordSet, String)>,
+    /// 登记过的字段分组（不含累计计数）。
+    sections: BTreeSet<Section>,
+    /// 是否登记了全部记录（生成撤销记录时同时比较之后新出现的日期）。
+    all: bool,
+    /// 登记时的原值。
+    before: Fragments,
+    /// 采集失败的原因（生成撤销记录时报告）。
+    error: Option<String>,
+}
+
+impl UndoCapture {
+    /// 登记某日的记录并保存其原值（同一日只保存一次）。
+    pub fn capture_day(&mut self, data: &AppData, set: RecordSet, date: &str) {
+        if self.days.insert((set, date.to_string())) {
+            self.keep(day_fragments(data, set, date));
+        }
+    }
+
+    /// 登记字段分组并保存其原值（累计计数由记录增减推算，不登记）。
+    pub fn capture_section(&mut self, data: &AppData, section: Section) {
+        if section != Section::Counters && self.sections.insert(section) {
+            self.keep(section_fragments(data, section));
+        }
+    }
+
+    /// 登记全部记录与字段分组（批量修复等无法逐项登记的操作）。
+    pub fn capture_all(&mut self, data: &AppData) {
+        self.all = true;
+        for (set, date) in all_days(data) {
+            self.capture_day(data, set, &date);
+        }
+        for section in Section::ALL {
+            self.capture_section(data, section);
+        }
+    }
+
+    /// 保存原值（同一片段以最先登记的为准，例如记录从已登记的日期移到另一日期后再登记该日）。
+    fn keep(&mut self, fragments: AppResult<Fragments>) {
+        match fragments {
+            Ok(fragments) => {
+                for (key, value) in fragments {
+                    self.before.entry(key).or_insert(value);
+                }
+            }
+            Err(e) => {
+                self.error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+}
+
+impl UndoEntry {
+    /// 由采集结果生成撤销记录：比较登记范围内的原值与当前值（无变化时返回 `None`）。
+    pub fn from_capture(
+        description: &str,
+        capture: UndoCapture,
+        data: &AppData,
+    ) -> AppResult<Option<Self>> {
+        if let Some(e) = capture.error {
+            return Err(AppError::Invariant(format!("采集撤销片段失败：{e}")));
+        }
+        let mut days = capture.days;
+        if capture.all {
+            days.extend(all_days(data));
+        }
+        let mut current = Fragments::new();
+        for (set, date) in &days {
+            current.extend(day_fragments(data, *set, date)?);
+        }
+        for section in &capture.sections {
+            current.extend(section_fragments(data, *section)?);
+        }
+        let mut before = Fragments::new();
+        let mut after = Fragments::new();
+        for key in capture.before.keys().chain(current.keys()) {
+            let o = capture.before.get(key).cloned().unwrap_or(Value::Null);
+            let n = current.get(key).cloned().unwrap_or(Value::Null);
+            if o != n {
+                before.insert(key.clone(), o);
+                after.insert(key.clone(), n);
+            }
+        }
+        if before.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            description: description.to_string(),
+            at: chrono::Local::now().to_rfc3339(),
+            before,
+            after,
+        }))
+    }
+
+    /// 是否改动了黑名单或模板。
+    pub fn touches_blacklist(&self) -> bool {
+        self.before
+            .keys()
+            .any(|key| BLACKLIST_SECTIONS.contains(&key.as_str()))
+    }
+
+    /// 撤销：当前数据须仍为变更后的状态，写回变更前的片段。
+    pub fn revert(&self, data: &mut AppData) -> AppResult<()> {
+        replace_fragments(data, &self.after, &self.before).map_err(|e| match e {
+            AppError::Validation(_) => AppError::Validation(format!(
+                "「{}」之后数据已有其他修改，无法撤销",
+                self.description
+            )),
+            other => other,
+        })
+    }
+
+    /// 重做：当前数据须仍为变更前的状态，写回变更后的片段。
+    pub fn reapply(&self, data: &mut AppData) -> AppResult<()> {
+        replace_fragments(data, &self.before, &self.after).map_err(|e| match e {
+            AppError::Validation(_) => AppError::Validation(format!(
+                "「{}」撤销后数据已有其他修改，无法重做",
+                self.description
+            )),
+            other => other,
+        })
+    }
+}
+
+/// 历史记录片段的值：记录及其所在日期。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PlacedRecord {
+    /// 日期（YYYY-MM-DD）。
+    date: String,
+    /// 记录。
+    record: HistoryRecord,
+}
+
+/// 片段键的含义。
+enum FragmentKey<'a> {
+    /// 历史记录：集合与记录 id。
+    Record(RecordSet, &'a str),
+    /// 某日某一中断时刻的中断记录：日期与中断时刻。
+    Interruptions(&'a str, &'a str),
+    /// `AppData` 顶层字段。
+    Field(&'a str),
+}
+
+/// 解析片段键。
+fn parse_key(key: &str) -> FragmentKey<'_> {
+    match key.split_once('/') {
+        Some(("history", id)) => FragmentKey::Record(RecordSet::History, id),
+        Some(("historyDev", id)) => FragmentKey::Record(RecordSet::HistoryDev, id),
+        Some(("interruptions", rest)) => {
+            let (date, at) = rest.split_once('/').unwrap_or((rest, ""));
+            FragmentKey::Interruptions(date, at)
+        }
+        _ => FragmentKey::Field(key),
+    }
+}
+
+/// 记录集合在片段键中的名称。
+fn set_name(set: RecordSet) -> &'static str {
+    match set {
+        RecordSet::History => "history",
+        RecordSet::HistoryDev => "historyDev",
+        RecordSet::Interruptions => "interruptions",
+    }
+}
+
+/// 数据中出现的全部 `(集合, 日期)`。
+fn all_days(data: &AppData) -> Vec<(RecordSet, String)> {
+    let history = data.history.iter().map(|d| (RecordSet::History, &d.date));
+    let history_dev = data
+        .history_dev
+        .iter()
+        .map(|d| (RecordSet::HistoryDev, &d.date));
+    let interruptions = data
+        .interruptions
+        .iter()
+        .map(|d| (RecordSet::Interruptions, &d.date));
+    history
+        .chain(history_dev)
+        .chain(interruptions)
+        .map(|(set, date)| (set, date.clone()))
+        .collect()
+}
+
+/// 某日记录的片段：历史记录每条一个片段，中断记录按中断时刻分组。
+fn day_fragments(data: &AppData, set: RecordSet, date: &str) -> AppResult<Fragments> {
+    let mut out = Fragments::new();
+    match set {
+        RecordSet::History | RecordSet::HistoryDev => {
+            let days = if set == RecordSet::HistoryDev {
+                &data.history_dev
+            } else {
+                &data.history
+            };
+            for day in days.iter().filter(|d| d.date == date) {
+                for record in &day.records {
+                    let placed = serde_json::json!({ "date": date, "record": record });
+                    out.insert(format!("{}/{}", set_name(set), record.id), placed);
+                }
+            }
+        }
+        RecordSet::Interruptions => {
+            let mut by_time: BTreeMap<&str, Vec<&InterruptionRecord>> = BTreeMap::new();
+            for day in data.interruptions.iter().filter(|d| d.date == date) {
+                for record in &day.records {
+                    by_time.entry(&record.timestamp).or_default().push(record);
+                }
+            }
+            for (at, records) in by_time {
+                out.insert(
+                    format!("{}/{date}/{at}", set_name(set)),
+                    serde_json::to_value(records)?,
+                );
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// 字段分组的片段（每个顶层字段一个）。
+fn section_fragments(data: &AppData, section: Section) -> AppResult<Fragments> {
+    let mut out = Fragments::new();
+    for field in section.fields() {
+        out.insert(field.to_string(), data.section_value(field)?);
+    }
+    Ok(out)
+}
+
+/// 片段的当前值（不存在时为 `null`）。
+fn current_fragment(data: &AppData, key: &str) -> AppResult<Value> {
+    Ok(match parse_key(key) {
+        FragmentKey::Record(set, id) => {
+            let days = if set == RecordSet::HistoryDev {
+                &data.history_dev
+            } else {
+                &data.history
+            };
+            days.iter()
+                .find_map(|d| {
+                    let record = d.records.iter().find(|r| r.id == id)?;
+                    Some(serde_json::json!({ "date": d.date, "record": record }))
+                })
+                .unwrap_or(Value::Null)
+        }
+        FragmentKey::Interruptions(date, at) => {
+            let records: Vec<&InterruptionRecord> = data
+                .interruptions
+                .iter()
+                .filter(|d| d.date == date)
+                .flat_map(|d| d.records.iter())
+                .filter(|r| r.timestamp == at)
+                .collect();
+            if records.is_empty() {
+                Value::Null
+            } else {
+                serde_json::to_value(records)?
+            }
+        }
+        FragmentKey::Field(name) => data.section_value(name)?,
+    })
+}
+
+/// 校验 `data` 中的片段等于 `expected` 后写入 `next`（不一致时返回 `Validation`）。
+///
+/// 写入的记录按开始时间插入所在日期，清空的日期随之移除；工作记录的增减同步到累计番茄数。
+fn replace_fragments(data: &mut AppData, expected: &Fragments, next: &Fragments) -> AppResult<()> {
+    for (key, value) in expected {
+        if current_fragment(data, key)? != *value {
+            return Err(AppError::Validation(format!("片段已变化：{key}")));
+        }
+    }
+
+    let mut touched = BTreeSet::new();
+    let mut work_delta = 0i64;
+    for (key, value) in next {
+        match parse_key(key) {
+            FragmentKey::Record(set, id) => {
+                let old: Option<PlacedRecord> =
+                    serde_json::from_value(expected.get(key).cloned().unwrap_or(Value::Null))?;
+                let new: Option<PlacedRecord> = serde_json::from_value(value.clone())?;
+                work_delta += is_work(new.as_ref()) - is_work(old.as_ref());
+                put_record(data, set, id, new, &mut touched);
+            }
+            FragmentKey::Interruptions(date, at) => {
+                let records: Option<Vec<InterruptionRecord>> =
+                    serde_json::from_value(value.clone())?;
+                let day = data.interruption_day_mut(date);
+                day.records.retain(|r| r.timestamp != at);
+                day.records.extend(records.unwrap_or_default());
+                day.records.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+                touched.insert((RecordSet::Interruptions, date.to_string()));
+            }
+            FragmentKey::Field(name) => {
+                if let Some(section) = Section::of_field(name) {
+                    data.touch_section(section);
+                }
+                data.set_section_value(name, value.clone())?;
+            }
+        }
+    }
+    if work_delta != 0 {
+        data.touch_section(Section::Counters);
+        data.total_pomodoros = data.total_pomodoros.saturating_add_signed(work_delta);
+    }
+
+    for (set, date) in &touched {
+        let keep = |d: &String, empty: bool| d != date || !empty;
+        match set {
+            RecordSet::History => data.history.retain(|d| keep(&d.date, d.records.is_empty())),
+            RecordSet::HistoryDev => data
+                .history_dev
+                .retain(|d| keep(&d.date, d.records.is_empty())),
+            RecordSet::Interruptions => data
+                .interruptions
+                .retain(|d| keep(&d.date, d.records.is_empty())),
+        }
+    }
+    data.record_days_replaced(touched);
+    Ok(())
+}
+
+/// 片段中的记录是否计入累计番茄数（1 或 0）。
+fn is_work(placed: Option<&PlacedRecord>) -> i64 {
+    i64::from(placed.is_some_and(|p| p.record.phase == Phase::Work))
+}
+
+/// 以 `placed` 替换 id 为 `id` 的记录（`None` 表示删除），并登记涉及的日期。
+fn put_record(
+    data: &mut AppData,
+    set: RecordSet,
+    id: &str,
+    placed: Option<PlacedRecord>,
+    touched: &mut BTreeSet<(RecordSet, String)>,
+) {
+    let dev = set == RecordSet::HistoryDev;
+    let days = if dev {
+        &data.history_dev
+    } else {
+        &data.history
+    };
+    let current = days
+        .iter()
+        .find(|d| d.records.iter().any(|r| r.id == id))
+        .map(|d| d.date.clone());
+    if let Some(date) = current {
+        data.touch_day(set, &date);
+        let days = if dev {
+            &mut data.history_dev
+        } else {
+            &mut data.history
+        };
+        for day in days.iter_mut().filter(|d| d.date == date) {
+            day.records.retain(|r| r.id != id);
+        }
+        touched.insert((set, date));
+    }
+    if let Some(PlacedRecord { date, record }) = placed {
+        let day = data.history_day_mut(dev, &date);
+        day.records.push(record);
+        day.records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        touched.insert((set, date));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app_data::{HistoryDay, HistoryRecord};
+
+    /// 构造带两天历史的数据。
+    fn sample_data() -> AppData {
+        AppData {
+            history: vec![
+                HistoryDay {
+                    date: "2025-01-01".to_string(),
+                    records: vec![HistoryRecord::for_test("工作", "09:00")],
+                },
+                HistoryDay {
+                    date: "2025-01-02".to_string(),
+                    records: vec![HistoryRecord::for_test("工作", "10:00")],
+                },
+            ],
+            total_pomodoros: 2,
+            ..AppData::default()
+        }
+    }
+
+    /// 以采集方式执行一次修改并生成撤销记录。
+    fn undoable(
+        data: &mut AppData,
+        description: &str,
+        f: impl FnOnce(&mut AppData),
+    ) -> Option<UndoEntry> {
+        data.begin_undo_capture();
+        f(data);
+        let capture = data.take_undo_capture();
+        UndoEntry::from_capture(description, capture, data).unwrap()
+    }
+
+    /// 撤销只写回被改动的记录：之后同一天与其他日期新增的记录保留；重做恢复改动。
+    #[test]
+    fn revert_keeps_unrelated_changes() {
+        let mut data = sample_data();
+        let entry = undoable(&mut data, "编辑备注", |data| {
+            data.history_day_mut(false, "2025-01-01").records[0].remark = "复盘".to_string();
+        })
+        .unwrap();
+        assert!(!entry.touches_blacklist());
+
+        for date in ["2025-01-01", "2025-01-02"] {
+            data.history_day_mut(false, date)
+                .records
+                .push(HistoryRecord::for_test("工作", "11:00"));
+        }
+        entry.revert(&mut data).unwrap();
+        assert_eq!(data.history[0].records[0].remark, "");
+        assert_eq!(data.history[0].records.len(), 2);
+        assert_eq!(data.history[1].records.len(), 2);
+
+        entry.reapply(&mut data).unwrap();
+        assert_eq!(data.history[0].records[0].remark, "复盘");
+        assert!(undoable(&mut data, "无变化", |data| {
+            data.touch_day(RecordSet::History, "2025-01-01");
+            data.touch_section(Section::Tags);
+        })
+        .is_none());
+    }
+
+    /// 被改动的记录之后又有修改时拒绝撤销；删除与新增整日均可往返。
+    #[test]
+    fn revert_rejects_conflicts_and_round_trips_days() {
+        let mut data = sample_data();
+        let entry = undoable(&mut data, "导入历史", |data| {
+            data.touch_day(RecordSet::History, "2025-01-02");
+            data.history.remove(1);
+            data.history_day_mut(false, "2024-12-31")
+                .records
+                .push(HistoryRecord::for_test("学习", "20:00"));
+        })
+        .unwrap();
+        let imported = data.clone();
+
+        entry.revert(&mut data).unwrap();
+        assert_eq!(
+            data.history
+                .iter()
+                .map(|d| d.date.as_str())
+                .collect::<Vec<_>>(),
+            ["2025-01-01", "2025-01-02"]
+        );
+
+        let mut conflicted = imported;
+        conflicted.history[0].records[0].remark = "改过".to_string();
+        assert!(matches!(
+            entry.revert(&mut conflicted),
+            Err(AppError::Validation(message)) if message.contains("导入历史")
+        ));
+    }
+
+    /// 累计番茄数不参与冲突校验：补录后又完成番茄仍可撤销，撤销按移除的工作记录扣减。
+    #[test]
+    fn revert_adjusts_total_without_conflicting() {
+        let mut data = sample_data();
+        let entry = undoable(&mut data, "补录", |data| {
+            data.touch_section(Section::Counters);
+            data.total_pomodoros += 1;
+            data.history_day_mut(false, "2025-01-01")
+                .records
+                .push(HistoryRecord::for_test("工作", "08:00"));
+        })
+        .unwrap();
+        assert!(!entry.before.contains_key("totalPomodoros"));
+
+        data.history_day_mut(false, "2025-01-01")
+            .records
+            .push(HistoryRecord::for_test("工作", "12:00"));
+        data.total_pomodoros += 1;
+        entry.revert(&mut data).unwrap();
+        assert_eq!(data.total_pomodoros, 3);
+        assert_eq!(data.history[0].records.len(), 2);
+
+        entry.reapply(&mut data).unwrap();
+        assert_eq!(data.total_pomodoros, 4);
+        assert_eq!(data.history[0].records[0].start_time, "08:00");
+    }
+}
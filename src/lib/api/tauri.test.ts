@@ -5,7 +5,19 @@ import { beforeEach, describe, expect, it, vi } from "vitest";
 const { invokeMock } = vi.hoisted(() => ({ invokeMock: vi.fn() }));
 vi.mock("@tauri-apps/api/core", () => ({ invoke: invokeMock }));
 
-import type { BlacklistItem, BlacklistTemplate, DateRange, ExportRequest, Settings } from "$lib/shared/types";
+import type {
+  BlacklistItem,
+  BlacklistTemplate,
+  DateRange,
+  ExportRequest,
+  ExternalImportRequest,
+  HistoryRecordInput,
+  ImportReport,
+  ImportRequest,
+  Settings,
+  Tag,
+  Task,
+} from "$lib/shared/types";
 import * as api from "./tauri";
 
 /** 构造一个最小的 DateRange（用于命令参数测试）。 */
@@ -36,7 +48,197 @@ describe("api/tauri wrappers", () => {
     expect(invokeMock).toHaveBeenLastCalledWith("open_store_dir");
   });
 
-  it("updateSettings / setGoals", async () => {
+  it("getDataRoot / relocateDataRoot", async () => {
+    const info = {
+      mode: "default",
+      root: "/data",
+      defaultRoot: "/data",
+      portableRoot: null,
+      pendingTarget: null,
+      lastError: null,
+    };
+    invokeMock.mockResolvedValueOnce(info);
+    await expect(api.getDataRoot()).resolves.toBe(info);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_data_root");
+
+    invokeMock.mockResolvedValueOnce(undefined);
+    const request = { mode: "custom" as const, path: "/usb/pomodoro" };
+    await expect(api.relocateDataRoot(request)).resolves.toBeUndefined();
+    expect(invokeMock).toHaveBeenLastCalledWith("relocate_data_root", { request });
+  });
+
+  it("getStoreRecoveryReport", async () => {
+    const report = { quarantinedPath: "q.json", fileUnreadable: false, dropped: ["history[1]"] };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.getStoreRecoveryReport()).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_store_recovery_report");
+  });
+
+  it("listBackups / previewBackup / restoreBackup", async () => {
+    const info = { fileName: "backup-20250101-000000000-scheduled.json" };
+    invokeMock.mockResolvedValueOnce([info]);
+    await expect(api.listBackups()).resolves.toEqual([info]);
+    expect(invokeMock).toHaveBeenLastCalledWith("list_backups");
+
+    const preview = { info, totalPomodoros: 1 };
+    invokeMock.mockResolvedValueOnce(preview);
+    await expect(api.previewBackup(info.fileName)).resolves.toBe(preview);
+    expect(invokeMock).toHaveBeenLastCalledWith("preview_backup", { fileName: info.fileName });
+
+    const snapshot = { data: {}, timer: {} };
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.restoreBackup(info.fileName)).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("restore_backup", { fileName: info.fileName });
+  });
+
+  it("history record editing commands", async () => {
+    const input: HistoryRecordInput = {
+      date: "2025-01-02",
+      startTime: "09:00",
+      duration: 25,
+      tag: "学习",
+      phase: "work",
+      remark: "",
+    };
+
+    await api.addHistoryRecord(input);
+    expect(invokeMock).toHaveBeenLastCalledWith("add_history_record", { input });
+    await api.updateHistoryRecord("2025-01-02", 1, input);
+    expect(invokeMock).toHaveBeenLastCalledWith("update_history_record", { date: "2025-01-02", recordIndex: 1, input });
+    await api.deleteHistoryRecord("2025-01-02", 0);
+    expect(invokeMock).toHaveBeenLastCalledWith("delete_history_record", { date: "2025-01-02", recordIndex: 0 });
+    await api.splitHistoryRecord("2025-01-02", 0, 10);
+    expect(invokeMock).toHaveBeenLastCalledWith("split_history_record", {
+      date: "2025-01-02",
+      recordIndex: 0,
+      minutes: 10,
+    });
+    await api.mergeHistoryRecords("2025-01-02", 0);
+    expect(invokeMock).toHaveBeenLastCalledWith("merge_history_records", { date: "2025-01-02", recordIndex: 0 });
+  });
+
+  it("getUndoState / undo / redo", async () => {
+    const state = { undo: "删除标签「A」", redo: null };
+    invokeMock.mockResolvedValueOnce(state);
+    await expect(api.getUndoState()).resolves.toBe(state);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_undo_state");
+
+    const outcome = { applied: "删除标签「A」", snapshot: { data: {}, timer: {} }, pending: state };
+    invokeMock.mockResolvedValueOnce(outcome);
+    await expect(api.undo()).resolves.toBe(outcome);
+    expect(invokeMock).toHaveBeenLastCalledWith("undo");
+
+    invokeMock.mockResolvedValueOnce(outcome);
+    await expect(api.redo()).resolves.toBe(outcome);
+    expect(invokeMock).toHaveBeenLastCalledWith("redo");
+  });
+
+  it("getChangeHistory / rebuildFromEventLog", async () => {
+    const entries = [{ seq: 2, at: "", kind: "remarkEdited", subject: "history:rec-1", summary: "备注" }];
+    invokeMock.mockResolvedValueOnce(entries);
+    await expect(api.getChangeHistory("history:rec-1")).resolves.toBe(entries);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_change_history", { subject: "history:rec-1" });
+
+    const report = { events: 3, matches: true, differingSections: [], committed: false };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.rebuildFromEventLog(false)).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("rebuild_from_event_log", { commit: false });
+  });
+
+  it("checkDataIntegrity", async () => {
+    const report = {
+      issues: [{ kind: "duplicateDay", location: "history/2025-01-01", message: "日期 2025-01-01 出现多次", repairable: true }],
+      repaired: false,
+    };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.checkDataIntegrity(false)).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("check_data_integrity", { repair: false });
+  });
+
+  it("getSyncStatus / enableSync / disableSync / syncNow", async () => {
+    const status = { enabled: true, folder: "/sync", deviceName: "台式机", lastSyncAt: null, devices: [], skipped: [] };
+    invokeMock.mockResolvedValueOnce(status);
+    await expect(api.getSyncStatus()).resolves.toBe(status);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_sync_status");
+
+    invokeMock.mockResolvedValueOnce(status);
+    await expect(api.enableSync("/sync", "台式机")).resolves.toBe(status);
+    expect(invokeMock).toHaveBeenLastCalledWith("enable_sync", { folder: "/sync", deviceName: "台式机" });
+
+    invokeMock.mockResolvedValueOnce({ ...status, enabled: false });
+    await api.disableSync();
+    expect(invokeMock).toHaveBeenLastCalledWith("disable_sync");
+
+    const report = { published: 1, received: 2, conflicts: [], skipped: [] };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.syncNow()).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("sync_now");
+  });
+
+  it("getArchiveStatus / archiveHistory", async () => {
+    const status = { afterMonths: 12, before: "2024-01-01", archivedDays: 3, archivedPomodoros: 9, files: [] };
+    invokeMock.mockResolvedValueOnce(status);
+    await expect(api.getArchiveStatus()).resolves.toBe(status);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_archive_status");
+
+    const report = { before: "2024-01-01", archivedDays: 3, archivedRecords: 12 };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.archiveHistory()).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("archive_history");
+  });
+
+  it("listProfiles / createProfile / switchProfile", async () => {
+    const registry = { active: "default", profiles: [{ id: "default", name: "默认", createdAt: "" }] };
+    invokeMock.mockResolvedValueOnce(registry);
+    await expect(api.listProfiles()).resolves.toBe(registry);
+    expect(invokeMock).toHaveBeenLastCalledWith("list_profiles");
+
+    invokeMock.mockResolvedValueOnce(registry);
+    await expect(api.createProfile("工作")).resolves.toBe(registry);
+    expect(invokeMock).toHaveBeenLastCalledWith("create_profile", { name: "工作" });
+
+    const snapshot = { data: {}, timer: {}, profile: registry.profiles[0] };
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.switchProfile("default")).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("switch_profile", { profileId: "default", passphrase: null });
+
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.switchProfile("work", "secret-pass")).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("switch_profile", { profileId: "work", passphrase: "secret-pass" });
+  });
+
+  it("getLockState / unlockStore / encryption commands", async () => {
+    const profile = { id: "default", name: "默认", createdAt: "" };
+    invokeMock.mockResolvedValueOnce(profile);
+    await expect(api.getLockState()).resolves.toBe(profile);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_lock_state");
+
+    const snapshot = { data: {}, timer: {}, profile };
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.unlockStore("secret-pass")).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("unlock_store", { passphrase: "secret-pass" });
+
+    invokeMock.mockResolvedValueOnce(true);
+    await expect(api.getEncryptionStatus()).resolves.toBe(true);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_encryption_status");
+
+    invokeMock.mockResolvedValueOnce(undefined);
+    await api.enableEncryption("secret-pass");
+    expect(invokeMock).toHaveBeenLastCalledWith("enable_encryption", { passphrase: "secret-pass" });
+
+    invokeMock.mockResolvedValueOnce(undefined);
+    await api.changePassphrase("secret-pass", "other-pass");
+    expect(invokeMock).toHaveBeenLastCalledWith("change_passphrase", {
+      oldPassphrase: "secret-pass",
+      newPassphrase: "other-pass",
+    });
+
+    invokeMock.mockResolvedValueOnce(undefined);
+    await api.disableEncryption("other-pass");
+    expect(invokeMock).toHaveBeenLastCalledWith("disable_encryption", { passphrase: "other-pass" });
+  });
+
+  it("updateSettings / setGoals / getGoalProgress", async () => {
     const settings = { pomodoro: 25 } as unknown as Settings;
     const snapshot = { data: { settings }, timer: { settings } };
     invokeMock.mockResolvedValueOnce(snapshot);
@@ -47,6 +249,11 @@ describe("api/tauri wrappers", () => {
     invokeMock.mockResolvedValueOnce(nextSettings);
     await expect(api.setGoals(1, 2)).resolves.toBe(nextSettings);
     expect(invokeMock).toHaveBeenLastCalledWith("set_goals", { daily: 1, weekly: 2 });
+
+    const progress = { dailyGoal: 1 };
+    invokeMock.mockResolvedValueOnce(progress);
+    await expect(api.getGoalProgress()).resolves.toBe(progress);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_goal_progress");
   });
 
   it("setCurrentTag / addTag / renameTag / deleteTag", async () => {
@@ -71,6 +278,43 @@ describe("api/tauri wrappers", () => {
     expect(invokeMock).toHaveBeenLastCalledWith("delete_tag", { tag: "新" });
   });
 
+  it("updateTag", async () => {
+    const tag: Tag = { id: "tag-1", name: "工作", color: "#f97316", icon: "", archived: false, parentId: null };
+    const snapshot = { ok: "updated" };
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.updateTag(tag)).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("update_tag", { tag });
+  });
+
+  it("getTasks / saveTask / deleteTask / setTaskDone / setCurrentTask / getTaskEstimates", async () => {
+    const task: Task = { id: "", title: "写周报", tag: "工作", tagId: null, estimatedPomodoros: 2, dueDate: null, done: false };
+    invokeMock.mockResolvedValueOnce([task]);
+    await expect(api.getTasks()).resolves.toEqual([task]);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_tasks");
+
+    invokeMock.mockResolvedValueOnce(task);
+    await expect(api.saveTask(task)).resolves.toBe(task);
+    expect(invokeMock).toHaveBeenLastCalledWith("save_task", { task });
+
+    invokeMock.mockResolvedValueOnce(true);
+    await expect(api.deleteTask("task-1")).resolves.toBe(true);
+    expect(invokeMock).toHaveBeenLastCalledWith("delete_task", { id: "task-1" });
+
+    invokeMock.mockResolvedValueOnce(task);
+    await api.setTaskDone("task-1", true);
+    expect(invokeMock).toHaveBeenLastCalledWith("set_task_done", { id: "task-1", done: true });
+
+    const snapshot = { ok: "task" };
+    invokeMock.mockResolvedValueOnce(snapshot);
+    await expect(api.setCurrentTask(null)).resolves.toBe(snapshot);
+    expect(invokeMock).toHaveBeenLastCalledWith("set_current_task", { id: null });
+
+    const report = { tasks: [], tags: [] };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.getTaskEstimates()).resolves.toBe(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("get_task_estimates");
+  });
+
   it("setBlacklist", async () => {
     const blacklist: BlacklistItem[] = [{ name: "a.exe", displayName: "A" }];
     invokeMock.mockResolvedValueOnce(blacklist);
@@ -130,12 +374,30 @@ describe("api/tauri wrappers", () => {
     expect(invokeMock).toHaveBeenLastCalledWith("set_mini_mode", { enabled: false });
   });
 
-  it("exportHistory / openLogDir / frontendLog / exitApp", async () => {
+  it("exportHistory / importHistory / importExternalHistory / openLogDir / frontendLog / exitApp", async () => {
     const request = { format: "csv", range: range(), fields: [] } as unknown as ExportRequest;
     invokeMock.mockResolvedValueOnce("x.csv");
     await expect(api.exportHistory(request)).resolves.toBe("x.csv");
     expect(invokeMock).toHaveBeenLastCalledWith("export_history", { request });
 
+    const importRequest: ImportRequest = { path: "x.csv", mode: "merge", commit: false, passphrase: null };
+    const report = { imported: 1 } as unknown as ImportReport;
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.importHistory(importRequest)).resolves.toEqual(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("import_history", { request: importRequest });
+
+    const externalRequest: ExternalImportRequest = {
+      path: "toggl.csv",
+      source: "toggl",
+      mapping: null,
+      projectTags: [{ project: "Website", tag: "工作" }],
+      mode: "merge",
+      commit: false,
+    };
+    invokeMock.mockResolvedValueOnce(report);
+    await expect(api.importExternalHistory(externalRequest)).resolves.toEqual(report);
+    expect(invokeMock).toHaveBeenLastCalledWith("import_external_history", { request: externalRequest });
+
     invokeMock.mockResolvedValueOnce(true);
     await expect(api.openLogDir()).resolves.toBe(true);
     expect(invokeMock).toHaveBeenLastCalledWith("open_log_dir");
@@ -3,20 +3,45 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AppSnapshot,
+  ArchiveReport,
+  ArchiveStatus,
+  AuditEntry,
+  BackupInfo,
+  BackupPreview,
   BlacklistItem,
   BlacklistTemplate,
   CustomAudio,
+  DataRootInfo,
   DateRange,
   ExportRequest,
+  ExternalImportRequest,
   FocusAnalysis,
+  GoalProgress,
   HistoryDay,
   HistoryRecord,
+  HistoryRecordInput,
+  ImportReport,
+  ImportRequest,
+  IntegrityReport,
   InterruptionRecord,
   InterruptionStats,
   ProcessInfo,
+  ProfileInfo,
+  ProfileRegistry,
+  RelocateRequest,
+  ReplayReport,
+  SessionPlan,
   Settings,
   StorePaths,
+  StoreRecoveryReport,
+  SyncReport,
+  SyncStatus,
+  Tag,
+  Task,
+  TaskEstimateReport,
   TimerSnapshot,
+  UndoOutcome,
+  UndoState,
 } from "../shared/types";
 
 /** 获取应用完整快照（持久化数据 + 计时器状态）。 */
@@ -34,6 +59,84 @@ export async function openStoreDir(): Promise<void> {
   return invoke<void>("open_store_dir");
 }
 
+/** 获取数据根目录信息（模式、路径、待迁移目标与上次迁移失败原因）。 */
+export async function getDataRoot(): Promise<DataRootInfo> {
+  return invoke<DataRootInfo>("get_data_root");
+}
+
+/** 登记数据根目录迁移并重启应用（迁移在启动时执行，失败会回滚并保留原目录）。 */
+export async function relocateDataRoot(request: RelocateRequest): Promise<void> {
+  return invoke<void>("relocate_data_root", { request });
+}
+
+/** 获取本次启动的数据恢复报告（store 损坏时非空，用于提示被丢弃的数据）。 */
+export async function getStoreRecoveryReport(): Promise<StoreRecoveryReport | null> {
+  return invoke<StoreRecoveryReport | null>("get_store_recovery_report");
+}
+
+/** 列出自动备份（按创建时间倒序）。 */
+export async function listBackups(): Promise<BackupInfo[]> {
+  return invoke<BackupInfo[]>("list_backups");
+}
+
+/** 预览备份内容（恢复前确认用，不修改当前数据）。 */
+export async function previewBackup(fileName: string): Promise<BackupPreview> {
+  return invoke<BackupPreview>("preview_backup", { fileName });
+}
+
+/** 恢复备份（恢复前会自动备份当前数据），返回恢复后的完整快照。 */
+export async function restoreBackup(fileName: string): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("restore_backup", { fileName });
+}
+
+/** 列出全部配置与当前配置。 */
+export async function listProfiles(): Promise<ProfileRegistry> {
+  return invoke<ProfileRegistry>("list_profiles");
+}
+
+/** 新建配置（不切换）。 */
+export async function createProfile(name: string): Promise<ProfileRegistry> {
+  return invoke<ProfileRegistry>("create_profile", { name });
+}
+
+/** 切换配置（专注锁定中会被拒绝；目标配置已加密时需提供口令），返回新配置的快照。 */
+export async function switchProfile(
+  profileId: ProfileInfo["id"],
+  passphrase: string | null = null,
+): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("switch_profile", { profileId, passphrase });
+}
+
+/** 获取启动锁状态：数据已加密且尚未解锁时返回待解锁的配置，否则为 `null`。 */
+export async function getLockState(): Promise<ProfileInfo | null> {
+  return invoke<ProfileInfo | null>("get_lock_state");
+}
+
+/** 以口令解锁加密数据并完成启动，返回应用快照（口令错误时报错）。 */
+export async function unlockStore(passphrase: string): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("unlock_store", { passphrase });
+}
+
+/** 查询当前配置是否已启用数据加密。 */
+export async function getEncryptionStatus(): Promise<boolean> {
+  return invoke<boolean>("get_encryption_status");
+}
+
+/** 启用数据加密（口令至少 8 个字符）。 */
+export async function enableEncryption(passphrase: string): Promise<void> {
+  return invoke<void>("enable_encryption", { passphrase });
+}
+
+/** 更换数据加密口令（旧口令错误时报错）。 */
+export async function changePassphrase(oldPassphrase: string, newPassphrase: string): Promise<void> {
+  return invoke<void>("change_passphrase", { oldPassphrase, newPassphrase });
+}
+
+/** 关闭数据加密（口令错误时报错）。 */
+export async function disableEncryption(passphrase: string): Promise<void> {
+  return invoke<void>("disable_encryption", { passphrase });
+}
+
 /** 更新设置（后端会进行范围校验并持久化）。 */
 export async function updateSettings(settings: Settings): Promise<AppSnapshot> {
   return invoke<AppSnapshot>("update_settings", { settings });
@@ -44,6 +147,11 @@ export async function setGoals(daily: number, weekly: number): Promise<Settings>
   return invoke<Settings>("set_goals", { daily, weekly });
 }
 
+/** 获取目标进度（每日/每周/每月/每年、标签目标与连续达标）。 */
+export async function getGoalProgress(): Promise<GoalProgress> {
+  return invoke<GoalProgress>("get_goal_progress");
+}
+
 /** 设置当前番茄的任务标签（若为新标签会自动加入标签历史）。 */
 export async function setCurrentTag(tag: string): Promise<AppSnapshot> {
   return invoke<AppSnapshot>("set_current_tag", { tag });
@@ -54,7 +162,7 @@ export async function addTag(tag: string): Promise<string[]> {
   return invoke<string[]>("add_tag", { tag });
 }
 
-/** 重命名标签（同时更新历史记录中的标签字段）。 */
+/** 重命名/移动标签（`to` 为新的完整路径，如 `工作/项目X`；历史记录同步刷新）。 */
 export async function renameTag(from: string, to: string): Promise<AppSnapshot> {
   return invoke<AppSnapshot>("rename_tag", { from, to });
 }
@@ -64,6 +172,41 @@ export async function deleteTag(tag: string): Promise<AppSnapshot> {
   return invoke<AppSnapshot>("delete_tag", { tag });
 }
 
+/** 更新标签的颜色/图标/归档状态（按 id 匹配；名称与层级请使用重命名）。 */
+export async function updateTag(tag: Tag): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("update_tag", { tag });
+}
+
+/** 获取任务列表。 */
+export async function getTasks(): Promise<Task[]> {
+  return invoke<Task[]>("get_tasks");
+}
+
+/** 保存任务（`id` 为空表示新增）。 */
+export async function saveTask(task: Task): Promise<Task> {
+  return invoke<Task>("save_task", { task });
+}
+
+/** 删除任务（若为当前任务则取消选择）。 */
+export async function deleteTask(id: string): Promise<boolean> {
+  return invoke<boolean>("delete_task", { id });
+}
+
+/** 标记任务完成/未完成。 */
+export async function setTaskDone(id: string, done: boolean): Promise<Task> {
+  return invoke<Task>("set_task_done", { id, done });
+}
+
+/** 选择当前任务（`null` 表示取消选择；任务带标签时同时切换当前标签）。 */
+export async function setCurrentTask(id: string | null): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("set_current_task", { id });
+}
+
+/** 获取任务预估准确度报告（按任务与按标签）。 */
+export async function getTaskEstimates(): Promise<TaskEstimateReport> {
+  return invoke<TaskEstimateReport>("get_task_estimates");
+}
+
 /** 设置黑名单（专注期内仅允许新增，不允许移除）。 */
 export async function setBlacklist(blacklist: BlacklistItem[]): Promise<BlacklistItem[]> {
   return invoke<BlacklistItem[]>("set_blacklist", { blacklist });
@@ -79,6 +222,95 @@ export async function setHistoryRemark(date: string, recordIndex: number, remark
   return invoke<HistoryRecord>("set_history_remark", { date, recordIndex, remark });
 }
 
+/** 手动补录一条历史记录，返回写入后的快照。 */
+export async function addHistoryRecord(input: HistoryRecordInput): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("add_history_record", { input });
+}
+
+/** 编辑指定历史记录（可修改日期、时间、时长、标签、阶段与备注）。 */
+export async function updateHistoryRecord(
+  date: string,
+  recordIndex: number,
+  input: HistoryRecordInput,
+): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("update_history_record", { date, recordIndex, input });
+}
+
+/** 删除指定历史记录。 */
+export async function deleteHistoryRecord(date: string, recordIndex: number): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("delete_history_record", { date, recordIndex });
+}
+
+/** 在专注第 `minutes` 分钟处拆分指定历史记录（之前的暂停不计入）。 */
+export async function splitHistoryRecord(date: string, recordIndex: number, minutes: number): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("split_history_record", { date, recordIndex, minutes });
+}
+
+/** 将指定历史记录与其后一条相邻记录合并。 */
+export async function mergeHistoryRecords(date: string, recordIndex: number): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("merge_history_records", { date, recordIndex });
+}
+
+/** 获取待撤销与待重做的操作描述（无可用操作时为 `null`）。 */
+export async function getUndoState(): Promise<UndoState> {
+  return invoke<UndoState>("get_undo_state");
+}
+
+/** 撤销最近一次标签/历史/模板/黑名单修改，返回执行后的快照与新的撤销状态。 */
+export async function undo(): Promise<UndoOutcome> {
+  return invoke<UndoOutcome>("undo");
+}
+
+/** 重做最近一次撤销的操作。 */
+export async function redo(): Promise<UndoOutcome> {
+  return invoke<UndoOutcome>("redo");
+}
+
+/** 查询某个主体的变更历史（如 `history:<记录 id>`、`tag:<id>`、`settings`）。 */
+export async function getChangeHistory(subject: string): Promise<AuditEntry[]> {
+  return invoke<AuditEntry[]>("get_change_history", { subject });
+}
+
+/** 按事件日志重放校验；`commit` 为 true 且不一致时以重放结果替换当前数据（替换前自动备份）。 */
+export async function rebuildFromEventLog(commit: boolean): Promise<ReplayReport> {
+  return invoke<ReplayReport>("rebuild_from_event_log", { commit });
+}
+
+/** 检查数据完整性；`repair` 为 true 时自动修复可修复的问题（修复前自动备份，可撤销）。 */
+export async function checkDataIntegrity(repair: boolean): Promise<IntegrityReport> {
+  return invoke<IntegrityReport>("check_data_integrity", { repair });
+}
+
+/** 获取文件夹同步状态（共享文件夹中的设备与待合并变更数）。 */
+export async function getSyncStatus(): Promise<SyncStatus> {
+  return invoke<SyncStatus>("get_sync_status");
+}
+
+/** 启用文件夹同步（或更换共享文件夹/设备名称）并立即同步一次。 */
+export async function enableSync(folder: string, deviceName: string): Promise<SyncStatus> {
+  return invoke<SyncStatus>("enable_sync", { folder, deviceName });
+}
+
+/** 关闭文件夹同步（共享文件夹中的日志保持不变）。 */
+export async function disableSync(): Promise<SyncStatus> {
+  return invoke<SyncStatus>("disable_sync");
+}
+
+/** 立即同步：发布本机变更并合并其他设备的日志。 */
+export async function syncNow(): Promise<SyncReport> {
+  return invoke<SyncReport>("sync_now");
+}
+
+/** 获取历史归档状态（保留月数、归档线与归档文件）。 */
+export async function getArchiveStatus(): Promise<ArchiveStatus> {
+  return invoke<ArchiveStatus>("get_archive_status");
+}
+
+/** 按保留月数立即归档旧历史（移入按年压缩的归档文件）。 */
+export async function archiveHistory(): Promise<ArchiveReport> {
+  return invoke<ArchiveReport>("archive_history");
+}
+
 /** 获取指定范围的专注时段分析数据。 */
 export async function getFocusAnalysis(range: DateRange): Promise<FocusAnalysis> {
   return invoke<FocusAnalysis>("get_focus_analysis", { range });
@@ -104,6 +336,26 @@ export async function applyTemplate(id: string): Promise<BlacklistItem[]> {
   return invoke<BlacklistItem[]>("apply_template", { id });
 }
 
+/** 获取全部会话计划（自定义阶段序列）。 */
+export async function getSessionPlans(): Promise<SessionPlan[]> {
+  return invoke<SessionPlan[]>("get_session_plans");
+}
+
+/** 保存会话计划（id 为空时新增，否则按 id 覆盖）。 */
+export async function saveSessionPlan(plan: SessionPlan): Promise<SessionPlan> {
+  return invoke<SessionPlan>("save_session_plan", { plan });
+}
+
+/** 删除会话计划（若为当前启用的计划则回退为经典循环）。 */
+export async function deleteSessionPlan(id: string): Promise<boolean> {
+  return invoke<boolean>("delete_session_plan", { id });
+}
+
+/** 启用/取消会话计划（传 null 回到经典循环），返回切换后的完整快照。 */
+export async function setActiveSessionPlan(id: string | null): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("set_active_session_plan", { id });
+}
+
 /** 设置主窗口置顶状态。 */
 export async function setAlwaysOnTop(enabled: boolean): Promise<boolean> {
   return invoke<boolean>("set_always_on_top", { enabled });
@@ -119,6 +371,16 @@ export async function exportHistory(request: ExportRequest): Promise<string> {
   return invoke<string>("export_history", { request });
 }
 
+/** 导入历史记录（`commit=false` 时仅预览，不写入数据）。 */
+export async function importHistory(request: ImportRequest): Promise<ImportReport> {
+  return invoke<ImportReport>("import_history", { request });
+}
+
+/** 从外部时间记录工具导入（Toggl 或通用 CSV 列映射；`commit=false` 时仅预览）。 */
+export async function importExternalHistory(request: ExternalImportRequest): Promise<ImportReport> {
+  return invoke<ImportReport>("import_external_history", { request });
+}
+
 /** 打开日志目录（文件管理器）。 */
 export async function openLogDir(): Promise<boolean> {
   return invoke<boolean>("open_log_dir");
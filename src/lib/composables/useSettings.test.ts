@@ -32,6 +32,17 @@ function makeSettings(alwaysOnTop: boolean): Settings {
     audio: { enabled: true, currentAudioId: "", volume: 60, autoPlay: true },
     animation: { enabled: true, comboEnabled: true, intensity: "standard" },
     interruption: { enabled: true, confirmOnInterrupt: true },
+    suspendExpiryPolicy: "paused",
+    dayRolloverHour: 0,
+    firstWeekday: "monday",
+    monthlyGoal: 0,
+    yearlyGoal: 0,
+    tagGoals: [],
+    streak: { restDaysPerWeek: 0, reminderEnabled: true, reminderHour: 21 },
+    tagOverrides: [],
+    rollUpTags: false,
+    backup: { enabled: true, intervalHours: 24, keepCount: 10, encrypt: false },
+    archiveAfterMonths: 0,
   };
 }
 
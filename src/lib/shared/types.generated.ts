@@ -54,6 +54,50 @@ export type Settings = {
    * 中断设置（PRD v4）。
    */
   interruption: InterruptionSettings;
+  /**
+   * 系统挂起期间阶段到期时的处理策略。
+   */
+  suspendExpiryPolicy: SuspendExpiryPolicy;
+  /**
+   * 逻辑日界线（0-23 点）：该时刻之前完成的番茄仍计入前一天（0 表示按自然日）。
+   */
+  dayRolloverHour: number;
+  /**
+   * 每周首日（影响本周统计、周目标与星期分布）。
+   */
+  firstWeekday: FirstWeekday;
+  /**
+   * 每月目标番茄数量（0 表示不设目标）。
+   */
+  monthlyGoal: number;
+  /**
+   * 每年目标番茄数量（0 表示不设目标）。
+   */
+  yearlyGoal: number;
+  /**
+   * 按标签设置的目标。
+   */
+  tagGoals: Array<TagGoal>;
+  /**
+   * 连续达标（每日目标）设置。
+   */
+  streak: StreakSettings;
+  /**
+   * 按标签覆盖的时长/自动继续/黑名单模板设置（切换到该标签时生效）。
+   */
+  tagOverrides: Array<TagOverride>;
+  /**
+   * 统计与分析时是否将子标签汇总到顶层标签（如 `工作/项目X` 计入 `工作`）。
+   */
+  rollUpTags: boolean;
+  /**
+   * 自动备份设置。
+   */
+  backup: BackupSettings;
+  /**
+   * 历史保留月数：早于该月数的历史移入按年压缩的归档文件（0 表示不归档，最多 `MAX_ARCHIVE_AFTER_MONTHS`）。
+   */
+  archiveAfterMonths: number;
 };
 export type AudioSettings = {
   /**
@@ -90,298 +134,1136 @@ export type AnimationSettings = {
 };
 export type InterruptionSettings = {
   /**
-   * 是否记录中断。
+   * 是否记录中断。
+   */
+  enabled: boolean;
+  /**
+   * 中断时是否弹窗确认。
+   */
+  confirmOnInterrupt: boolean;
+};
+export type SuspendExpiryPolicy = "completed" | "interrupted" | "paused";
+export type FirstWeekday = "monday" | "tuesday" | "wednesday" | "thursday" | "friday" | "saturday" | "sunday";
+export type GoalPeriod = "daily" | "weekly" | "monthly" | "yearly";
+export type Tag = {
+  /**
+   * 稳定 id（历史/中断记录通过它引用标签，重命名不会改变）。
+   */
+  id: string;
+  /**
+   * 标签名（不含父级路径）。
+   */
+  name: string;
+  /**
+   * 颜色（如 `#f97316`；为空表示使用默认配色）。
+   */
+  color: string;
+  /**
+   * 图标（emoji；为空表示不显示）。
+   */
+  icon: string;
+  /**
+   * 是否已归档（归档后不出现在标签选择器中，历史记录保留）。
+   */
+  archived: boolean;
+  /**
+   * 父标签 id（为空表示顶层标签）。
+   */
+  parentId: string | null;
+};
+export type TagGoal = {
+  /**
+   * 标签名（保存时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+   */
+  tag: string;
+  /**
+   * 标签 id（保存设置时按路径绑定；旧数据由 `migrate_v6` 回填）。
+   */
+  tagId: string | null;
+  /**
+   * 统计周期。
+   */
+  period: GoalPeriod;
+  /**
+   * 目标番茄数量。
+   */
+  target: number;
+};
+export type TagOverride = {
+  /**
+   * 标签名（保存时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+   */
+  tag: string;
+  /**
+   * 标签 id（保存设置时按路径绑定；旧数据由 `migrate_v6` 回填）。
+   */
+  tagId: string | null;
+  /**
+   * 工作时长（分钟）。
+   */
+  pomodoro: number | null;
+  /**
+   * 短休息时长（分钟）。
+   */
+  shortBreak: number | null;
+  /**
+   * 长休息时长（分钟）。
+   */
+  longBreak: number | null;
+  /**
+   * 是否启用连续番茄模式。
+   */
+  autoContinueEnabled: boolean | null;
+  /**
+   * 连续番茄数量。
+   */
+  autoContinuePomodoros: number | null;
+  /**
+   * 切换到该标签时启用的黑名单模板 id 集合。
+   */
+  blacklistTemplateIds: Array<string> | null;
+};
+export type Task = {
+  /**
+   * 任务 id（保存时若为空则自动生成）。
+   */
+  id: string;
+  /**
+   * 任务标题。
+   */
+  title: string;
+  /**
+   * 所属标签（保存时的完整路径，读取时按 `tag_id` 解析为当前路径；为空表示沿用当前标签）。
+   */
+  tag: string;
+  /**
+   * 所属标签 id（保存任务时按路径绑定；旧数据由 `migrate_v6` 回填）。
+   */
+  tagId: string | null;
+  /**
+   * 预估番茄数（0 表示未预估）。
+   */
+  estimatedPomodoros: number;
+  /**
+   * 截止日期（YYYY-MM-DD）。
+   */
+  dueDate: string | null;
+  /**
+   * 是否已完成。
+   */
+  done: boolean;
+};
+export type TaskEstimate = {
+  /**
+   * 任务 id。
+   */
+  taskId: string;
+  /**
+   * 任务标题。
+   */
+  title: string;
+  /**
+   * 所属标签。
+   */
+  tag: string;
+  /**
+   * 预估番茄数。
+   */
+  estimated: number;
+  /**
+   * 实际完成的番茄数（关联到该任务的工作记录数）。
+   */
+  actual: number;
+  /**
+   * 是否已完成。
+   */
+  done: boolean;
+  /**
+   * 实际/预估比值（>1 表示低估；未预估时为空）。
+   */
+  ratio: number | null;
+};
+export type TagEstimate = {
+  /**
+   * 标签名。
+   */
+  tag: string;
+  /**
+   * 参与统计的任务数（已完成且有预估）。
+   */
+  taskCount: number;
+  /**
+   * 预估番茄数合计。
+   */
+  estimated: number;
+  /**
+   * 实际番茄数合计。
+   */
+  actual: number;
+  /**
+   * 实际/预估比值。
+   */
+  ratio: number;
+};
+export type TaskEstimateReport = {
+  /**
+   * 各任务的预估对比（顺序同任务列表）。
+   */
+  tasks: Array<TaskEstimate>;
+  /**
+   * 按标签汇总（仅统计已完成且有预估的任务，按标签名排序）。
+   */
+  tags: Array<TagEstimate>;
+};
+export type StreakSettings = {
+  /**
+   * 每周允许的休息日数量（0-6）：未达标但不中断连续记录的天数。
+   */
+  restDaysPerWeek: number;
+  /**
+   * 是否在当天较晚时提醒“连续记录即将中断”。
+   */
+  reminderEnabled: boolean;
+  /**
+   * 提醒时刻（0-23 点，按逻辑日计算）。
+   */
+  reminderHour: number;
+};
+export type BackupSettings = {
+  /**
+   * 是否启用定时备份。
+   */
+  enabled: boolean;
+  /**
+   * 定时备份间隔（小时，1-168）。
+   */
+  intervalHours: number;
+  /**
+   * 每种备份保留的份数（1-100，超出后删除最旧的）。
+   */
+  keepCount: number;
+  /**
+   * 是否加密备份（仅在启用数据加密后生效）。
+   */
+  encrypt: boolean;
+};
+export type BlacklistItem = {
+  /**
+   * 进程名（例如 `WeChat.exe`）。
+   */
+  name: string;
+  /**
+   * 展示名（例如 `微信`）。
+   */
+  displayName: string;
+};
+export type BlacklistTemplate = {
+  /**
+   * 模板 id（内置模板为固定值，自定义模板可为 uuid/自定义字符串）。
+   */
+  id: string;
+  /**
+   * 模板名称。
+   */
+  name: string;
+  /**
+   * 是否为内置模板（内置模板不可删除）。
+   */
+  builtin: boolean;
+  /**
+   * 模板包含的黑名单进程集合。
+   */
+  processes: Array<BlacklistItem>;
+};
+export type PauseSegment = {
+  /**
+   * 暂停开始时间（HH:mm）。
+   */
+  startTime: string;
+  /**
+   * 暂停结束时间（HH:mm）。
+   */
+  endTime: string;
+  /**
+   * 暂停时长（秒）。
+   */
+  seconds: bigint;
+};
+export type HistoryRecord = {
+  /**
+   * 记录 id（创建时生成，编辑、移动日期后保持不变；旧数据由 `migrate_v7` 补齐）。
+   */
+  id: string;
+  /**
+   * 任务标签（写入时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+   */
+  tag: string;
+  /**
+   * 任务标签 id（为空表示无标签；旧数据由 `migrate_v6` 回填）。
+   */
+  tagId: string | null;
+  /**
+   * 关联的任务 id（仅工作记录；为空表示未关联任务）。
+   */
+  taskId: string | null;
+  /**
+   * 开始时间（HH:mm）。
+   */
+  startTime: string;
+  /**
+   * 结束时间（HH:mm；旧数据可能缺失，前端可按 `start_time + duration` 推导展示）。
+   */
+  endTime: string | null;
+  /**
+   * 开始时刻（RFC 3339，带 UTC 偏移；旧数据由 `migrate_v5` 回填）。
+   */
+  startedAt: string | null;
+  /**
+   * 结束时刻（RFC 3339，带 UTC 偏移；跨午夜/时区变化/夏令时下不再有歧义）。
+   */
+  endedAt: string | null;
+  /**
+   * 本次番茄时长（分钟）。
+   */
+  duration: number;
+  /**
+   * 阶段类型（用于导出/分析；休息阶段不计入番茄统计）。
+   */
+  phase: Phase;
+  /**
+   * 备注（完成后可填写，也可在历史中编辑）。
+   */
+  remark: string;
+  /**
+   * 实际计时秒数（不含暂停；旧数据缺失时按 `duration` 推算）。
+   */
+  elapsedSeconds: bigint | null;
+  /**
+   * 阶段内的暂停片段（按发生顺序）。
+   */
+  pauses: Array<PauseSegment>;
+  /**
+   * 是否被提前跳过（仅休息阶段会写入跳过记录）。
+   */
+  skipped: boolean;
+};
+export type HistoryDay = {
+  /**
+   * 日期（YYYY-MM-DD）。
+   */
+  date: string;
+  /**
+   * 当日记录。
+   */
+  records: Array<HistoryRecord>;
+};
+export type HistoryRecordInput = {
+  /**
+   * 日期（YYYY-MM-DD）。
+   */
+  date: string;
+  /**
+   * 开始时间（HH:mm）。
+   */
+  startTime: string;
+  /**
+   * 时长（分钟，1-1440）。
+   */
+  duration: number;
+  /**
+   * 标签路径（为空表示未分类；缺失的标签会自动创建）。
+   */
+  tag: string;
+  /**
+   * 阶段类型。
+   */
+  phase: Phase;
+  /**
+   * 备注。
+   */
+  remark: string;
+};
+export type CustomAudio = {
+  /**
+   * 音频 id（uuid）。
+   */
+  id: string;
+  /**
+   * 显示名称。
+   */
+  name: string;
+  /**
+   * 文件名（存储在 audio 目录）。
+   */
+  fileName: string;
+  /**
+   * 是否内置。
+   */
+  builtin: boolean;
+};
+export type InterruptionType = "reset" | "skip" | "quit" | "suspend";
+export type InterruptionRecord = {
+  /**
+   * 中断时间（ISO 8601）。
+   */
+  timestamp: string;
+  /**
+   * 中断时剩余秒数。
+   */
+  remainingSeconds: bigint;
+  /**
+   * 已专注秒数。
+   */
+  focusedSeconds: bigint;
+  /**
+   * 中断原因（用户填写，可为空）。
+   */
+  reason: string;
+  /**
+   * 中断类型。
+   */
+  type: InterruptionType;
+  /**
+   * 当时的任务标签（写入时的完整路径；读取时按 `tag_id` 解析为当前路径）。
+   */
+  tag: string;
+  /**
+   * 当时的任务标签 id（旧数据由 `migrate_v6` 回填）。
+   */
+  tagId: string | null;
+  /**
+   * 本次工作阶段的开始时刻（RFC 3339，带 UTC 偏移；旧数据由 `migrate_v5` 按已专注秒数回推）。
+   */
+  startedAt: string | null;
+  /**
+   * 中断发生时刻（RFC 3339，带本地 UTC 偏移）。
+   */
+  endedAt: string | null;
+};
+export type InterruptionDay = {
+  /**
+   * 日期（YYYY-MM-DD）。
+   */
+  date: string;
+  /**
+   * 当日中断记录集合。
+   */
+  records: Array<InterruptionRecord>;
+};
+export type PlanStep = {
+  /**
+   * 步骤名称（例如“热身”“深度工作”）。
+   */
+  name: string;
+  /**
+   * 步骤类型（工作/短休息/长休息）。
+   */
+  kind: Phase;
+  /**
+   * 步骤时长（分钟）。
+   */
+  minutes: number;
+};
+export type SessionPlan = {
+  /**
+   * 计划 id。
+   */
+  id: string;
+  /**
+   * 计划名称。
+   */
+  name: string;
+  /**
+   * 有序步骤列表。
+   */
+  steps: Array<PlanStep>;
+};
+export type ArchivedTagTotal = {
+  /**
+   * 标签完整路径（归档时的路径）。
+   */
+  tag: string;
+  /**
+   * 标签 id（读取时据此解析当前路径）。
+   */
+  tagId: string | null;
+  /**
+   * 完成的番茄数量。
+   */
+  count: number;
+  /**
+   * 专注分钟数。
+   */
+  minutes: number;
+};
+export type ArchivedDay = {
+  /**
+   * 逻辑日期（YYYY-MM-DD）。
+   */
+  date: string;
+  /**
+   * 按标签汇总的工作记录。
+   */
+  byTag: Array<ArchivedTagTotal>;
+};
+export type HistoryArchive = {
+  /**
+   * 归档线（YYYY-MM-DD）：自然日期早于该日的历史已归档（从未归档时为 `null`）。
+   */
+  before: string | null;
+  /**
+   * 已归档历史的逐日汇总（按逻辑日期升序）。
+   */
+  days: Array<ArchivedDay>;
+};
+export type AppData = {
+  /**
+   * 数据结构版本（缺失时为 0，表示引入版本号之前写入的数据；见 `migrations`）。
+   */
+  schemaVersion: number;
+  /**
+   * 用户设置。
+   */
+  settings: Settings;
+  /**
+   * 进程黑名单。
+   */
+  blacklist: Array<BlacklistItem>;
+  /**
+   * 黑名单模板列表（包含内置模板与自定义模板）。
+   */
+  blacklistTemplates: Array<BlacklistTemplate>;
+  /**
+   * 当前启用的模板 id 列表（支持同时启用多套模板）。
+   */
+  activeTemplateIds: Array<string>;
+  /**
+   * 兼容字段：旧/示例数据中的单一激活模板（用于自动迁移到 `active_template_ids`）。
+   */
+  activeTemplateId: string | null;
+  /**
+   * 兼容字段：未归档标签的完整路径列表（由 `tag_defs` 同步，供标签选择器使用）。
+   */
+  tags: Array<string>;
+  /**
+   * 标签定义（含 id/颜色/图标/归档/层级）。
+   */
+  tagDefs: Array<Tag>;
+  /**
+   * 历史记录（按日分组）。
+   */
+  history: Array<HistoryDay>;
+  /**
+   * 调试历史记录（仅开发环境使用，与正式数据隔离）。
+   */
+  historyDev: Array<HistoryDay>;
+  /**
+   * 自定义音频列表。
+   */
+  customAudios: Array<CustomAudio>;
+  /**
+   * 中断记录（按日分组）。
+   */
+  interruptions: Array<InterruptionDay>;
+  /**
+   * 当前 Combo 数（运行时状态，可选持久化）。
+   */
+  currentCombo: number;
+  /**
+   * 累计完成番茄总数（用于里程碑）。
+   */
+  totalPomodoros: bigint;
+  /**
+   * 自定义会话计划列表。
+   */
+  sessionPlans: Array<SessionPlan>;
+  /**
+   * 当前启用的会话计划 id（为空时使用经典循环）。
+   */
+  activePlanId: string | null;
+  /**
+   * 任务列表。
+   */
+  tasks: Array<Task>;
+  /**
+   * 历史归档概况（已归档历史的汇总，见 `archive`）。
+   */
+  historyArchive: HistoryArchive;
+};
+export type TagCount = {
+  /**
+   * 标签名。
+   */
+  tag: string;
+  /**
+   * 完成次数。
+   */
+  count: number;
+};
+export type TodayStats = {
+  /**
+   * 今日完成的番茄总数。
+   */
+  total: number;
+  /**
+   * 按标签统计。
+   */
+  byTag: Array<TagCount>;
+};
+export type WeekStats = {
+  /**
+   * 本周完成的番茄总数。
+   */
+  total: number;
+  /**
+   * 按标签统计。
+   */
+  byTag: Array<TagCount>;
+};
+export type TagGoalProgress = {
+  /**
+   * 标签名。
+   */
+  tag: string;
+  /**
+   * 统计周期。
+   */
+  period: GoalPeriod;
+  /**
+   * 目标番茄数量。
+   */
+  target: number;
+  /**
+   * 当前周期内已完成。
+   */
+  completed: number;
+};
+export type StreakProgress = {
+  /**
+   * 当前连续达标天数（今天未达标时不计入，也不中断）。
+   */
+  currentDays: number;
+  /**
+   * 历史最长连续达标天数。
+   */
+  longestDays: number;
+  /**
+   * 今天是否已达标。
+   */
+  todayMet: boolean;
+  /**
+   * 本周剩余可用休息日数量。
+   */
+  restDaysLeft: number;
+};
+export type GoalProgress = {
+  /**
+   * 每日目标（0 表示未设置）。
+   */
+  dailyGoal: number;
+  /**
+   * 今日已完成。
+   */
+  dailyCompleted: number;
+  /**
+   * 每周目标（0 表示未设置）。
+   */
+  weeklyGoal: number;
+  /**
+   * 本周已完成。
+   */
+  weeklyCompleted: number;
+  /**
+   * 每月目标（0 表示未设置）。
+   */
+  monthlyGoal: number;
+  /**
+   * 本月已完成。
+   */
+  monthlyCompleted: number;
+  /**
+   * 每年目标（0 表示未设置）。
+   */
+  yearlyGoal: number;
+  /**
+   * 今年已完成。
+   */
+  yearlyCompleted: number;
+  /**
+   * 标签目标进度（顺序与设置一致）。
+   */
+  tagGoals: Array<TagGoalProgress>;
+  /**
+   * 连续达标（每日目标）。
+   */
+  streak: StreakProgress;
+};
+export type TimerSnapshot = {
+  /**
+   * 当前阶段。
+   */
+  phase: Phase;
+  /**
+   * 剩余秒数。
+   */
+  remainingSeconds: bigint;
+  /**
+   * 是否运行中。
+   */
+  isRunning: boolean;
+  /**
+   * 当前任务标签。
+   */
+  currentTag: string;
+  /**
+   * 当前任务 id（未选择任务时为空）。
+   */
+  currentTaskId: string | null;
+  /**
+   * 专注期内黑名单是否锁定（只能增不能减）。
+   */
+  blacklistLocked: boolean;
+  /**
+   * 当前设置（已合并当前标签的覆盖项，用于前端展示/校验）。
+   */
+  settings: Settings;
+  /**
+   * 今日统计（用于主界面展示）。
+   */
+  todayStats: TodayStats;
+  /**
+   * 本周统计（用于主界面展示）。
+   */
+  weekStats: WeekStats;
+  /**
+   * 目标进度（用于主界面展示与提醒判断）。
+   */
+  goalProgress: GoalProgress;
+};
+export type WorkCompletedEvent = {
+  /**
+   * 记录日期（YYYY-MM-DD）。
+   */
+  date: string;
+  /**
+   * 当日记录索引（从 0 开始）。
+   */
+  recordIndex: number;
+  /**
+   * 写入的记录内容。
+   */
+  record: HistoryRecord;
+};
+export type AppSnapshot = {
+  /**
+   * 持久化数据（settings/blacklist/tags/history）。
+   */
+  data: AppData;
+  /**
+   * 计时器状态快照。
+   */
+  timer: TimerSnapshot;
+  /**
+   * 当前配置。
+   */
+  profile: ProfileInfo;
+};
+export type StorePaths = {
+  /**
+   * 数据根目录路径（统一入口，可用于打开文件夹）。
+   */
+  storeDirPath: string;
+};
+export type DataRootMode = "default" | "custom" | "portable";
+export type DataRootInfo = {
+  /**
+   * 当前模式。
+   */
+  mode: DataRootMode;
+  /**
+   * 当前根目录。
+   */
+  root: string;
+  /**
+   * 默认模式的根目录。
+   */
+  defaultRoot: string;
+  /**
+   * 便携模式的根目录（无法定位可执行文件时为 `null`）。
+   */
+  portableRoot: string | null;
+  /**
+   * 已登记、将在下次启动时迁移到的目录。
+   */
+  pendingTarget: string | null;
+  /**
+   * 本次启动执行迁移失败的原因（数据仍位于原目录）。
+   */
+  lastError: string | null;
+};
+export type RelocateRequest = {
+  /**
+   * 目标模式。
+   */
+  mode: DataRootMode;
+  /**
+   * 自定义目录（仅 `custom` 模式需要，绝对路径）。
+   */
+  path: string | null;
+};
+export type BackupKind = "scheduled" | "beforeOperation";
+export type MigrationStepReport = {
+  /**
+   * 迁移后的版本号。
+   */
+  version: number;
+  /**
+   * 迁移说明。
+   */
+  description: string;
+  /**
+   * 是否修改了数据。
+   */
+  changed: boolean;
+};
+export type MigrationReport = {
+  /**
+   * 迁移前的版本号（0 表示引入版本号之前写入的数据）。
+   */
+  fromVersion: number;
+  /**
+   * 迁移后的版本号。
+   */
+  toVersion: number;
+  /**
+   * 执行（或将要执行）的步骤。
+   */
+  steps: Array<MigrationStepReport>;
+};
+export type BackupInfo = {
+  /**
+   * 文件名（恢复/预览时作为标识）。
    */
-  enabled: boolean;
+  fileName: string;
   /**
-   * 中断时是否弹窗确认。
+   * 创建时刻（RFC 3339，本地时区）。
    */
-  confirmOnInterrupt: boolean;
-};
-export type BlacklistItem = {
+  createdAt: string;
   /**
-   * 进程名（例如 `WeChat.exe`）。
+   * 备份类型。
    */
-  name: string;
+  kind: BackupKind;
   /**
-   * 展示名（例如 `微信`）。
+   * 触发备份的操作（仅操作前备份，如 `delete-tag`）。
    */
-  displayName: string;
+  reason: string;
+  /**
+   * 文件大小（字节）。
+   */
+  sizeBytes: number;
 };
-export type BlacklistTemplate = {
+export type BackupPreview = {
   /**
-   * 模板 id（内置模板为固定值，自定义模板可为 uuid/自定义字符串）。
+   * 备份条目信息。
    */
-  id: string;
+  info: BackupInfo;
   /**
-   * 模板名称。
+   * 累计完成番茄数。
    */
-  name: string;
+  totalPomodoros: number;
   /**
-   * 是否为内置模板（内置模板不可删除）。
+   * 标签数量（不含归档标签）。
    */
-  builtin: boolean;
+  tagCount: number;
   /**
-   * 模板包含的黑名单进程集合。
+   * 任务数量。
    */
-  processes: Array<BlacklistItem>;
-};
-export type HistoryRecord = {
+  taskCount: number;
   /**
-   * 任务标签。
+   * 有历史记录的天数。
    */
-  tag: string;
+  historyDays: number;
   /**
-   * 开始时间（HH:mm）。
+   * 历史记录总数。
    */
-  startTime: string;
+  historyRecords: number;
   /**
-   * 结束时间（HH:mm；旧数据可能缺失，前端可按 `start_time + duration` 推导展示）。
+   * 中断记录总数。
    */
-  endTime: string | null;
+  interruptionRecords: number;
   /**
-   * 本次番茄时长（分钟）。
+   * 最早的历史日期（无历史时为空）。
    */
-  duration: number;
+  firstDate: string | null;
   /**
-   * 阶段类型（用于导出/分析；当前仅记录工作阶段）。
+   * 最晚的历史日期（无历史时为空）。
    */
-  phase: Phase;
+  lastDate: string | null;
   /**
-   * 备注（完成后可填写，也可在历史中编辑）。
+   * 恢复时将执行的数据迁移（预演结果）。
    */
-  remark: string;
+  migration: MigrationReport;
 };
-export type HistoryDay = {
+export type StoreRecoveryReport = {
   /**
-   * 日期（YYYY-MM-DD）。
+   * 被隔离的原始 store 副本路径。
    */
-  date: string;
+  quarantinedPath: string;
   /**
-   * 当日记录。
+   * 整个文件无法解析（此时仅历史数据库中的记录得以保留）。
    */
-  records: Array<HistoryRecord>;
+  fileUnreadable: boolean;
+  /**
+   * 被丢弃的数据段路径（如 `settings.pomodoro`、`history[3]`）。
+   */
+  dropped: Array<string>;
 };
-export type CustomAudio = {
+export type ProfileInfo = {
   /**
-   * 音频 id（uuid）。
+   * 配置 id（默认配置为 `default`，其余为 uuid）。
    */
   id: string;
   /**
-   * 显示名称。
+   * 展示名称。
    */
   name: string;
   /**
-   * 文件名（存储在 audio 目录）。
+   * 创建时间（RFC 3339；默认配置为空）。
    */
-  fileName: string;
+  createdAt: string;
+};
+export type ProfileRegistry = {
   /**
-   * 是否内置。
+   * 当前配置 id。
    */
-  builtin: boolean;
-};
-export type InterruptionType = "reset" | "skip" | "quit";
-export type InterruptionRecord = {
+  active: string;
   /**
-   * 中断时间（ISO 8601）。
+   * 全部配置（默认配置总在首位）。
    */
-  timestamp: string;
+  profiles: Array<ProfileInfo>;
+};
+export type AuditEntry = {
   /**
-   * 中断时剩余秒数。
+   * 事件序号（日志内单调递增）。
    */
-  remainingSeconds: bigint;
+  seq: number;
   /**
-   * 已专注秒数。
+   * 记录时刻（RFC 3339）。
    */
-  focusedSeconds: bigint;
+  at: string;
   /**
-   * 中断原因（用户填写，可为空）。
+   * 事件类型（与 `DomainEvent` 的 `type` 一致）。
    */
-  reason: string;
+  kind: string;
   /**
-   * 中断类型。
+   * 事件主体（如 `history:<记录 id>`、`tag:<id>`、`settings`）。
    */
-  type: InterruptionType;
+  subject: string;
   /**
-   * 当时的任务标签。
+   * 中文摘要。
    */
-  tag: string;
+  summary: string;
 };
-export type InterruptionDay = {
+export type ReplayReport = {
   /**
-   * 日期（YYYY-MM-DD）。
+   * 重放的事件数量（从最近一次基线起，含基线）。
    */
-  date: string;
+  events: number;
   /**
-   * 当日中断记录集合。
+   * 重放结果是否与当前数据一致。
    */
-  records: Array<InterruptionRecord>;
+  matches: boolean;
+  /**
+   * 不一致的字段（`AppData` 的 camelCase 字段名）。
+   */
+  differingSections: Array<string>;
+  /**
+   * 是否已用重放结果替换当前数据。
+   */
+  committed: boolean;
 };
-export type AppData = {
+export type IntegrityIssueKind = "invalidDate" | "invalidTime" | "durationMismatch" | "duplicateDay" | "unordered" | "unknownTag" | "tagListOutOfSync" | "missingTemplate";
+export type IntegrityIssue = {
   /**
-   * 用户设置。
+   * 问题类型。
    */
-  settings: Settings;
+  kind: IntegrityIssueKind;
   /**
-   * 进程黑名单。
+   * 问题位置（如 `history/2025-01-01#3`）。
    */
-  blacklist: Array<BlacklistItem>;
+  location: string;
   /**
-   * 黑名单模板列表（包含内置模板与自定义模板）。
+   * 问题说明。
    */
-  blacklistTemplates: Array<BlacklistTemplate>;
+  message: string;
   /**
-   * 当前启用的模板 id 列表（支持同时启用多套模板）。
+   * 是否可自动修复。
    */
-  activeTemplateIds: Array<string>;
+  repairable: boolean;
+};
+export type IntegrityReport = {
   /**
-   * 兼容字段：旧/示例数据中的单一激活模板（用于自动迁移到 `active_template_ids`）。
+   * 检查（或修复前）发现的问题。
    */
-  activeTemplateId: string | null;
+  issues: Array<IntegrityIssue>;
   /**
-   * 历史标签。
+   * 是否已执行修复（不可修复的问题保持原样）。
    */
-  tags: Array<string>;
+  repaired: boolean;
+};
+export type SyncDeviceStatus = {
   /**
-   * 历史记录（按日分组）。
+   * 设备 id。
    */
-  history: Array<HistoryDay>;
+  deviceId: string;
   /**
-   * 调试历史记录（仅开发环境使用，与正式数据隔离）。
+   * 设备名称。
    */
-  historyDev: Array<HistoryDay>;
+  deviceName: string;
   /**
-   * 自定义音频列表。
+   * 是否为本机。
    */
-  customAudios: Array<CustomAudio>;
+  isLocal: boolean;
   /**
-   * 中断记录（按日分组）。
+   * 日志中的操作数。
    */
-  interruptions: Array<InterruptionDay>;
+  entries: number;
   /**
-   * 当前 Combo 数（运行时状态，可选持久化）。
+   * 尚未合并到本机的操作数。
    */
-  currentCombo: number;
+  pending: number;
   /**
-   * 累计完成番茄总数（用于里程碑）。
+   * 最近一次写入的逻辑时刻（RFC 3339；日志为空时为 `null`）。
    */
-  totalPomodoros: bigint;
+  lastChangeAt: string | null;
 };
-export type TagCount = {
+export type SyncStatus = {
   /**
-   * 标签名。
+   * 是否已启用同步。
    */
-  tag: string;
+  enabled: boolean;
   /**
-   * 完成次数。
+   * 共享文件夹。
    */
-  count: number;
-};
-export type TodayStats = {
+  folder: string | null;
   /**
-   * 今日完成的番茄总数。
+   * 本机设备名称。
    */
-  total: number;
+  deviceName: string | null;
   /**
-   * 按标签统计。
+   * 上次同步完成时刻（RFC 3339）。
    */
-  byTag: Array<TagCount>;
+  lastSyncAt: string | null;
+  /**
+   * 共享文件夹中的设备（本机在前）。
+   */
+  devices: Array<SyncDeviceStatus>;
+  /**
+   * 无法读取的日志（文件名与原因）。
+   */
+  skipped: Array<string>;
 };
-export type WeekStats = {
+export type SyncConflict = {
   /**
-   * 本周完成的番茄总数。
+   * 冲突主体（记录 id，如 `history:2025-01-01T01:00:00Z/work`；或字段名，如 `settings`）。
    */
-  total: number;
+  subject: string;
   /**
-   * 按标签统计。
+   * 参与冲突的设备名称。
    */
-  byTag: Array<TagCount>;
+  devices: Array<string>;
+  /**
+   * 胜出（最后写入）的设备名称。
+   */
+  winner: string;
 };
-export type GoalProgress = {
+export type SyncReport = {
   /**
-   * 每日目标（0 表示未设置）。
+   * 本机写入日志的操作数。
    */
-  dailyGoal: number;
+  published: number;
   /**
-   * 今日已完成。
+   * 合并的其他设备操作数。
    */
-  dailyCompleted: number;
+  received: number;
   /**
-   * 每周目标（0 表示未设置）。
+   * 并发修改冲突（已按最后写入者胜出解决）。
    */
-  weeklyGoal: number;
+  conflicts: Array<SyncConflict>;
   /**
-   * 本周已完成。
+   * 本次跳过的日志（文件名与原因，如无法解析或版本过新）。
    */
-  weeklyCompleted: number;
+  skipped: Array<string>;
 };
-export type TimerSnapshot = {
+export type ArchiveReport = {
   /**
-   * 当前阶段。
+   * 归档后的归档线（YYYY-MM-DD）。
    */
-  phase: Phase;
+  before: string | null;
   /**
-   * 剩余秒数。
+   * 本次移入归档文件的天数。
    */
-  remainingSeconds: bigint;
+  archivedDays: number;
   /**
-   * 是否运行中。
+   * 本次移入归档文件的记录数。
    */
-  isRunning: boolean;
+  archivedRecords: number;
+};
+export type ArchiveFileInfo = {
   /**
-   * 当前任务标签。
+   * 年份（YYYY）。
    */
-  currentTag: string;
+  year: string;
   /**
-   * 专注期内黑名单是否锁定（只能增不能减）。
+   * 文件大小（字节）。
    */
-  blacklistLocked: boolean;
+  sizeBytes: number;
+};
+export type ArchiveStatus = {
   /**
-   * 当前设置（用于前端展示/校验）。
+   * 历史保留月数（0 表示不归档）。
    */
-  settings: Settings;
+  afterMonths: number;
   /**
-   * 今日统计（用于主界面展示）。
+   * 当前归档线（从未归档时为 `null`）。
    */
-  todayStats: TodayStats;
+  before: string | null;
   /**
-   * 本周统计（用于主界面展示）。
+   * 已归档的天数（按汇总）。
    */
-  weekStats: WeekStats;
+  archivedDays: number;
   /**
-   * 目标进度（用于主界面展示与提醒判断）。
+   * 已归档的番茄数（按汇总）。
    */
-  goalProgress: GoalProgress;
-};
-export type WorkCompletedEvent = {
+  archivedPomodoros: number;
   /**
-   * 记录日期（YYYY-MM-DD）。
+   * 归档文件（按年份升序）。
    */
-  date: string;
+  files: Array<ArchiveFileInfo>;
+};
+export type UndoState = {
   /**
-   * 当日记录索引（从 0 开始）。
+   * 下一次撤销将恢复的操作。
    */
-  recordIndex: number;
+  undo: string | null;
   /**
-   * 写入的记录内容。
+   * 下一次重做将重新执行的操作。
    */
-  record: HistoryRecord;
+  redo: string | null;
 };
-export type AppSnapshot = {
+export type UndoOutcome = {
   /**
-   * 持久化数据（settings/blacklist/tags/history）。
+   * 本次撤销/重做的操作描述。
    */
-  data: AppData;
+  applied: string;
   /**
-   * 计时器状态快照。
+   * 执行后的完整快照。
    */
-  timer: TimerSnapshot;
-};
-export type StorePaths = {
+  snapshot: AppSnapshot;
   /**
-   * 数据根目录路径（统一入口，可用于打开文件夹）。
+   * 执行后的撤销/重做状态。
    */
-  storeDirPath: string;
+  pending: UndoState;
 };
 export type ProcessInfo = {
   /**
@@ -467,11 +1349,11 @@ export type FocusAnalysis = {
    */
   periodCounts: Array<number>;
   /**
-   * 星期分布：`[周一..周日]`。
+   * 星期分布：从设置的每周首日起的 7 天（默认 `[周一..周日]`）。
    */
   weekdayCounts: Array<number>;
   /**
-   * 交叉热力：`weekday_hour_counts[weekday][hour]`（7x24）。
+   * 交叉热力：`weekday_hour_counts[weekday][hour]`（7x24，星期索引同 `weekday_counts`）。
    */
   weekdayHourCounts: Array<Array<number>>;
   /**
@@ -482,6 +1364,30 @@ export type FocusAnalysis = {
    * 文字总结（示例：「你在上午 9-11 点专注效率最高」）。
    */
   summary: string;
+  /**
+   * 实际专注秒数（工作阶段计时，不含暂停）。
+   */
+  focusSeconds: bigint;
+  /**
+   * 工作阶段的墙钟跨度秒数（实际专注 + 暂停）。
+   */
+  focusSpanSeconds: bigint;
+  /**
+   * 实际休息秒数（含被跳过休息的已休时长）。
+   */
+  breakSeconds: bigint;
+  /**
+   * 休息占比：`休息 / (专注 + 休息)`（无数据时为 0）。
+   */
+  restRatio: number;
+  /**
+   * 开始过的休息次数（含跳过）。
+   */
+  breakCount: number;
+  /**
+   * 被提前跳过的休息次数。
+   */
+  skippedBreakCount: number;
 };
 export type InterruptionReasonCount = {
   /**
@@ -524,7 +1430,19 @@ export type InterruptionStats = {
   averageFocusedSeconds: number;
 };
 export type ExportFormat = "csv" | "json";
-export type ExportField = "date" | "startTime" | "endTime" | "duration" | "tag" | "phase" | "remark";
+export type ExportField =
+  | "date"
+  | "startTime"
+  | "endTime"
+  | "duration"
+  | "tag"
+  | "phase"
+  | "remark"
+  | "elapsedSeconds"
+  | "pausedSeconds"
+  | "skipped"
+  | "startedAt"
+  | "endedAt";
 export type ExportRequest = {
   /**
    * 导出范围。
@@ -538,6 +1456,152 @@ export type ExportRequest = {
    * 导出字段（为空则导出默认字段集）。
    */
   fields: Array<ExportField>;
+  /**
+   * 加密口令（非空时导出文件整体加密，需同一口令才能导入）。
+   */
+  passphrase: string | null;
+};
+export type ImportMode = "merge" | "replace";
+export type ImportRequest = {
+  /**
+   * 导入文件路径（本应用导出的 CSV/JSON）。
+   */
+  path: string;
+  /**
+   * 导入模式。
+   */
+  mode: ImportMode;
+  /**
+   * 是否写入（为 false 时仅返回预览报告，不修改数据）。
+   */
+  commit: boolean;
+  /**
+   * 解密口令（导入加密导出文件时必填）。
+   */
+  passphrase: string | null;
+};
+export type ImportRowError = {
+  /**
+   * 行号（CSV 不含表头，从 1 开始；JSON 为 records 下标 + 1）。
+   */
+  row: number;
+  /**
+   * 跳过原因。
+   */
+  message: string;
+};
+export type ImportReport = {
+  /**
+   * 识别出的文件格式。
+   */
+  format: ExportFormat;
+  /**
+   * 文件中的记录行数。
+   */
+  totalRows: number;
+  /**
+   * 新增的记录数。
+   */
+  imported: number;
+  /**
+   * 与已有记录（或文件内）重复而跳过的记录数。
+   */
+  duplicates: number;
+  /**
+   * 替换模式下被移除的已有记录数。
+   */
+  replaced: number;
+  /**
+   * 校验失败而跳过的行。
+   */
+  invalid: Array<ImportRowError>;
+  /**
+   * 自动创建的标签路径。
+   */
+  newTags: Array<string>;
+  /**
+   * 导入涉及的日期范围（无有效记录时为空）。
+   */
+  range: DateRange | null;
+  /**
+   * 是否已写入。
+   */
+  committed: boolean;
+};
+export type ExternalImportSource = "toggl" | "generic";
+export type CsvColumnMapping = {
+  /**
+   * 开始列：配置了 `start_date` 时仅含时间，否则为“日期 时间”。
+   */
+  start: string;
+  /**
+   * 开始日期列（可选）。
+   */
+  startDate: string | null;
+  /**
+   * 结束列（与 `start` 同样的规则；与 `duration` 至少配置一个）。
+   */
+  end: string | null;
+  /**
+   * 结束日期列（可选；缺省时沿用开始日期）。
+   */
+  endDate: string | null;
+  /**
+   * 时长列（`HH:MM:SS`、`HH:MM` 或分钟数）。
+   */
+  duration: string | null;
+  /**
+   * 项目/标签列。
+   */
+  tag: string | null;
+  /**
+   * 备注列。
+   */
+  remark: string | null;
+  /**
+   * 日期格式（如 `%Y-%m-%d`）。
+   */
+  dateFormat: string;
+  /**
+   * 时间格式（如 `%H:%M`）。
+   */
+  timeFormat: string;
+};
+export type ProjectTagMapping = {
+  /**
+   * 外部工具中的项目名。
+   */
+  project: string;
+  /**
+   * 映射到的标签路径（为空表示不打标签）。
+   */
+  tag: string;
+};
+export type ExternalImportRequest = {
+  /**
+   * 待导入的 CSV 文件路径。
+   */
+  path: string;
+  /**
+   * CSV 布局。
+   */
+  source: ExternalImportSource;
+  /**
+   * 通用 CSV 的列映射（`source=generic` 时必填）。
+   */
+  mapping: CsvColumnMapping | null;
+  /**
+   * 项目名到标签的映射（未映射的项目按项目名作为标签）。
+   */
+  projectTags: Array<ProjectTagMapping>;
+  /**
+   * 导入模式。
+   */
+  mode: ImportMode;
+  /**
+   * 是否写入（为 false 时仅返回预览报告）。
+   */
+  commit: boolean;
 };
 export type PomodoroCompletedPayload = {
   /**
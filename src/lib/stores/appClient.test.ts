@@ -3,13 +3,24 @@
 import { afterEach, describe, expect, it, vi } from "vitest";
 import { get } from "svelte/store";
 
-const { listenMock, getAppSnapshotMock } = vi.hoisted(() => ({
-  listenMock: vi.fn(),
-  getAppSnapshotMock: vi.fn(),
-}));
+const { listenMock, getAppSnapshotMock, getStoreRecoveryReportMock, getLockStateMock, unlockStoreMock } = vi.hoisted(
+  () => ({
+    listenMock: vi.fn(),
+    getAppSnapshotMock: vi.fn(),
+    getStoreRecoveryReportMock: vi.fn(),
+    getLockStateMock: vi.fn(async () => null),
+    unlockStoreMock: vi.fn(),
+  }),
+);
 
 vi.mock("@tauri-apps/api/event", () => ({ listen: listenMock }));
-vi.mock("$lib/api/tauri", () => ({ getAppSnapshot: getAppSnapshotMock, frontendLog: vi.fn() }));
+vi.mock("$lib/api/tauri", () => ({
+  getAppSnapshot: getAppSnapshotMock,
+  getStoreRecoveryReport: getStoreRecoveryReportMock,
+  getLockState: getLockStateMock,
+  unlockStore: unlockStoreMock,
+  frontendLog: vi.fn(),
+}));
 
 import type { AppData, AppSnapshot, TimerSnapshot, WorkCompletedEvent } from "$lib/shared/types";
 
@@ -59,6 +70,7 @@ function makeSnapshot(partial: Partial<AppSnapshot> = {}): AppSnapshot {
   return {
     data: makeAppData(),
     timer: makeTimerSnapshot(),
+    profile: { id: "default", name: "默认", createdAt: "" },
     ...partial,
   } as unknown as AppSnapshot;
 }
@@ -68,7 +80,7 @@ describe("appClient stores", () => {
     vi.useRealTimers();
   });
 
-  it("applyAppSnapshot 应写入 appData 与 timerSnapshot", async () => {
+  it("applyAppSnapshot 应写入 appData、timerSnapshot 与 activeProfile", async () => {
     vi.resetModules();
     const mod = await import("./appClient");
 
@@ -80,6 +92,7 @@ describe("appClient stores", () => {
     mod.applyAppSnapshot(snapshot);
     expect(get(mod.appData)?.tags).toEqual(["A"]);
     expect(get(mod.timerSnapshot)?.currentTag).toBe("A");
+    expect(get(mod.activeProfile)?.id).toBe("default");
   });
 
   it("applyWorkCompletedEvent 应写入 workCompleted 并同步追加到 history", async () => {
@@ -125,6 +138,7 @@ describe("appClient stores", () => {
     });
 
     getAppSnapshotMock.mockResolvedValueOnce(snapshot1).mockResolvedValueOnce(snapshot2);
+    getStoreRecoveryReportMock.mockResolvedValueOnce(null);
 
     const handlers = new Map<string, (e: { payload: unknown }) => void>();
     listenMock.mockImplementation(async (event: string, handler: (e: { payload: unknown }) => void) => {
@@ -140,7 +154,7 @@ describe("appClient stores", () => {
     expect(get(mod.appData)?.tags).toEqual(["A"]);
     expect(get(mod.timerSnapshot)?.currentTag).toBe("A");
 
-    expect(listenMock).toHaveBeenCalledTimes(8);
+    expect(listenMock).toHaveBeenCalledTimes(10);
     expect(handlers.has("pomodoro://snapshot")).toBe(true);
     expect(handlers.has("pomodoro://kill_result")).toBe(true);
     expect(handlers.has("pomodoro://work_completed")).toBe(true);
@@ -149,6 +163,14 @@ describe("appClient stores", () => {
     expect(handlers.has("milestone-reached")).toBe(true);
     expect(handlers.has("pomodoro://audio_library_changed")).toBe(true);
     expect(handlers.has("pomodoro://mini_mode_changed")).toBe(true);
+    expect(handlers.has("pomodoro://store_recovered")).toBe(true);
+    expect(handlers.has("pomodoro://synced")).toBe(true);
+    expect(get(mod.storeRecovery)).toBeNull();
+
+    // store_recovered 事件：应写入恢复报告
+    const report = { quarantinedPath: "q.json", fileUnreadable: false, dropped: ["history[1]"] };
+    handlers.get("pomodoro://store_recovered")?.({ payload: report });
+    expect(get(mod.storeRecovery)).toEqual(report);
 
     // snapshot 事件：应更新 timerSnapshot
     handlers.get("pomodoro://snapshot")?.({ payload: makeTimerSnapshot({ currentTag: "X" }) });
@@ -196,4 +218,28 @@ describe("appClient stores", () => {
     await mod.initAppClient();
     expect(getAppSnapshotMock).toHaveBeenCalledTimes(2);
   });
+
+  it("数据已加密时 initAppClient 不加载快照，unlockAppClient 解锁后写入快照", async () => {
+    vi.resetModules();
+    listenMock.mockReset();
+    getAppSnapshotMock.mockReset();
+    listenMock.mockResolvedValue(() => {});
+
+    const profile = { id: "default", name: "默认", createdAt: "" };
+    getLockStateMock.mockResolvedValueOnce(profile as never);
+    unlockStoreMock.mockResolvedValueOnce(makeSnapshot({ data: makeAppData({ tags: ["密"] }) }));
+    getStoreRecoveryReportMock.mockResolvedValueOnce(null);
+
+    const mod = await import("./appClient");
+    await mod.initAppClient();
+    expect(get(mod.appError)).toBeNull();
+    expect(get(mod.storeLocked)).toEqual(profile);
+    expect(getAppSnapshotMock).not.toHaveBeenCalled();
+
+    await mod.unlockAppClient("correct horse");
+    expect(unlockStoreMock).toHaveBeenCalledWith("correct horse");
+    expect(get(mod.storeLocked)).toBeNull();
+    expect(get(mod.appData)?.tags).toEqual(["密"]);
+    mod.disposeAppClient();
+  });
 });
@@ -3,7 +3,7 @@
 import { isTauri } from "@tauri-apps/api/core";
 import { listen, type Event as TauriEvent, type UnlistenFn } from "@tauri-apps/api/event";
 import { writable } from "svelte/store";
-import { frontendLog, getAppSnapshot } from "$lib/api/tauri";
+import { frontendLog, getAppSnapshot, getLockState, getStoreRecoveryReport, unlockStore } from "$lib/api/tauri";
 import { miniMode } from "$lib/stores/uiState";
 import type {
   AppData,
@@ -14,6 +14,8 @@ import type {
   KillSummary,
   MilestoneReachedPayload,
   PomodoroCompletedPayload,
+  ProfileInfo,
+  StoreRecoveryReport,
   TimerSnapshot,
   WorkCompletedEvent,
 } from "$lib/shared/types";
@@ -21,6 +23,9 @@ import type {
 /** 全局：持久化数据快照（settings/blacklist/tags/history）。 */
 export const appData = writable<AppData | null>(null);
 
+/** 全局：当前配置（切换配置后随快照更新）。 */
+export const activeProfile = writable<ProfileInfo | null>(null);
+
 /** 全局：计时器状态快照（实时更新）。 */
 export const timerSnapshot = writable<TimerSnapshot | null>(null);
 
@@ -48,6 +53,12 @@ export const historyDevChangedAt = writable<number>(0);
 /** 全局：自定义音频列表变更时间戳（用于主界面下拉框刷新）。 */
 export const audioLibraryChangedAt = writable<number>(0);
 
+/** 全局：等待口令解锁的配置（数据已加密且尚未解锁时非空，此时不会加载快照）。 */
+export const storeLocked = writable<ProfileInfo | null>(null);
+
+/** 全局：启动时的数据恢复报告（store 损坏并已恢复时非空，用于提示用户）。 */
+export const storeRecovery = writable<StoreRecoveryReport | null>(null);
+
 let initialized = false;
 let unlistenFns: UnlistenFn[] = [];
 
@@ -80,6 +91,7 @@ async function withTimeout<T>(p: Promise<T>, timeoutMs: number, message: string)
 export function applyAppSnapshot(snapshot: AppSnapshot): void {
   appData.set(snapshot.data);
   timerSnapshot.set(snapshot.timer);
+  activeProfile.set(snapshot.profile);
 }
 
 /** 将工作完成事件写入 store，并同步追加到 `appData.history`（用于 UI 即时展示）。 */
@@ -125,8 +137,13 @@ export async function initAppClient(): Promise<void> {
     void withTimeout(frontendLog("info", "[frontend] initAppClient: start"), 800, "frontend_log timeout").catch(
       () => {},
     );
-    const snapshot = await withTimeout(getAppSnapshot(), 15000, "获取后端快照超时，请重启应用后重试。");
-    applyAppSnapshot(snapshot);
+    const locked = await withTimeout(getLockState(), 15000, "获取后端状态超时，请重启应用后重试。");
+    storeLocked.set(locked);
+    if (!locked) {
+      const snapshot = await withTimeout(getAppSnapshot(), 15000, "获取后端快照超时，请重启应用后重试。");
+      applyAppSnapshot(snapshot);
+      void loadStoreRecoveryBestEffort();
+    }
     void withTimeout(
       frontendLog("info", "[frontend] initAppClient: snapshot loaded"),
       800,
@@ -158,6 +175,14 @@ export async function initAppClient(): Promise<void> {
   }
 }
 
+/** 使用口令解锁加密数据：成功后写入快照并清除锁定状态（口令错误时抛出后端错误）。 */
+export async function unlockAppClient(passphrase: string): Promise<void> {
+  const snapshot = await unlockStore(passphrase);
+  applyAppSnapshot(snapshot);
+  storeLocked.set(null);
+  void loadStoreRecoveryBestEffort();
+}
+
 /** 释放事件监听（一般不需要；仅用于热重载/测试场景）。 */
 export function disposeAppClient(): void {
   for (const fn of unlistenFns) {
@@ -181,6 +206,16 @@ async function reloadSnapshotBestEffort(): Promise<void> {
   }
 }
 
+/** 拉取启动时的数据恢复报告（事件可能早于前端监听发出，因此首屏主动拉取一次）。 */
+async function loadStoreRecoveryBestEffort(): Promise<void> {
+  try {
+    const report = await getStoreRecoveryReport();
+    if (report) storeRecovery.set(report);
+  } catch {
+    // 忽略拉取失败：恢复提示不影响正常使用
+  }
+}
+
 /** 注册 Tauri 后端事件监听。 */
 async function registerListeners(): Promise<void> {
   if (unlistenFns.length > 0) return;
@@ -226,6 +261,16 @@ async function registerListeners(): Promise<void> {
     });
   }
 
+  /** 处理后端推送的“数据已恢复”事件。 */
+  function onStoreRecoveredEvent(e: TauriEvent<StoreRecoveryReport>): void {
+    storeRecovery.set(e.payload);
+  }
+
+  /** 处理后端推送的“同步已合并其他设备变更”事件：重新拉取快照。 */
+  function onSyncedEvent(): void {
+    void reloadSnapshotBestEffort();
+  }
+
   /** 处理后端推送的“迷你模式变更”事件：同步到前端 UI 状态。 */
   function onMiniModeChangedEvent(e: TauriEvent<boolean>): void {
     miniMode.set(Boolean(e.payload));
@@ -239,4 +284,6 @@ async function registerListeners(): Promise<void> {
   unlistenFns.push(await listen<MilestoneReachedPayload>("milestone-reached", onMilestoneReachedEvent));
   unlistenFns.push(await listen<CustomAudio[]>("pomodoro://audio_library_changed", onAudioLibraryChangedEvent));
   unlistenFns.push(await listen<boolean>("pomodoro://mini_mode_changed", onMiniModeChangedEvent));
+  unlistenFns.push(await listen<StoreRecoveryReport>("pomodoro://store_recovered", onStoreRecoveredEvent));
+  unlistenFns.push(await listen<null>("pomodoro://synced", onSyncedEvent));
 }